@@ -0,0 +1,44 @@
+//! WebAssembly bindings of `amm-math` for the TypeScript SDK, so off-chain
+//! quotes run the same curve and fee code as the program instead of a port.
+//!
+//! Built with `wasm-pack build crates/amm-math-wasm --target nodejs`; amounts
+//! cross the boundary as `bigint`.
+
+use amm_math::curve::Curve;
+use amm_math::MathError;
+use wasm_bindgen::prelude::*;
+
+fn js_error(error: MathError) -> JsError {
+    JsError::new(&format!("{error:?}"))
+}
+
+/// Swap curve oriented from the swap's input side, as `amm_math::curve::Curve`
+#[wasm_bindgen]
+pub struct SwapCurve(Curve);
+
+#[wasm_bindgen]
+impl SwapCurve {
+    /// x * y = k
+    #[wasm_bindgen(js_name = constantProduct)]
+    pub fn constant_product() -> SwapCurve {
+        SwapCurve(Curve::ConstantProduct)
+    }
+
+    /// Weighted curve with the input side's weight first
+    pub fn weighted(weight_in: u64, weight_out: u64) -> SwapCurve {
+        SwapCurve(Curve::Weighted { weight_in, weight_out })
+    }
+
+    /// StableSwap curve with amplification `amp`
+    #[wasm_bindgen(js_name = stableSwap)]
+    pub fn stable_swap(amp: u64) -> SwapCurve {
+        SwapCurve(Curve::StableSwap { amp })
+    }
+
+    /// Output paid for `amount_in` at `fee_rate_bps`, exactly as `swap` pays it
+    #[wasm_bindgen(js_name = outputAfterFee)]
+    pub fn output_after_fee(&self, reserve_in: u64, reserve_out: u64, amount_in: u64, fee_rate_bps: u64) -> Result<u64, JsError> {
+        let amount_out = self.0.output_after_fee(reserve_in, reserve_out, amount_in, fee_rate_bps).map_err(js_error)?;
+        u64::try_from(amount_out).map_err(|_| js_error(MathError::Overflow))
+    }
+}
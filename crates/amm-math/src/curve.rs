@@ -0,0 +1,199 @@
+use crate::fixed_point::{
+    mul_div_ceil, mul_div_floor, pow_ratio_x64, pow_round_up, sqrt_u128, ONE_X64, Q64_SHIFT,
+};
+use crate::fee::swap_fee;
+use crate::{MathError, Result};
+
+/// Invariant a swap trades along, oriented from the swap's input side
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    /// x * y = k
+    ConstantProduct,
+    /// Constant mean x^w_in * y^w_out = k
+    Weighted { weight_in: u64, weight_out: u64 },
+    /// Two-coin stable swap 4A(x + y) + D = 4AD + D^3 / (4xy)
+    /// with amplification `amp` (at least 1)
+    StableSwap { amp: u64 },
+}
+
+/// Newton iterations before a stable-swap solve is declared divergent
+const STABLE_MAX_ITERATIONS: usize = 255;
+
+impl Curve {
+    /// Output paid for `amount_in` (after the fee) entering a pool holding
+    /// `reserve_in` / `reserve_out`, rounded down
+    pub fn output(&self, reserve_in: u64, reserve_out: u64, amount_in: u128) -> Result<u128> {
+        let reserve_in_after = (reserve_in as u128).checked_add(amount_in).ok_or(MathError::Overflow)?;
+        match *self {
+            // Constant product formula: (x + dx) * (y - dy) = x * y
+            // dy = (y * dx) / (x + dx)
+            Curve::ConstantProduct => mul_div_floor(reserve_out as u128, amount_in, reserve_in_after),
+            // Constant mean: dy = y * (1 - (x / (x + dx))^(w_in / w_out)),
+            // taking the power's upper bound so rounding favours the pool
+            Curve::Weighted { weight_in, weight_out } => {
+                if reserve_in_after == 0 {
+                    return Err(MathError::DivideByZero);
+                }
+                let base = ((reserve_in as u128) << Q64_SHIFT) / reserve_in_after;
+                let remaining = pow_round_up(pow_ratio_x64(base, weight_in, weight_out)?).min(ONE_X64);
+                mul_div_floor(reserve_out as u128, ONE_X64 - remaining, ONE_X64)
+            }
+            // dy = y - y', with y' solving the invariant at x + dx rounded up
+            Curve::StableSwap { amp } => {
+                let invariant = stable_invariant(reserve_in as u128, reserve_out as u128, amp)?;
+                let reserve_out_after = stable_balance_ceil(reserve_in_after, invariant, amp)?;
+                Ok((reserve_out as u128).saturating_sub(reserve_out_after))
+            }
+        }
+    }
+
+    /// Output paid for `amount_in` before the fee, as a swap executes it: the
+    /// fee is rounded down and comes off the input ahead of the curve
+    pub fn output_after_fee(&self, reserve_in: u64, reserve_out: u64, amount_in: u64, fee_rate_bps: u64) -> Result<u128> {
+        let amount_in_after_fee = amount_in
+            .checked_sub(swap_fee(amount_in, fee_rate_bps)?)
+            .ok_or(MathError::Overflow)?;
+        self.output(reserve_in, reserve_out, amount_in_after_fee as u128)
+    }
+
+    /// Input (before the fee) paying out exactly `amount_out`, rounded up.
+    /// `amount_out` must be below `reserve_out`.
+    pub fn input(&self, reserve_in: u64, reserve_out: u64, amount_out: u64) -> Result<u128> {
+        let reserve_out_after = reserve_out.checked_sub(amount_out).ok_or(MathError::Overflow)?;
+        match *self {
+            // dx = ceil(x * dy / (y - dy))
+            Curve::ConstantProduct => mul_div_ceil(
+                reserve_in as u128,
+                amount_out as u128,
+                reserve_out_after as u128,
+            ),
+            // dx = ceil(x * ((y / (y - dy))^(w_out / w_in) - 1))
+            Curve::Weighted { weight_in, weight_out } => {
+                let base = mul_div_ceil(reserve_out as u128, ONE_X64, reserve_out_after as u128)?;
+                let growth = pow_round_up(pow_ratio_x64(base, weight_out, weight_in)?).max(ONE_X64);
+                mul_div_ceil(reserve_in as u128, growth - ONE_X64, ONE_X64)
+            }
+            // dx = x' - x, with x' solving the invariant at y - dy rounded up.
+            // The two solves floor their coefficients from opposite sides, so
+            // dx is then raised, by doubling steps, until the exact-input path
+            // pays at least dy for it
+            Curve::StableSwap { amp } => {
+                if reserve_out_after == 0 {
+                    return Err(MathError::Overflow);
+                }
+                let invariant = stable_invariant(reserve_in as u128, reserve_out as u128, amp)?;
+                let reserve_in_after = stable_balance_ceil(reserve_out_after as u128, invariant, amp)?;
+                let mut amount_in = reserve_in_after.saturating_sub(reserve_in as u128);
+                let mut step = 1u128;
+                for _ in 0..u128::BITS {
+                    if self.output(reserve_in, reserve_out, amount_in)? >= amount_out as u128 {
+                        return Ok(amount_in);
+                    }
+                    amount_in = amount_in.checked_add(step).ok_or(MathError::Overflow)?;
+                    step <<= 1;
+                }
+                Err(MathError::Overflow)
+            }
+        }
+    }
+}
+
+/// Stable-swap invariant D of reserves `x` / `y`, by Newton's method from
+/// D = x + y: D' = (4A * S + 2 * D_P) * D / ((4A - 1) * D + 3 * D_P) with
+/// D_P = D^3 / (4xy)
+pub fn stable_invariant(x: u128, y: u128, amp: u64) -> Result<u128> {
+    let sum = x.checked_add(y).ok_or(MathError::Overflow)?;
+    if sum == 0 {
+        return Ok(0);
+    }
+    // A * n^n for n = 2
+    let ann = (amp as u128).checked_mul(4).ok_or(MathError::Overflow)?;
+    let ann_sum = ann.checked_mul(sum).ok_or(MathError::Overflow)?;
+    let ann_less_one = ann.checked_sub(1).ok_or(MathError::Overflow)?;
+    let mut invariant = sum;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let d_p = mul_div_floor(invariant, invariant, x.checked_mul(2).ok_or(MathError::Overflow)?)?;
+        let d_p = mul_div_floor(d_p, invariant, y.checked_mul(2).ok_or(MathError::Overflow)?)?;
+        let numerator = d_p
+            .checked_mul(2)
+            .and_then(|value| value.checked_add(ann_sum))
+            .ok_or(MathError::Overflow)?;
+        let denominator = ann_less_one
+            .checked_mul(invariant)
+            .and_then(|value| value.checked_add(d_p.checked_mul(3)?))
+            .ok_or(MathError::Overflow)?;
+        let previous = invariant;
+        invariant = mul_div_floor(numerator, invariant, denominator)?;
+        if invariant.abs_diff(previous) <= 1 {
+            return Ok(invariant);
+        }
+    }
+    Err(MathError::Overflow)
+}
+
+/// Balance of one side keeping invariant `d` while the other side holds `x`,
+/// rounded up so a swap never pays out more than the curve allows.
+///
+/// Solves y^2 + (b - D) * y = c with b = x + D / 4A and c = D^3 / (16A * x)
+/// by Newton's method from the upper bound max(D - b, 0) + sqrt(c) + 1 of the
+/// root. The iterates fall strictly until they reach the floor of the root, so the result depends only on `x` and never on how
+/// quickly the iteration happened to converge.
+pub fn stable_balance_ceil(x: u128, d: u128, amp: u64) -> Result<u128> {
+    let ann = (amp as u128).checked_mul(4).ok_or(MathError::Overflow)?;
+    let c = mul_div_floor(d, d, x.checked_mul(2).ok_or(MathError::Overflow)?)?;
+    let c = mul_div_floor(c, d, ann.checked_mul(2).ok_or(MathError::Overflow)?)?;
+    let b = x.checked_add(d / ann).ok_or(MathError::Overflow)?;
+    let step = |y: u128| -> Result<u128> {
+        let numerator = y
+            .checked_mul(y)
+            .and_then(|square| square.checked_add(c))
+            .ok_or(MathError::Overflow)?;
+        let denominator = y
+            .checked_mul(2)
+            .and_then(|value| value.checked_add(b))
+            .and_then(|value| value.checked_sub(d))
+            .ok_or(MathError::Overflow)?;
+        if denominator == 0 {
+            return Err(MathError::DivideByZero);
+        }
+        Ok(numerator / denominator)
+    };
+    let mut y = d
+        .saturating_sub(b)
+        .checked_add(sqrt_u128(c) + 1)
+        .ok_or(MathError::Overflow)?;
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let next = step(y)?;
+        if next >= y {
+            // y is the floor of the root; round up unless it solves exactly
+            let lhs = y.checked_mul(y).and_then(|square| square.checked_add(y.checked_mul(b)?));
+            let rhs = c.checked_add(y.checked_mul(d).ok_or(MathError::Overflow)?);
+            return Ok(if lhs == rhs { y } else { y + 1 });
+        }
+        y = next;
+    }
+    Err(MathError::Overflow)
+}
+
+/// Marginal price of the `x` side in units of the `y` side on a stable-swap
+/// curve (Q64.64), rounded down: the ratio of the invariant's partial
+/// derivatives, (16A * xy + D^3 / x) / (16A * xy + D^3 / y), evaluated with
+/// both terms divided by D. Tends to y / x as A falls and to 1 as A grows.
+pub fn stable_spot_price_x64(x: u128, y: u128, amp: u64) -> Result<u128> {
+    let d = stable_invariant(x, y, amp)?;
+    if d == 0 {
+        return Err(MathError::DivideByZero);
+    }
+    let ann_xy = mul_div_floor(x, y, d)?
+        .checked_mul((amp as u128).checked_mul(16).ok_or(MathError::Overflow)?)
+        .ok_or(MathError::Overflow)?;
+    let numerator = ann_xy.checked_add(mul_div_floor(d, d, x)?).ok_or(MathError::Overflow)?;
+    let denominator = ann_xy.checked_add(mul_div_floor(d, d, y)?).ok_or(MathError::Overflow)?;
+
+    // Both terms may exceed 64 bits; drop the same low bits from each so the
+    // Q64.64 division stays inside u128
+    let shift = (u128::BITS - denominator.leading_zeros()).saturating_sub(64);
+    let (numerator, denominator) = (numerator >> shift, denominator >> shift);
+    let whole = (numerator / denominator).checked_mul(ONE_X64).ok_or(MathError::Overflow)?;
+    Ok(whole + ((numerator % denominator) << Q64_SHIFT) / denominator)
+}
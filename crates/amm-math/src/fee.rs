@@ -0,0 +1,26 @@
+use crate::fixed_point::{mul_div_ceil, mul_div_floor, mul_div_floor_u64};
+use crate::{MathError, Result, BPS_DENOMINATOR};
+
+/// Swap fee charged on `amount_in` at `fee_rate_bps`, rounded down
+pub fn swap_fee(amount_in: u64, fee_rate_bps: u64) -> Result<u64> {
+    mul_div_floor_u64(amount_in, fee_rate_bps, BPS_DENOMINATOR)
+}
+
+/// `amount_in` scaled by (1 - fee), rounded down
+pub fn amount_after_fee(amount_in: u64, fee_rate_bps: u64) -> Result<u128> {
+    mul_div_floor(
+        amount_in as u128,
+        BPS_DENOMINATOR.checked_sub(fee_rate_bps).ok_or(MathError::Overflow)? as u128,
+        BPS_DENOMINATOR as u128,
+    )
+}
+
+/// Gross amount that is left with `amount_after_fee` once the fee is
+/// taken, rounded up; the inverse of `amount_after_fee`
+pub fn amount_before_fee(amount_after_fee: u128, fee_rate_bps: u64) -> Result<u128> {
+    mul_div_ceil(
+        amount_after_fee,
+        BPS_DENOMINATOR as u128,
+        BPS_DENOMINATOR.checked_sub(fee_rate_bps).ok_or(MathError::Overflow)? as u128,
+    )
+}
@@ -0,0 +1,173 @@
+use crate::{MathError, Result};
+
+/// Fractional bits of the Q64.64 fixed-point format
+pub const Q64_SHIFT: u32 = 64;
+
+/// 1.0 as Q64.64
+pub const ONE_X64: u128 = 1 << Q64_SHIFT;
+
+/// ln(2) as Q64.64
+const LN_2_X64: u128 = 12_786_308_645_202_655_659;
+
+/// Relative error bound of `pow_ratio_x64`, as a right shift (2^-40)
+const POW_ERROR_SHIFT: u32 = 40;
+
+/// `a * b / denominator`, rounded down
+pub fn mul_div_floor(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    if denominator == 0 {
+        return Err(MathError::DivideByZero);
+    }
+    let product = a.checked_mul(b).ok_or(MathError::Overflow)?;
+    Ok(product / denominator)
+}
+
+/// `a * b / denominator`, rounded up
+pub fn mul_div_ceil(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    if denominator == 0 {
+        return Err(MathError::DivideByZero);
+    }
+    let product = a.checked_mul(b).ok_or(MathError::Overflow)?;
+    let quotient = product / denominator;
+    if product % denominator == 0 {
+        Ok(quotient)
+    } else {
+        quotient.checked_add(1).ok_or(MathError::Overflow)
+    }
+}
+
+/// `a * b / denominator` rounded down and narrowed back to u64
+pub fn mul_div_floor_u64(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    let result = mul_div_floor(a as u128, b as u128, denominator as u128)?;
+    u64::try_from(result).map_err(|_| MathError::Overflow)
+}
+
+/// `numerator / denominator` as a Q64.64 fixed-point ratio
+pub fn ratio_x64(numerator: u64, denominator: u64) -> Result<u128> {
+    if denominator == 0 {
+        return Err(MathError::DivideByZero);
+    }
+    Ok(((numerator as u128) << Q64_SHIFT) / (denominator as u128))
+}
+
+/// Integer square root, rounded down
+pub fn sqrt_u128(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    // Newton's method from an initial guess above the root; the sequence
+    // decreases monotonically until it settles on floor(sqrt(value))
+    let mut x = 1u128 << (128 - value.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// log2 of a positive Q64.64 value, as a signed Q64.64 value
+pub fn log2_x64(value_x64: u128) -> Result<i128> {
+    if value_x64 == 0 {
+        return Err(MathError::Overflow);
+    }
+    let msb = 127 - value_x64.leading_zeros() as i32;
+    let integer = (msb - Q64_SHIFT as i32) as i128;
+
+    // Normalize to a mantissa in [1, 2) with 63 fractional bits, then read the
+    // fraction off one bit per squaring
+    let mut mantissa = if msb >= 63 {
+        value_x64 >> (msb - 63)
+    } else {
+        value_x64 << (63 - msb)
+    };
+    let mut fraction: u128 = 0;
+    for bit in (0..Q64_SHIFT).rev() {
+        mantissa = (mantissa * mantissa) >> 63;
+        if mantissa >= 1 << Q64_SHIFT {
+            mantissa >>= 1;
+            fraction |= 1 << bit;
+        }
+    }
+    Ok((integer << Q64_SHIFT) + fraction as i128)
+}
+
+/// 2 raised to a signed Q64.64 power, as a Q64.64 value. Results below the
+/// format's resolution round to zero.
+pub fn exp2_x64(exponent_x64: i128) -> Result<u128> {
+    let integer = exponent_x64 >> Q64_SHIFT;
+    let fraction = (exponent_x64 & u64::MAX as i128) as u128;
+    if integer >= Q64_SHIFT as i128 {
+        return Err(MathError::Overflow);
+    }
+    if integer < -(Q64_SHIFT as i128) {
+        return Ok(0);
+    }
+
+    // 2^fraction = e^(fraction * ln 2) by its Taylor series; the argument is
+    // below ln 2, so the terms vanish well within 32 steps
+    let z = (fraction * LN_2_X64) >> Q64_SHIFT;
+    let mut term: u128 = 1 << Q64_SHIFT;
+    let mut sum = term;
+    for k in 1..=32u128 {
+        term = ((term * z) >> Q64_SHIFT) / k;
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+
+    Ok(if integer >= 0 {
+        sum << integer
+    } else {
+        sum >> (-integer)
+    })
+}
+
+/// `base ^ (numerator / denominator)` for a positive Q64.64 base, accurate
+/// to within `pow_round_up` / `pow_round_down` of the exact value
+pub fn pow_ratio_x64(base_x64: u128, numerator: u64, denominator: u64) -> Result<u128> {
+    if denominator == 0 {
+        return Err(MathError::DivideByZero);
+    }
+    if numerator == 0 {
+        return Ok(ONE_X64);
+    }
+    let exponent = log2_x64(base_x64)?
+        .checked_mul(numerator as i128)
+        .ok_or(MathError::Overflow)?
+        / denominator as i128;
+    exp2_x64(exponent)
+}
+
+/// Upper bound of a `pow_ratio_x64` result
+pub fn pow_round_up(value_x64: u128) -> u128 {
+    value_x64
+        .saturating_add(value_x64 >> POW_ERROR_SHIFT)
+        .saturating_add(1)
+}
+
+/// Lower bound of a `pow_ratio_x64` result
+pub fn pow_round_down(value_x64: u128) -> u128 {
+    value_x64
+        .saturating_sub(value_x64 >> POW_ERROR_SHIFT)
+        .saturating_sub(1)
+}
+
+/// a^(weight_a / (weight_a + weight_b)) * b^(weight_b / (weight_a + weight_b)),
+/// rounded down; the weighted constant-mean analogue of sqrt(a * b)
+pub fn weighted_geometric_mean(a: u64, b: u64, weight_a: u64, weight_b: u64) -> Result<u64> {
+    if a == 0 || b == 0 {
+        return Ok(0);
+    }
+    let total_weight = weight_a.checked_add(weight_b).ok_or(MathError::Overflow)?;
+    if total_weight == 0 {
+        return Err(MathError::DivideByZero);
+    }
+
+    let log_a = log2_x64((a as u128) << Q64_SHIFT)?;
+    let log_b = log2_x64((b as u128) << Q64_SHIFT)?;
+    let log_mean = (log_a * weight_a as i128 + log_b * weight_b as i128) / total_weight as i128;
+    let mean_x64 = pow_round_down(exp2_x64(log_mean)?);
+    Ok((mean_x64 >> Q64_SHIFT) as u64)
+}
@@ -0,0 +1,27 @@
+//! Swap math of the Token-2022 AMM, shared by the on-chain program and
+//! off-chain clients so a quote computed off-chain matches execution
+//! bit-for-bit.
+//!
+//! Everything is integer arithmetic on u128 so results are identical on every
+//! validator and every client; floating point must not be used for amounts.
+#![no_std]
+
+pub mod curve;
+pub mod fee;
+pub mod fixed_point;
+
+pub use fixed_point::*;
+
+/// Failure of a math operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    /// A result or intermediate value does not fit its type
+    Overflow,
+    /// Division by zero
+    DivideByZero,
+}
+
+pub type Result<T> = core::result::Result<T, MathError>;
+
+/// Denominator of basis-point rates
+pub const BPS_DENOMINATOR: u64 = 10_000;
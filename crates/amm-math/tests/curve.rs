@@ -0,0 +1,277 @@
+use amm_math::curve::{stable_invariant, stable_spot_price_x64, Curve};
+use amm_math::ONE_X64;
+use amm_math::fee::{amount_after_fee, amount_before_fee, swap_fee};
+use amm_math::{mul_div_ceil, mul_div_floor, sqrt_u128, BPS_DENOMINATOR};
+use proptest::prelude::*;
+
+// Reserves stay below 2^48 and amounts below 2^40 so invariant products fit in u128
+const MAX_RESERVE: u64 = 1 << 48;
+const MAX_AMOUNT: u64 = 1 << 40;
+
+fn weighted() -> impl Strategy<Value = Curve> {
+    (1_000u64..=9_000).prop_map(|weight_in| Curve::Weighted {
+        weight_in,
+        weight_out: BPS_DENOMINATOR - weight_in,
+    })
+}
+
+// Stable-swap intermediates grow with D^3 / x, so its reserves also stay
+// above 2^24 to keep every Newton step inside u128
+const STABLE_MIN_RESERVE: u64 = 1 << 24;
+
+fn stable() -> impl Strategy<Value = Curve> {
+    (1u64..=10_000).prop_map(|amp| Curve::StableSwap { amp })
+}
+
+fn any_curve() -> impl Strategy<Value = Curve> {
+    prop_oneof![Just(Curve::ConstantProduct), weighted()]
+}
+
+proptest! {
+    #[test]
+    fn constant_product_never_shrinks_k(
+        reserve_in in 1..MAX_RESERVE,
+        reserve_out in 1..MAX_RESERVE,
+        amount_in in 1..MAX_AMOUNT,
+    ) {
+        let amount_out = Curve::ConstantProduct.output(reserve_in, reserve_out, amount_in as u128).unwrap();
+        prop_assert!(amount_out < reserve_out as u128);
+        let k_before = reserve_in as u128 * reserve_out as u128;
+        let k_after = (reserve_in as u128 + amount_in as u128) * (reserve_out as u128 - amount_out);
+        prop_assert!(k_after >= k_before);
+    }
+
+    #[test]
+    fn constant_product_matches_closed_form(
+        reserve_in in 1..MAX_RESERVE,
+        reserve_out in 1..MAX_RESERVE,
+        amount_in in 1..MAX_AMOUNT,
+    ) {
+        let amount_out = Curve::ConstantProduct.output(reserve_in, reserve_out, amount_in as u128).unwrap();
+        let expected = reserve_out as u128 * amount_in as u128 / (reserve_in as u128 + amount_in as u128);
+        prop_assert_eq!(amount_out, expected);
+    }
+
+    #[test]
+    fn output_stays_below_reserve(
+        curve in any_curve(),
+        reserve_in in 1..MAX_RESERVE,
+        reserve_out in 1..MAX_RESERVE,
+        amount_in in 1..MAX_AMOUNT,
+    ) {
+        let amount_out = curve.output(reserve_in, reserve_out, amount_in as u128).unwrap();
+        prop_assert!(amount_out < reserve_out as u128);
+    }
+
+    #[test]
+    fn output_is_monotonic_in_input(
+        curve in any_curve(),
+        reserve_in in 1..MAX_RESERVE,
+        reserve_out in 1..MAX_RESERVE,
+        amount_in in 1..MAX_AMOUNT,
+        extra in 1..MAX_AMOUNT,
+    ) {
+        let smaller = curve.output(reserve_in, reserve_out, amount_in as u128).unwrap();
+        let larger = curve.output(reserve_in, reserve_out, (amount_in + extra) as u128).unwrap();
+        prop_assert!(larger >= smaller);
+    }
+
+    #[test]
+    fn exact_output_input_covers_output(
+        reserve_in in 1..MAX_RESERVE,
+        reserve_out in 2..MAX_RESERVE,
+        amount_out_bps in 1u64..9_000,
+    ) {
+        let amount_out = (reserve_out as u128 * amount_out_bps as u128 / BPS_DENOMINATOR as u128).max(1) as u64;
+        let amount_in = Curve::ConstantProduct.input(reserve_in, reserve_out, amount_out).unwrap();
+        let paid_out = Curve::ConstantProduct.output(reserve_in, reserve_out, amount_in).unwrap();
+        prop_assert!(paid_out >= amount_out as u128);
+    }
+
+    // The weighted power is only bounded, not exact, so an exact-output input
+    // may buy marginally less on the exact-input path; it must still cost at
+    // least the spot price, which only rises along the curve
+    #[test]
+    fn weighted_exact_output_costs_at_least_spot(
+        curve in weighted(),
+        reserve_in in 1..MAX_RESERVE,
+        reserve_out in 2..MAX_RESERVE,
+        amount_out_bps in 1u64..9_000,
+    ) {
+        let Curve::Weighted { weight_in, weight_out } = curve else { unreachable!() };
+        let amount_out = (reserve_out as u128 * amount_out_bps as u128 / BPS_DENOMINATOR as u128).max(1) as u64;
+        // Large outputs against a light input weight legitimately overflow
+        let Ok(amount_in) = curve.input(reserve_in, reserve_out, amount_out) else { return Ok(()) };
+        let paid = amount_in
+            .checked_mul(reserve_out as u128)
+            .and_then(|value| value.checked_mul(weight_in as u128));
+        let spot_cost = amount_out as u128 * reserve_in as u128 * weight_out as u128;
+        prop_assert!(paid.is_none_or(|paid| paid >= spot_cost));
+    }
+
+    #[test]
+    fn stable_output_stays_below_reserve(
+        curve in stable(),
+        reserve_in in STABLE_MIN_RESERVE..MAX_RESERVE,
+        reserve_out in STABLE_MIN_RESERVE..MAX_RESERVE,
+        amount_in in 1..MAX_AMOUNT,
+    ) {
+        let amount_out = curve.output(reserve_in, reserve_out, amount_in as u128).unwrap();
+        prop_assert!(amount_out < reserve_out as u128);
+    }
+
+    #[test]
+    fn stable_output_is_monotonic_in_input(
+        curve in stable(),
+        reserve_in in STABLE_MIN_RESERVE..MAX_RESERVE,
+        reserve_out in STABLE_MIN_RESERVE..MAX_RESERVE,
+        amount_in in 1..MAX_AMOUNT,
+        extra in 1..MAX_AMOUNT,
+    ) {
+        let smaller = curve.output(reserve_in, reserve_out, amount_in as u128).unwrap();
+        let larger = curve.output(reserve_in, reserve_out, (amount_in + extra) as u128).unwrap();
+        prop_assert!(larger >= smaller);
+    }
+
+    #[test]
+    fn stable_exact_output_input_covers_output(
+        curve in stable(),
+        reserve_in in STABLE_MIN_RESERVE..MAX_RESERVE,
+        reserve_out in STABLE_MIN_RESERVE..MAX_RESERVE,
+        amount_out_bps in 1u64..5_000,
+    ) {
+        let amount_out = (reserve_out as u128 * amount_out_bps as u128 / BPS_DENOMINATOR as u128).max(1) as u64;
+        let amount_in = curve.input(reserve_in, reserve_out, amount_out).unwrap();
+        let paid_out = curve.output(reserve_in, reserve_out, amount_in).unwrap();
+        prop_assert!(paid_out >= amount_out as u128);
+    }
+
+    #[test]
+    fn stable_never_shrinks_the_invariant(
+        curve in stable(),
+        reserve_in in STABLE_MIN_RESERVE..MAX_RESERVE,
+        reserve_out in STABLE_MIN_RESERVE..MAX_RESERVE,
+        amount_in in 1..MAX_AMOUNT,
+    ) {
+        let Curve::StableSwap { amp } = curve else { unreachable!() };
+        let amount_out = curve.output(reserve_in, reserve_out, amount_in as u128).unwrap();
+        let before = stable_invariant(reserve_in as u128, reserve_out as u128, amp).unwrap();
+        let after = stable_invariant(
+            reserve_in as u128 + amount_in as u128,
+            reserve_out as u128 - amount_out,
+            amp,
+        )
+        .unwrap();
+        prop_assert!(after >= before);
+    }
+
+    // A balanced stable pool starts at a 1:1 price that only worsens for the
+    // trader, and the curve is never steeper than constant product
+    #[test]
+    fn stable_output_is_bracketed(
+        curve in stable(),
+        reserve in STABLE_MIN_RESERVE..MAX_RESERVE,
+        amount_in in 1..MAX_AMOUNT,
+    ) {
+        let amount_out = curve.output(reserve, reserve, amount_in as u128).unwrap();
+        prop_assert!(amount_out <= amount_in as u128);
+        let constant_out = Curve::ConstantProduct.output(reserve, reserve, amount_in as u128).unwrap();
+        prop_assert!(amount_out + 1 >= constant_out);
+    }
+
+    #[test]
+    fn fee_comes_off_the_input_before_the_curve(
+        curve in any_curve(),
+        reserve_in in 1..MAX_RESERVE,
+        reserve_out in 1..MAX_RESERVE,
+        amount_in in 1..MAX_AMOUNT,
+        fee_rate in 0..BPS_DENOMINATOR,
+    ) {
+        let net = amount_in - swap_fee(amount_in, fee_rate).unwrap();
+        prop_assert_eq!(
+            curve.output_after_fee(reserve_in, reserve_out, amount_in, fee_rate).unwrap(),
+            curve.output(reserve_in, reserve_out, net as u128).unwrap()
+        );
+    }
+
+    #[test]
+    fn balanced_stable_pool_prices_at_par(curve in stable(), reserve in STABLE_MIN_RESERVE..MAX_RESERVE) {
+        let Curve::StableSwap { amp } = curve else { unreachable!() };
+        prop_assert_eq!(stable_spot_price_x64(reserve as u128, reserve as u128, amp).unwrap(), ONE_X64);
+    }
+
+    // The stable spot price lies between par and the constant-product price
+    // y / x, up to the truncation of its fixed-point terms
+    #[test]
+    fn stable_spot_price_is_bracketed(
+        curve in stable(),
+        reserve_x in STABLE_MIN_RESERVE..MAX_RESERVE,
+        reserve_y in STABLE_MIN_RESERVE..MAX_RESERVE,
+    ) {
+        let Curve::StableSwap { amp } = curve else { unreachable!() };
+        let price = stable_spot_price_x64(reserve_x as u128, reserve_y as u128, amp).unwrap();
+        let constant = ((reserve_y as u128) << 64) / reserve_x as u128;
+        let (low, high) = (constant.min(ONE_X64), constant.max(ONE_X64));
+        prop_assert!(price >= low - (low >> 32) && price <= high + (high >> 32));
+    }
+
+    #[test]
+    fn input_rejects_draining_the_reserve(
+        curve in prop_oneof![any_curve(), stable()],
+        reserve_in in 1..MAX_RESERVE,
+        reserve_out in 1..MAX_RESERVE,
+    ) {
+        prop_assert!(curve.input(reserve_in, reserve_out, reserve_out).is_err());
+        prop_assert!(curve.input(reserve_in, reserve_out, reserve_out + 1).is_err());
+    }
+
+    #[test]
+    fn even_weighted_rounds_no_better_than_constant_product(
+        reserve_in in 1..MAX_RESERVE,
+        reserve_out in 1..MAX_RESERVE,
+        amount_in in 1..MAX_AMOUNT,
+    ) {
+        let even = Curve::Weighted { weight_in: 5_000, weight_out: 5_000 };
+        let weighted_out = even.output(reserve_in, reserve_out, amount_in as u128).unwrap();
+        let constant_out = Curve::ConstantProduct.output(reserve_in, reserve_out, amount_in as u128).unwrap();
+        prop_assert!(weighted_out <= constant_out);
+    }
+
+    #[test]
+    fn swap_fee_is_bounded(amount_in in any::<u64>(), fee_rate in 0..BPS_DENOMINATOR) {
+        let fee = swap_fee(amount_in, fee_rate).unwrap();
+        prop_assert!(fee <= amount_in);
+        let after = amount_after_fee(amount_in, fee_rate).unwrap();
+        prop_assert!(after <= amount_in as u128);
+        prop_assert!(after + fee as u128 <= amount_in as u128 + 1);
+    }
+
+    #[test]
+    fn fee_gross_up_covers_net(net in 0..MAX_AMOUNT as u128, fee_rate in 0..BPS_DENOMINATOR) {
+        let gross = amount_before_fee(net, fee_rate).unwrap();
+        prop_assert!(gross >= net);
+        let gross = u64::try_from(gross).unwrap();
+        prop_assert!(amount_after_fee(gross, fee_rate).unwrap() >= net);
+    }
+
+    #[test]
+    fn full_fee_rate_is_rejected(amount in any::<u64>(), excess in 1u64..1_000) {
+        prop_assert!(amount_before_fee(amount as u128, BPS_DENOMINATOR).is_err());
+        prop_assert!(amount_after_fee(amount, BPS_DENOMINATOR + excess).is_err());
+    }
+
+    #[test]
+    fn mul_div_rounding_brackets(a in any::<u64>(), b in any::<u64>(), denominator in 1..u64::MAX) {
+        let floor = mul_div_floor(a as u128, b as u128, denominator as u128).unwrap();
+        let ceil = mul_div_ceil(a as u128, b as u128, denominator as u128).unwrap();
+        prop_assert!(floor <= ceil && ceil <= floor + 1);
+        prop_assert!(floor * denominator as u128 <= a as u128 * b as u128);
+    }
+
+    #[test]
+    fn sqrt_is_floor(value in any::<u128>()) {
+        let root = sqrt_u128(value);
+        prop_assert!(root.checked_mul(root).is_some_and(|square| square <= value));
+        prop_assert!((root + 1).checked_mul(root + 1).is_none_or(|square| square > value));
+    }
+}
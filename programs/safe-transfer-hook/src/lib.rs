@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
 
 declare_id!("BroadwayHooK11111111111111111111111111111111");
 
@@ -10,9 +13,8 @@ pub mod safe_transfer_hook {
     pub fn execute(ctx: Context<Execute>, amount: u64) -> Result<()> {
         msg!("Safe Transfer Hook: Executing transfer of {} tokens", amount);
         
-        // This is a "safe" hook that performs basic validation and logging
-        // In a real-world scenario, this could implement:
-        // - Wallet whitelisting
+        // This is a "safe" hook that performs basic validation, logging and
+        // optional wallet allow/deny lists. It could further implement:
         // - Transaction limits
         // - Time-based restrictions
         // - Compliance checks
@@ -33,6 +35,20 @@ pub mod safe_transfer_hook {
             SafeTransferHookError::InvalidAmount
         );
         
+        // While a list is active the extra account metas resolve the hook
+        // config and both wallets' list entries, in that order
+        if let [hook_config_info, source_entry, destination_entry, ..] = ctx.remaining_accounts {
+            let mint = transfer_hook_accounts.mint.key();
+            let (expected_config, _) = Pubkey::find_program_address(&[HOOK_CONFIG_SEED, mint.as_ref()], &crate::ID);
+            require_keys_eq!(hook_config_info.key(), expected_config, SafeTransferHookError::InvalidListEntry);
+            require_keys_eq!(*hook_config_info.owner, crate::ID, SafeTransferHookError::InvalidListEntry);
+            let hook_config = HookConfig::try_deserialize(&mut &hook_config_info.try_borrow_data()?[..])?;
+            
+            let source_listed = is_listed(source_entry, &mint, &transfer_hook_accounts.source_token)?;
+            let destination_listed = is_listed(destination_entry, &mint, &transfer_hook_accounts.destination_token)?;
+            hook_config.list_mode.check(source_listed, destination_listed)?;
+        }
+        
         // Example: Simple rate limiting check (in production, this would use a PDA to store state)
         // For now, we just log and approve all transfers
         msg!("Transfer approved by Safe Transfer Hook");
@@ -40,20 +56,320 @@ pub mod safe_transfer_hook {
         Ok(())
     }
 
-    /// Initialize extra account metas - called when setting up the transfer hook
+    /// Token-2022 calls the hook with the spl-transfer-hook-interface `Execute`
+    /// discriminator rather than Anchor's, so route that to `execute`
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        match TransferHookInstruction::unpack(data)? {
+            TransferHookInstruction::Execute { amount } => {
+                __private::__global::execute(program_id, accounts, &amount.to_le_bytes())
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
+    }
+
+    /// Initialize extra account metas - called when setting up the transfer hook.
+    /// Idempotent: calling it again for an initialized mint is a no-op, so
+    /// setup scripts can be re-run safely.
     pub fn initialize_extra_account_metas(
-        _ctx: Context<InitializeExtraAccountMetas>,
+        ctx: Context<InitializeExtraAccountMetas>,
     ) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        if hook_config.admin != Pubkey::default() {
+            msg!("Extra account metas already initialized for mint {}", ctx.accounts.mint.key());
+            return Ok(());
+        }
+        
         msg!("Initializing extra account metas for Safe Transfer Hook");
         
+        // The authority that sets the hook up becomes its admin and can later
+        // change the extra accounts via update_extra_account_metas
+        hook_config.mint = ctx.accounts.mint.key();
+        hook_config.admin = ctx.accounts.authority.key();
+        hook_config.bump = ctx.bumps.hook_config;
+        
         // This hook doesn't require any extra accounts beyond the standard ones
         // In more complex hooks, you might require additional accounts like:
         // - Authority accounts
         // - State accounts
         // - Oracle accounts
+        write_extra_account_metas(
+            &ctx.accounts.extra_account_metas.to_account_info(),
+            &ctx.accounts.mint.key(),
+            ctx.bumps.extra_account_metas,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &[],
+        )
+    }
+
+    /// Replace the extra accounts the hook requires, e.g. to add a KYC
+    /// registry when the transfer rules change, without re-creating the mint
+    pub fn update_extra_account_metas(
+        ctx: Context<UpdateExtraAccountMetas>,
+        extra_accounts: Vec<ExtraAccountInput>,
+    ) -> Result<()> {
+        require!(
+            extra_accounts.len() <= MAX_EXTRA_ACCOUNTS,
+            SafeTransferHookError::TooManyExtraAccounts
+        );
+        
+        // The list accounts stay in front so execute finds them at fixed indices
+        let mut metas = list_account_metas(ctx.accounts.hook_config.list_mode)?;
+        require!(
+            metas.len() + extra_accounts.len() <= MAX_EXTRA_ACCOUNTS,
+            SafeTransferHookError::TooManyExtraAccounts
+        );
+        for account in &extra_accounts {
+            metas.push(ExtraAccountMeta::new_with_pubkey(&account.pubkey, account.is_signer, account.is_writable)?);
+        }
         
+        write_extra_account_metas(
+            &ctx.accounts.extra_account_metas.to_account_info(),
+            &ctx.accounts.mint.key(),
+            ctx.bumps.extra_account_metas,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &metas,
+        )?;
+        
+        msg!("Extra account metas updated: {} accounts", metas.len());
         Ok(())
     }
+
+    /// Switch the hook between no list, an allow-list and a deny-list of
+    /// wallets. Rewrites the extra account metas to the list accounts, so
+    /// any custom extra accounts must be re-applied afterwards.
+    pub fn set_list_mode(ctx: Context<SetListMode>, list_mode: ListMode) -> Result<()> {
+        ctx.accounts.hook_config.list_mode = list_mode;
+        
+        let metas = list_account_metas(list_mode)?;
+        write_extra_account_metas(
+            &ctx.accounts.extra_account_metas.to_account_info(),
+            &ctx.accounts.mint.key(),
+            ctx.bumps.extra_account_metas,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &metas,
+        )?;
+        
+        msg!("List mode set to {:?}", list_mode);
+        Ok(())
+    }
+
+    /// Put a wallet on the mint's list. Whether that allows or denies it
+    /// depends on the hook config's list mode.
+    pub fn add_address_to_list(ctx: Context<AddAddressToList>, wallet: Pubkey) -> Result<()> {
+        let list_entry = &mut ctx.accounts.list_entry;
+        list_entry.mint = ctx.accounts.mint.key();
+        list_entry.wallet = wallet;
+        list_entry.bump = ctx.bumps.list_entry;
+        
+        msg!("Wallet {} added to the list", wallet);
+        Ok(())
+    }
+
+    /// Take a wallet off the mint's list, refunding the entry's rent to the admin
+    pub fn remove_address_from_list(_ctx: Context<RemoveAddressFromList>, wallet: Pubkey) -> Result<()> {
+        msg!("Wallet {} removed from the list", wallet);
+        Ok(())
+    }
+}
+
+/// Maximum extra accounts the hook can require
+pub const MAX_EXTRA_ACCOUNTS: usize = 8;
+
+/// Seed of the extra account metas PDA defined by the transfer hook interface
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// Seed of the per-mint hook config PDA
+pub const HOOK_CONFIG_SEED: &[u8] = b"hook_config";
+
+/// Seed of the per-wallet list entry PDAs
+pub const LIST_ENTRY_SEED: &[u8] = b"list_entry";
+
+/// Byte offset of the owner in a token account
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+
+/// Extra accounts execute needs to enforce `list_mode`: the hook config, then
+/// the list entries of the source and destination token accounts' owners
+fn list_account_metas(list_mode: ListMode) -> Result<Vec<ExtraAccountMeta>> {
+    if list_mode == ListMode::Disabled {
+        return Ok(Vec::new());
+    }
+    
+    // Execute's fixed accounts: source (0), mint (1), destination (2), owner (3)
+    let entry_meta = |token_account_index: u8| {
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: LIST_ENTRY_SEED.to_vec() },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountData {
+                    account_index: token_account_index,
+                    data_index: TOKEN_ACCOUNT_OWNER_OFFSET as u8,
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )
+    };
+    Ok(vec![
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: HOOK_CONFIG_SEED.to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        entry_meta(0)?,
+        entry_meta(2)?,
+    ])
+}
+
+/// Whether the owner of `token_account` is on the mint's list. `entry` must
+/// be that owner's list entry PDA; an empty one means the wallet is unlisted.
+fn is_listed(entry: &AccountInfo, mint: &Pubkey, token_account: &AccountInfo) -> Result<bool> {
+    let data = token_account.try_borrow_data()?;
+    require!(
+        data.len() >= TOKEN_ACCOUNT_OWNER_OFFSET + 32,
+        SafeTransferHookError::InvalidListEntry
+    );
+    let wallet = Pubkey::try_from(&data[TOKEN_ACCOUNT_OWNER_OFFSET..TOKEN_ACCOUNT_OWNER_OFFSET + 32])
+        .map_err(|_| SafeTransferHookError::InvalidListEntry)?;
+    
+    let (expected_entry, _) = Pubkey::find_program_address(
+        &[LIST_ENTRY_SEED, mint.as_ref(), wallet.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(entry.key(), expected_entry, SafeTransferHookError::InvalidListEntry);
+    Ok(entry.owner == &crate::ID && !entry.data_is_empty())
+}
+
+/// Create or resize the extra account metas PDA and write `metas` into it,
+/// replacing any previous list
+fn write_extra_account_metas<'info>(
+    extra_account_metas: &AccountInfo<'info>,
+    mint: &Pubkey,
+    bump: u8,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    metas: &[ExtraAccountMeta],
+) -> Result<()> {
+    let space = ExtraAccountMetaList::size_of(metas.len())?;
+    let rent_required = Rent::get()?.minimum_balance(space);
+    
+    if extra_account_metas.data_is_empty() {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                extra_account_metas.key,
+                rent_required,
+                space as u64,
+                &crate::ID,
+            ),
+            &[payer.clone(), extra_account_metas.clone(), system_program.clone()],
+            &[&[EXTRA_ACCOUNT_METAS_SEED, mint.as_ref(), &[bump]]],
+        )?;
+    } else {
+        let current_lamports = extra_account_metas.lamports();
+        if current_lamports < rent_required {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    anchor_lang::system_program::Transfer {
+                        from: payer.clone(),
+                        to: extra_account_metas.clone(),
+                    },
+                ),
+                rent_required - current_lamports,
+            )?;
+        }
+        extra_account_metas.realloc(space, false)?;
+        extra_account_metas.try_borrow_mut_data()?.fill(0);
+    }
+    
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut extra_account_metas.try_borrow_mut_data()?,
+        metas,
+    )?;
+    Ok(())
+}
+
+/// Hook Config
+/// Per-mint settings of the hook, recording who may change its extra accounts
+#[account]
+#[derive(Default)]
+pub struct HookConfig {
+    /// Mint this config applies to
+    pub mint: Pubkey,
+    
+    /// Admin allowed to update the extra account metas
+    pub admin: Pubkey,
+    
+    /// Config bump seed
+    pub bump: u8,
+    
+    /// Which wallet list execute enforces
+    pub list_mode: ListMode,
+}
+
+/// How execute treats wallets with a list entry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListMode {
+    /// No list is enforced
+    #[default]
+    Disabled,
+    /// Sender and recipient must both be listed
+    AllowList,
+    /// Neither sender nor recipient may be listed
+    DenyList,
+}
+
+impl ListMode {
+    /// Check a transfer between wallets with the given listing status
+    pub fn check(&self, source_listed: bool, destination_listed: bool) -> Result<()> {
+        match self {
+            ListMode::Disabled => {}
+            ListMode::AllowList => require!(
+                source_listed && destination_listed,
+                SafeTransferHookError::AddressNotAllowed
+            ),
+            ListMode::DenyList => require!(
+                !source_listed && !destination_listed,
+                SafeTransferHookError::AddressDenied
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// List Entry
+/// Marks a wallet as listed for a mint; the config's list mode decides
+/// whether that allows or denies its transfers
+#[account]
+#[derive(Default)]
+pub struct ListEntry {
+    /// Mint whose list this entry belongs to
+    pub mint: Pubkey,
+    
+    /// Listed wallet (token account owner)
+    pub wallet: Pubkey,
+    
+    /// Entry bump seed
+    pub bump: u8,
+}
+
+/// Fixed-address extra account required by the hook
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ExtraAccountInput {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
 }
 
 #[derive(Accounts)]
@@ -73,26 +389,178 @@ pub struct Execute<'info> {
     /// The token account owner/delegate
     /// CHECK: This is validated by the Token-2022 program
     pub owner: UncheckedAccount<'info>,
+    
+    /// The extra account metas account
+    /// CHECK: This is validated by the Token-2022 program
+    pub extra_account_metas: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
 pub struct InitializeExtraAccountMetas<'info> {
     /// The extra account metas account
     /// CHECK: This account is used by the transfer hook interface
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump
+    )]
     pub extra_account_metas: UncheckedAccount<'info>,
     
+    /// Hook config recording the admin
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + std::mem::size_of::<HookConfig>(),
+        seeds = [HOOK_CONFIG_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+    
     /// The mint account
     /// CHECK: This is validated by the Token-2022 program
     pub mint: UncheckedAccount<'info>,
     
     /// The authority for the extra account metas
+    #[account(mut)]
     pub authority: Signer<'info>,
     
     /// System program for account creation
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateExtraAccountMetas<'info> {
+    /// The extra account metas account
+    /// CHECK: This account is used by the transfer hook interface
+    #[account(
+        mut,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_metas: UncheckedAccount<'info>,
+    
+    /// Hook config recording the admin
+    #[account(
+        seeds = [HOOK_CONFIG_SEED, mint.key().as_ref()],
+        bump = hook_config.bump,
+        has_one = mint,
+        has_one = admin @ SafeTransferHookError::NotAuthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+    
+    /// The mint account
+    /// CHECK: This is validated by the Token-2022 program
+    pub mint: UncheckedAccount<'info>,
+    
+    /// The hook admin, paying for any extra space
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    /// System program for rent top-ups
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetListMode<'info> {
+    /// The extra account metas account
+    /// CHECK: This account is used by the transfer hook interface
+    #[account(
+        mut,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_metas: UncheckedAccount<'info>,
+    
+    /// Hook config, grown to the current layout if it predates list modes
+    #[account(
+        mut,
+        seeds = [HOOK_CONFIG_SEED, mint.key().as_ref()],
+        bump = hook_config.bump,
+        has_one = mint,
+        has_one = admin @ SafeTransferHookError::NotAuthorized,
+        realloc = 8 + std::mem::size_of::<HookConfig>(),
+        realloc::payer = admin,
+        realloc::zero = false
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+    
+    /// The mint account
+    /// CHECK: This is validated by the Token-2022 program
+    pub mint: UncheckedAccount<'info>,
+    
+    /// The hook admin, paying for any extra space
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    /// System program for rent top-ups
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddAddressToList<'info> {
+    /// Hook config recording the admin
+    #[account(
+        seeds = [HOOK_CONFIG_SEED, mint.key().as_ref()],
+        bump = hook_config.bump,
+        has_one = mint,
+        has_one = admin @ SafeTransferHookError::NotAuthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+    
+    /// The new list entry
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<ListEntry>(),
+        seeds = [LIST_ENTRY_SEED, mint.key().as_ref(), wallet.as_ref()],
+        bump
+    )]
+    pub list_entry: Account<'info, ListEntry>,
+    
+    /// The mint account
+    /// CHECK: This is validated by the Token-2022 program
+    pub mint: UncheckedAccount<'info>,
+    
+    /// The hook admin, paying for the entry
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RemoveAddressFromList<'info> {
+    /// Hook config recording the admin
+    #[account(
+        seeds = [HOOK_CONFIG_SEED, mint.key().as_ref()],
+        bump = hook_config.bump,
+        has_one = mint,
+        has_one = admin @ SafeTransferHookError::NotAuthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+    
+    /// The list entry to close
+    #[account(
+        mut,
+        close = admin,
+        seeds = [LIST_ENTRY_SEED, mint.key().as_ref(), wallet.as_ref()],
+        bump = list_entry.bump,
+        has_one = mint
+    )]
+    pub list_entry: Account<'info, ListEntry>,
+    
+    /// The mint account
+    /// CHECK: This is validated by the Token-2022 program
+    pub mint: UncheckedAccount<'info>,
+    
+    /// The hook admin, receiving the entry's rent
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
 #[error_code]
 pub enum SafeTransferHookError {
     #[msg("Invalid transfer amount")]
@@ -101,13 +569,22 @@ pub enum SafeTransferHookError {
     NotAuthorized,
     #[msg("Rate limit exceeded")]
     RateLimitExceeded,
+    #[msg("Too many extra accounts")]
+    TooManyExtraAccounts,
+    #[msg("List account does not match the mint or token account owner")]
+    InvalidListEntry,
+    #[msg("Wallet is not on the mint's allow-list")]
+    AddressNotAllowed,
+    #[msg("Wallet is on the mint's deny-list")]
+    AddressDenied,
 }
 
 // Security features that this hook demonstrates:
 // 1. Input validation (amount > 0)
 // 2. Transfer logging for audit trails
-// 3. Extensible architecture for additional security measures
-// 4. Clear error handling and messaging
+// 3. Wallet allow-lists or deny-lists, managed by the hook admin
+// 4. Extensible architecture for additional security measures
+// 5. Clear error handling and messaging
 //
 // This hook is designed to be a "known safe" program that can be whitelisted
 // in the AMM without introducing security risks. It serves as a template
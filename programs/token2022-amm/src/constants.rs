@@ -2,3 +2,7 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+/// Denominator for all basis-point parameters (10_000 bps = 100%)
+#[constant]
+pub const BPS_DENOMINATOR: u64 = 10_000;
@@ -79,4 +79,488 @@ pub enum AmmError {
     
     #[msg("Invalid proposal proposer")]
     InvalidProposalProposer,
+    
+    #[msg("Invalid balance snapshot")]
+    InvalidBalanceSnapshot,
+    
+    #[msg("Invalid balance delta range")]
+    InvalidBalanceDeltaRange,
+    
+    #[msg("Balance snapshot was not taken in this slot")]
+    StaleBalanceSnapshot,
+    
+    #[msg("Balance change out of range")]
+    BalanceChangeOutOfRange,
+    
+    #[msg("Math overflow")]
+    MathOverflow,
+    
+    #[msg("Invalid emission schedule")]
+    InvalidEmissionSchedule,
+    
+    #[msg("No emissions due")]
+    NoEmissionsDue,
+    
+    #[msg("Insufficient emissions funding")]
+    InsufficientEmissionsFunding,
+    
+    #[msg("Invalid hook cache TTL")]
+    InvalidCacheTtl,
+    
+    #[msg("Mint has no transfer hook")]
+    MintHasNoTransferHook,
+    
+    #[msg("Invalid pool vault")]
+    InvalidVault,
+    
+    #[msg("Invalid remaining accounts")]
+    InvalidRemainingAccounts,
+    
+    #[msg("Too many pools requested")]
+    TooManyPools,
+    
+    #[msg("Invalid LP mint")]
+    InvalidLpMint,
+    
+    #[msg("Invalid token account owner")]
+    InvalidTokenAccountOwner,
+    
+    #[msg("Hook program account missing from remaining accounts")]
+    MissingHookProgram,
+    
+    #[msg("Invalid config authority")]
+    InvalidConfigAuthority,
+    
+    #[msg("Invalid rate limit configuration")]
+    InvalidRateLimit,
+    
+    #[msg("Pool creation limit for this slot reached")]
+    PoolCreationRateLimited,
+    
+    #[msg("Pool creation cooldown has not elapsed")]
+    PoolCreationCooldown,
+    
+    #[msg("Invalid launch guard")]
+    InvalidLaunchGuard,
+    
+    #[msg("Launch guard account missing or mismatched")]
+    MissingLaunchGuard,
+    
+    #[msg("Launch allowlist is full")]
+    LaunchAllowlistFull,
+    
+    #[msg("Depositor not on launch allowlist")]
+    NotOnLaunchAllowlist,
+    
+    #[msg("Pool already has liquidity")]
+    PoolAlreadyLaunched,
+    
+    #[msg("Invalid swap route")]
+    InvalidRoute,
+    
+    #[msg("Too many routes in one call")]
+    TooManyRoutes,
+    
+    #[msg("Signer is not the guardian")]
+    InvalidGuardian,
+    
+    #[msg("Signer does not hold emergency powers")]
+    InvalidEmergencyAuthority,
+    
+    #[msg("Invalid guardian heartbeat timeout")]
+    InvalidHeartbeatTimeout,
+    
+    #[msg("Account is not a whitelist")]
+    InvalidWhitelistAccount,
+    
+    #[msg("Whitelist already uses the current layout")]
+    WhitelistAlreadyMigrated,
+    
+    #[msg("Invalid TWAP window")]
+    InvalidTwapWindow,
+    
+    #[msg("Price feed already published at this timestamp")]
+    PriceFeedUpToDate,
+    
+    #[msg("Invalid fee ramp schedule")]
+    InvalidFeeRamp,
+    
+    #[msg("Proposal dependency account missing or mismatched")]
+    MissingProposalDependency,
+    
+    #[msg("Proposal dependency has not been executed")]
+    ProposalDependencyNotExecuted,
+    
+    #[msg("Taker allowlist is full")]
+    TakerAllowlistFull,
+    
+    #[msg("Taker already on allowlist")]
+    TakerAlreadyAllowed,
+    
+    #[msg("Taker not on the pool's allowlist")]
+    TakerNotAllowed,
+    
+    #[msg("Taker allowlist account missing or mismatched")]
+    MissingTakerAllowlist,
+    
+    #[msg("Pool is already private")]
+    PoolAlreadyPrivate,
+    
+    #[msg("Hook failure slot outside the reporting window")]
+    InvalidHookFailureReport,
+    
+    #[msg("Signer is not the hook failure reporter")]
+    InvalidHookFailureReporter,
+    
+    #[msg("Invalid exit fee configuration")]
+    InvalidExitFee,
+    
+    #[msg("LP position does not match pool and owner")]
+    InvalidLpPosition,
+    
+    #[msg("Extension region was written by a newer program version")]
+    UnsupportedExtensionVersion,
+    
+    #[msg("Transfer hook exceeded the pool's compute budget")]
+    HookComputeBudgetExceeded,
+    
+    #[msg("Incentive group is full")]
+    IncentiveGroupFull,
+    
+    #[msg("Pool is already in the incentive group")]
+    PoolAlreadyInGroup,
+    
+    #[msg("Pool is not in the incentive group")]
+    PoolNotInGroup,
+    
+    #[msg("Member pool accounts missing or out of order")]
+    InvalidGroupMemberAccounts,
+    
+    #[msg("Insufficient staked LP tokens")]
+    InsufficientStakedLp,
+    
+    #[msg("No rewards to claim")]
+    NoRewardsToClaim,
+    
+    #[msg("Token pair mints must be in canonical order")]
+    InvalidTokenPairOrder,
+    
+    #[msg("Pool is winding down")]
+    PoolWindingDown,
+    
+    #[msg("Pool is not winding down")]
+    PoolNotWindingDown,
+    
+    #[msg("Signer is not the pair oracle")]
+    InvalidOracleSigner,
+    
+    #[msg("Invalid oracle price")]
+    InvalidOraclePrice,
+    
+    #[msg("Oracle price missing or stale")]
+    StaleOraclePrice,
+    
+    #[msg("Initial deposit price is outside the oracle band")]
+    InitialPriceOutOfBand,
+    
+    #[msg("Invalid oracle admission configuration")]
+    InvalidOracleAdmission,
+    
+    #[msg("Required input exceeds the maximum amount in")]
+    ExcessiveInputAmount,
+    
+    #[msg("Vault balance does not cover outstanding IOUs and claimable fees")]
+    VaultBelowObligations,
+    
+    #[msg("Protocol fee share exceeds the maximum")]
+    InvalidProtocolFeeShare,
+    
+    #[msg("Signer is not the protocol fee authority")]
+    InvalidProtocolFeeAuthority,
+    
+    #[msg("Protocol fee vault required while the pool takes a protocol fee")]
+    MissingProtocolFeeVault,
+    
+    #[msg("Fee tier is not offered by the AMM config")]
+    InvalidFeeTier,
+    
+    #[msg("AMM is paused")]
+    AmmPaused,
+    
+    #[msg("Whitelist does not match the AMM config")]
+    WhitelistMismatch,
+    
+    #[msg("Pool is paused")]
+    PoolPaused,
+    
+    #[msg("Hook program id is invalid")]
+    InvalidHookProgram,
+    
+    #[msg("Whitelist timelock is below the minimum or shorter than the current one")]
+    InvalidWhitelistTimelock,
+    
+    #[msg("Whitelist is timelocked; queue the change instead")]
+    WhitelistTimelocked,
+    
+    #[msg("A change for this hook is already queued")]
+    HookChangeAlreadyQueued,
+    
+    #[msg("Hook change queue is full")]
+    HookChangeQueueFull,
+    
+    #[msg("No change is queued for this hook")]
+    HookChangeNotQueued,
+    
+    #[msg("Hook change timelock has not elapsed")]
+    HookChangeNotReady,
+    
+    #[msg("Pool already has its own whitelist")]
+    PoolWhitelistAlreadySet,
+    
+    #[msg("Pool whitelist account missing or mismatched")]
+    MissingPoolWhitelist,
+    
+    #[msg("Invalid hook metadata")]
+    InvalidHookMetadata,
+    
+    #[msg("Hook metadata account missing for a high-risk hook")]
+    MissingHookMetadata,
+    
+    #[msg("Transfer exceeds the high-risk hook's notional cap")]
+    HookNotionalCapExceeded,
+    
+    #[msg("Vote stake below the minimum")]
+    InsufficientVoteStake,
+    
+    #[msg("Proposal has not been finalized")]
+    ProposalNotFinalized,
+    
+    #[msg("Stake already withdrawn")]
+    StakeAlreadyWithdrawn,
+    
+    #[msg("No stake escrowed for this voter")]
+    NoStakeToWithdraw,
+    
+    #[msg("Proposal slash rate above 100%")]
+    InvalidSlashRate,
+    
+    #[msg("Proposer stake already settled")]
+    ProposerStakeAlreadySettled,
+    
+    #[msg("Treasury account missing or mismatched")]
+    InvalidTreasury,
+    
+    #[msg("Invalid governance parameters")]
+    InvalidGovernanceParams,
+    
+    #[msg("Proposal kind does not match this instruction")]
+    InvalidProposalKind,
+    
+    #[msg("Fee rate must be below 100%")]
+    InvalidFeeRate,
+    
+    #[msg("Governance does not hold the role this action needs")]
+    InvalidGovernanceRole,
+    
+    #[msg("Proposal is still within its veto window")]
+    VetoWindowOpen,
+    
+    #[msg("Veto window has closed")]
+    VetoWindowClosed,
+    
+    #[msg("Invalid security council")]
+    InvalidSecurityCouncil,
+    
+    #[msg("Not enough security council members signed the veto")]
+    InsufficientVetoSigners,
+    
+    #[msg("Pool observation account missing or mismatched")]
+    MissingObservation,
+    
+    #[msg("Requested time is older than the oldest observation")]
+    ObservationTooOld,
+    
+    #[msg("Invalid number of observation points requested")]
+    InvalidObservePoints,
+    
+    #[msg("Swap would move the pool price more than the allowed impact")]
+    PriceImpactTooHigh,
+    
+    #[msg("Tick range is out of bounds, unordered or off the tick spacing")]
+    InvalidTickRange,
+    
+    #[msg("Square root price is out of range")]
+    InvalidSqrtPrice,
+    
+    #[msg("Liquidity exceeds the pool maximum")]
+    LiquidityOverflow,
+    
+    #[msg("Tick array does not belong to this pool or does not cover the tick")]
+    InvalidTickArray,
+    
+    #[msg("Swap ran past the tick arrays provided")]
+    MissingTickArray,
+    
+    #[msg("Position does not hold enough liquidity")]
+    InsufficientPositionLiquidity,
+    
+    #[msg("Square root price limit is on the wrong side of the current price")]
+    InvalidSqrtPriceLimit,
+    
+    #[msg("Signer does not own the position")]
+    InvalidPositionOwner,
+    
+    #[msg("Pool weights must each be at least 1% and sum to 100%")]
+    InvalidPoolWeights,
+    
+    #[msg("Mints with a permanent delegate are not allowed in pools")]
+    PermanentDelegateNotAllowed,
+    
+    #[msg("Non-transferable mints are not allowed in pools")]
+    NonTransferableMintNotAllowed,
+    
+    #[msg("Mints whose accounts start frozen need their freeze authority to co-sign pool creation")]
+    DefaultFrozenMintNotAllowed,
+    
+    #[msg("Transaction deadline has passed")]
+    DeadlineExceeded,
+    
+    #[msg("Pool is locked by an instruction in progress")]
+    PoolLocked,
+    
+    #[msg("Swap would decrease the pool invariant")]
+    InvariantViolation,
+    
+    #[msg("Lock duration out of range")]
+    InvalidLockDuration,
+    
+    #[msg("LP position is still locked")]
+    LpPositionLocked,
+    
+    #[msg("Lock-up positions mint LP tokens into their escrow account")]
+    LockedLpEscrowRequired,
+    
+    #[msg("Farm end time must be after its start time, which cannot be in the past")]
+    InvalidFarmSchedule,
+    
+    #[msg("Pool price has not reached the order's limit")]
+    LimitPriceNotReached,
+    
+    #[msg("Max swap size cannot exceed 10000 bps")]
+    InvalidMaxSwapSize,
+    
+    #[msg("Swap exceeds the pool's max swap size")]
+    SwapTooLarge,
+    
+    #[msg("Dynamic fee bounds must satisfy min <= max < 100% with a non-zero volatility cap")]
+    InvalidDynamicFee,
+    
+    #[msg("Pool already has liquidity and cannot be bootstrapped")]
+    PoolAlreadyBootstrapped,
+    
+    #[msg("Protocol liquidity is still timelocked")]
+    ProtocolLiquidityLocked,
+    
+    #[msg("Pool is in withdraw-only mode")]
+    PoolWithdrawOnly,
+    
+    #[msg("Whitelist entry PDA of the mint's hook must be passed")]
+    MissingWhitelistEntry,
+    
+    #[msg("No mint hook of the pool has been removed from the whitelist")]
+    HookStillWhitelisted,
+    
+    #[msg("Fillable amount is below the requested minimum fill")]
+    PartialFillTooSmall,
+    
+    #[msg("Observation was written less than the crank interval ago")]
+    ObservationCrankTooSoon,
+    
+    #[msg("Mints configured for confidential transfers are not allowed in pools")]
+    ConfidentialTransferMintNotAllowed,
+    
+    #[msg("Token account only accepts confidential credits; enable non-confidential credits to receive pool transfers")]
+    PublicCreditsDisabled,
+    
+    #[msg("Destination requires a memo but the SPL Memo program was not supplied")]
+    MemoProgramRequired,
+    
+    #[msg("Token account has CPI Guard enabled, which blocks transfers signed by its owner through the AMM; disable it with the Token-2022 DisableCpiGuard instruction (or trade from another account) and retry")]
+    CpiGuardEnabled,
+    
+    #[msg("Signer is not the mint's freeze authority")]
+    InvalidFreezeAuthority,
+    
+    #[msg("Pair has a permanent delegate that can drain the pool; pass acknowledge_permanent_delegate to create it")]
+    PermanentDelegateNotAcknowledged,
+    
+    #[msg("AMM config already has an LP token group")]
+    LpGroupAlreadyInitialized,
+    
+    #[msg("LP group mint is missing or does not match the AMM config's")]
+    LpGroupMismatch,
+    
+    #[msg("Mint is not a native (wrapped SOL) mint")]
+    NotNativeMint,
+    
+    #[msg("Wrapped SOL path needs the user's associated WSOL account and the Associated Token program")]
+    InvalidWrappedSolAccount,
+    
+    #[msg("Token account does not exist; pass the Associated Token and System programs to create it")]
+    TokenAccountMissing,
+    
+    #[msg("Instruction is not compiled into this deployment profile")]
+    FeatureDisabled,
+    
+    #[msg("The affected leg's hook is neither flagged as blocked nor removed from the whitelist")]
+    HookNotBlocked,
+    
+    #[msg("Stable pool amplification must be between 1 and the maximum")]
+    InvalidAmplification,
+    
+    #[msg("Instruction does not support this pool curve")]
+    UnsupportedPoolCurve,
+}
+
+/// Context for a failure, emitted as an event just before the error is returned.
+/// Errors like `InsufficientLiquidity` are raised from many code paths; the event
+/// names the failed constraint and the mint / hook involved so the failing path
+/// can be told apart from the transaction log.
+#[event]
+pub struct ErrCtx {
+    /// Anchor error code being returned
+    pub error_code: u32,
+    /// Source of the constraint that failed
+    pub constraint: String,
+    /// Mint involved, if any
+    pub mint: Option<Pubkey>,
+    /// Transfer Hook program involved, if any
+    pub hook_program: Option<Pubkey>,
+}
+
+impl AmmError {
+    /// Emit an `ErrCtx` for this error and convert it for returning
+    pub fn with_ctx(self, constraint: &str, mint: Option<Pubkey>, hook_program: Option<Pubkey>) -> Error {
+        emit!(ErrCtx {
+            error_code: self.into(),
+            constraint: constraint.to_string(),
+            mint,
+            hook_program,
+        });
+        self.into()
+    }
+}
+
+/// `require!` that emits an `ErrCtx` naming the failed constraint (plus the
+/// optional mint and hook program) before returning the error
+#[macro_export]
+macro_rules! require_ctx {
+    ($cond:expr, $err:expr) => {
+        $crate::require_ctx!($cond, $err, None, None)
+    };
+    ($cond:expr, $err:expr, $mint:expr, $hook_program:expr) => {
+        if !($cond) {
+            return Err($err.with_ctx(stringify!($cond), $mint, $hook_program));
+        }
+    };
 }
@@ -0,0 +1,197 @@
+use anchor_lang::prelude::*;
+use crate::state::{PoolSide, ProposalAction};
+
+/// Which config account a `ConfigUpdated` event refers to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigKind {
+    AmmConfig,
+    Pool,
+    GlobalConfig,
+    GovernanceConfig,
+}
+
+/// Emitted when a pool is initialized
+#[event]
+pub struct PoolCreated {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    /// Swap fee of the pool (basis points)
+    pub fee_rate: u64,
+    /// Permanent delegates of the pair's mints; frontends should warn LPs
+    /// that these can move tokens out of the vaults
+    pub permanent_delegate_a: Option<Pubkey>,
+    pub permanent_delegate_b: Option<Pubkey>,
+    pub created_at: i64,
+}
+
+/// Emitted when a pool's LP mint joins the AMM's LP token group
+#[event]
+pub struct LpGroupMemberAdded {
+    pub pool: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_group_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when a swap settles
+#[event]
+pub struct SwapExecuted {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    /// Side tokens entered the pool on
+    pub input_side: PoolSide,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Total swap fee charged on `amount_in`, protocol share included
+    pub fee_amount: u64,
+    pub protocol_fee: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted after every constant-product / weighted pool swap with the price
+/// it executed at, so indexers need not derive it from `SwapExecuted`
+#[event]
+pub struct SwapPriceRealized {
+    pub pool: Pubkey,
+    /// Side tokens entered the pool on
+    pub input_side: PoolSide,
+    /// Output per input the swap paid, fee included (Q64.64)
+    pub realized_price_x64: u128,
+    /// Spot price of the input token in the output token after the swap (Q64.64)
+    pub spot_price_x64: u128,
+    /// Pool's lifetime swap count, this swap included
+    pub swap_count: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a keeper writes an observation point for an idle pool
+#[event]
+pub struct ObservationCranked {
+    pub pool: Pubkey,
+    pub cranker: Pubkey,
+    /// Mint of the protocol fee vault the reward came from
+    pub reward_mint: Pubkey,
+    pub reward: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a keeper fills a limit order
+#[event]
+pub struct LimitOrderFilled {
+    pub pool: Pubkey,
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub keeper: Pubkey,
+    /// Side the order's input entered the pool on
+    pub input_side: PoolSide,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when liquidity is deposited into a pool
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_tokens_minted: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when liquidity is withdrawn from a pool
+#[event]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    /// Amounts paid out to the user
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_tokens_burned: u64,
+    /// Leg recorded as an IOU instead of paid out, for single-leg exits
+    pub iou_side: Option<PoolSide>,
+    pub timestamp: i64,
+}
+
+/// Emitted when an LP position collects its earned swap fees
+#[event]
+pub struct FeesCollected {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a hook is added to or removed from a whitelist
+#[event]
+pub struct HookWhitelisted {
+    pub whitelist: Pubkey,
+    pub hook_program_id: Pubkey,
+    /// False when the hook was removed from the whitelist
+    pub whitelisted: bool,
+    /// Proposal the change was executed from, if any
+    pub proposal: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+/// Emitted when a pool is put into withdraw-only mode because the hook of
+/// one of its mints is no longer whitelisted
+#[event]
+pub struct PoolHookRevoked {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub hook_program_id: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when a governance proposal is opened
+#[event]
+pub struct ProposalCreated {
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    /// Hook proposed, for `AddHook` proposals
+    pub hook_program_id: Pubkey,
+    pub action: ProposalAction,
+    pub proposer_stake: u64,
+    pub voting_deadline: i64,
+}
+
+/// Emitted when a vote is escrowed on a proposal
+#[event]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    /// True for approve, false for reject
+    pub approve: bool,
+    pub stake_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when voting on a proposal closes
+#[event]
+pub struct ProposalFinalized {
+    pub proposal: Pubkey,
+    pub hook_program_id: Pubkey,
+    /// Whether the proposal passed quorum and the approval threshold
+    pub approved: bool,
+    pub total_approve_stake: u64,
+    pub total_reject_stake: u64,
+    pub vote_count: u64,
+    pub finalized_at: i64,
+}
+
+/// Emitted when an AMM, pool, global or governance config changes
+#[event]
+pub struct ConfigUpdated {
+    pub kind: ConfigKind,
+    /// Config or pool account that changed
+    pub config: Pubkey,
+    /// Signer of the update (a proposal key for governance execution)
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
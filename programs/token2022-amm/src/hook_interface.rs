@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    compute_units::sol_remaining_compute_units,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use anchor_spl::token_2022::TransferChecked;
+use anchor_spl::token_2022::spl_token_2022::onchain::invoke_transfer_checked;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use crate::error::AmmError;
+
+/// Discriminator of the spl-transfer-hook-interface `Execute` instruction
+/// (first 8 bytes of sha256("spl-transfer-hook-interface:execute"))
+pub const SPL_EXECUTE_DISCRIMINATOR: [u8; 8] = [105, 37, 101, 197, 75, 251, 102, 26];
+
+/// Discriminator of a legacy Anchor `execute(amount)` hook method
+/// (first 8 bytes of sha256("global:execute"))
+pub const LEGACY_EXECUTE_DISCRIMINATOR: [u8; 8] = [130, 221, 242, 154, 13, 193, 189, 29];
+
+/// Seed of the spl-transfer-hook-interface validation account, derived as
+/// [EXTRA_ACCOUNT_METAS_SEED, mint] under the hook program
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// Address of the extra-account-metas validation account a hook program keeps for `mint`
+pub fn extra_account_metas_address(mint: &Pubkey, hook_program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[EXTRA_ACCOUNT_METAS_SEED, mint.as_ref()], hook_program_id).0
+}
+
+/// Interface a whitelisted Transfer Hook program speaks
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HookInterfaceVersion {
+    /// spl-transfer-hook-interface `Execute`, invoked by Token-2022 itself during
+    /// `transfer_checked`
+    #[default]
+    SplInterface = 0,
+    /// Pre-interface hooks exposing an Anchor `execute(amount)` method over
+    /// (source, mint, destination, owner); the AMM invokes these directly
+    /// alongside each transfer
+    LegacyExecute = 1,
+}
+
+impl HookInterfaceVersion {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => HookInterfaceVersion::LegacyExecute,
+            _ => HookInterfaceVersion::SplInterface,
+        }
+    }
+
+    pub fn execute_discriminator(&self) -> [u8; 8] {
+        match self {
+            HookInterfaceVersion::SplInterface => SPL_EXECUTE_DISCRIMINATOR,
+            HookInterfaceVersion::LegacyExecute => LEGACY_EXECUTE_DISCRIMINATOR,
+        }
+    }
+
+    /// Build the execute instruction in the shape this interface version expects.
+    /// `extra_accounts` are appended for the SPL interface (validation account
+    /// first, then resolved extra metas) and ignored for legacy hooks.
+    pub fn build_execute_instruction(
+        &self,
+        hook_program_id: Pubkey,
+        source: Pubkey,
+        mint: Pubkey,
+        destination: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+        extra_accounts: &[AccountMeta],
+    ) -> Instruction {
+        let mut data = self.execute_discriminator().to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(source, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(destination, false),
+            AccountMeta::new_readonly(owner, false),
+        ];
+        if *self == HookInterfaceVersion::SplInterface {
+            accounts.extend_from_slice(extra_accounts);
+        }
+
+        Instruction {
+            program_id: hook_program_id,
+            accounts,
+            data,
+        }
+    }
+}
+
+/// Run the hook step that Token-2022 will not run on the AMM's behalf.
+/// SPL-interface hooks are executed by Token-2022 inside `transfer_checked`, so
+/// this is a no-op for them; legacy hooks are invoked here with their own
+/// instruction shape. Each account keeps the writable and signer flags it has
+/// in the transfer, and when the mint's validation account is supplied it is
+/// passed on together with the extra metas it resolves, in the same order the
+/// SPL `Execute` uses. The hook program must be present in `remaining_accounts`.
+pub fn invoke_legacy_hook_if_needed<'info>(
+    version: Option<HookInterfaceVersion>,
+    hook_program_id: Option<Pubkey>,
+    source: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    amount: u64,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let (Some(HookInterfaceVersion::LegacyExecute), Some(hook_program_id)) = (version, hook_program_id) else {
+        return Ok(());
+    };
+
+    let hook_program = remaining_accounts
+        .iter()
+        .find(|info| info.key() == hook_program_id)
+        .ok_or(AmmError::MissingHookProgram)?;
+
+    let mut ix = legacy_execute_instruction(hook_program_id, source, mint, destination, owner, amount);
+    let mut account_infos = vec![source.clone(), mint.clone(), destination.clone(), owner.clone()];
+
+    let validation_address = extra_account_metas_address(mint.key, &hook_program_id);
+    if let Some(validation_info) = remaining_accounts.iter().find(|info| info.key() == validation_address) {
+        ix.accounts.push(AccountMeta::new_readonly(validation_address, false));
+        account_infos.push(validation_info.clone());
+        ExtraAccountMetaList::add_to_cpi_instruction::<ExecuteInstruction>(
+            &mut ix,
+            &mut account_infos,
+            &validation_info.try_borrow_data()?,
+            remaining_accounts,
+        )?;
+    }
+    account_infos.push(hook_program.clone());
+
+    invoke(&ix, &account_infos)?;
+    Ok(())
+}
+
+/// Legacy `execute(amount)` over (source, mint, destination, owner), each
+/// account marked writable or signer exactly as it is in the transfer
+fn legacy_execute_instruction<'info>(
+    hook_program_id: Pubkey,
+    source: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    amount: u64,
+) -> Instruction {
+    let mut ix = HookInterfaceVersion::LegacyExecute.build_execute_instruction(
+        hook_program_id,
+        source.key(),
+        mint.key(),
+        destination.key(),
+        owner.key(),
+        amount,
+        &[],
+    );
+    for (meta, info) in ix.accounts.iter_mut().zip([source, mint, destination, owner]) {
+        meta.is_writable = info.is_writable;
+        meta.is_signer = info.is_signer;
+    }
+    ix
+}
+
+/// Token-2022 `transfer_checked` that carries the mint's Transfer Hook accounts.
+/// For SPL-interface hooks the validation account and every extra account meta
+/// it lists are resolved out of `hook_accounts` and appended to the CPI (via
+/// `add_extra_accounts_for_execute_cpi`), so Token-2022 can invoke the hook.
+/// Mints without a hook transfer as usual. Token-2022 insists on the hook
+/// accounts for every TransferHook mint, so legacy hooks take the same
+/// transfer and are additionally run by `invoke_legacy_hook_if_needed`.
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        hook_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Run a hook-bearing step under a compute budget. Compute is measured before
+/// and after `step`; exceeding `max_hook_cu` aborts with
+/// `HookComputeBudgetExceeded` so a bloated hook fails fast instead of pushing
+/// the whole transaction past its limit. `None` runs the step unmetered.
+pub fn with_hook_compute_budget<T>(
+    max_hook_cu: Option<u64>,
+    step: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let Some(max_hook_cu) = max_hook_cu else {
+        return step();
+    };
+
+    let before = sol_remaining_compute_units();
+    let result = step()?;
+    let consumed = before.saturating_sub(sol_remaining_compute_units());
+    require!(consumed <= max_hook_cu, AmmError::HookComputeBudgetExceeded);
+    Ok(result)
+}
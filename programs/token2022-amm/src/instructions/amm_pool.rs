@@ -1,74 +1,469 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Mint};
-use crate::state::AmmPool;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::system_program::{self, CreateAccount, Transfer};
+use anchor_spl::token_2022::{self, initialize_mint2, InitializeMint2, Token2022};
+use spl_token_2022::{
+    extension::{group_member_pointer, group_pointer, metadata_pointer, BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    state::Mint as MintState,
+};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_interface::{thaw_account, Mint, ThawAccount, TokenAccount, TokenInterface};
+use spl_token_group_interface::state::TokenGroupMember;
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
+use crate::state::{AmmPool, AmmConfig, GlobalConfig, PoolCreatorRecord, FeeMode, PoolCurve, LaunchGuard, MAX_LAUNCH_ALLOWLIST, TakerAllowlist, TransferHookWhitelist, WhitelistEntry};
 use crate::error::AmmError;
+use crate::events::{PoolCreated, ConfigUpdated, ConfigKind, PoolHookRevoked, LpGroupMemberAdded};
+use crate::token_extensions::{
+    is_native_mint, mint_symbol, permanent_delegate, screen_mint, transfer_hook_program_id, wrap_sol,
+    MINT_EXT_DEFAULT_FROZEN,
+};
 
 #[derive(Accounts)]
+#[instruction(fee_tier: u64)]
 pub struct InitializePool<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + std::mem::size_of::<AmmPool>()
+        space = 8 + std::mem::size_of::<AmmPool>(),
+        seeds = [
+            AmmPool::SEED,
+            token_a_mint.key().as_ref(),
+            token_b_mint.key().as_ref(),
+            fee_tier.to_le_bytes().as_ref()
+        ],
+        bump
     )]
     pub pool: Account<'info, AmmPool>,
     
+    /// Pool creator; anyone may create a pool and becomes its authority
     #[account(mut)]
     pub authority: Signer<'info>,
     
-    /// Token A mint (e.g., SOL)
-    pub token_a_mint: Account<'info, Mint>,
+    /// Global config holding the pool creation rate limits
+    #[account(
+        mut,
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    
+    /// AMM config offering the fee tiers
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = amm_config.offers_fee_tier(fee_tier) @ AmmError::InvalidFeeTier
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// Transfer Hook Whitelist the pair's hooks must be on
+    #[account(address = amm_config.whitelist @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    /// Payer's pool creation record for the per-payer cooldown
+    #[account(
+        mut,
+        seeds = [PoolCreatorRecord::SEED, authority.key().as_ref()],
+        bump = creator_record.bump
+    )]
+    pub creator_record: Account<'info, PoolCreatorRecord>,
+    
+    /// Token A mint (e.g., SOL); the pair must be in canonical order
+    #[account(
+        constraint = token_a_mint.key() < token_b_mint.key() @ AmmError::InvalidTokenPairOrder,
+        mint::token_program = token_a_program
+    )]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
     
     /// Token B mint (e.g., Token-2022)
-    pub token_b_mint: Account<'info, Mint>,
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+    
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
     
     /// Pool's token A vault
     #[account(
         init,
         payer = authority,
         token::mint = token_a_mint,
-        token::authority = pool,
+        token::authority = vault_authority,
+        token::token_program = token_a_program,
     )]
-    pub token_a_vault: Account<'info, TokenAccount>,
+    pub token_a_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's token B vault
     #[account(
         init,
         payer = authority,
         token::mint = token_b_mint,
-        token::authority = pool,
+        token::authority = vault_authority,
+        token::token_program = token_b_program,
     )]
-    pub token_b_vault: Account<'info, TokenAccount>,
+    pub token_b_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Pool's LP token mint, a fresh keypair created in the handler as a
+    /// Token-2022 mint carrying its own metadata
+    #[account(mut)]
+    pub lp_mint: Signer<'info>,
+    
+    /// CHECK: the AMM config's LP group mint the new LP mint joins; required
+    /// once the config has an LP group
+    #[account(mut, address = amm_config.lp_group_mint @ AmmError::LpGroupMismatch)]
+    pub lp_group_mint: Option<UncheckedAccount<'info>>,
+    
+    /// Token A's freeze authority, required when token A is default-frozen so
+    /// its vault can be thawed
+    pub freeze_authority_a: Option<Signer<'info>>,
+    
+    /// Token B's freeze authority, required when token B is default-frozen so
+    /// its vault can be thawed
+    pub freeze_authority_b: Option<Signer<'info>>,
+    
+    /// CHECK: creator's associated WSOL account, used by
+    /// `initialize_pool_with_sol` (created there if missing)
+    #[account(mut)]
+    pub creator_wsol: Option<UncheckedAccount<'info>>,
+    
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+    
+    /// Token-2022 program owning the LP mint
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    
+    /// Associated Token program creating `creator_wsol`, used by
+    /// `initialize_pool_with_sol`
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLpMetadata<'info> {
+    #[account(
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        has_one = lp_mint @ AmmError::InvalidLpMint
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    /// Pool's LP token mint; only Token-2022 LP mints carry metadata
+    #[account(mut, mint::token_program = token_program)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Token A mint
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Token B mint
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Pays any extra rent the longer metadata needs
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLpGroup<'info> {
+    #[account(
+        mut,
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        has_one = authority @ AmmError::InvalidConfigAuthority,
+        constraint = amm_config.lp_group_mint == Pubkey::default() @ AmmError::LpGroupAlreadyInitialized
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// Protocol authority; pays for the group mint
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// LP group mint, a fresh keypair created in the handler as a Token-2022
+    /// mint carrying the TokenGroup extension
+    #[account(mut)]
+    pub lp_group_mint: Signer<'info>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinLpGroup<'info> {
+    #[account(has_one = lp_mint @ AmmError::InvalidLpMint)]
+    pub pool: Account<'info, AmmPool>,
+    
+    /// AMM config, the LP group's update authority
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = amm_config.lp_group_mint != Pubkey::default() @ AmmError::LpGroupMismatch
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// CHECK: the AMM config's LP group mint; the token program validates it
+    #[account(mut, address = amm_config.lp_group_mint @ AmmError::LpGroupMismatch)]
+    pub lp_group_mint: UncheckedAccount<'info>,
+    
+    /// Pool's LP token mint; only Token-2022 LP mints can join
+    #[account(mut, mint::token_program = token_program)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Pays any extra rent the member extension needs
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidPoolAuthority
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FlagPoolHookRevoked<'info> {
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        constraint = !pool.is_withdraw_only() @ AmmError::PoolWithdrawOnly
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    /// AMM config naming the global whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// Transfer Hook Whitelist the pool's mints are validated against
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    /// Token A mint
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Token B mint
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+    // remaining_accounts: WhitelistEntry PDA of each hook not in the whitelist's own array
+}
+
+#[derive(Accounts)]
+pub struct EnableLaunchGuard<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidPoolAuthority
+    )]
+    pub pool: Account<'info, AmmPool>,
     
-    /// Pool's LP token mint
     #[account(
         init,
         payer = authority,
-        mint::decimals = 6,
-        mint::authority = pool,
+        space = 8 + std::mem::size_of::<LaunchGuard>(),
+        seeds = [LaunchGuard::SEED, pool.key().as_ref()],
+        bump
     )]
-    pub lp_mint: Account<'info, Mint>,
+    pub launch_guard: Account<'info, LaunchGuard>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
     
-    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct UpdatePoolConfig<'info> {
+pub struct AddLaunchAllowlistWallet<'info> {
+    #[account(
+        has_one = authority @ AmmError::InvalidPoolAuthority,
+        has_one = launch_guard @ AmmError::MissingLaunchGuard
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    #[account(mut)]
+    pub launch_guard: Account<'info, LaunchGuard>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnablePrivatePool<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidPoolAuthority
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<TakerAllowlist>(),
+        seeds = [TakerAllowlist::SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub taker_allowlist: Account<'info, TakerAllowlist>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTakerAllowlist<'info> {
+    #[account(
+        has_one = authority @ AmmError::InvalidPoolAuthority,
+        has_one = taker_allowlist @ AmmError::MissingTakerAllowlist
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    #[account(mut)]
+    pub taker_allowlist: Account<'info, TakerAllowlist>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DisablePrivatePool<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidPoolAuthority,
+        has_one = taker_allowlist @ AmmError::MissingTakerAllowlist
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    #[account(mut, close = authority)]
+    pub taker_allowlist: Account<'info, TakerAllowlist>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnablePoolWhitelist<'info> {
     #[account(
         mut,
         has_one = authority @ AmmError::InvalidPoolAuthority
     )]
     pub pool: Account<'info, AmmPool>,
     
+    /// Pool-specific whitelist, managed by the pool authority through the
+    /// regular whitelist instructions
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<TransferHookWhitelist>(),
+        seeds = [AmmPool::POOL_WHITELIST_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub pool_whitelist: Account<'info, TransferHookWhitelist>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DisablePoolWhitelist<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidPoolAuthority,
+        constraint = pool.hook_whitelist == pool_whitelist.key() @ AmmError::MissingPoolWhitelist
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    #[account(mut, close = authority)]
+    pub pool_whitelist: Account<'info, TransferHookWhitelist>,
+    
+    #[account(mut)]
     pub authority: Signer<'info>,
 }
 
-pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+/// Create a pool for a token pair in one of the AMM config's fee tiers. A
+/// pair can have one pool per fee tier. `curve` picks constant product or
+/// weighted (e.g. 80/20 launch pools) pricing for the pool's lifetime.
+/// Create a pool for any canonically ordered mint pair in an offered fee
+/// tier. The pool PDA is keyed by pair and fee tier, so a second pool for the
+/// same pair and tier cannot be created. A mint's Transfer Hook, if any, must
+/// be whitelisted (remaining accounts may carry `WhitelistEntry` PDAs). Each
+/// mint is screened for risky Token-2022 extensions: permanent delegate,
+/// non-transferable and confidential-transfer mints are rejected unless the
+/// AMM config allows them,
+/// default-frozen mints unless their freeze authority co-signs (the vault is
+/// then thawed), and the detected bitmap is stored on the pool for clients.
+/// A pair with a permanent delegate, which could drain the pool, also needs
+/// `acknowledge_permanent_delegate`; the delegates are recorded on the pool.
+pub fn initialize_pool(
+    ctx: Context<InitializePool>,
+    fee_tier: u64,
+    curve: PoolCurve,
+    acknowledge_permanent_delegate: bool,
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let authority = &ctx.accounts.authority;
     
-    // Initialize pool with basic configuration
+    // Screen both mints before anything is recorded
+    let amm_config = &ctx.accounts.amm_config;
+    let whitelist = &ctx.accounts.whitelist;
+    let [mint_extensions_a, mint_extensions_b] = [
+        (&ctx.accounts.token_a_mint, ctx.accounts.freeze_authority_a.as_ref()),
+        (&ctx.accounts.token_b_mint, ctx.accounts.freeze_authority_b.as_ref()),
+    ]
+        .map(|(mint, freeze_authority)| -> Result<u16> {
+            let mint_info = mint.to_account_info();
+            let mint_extensions = screen_mint(&mint_info)?;
+            if let Some(freeze_authority) = freeze_authority {
+                require!(
+                    mint.freeze_authority == COption::Some(freeze_authority.key()),
+                    AmmError::InvalidFreezeAuthority
+                );
+            }
+            amm_config.check_mint_extensions(mint_extensions, freeze_authority.is_some())?;
+            whitelist.validate_mint_hook_with_entries(&whitelist.key(), &mint_info, ctx.remaining_accounts)?;
+            Ok(mint_extensions)
+        });
+    let (mint_extensions_a, mint_extensions_b) = (mint_extensions_a?, mint_extensions_b?);
+    // Once the config has an LP group, every new LP mint joins it
+    let lp_group_mint = ctx.accounts.lp_group_mint.as_ref();
+    require!(
+        lp_group_mint.is_some() == (amm_config.lp_group_mint != Pubkey::default()),
+        AmmError::LpGroupMismatch
+    );
+    let permanent_delegate_a = permanent_delegate(&ctx.accounts.token_a_mint.to_account_info())?;
+    let permanent_delegate_b = permanent_delegate(&ctx.accounts.token_b_mint.to_account_info())?;
+    require!(
+        acknowledge_permanent_delegate || (permanent_delegate_a.is_none() && permanent_delegate_b.is_none()),
+        AmmError::PermanentDelegateNotAcknowledged
+    );
+    
+    // Enforce pool creation rate limits
+    let clock = Clock::get()?;
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.record_pool_creation(clock.slot)?;
+    ctx.accounts.creator_record.record_pool_creation(
+        clock.unix_timestamp,
+        global_config.pool_creation_cooldown,
+    )?;
+    
+    // Initialize pool in the chosen fee tier of the AMM config
+    let fee_rate = fee_tier;
     pool.initialize(
         authority.key(),
         ctx.accounts.token_a_mint.key(),
@@ -76,12 +471,472 @@ pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
         ctx.accounts.token_a_vault.key(),
         ctx.accounts.token_b_vault.key(),
         ctx.accounts.lp_mint.key(),
+        fee_rate,
+        curve,
+        ctx.bumps.pool,
+        ctx.bumps.vault_authority,
     )?;
+    // Recorded for clients; flagged extensions that are allowed stay usable
+    pool.mint_extensions_a = mint_extensions_a;
+    pool.mint_extensions_b = mint_extensions_b;
+    pool.permanent_delegate_a = permanent_delegate_a.unwrap_or_default();
+    pool.permanent_delegate_b = permanent_delegate_b.unwrap_or_default();
+    
+    // Vaults of default-frozen mints start frozen; the co-signing freeze
+    // authority thaws them so they can take deposits
+    if mint_extensions_a & MINT_EXT_DEFAULT_FROZEN != 0 {
+        thaw_vault(
+            &ctx.accounts.token_a_program,
+            &ctx.accounts.token_a_vault,
+            &ctx.accounts.token_a_mint,
+            ctx.accounts.freeze_authority_a.as_ref(),
+        )?;
+    }
+    if mint_extensions_b & MINT_EXT_DEFAULT_FROZEN != 0 {
+        thaw_vault(
+            &ctx.accounts.token_b_program,
+            &ctx.accounts.token_b_vault,
+            &ctx.accounts.token_b_mint,
+            ctx.accounts.freeze_authority_b.as_ref(),
+        )?;
+    }
+    
+    // Name the LP mint after the pair and tier so wallets can display it
+    let lp_token_name = pool.lp_token_name(
+        &mint_symbol(&ctx.accounts.token_a_mint.to_account_info())?,
+        &mint_symbol(&ctx.accounts.token_b_mint.to_account_info())?,
+    );
+    let token_a_mint_key = ctx.accounts.token_a_mint.key();
+    let token_b_mint_key = ctx.accounts.token_b_mint.key();
+    let fee_tier_seed = fee_tier.to_le_bytes();
+    let pool_seeds: &[&[u8]] = &[
+        AmmPool::SEED,
+        token_a_mint_key.as_ref(),
+        token_b_mint_key.as_ref(),
+        fee_tier_seed.as_ref(),
+        &[ctx.bumps.pool],
+    ];
+    create_lp_mint(
+        &authority.to_account_info(),
+        &ctx.accounts.lp_mint.to_account_info(),
+        &pool.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        lp_token_name.clone(),
+        lp_group_mint.is_some(),
+        &[pool_seeds],
+    )?;
+    if let Some(lp_group_mint) = lp_group_mint {
+        let config_seeds: &[&[u8]] = &[AmmConfig::SEED, &[amm_config.bump]];
+        add_lp_group_member(
+            &authority.to_account_info(),
+            &ctx.accounts.lp_mint.to_account_info(),
+            &pool.to_account_info(),
+            &lp_group_mint.to_account_info(),
+            &amm_config.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &[pool_seeds, config_seeds],
+        )?;
+        emit!(LpGroupMemberAdded {
+            pool: pool.key(),
+            lp_mint: ctx.accounts.lp_mint.key(),
+            lp_group_mint: lp_group_mint.key(),
+            timestamp: clock.unix_timestamp,
+        });
+    }
+    
+    emit!(PoolCreated {
+        pool: pool.key(),
+        authority: authority.key(),
+        token_a_mint: ctx.accounts.token_a_mint.key(),
+        token_b_mint: ctx.accounts.token_b_mint.key(),
+        lp_mint: ctx.accounts.lp_mint.key(),
+        fee_rate,
+        permanent_delegate_a,
+        permanent_delegate_b,
+        created_at: clock.unix_timestamp,
+    });
     
     msg!("AMM Pool initialized successfully");
     msg!("Token A: {}", ctx.accounts.token_a_mint.key());
     msg!("Token B: {}", ctx.accounts.token_b_mint.key());
-    msg!("LP Mint: {}", ctx.accounts.lp_mint.key());
+    msg!("LP Mint: {} ({})", ctx.accounts.lp_mint.key(), lp_token_name);
+    msg!("Fee rate: {} bps", fee_rate);
+    msg!("Curve: {:?}", curve);
+    msg!("Mint extensions: A {:#08b}, B {:#08b}", mint_extensions_a, mint_extensions_b);
+    msg!("Vault authority: {}", ctx.accounts.vault_authority.key());
+    
+    Ok(())
+}
+
+/// `initialize_pool` for a pair with a native (wrapped SOL) mint that also
+/// wraps `lamports` of the creator's SOL into their associated WSOL account,
+/// creating it if missing, so the seeding `add_liquidity` in the same
+/// transaction can spend it without separate ATA and sync-native steps.
+pub fn initialize_pool_with_sol(
+    ctx: Context<InitializePool>,
+    fee_tier: u64,
+    curve: PoolCurve,
+    acknowledge_permanent_delegate: bool,
+    lamports: u64,
+) -> Result<()> {
+    require!(lamports > 0, AmmError::InvalidAmount);
+    let accounts = &ctx.accounts;
+    let (native_mint, native_token_program) = if is_native_mint(&accounts.token_a_mint.key()) {
+        (accounts.token_a_mint.to_account_info(), accounts.token_a_program.to_account_info())
+    } else if is_native_mint(&accounts.token_b_mint.key()) {
+        (accounts.token_b_mint.to_account_info(), accounts.token_b_program.to_account_info())
+    } else {
+        return err!(AmmError::NotNativeMint);
+    };
+    let (Some(creator_wsol), Some(associated_token_program)) =
+        (accounts.creator_wsol.as_ref(), accounts.associated_token_program.as_ref())
+    else {
+        return err!(AmmError::InvalidWrappedSolAccount);
+    };
+    require_keys_eq!(
+        creator_wsol.key(),
+        get_associated_token_address_with_program_id(
+            &accounts.authority.key(),
+            native_mint.key,
+            native_token_program.key,
+        ),
+        AmmError::InvalidWrappedSolAccount
+    );
+    
+    wrap_sol(
+        &accounts.authority.to_account_info(),
+        &creator_wsol.to_account_info(),
+        &native_mint,
+        &native_token_program,
+        &associated_token_program.to_account_info(),
+        &accounts.system_program.to_account_info(),
+        lamports,
+    )?;
+    msg!("Wrapped {} lamports into {}", lamports, creator_wsol.key());
+    
+    initialize_pool(ctx, fee_tier, curve, acknowledge_permanent_delegate)
+}
+
+/// Thaw a freshly created vault of a default-frozen mint, signed by the
+/// mint's freeze authority
+fn thaw_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    freeze_authority: Option<&Signer<'info>>,
+) -> Result<()> {
+    let freeze_authority = freeze_authority.ok_or(AmmError::DefaultFrozenMintNotAllowed)?;
+    thaw_account(CpiContext::new(
+        token_program.to_account_info(),
+        ThawAccount {
+            account: vault.to_account_info(),
+            mint: mint.to_account_info(),
+            authority: freeze_authority.to_account_info(),
+        },
+    ))
+}
+
+/// Create `lp_mint` as a Token-2022 mint whose MetadataPointer names itself,
+/// with the pool as mint authority and metadata update authority. With
+/// `group_member` its GroupMemberPointer names itself as well, ready for
+/// `add_lp_group_member`.
+fn create_lp_mint<'info>(
+    payer: &AccountInfo<'info>,
+    lp_mint: &AccountInfo<'info>,
+    pool: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    name: String,
+    group_member: bool,
+    pool_signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let extensions: &[ExtensionType] = if group_member {
+        &[ExtensionType::MetadataPointer, ExtensionType::GroupMemberPointer]
+    } else {
+        &[ExtensionType::MetadataPointer]
+    };
+    let space = ExtensionType::try_calculate_account_len::<MintState>(extensions)?;
+    let metadata = TokenMetadata {
+        name,
+        symbol: AmmPool::LP_TOKEN_SYMBOL.to_string(),
+        ..Default::default()
+    };
+    // The token program grows the mint to hold the metadata, so its rent is
+    // paid up front
+    let lamports = Rent::get()?.minimum_balance(space + metadata.tlv_size_of()?);
+    
+    system_program::create_account(
+        CpiContext::new(
+            system_program.clone(),
+            CreateAccount {
+                from: payer.clone(),
+                to: lp_mint.clone(),
+            },
+        ),
+        lamports,
+        space as u64,
+        &token_2022::ID,
+    )?;
+    invoke(
+        &metadata_pointer::instruction::initialize(
+            &token_2022::ID,
+            lp_mint.key,
+            Some(*pool.key),
+            Some(*lp_mint.key),
+        )?,
+        std::slice::from_ref(lp_mint),
+    )?;
+    if group_member {
+        invoke(
+            &group_member_pointer::instruction::initialize(
+                &token_2022::ID,
+                lp_mint.key,
+                Some(*pool.key),
+                Some(*lp_mint.key),
+            )?,
+            std::slice::from_ref(lp_mint),
+        )?;
+    }
+    initialize_mint2(
+        CpiContext::new(token_program.clone(), InitializeMint2 { mint: lp_mint.clone() }),
+        AmmPool::LP_MINT_DECIMALS,
+        pool.key,
+        None,
+    )?;
+    invoke_signed(
+        &spl_token_metadata_interface::instruction::initialize(
+            &token_2022::ID,
+            lp_mint.key,
+            pool.key,
+            lp_mint.key,
+            pool.key,
+            metadata.name,
+            metadata.symbol,
+            metadata.uri,
+        ),
+        &[lp_mint.clone(), pool.clone()],
+        pool_signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Add `lp_mint` to the AMM's LP token group, topping up the rent the
+/// member extension needs. The pool signs as mint authority and the AMM
+/// config as the group's update authority.
+fn add_lp_group_member<'info>(
+    payer: &AccountInfo<'info>,
+    lp_mint: &AccountInfo<'info>,
+    pool: &AccountInfo<'info>,
+    lp_group_mint: &AccountInfo<'info>,
+    amm_config: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    // The member extension is a 4-byte TLV header plus the member state
+    let required = Rent::get()?.minimum_balance(
+        lp_mint.data_len() + 4 + std::mem::size_of::<TokenGroupMember>(),
+    );
+    let shortfall = required.saturating_sub(lp_mint.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: lp_mint.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    invoke_signed(
+        &spl_token_group_interface::instruction::initialize_member(
+            &token_2022::ID,
+            lp_mint.key,
+            lp_mint.key,
+            pool.key,
+            lp_group_mint.key,
+            amm_config.key,
+        ),
+        &[lp_mint.clone(), pool.clone(), lp_group_mint.clone(), amm_config.clone()],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Create the AMM's LP token group: a Token-2022 mint whose GroupPointer
+/// names itself, with the AMM config as mint and update authority. Pools
+/// created afterwards add their LP mints as members; `max_size` caps the
+/// member count.
+pub fn initialize_lp_group(ctx: Context<InitializeLpGroup>, max_size: u32) -> Result<()> {
+    let config_info = ctx.accounts.amm_config.to_account_info();
+    let lp_group_mint = ctx.accounts.lp_group_mint.to_account_info();
+    
+    let space = ExtensionType::try_calculate_account_len::<MintState>(&[ExtensionType::GroupPointer])?;
+    // The token program grows the mint to hold the group, so its rent is paid
+    // up front
+    let lamports = Rent::get()?.minimum_balance(ExtensionType::try_calculate_account_len::<MintState>(&[
+        ExtensionType::GroupPointer,
+        ExtensionType::TokenGroup,
+    ])?);
+    
+    system_program::create_account(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.authority.to_account_info(),
+                to: lp_group_mint.clone(),
+            },
+        ),
+        lamports,
+        space as u64,
+        &token_2022::ID,
+    )?;
+    invoke(
+        &group_pointer::instruction::initialize(
+            &token_2022::ID,
+            lp_group_mint.key,
+            Some(*config_info.key),
+            Some(*lp_group_mint.key),
+        )?,
+        std::slice::from_ref(&lp_group_mint),
+    )?;
+    initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            InitializeMint2 { mint: lp_group_mint.clone() },
+        ),
+        0,
+        config_info.key,
+        None,
+    )?;
+    let config_seeds: &[&[u8]] = &[AmmConfig::SEED, &[ctx.accounts.amm_config.bump]];
+    invoke_signed(
+        &spl_token_group_interface::instruction::initialize_group(
+            &token_2022::ID,
+            lp_group_mint.key,
+            lp_group_mint.key,
+            config_info.key,
+            Some(*config_info.key),
+            max_size,
+        ),
+        &[lp_group_mint.clone(), config_info.clone()],
+        &[config_seeds],
+    )?;
+    
+    let amm_config = &mut ctx.accounts.amm_config;
+    amm_config.lp_group_mint = lp_group_mint.key();
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::AmmConfig,
+        config: amm_config.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("LP group initialized: {}", lp_group_mint.key());
+    msg!("Max size: {}", max_size);
+    
+    Ok(())
+}
+
+/// Add the LP mint of a pool created before the AMM had an LP group to the
+/// group. Permissionless; the payer covers the member extension's rent.
+pub fn join_lp_group(ctx: Context<JoinLpGroup>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let amm_config = &ctx.accounts.amm_config;
+    
+    let fee_tier_seed = pool.fee_tier.to_le_bytes();
+    let pool_seeds: &[&[u8]] = &[
+        AmmPool::SEED,
+        pool.token_a_mint.as_ref(),
+        pool.token_b_mint.as_ref(),
+        fee_tier_seed.as_ref(),
+        &[pool.bump],
+    ];
+    let config_seeds: &[&[u8]] = &[AmmConfig::SEED, &[amm_config.bump]];
+    add_lp_group_member(
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.lp_mint.to_account_info(),
+        &pool.to_account_info(),
+        &ctx.accounts.lp_group_mint.to_account_info(),
+        &amm_config.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &[pool_seeds, config_seeds],
+    )?;
+    
+    emit!(LpGroupMemberAdded {
+        pool: pool.key(),
+        lp_mint: ctx.accounts.lp_mint.key(),
+        lp_group_mint: ctx.accounts.lp_group_mint.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("LP mint {} joined LP group {}", ctx.accounts.lp_mint.key(), ctx.accounts.lp_group_mint.key());
+    
+    Ok(())
+}
+
+/// Rewrite a Token-2022 LP mint's metadata name from the pair's current
+/// symbols, e.g. after a pool is migrated or a token adopts metadata. Legacy
+/// SPL LP mints carry no metadata and are rejected.
+pub fn update_lp_metadata(ctx: Context<UpdateLpMetadata>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let lp_mint_info = ctx.accounts.lp_mint.to_account_info();
+    
+    let name = pool.lp_token_name(
+        &mint_symbol(&ctx.accounts.token_a_mint.to_account_info())?,
+        &mint_symbol(&ctx.accounts.token_b_mint.to_account_info())?,
+    );
+    
+    // Top up rent for the resized metadata before the token program reallocs
+    let (current_len, new_len) = {
+        let data = lp_mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<MintState>::unpack(&data)?;
+        let metadata = mint
+            .get_variable_len_extension::<TokenMetadata>()
+            .map_err(|_| AmmError::InvalidLpMint)?;
+        let current_size = metadata.tlv_size_of()?;
+        let new_size = TokenMetadata { name: name.clone(), ..metadata }.tlv_size_of()?;
+        (data.len(), (data.len() + new_size).saturating_sub(current_size))
+    };
+    let required = Rent::get()?.minimum_balance(new_len.max(current_len));
+    let shortfall = required.saturating_sub(lp_mint_info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: lp_mint_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    
+    let fee_tier_seed = pool.fee_tier.to_le_bytes();
+    let pool_seeds: &[&[u8]] = &[
+        AmmPool::SEED,
+        pool.token_a_mint.as_ref(),
+        pool.token_b_mint.as_ref(),
+        fee_tier_seed.as_ref(),
+        &[pool.bump],
+    ];
+    invoke_signed(
+        &spl_token_metadata_interface::instruction::update_field(
+            &token_2022::ID,
+            lp_mint_info.key,
+            &pool.key(),
+            Field::Name,
+            name.clone(),
+        ),
+        &[lp_mint_info.clone(), pool.to_account_info()],
+        &[pool_seeds],
+    )?;
+    
+    msg!("LP metadata updated: {}", name);
     
     Ok(())
 }
@@ -95,9 +950,341 @@ pub fn update_pool_config(
     
     pool.update_config(fee_rate, min_liquidity)?;
     
+    emit!(ConfigUpdated {
+        kind: ConfigKind::Pool,
+        config: pool.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
     msg!("Pool configuration updated");
     msg!("Fee rate: {}", fee_rate);
     msg!("Min liquidity: {}", min_liquidity);
     
     Ok(())
-} 
\ No newline at end of file
+}
+
+pub fn set_fee_mode(ctx: Context<UpdatePoolConfig>, fee_mode: FeeMode) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    
+    pool.set_fee_mode(fee_mode)?;
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::Pool,
+        config: pool.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Pool fee mode set to {:?}", fee_mode);
+    
+    Ok(())
+}
+
+/// Hand the pool to a new authority. Handing it to the governance PDA
+/// (`GlobalConfig::governance_authority`) puts its parameters under
+/// governance proposals.
+pub fn set_pool_authority(ctx: Context<UpdatePoolConfig>, new_authority: Pubkey) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    
+    pool.authority = new_authority;
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::Pool,
+        config: pool.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Pool authority set to {}", new_authority);
+    
+    Ok(())
+}
+
+pub fn schedule_fee_ramp(
+    ctx: Context<UpdatePoolConfig>,
+    target_fee_rate: u64,
+    start_time: i64,
+    end_time: i64,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    
+    pool.schedule_fee_ramp(target_fee_rate, start_time, end_time, Clock::get()?.unix_timestamp)?;
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::Pool,
+        config: pool.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Fee ramp scheduled: {} -> {} bps", pool.fee_ramp.start_fee_rate, target_fee_rate);
+    msg!("From {} to {}", start_time, end_time);
+    
+    Ok(())
+}
+
+pub fn set_exit_fee(
+    ctx: Context<UpdatePoolConfig>,
+    exit_fee_bps: u64,
+    decay_period: i64,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    
+    pool.set_exit_fee(exit_fee_bps, decay_period)?;
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::Pool,
+        config: pool.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Exit fee set to {} bps decaying over {}s", exit_fee_bps, decay_period);
+    
+    Ok(())
+}
+
+pub fn set_max_hook_cu(ctx: Context<UpdatePoolConfig>, max_hook_cu: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    
+    pool.set_max_hook_cu(max_hook_cu)?;
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::Pool,
+        config: pool.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Max hook compute units set to {}", max_hook_cu);
+    
+    Ok(())
+}
+
+pub fn set_max_swap_size(ctx: Context<UpdatePoolConfig>, max_swap_bps: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    
+    pool.set_max_swap_size(max_swap_bps)?;
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::Pool,
+        config: pool.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Max swap size set to {} bps of the input reserve", max_swap_bps);
+    
+    Ok(())
+}
+
+pub fn set_dynamic_fee(
+    ctx: Context<UpdatePoolConfig>,
+    min_fee_rate: u64,
+    max_fee_rate: u64,
+    max_volatility_bps: u64,
+    window: u32,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    
+    pool.set_dynamic_fee(min_fee_rate, max_fee_rate, max_volatility_bps, window)?;
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::Pool,
+        config: pool.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    if window == 0 {
+        msg!("Dynamic fees disabled");
+    } else {
+        msg!("Dynamic fee set: {} -> {} bps up to {} bps of volatility", min_fee_rate, max_fee_rate, max_volatility_bps);
+        msg!("Volatility measured over {}s", window);
+    }
+    
+    Ok(())
+}
+
+pub fn set_withdraw_only(ctx: Context<UpdatePoolConfig>, withdraw_only: bool) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    
+    pool.set_withdraw_only(withdraw_only)?;
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::Pool,
+        config: pool.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Pool {} withdraw-only mode", if withdraw_only { "entered" } else { "left" });
+    
+    Ok(())
+}
+
+/// Put a pool into withdraw-only mode once the hook of one of its mints has
+/// been removed from the whitelist. Permissionless; a hook not in the
+/// whitelist's own array only counts as revoked when its `WhitelistEntry`
+/// PDA is passed and holds no entry.
+pub fn flag_pool_hook_revoked(ctx: Context<FlagPoolHookRevoked>) -> Result<()> {
+    let whitelist = &ctx.accounts.whitelist;
+    let whitelist_key = whitelist.key();
+    
+    let mut revoked = None;
+    for mint in [&ctx.accounts.token_a_mint, &ctx.accounts.token_b_mint] {
+        let Some(hook_program_id) = transfer_hook_program_id(&mint.to_account_info())? else {
+            continue;
+        };
+        if whitelist.is_hook_whitelisted(&hook_program_id) {
+            continue;
+        }
+        
+        let entry_address = WhitelistEntry::address(&whitelist_key, &hook_program_id);
+        require!(
+            ctx.remaining_accounts.iter().any(|info| info.key() == entry_address),
+            AmmError::MissingWhitelistEntry
+        );
+        if WhitelistEntry::find_in(ctx.remaining_accounts, &whitelist_key, &hook_program_id).is_none() {
+            revoked = Some((mint.key(), hook_program_id));
+            break;
+        }
+    }
+    let (mint, hook_program_id) = revoked.ok_or(AmmError::HookStillWhitelisted)?;
+    
+    let pool = &mut ctx.accounts.pool;
+    pool.set_withdraw_only(true)?;
+    
+    emit!(PoolHookRevoked {
+        pool: pool.key(),
+        mint,
+        hook_program_id,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Hook {} of mint {} is no longer whitelisted", hook_program_id, mint);
+    msg!("Pool {} entered withdraw-only mode", pool.key());
+    
+    Ok(())
+}
+
+pub fn enable_launch_guard(
+    ctx: Context<EnableLaunchGuard>,
+    max_guarded_lps: u32,
+    guard_duration: i64,
+    allowlist: Vec<Pubkey>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    
+    // The guard only makes sense before the first deposit
+    require!(pool.total_lp_supply == 0, AmmError::PoolAlreadyLaunched);
+    require!(pool.launch_guard == Pubkey::default(), AmmError::InvalidLaunchGuard);
+    require!(guard_duration >= 0, AmmError::InvalidLaunchGuard);
+    require!(allowlist.len() <= MAX_LAUNCH_ALLOWLIST, AmmError::LaunchAllowlistFull);
+    
+    let guarded_until = if guard_duration > 0 {
+        Clock::get()?.unix_timestamp
+            .checked_add(guard_duration)
+            .ok_or(AmmError::MathOverflow)?
+    } else {
+        0
+    };
+    
+    let launch_guard = &mut ctx.accounts.launch_guard;
+    launch_guard.initialize(pool.key(), max_guarded_lps, guarded_until, ctx.bumps.launch_guard)?;
+    for wallet in allowlist {
+        launch_guard.add_wallet(wallet)?;
+    }
+    pool.launch_guard = launch_guard.key();
+    
+    msg!("Launch guard enabled for pool: {}", pool.key());
+    msg!("Guarded LPs: {}", max_guarded_lps);
+    msg!("Guarded until: {}", guarded_until);
+    
+    Ok(())
+}
+
+pub fn add_launch_allowlist_wallet(
+    ctx: Context<AddLaunchAllowlistWallet>,
+    wallet: Pubkey,
+) -> Result<()> {
+    let launch_guard = &mut ctx.accounts.launch_guard;
+    
+    launch_guard.add_wallet(wallet)?;
+    
+    msg!("Added wallet to launch allowlist: {}", wallet);
+    
+    Ok(())
+}
+
+pub fn enable_private_pool(ctx: Context<EnablePrivatePool>, takers: Vec<Pubkey>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    require!(pool.taker_allowlist == Pubkey::default(), AmmError::PoolAlreadyPrivate);
+    
+    let taker_allowlist = &mut ctx.accounts.taker_allowlist;
+    taker_allowlist.initialize(pool.key(), ctx.bumps.taker_allowlist)?;
+    for taker in takers {
+        taker_allowlist.add_taker(taker)?;
+    }
+    pool.taker_allowlist = taker_allowlist.key();
+    
+    msg!("Pool is now private: {}", pool.key());
+    msg!("Allowlisted takers: {}", taker_allowlist.taker_count);
+    
+    Ok(())
+}
+
+pub fn add_pool_taker(ctx: Context<UpdateTakerAllowlist>, taker: Pubkey) -> Result<()> {
+    ctx.accounts.taker_allowlist.add_taker(taker)?;
+    
+    msg!("Added taker to pool allowlist: {}", taker);
+    
+    Ok(())
+}
+
+pub fn remove_pool_taker(ctx: Context<UpdateTakerAllowlist>, taker: Pubkey) -> Result<()> {
+    ctx.accounts.taker_allowlist.remove_taker(&taker)?;
+    
+    msg!("Removed taker from pool allowlist: {}", taker);
+    
+    Ok(())
+}
+
+pub fn disable_private_pool(ctx: Context<DisablePrivatePool>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.taker_allowlist = Pubkey::default();
+    
+    msg!("Pool is now public: {}", pool.key());
+    
+    Ok(())
+}
+
+/// Give the pool its own hook whitelist, seeded with `hooks`. From then on the
+/// pool's mints are validated against this list instead of the global one.
+pub fn enable_pool_whitelist(ctx: Context<EnablePoolWhitelist>, hooks: Vec<Pubkey>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    require!(pool.hook_whitelist == Pubkey::default(), AmmError::PoolWhitelistAlreadySet);
+    
+    let pool_whitelist = &mut ctx.accounts.pool_whitelist;
+    pool_whitelist.initialize(ctx.accounts.authority.key())?;
+    for hook in hooks {
+        pool_whitelist.add_hook(hook)?;
+    }
+    pool.hook_whitelist = pool_whitelist.key();
+    
+    msg!("Pool {} now uses its own whitelist: {}", pool.key(), pool_whitelist.key());
+    msg!("Whitelisted hooks: {}", pool_whitelist.hook_count);
+    
+    Ok(())
+}
+
+/// Drop the pool's own whitelist and fall back to the global one
+pub fn disable_pool_whitelist(ctx: Context<DisablePoolWhitelist>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.hook_whitelist = Pubkey::default();
+    
+    msg!("Pool {} now uses the global whitelist", pool.key());
+    
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::BalanceSnapshot;
+use crate::error::AmmError;
+
+#[derive(Accounts)]
+pub struct SnapshotBalance<'info> {
+    /// Reused if a stale snapshot was left behind, e.g. by a snapshot sent
+    /// outside the transaction meant to assert it; recording overwrites it
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + std::mem::size_of::<BalanceSnapshot>(),
+        seeds = [BalanceSnapshot::SEED, token_account.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub snapshot: Account<'info, BalanceSnapshot>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Token account whose balance change will be asserted
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AssertBalanceChange<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [BalanceSnapshot::SEED, token_account.key().as_ref(), owner.key().as_ref()],
+        bump = snapshot.bump,
+        has_one = owner @ AmmError::InvalidBalanceSnapshot,
+        has_one = token_account @ AmmError::InvalidBalanceSnapshot
+    )]
+    pub snapshot: Account<'info, BalanceSnapshot>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Token account whose balance change is asserted
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub fn snapshot_balance(ctx: Context<SnapshotBalance>) -> Result<()> {
+    let snapshot = &mut ctx.accounts.snapshot;
+    let token_account = &ctx.accounts.token_account;
+
+    snapshot.record(
+        ctx.accounts.owner.key(),
+        token_account.key(),
+        token_account.amount,
+        Clock::get()?.slot,
+        ctx.bumps.snapshot,
+    )?;
+
+    msg!("Balance snapshot recorded for {}: {}", token_account.key(), token_account.amount);
+    Ok(())
+}
+
+pub fn assert_balance_change(
+    ctx: Context<AssertBalanceChange>,
+    min_delta: i64,
+    max_delta: i64,
+) -> Result<()> {
+    let snapshot = &ctx.accounts.snapshot;
+    let token_account = &ctx.accounts.token_account;
+
+    let delta = snapshot.check_delta(
+        token_account.amount,
+        Clock::get()?.slot,
+        min_delta,
+        max_delta,
+    )?;
+
+    msg!("Balance change for {} within bounds: {}", token_account.key(), delta);
+    Ok(())
+}
@@ -0,0 +1,856 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::{
+    AmmConfig, ClmmPool, ClmmPosition, PoolSide, TickArray, TransferHookWhitelist, add_delta,
+    compute_swap_step, lock_clmm_pool, sqrt_price_at_tick, tick_at_sqrt_price, MAX_SQRT_PRICE_X64, MAX_TICK,
+    MIN_SQRT_PRICE_X64, MIN_TICK,
+};
+use crate::hook_interface::transfer_checked_with_hook;
+use crate::error::AmmError;
+use crate::events::{PoolCreated, SwapExecuted, LiquidityAdded, LiquidityRemoved};
+use crate::instructions::trading::enforce_deadline;
+use crate::token_extensions::permanent_delegate;
+
+#[derive(Accounts)]
+#[instruction(fee_tier: u64)]
+pub struct InitializeClmmPool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<ClmmPool>(),
+        seeds = [
+            ClmmPool::SEED,
+            token_a_mint.key().as_ref(),
+            token_b_mint.key().as_ref(),
+            fee_tier.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub pool: Account<'info, ClmmPool>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// AMM config offering the fee tiers
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = amm_config.offers_fee_tier(fee_tier) @ AmmError::InvalidFeeTier
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Token A mint; the pair must be in canonical order
+    #[account(
+        constraint = token_a_mint.key() < token_b_mint.key() @ AmmError::InvalidTokenPairOrder,
+        mint::token_program = token_a_program
+    )]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), ClmmPool::VAULT_AUTHORITY_SEED],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Pool's token A vault
+    #[account(
+        init,
+        payer = authority,
+        token::mint = token_a_mint,
+        token::authority = vault_authority,
+        token::token_program = token_a_program,
+    )]
+    pub token_a_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's token B vault
+    #[account(
+        init,
+        payer = authority,
+        token::mint = token_b_mint,
+        token::authority = vault_authority,
+        token::token_program = token_b_program,
+    )]
+    pub token_b_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(start_tick_index: i32)]
+pub struct InitializeTickArray<'info> {
+    pub pool: Account<'info, ClmmPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<TickArray>(),
+        seeds = [TickArray::SEED, pool.key().as_ref(), start_tick_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_array: Account<'info, TickArray>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct OpenPosition<'info> {
+    pub pool: Account<'info, ClmmPool>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<ClmmPosition>(),
+        seeds = [
+            ClmmPosition::SEED,
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            tick_lower.to_le_bytes().as_ref(),
+            tick_upper.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub position: Account<'info, ClmmPosition>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ModifyClmmLiquidity<'info> {
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault
+    )]
+    pub pool: Account<'info, ClmmPool>,
+
+    #[account(
+        mut,
+        has_one = pool @ AmmError::InvalidLpPosition,
+        has_one = owner @ AmmError::InvalidPositionOwner
+    )]
+    pub position: Account<'info, ClmmPosition>,
+
+    pub owner: Signer<'info>,
+
+    /// CHECK: Tick array holding the position's lower tick; loaded and
+    /// checked against the pool by `TickArray::load`
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Tick array holding the position's upper tick (may be the same
+    /// account as `tick_array_lower`); loaded by `TickArray::load`
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// Owner's token A account
+    #[account(mut)]
+    pub user_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// Owner's token B account
+    #[account(mut)]
+    pub user_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), ClmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Pool's token A vault
+    #[account(mut)]
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's token B vault
+    #[account(mut)]
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    /// AMM config gating deposits and naming the whitelist
+    #[account(seeds = [AmmConfig::SEED], bump = amm_config.bump)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Transfer Hook Whitelist for validation
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClmmSwap<'info> {
+    #[account(
+        mut,
+        constraint = !pool.paused @ AmmError::PoolPaused,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault
+    )]
+    pub pool: Account<'info, ClmmPool>,
+
+    pub user: Signer<'info>,
+
+    /// User's token A account
+    #[account(mut)]
+    pub user_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's token B account
+    #[account(mut)]
+    pub user_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), ClmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Pool's token A vault
+    #[account(mut)]
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's token B vault
+    #[account(mut)]
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    /// AMM config gating trading and naming the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = !amm_config.paused @ AmmError::AmmPaused
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Transfer Hook Whitelist for validation
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct EnableClmmPoolWhitelist<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidPoolAuthority
+    )]
+    pub pool: Account<'info, ClmmPool>,
+
+    /// Pool-specific whitelist, managed by the pool authority through the
+    /// regular whitelist instructions
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<TransferHookWhitelist>(),
+        seeds = [ClmmPool::POOL_WHITELIST_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub pool_whitelist: Account<'info, TransferHookWhitelist>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DisableClmmPoolWhitelist<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidPoolAuthority,
+        constraint = pool.hook_whitelist == pool_whitelist.key() @ AmmError::MissingPoolWhitelist
+    )]
+    pub pool: Account<'info, ClmmPool>,
+
+    #[account(mut, close = authority)]
+    pub pool_whitelist: Account<'info, TransferHookWhitelist>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Create a concentrated liquidity pool in `fee_tier` at the starting price
+/// `initial_sqrt_price_x64` (square root of the token A price in token B, Q64.64)
+pub fn initialize_clmm_pool(ctx: Context<InitializeClmmPool>, fee_tier: u64, initial_sqrt_price_x64: u128) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.initialize(
+        ctx.accounts.authority.key(),
+        ctx.accounts.token_a_mint.key(),
+        ctx.accounts.token_b_mint.key(),
+        ctx.accounts.token_a_vault.key(),
+        ctx.accounts.token_b_vault.key(),
+        fee_tier,
+        initial_sqrt_price_x64,
+        ctx.bumps.pool,
+        ctx.bumps.vault_authority,
+    )?;
+
+    emit!(PoolCreated {
+        pool: pool.key(),
+        authority: pool.authority,
+        token_a_mint: pool.token_a_mint,
+        token_b_mint: pool.token_b_mint,
+        // Positions stand in for LP tokens
+        lp_mint: Pubkey::default(),
+        fee_rate: pool.fee_rate,
+        permanent_delegate_a: permanent_delegate(&ctx.accounts.token_a_mint.to_account_info())?,
+        permanent_delegate_b: permanent_delegate(&ctx.accounts.token_b_mint.to_account_info())?,
+        created_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Concentrated liquidity pool initialized");
+    msg!("Token A: {}", pool.token_a_mint);
+    msg!("Token B: {}", pool.token_b_mint);
+    msg!("Fee tier: {} bps, tick spacing: {}", fee_tier, pool.tick_spacing);
+    msg!("Current tick: {}", pool.tick_current);
+    Ok(())
+}
+
+/// Create the tick array starting at `start_tick_index`. Permissionless:
+/// positions and swaps need the arrays covering the ticks they touch.
+pub fn initialize_tick_array(ctx: Context<InitializeTickArray>, start_tick_index: i32) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    require!(
+        pool.tick_array_start(start_tick_index) == start_tick_index
+            && start_tick_index >= pool.tick_array_start(MIN_TICK)
+            && start_tick_index <= MAX_TICK,
+        AmmError::InvalidTickArray
+    );
+
+    let tick_array = &mut ctx.accounts.tick_array;
+    tick_array.pool = pool.key();
+    tick_array.start_tick_index = start_tick_index;
+
+    msg!("Tick array initialized at tick {}", start_tick_index);
+    Ok(())
+}
+
+/// Open an empty position over [tick_lower, tick_upper); fund it with
+/// `increase_liquidity`
+pub fn open_position(ctx: Context<OpenPosition>, tick_lower: i32, tick_upper: i32) -> Result<()> {
+    ctx.accounts.pool.validate_tick_range(tick_lower, tick_upper)?;
+    ctx.accounts.position.initialize(
+        ctx.accounts.pool.key(),
+        ctx.accounts.owner.key(),
+        tick_lower,
+        tick_upper,
+        ctx.bumps.position,
+    )?;
+
+    msg!("Position opened: [{}, {})", tick_lower, tick_upper);
+    Ok(())
+}
+
+/// Add `liquidity_delta` to a position, paying in the token amounts it needs
+/// at the current price (at most `max_amount_a` / `max_amount_b`)
+pub fn increase_liquidity<'info>(
+    ctx: Context<'_, '_, '_, 'info, ModifyClmmLiquidity<'info>>,
+    liquidity_delta: u128,
+    max_amount_a: u64,
+    max_amount_b: u64,
+    deadline: i64,
+) -> Result<()> {
+    enforce_deadline(deadline)?;
+    require!(!ctx.accounts.amm_config.paused, AmmError::AmmPaused);
+    require!(!ctx.accounts.pool.paused, AmmError::PoolPaused);
+    require!(liquidity_delta > 0, AmmError::InvalidAmount);
+    let delta = i128::try_from(liquidity_delta).map_err(|_| AmmError::LiquidityOverflow)?;
+
+    let (amount_a, amount_b) = modify_liquidity(ctx.accounts, delta)?;
+    require!(
+        amount_a <= max_amount_a && amount_b <= max_amount_b,
+        AmmError::ExcessiveInputAmount
+    );
+
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_clmm_pool(&mut ctx.accounts.pool)?;
+
+    let accounts = &ctx.accounts;
+    let whitelist = &accounts.whitelist;
+    validate_hook(whitelist, &accounts.token_a_mint, ctx.remaining_accounts)?;
+    validate_hook(whitelist, &accounts.token_b_mint, ctx.remaining_accounts)?;
+
+    transfer_in(
+        &accounts.token_a_program,
+        &accounts.user_token_a,
+        &accounts.token_a_mint,
+        &accounts.pool_token_a_vault,
+        &accounts.owner,
+        amount_a,
+        ctx.remaining_accounts,
+    )?;
+    transfer_in(
+        &accounts.token_b_program,
+        &accounts.user_token_b,
+        &accounts.token_b_mint,
+        &accounts.pool_token_b_vault,
+        &accounts.owner,
+        amount_b,
+        ctx.remaining_accounts,
+    )?;
+
+    emit!(LiquidityAdded {
+        pool: accounts.pool.key(),
+        user: accounts.owner.key(),
+        amount_a,
+        amount_b,
+        lp_tokens_minted: 0,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Position liquidity increased by {}", liquidity_delta);
+    msg!("Token A: {}", amount_a);
+    msg!("Token B: {}", amount_b);
+    ctx.accounts.pool.unlock();
+    Ok(())
+}
+
+/// Remove `liquidity_delta` from a position and withdraw the tokens it
+/// backed (at least `min_amount_a` / `min_amount_b`) along with the fees the
+/// position has earned. A zero delta only collects fees.
+pub fn decrease_liquidity<'info>(
+    ctx: Context<'_, '_, '_, 'info, ModifyClmmLiquidity<'info>>,
+    liquidity_delta: u128,
+    min_amount_a: u64,
+    min_amount_b: u64,
+    deadline: i64,
+) -> Result<()> {
+    enforce_deadline(deadline)?;
+    withdraw_position(ctx, liquidity_delta, min_amount_a, min_amount_b)
+}
+
+/// Withdraw the fees a position has earned without changing its liquidity
+pub fn collect_clmm_fees<'info>(ctx: Context<'_, '_, '_, 'info, ModifyClmmLiquidity<'info>>) -> Result<()> {
+    withdraw_position(ctx, 0, 0, 0)
+}
+
+fn withdraw_position<'info>(
+    ctx: Context<'_, '_, '_, 'info, ModifyClmmLiquidity<'info>>,
+    liquidity_delta: u128,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> Result<()> {
+    require!(
+        liquidity_delta <= ctx.accounts.position.liquidity,
+        AmmError::InsufficientPositionLiquidity
+    );
+    let delta = -(liquidity_delta as i128);
+
+    let (amount_a, amount_b) = modify_liquidity(ctx.accounts, delta)?;
+    require!(amount_a >= min_amount_a, AmmError::InsufficientTokenA);
+    require!(amount_b >= min_amount_b, AmmError::InsufficientTokenB);
+
+    let (fees_a, fees_b) = ctx.accounts.position.take_fees();
+    let total_a = amount_a.checked_add(fees_a).ok_or(AmmError::MathOverflow)?;
+    let total_b = amount_b.checked_add(fees_b).ok_or(AmmError::MathOverflow)?;
+
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_clmm_pool(&mut ctx.accounts.pool)?;
+
+    let accounts = &ctx.accounts;
+    let whitelist = &accounts.whitelist;
+    validate_hook(whitelist, &accounts.token_a_mint, ctx.remaining_accounts)?;
+    validate_hook(whitelist, &accounts.token_b_mint, ctx.remaining_accounts)?;
+
+    let pool_key = accounts.pool.key();
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        ClmmPool::VAULT_AUTHORITY_SEED,
+        &[accounts.pool.vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
+
+    transfer_out(
+        &accounts.token_a_program,
+        &accounts.pool_token_a_vault,
+        &accounts.token_a_mint,
+        &accounts.user_token_a,
+        &accounts.vault_authority.to_account_info(),
+        signer_seeds,
+        total_a,
+        ctx.remaining_accounts,
+    )?;
+    transfer_out(
+        &accounts.token_b_program,
+        &accounts.pool_token_b_vault,
+        &accounts.token_b_mint,
+        &accounts.user_token_b,
+        &accounts.vault_authority.to_account_info(),
+        signer_seeds,
+        total_b,
+        ctx.remaining_accounts,
+    )?;
+
+    emit!(LiquidityRemoved {
+        pool: pool_key,
+        user: accounts.owner.key(),
+        amount_a: total_a,
+        amount_b: total_b,
+        lp_tokens_burned: 0,
+        iou_side: None,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Position liquidity decreased by {}", liquidity_delta);
+    msg!("Token A: {} (fees {})", amount_a, fees_a);
+    msg!("Token B: {} (fees {})", amount_b, fees_b);
+    ctx.accounts.pool.unlock();
+    Ok(())
+}
+
+/// Swap `amount_in` of the `input_side` token, walking the ticks of the
+/// first `tick_array_count` remaining accounts (tick arrays in swap order;
+/// any further remaining accounts carry Transfer Hook accounts). The swap
+/// stops early at `sqrt_price_limit_x64`, charging only the input used.
+pub fn clmm_swap<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClmmSwap<'info>>,
+    amount_in: u64,
+    min_amount_out: u64,
+    sqrt_price_limit_x64: Option<u128>,
+    input_side: PoolSide,
+    tick_array_count: u8,
+    deadline: i64,
+) -> Result<()> {
+    enforce_deadline(deadline)?;
+    require!(amount_in > 0, AmmError::InvalidAmount);
+    require!(
+        tick_array_count > 0 && tick_array_count as usize <= ctx.remaining_accounts.len(),
+        AmmError::MissingTickArray
+    );
+    let (tick_array_infos, hook_accounts) = ctx.remaining_accounts.split_at(tick_array_count as usize);
+
+    let a_to_b = input_side == PoolSide::A;
+    let pool_key = ctx.accounts.pool.key();
+    let pool = &mut ctx.accounts.pool;
+
+    // The price moves down when token A comes in and up when token B does
+    let sqrt_price_limit_x64 = sqrt_price_limit_x64.unwrap_or(if a_to_b {
+        MIN_SQRT_PRICE_X64 + 1
+    } else {
+        MAX_SQRT_PRICE_X64 - 1
+    });
+    if a_to_b {
+        require!(
+            sqrt_price_limit_x64 > MIN_SQRT_PRICE_X64 && sqrt_price_limit_x64 < pool.sqrt_price_x64,
+            AmmError::InvalidSqrtPriceLimit
+        );
+    } else {
+        require!(
+            sqrt_price_limit_x64 < MAX_SQRT_PRICE_X64 && sqrt_price_limit_x64 > pool.sqrt_price_x64,
+            AmmError::InvalidSqrtPriceLimit
+        );
+    }
+
+    let mut tick_arrays = tick_array_infos
+        .iter()
+        .map(|info| TickArray::load(info, &pool_key))
+        .collect::<Result<Vec<_>>>()?;
+    let mut modified = vec![false; tick_arrays.len()];
+
+    let mut amount_remaining = amount_in;
+    let mut amount_out: u64 = 0;
+    let mut fee_amount: u64 = 0;
+
+    while amount_remaining > 0 && pool.sqrt_price_x64 != sqrt_price_limit_x64 {
+        // Next tick to stop at: the nearest initialized tick in the covering
+        // array, or that array's edge when it has none
+        let index = tick_arrays
+            .iter()
+            .position(|tick_array| tick_array.contains(pool, pool.tick_current))
+            .ok_or(AmmError::MissingTickArray)?;
+        let tick_array = &tick_arrays[index];
+        let (tick_next, initialized) = match tick_array.next_initialized_tick(pool, pool.tick_current, a_to_b) {
+            Some(tick) => (tick, true),
+            None if a_to_b => (tick_array.start_tick_index, false),
+            None => (tick_array.start_tick_index + pool.tick_array_span(), false),
+        };
+        let tick_next = tick_next.clamp(MIN_TICK, MAX_TICK);
+        let sqrt_price_next_tick = sqrt_price_at_tick(tick_next)?;
+        let sqrt_target = if a_to_b {
+            sqrt_price_next_tick.max(sqrt_price_limit_x64)
+        } else {
+            sqrt_price_next_tick.min(sqrt_price_limit_x64)
+        };
+
+        let step = compute_swap_step(
+            pool.sqrt_price_x64,
+            sqrt_target,
+            pool.liquidity,
+            amount_remaining,
+            pool.fee_rate,
+            a_to_b,
+            true,
+        )?;
+        amount_remaining -= step.amount_in + step.fee_amount;
+        amount_out = amount_out.checked_add(step.amount_out).ok_or(AmmError::MathOverflow)?;
+        fee_amount += step.fee_amount;
+        pool.accrue_fee(step.fee_amount, a_to_b);
+        pool.sqrt_price_x64 = step.sqrt_price_next_x64;
+
+        if step.sqrt_price_next_x64 == sqrt_price_next_tick {
+            // Crossing a tick switches the in-range liquidity
+            if initialized {
+                let liquidity_net = tick_arrays[index].cross_tick(pool, tick_next)?;
+                modified[index] = true;
+                pool.liquidity = add_delta(
+                    pool.liquidity,
+                    if a_to_b { -liquidity_net } else { liquidity_net },
+                )?;
+            }
+            pool.tick_current = if a_to_b { tick_next - 1 } else { tick_next };
+        } else {
+            pool.tick_current = tick_at_sqrt_price(pool.sqrt_price_x64)?;
+        }
+    }
+
+    for ((tick_array, info), modified) in tick_arrays.iter().zip(tick_array_infos).zip(modified) {
+        if modified {
+            tick_array.store(info)?;
+        }
+    }
+
+    let amount_in = amount_in - amount_remaining;
+    require!(amount_out >= min_amount_out, AmmError::InsufficientOutputAmount);
+
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_clmm_pool(pool)?;
+
+    // Orient the pair: tokens enter on `input_side` and leave on the other
+    let accounts = &ctx.accounts;
+    let (user_in, user_out, vault_in, vault_out, mint_in, mint_out, program_in, program_out) = match input_side {
+        PoolSide::A => (
+            &accounts.user_token_a,
+            &accounts.user_token_b,
+            &accounts.pool_token_a_vault,
+            &accounts.pool_token_b_vault,
+            &accounts.token_a_mint,
+            &accounts.token_b_mint,
+            &accounts.token_a_program,
+            &accounts.token_b_program,
+        ),
+        PoolSide::B => (
+            &accounts.user_token_b,
+            &accounts.user_token_a,
+            &accounts.pool_token_b_vault,
+            &accounts.pool_token_a_vault,
+            &accounts.token_b_mint,
+            &accounts.token_a_mint,
+            &accounts.token_b_program,
+            &accounts.token_a_program,
+        ),
+    };
+
+    let whitelist = &accounts.whitelist;
+    validate_hook(whitelist, mint_in, hook_accounts)?;
+    validate_hook(whitelist, mint_out, hook_accounts)?;
+
+    transfer_in(program_in, user_in, mint_in, vault_in, &accounts.user, amount_in, hook_accounts)?;
+
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        ClmmPool::VAULT_AUTHORITY_SEED,
+        &[accounts.pool.vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
+    transfer_out(
+        program_out,
+        vault_out,
+        mint_out,
+        user_out,
+        &accounts.vault_authority.to_account_info(),
+        signer_seeds,
+        amount_out,
+        hook_accounts,
+    )?;
+
+    emit!(SwapExecuted {
+        pool: pool_key,
+        user: accounts.user.key(),
+        input_side,
+        amount_in,
+        amount_out,
+        fee_amount,
+        protocol_fee: 0,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Concentrated liquidity swap executed");
+    msg!("Direction: {:?} -> {:?}", input_side, input_side.opposite());
+    msg!("Amount in: {}", amount_in);
+    msg!("Amount out: {}", amount_out);
+    msg!("Current tick: {}", accounts.pool.tick_current);
+    ctx.accounts.pool.unlock();
+    Ok(())
+}
+
+/// `enable_pool_whitelist` for a concentrated liquidity pool
+pub fn enable_clmm_pool_whitelist(ctx: Context<EnableClmmPoolWhitelist>, hooks: Vec<Pubkey>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    require!(pool.hook_whitelist == Pubkey::default(), AmmError::PoolWhitelistAlreadySet);
+
+    let pool_whitelist = &mut ctx.accounts.pool_whitelist;
+    pool_whitelist.initialize(ctx.accounts.authority.key())?;
+    for hook in hooks {
+        pool_whitelist.add_hook(hook)?;
+    }
+    pool.hook_whitelist = pool_whitelist.key();
+
+    msg!("CLMM pool {} now uses its own whitelist: {}", pool.key(), pool_whitelist.key());
+    msg!("Whitelisted hooks: {}", pool_whitelist.hook_count);
+    Ok(())
+}
+
+/// Drop the pool's own whitelist and fall back to the global one
+pub fn disable_clmm_pool_whitelist(ctx: Context<DisableClmmPoolWhitelist>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.hook_whitelist = Pubkey::default();
+
+    msg!("CLMM pool {} now uses the global whitelist", pool.key());
+    Ok(())
+}
+
+/// Apply `delta` to the position, its edge ticks and the pool. Returns the
+/// token amounts the change is worth: owed by the owner when adding (rounded
+/// up), owed to the owner when removing (rounded down).
+fn modify_liquidity(accounts: &mut ModifyClmmLiquidity, delta: i128) -> Result<(u64, u64)> {
+    let pool_key = accounts.pool.key();
+    let pool = &mut accounts.pool;
+    let position = &mut accounts.position;
+    let (tick_lower, tick_upper) = (position.tick_lower, position.tick_upper);
+
+    let lower_info = accounts.tick_array_lower.to_account_info();
+    let upper_info = accounts.tick_array_upper.to_account_info();
+    let shared_array = lower_info.key == upper_info.key;
+
+    let mut lower_array = TickArray::load(&lower_info, &pool_key)?;
+    let mut upper_array = if shared_array { None } else { Some(TickArray::load(&upper_info, &pool_key)?) };
+
+    let (lower, lower_flipped) = lower_array.update_tick(pool, tick_lower, delta, false)?;
+    let upper_target = upper_array.as_mut().unwrap_or(&mut lower_array);
+    let (upper, upper_flipped) = upper_target.update_tick(pool, tick_upper, delta, true)?;
+
+    let (fee_growth_inside_a, fee_growth_inside_b) = pool.fee_growth_inside(tick_lower, &lower, tick_upper, &upper);
+    position.update(delta, fee_growth_inside_a, fee_growth_inside_b)?;
+
+    // Ticks no position uses any more go back to blank
+    if delta < 0 && lower_flipped {
+        lower_array.clear_tick(pool, tick_lower)?;
+    }
+    if delta < 0 && upper_flipped {
+        upper_array.as_mut().unwrap_or(&mut lower_array).clear_tick(pool, tick_upper)?;
+    }
+
+    lower_array.store(&lower_info)?;
+    if let Some(upper_array) = upper_array {
+        upper_array.store(&upper_info)?;
+    }
+
+    pool.apply_liquidity_delta(tick_lower, tick_upper, delta)?;
+    pool.amounts_for_liquidity(tick_lower, tick_upper, delta.unsigned_abs(), delta > 0)
+}
+
+/// Check a mint's Transfer Hook against the whitelist, directly or through a
+/// `WhitelistEntry` among `hook_accounts`
+fn validate_hook(
+    whitelist: &Account<TransferHookWhitelist>,
+    mint: &InterfaceAccount<Mint>,
+    hook_accounts: &[AccountInfo],
+) -> Result<()> {
+    whitelist.validate_mint_hook_with_entries(&whitelist.key(), &mint.to_account_info(), hook_accounts)?;
+    Ok(())
+}
+
+fn transfer_in<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    authority: &Signer<'info>,
+    amount: u64,
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let transfer_ctx = CpiContext::new(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: authority.to_account_info(),
+        },
+    );
+    transfer_checked_with_hook(transfer_ctx, amount, mint.decimals, hook_accounts)
+}
+
+fn transfer_out<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    vault_authority: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let transfer_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: vault_authority.clone(),
+        },
+        signer_seeds,
+    );
+    transfer_checked_with_hook(transfer_ctx, amount, mint.decimals, hook_accounts)
+}
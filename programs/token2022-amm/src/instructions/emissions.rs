@@ -0,0 +1,153 @@
+// Handlers are compiled only with the `rewards` feature; the account
+// structs stay in every build, see `feature_gated!`
+#![cfg_attr(not(feature = "rewards"), allow(unused_imports))]
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked,
+};
+use crate::state::{AmmConfig, EmissionsController, IncentiveGroup};
+use crate::error::AmmError;
+
+#[derive(Accounts)]
+pub struct InitializeEmissions<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<EmissionsController>(),
+        seeds = [EmissionsController::SEED, reward_mint.key().as_ref()],
+        bump
+    )]
+    pub controller: Account<'info, EmissionsController>,
+
+    /// The controller PDA is keyed only by the reward mint, so only the
+    /// config authority may claim it
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Reward token mint
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// Incentive group gauge whose reward vault receives released emissions
+    #[account(
+        has_one = reward_mint @ AmmError::InvalidVault,
+        constraint = gauge.reward_vault == gauge_vault.key() @ AmmError::InvalidVault
+    )]
+    pub gauge: Account<'info, IncentiveGroup>,
+
+    /// Vault holding the reward supply, owned by the controller
+    #[account(
+        init,
+        payer = authority,
+        token::mint = reward_mint,
+        token::authority = controller,
+        token::token_program = token_program,
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Gauge vault receiving released emissions: the gauge's reward vault
+    #[account(token::mint = reward_mint, token::token_program = token_program)]
+    pub gauge_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseEmissions<'info> {
+    #[account(
+        mut,
+        seeds = [EmissionsController::SEED, reward_mint.key().as_ref()],
+        bump = controller.bump,
+        has_one = reward_mint,
+        has_one = reward_vault,
+        has_one = gauge_vault
+    )]
+    pub controller: Account<'info, EmissionsController>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub gauge_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[cfg(feature = "rewards")]
+pub fn initialize_emissions(
+    ctx: Context<InitializeEmissions>,
+    start_time: i64,
+    epoch_duration: i64,
+    initial_epoch_emission: u64,
+    decay_bps: u64,
+) -> Result<()> {
+    let controller = &mut ctx.accounts.controller;
+
+    controller.initialize(
+        ctx.accounts.authority.key(),
+        ctx.accounts.reward_mint.key(),
+        ctx.accounts.reward_vault.key(),
+        ctx.accounts.gauge_vault.key(),
+        start_time,
+        epoch_duration,
+        initial_epoch_emission,
+        decay_bps,
+        ctx.bumps.controller,
+    )?;
+
+    msg!("Emissions controller initialized for mint: {}", ctx.accounts.reward_mint.key());
+    msg!("Epoch duration: {}s, initial emission: {}, decay: {} bps", epoch_duration, initial_epoch_emission, decay_bps);
+    Ok(())
+}
+
+/// Permissionless crank releasing every completed epoch's emission to the gauge vault
+#[cfg(feature = "rewards")]
+pub fn release_emissions(ctx: Context<ReleaseEmissions>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let (amount, epochs, next_emission) = ctx.accounts.controller.pending_release(now)?;
+
+    require!(epochs > 0, AmmError::NoEmissionsDue);
+    require!(
+        ctx.accounts.reward_vault.amount >= amount,
+        AmmError::InsufficientEmissionsFunding
+    );
+
+    let reward_mint_key = ctx.accounts.reward_mint.key();
+    let controller_seeds: &[&[u8]] = &[
+        EmissionsController::SEED,
+        reward_mint_key.as_ref(),
+        &[ctx.accounts.controller.bump],
+    ];
+    let signer_seeds = &[controller_seeds];
+
+    if amount > 0 {
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.gauge_vault.to_account_info(),
+                authority: ctx.accounts.controller.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked(transfer_ctx, amount, ctx.accounts.reward_mint.decimals)?;
+    }
+
+    let controller = &mut ctx.accounts.controller;
+    controller.apply_release(amount, epochs, next_emission)?;
+
+    msg!("Released {} reward tokens for {} epoch(s)", amount, epochs);
+    msg!("Next epoch: {}, next emission: {}", controller.next_epoch, controller.next_epoch_emission);
+    Ok(())
+}
@@ -0,0 +1,348 @@
+// Handlers are compiled only with the `rewards` feature; the account
+// structs stay in every build, see `feature_gated!`
+#![cfg_attr(not(feature = "rewards"), allow(unused_imports))]
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked,
+};
+use crate::state::{AmmPool, Farm, FarmStake};
+use crate::error::AmmError;
+use crate::hook_interface::transfer_checked_with_hook;
+
+#[derive(Accounts)]
+pub struct CreateFarm<'info> {
+    /// Pool the farm attaches to. The farm PDA is one per (pool, reward mint),
+    /// so only the pool authority may create it.
+    #[account(
+        has_one = lp_mint @ AmmError::InvalidLpMint,
+        has_one = authority @ AmmError::InvalidPoolAuthority
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<Farm>(),
+        seeds = [Farm::SEED, pool.key().as_ref(), reward_mint.key().as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, Farm>,
+
+    /// Pool authority funding the reward stream
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Reward token mint (SPL Token or Token-2022)
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault holding the reward supply, owned by the farm
+    #[account(
+        init,
+        payer = authority,
+        token::mint = reward_mint,
+        token::authority = farm,
+        token::token_program = reward_token_program,
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Creator's reward token account the stream is funded from
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = authority,
+    )]
+    pub funder_reward_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's LP token mint
+    #[account(mint::token_program = lp_token_program)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault holding staked LP tokens, owned by the farm
+    #[account(
+        init,
+        payer = authority,
+        token::mint = lp_mint,
+        token::authority = farm,
+        token::token_program = lp_token_program,
+    )]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program owning the reward mint
+    pub reward_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning the LP mint
+    pub lp_token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: Transfer Hook accounts of the reward mint, if any
+}
+
+#[derive(Accounts)]
+pub struct OpenFarmStake<'info> {
+    pub farm: Account<'info, Farm>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<FarmStake>(),
+        seeds = [FarmStake::SEED, farm.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, FarmStake>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFarmStake<'info> {
+    #[account(
+        mut,
+        has_one = pool,
+        has_one = lp_vault
+    )]
+    pub farm: Account<'info, Farm>,
+
+    #[account(has_one = lp_mint @ AmmError::InvalidLpMint)]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        mut,
+        seeds = [FarmStake::SEED, farm.key().as_ref(), owner.key().as_ref()],
+        bump = stake.bump,
+        has_one = farm,
+        has_one = owner
+    )]
+    pub stake: Account<'info, FarmStake>,
+
+    pub owner: Signer<'info>,
+
+    /// Pool's LP token mint
+    #[account(mint::token_program = lp_token_program)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// Owner's LP token account
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner,
+    )]
+    pub user_lp_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Farm vault holding staked LP tokens
+    #[account(mut)]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program owning the LP mint
+    pub lp_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimFarmRewards<'info> {
+    #[account(
+        mut,
+        has_one = reward_mint,
+        has_one = reward_vault
+    )]
+    pub farm: Account<'info, Farm>,
+
+    #[account(
+        mut,
+        seeds = [FarmStake::SEED, farm.key().as_ref(), owner.key().as_ref()],
+        bump = stake.bump,
+        has_one = farm,
+        has_one = owner
+    )]
+    pub stake: Account<'info, FarmStake>,
+
+    pub owner: Signer<'info>,
+
+    /// Reward token mint
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Owner's reward token account
+    #[account(mut, token::mint = reward_mint)]
+    pub user_reward_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token program owning the reward mint
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    // remaining_accounts: Transfer Hook accounts of the reward mint, if any
+}
+
+/// Create a farm streaming `reward_amount` of the reward mint to the pool's
+/// staked LP tokens between `start_time` and `end_time`
+#[cfg(feature = "rewards")]
+pub fn create_farm<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateFarm<'info>>,
+    start_time: i64,
+    end_time: i64,
+    reward_amount: u64,
+) -> Result<()> {
+    require!(reward_amount > 0, AmmError::InvalidAmount);
+    let now = Clock::get()?.unix_timestamp;
+    require!(start_time >= now, AmmError::InvalidFarmSchedule);
+
+    let farm = &mut ctx.accounts.farm;
+    farm.initialize(
+        ctx.accounts.pool.key(),
+        ctx.accounts.authority.key(),
+        ctx.accounts.reward_mint.key(),
+        ctx.accounts.reward_vault.key(),
+        ctx.accounts.lp_vault.key(),
+        start_time,
+        end_time,
+        ctx.bumps.farm,
+    )?;
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.reward_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.funder_reward_token.to_account_info(),
+            mint: ctx.accounts.reward_mint.to_account_info(),
+            to: ctx.accounts.reward_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+    );
+    transfer_checked_with_hook(transfer_ctx, reward_amount, ctx.accounts.reward_mint.decimals, ctx.remaining_accounts)?;
+
+    // A transfer-fee reward mint delivers less than was sent
+    ctx.accounts.reward_vault.reload()?;
+    farm.fund(ctx.accounts.reward_vault.amount)?;
+
+    msg!("Farm created for pool {}", ctx.accounts.pool.key());
+    msg!("Streaming {}/s of mint {} from {} to {}", farm.reward_rate, farm.reward_mint, start_time, end_time);
+    Ok(())
+}
+
+#[cfg(feature = "rewards")]
+pub fn open_farm_stake(ctx: Context<OpenFarmStake>) -> Result<()> {
+    let farm = &ctx.accounts.farm;
+    let stake = &mut ctx.accounts.stake;
+    stake.initialize(
+        farm.key(),
+        ctx.accounts.owner.key(),
+        farm.reward_per_lp_x64,
+        ctx.bumps.stake,
+    )?;
+
+    msg!("Farm stake opened for farm {}", farm.key());
+    Ok(())
+}
+
+#[cfg(feature = "rewards")]
+pub fn stake_lp(
+    ctx: Context<UpdateFarmStake>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, AmmError::InvalidAmount);
+
+    let farm = &mut ctx.accounts.farm;
+    farm.accrue(Clock::get()?.unix_timestamp)?;
+    let stake = &mut ctx.accounts.stake;
+    stake.settle(farm.reward_per_lp_x64)?;
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.lp_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.user_lp_token.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.lp_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        },
+    );
+    transfer_checked(transfer_ctx, amount, ctx.accounts.lp_mint.decimals)?;
+
+    stake.staked_lp = stake.staked_lp.checked_add(amount).ok_or(AmmError::MathOverflow)?;
+    farm.record_stake(amount)?;
+
+    msg!("Staked {} LP tokens in farm {}", amount, farm.key());
+    Ok(())
+}
+
+#[cfg(feature = "rewards")]
+pub fn unstake_lp(
+    ctx: Context<UpdateFarmStake>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, AmmError::InvalidAmount);
+    require!(amount <= ctx.accounts.stake.staked_lp, AmmError::InsufficientStakedLp);
+
+    let farm = &mut ctx.accounts.farm;
+    farm.accrue(Clock::get()?.unix_timestamp)?;
+    let stake = &mut ctx.accounts.stake;
+    stake.settle(farm.reward_per_lp_x64)?;
+
+    let farm_seeds: &[&[u8]] = &[
+        Farm::SEED,
+        farm.pool.as_ref(),
+        farm.reward_mint.as_ref(),
+        &[farm.bump],
+    ];
+    let signer_seeds = &[farm_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.lp_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.lp_vault.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.user_lp_token.to_account_info(),
+            authority: farm.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer_checked(transfer_ctx, amount, ctx.accounts.lp_mint.decimals)?;
+
+    stake.staked_lp -= amount;
+    farm.record_unstake(amount)?;
+
+    msg!("Unstaked {} LP tokens from farm {}", amount, farm.key());
+    Ok(())
+}
+
+#[cfg(feature = "rewards")]
+pub fn claim_rewards<'info>(ctx: Context<'_, '_, '_, 'info, ClaimFarmRewards<'info>>) -> Result<()> {
+    let farm = &mut ctx.accounts.farm;
+    farm.accrue(Clock::get()?.unix_timestamp)?;
+    let stake = &mut ctx.accounts.stake;
+    stake.settle(farm.reward_per_lp_x64)?;
+
+    let amount = stake.pending_rewards;
+    require!(amount > 0, AmmError::NoRewardsToClaim);
+    require!(
+        ctx.accounts.reward_vault.amount >= amount,
+        AmmError::InsufficientEmissionsFunding
+    );
+
+    let farm_seeds: &[&[u8]] = &[
+        Farm::SEED,
+        farm.pool.as_ref(),
+        farm.reward_mint.as_ref(),
+        &[farm.bump],
+    ];
+    let signer_seeds = &[farm_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.reward_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            mint: ctx.accounts.reward_mint.to_account_info(),
+            to: ctx.accounts.user_reward_token.to_account_info(),
+            authority: farm.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer_checked_with_hook(transfer_ctx, amount, ctx.accounts.reward_mint.decimals, ctx.remaining_accounts)?;
+
+    stake.pending_rewards = 0;
+
+    msg!("Claimed {} reward tokens from farm {}", amount, farm.key());
+    Ok(())
+}
@@ -0,0 +1,359 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalConfig, PoolCreatorRecord, PairOracle, AmmPool, AmmConfig, ClmmPool, PoolSide};
+use crate::error::AmmError;
+use crate::events::{ConfigUpdated, ConfigKind};
+
+#[derive(Accounts)]
+pub struct InitializeGlobalConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<GlobalConfig>(),
+        seeds = [GlobalConfig::SEED],
+        bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolCreationLimits<'info> {
+    #[account(
+        mut,
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GuardianHeartbeat<'info> {
+    #[account(
+        mut,
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(
+        mut,
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Current holder of emergency powers (guardian, or governance PDA once lapsed)
+    pub emergency_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PausePool<'info> {
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(mut)]
+    pub pool: Account<'info, AmmPool>,
+
+    /// Current holder of emergency powers (guardian, or governance PDA once lapsed)
+    pub emergency_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PauseClmmPool<'info> {
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(mut)]
+    pub pool: Account<'info, ClmmPool>,
+
+    /// Current holder of emergency powers (guardian, or governance PDA once lapsed)
+    pub emergency_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGlobalPause<'info> {
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Current holder of emergency powers (guardian, or governance PDA once lapsed)
+    pub emergency_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterPairOracle<'info> {
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<PairOracle>(),
+        seeds = [PairOracle::SEED, token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pair_oracle: Account<'info, PairOracle>,
+
+    /// CHECK: Token A mint of the pair, only used as a seed
+    #[account(constraint = token_a_mint.key() < token_b_mint.key() @ AmmError::InvalidTokenPairOrder)]
+    pub token_a_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Token B mint of the pair, only used as a seed
+    pub token_b_mint: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PublishPairPrice<'info> {
+    #[account(
+        mut,
+        seeds = [PairOracle::SEED, pair_oracle.token_a_mint.as_ref(), pair_oracle.token_b_mint.as_ref()],
+        bump = pair_oracle.bump
+    )]
+    pub pair_oracle: Account<'info, PairOracle>,
+
+    pub oracle: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterPoolCreator<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + std::mem::size_of::<PoolCreatorRecord>(),
+        seeds = [PoolCreatorRecord::SEED, creator.key().as_ref()],
+        bump
+    )]
+    pub creator_record: Account<'info, PoolCreatorRecord>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_global_config(
+    ctx: Context<InitializeGlobalConfig>,
+    pool_creation_cooldown: i64,
+    max_pools_per_slot: u32,
+) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.initialize(
+        ctx.accounts.authority.key(),
+        pool_creation_cooldown,
+        max_pools_per_slot,
+        ctx.bumps.global_config,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    msg!("Global config initialized with authority: {}", ctx.accounts.authority.key());
+    Ok(())
+}
+
+pub fn update_pool_creation_limits(
+    ctx: Context<UpdatePoolCreationLimits>,
+    pool_creation_cooldown: i64,
+    max_pools_per_slot: u32,
+) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.set_pool_creation_limits(pool_creation_cooldown, max_pools_per_slot)?;
+
+    emit!(ConfigUpdated {
+        kind: ConfigKind::GlobalConfig,
+        config: global_config.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Pool creation limits updated");
+    msg!("Cooldown: {}s", pool_creation_cooldown);
+    msg!("Max pools per slot: {}", max_pools_per_slot);
+    Ok(())
+}
+
+pub fn guardian_heartbeat(ctx: Context<GuardianHeartbeat>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.record_heartbeat(&ctx.accounts.guardian.key(), now)?;
+
+    msg!("Guardian heartbeat recorded at {}", now);
+    Ok(())
+}
+
+pub fn set_guardian(
+    ctx: Context<SetGuardian>,
+    guardian: Pubkey,
+    heartbeat_timeout: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.require_emergency_authority(&ctx.accounts.emergency_authority.key(), now)?;
+    global_config.set_guardian(guardian, heartbeat_timeout, now)?;
+
+    msg!("Guardian set to {}", guardian);
+    msg!("Heartbeat timeout: {}s", heartbeat_timeout);
+    Ok(())
+}
+
+/// Circuit breaker for one pool: block swaps and deposits while leaving
+/// withdrawals open, e.g. when a whitelisted hook turns malicious
+pub fn pause_pool(ctx: Context<PausePool>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.global_config.require_emergency_authority(&ctx.accounts.emergency_authority.key(), now)?;
+    ctx.accounts.pool.set_paused(true)?;
+
+    msg!("Pool paused: {}", ctx.accounts.pool.key());
+    Ok(())
+}
+
+pub fn unpause_pool(ctx: Context<PausePool>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.global_config.require_emergency_authority(&ctx.accounts.emergency_authority.key(), now)?;
+    ctx.accounts.pool.set_paused(false)?;
+
+    msg!("Pool unpaused: {}", ctx.accounts.pool.key());
+    Ok(())
+}
+
+/// Mark a pool mint's hook as blocking transfers (paused or otherwise
+/// failing), letting LPs exit through `remove_liquidity_single_leg`
+pub fn set_pool_hook_blocked(ctx: Context<PausePool>, side: PoolSide, blocked: bool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.global_config.require_emergency_authority(&ctx.accounts.emergency_authority.key(), now)?;
+    ctx.accounts.pool.set_hook_blocked(side, blocked)?;
+
+    msg!("Pool {} hook of side {:?} {}", ctx.accounts.pool.key(), side, if blocked { "blocked" } else { "unblocked" });
+    Ok(())
+}
+
+/// `pause_pool` for a concentrated liquidity pool
+pub fn pause_clmm_pool(ctx: Context<PauseClmmPool>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.global_config.require_emergency_authority(&ctx.accounts.emergency_authority.key(), now)?;
+    ctx.accounts.pool.paused = true;
+
+    msg!("CLMM pool paused: {}", ctx.accounts.pool.key());
+    Ok(())
+}
+
+pub fn unpause_clmm_pool(ctx: Context<PauseClmmPool>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.global_config.require_emergency_authority(&ctx.accounts.emergency_authority.key(), now)?;
+    ctx.accounts.pool.paused = false;
+
+    msg!("CLMM pool unpaused: {}", ctx.accounts.pool.key());
+    Ok(())
+}
+
+/// Circuit breaker for every pool at once
+pub fn set_global_pause(ctx: Context<SetGlobalPause>, paused: bool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.global_config.require_emergency_authority(&ctx.accounts.emergency_authority.key(), now)?;
+    ctx.accounts.amm_config.paused = paused;
+
+    msg!("Global pause: {}", paused);
+    Ok(())
+}
+
+pub fn set_oracle_admission(
+    ctx: Context<UpdatePoolCreationLimits>,
+    band_bps: u64,
+    max_staleness: i64,
+) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.set_oracle_admission(band_bps, max_staleness)?;
+
+    emit!(ConfigUpdated {
+        kind: ConfigKind::GlobalConfig,
+        config: global_config.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Oracle admission band: {} bps", band_bps);
+    msg!("Oracle max staleness: {}s", max_staleness);
+    Ok(())
+}
+
+pub fn set_protocol_fee_authority(
+    ctx: Context<UpdatePoolCreationLimits>,
+    protocol_fee_authority: Pubkey,
+) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.protocol_fee_authority = protocol_fee_authority;
+
+    emit!(ConfigUpdated {
+        kind: ConfigKind::GlobalConfig,
+        config: global_config.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Protocol fee authority set to {}", protocol_fee_authority);
+    Ok(())
+}
+
+pub fn register_pair_oracle(ctx: Context<RegisterPairOracle>, oracle: Pubkey) -> Result<()> {
+    let pair_oracle = &mut ctx.accounts.pair_oracle;
+    pair_oracle.initialize(
+        ctx.accounts.token_a_mint.key(),
+        ctx.accounts.token_b_mint.key(),
+        oracle,
+        ctx.bumps.pair_oracle,
+    )?;
+
+    msg!("Pair oracle registered with signer: {}", oracle);
+    Ok(())
+}
+
+pub fn publish_pair_price(ctx: Context<PublishPairPrice>, price_x64: u128) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pair_oracle = &mut ctx.accounts.pair_oracle;
+    pair_oracle.publish(&ctx.accounts.oracle.key(), price_x64, now)?;
+
+    msg!("Pair price published at {}", now);
+    Ok(())
+}
+
+pub fn register_pool_creator(ctx: Context<RegisterPoolCreator>) -> Result<()> {
+    let creator_record = &mut ctx.accounts.creator_record;
+    creator_record.initialize(ctx.accounts.creator.key(), ctx.bumps.creator_record)?;
+
+    msg!("Pool creator registered: {}", ctx.accounts.creator.key());
+    Ok(())
+}
@@ -1,50 +1,301 @@
+// Handlers are compiled only with the `governance` feature; the account
+// structs stay in every build, see `feature_gated!`
+#![cfg_attr(not(feature = "governance"), allow(unused_imports))]
+
 use anchor_lang::prelude::*;
-use crate::state::{TransferHookWhitelist, HookProposal};
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::{TransferHookWhitelist, Proposal, ProposalAction, VoteRecord, AmmConfig, AmmPool, GlobalConfig, GovernanceConfig};
 use crate::error::AmmError;
+use crate::events::{ProposalCreated, VoteCast, ProposalFinalized, HookWhitelisted, ConfigUpdated, ConfigKind};
 
 #[derive(Accounts)]
 pub struct CreateHookProposal<'info> {
     #[account(
         init,
         payer = proposer,
-        space = 8 + std::mem::size_of::<HookProposal>()
+        space = 8 + std::mem::size_of::<Proposal>()
     )]
-    pub proposal: Account<'info, HookProposal>,
+    pub proposal: Account<'info, Proposal>,
     
     #[account(mut)]
     pub proposer: Signer<'info>,
     
     pub whitelist: Account<'info, TransferHookWhitelist>,
     
+    /// Proposal that must execute before this one, if any
+    pub dependency: Option<Account<'info, Proposal>>,
+    
+    /// Governance rules proposals are created and finalized under
+    #[account(
+        seeds = [GovernanceConfig::SEED],
+        bump = governance_config.bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// Vault escrowing the proposer stake and every vote on this proposal
+    #[account(
+        mut,
+        seeds = [Proposal::STAKE_VAULT_SEED, proposal.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: SystemAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + std::mem::size_of::<Proposal>()
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    /// Proposal that must execute before this one, if any
+    pub dependency: Option<Account<'info, Proposal>>,
+    
+    /// Governance rules proposals are created and finalized under
+    #[account(
+        seeds = [GovernanceConfig::SEED],
+        bump = governance_config.bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// Vault escrowing the proposer stake and every vote on this proposal
+    #[account(
+        mut,
+        seeds = [Proposal::STAKE_VAULT_SEED, proposal.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: SystemAccount<'info>,
+    
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct VoteOnProposal<'info> {
     #[account(mut)]
-    pub proposal: Account<'info, HookProposal>,
+    pub proposal: Account<'info, Proposal>,
     
     #[account(mut)]
     pub voter: Signer<'info>,
     
     pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    /// The voter's vote; creating it fails if they already voted
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + std::mem::size_of::<VoteRecord>(),
+        seeds = [proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    /// Vault escrowing every voter's stake on this proposal
+    #[account(
+        mut,
+        seeds = [Proposal::STAKE_VAULT_SEED, proposal.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: SystemAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeProposal<'info> {
+    /// Proposal whose voting period has ended; anyone may finalize it
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Governance rules proposals are created and finalized under
+    #[account(
+        seeds = [GovernanceConfig::SEED],
+        bump = governance_config.bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+}
+
+#[derive(Accounts)]
+pub struct VetoProposal<'info> {
+    /// Approved proposal still inside its veto window. Council members sign
+    /// as remaining accounts.
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        seeds = [GovernanceConfig::SEED],
+        bump = governance_config.bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [proposal.key().as_ref(), voter.key().as_ref()],
+        bump = vote_record.bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    #[account(
+        mut,
+        seeds = [Proposal::STAKE_VAULT_SEED, proposal.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct ExecuteProposal<'info> {
+    /// Approved hook proposal; anyone may execute it
     #[account(
         mut,
         constraint = proposal.is_executable() @ AmmError::ProposalNotExecutable
     )]
-    pub proposal: Account<'info, HookProposal>,
+    pub proposal: Account<'info, Proposal>,
+    
+    /// AMM config naming the whitelist governance adds hooks to
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
     
     #[account(
         mut,
-        has_one = authority @ AmmError::InvalidWhitelistAuthority
+        address = amm_config.whitelist @ AmmError::WhitelistMismatch
     )]
     pub whitelist: Account<'info, TransferHookWhitelist>,
     
+    /// The proposal's declared dependency, required when it has one
+    pub dependency: Option<Account<'info, Proposal>>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigProposal<'info> {
+    /// Approved config proposal; anyone may execute it
+    #[account(
+        mut,
+        constraint = proposal.is_executable() @ AmmError::ProposalNotExecutable
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [GovernanceConfig::SEED],
+        bump = governance_config.bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The proposal's declared dependency, required when it has one
+    pub dependency: Option<Account<'info, Proposal>>,
+}
+
+#[derive(Accounts)]
+pub struct ExecutePoolProposal<'info> {
+    /// Approved pool proposal; anyone may execute it
+    #[account(
+        mut,
+        constraint = proposal.is_executable() @ AmmError::ProposalNotExecutable,
+        constraint = proposal.action.pool() == Some(pool.key()) @ AmmError::InvalidProposalKind
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(mut)]
+    pub pool: Account<'info, AmmPool>,
+    
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    
+    /// Protocol fee vault for token A; required to set a protocol fee share
+    #[account(
+        seeds = [AmmPool::PROTOCOL_FEE_VAULT_SEED, pool.key().as_ref(), pool.token_a_mint.as_ref()],
+        bump
+    )]
+    pub protocol_fee_vault_a: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Protocol fee vault for token B; required to set a protocol fee share
+    #[account(
+        seeds = [AmmPool::PROTOCOL_FEE_VAULT_SEED, pool.key().as_ref(), pool.token_b_mint.as_ref()],
+        bump
+    )]
+    pub protocol_fee_vault_b: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// The proposal's declared dependency, required when it has one
+    pub dependency: Option<Account<'info, Proposal>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGovernanceConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<GovernanceConfig>(),
+        seeds = [GovernanceConfig::SEED],
+        bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    #[account(mut)]
     pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleProposerStake<'info> {
+    #[account(mut, has_one = proposer @ AmmError::InvalidProposalProposer)]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [Proposal::STAKE_VAULT_SEED, proposal.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: SystemAccount<'info>,
+    
+    /// CHECK: Proposer the stake is refunded to; bound by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    
+    /// AMM config holding the treasury and slash rate
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// CHECK: Receives slashed stake; must be the config's treasury, required
+    /// only when a share is slashed
+    #[account(mut, address = amm_config.treasury @ AmmError::InvalidTreasury)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -53,11 +304,12 @@ pub struct CancelProposal<'info> {
         mut,
         has_one = proposer @ AmmError::InvalidProposalProposer
     )]
-    pub proposal: Account<'info, HookProposal>,
+    pub proposal: Account<'info, Proposal>,
     
     pub proposer: Signer<'info>,
 }
 
+#[cfg(feature = "governance")]
 pub fn create_hook_proposal(
     ctx: Context<CreateHookProposal>,
     hook_program_id: Pubkey,
@@ -67,6 +319,7 @@ pub fn create_hook_proposal(
 ) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
     let proposer = &ctx.accounts.proposer;
+    let depends_on = ctx.accounts.dependency.as_ref().map(|dependency| dependency.key());
     
     proposal.initialize(
         proposer.key(),
@@ -75,15 +328,102 @@ pub fn create_hook_proposal(
         audit_report_url.clone(),
         proposer_stake,
         Clock::get()?.unix_timestamp,
+        depends_on,
+        ProposalAction::AddHook,
+        &ctx.accounts.governance_config.params,
+    )?;
+    
+    // The proposer stake is escrowed until the proposal is settled
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: proposer.to_account_info(),
+                to: ctx.accounts.stake_vault.to_account_info(),
+            },
+        ),
+        proposer_stake,
     )?;
     
+    emit!(ProposalCreated {
+        proposal: proposal.key(),
+        proposer: proposer.key(),
+        hook_program_id,
+        action: ProposalAction::AddHook,
+        proposer_stake,
+        voting_deadline: proposal.voting_deadline,
+    });
+    
     msg!("Hook proposal created: {}", hook_program_id);
     msg!("Description: {}", description);
-    msg!("Stake: {} SOL", proposer_stake as f64 / 1e9);
+    msg!("Stake: {} lamports", proposer_stake);
+    if let Some(depends_on) = depends_on {
+        msg!("Depends on proposal: {}", depends_on);
+    }
     
     Ok(())
 }
 
+/// Propose a governance or pool action. The proposal is voted on like a hook
+/// proposal and, once approved, carried out by `execute_config_proposal` or
+/// `execute_pool_proposal`. Hooks are proposed through `create_hook_proposal`.
+#[cfg(feature = "governance")]
+pub fn create_proposal(
+    ctx: Context<CreateProposal>,
+    action: ProposalAction,
+    description: String,
+    proposer_stake: u64,
+) -> Result<()> {
+    require!(action != ProposalAction::AddHook, AmmError::InvalidProposalKind);
+    
+    let proposal = &mut ctx.accounts.proposal;
+    let proposer = &ctx.accounts.proposer;
+    let depends_on = ctx.accounts.dependency.as_ref().map(|dependency| dependency.key());
+    
+    proposal.initialize(
+        proposer.key(),
+        Pubkey::default(),
+        description.clone(),
+        String::new(),
+        proposer_stake,
+        Clock::get()?.unix_timestamp,
+        depends_on,
+        action,
+        &ctx.accounts.governance_config.params,
+    )?;
+    
+    // The proposer stake is escrowed until the proposal is settled
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: proposer.to_account_info(),
+                to: ctx.accounts.stake_vault.to_account_info(),
+            },
+        ),
+        proposer_stake,
+    )?;
+    
+    emit!(ProposalCreated {
+        proposal: proposal.key(),
+        proposer: proposer.key(),
+        hook_program_id: Pubkey::default(),
+        action,
+        proposer_stake,
+        voting_deadline: proposal.voting_deadline,
+    });
+    
+    msg!("Proposal created: {:?}", action);
+    msg!("Description: {}", description);
+    msg!("Stake: {} lamports", proposer_stake);
+    if let Some(depends_on) = depends_on {
+        msg!("Depends on proposal: {}", depends_on);
+    }
+    
+    Ok(())
+}
+
+#[cfg(feature = "governance")]
 pub fn vote_on_proposal(
     ctx: Context<VoteOnProposal>,
     vote: bool, // true for approve, false for reject
@@ -92,32 +432,323 @@ pub fn vote_on_proposal(
     let proposal = &mut ctx.accounts.proposal;
     let voter = &ctx.accounts.voter;
     
-    proposal.add_vote(voter.key(), vote, stake_amount)?;
+    proposal.add_vote(vote, stake_amount)?;
+    ctx.accounts.vote_record.initialize(
+        proposal.key(),
+        voter.key(),
+        vote,
+        stake_amount,
+        Clock::get()?.unix_timestamp,
+        ctx.bumps.vote_record,
+    )?;
+    
+    // Votes are weighted by stake actually escrowed for the proposal
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: voter.to_account_info(),
+                to: ctx.accounts.stake_vault.to_account_info(),
+            },
+        ),
+        stake_amount,
+    )?;
+    
+    emit!(VoteCast {
+        proposal: proposal.key(),
+        voter: voter.key(),
+        approve: vote,
+        stake_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     
     let vote_type = if vote { "APPROVE" } else { "REJECT" };
-    msg!("Vote recorded: {} with {} SOL stake", vote_type, stake_amount as f64 / 1e9);
+    msg!("Vote recorded: {} with {} lamports stake", vote_type, stake_amount);
     
     Ok(())
 }
 
+#[cfg(feature = "governance")]
 pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
-    let proposal = &ctx.accounts.proposal;
+    let proposal = &mut ctx.accounts.proposal;
     let whitelist = &mut ctx.accounts.whitelist;
     
-    // Check if proposal passed
+    // Quorum and threshold were applied at finalization; config proposals
+    // execute through `execute_config_proposal`
     require!(
-        proposal.is_approved(),
-        AmmError::ProposalNotApproved
+        proposal.action == ProposalAction::AddHook,
+        AmmError::InvalidProposalKind
     );
     
+    // Dependencies must execute first
+    proposal.check_dependency(ctx.accounts.dependency.as_ref())?;
+    
     // Add hook to whitelist
     whitelist.add_hook(proposal.hook_program_id)?;
+    proposal.mark_executed()?;
+    
+    emit!(HookWhitelisted {
+        whitelist: whitelist.key(),
+        hook_program_id: proposal.hook_program_id,
+        whitelisted: true,
+        proposal: Some(proposal.key()),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     
     msg!("Proposal executed: Hook {} added to whitelist", proposal.hook_program_id);
     
     Ok(())
 }
 
+/// Close voting once the deadline has passed. The proposal is approved when
+/// escrowed stake reaches quorum and the approval threshold, rejected otherwise.
+#[cfg(feature = "governance")]
+pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.finalize(&ctx.accounts.governance_config.params)?;
+    
+    let (approve_stake, reject_stake, vote_count) = proposal.get_vote_summary();
+    let approved = proposal.is_executable();
+    emit!(ProposalFinalized {
+        proposal: proposal.key(),
+        hook_program_id: proposal.hook_program_id,
+        approved,
+        total_approve_stake: approve_stake,
+        total_reject_stake: reject_stake,
+        vote_count,
+        finalized_at: Clock::get()?.unix_timestamp,
+    });
+    
+    let outcome = if approved { "APPROVED" } else { "REJECTED" };
+    msg!("Proposal finalized: {}", outcome);
+    msg!("Approve: {} lamports, reject: {} lamports, votes: {}", approve_stake, reject_stake, vote_count);
+    
+    Ok(())
+}
+
+/// Return a voter's escrowed stake once the proposal is no longer active
+#[cfg(feature = "governance")]
+pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+    let proposal_key = ctx.accounts.proposal.key();
+    let amount = ctx.accounts.vote_record.release_stake(&ctx.accounts.proposal)?;
+    
+    let stake_vault_seeds: &[&[u8]] = &[
+        Proposal::STAKE_VAULT_SEED,
+        proposal_key.as_ref(),
+        &[ctx.bumps.stake_vault],
+    ];
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                to: ctx.accounts.voter.to_account_info(),
+            },
+            &[stake_vault_seeds],
+        ),
+        amount,
+    )?;
+    
+    msg!("Withdrew {} lamports of stake", amount);
+    
+    Ok(())
+}
+
+/// Refund the proposer stake of a passed proposal, or slash the configured
+/// share of it to the treasury for a rejected or cancelled one
+#[cfg(feature = "governance")]
+pub fn settle_proposer_stake(ctx: Context<SettleProposerStake>) -> Result<()> {
+    let proposal_key = ctx.accounts.proposal.key();
+    let stake = ctx.accounts.proposal.proposer_stake;
+    let passed = ctx.accounts.proposal.settle_proposer_stake()?;
+    
+    let slashed = if passed {
+        0
+    } else {
+        ctx.accounts.amm_config.calculate_proposal_slash(stake)?
+    };
+    let refund = stake - slashed;
+    
+    let mut payouts = vec![(ctx.accounts.proposer.to_account_info(), refund)];
+    if slashed > 0 {
+        let treasury = ctx.accounts.treasury.as_ref().ok_or(AmmError::InvalidTreasury)?;
+        payouts.push((treasury.to_account_info(), slashed));
+    }
+    
+    let stake_vault_seeds: &[&[u8]] = &[
+        Proposal::STAKE_VAULT_SEED,
+        proposal_key.as_ref(),
+        &[ctx.bumps.stake_vault],
+    ];
+    for (recipient, amount) in payouts {
+        if amount == 0 {
+            continue;
+        }
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    to: recipient,
+                },
+                &[stake_vault_seeds],
+            ),
+            amount,
+        )?;
+    }
+    
+    msg!("Proposer stake settled: {} lamports refunded, {} lamports slashed", refund, slashed);
+    
+    Ok(())
+}
+
+/// Block an approved proposal before it can be executed. Needs signatures
+/// from `veto_threshold` security council members, passed as remaining
+/// accounts, while the proposal is inside its veto window.
+#[cfg(feature = "governance")]
+pub fn veto_proposal(ctx: Context<VetoProposal>) -> Result<()> {
+    let signers: Vec<Pubkey> = ctx
+        .remaining_accounts
+        .iter()
+        .filter(|info| info.is_signer)
+        .map(|info| info.key())
+        .collect();
+    ctx.accounts.governance_config.require_council_veto(&signers)?;
+    
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.veto(Clock::get()?.unix_timestamp)?;
+    
+    msg!("Proposal vetoed by the security council: {}", proposal.key());
+    
+    Ok(())
+}
+
+/// Adopt the governance rules or security council of an approved config proposal
+#[cfg(feature = "governance")]
+pub fn execute_config_proposal(ctx: Context<ExecuteConfigProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let governance_config = &mut ctx.accounts.governance_config;
+    
+    // Dependencies must execute first
+    proposal.check_dependency(ctx.accounts.dependency.as_ref())?;
+    
+    match proposal.action {
+        ProposalAction::UpdateGovernanceConfig { params } => {
+            governance_config.apply(params)?;
+            msg!("Governance config updated by proposal {}", proposal.key());
+            msg!("Voting period: {}s, quorum: {} lamports", params.voting_period, params.quorum_stake);
+            msg!("Approval threshold: {} bps, veto window: {}s", params.approval_threshold_bps, params.veto_window);
+        }
+        ProposalAction::SetSecurityCouncil { members, size, veto_threshold } => {
+            governance_config.set_security_council(&members[..size as usize], veto_threshold)?;
+            msg!("Security council replaced by proposal {}", proposal.key());
+            msg!("Members: {}, veto threshold: {}", size, veto_threshold);
+        }
+        _ => return Err(AmmError::InvalidProposalKind.into()),
+    }
+    proposal.mark_executed()?;
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::GovernanceConfig,
+        config: governance_config.key(),
+        updated_by: proposal.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Carry out an approved pool proposal. Governance only acts where the
+/// governance PDA holds the role: pool authority for fee, pause, withdraw-only
+/// and authority changes (or emergency powers for a pause), protocol fee
+/// authority for the protocol fee share.
+#[cfg(feature = "governance")]
+pub fn execute_pool_proposal(ctx: Context<ExecutePoolProposal>) -> Result<()> {
+    let governance = GlobalConfig::governance_authority();
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    let pool = &mut ctx.accounts.pool;
+    let governs_pool = pool.authority == governance;
+    
+    // Dependencies must execute first
+    proposal.check_dependency(ctx.accounts.dependency.as_ref())?;
+    
+    match proposal.action {
+        ProposalAction::SetFeeRate { fee_rate, .. } => {
+            require!(governs_pool, AmmError::InvalidGovernanceRole);
+            let min_liquidity = pool.min_liquidity;
+            pool.update_config(fee_rate, min_liquidity)?;
+            msg!("Fee rate set to {} bps", fee_rate);
+        }
+        ProposalAction::SetProtocolFeeShare { share_bps, .. } => {
+            require_keys_eq!(
+                ctx.accounts.global_config.protocol_fee_authority,
+                governance,
+                AmmError::InvalidGovernanceRole
+            );
+            require!(
+                ctx.accounts.protocol_fee_vault_a.is_some() && ctx.accounts.protocol_fee_vault_b.is_some(),
+                AmmError::MissingProtocolFeeVault
+            );
+            pool.set_protocol_fee_share(share_bps)?;
+            msg!("Protocol fee share set to {} bps of the swap fee", share_bps);
+        }
+        ProposalAction::SetPoolPaused { paused, .. } => {
+            require!(
+                governs_pool || ctx.accounts.global_config.emergency_authority(now) == governance,
+                AmmError::InvalidGovernanceRole
+            );
+            pool.set_paused(paused)?;
+            msg!("Pool {}", if paused { "paused" } else { "unpaused" });
+        }
+        ProposalAction::SetPoolWithdrawOnly { withdraw_only, .. } => {
+            require!(governs_pool, AmmError::InvalidGovernanceRole);
+            pool.set_withdraw_only(withdraw_only)?;
+            msg!("Pool {} withdraw-only mode", if withdraw_only { "entered" } else { "left" });
+        }
+        ProposalAction::RotatePoolAuthority { new_authority, .. } => {
+            require!(governs_pool, AmmError::InvalidGovernanceRole);
+            pool.authority = new_authority;
+            msg!("Pool authority rotated to {}", new_authority);
+        }
+        ProposalAction::AddHook
+        | ProposalAction::UpdateGovernanceConfig { .. }
+        | ProposalAction::SetSecurityCouncil { .. } => {
+            return Err(AmmError::InvalidProposalKind.into());
+        }
+    }
+    proposal.mark_executed()?;
+    
+    emit!(ConfigUpdated {
+        kind: ConfigKind::Pool,
+        config: pool.key(),
+        updated_by: proposal.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Pool proposal {} executed on pool {}", proposal.key(), pool.key());
+    
+    Ok(())
+}
+
+/// Create the governance config with the default rules and an initial
+/// security council (which may be empty)
+#[cfg(feature = "governance")]
+pub fn initialize_governance_config(
+    ctx: Context<InitializeGovernanceConfig>,
+    security_council: Vec<Pubkey>,
+    veto_threshold: u8,
+) -> Result<()> {
+    let governance_config = &mut ctx.accounts.governance_config;
+    governance_config.initialize(&security_council, veto_threshold, ctx.bumps.governance_config)?;
+    
+    msg!("Governance config initialized: {}", governance_config.key());
+    msg!("Security council: {} members, veto threshold: {}", security_council.len(), veto_threshold);
+    
+    Ok(())
+}
+
+#[cfg(feature = "governance")]
 pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
     
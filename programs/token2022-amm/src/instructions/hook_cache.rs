@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::{HookValidationCache, TransferHookWhitelist, MAX_HOOK_CACHE_TTL};
+use crate::error::AmmError;
+use crate::token_extensions::transfer_hook_program_id;
+
+#[derive(Accounts)]
+pub struct OpenHookCache<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<HookValidationCache>(),
+        seeds = [HookValidationCache::SEED, mint.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub cache: Account<'info, HookValidationCache>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Mint whose Transfer Hook is validated
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Transfer Hook Whitelist for validation
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshHookCache<'info> {
+    #[account(
+        mut,
+        seeds = [HookValidationCache::SEED, mint.key().as_ref(), owner.key().as_ref()],
+        bump = cache.bump,
+        has_one = owner,
+        has_one = mint
+    )]
+    pub cache: Account<'info, HookValidationCache>,
+
+    pub owner: Signer<'info>,
+
+    /// Mint whose Transfer Hook is validated
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Transfer Hook Whitelist for validation
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+}
+
+pub fn open_hook_cache(ctx: Context<OpenHookCache>, ttl: i64) -> Result<()> {
+    require!(ttl > 0 && ttl <= MAX_HOOK_CACHE_TTL, AmmError::InvalidCacheTtl);
+
+    let hook_program_id = ctx.accounts.whitelist
+        .validate_mint_hook(&ctx.accounts.mint.to_account_info())?
+        .unwrap_or_default();
+
+    let cache = &mut ctx.accounts.cache;
+    cache.record(
+        ctx.accounts.mint.key(),
+        ctx.accounts.owner.key(),
+        ctx.accounts.whitelist.key(),
+        hook_program_id,
+        Clock::get()?.unix_timestamp,
+        ttl,
+        ctx.bumps.cache,
+    )?;
+
+    msg!("Hook validation cache opened for mint {} (hook: {})", ctx.accounts.mint.key(), hook_program_id);
+    Ok(())
+}
+
+pub fn refresh_hook_cache(ctx: Context<RefreshHookCache>, ttl: i64) -> Result<()> {
+    require!(ttl > 0 && ttl <= MAX_HOOK_CACHE_TTL, AmmError::InvalidCacheTtl);
+
+    let hook_program_id = ctx.accounts.whitelist
+        .validate_mint_hook(&ctx.accounts.mint.to_account_info())?
+        .unwrap_or_default();
+
+    let cache = &mut ctx.accounts.cache;
+    let bump = cache.bump;
+    cache.record(
+        ctx.accounts.mint.key(),
+        ctx.accounts.owner.key(),
+        ctx.accounts.whitelist.key(),
+        hook_program_id,
+        Clock::get()?.unix_timestamp,
+        ttl,
+        bump,
+    )?;
+
+    msg!("Hook validation cache refreshed for mint {} (hook: {})", ctx.accounts.mint.key(), hook_program_id);
+    Ok(())
+}
+
+/// Pre-check used by trading instructions: skip the full whitelist validation
+/// when a fresh cache entry vouches for this (mint, owner). The mint's current
+/// hook must still match the cached one and still be on the whitelist proper,
+/// so a hook swapped on the mint or delisted since the approval falls through
+/// to full validation. Hooks listed through `WhitelistEntry` PDAs are accepted
+/// when the entry is among `entries`. Returns the mint's hook program id, if any.
+pub fn check_mint_hook_cached(
+    whitelist: &Account<TransferHookWhitelist>,
+    mint_info: &AccountInfo,
+    owner: &Pubkey,
+    cache: Option<&Account<HookValidationCache>>,
+    entries: &[AccountInfo],
+) -> Result<Option<Pubkey>> {
+    if let Some(cache) = cache {
+        let now = Clock::get()?.unix_timestamp;
+        let hook_program_id = transfer_hook_program_id(mint_info)?;
+        if cache.is_fresh_for(mint_info.key, owner, &whitelist.key(), hook_program_id, now)
+            && hook_program_id.is_none_or(|id| whitelist.is_hook_whitelisted(&id))
+        {
+            return Ok(hook_program_id);
+        }
+    }
+
+    whitelist.validate_mint_hook_with_entries(&whitelist.key(), mint_info, entries)
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::{HookFailureLog, TransferHookWhitelist};
+use crate::error::AmmError;
+
+/// Rolling reliability of one hook mint, returned via return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct HookReliability {
+    /// Mint whose Transfer Hook is tracked
+    pub mint: Pubkey,
+    /// Transfers counted in the rolling window
+    pub attempts: u64,
+    /// Rejected transfers in the rolling window
+    pub failures: u64,
+    /// Failure rate in the rolling window (basis points)
+    pub failure_rate_bps: u64,
+    /// Slot of the most recent reported failure
+    pub last_failure_slot: u64,
+    /// Hook error code of the most recent reported failure
+    pub last_failure_reason: u32,
+}
+
+#[derive(Accounts)]
+pub struct OpenHookFailureLog<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<HookFailureLog>(),
+        seeds = [HookFailureLog::SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub failure_log: Account<'info, HookFailureLog>,
+
+    /// Mint whose Transfer Hook is tracked
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(has_one = authority @ AmmError::InvalidWhitelistAuthority)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReportHookFailure<'info> {
+    #[account(
+        mut,
+        seeds = [HookFailureLog::SEED, failure_log.mint.as_ref()],
+        bump = failure_log.bump,
+        has_one = reporter @ AmmError::InvalidHookFailureReporter
+    )]
+    pub failure_log: Account<'info, HookFailureLog>,
+
+    pub reporter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct HookFailureRate<'info> {
+    pub failure_log: Account<'info, HookFailureLog>,
+}
+
+pub fn open_hook_failure_log(ctx: Context<OpenHookFailureLog>, reporter: Pubkey) -> Result<()> {
+    let failure_log = &mut ctx.accounts.failure_log;
+    failure_log.initialize(ctx.accounts.mint.key(), reporter, ctx.bumps.failure_log)?;
+
+    msg!("Hook failure log opened for mint {} (reporter: {})", ctx.accounts.mint.key(), reporter);
+    Ok(())
+}
+
+/// Record an output-leg hook rejection observed in a failed swap transaction
+pub fn report_hook_failure(
+    ctx: Context<ReportHookFailure>,
+    failed_slot: u64,
+    reason: u32,
+) -> Result<()> {
+    let failure_log = &mut ctx.accounts.failure_log;
+    failure_log.record_failure(failed_slot, reason, Clock::get()?.slot)?;
+
+    msg!("Hook failure recorded for mint {}", failure_log.mint);
+    msg!("Slot: {}, reason: {}", failed_slot, reason);
+    Ok(())
+}
+
+pub fn hook_failure_rate(ctx: Context<HookFailureRate>) -> Result<HookReliability> {
+    let failure_log = &ctx.accounts.failure_log;
+    let slot = Clock::get()?.slot;
+    let (attempts, failures) = failure_log.window_counts(slot);
+
+    Ok(HookReliability {
+        mint: failure_log.mint,
+        attempts,
+        failures,
+        failure_rate_bps: failure_log.failure_rate_bps(slot),
+        last_failure_slot: failure_log.last_failure_slot,
+        last_failure_reason: failure_log.last_failure_reason,
+    })
+}
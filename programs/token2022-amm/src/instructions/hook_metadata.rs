@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use crate::state::{TransferHookWhitelist, WhitelistEntry, HookMetadata, HookRiskTier};
+use crate::error::AmmError;
+
+#[derive(Accounts)]
+#[instruction(hook_program_id: Pubkey)]
+pub struct RegisterHookMetadata<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidWhitelistAuthority
+    )]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = HookMetadata::SPACE,
+        seeds = [HookMetadata::SEED, whitelist.key().as_ref(), hook_program_id.as_ref()],
+        bump
+    )]
+    pub hook_metadata: Account<'info, HookMetadata>,
+
+    /// The hook's entry, required when it is not listed inline
+    #[account(mut, has_one = whitelist @ AmmError::InvalidWhitelistAccount)]
+    pub whitelist_entry: Option<Account<'info, WhitelistEntry>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateHookMetadata<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidWhitelistAuthority
+    )]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    #[account(
+        mut,
+        seeds = [HookMetadata::SEED, whitelist.key().as_ref(), hook_metadata.hook_program_id.as_ref()],
+        bump = hook_metadata.bump
+    )]
+    pub hook_metadata: Account<'info, HookMetadata>,
+
+    /// The hook's entry, required when it is not listed inline
+    #[account(mut, has_one = whitelist @ AmmError::InvalidWhitelistAccount)]
+    pub whitelist_entry: Option<Account<'info, WhitelistEntry>>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Mirror a hook's risk tier onto its whitelist listing, where swaps read it
+fn record_risk_tier(
+    whitelist: &mut Account<TransferHookWhitelist>,
+    whitelist_entry: Option<&mut Account<WhitelistEntry>>,
+    hook_program_id: &Pubkey,
+    risk_tier: HookRiskTier,
+) -> Result<()> {
+    if whitelist.is_hook_whitelisted(hook_program_id) {
+        return whitelist.set_hook_risk_tier(hook_program_id, risk_tier);
+    }
+
+    let entry = whitelist_entry.ok_or(AmmError::HookNotWhitelisted)?;
+    require_keys_eq!(entry.hook_program_id, *hook_program_id, AmmError::HookNotWhitelisted);
+    entry.risk_tier = risk_tier;
+    Ok(())
+}
+
+pub fn register_hook_metadata(
+    ctx: Context<RegisterHookMetadata>,
+    hook_program_id: Pubkey,
+    audit_url: String,
+    auditor: String,
+    risk_tier: HookRiskTier,
+    max_notional: u64,
+) -> Result<()> {
+    record_risk_tier(
+        &mut ctx.accounts.whitelist,
+        ctx.accounts.whitelist_entry.as_mut(),
+        &hook_program_id,
+        risk_tier,
+    )?;
+
+    let metadata = &mut ctx.accounts.hook_metadata;
+    metadata.whitelist = ctx.accounts.whitelist.key();
+    metadata.hook_program_id = hook_program_id;
+    metadata.bump = ctx.bumps.hook_metadata;
+    metadata.update(audit_url, auditor, risk_tier, max_notional, Clock::get()?.unix_timestamp)?;
+
+    msg!("Hook metadata registered for {}", hook_program_id);
+    msg!("Risk tier: {:?}, max notional: {}", risk_tier, max_notional);
+    Ok(())
+}
+
+pub fn update_hook_metadata(
+    ctx: Context<UpdateHookMetadata>,
+    audit_url: String,
+    auditor: String,
+    risk_tier: HookRiskTier,
+    max_notional: u64,
+) -> Result<()> {
+    let hook_program_id = ctx.accounts.hook_metadata.hook_program_id;
+    record_risk_tier(
+        &mut ctx.accounts.whitelist,
+        ctx.accounts.whitelist_entry.as_mut(),
+        &hook_program_id,
+        risk_tier,
+    )?;
+
+    let metadata = &mut ctx.accounts.hook_metadata;
+    metadata.update(audit_url, auditor, risk_tier, max_notional, Clock::get()?.unix_timestamp)?;
+
+    msg!("Hook metadata updated for {}", hook_program_id);
+    msg!("Risk tier: {:?}, max notional: {}", risk_tier, max_notional);
+    Ok(())
+}
@@ -0,0 +1,409 @@
+// Handlers are compiled only with the `rewards` feature; the account
+// structs stay in every build, see `feature_gated!`
+#![cfg_attr(not(feature = "rewards"), allow(unused_imports))]
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked,
+};
+use crate::state::{AmmPool, GlobalConfig, IncentiveGroup, IncentiveStake, GroupPoolReading};
+use crate::error::AmmError;
+use crate::hook_interface::transfer_checked_with_hook;
+
+#[derive(Accounts)]
+#[instruction(group_id: u64)]
+pub struct CreateIncentiveGroup<'info> {
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<IncentiveGroup>(),
+        seeds = [IncentiveGroup::SEED, &group_id.to_le_bytes()],
+        bump
+    )]
+    pub group: Account<'info, IncentiveGroup>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Reward token mint
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault funding the shared reward stream, owned by the group
+    #[account(
+        init,
+        payer = authority,
+        token::mint = reward_mint,
+        token::authority = group,
+        token::token_program = token_program,
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddGroupPool<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub group: Account<'info, IncentiveGroup>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = lp_mint @ AmmError::InvalidLpMint)]
+    pub pool: Account<'info, AmmPool>,
+
+    /// Pool's LP token mint
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault holding the pool's staked LP tokens, owned by the group
+    #[account(
+        init,
+        payer = authority,
+        token::mint = lp_mint,
+        token::authority = group,
+        token::token_program = token_program,
+    )]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetGroupRewardRate<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub group: Account<'info, IncentiveGroup>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenIncentiveStake<'info> {
+    pub group: Account<'info, IncentiveGroup>,
+
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<IncentiveStake>(),
+        seeds = [IncentiveStake::SEED, group.key().as_ref(), pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub stake: Account<'info, IncentiveStake>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGroupStake<'info> {
+    #[account(mut)]
+    pub group: Account<'info, IncentiveGroup>,
+
+    #[account(has_one = lp_mint @ AmmError::InvalidLpMint)]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        mut,
+        seeds = [IncentiveStake::SEED, group.key().as_ref(), pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake.bump,
+        has_one = group,
+        has_one = pool,
+        has_one = owner
+    )]
+    pub stake: Account<'info, IncentiveStake>,
+
+    pub owner: Signer<'info>,
+
+    /// Pool's LP token mint
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// Owner's LP token account
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner,
+    )]
+    pub user_lp_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Group vault holding this pool's staked LP tokens
+    #[account(mut)]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimGroupRewards<'info> {
+    #[account(
+        mut,
+        has_one = reward_mint,
+        has_one = reward_vault
+    )]
+    pub group: Account<'info, IncentiveGroup>,
+
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        mut,
+        seeds = [IncentiveStake::SEED, group.key().as_ref(), pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake.bump,
+        has_one = group,
+        has_one = pool,
+        has_one = owner
+    )]
+    pub stake: Account<'info, IncentiveStake>,
+
+    pub owner: Signer<'info>,
+
+    /// Reward token mint
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Owner's reward token account
+    #[account(mut, token::mint = reward_mint)]
+    pub user_reward_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    // remaining_accounts: the member pools in member order, then the Transfer
+    // Hook accounts of the reward mint, if any
+}
+
+/// Read every member pool from `remaining_accounts`, which must list them in
+/// member order, and accrue the group's emission up to now
+#[cfg(feature = "rewards")]
+fn accrue_group(group: &mut IncentiveGroup, remaining_accounts: &[AccountInfo]) -> Result<()> {
+    require!(
+        remaining_accounts.len() == group.pool_count as usize,
+        AmmError::InvalidGroupMemberAccounts
+    );
+
+    let mut readings = Vec::with_capacity(remaining_accounts.len());
+    for (i, info) in remaining_accounts.iter().enumerate() {
+        require_keys_eq!(info.key(), group.pools[i], AmmError::InvalidGroupMemberAccounts);
+        require_keys_eq!(*info.owner, crate::ID, AmmError::InvalidGroupMemberAccounts);
+        let pool = AmmPool::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        readings.push(GroupPoolReading {
+            cumulative_volume_a: pool.cumulative_volume_a(),
+            token_a_reserve: pool.token_a_reserve,
+        });
+    }
+
+    group.accrue(&readings, Clock::get()?.unix_timestamp)
+}
+
+#[cfg(feature = "rewards")]
+pub fn create_incentive_group(
+    ctx: Context<CreateIncentiveGroup>,
+    group_id: u64,
+    reward_rate: u64,
+) -> Result<()> {
+    let group = &mut ctx.accounts.group;
+    group.initialize(
+        ctx.accounts.authority.key(),
+        group_id,
+        ctx.accounts.reward_mint.key(),
+        ctx.accounts.reward_vault.key(),
+        reward_rate,
+        Clock::get()?.unix_timestamp,
+        ctx.bumps.group,
+    )?;
+
+    msg!("Incentive group {} created with reward rate {}/s", group_id, reward_rate);
+    Ok(())
+}
+
+#[cfg(feature = "rewards")]
+pub fn add_group_pool(ctx: Context<AddGroupPool>) -> Result<()> {
+    let group = &mut ctx.accounts.group;
+    accrue_group(group, ctx.remaining_accounts)?;
+    group.add_pool(
+        ctx.accounts.pool.key(),
+        ctx.accounts.lp_vault.key(),
+        ctx.accounts.pool.cumulative_volume_a(),
+    )?;
+
+    msg!("Pool {} linked into incentive group {}", ctx.accounts.pool.key(), group.group_id);
+    Ok(())
+}
+
+#[cfg(feature = "rewards")]
+pub fn set_group_reward_rate(
+    ctx: Context<SetGroupRewardRate>,
+    reward_rate: u64,
+) -> Result<()> {
+    let group = &mut ctx.accounts.group;
+    accrue_group(group, ctx.remaining_accounts)?;
+    group.reward_rate = reward_rate;
+
+    msg!("Incentive group {} reward rate set to {}/s", group.group_id, reward_rate);
+    Ok(())
+}
+
+#[cfg(feature = "rewards")]
+pub fn open_incentive_stake(ctx: Context<OpenIncentiveStake>) -> Result<()> {
+    let group = &ctx.accounts.group;
+    let index = group
+        .pool_index(&ctx.accounts.pool.key())
+        .ok_or(AmmError::PoolNotInGroup)?;
+
+    let stake = &mut ctx.accounts.stake;
+    stake.initialize(
+        group.key(),
+        ctx.accounts.pool.key(),
+        ctx.accounts.owner.key(),
+        group.reward_per_lp_x64[index],
+        ctx.bumps.stake,
+    )?;
+
+    msg!("Incentive stake opened for pool {}", ctx.accounts.pool.key());
+    Ok(())
+}
+
+#[cfg(feature = "rewards")]
+pub fn stake_group_lp(
+    ctx: Context<UpdateGroupStake>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, AmmError::InvalidAmount);
+
+    let group = &mut ctx.accounts.group;
+    let index = group
+        .pool_index(&ctx.accounts.pool.key())
+        .ok_or(AmmError::PoolNotInGroup)?;
+    require_keys_eq!(ctx.accounts.lp_vault.key(), group.lp_vaults[index], AmmError::InvalidVault);
+
+    accrue_group(group, ctx.remaining_accounts)?;
+    let stake = &mut ctx.accounts.stake;
+    stake.settle(group.reward_per_lp_x64[index])?;
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.user_lp_token.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.lp_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        },
+    );
+    transfer_checked(transfer_ctx, amount, ctx.accounts.lp_mint.decimals)?;
+
+    stake.staked_lp = stake.staked_lp.checked_add(amount).ok_or(AmmError::MathOverflow)?;
+    group.record_stake(index, amount)?;
+
+    msg!("Staked {} LP tokens in incentive group {}", amount, group.group_id);
+    Ok(())
+}
+
+#[cfg(feature = "rewards")]
+pub fn unstake_group_lp(
+    ctx: Context<UpdateGroupStake>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, AmmError::InvalidAmount);
+    require!(amount <= ctx.accounts.stake.staked_lp, AmmError::InsufficientStakedLp);
+
+    let group = &mut ctx.accounts.group;
+    let index = group
+        .pool_index(&ctx.accounts.pool.key())
+        .ok_or(AmmError::PoolNotInGroup)?;
+    require_keys_eq!(ctx.accounts.lp_vault.key(), group.lp_vaults[index], AmmError::InvalidVault);
+
+    accrue_group(group, ctx.remaining_accounts)?;
+    let stake = &mut ctx.accounts.stake;
+    stake.settle(group.reward_per_lp_x64[index])?;
+
+    let group_id_bytes = group.group_id.to_le_bytes();
+    let group_seeds: &[&[u8]] = &[
+        IncentiveGroup::SEED,
+        &group_id_bytes,
+        &[group.bump],
+    ];
+    let signer_seeds = &[group_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.lp_vault.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.user_lp_token.to_account_info(),
+            authority: group.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer_checked(transfer_ctx, amount, ctx.accounts.lp_mint.decimals)?;
+
+    stake.staked_lp -= amount;
+    group.record_unstake(index, amount)?;
+
+    msg!("Unstaked {} LP tokens from incentive group {}", amount, group.group_id);
+    Ok(())
+}
+
+#[cfg(feature = "rewards")]
+pub fn claim_group_rewards<'info>(ctx: Context<'_, '_, '_, 'info, ClaimGroupRewards<'info>>) -> Result<()> {
+    let group = &mut ctx.accounts.group;
+    let index = group
+        .pool_index(&ctx.accounts.pool.key())
+        .ok_or(AmmError::PoolNotInGroup)?;
+
+    require!(
+        ctx.remaining_accounts.len() >= group.pool_count as usize,
+        AmmError::InvalidGroupMemberAccounts
+    );
+    let (member_pools, hook_accounts) = ctx.remaining_accounts.split_at(group.pool_count as usize);
+    accrue_group(group, member_pools)?;
+    let stake = &mut ctx.accounts.stake;
+    stake.settle(group.reward_per_lp_x64[index])?;
+
+    let amount = stake.pending_rewards;
+    require!(amount > 0, AmmError::NoRewardsToClaim);
+    require!(
+        ctx.accounts.reward_vault.amount >= amount,
+        AmmError::InsufficientEmissionsFunding
+    );
+
+    let group_id_bytes = group.group_id.to_le_bytes();
+    let group_seeds: &[&[u8]] = &[
+        IncentiveGroup::SEED,
+        &group_id_bytes,
+        &[group.bump],
+    ];
+    let signer_seeds = &[group_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            mint: ctx.accounts.reward_mint.to_account_info(),
+            to: ctx.accounts.user_reward_token.to_account_info(),
+            authority: group.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer_checked_with_hook(transfer_ctx, amount, ctx.accounts.reward_mint.decimals, hook_accounts)?;
+
+    stake.pending_rewards = 0;
+
+    msg!("Claimed {} reward tokens from incentive group {}", amount, group.group_id);
+    Ok(())
+}
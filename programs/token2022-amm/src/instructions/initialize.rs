@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use crate::state::{AmmConfig, TransferHookWhitelist};
+use crate::error::AmmError;
+use crate::events::{ConfigUpdated, ConfigKind};
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<AmmConfig>(),
+        seeds = [AmmConfig::SEED],
+        bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Transfer Hook Whitelist pools will validate mints against
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAmmConfig<'info> {
+    #[account(
+        mut,
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAmmConfigWhitelist<'info> {
+    #[account(
+        mut,
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    pub authority: Signer<'info>,
+
+    /// Transfer Hook Whitelist pools will validate mints against
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+}
+
+/// Create the AMM config with the caller as protocol authority and the
+/// default fee tiers
+pub fn handler(ctx: Context<Initialize>) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    amm_config.initialize(
+        ctx.accounts.authority.key(),
+        ctx.accounts.whitelist.key(),
+        ctx.bumps.amm_config,
+    )?;
+
+    msg!("AMM config initialized with authority: {}", ctx.accounts.authority.key());
+    msg!("Whitelist: {}", ctx.accounts.whitelist.key());
+    Ok(())
+}
+
+pub fn update_amm_config(
+    ctx: Context<UpdateAmmConfig>,
+    fee_tiers: Vec<u64>,
+    protocol_fee_share_bps: u64,
+) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    amm_config.set_fee_tiers(&fee_tiers)?;
+    amm_config.set_protocol_fee_share(protocol_fee_share_bps)?;
+
+    emit!(ConfigUpdated {
+        kind: ConfigKind::AmmConfig,
+        config: amm_config.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("AMM config updated");
+    msg!("Fee tiers: {:?}", fee_tiers);
+    msg!("Protocol fee share: {} bps", protocol_fee_share_bps);
+    Ok(())
+}
+
+pub fn set_amm_config_whitelist(ctx: Context<SetAmmConfigWhitelist>) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    amm_config.whitelist = ctx.accounts.whitelist.key();
+
+    emit!(ConfigUpdated {
+        kind: ConfigKind::AmmConfig,
+        config: amm_config.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("AMM config whitelist set to {}", ctx.accounts.whitelist.key());
+    Ok(())
+}
+
+pub fn set_governance_treasury(
+    ctx: Context<UpdateAmmConfig>,
+    treasury: Pubkey,
+    proposal_slash_bps: u64,
+) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    amm_config.set_governance_treasury(treasury, proposal_slash_bps)?;
+
+    emit!(ConfigUpdated {
+        kind: ConfigKind::AmmConfig,
+        config: amm_config.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Governance treasury set to {}", treasury);
+    msg!("Proposal slash rate: {} bps", proposal_slash_bps);
+    Ok(())
+}
+
+/// Allow or forbid pool creation for mints with a permanent delegate, the
+/// non-transferable extension or confidential transfers
+pub fn set_mint_extension_policy(
+    ctx: Context<UpdateAmmConfig>,
+    allow_permanent_delegate: bool,
+    allow_non_transferable: bool,
+    allow_confidential_transfer: bool,
+) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    amm_config.allow_permanent_delegate = allow_permanent_delegate;
+    amm_config.allow_non_transferable = allow_non_transferable;
+    amm_config.allow_confidential_transfer = allow_confidential_transfer;
+
+    emit!(ConfigUpdated {
+        kind: ConfigKind::AmmConfig,
+        config: amm_config.key(),
+        updated_by: ctx.accounts.authority.key(),
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Permanent delegate mints allowed: {}", allow_permanent_delegate);
+    msg!("Non-transferable mints allowed: {}", allow_non_transferable);
+    msg!("Confidential transfer mints allowed: {}", allow_confidential_transfer);
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::{AmmPool, AmmConfig, TransferHookWhitelist, WhitelistEntry, HookRiskTier, PoolSide};
+use crate::error::AmmError;
+use crate::hook_interface::HookInterfaceVersion;
+use crate::token_extensions::transfer_hook_program_id;
+
+/// Hook validation result returned via return data.
+/// Part of the CPI surface for integrators: fields are only ever appended.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct HookValidation {
+    /// Mint checked
+    pub mint: Pubkey,
+    /// Side of the pool the mint trades on
+    pub side: PoolSide,
+    /// Transfer Hook program of the mint, if it has one
+    pub hook_program_id: Option<Pubkey>,
+    /// Whether the pool accepts transfers of the mint (always true without a hook)
+    pub whitelisted: bool,
+    /// Interface the hook speaks, for hooks in the whitelist's own array
+    pub interface_version: Option<HookInterfaceVersion>,
+    /// Whether swaps of the mint are capped by the hook's notional limit
+    pub high_risk: bool,
+}
+
+#[derive(Accounts)]
+pub struct ValidateHook<'info> {
+    #[account(constraint = pool.side_of_mint(&mint.key()).is_some() @ AmmError::InvalidTokenPair)]
+    pub pool: Account<'info, AmmPool>,
+
+    /// AMM config naming the global whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Transfer Hook Whitelist the pool validates against
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    /// Mint to check; one of the pool's
+    pub mint: InterfaceAccount<'info, Mint>,
+    // remaining_accounts: WhitelistEntry PDA of the mint's hook, if it is entry-listed
+}
+
+/// Report whether `pool` would accept transfers of `mint` under its
+/// whitelist. Unlike `validate_transfer_hook` this never fails on a
+/// non-whitelisted hook, so a calling program can branch on the result.
+pub fn validate_hook(ctx: Context<ValidateHook>) -> Result<HookValidation> {
+    let pool = &ctx.accounts.pool;
+    let whitelist = &ctx.accounts.whitelist;
+    let mint = ctx.accounts.mint.key();
+    let side = pool.side_of_mint(&mint).ok_or(AmmError::InvalidTokenPair)?;
+
+    let hook_program_id = transfer_hook_program_id(&ctx.accounts.mint.to_account_info())?;
+    let (whitelisted, interface_version, high_risk) = match hook_program_id {
+        Some(hook_program_id) if whitelist.is_hook_whitelisted(&hook_program_id) => (
+            true,
+            whitelist.hook_interface_version(&hook_program_id),
+            whitelist.is_high_risk_hook(&hook_program_id),
+        ),
+        Some(hook_program_id) => {
+            match WhitelistEntry::find_in(ctx.remaining_accounts, &whitelist.key(), &hook_program_id) {
+                Some(entry) => (true, Some(HookInterfaceVersion::SplInterface), entry.risk_tier == HookRiskTier::High),
+                None => (false, None, false),
+            }
+        }
+        None => (true, None, false),
+    };
+
+    Ok(HookValidation {
+        mint,
+        side,
+        hook_program_id,
+        whitelisted,
+        interface_version,
+        high_risk,
+    })
+}
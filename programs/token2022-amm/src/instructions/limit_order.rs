@@ -0,0 +1,514 @@
+// Handlers are compiled only with the `limit-orders` feature; the account
+// structs stay in every build, see `feature_gated!`
+#![cfg_attr(not(feature = "limit-orders"), allow(unused_imports))]
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, close_account, CloseAccount};
+use crate::state::{AmmPool, lock_pool, AmmConfig, TransferHookWhitelist, LimitOrder, TakerAllowlist, enforce_taker_allowlist, enforce_hook_notional_cap, Observation, record_observation, refresh_dynamic_fee};
+use crate::error::AmmError;
+use crate::events::{SwapExecuted, LimitOrderFilled};
+use crate::hook_interface::{invoke_legacy_hook_if_needed, transfer_checked_with_hook, with_hook_compute_budget};
+use crate::instructions::trading::{emit_realized_price, transfer_protocol_fee, vault_balances};
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct PlaceLimitOrder<'info> {
+    #[account(
+        constraint = pool.side_of_mint(&input_mint.key()).is_some() @ AmmError::InvalidTokenPair,
+        constraint = !pool.is_paused() @ AmmError::PoolPaused,
+        constraint = !pool.is_withdraw_only() @ AmmError::PoolWithdrawOnly
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<LimitOrder>(),
+        seeds = [LimitOrder::SEED, pool.key().as_ref(), owner.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    /// Escrow for the order's input, owned by the order
+    #[account(
+        init,
+        payer = owner,
+        seeds = [LimitOrder::ESCROW_SEED, order.key().as_ref()],
+        bump,
+        token::mint = input_mint,
+        token::authority = order,
+        token::token_program = input_token_program,
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Owner's input token account
+    #[account(mut)]
+    pub owner_input_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Input token mint
+    #[account(mint::token_program = input_token_program)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+
+    /// AMM config naming the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Transfer Hook Whitelist for validation (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    /// Taker allowlist, required when the pool is private
+    pub taker_allowlist: Option<Account<'info, TakerAllowlist>>,
+
+    /// Token program owning the input mint (SPL Token or Token-2022)
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = escrow,
+        close = owner
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    #[account(mut)]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Owner's input token account receiving the refund
+    #[account(mut)]
+    pub owner_input_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Input token mint
+    #[account(
+        address = escrow.mint @ AmmError::InvalidTokenPair,
+        mint::token_program = input_token_program
+    )]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token program owning the input mint (SPL Token or Token-2022)
+    pub input_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FillLimitOrder<'info> {
+    #[account(
+        mut,
+        constraint = pool.side_of_mint(&input_mint.key()) == Some(order.input_side) @ AmmError::InvalidTokenPair,
+        constraint = pool.side_of_mint(&output_mint.key()) == Some(order.input_side.opposite()) @ AmmError::InvalidTokenPair,
+        constraint = pool.side_of_vault(&pool_input_vault.key()) == Some(order.input_side) @ AmmError::InvalidVault,
+        constraint = pool.side_of_vault(&pool_output_vault.key()) == Some(order.input_side.opposite()) @ AmmError::InvalidVault,
+        constraint = !pool.is_paused() @ AmmError::PoolPaused,
+        constraint = !pool.is_withdraw_only() @ AmmError::PoolWithdrawOnly,
+        constraint = !pool.locked @ AmmError::PoolLocked
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        mut,
+        has_one = pool,
+        has_one = owner,
+        has_one = escrow,
+        close = owner
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    #[account(mut)]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Order owner; receives the escrow and order rent, checked via `has_one`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// Owner's output token account
+    #[account(
+        mut,
+        token::mint = output_mint,
+        token::authority = owner,
+    )]
+    pub owner_output_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Anyone filling the order once the pool price reaches its limit
+    pub keeper: Signer<'info>,
+
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Pool's input token vault
+    #[account(mut)]
+    pub pool_input_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's output token vault
+    #[account(mut)]
+    pub pool_output_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Input token mint
+    #[account(mint::token_program = input_token_program)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+
+    /// Output token mint
+    #[account(mint::token_program = output_token_program)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    /// AMM config gating trading and naming the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = !amm_config.paused @ AmmError::AmmPaused
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Transfer Hook Whitelist for validation (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    /// TWAP observation, required when the pool has one
+    #[account(mut)]
+    pub observation: Option<Account<'info, Observation>>,
+
+    /// Protocol fee vault of the input mint, required while the pool takes a protocol fee
+    #[account(mut)]
+    pub protocol_fee_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token program owning the input mint (SPL Token or Token-2022)
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning the output mint (SPL Token or Token-2022)
+    pub output_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Escrow `amount_in` of the input mint until the pool pays at least
+/// `min_amount_out` for it
+#[cfg(feature = "limit-orders")]
+pub fn place_limit_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, PlaceLimitOrder<'info>>,
+    nonce: u64,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    require!(amount_in > 0, AmmError::InvalidAmount);
+    require!(min_amount_out > 0, AmmError::InvalidAmount);
+
+    let pool = &ctx.accounts.pool;
+    let owner = &ctx.accounts.owner;
+    let input_side = pool
+        .side_of_mint(&ctx.accounts.input_mint.key())
+        .ok_or(AmmError::InvalidTokenPair)?;
+
+    // Private pools only trade with allowlisted takers; the taker is the owner
+    enforce_taker_allowlist(
+        &pool.taker_allowlist,
+        ctx.accounts.taker_allowlist.as_ref(),
+        &owner.key(),
+    )?;
+
+    // The input mint's Transfer Hook, if it has one, must be whitelisted
+    let whitelist = &ctx.accounts.whitelist;
+    whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.input_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.input_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.owner_input_token.to_account_info(),
+            mint: ctx.accounts.input_mint.to_account_info(),
+            to: ctx.accounts.escrow.to_account_info(),
+            authority: owner.to_account_info(),
+        },
+    );
+    transfer_checked_with_hook(
+        transfer_ctx,
+        amount_in,
+        ctx.accounts.input_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+
+    // A transfer-fee input mint delivers less than was sent
+    ctx.accounts.escrow.reload()?;
+    let escrowed = ctx.accounts.escrow.amount;
+
+    let order = &mut ctx.accounts.order;
+    order.initialize(
+        pool.key(),
+        owner.key(),
+        input_side,
+        ctx.accounts.escrow.key(),
+        escrowed,
+        min_amount_out,
+        Clock::get()?.unix_timestamp,
+        nonce,
+        ctx.bumps.order,
+    )?;
+
+    msg!("Limit order placed: {}", order.key());
+    msg!("Amount in: {} ({:?} side), min amount out: {}", escrowed, input_side, min_amount_out);
+
+    Ok(())
+}
+
+/// Refund an open order's escrow to its owner
+#[cfg(feature = "limit-orders")]
+pub fn cancel_limit_order<'info>(ctx: Context<'_, '_, '_, 'info, CancelLimitOrder<'info>>) -> Result<()> {
+    let order = &ctx.accounts.order;
+    let nonce_bytes = order.nonce.to_le_bytes();
+    let order_seeds: &[&[u8]] = &[
+        LimitOrder::SEED,
+        order.pool.as_ref(),
+        order.owner.as_ref(),
+        &nonce_bytes,
+        &[order.bump],
+    ];
+    let order_signer = &[order_seeds];
+
+    let refund_ctx = CpiContext::new_with_signer(
+        ctx.accounts.input_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.escrow.to_account_info(),
+            mint: ctx.accounts.input_mint.to_account_info(),
+            to: ctx.accounts.owner_input_token.to_account_info(),
+            authority: order.to_account_info(),
+        },
+        order_signer,
+    );
+    transfer_checked_with_hook(refund_ctx, ctx.accounts.escrow.amount, ctx.accounts.input_mint.decimals, ctx.remaining_accounts)?;
+
+    close_escrow(
+        &ctx.accounts.input_token_program,
+        &ctx.accounts.escrow,
+        &ctx.accounts.owner.to_account_info(),
+        &order.to_account_info(),
+        order_signer,
+    )?;
+
+    msg!("Limit order cancelled and refunded: {}", order.key());
+
+    Ok(())
+}
+
+/// Fill an order through the pool once the pool pays at least its limit.
+/// Permissionless: the keeper only signs, the owner receives the output.
+#[cfg(feature = "limit-orders")]
+pub fn fill_limit_order<'info>(ctx: Context<'_, '_, '_, 'info, FillLimitOrder<'info>>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let vault_authority_info = ctx.accounts.vault_authority.to_account_info();
+    let order = &ctx.accounts.order;
+    let input_side = order.input_side;
+    // Swap the whole escrow: tokens donated on top of the order would
+    // otherwise be left in it and make closing it fail
+    let amount_in = ctx.accounts.escrow.amount;
+    let nonce_bytes = order.nonce.to_le_bytes();
+
+    // The limit is reached once the pool pays at least `min_amount_out`
+    let pool = &mut ctx.accounts.pool;
+    let amount_out = pool.calculate_swap_output(input_side, amount_in)?;
+    require!(amount_out >= order.min_amount_out, AmmError::LimitPriceNotReached);
+    let vault_authority_bump = pool.vault_authority_bump;
+
+    // Both mints' Transfer Hooks, if any, must be whitelisted
+    let whitelist = &ctx.accounts.whitelist;
+    let input_hook = whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.input_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    let output_hook = whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.output_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    // High-risk hooks cap how much a single transfer may move
+    enforce_hook_notional_cap(whitelist, input_hook, amount_in, ctx.remaining_accounts)?;
+    enforce_hook_notional_cap(whitelist, output_hook, amount_out, ctx.remaining_accounts)?;
+
+    // Hook-bearing transfers run under the pool's hook compute budget, if set
+    let budget_in = pool.hook_compute_budget(input_hook);
+    let budget_out = pool.hook_compute_budget(output_hook);
+
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+
+    // Legacy-interface hooks are not invoked by Token-2022, run them here
+    with_hook_compute_budget(budget_in, || invoke_legacy_hook_if_needed(
+        input_hook.and_then(|id| whitelist.hook_interface_version(&id)),
+        input_hook,
+        &ctx.accounts.escrow.to_account_info(),
+        &ctx.accounts.input_mint.to_account_info(),
+        &ctx.accounts.pool_input_vault.to_account_info(),
+        &order.to_account_info(),
+        amount_in,
+        ctx.remaining_accounts,
+    ))?;
+    with_hook_compute_budget(budget_out, || invoke_legacy_hook_if_needed(
+        output_hook.and_then(|id| whitelist.hook_interface_version(&id)),
+        output_hook,
+        &ctx.accounts.pool_output_vault.to_account_info(),
+        &ctx.accounts.output_mint.to_account_info(),
+        &ctx.accounts.owner_output_token.to_account_info(),
+        &vault_authority_info,
+        amount_out,
+        ctx.remaining_accounts,
+    ))?;
+
+    // Release the escrow into the pool
+    let order_seeds: &[&[u8]] = &[
+        LimitOrder::SEED,
+        pool_key.as_ref(),
+        order.owner.as_ref(),
+        &nonce_bytes,
+        &[order.bump],
+    ];
+    let order_signer = &[order_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.input_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.escrow.to_account_info(),
+            mint: ctx.accounts.input_mint.to_account_info(),
+            to: ctx.accounts.pool_input_vault.to_account_info(),
+            authority: order.to_account_info(),
+        },
+        order_signer,
+    );
+    with_hook_compute_budget(budget_in, || transfer_checked_with_hook(
+        transfer_ctx,
+        amount_in,
+        ctx.accounts.input_mint.decimals,
+        ctx.remaining_accounts,
+    ))?;
+
+    // Pay the output to the owner
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.output_token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.pool_output_vault.to_account_info(),
+            mint: ctx.accounts.output_mint.to_account_info(),
+            to: ctx.accounts.owner_output_token.to_account_info(),
+            authority: vault_authority_info.clone(),
+        },
+        signer_seeds,
+    );
+    with_hook_compute_budget(budget_out, || transfer_checked_with_hook(
+        transfer_ctx,
+        amount_out,
+        ctx.accounts.output_mint.decimals,
+        ctx.remaining_accounts,
+    ))?;
+
+    // Accumulate the pre-trade price, then update pool state, splitting the
+    // protocol's share off the swap fee
+    let reserves_before = (pool.token_a_reserve, pool.token_b_reserve);
+    record_observation(pool, ctx.accounts.observation.as_mut(), Clock::get()?.unix_timestamp)?;
+    pool.update_swap_state(input_side, amount_in, amount_out)?;
+    let fee_amount = pool.calculate_swap_fee(amount_in)?;
+    let protocol_fee = pool.calculate_protocol_fee(fee_amount)?;
+    pool.debit_protocol_fee(input_side, protocol_fee)?;
+    pool.accrue_swap_fee(input_side, fee_amount - protocol_fee)?;
+    let balances = vault_balances(
+        input_side,
+        &ctx.accounts.pool_input_vault.to_account_info(),
+        &ctx.accounts.pool_output_vault.to_account_info(),
+    )?;
+    pool.enforce_swap_invariant(reserves_before, balances, input_side, protocol_fee)?;
+    refresh_dynamic_fee(pool, ctx.accounts.observation.as_ref(), Clock::get()?.unix_timestamp)?;
+
+    with_hook_compute_budget(budget_in, || transfer_protocol_fee(
+        &pool_key,
+        &ctx.accounts.input_token_program.to_account_info(),
+        &ctx.accounts.pool_input_vault,
+        &ctx.accounts.input_mint.to_account_info(),
+        ctx.accounts.input_mint.decimals,
+        ctx.accounts.protocol_fee_vault.as_ref(),
+        &vault_authority_info,
+        signer_seeds,
+        protocol_fee,
+        input_hook,
+        input_hook.and_then(|id| whitelist.hook_interface_version(&id)),
+        ctx.remaining_accounts,
+    ))?;
+    pool.unlock();
+
+    close_escrow(
+        &ctx.accounts.input_token_program,
+        &ctx.accounts.escrow,
+        &ctx.accounts.owner.to_account_info(),
+        &order.to_account_info(),
+        order_signer,
+    )?;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    emit!(SwapExecuted {
+        pool: pool_key,
+        user: order.owner,
+        input_side,
+        amount_in,
+        amount_out,
+        fee_amount,
+        protocol_fee,
+        timestamp,
+    });
+    emit_realized_price(pool_key, &ctx.accounts.pool, input_side, amount_in, amount_out, timestamp)?;
+    emit!(LimitOrderFilled {
+        pool: pool_key,
+        order: order.key(),
+        owner: order.owner,
+        keeper: ctx.accounts.keeper.key(),
+        input_side,
+        amount_in,
+        amount_out,
+        timestamp,
+    });
+
+    msg!("Limit order filled: {}", order.key());
+    msg!("Amount in: {}, amount out: {} (limit {})", amount_in, amount_out, order.min_amount_out);
+
+    Ok(())
+}
+
+/// Close the (now empty) escrow token account, returning rent to the owner
+#[cfg(feature = "limit-orders")]
+fn close_escrow<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    escrow: &InterfaceAccount<'info, TokenAccount>,
+    owner: &AccountInfo<'info>,
+    order: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let close_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: escrow.to_account_info(),
+            destination: owner.clone(),
+            authority: order.clone(),
+        },
+        signer_seeds,
+    );
+    close_account(close_ctx)
+}
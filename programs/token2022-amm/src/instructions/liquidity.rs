@@ -1,12 +1,30 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Mint, transfer, mint_to, MintTo, Transfer};
-use anchor_spl::token_2022::{Token2022, transfer_checked, TransferChecked};
-use crate::state::{AmmPool, TransferHookWhitelist};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, mint_to, MintTo, burn, Burn};
+use crate::state::{AmmPool, lock_pool, AmmConfig, TransferHookWhitelist, WhitelistEntry, LiquidityIou, PoolSide, LaunchGuard, enforce_launch_guard, LpPosition, apply_exit_fee, GlobalConfig, PairOracle, enforce_oracle_admission};
 use crate::error::AmmError;
+use crate::events::{LiquidityAdded, LiquidityRemoved, FeesCollected};
+use crate::hook_interface::transfer_checked_with_hook;
+use crate::instructions::trading::enforce_deadline;
+use crate::require_ctx;
+use crate::token_extensions::{
+    create_ata_if_missing, memo_if_required, require_no_cpi_guard, require_public_credits, token_account_summary,
+    transfer_hook_program_id,
+};
 
 #[derive(Accounts)]
 pub struct AddLiquidity<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        has_one = lp_mint @ AmmError::InvalidLpMint,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault,
+        constraint = !pool.is_paused() @ AmmError::PoolPaused,
+        constraint = !pool.is_withdraw_only() @ AmmError::PoolWithdrawOnly,
+        constraint = !pool.locked @ AmmError::PoolLocked
+    )]
     pub pool: Account<'info, AmmPool>,
     
     #[account(mut)]
@@ -14,99 +32,764 @@ pub struct AddLiquidity<'info> {
     
     /// User's token A account
     #[account(mut)]
-    pub user_token_a: Account<'info, TokenAccount>,
+    pub user_token_a: InterfaceAccount<'info, TokenAccount>,
     
     /// User's token B account
     #[account(mut)]
-    pub user_token_b: Account<'info, TokenAccount>,
+    pub user_token_b: InterfaceAccount<'info, TokenAccount>,
     
-    /// User's LP token account
+    /// CHECK: user's LP token account, or the lock-up escrow of `lp_position`;
+    /// created as the user's associated LP token account if it does not exist yet
     #[account(mut)]
-    pub user_lp_token: Account<'info, TokenAccount>,
+    pub user_lp_token: UncheckedAccount<'info>,
     
     /// Pool's token A vault
     #[account(mut)]
-    pub pool_token_a_vault: Account<'info, TokenAccount>,
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's token B vault
     #[account(mut)]
-    pub pool_token_b_vault: Account<'info, TokenAccount>,
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's LP token mint
     #[account(mut)]
-    pub lp_mint: Account<'info, Mint>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
     
     /// Token A mint
-    pub token_a_mint: Account<'info, Mint>,
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
     
     /// Token B mint
-    pub token_b_mint: Account<'info, Mint>,
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+    
+    /// AMM config gating deposits and naming the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = !amm_config.paused @ AmmError::AmmPaused
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
     
-    /// Transfer Hook Whitelist for validation
+    /// Transfer Hook Whitelist for validation (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
     pub whitelist: Account<'info, TransferHookWhitelist>,
     
-    pub token_program: Program<'info, Token>,
-    pub token_2022_program: Program<'info, Token2022>,
+    /// Launch guard, required while the pool has one
+    #[account(mut)]
+    pub launch_guard: Option<Account<'info, LaunchGuard>>,
+    
+    /// Global config holding the oracle admission settings
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+    
+    /// CHECK: Pair oracle PDA; may be uninitialized when the pair has no oracle
+    #[account(
+        seeds = [PairOracle::SEED, pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump
+    )]
+    pub pair_oracle: UncheckedAccount<'info>,
+    
+    /// LP position tracking holding time for the exit fee
+    #[account(
+        mut,
+        constraint = lp_position.pool == pool.key() && lp_position.owner == user.key() @ AmmError::InvalidLpPosition
+    )]
+    pub lp_position: Option<Account<'info, LpPosition>>,
+    
+    /// Token program owning the LP mint (SPL Token, or Token-2022 for newer pools)
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+    
+    /// Associated Token program, required when `user_lp_token` must be created
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    
+    /// System program, required when `user_lp_token` must be created
+    pub system_program: Option<Program<'info, System>>,
 }
 
 #[derive(Accounts)]
 pub struct RemoveLiquidity<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        has_one = lp_mint @ AmmError::InvalidLpMint,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault,
+        constraint = !pool.locked @ AmmError::PoolLocked
+    )]
     pub pool: Account<'info, AmmPool>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
-    /// User's token A account
+    /// CHECK: user's token A account; created as the user's associated token
+    /// account if it does not exist yet
     #[account(mut)]
-    pub user_token_a: Account<'info, TokenAccount>,
+    pub user_token_a: UncheckedAccount<'info>,
     
-    /// User's token B account
+    /// CHECK: user's token B account; created as the user's associated token
+    /// account if it does not exist yet
+    #[account(mut)]
+    pub user_token_b: UncheckedAccount<'info>,
+    
+    /// User's LP token account, or the lock-up escrow of `lp_position`
+    #[account(
+        mut,
+        constraint = user_lp_token.mint == lp_mint.key() @ AmmError::InvalidLpMint,
+        constraint = user_lp_token.owner == user.key()
+            || lp_position.as_ref().is_some_and(|position| user_lp_token.owner == position.key())
+            @ AmmError::InvalidTokenAccountOwner
+    )]
+    pub user_lp_token: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    /// Pool's token A vault
+    #[account(mut)]
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Pool's token B vault
     #[account(mut)]
-    pub user_token_b: Account<'info, TokenAccount>,
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
     
-    /// User's LP token account
+    /// Pool's LP token mint
     #[account(mut)]
-    pub user_lp_token: Account<'info, TokenAccount>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+    
+    /// AMM config naming the global whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// Transfer Hook Whitelist for validation (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    /// LP position tracking holding time for the exit fee
+    #[account(
+        mut,
+        constraint = lp_position.pool == pool.key() && lp_position.owner == user.key() @ AmmError::InvalidLpPosition
+    )]
+    pub lp_position: Option<Account<'info, LpPosition>>,
+    
+    /// Token program owning the LP mint (SPL Token, or Token-2022 for newer pools)
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+    
+    /// Associated Token program, required when a user token account must be created
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    
+    /// System program, required when a user token account must be created
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+#[instruction(lp_tokens_to_burn: u64, affected_side: PoolSide, min_amount_out: u64, nonce: u64)]
+pub struct RemoveLiquiditySingleLeg<'info> {
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        has_one = lp_mint @ AmmError::InvalidLpMint,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault,
+        constraint = !pool.locked @ AmmError::PoolLocked
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// Claim on the leg that cannot be transferred right now
+    #[account(
+        init,
+        payer = user,
+        space = 8 + std::mem::size_of::<LiquidityIou>(),
+        seeds = [LiquidityIou::SEED, pool.key().as_ref(), user.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub iou: Account<'info, LiquidityIou>,
+    
+    /// CHECK: user's token A account; created as the user's associated token
+    /// account if it does not exist yet
+    #[account(mut)]
+    pub user_token_a: UncheckedAccount<'info>,
+    
+    /// CHECK: user's token B account; created as the user's associated token
+    /// account if it does not exist yet
+    #[account(mut)]
+    pub user_token_b: UncheckedAccount<'info>,
+    
+    /// User's LP token account, or the lock-up escrow of `lp_position`
+    #[account(
+        mut,
+        constraint = user_lp_token.mint == lp_mint.key() @ AmmError::InvalidLpMint,
+        constraint = user_lp_token.owner == user.key()
+            || lp_position.as_ref().is_some_and(|position| user_lp_token.owner == position.key())
+            @ AmmError::InvalidTokenAccountOwner
+    )]
+    pub user_lp_token: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
     
     /// Pool's token A vault
     #[account(mut)]
-    pub pool_token_a_vault: Account<'info, TokenAccount>,
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's token B vault
     #[account(mut)]
-    pub pool_token_b_vault: Account<'info, TokenAccount>,
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Pool's LP token mint
+    #[account(mut)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+    
+    /// AMM config naming the global whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// Transfer Hook Whitelist the affected leg's hook is checked against
+    /// (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    /// LP position tracking holding time for the exit fee
+    #[account(
+        mut,
+        constraint = lp_position.pool == pool.key() && lp_position.owner == user.key() @ AmmError::InvalidLpPosition
+    )]
+    pub lp_position: Option<Account<'info, LpPosition>>,
+    
+    /// Token program owning the LP mint (SPL Token, or Token-2022 for newer pools)
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+    
+    /// Associated Token program, required when the paid leg's user token
+    /// account must be created
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemLiquidityIou<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, AmmPool>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [LiquidityIou::SEED, pool.key().as_ref(), owner.key().as_ref(), &iou.nonce.to_le_bytes()],
+        bump = iou.bump,
+        has_one = pool,
+        has_one = owner
+    )]
+    pub iou: Account<'info, LiquidityIou>,
+    
+    /// Owner's token account for the IOU's side
+    #[account(mut)]
+    pub user_token: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    /// Pool's vault for the IOU's side
+    #[account(mut)]
+    pub pool_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Mint for the IOU's side
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// Token program owning the IOU's mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileLpSupply<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidPoolAuthority,
+        has_one = lp_mint @ AmmError::InvalidLpMint
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    pub authority: Signer<'info>,
+    
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
     
     /// Pool's LP token mint
     #[account(mut)]
-    pub lp_mint: Account<'info, Mint>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Pool-owned LP token account holding stray LP tokens to burn
+    #[account(
+        mut,
+        constraint = stray_lp_token.mint == lp_mint.key() @ AmmError::InvalidLpMint,
+        constraint = stray_lp_token.owner == vault_authority.key() @ AmmError::InvalidTokenAccountOwner
+    )]
+    pub stray_lp_token: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Token program owning the LP mint (SPL Token, or Token-2022 for newer pools)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SyncReserves<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidPoolAuthority,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    pub authority: Signer<'info>,
+    
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    /// Pool's token A vault
+    #[account(mut)]
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Pool's token B vault
+    #[account(mut)]
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Protocol fee account receiving the token A surplus; without it the
+    /// surplus is folded into the reserve
+    #[account(mut, token::mint = token_a_mint)]
+    pub protocol_fee_token_a: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Protocol fee account receiving the token B surplus; without it the
+    /// surplus is folded into the reserve
+    #[account(mut, token::mint = token_b_mint)]
+    pub protocol_fee_token_b: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct OpenLpPosition<'info> {
+    pub pool: Account<'info, AmmPool>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<LpPosition>(),
+        seeds = [LpPosition::SEED, pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault
+    )]
+    pub pool: Account<'info, AmmPool>,
+    
+    /// LP position whose earned fees are collected
+    #[account(
+        mut,
+        constraint = lp_position.pool == pool.key() && lp_position.owner == owner.key() @ AmmError::InvalidLpPosition
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+    
+    pub owner: Signer<'info>,
+    
+    /// Owner's token A account
+    #[account(mut)]
+    pub user_token_a: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Owner's token B account
+    #[account(mut)]
+    pub user_token_b: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    /// Pool's token A vault
+    #[account(mut)]
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Pool's token B vault
+    #[account(mut)]
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Token A mint
-    pub token_a_mint: Account<'info, Mint>,
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
     
     /// Token B mint
-    pub token_b_mint: Account<'info, Mint>,
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
     
-    /// Transfer Hook Whitelist for validation
+    /// AMM config naming the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// Transfer Hook Whitelist for validation (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
     pub whitelist: Account<'info, TransferHookWhitelist>,
     
-    pub token_program: Program<'info, Token>,
-    pub token_2022_program: Program<'info, Token2022>,
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+/// Repair pools whose LP accounting drifted while removals "burned" LP tokens
+/// by moving them into pool custody: burn any LP tokens held by the vault
+/// authority and resync the recorded supply with the LP mint
+pub fn reconcile_lp_supply(ctx: Context<ReconcileLpSupply>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let vault_authority_bump = ctx.accounts.pool.vault_authority_bump;
+    
+    if let Some(stray_lp_token) = ctx.accounts.stray_lp_token.as_ref() {
+        let stray_amount = stray_lp_token.amount;
+        if stray_amount > 0 {
+            let vault_authority_seeds: &[&[u8]] = &[
+                pool_key.as_ref(),
+                AmmPool::VAULT_AUTHORITY_SEED,
+                &[vault_authority_bump],
+            ];
+            let signer_seeds = &[vault_authority_seeds];
+            
+            let burn_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    from: stray_lp_token.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            burn(burn_ctx, stray_amount)?;
+            msg!("Burned {} stray LP tokens", stray_amount);
+        }
+    }
+    
+    ctx.accounts.lp_mint.reload()?;
+    let lp_supply = ctx.accounts.lp_mint.supply;
+    let pool = &mut ctx.accounts.pool;
+    let previous_supply = pool.total_lp_supply;
+    pool.total_lp_supply = lp_supply;
+    pool.refresh_invariant_metric();
+    
+    msg!("LP supply reconciled: {} -> {}", previous_supply, lp_supply);
+    
+    Ok(())
+}
+
+/// Reconcile the recorded reserves with the real vault balances. Donations,
+/// inbound transfer fees and rounding make the two drift apart; each reserve is
+/// reset to what its vault holds beyond outstanding IOUs and claimable fees.
+/// When a protocol fee account is passed for a side, that side's surplus over
+/// the recorded reserve is skimmed to it instead of accruing to LPs.
+pub fn sync_reserves<'info>(ctx: Context<'_, '_, '_, 'info, SyncReserves<'info>>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let pool = &mut ctx.accounts.pool;
+    let vault_authority_bump = pool.vault_authority_bump;
+    
+    let (previous_a, skim_a) = pool.sync_reserve(
+        PoolSide::A,
+        ctx.accounts.pool_token_a_vault.amount,
+        ctx.accounts.protocol_fee_token_a.is_some(),
+    )?;
+    let (previous_b, skim_b) = pool.sync_reserve(
+        PoolSide::B,
+        ctx.accounts.pool_token_b_vault.amount,
+        ctx.accounts.protocol_fee_token_b.is_some(),
+    )?;
+    pool.refresh_invariant_metric();
+    
+    msg!("Reserve A synced: {} -> {}", previous_a, pool.token_a_reserve);
+    msg!("Reserve B synced: {} -> {}", previous_b, pool.token_b_reserve);
+    
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+    
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
+    
+    for (vault, mint, destination, token_program, surplus) in [
+        (&ctx.accounts.pool_token_a_vault, &ctx.accounts.token_a_mint, &ctx.accounts.protocol_fee_token_a, &ctx.accounts.token_a_program, skim_a),
+        (&ctx.accounts.pool_token_b_vault, &ctx.accounts.token_b_mint, &ctx.accounts.protocol_fee_token_b, &ctx.accounts.token_b_program, skim_b),
+    ] {
+        let Some(destination) = destination.as_ref().filter(|_| surplus > 0) else {
+            continue;
+        };
+        let transfer_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: destination.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked_with_hook(transfer_ctx, surplus, mint.decimals, ctx.remaining_accounts)?;
+        msg!("Skimmed {} surplus of mint {} to the protocol fee account", surplus, mint.key());
+    }
+    pool.unlock();
+    
+    Ok(())
 }
 
-pub fn add_liquidity(
-    ctx: Context<AddLiquidity>,
-    amount_a: u64,
-    amount_b: u64,
+/// Open an LP position. With a non-zero `lock_duration`, LP tokens deposited
+/// through it go to an escrow token account owned by the position and stay
+/// there until the lock-up ends, earning claimable fees at the lock tier's
+/// multiplier.
+pub fn open_lp_position(ctx: Context<OpenLpPosition>, lock_duration: i64) -> Result<()> {
+    let lp_position = &mut ctx.accounts.lp_position;
+    lp_position.initialize(
+        ctx.accounts.pool.key(),
+        ctx.accounts.owner.key(),
+        lock_duration,
+        ctx.bumps.lp_position,
+    )?;
+    
+    msg!("LP position opened for pool: {}", ctx.accounts.pool.key());
+    if lp_position.is_lockup() {
+        msg!("Lock-up: {}s, fee multiplier {} bps", lock_duration, lp_position.fee_multiplier_bps);
+    }
+    
+    Ok(())
+}
+
+/// Pay out the claimable-mode swap fees an LP position has earned
+pub fn collect_fees<'info>(ctx: Context<'_, '_, '_, 'info, CollectFees<'info>>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let vault_authority_info = ctx.accounts.vault_authority.to_account_info();
+    
+    let pool = &mut ctx.accounts.pool;
+    let lp_position = &mut ctx.accounts.lp_position;
+    lp_position.settle_fees(pool)?;
+    let (owed_a, owed_b) = lp_position.take_fees();
+    
+    // Anything the bucket no longer holds was folded back into reserves
+    let (amount_a, amount_b) = pool.collect_claimable_fees(owed_a, owed_b)?;
+    let vault_authority_bump = pool.vault_authority_bump;
+    
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+    
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
+    
+    if amount_a > 0 {
+        let transfer_a_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_a_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_token_a_vault.to_account_info(),
+                mint: ctx.accounts.token_a_mint.to_account_info(),
+                to: ctx.accounts.user_token_a.to_account_info(),
+                authority: vault_authority_info.clone(),
+            },
+            signer_seeds,
+        );
+        memo_if_required(
+            &ctx.accounts.user_token_a.to_account_info(),
+            "collect_fees",
+            &pool_key,
+            ctx.remaining_accounts,
+        )?;
+        transfer_checked_with_hook(
+            transfer_a_ctx,
+            amount_a,
+            ctx.accounts.token_a_mint.decimals,
+            ctx.remaining_accounts,
+        )?;
+    }
+    
+    if amount_b > 0 {
+        let transfer_b_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_b_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.pool_token_b_vault.to_account_info(),
+                mint: ctx.accounts.token_b_mint.to_account_info(),
+                to: ctx.accounts.user_token_b.to_account_info(),
+                authority: vault_authority_info.clone(),
+            },
+            signer_seeds,
+        );
+        memo_if_required(
+            &ctx.accounts.user_token_b.to_account_info(),
+            "collect_fees",
+            &pool_key,
+            ctx.remaining_accounts,
+        )?;
+        transfer_checked_with_hook(
+            transfer_b_ctx,
+            amount_b,
+            ctx.accounts.token_b_mint.decimals,
+            ctx.remaining_accounts,
+        )?;
+    }
+    pool.unlock();
+    
+    emit!(FeesCollected {
+        pool: pool_key,
+        owner: ctx.accounts.owner.key(),
+        amount_a,
+        amount_b,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Fees collected: {} token A, {} token B", amount_a, amount_b);
+    
+    Ok(())
+}
+
+/// Deposit up to `max_amount_a` / `max_amount_b`. Only the amounts matching
+/// the pool ratio are transferred; the excess of an imbalanced add stays in
+/// the user's accounts.
+pub fn add_liquidity<'info>(
+    ctx: Context<'_, '_, '_, 'info, AddLiquidity<'info>>,
+    max_amount_a: u64,
+    max_amount_b: u64,
     min_lp_tokens: u64,
+    deadline: i64,
 ) -> Result<()> {
+    enforce_deadline(deadline)?;
+    
     let user = &ctx.accounts.user;
     let pool_account_info = ctx.accounts.pool.to_account_info();
     
     // Get pool data before mutable borrow
     let pool = &mut ctx.accounts.pool;
-    let lp_tokens_to_mint = pool.calculate_lp_tokens_for_liquidity(amount_a, amount_b)?;
+    let (lp_tokens_to_mint, amount_a, amount_b) = pool.calculate_deposit(max_amount_a, max_amount_b)?;
     let pool_bump = pool.bump;
+    let fee_tier_seed = pool.fee_tier.to_le_bytes();
+    let (token_a_mint_key, token_b_mint_key) = (pool.token_a_mint, pool.token_b_mint);
+    
+    // Only allowlisted LPs may deposit while a launch guard is active
+    enforce_launch_guard(
+        &pool.launch_guard,
+        ctx.accounts.launch_guard.as_mut(),
+        &user.key(),
+        Clock::get()?.unix_timestamp,
+    )?;
+    
+    // A pair with an oracle cannot be seeded far from the oracle price
+    enforce_oracle_admission(
+        &ctx.accounts.global_config,
+        &ctx.accounts.pair_oracle.to_account_info(),
+        pool,
+        amount_a,
+        amount_b,
+        Clock::get()?.unix_timestamp,
+    )?;
     
     // Check minimum LP tokens
     require!(
@@ -114,9 +797,47 @@ pub fn add_liquidity(
         AmmError::InsufficientLPTokens
     );
     
-    // Transfer token A from user to pool using Token-2022
+    // A missing LP token account is created as the user's associated token account
+    create_ata_if_missing(
+        &user.to_account_info(),
+        &ctx.accounts.user_lp_token.to_account_info(),
+        &user.to_account_info(),
+        &ctx.accounts.lp_mint.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.associated_token_program.as_ref().map(ToAccountInfo::to_account_info),
+        ctx.accounts.system_program.as_ref().map(ToAccountInfo::to_account_info),
+    )?;
+    
+    // A lock-up position's LP tokens are minted into its escrow
+    if let Some(lp_position) = ctx.accounts.lp_position.as_ref().filter(|position| position.is_lockup()) {
+        let lp_token_owner = token_account_summary(&ctx.accounts.user_lp_token.to_account_info())
+            .map(|account| account.owner);
+        require!(lp_token_owner == Some(lp_position.key()), AmmError::LockedLpEscrowRequired);
+    }
+    
+    // Each mint's Transfer Hook, if it has one, must be whitelisted
+    let whitelist = &ctx.accounts.whitelist;
+    whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.token_a_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.token_b_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    
+    // CPI Guard would block the user-signed deposits
+    require_no_cpi_guard(&ctx.accounts.user_token_a.to_account_info())?;
+    require_no_cpi_guard(&ctx.accounts.user_token_b.to_account_info())?;
+    
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+    
+    // Transfer token A from user to pool
     let transfer_a_ctx = CpiContext::new(
-        ctx.accounts.token_2022_program.to_account_info(),
+        ctx.accounts.token_a_program.to_account_info(),
         TransferChecked {
             from: ctx.accounts.user_token_a.to_account_info(),
             mint: ctx.accounts.token_a_mint.to_account_info(),
@@ -124,11 +845,16 @@ pub fn add_liquidity(
             authority: user.to_account_info(),
         },
     );
-    transfer_checked(transfer_a_ctx, amount_a, ctx.accounts.token_a_mint.decimals)?;
+    transfer_checked_with_hook(
+        transfer_a_ctx,
+        amount_a,
+        ctx.accounts.token_a_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
     
-    // Transfer token B from user to pool using Token-2022
+    // Transfer token B from user to pool
     let transfer_b_ctx = CpiContext::new(
-        ctx.accounts.token_2022_program.to_account_info(),
+        ctx.accounts.token_b_program.to_account_info(),
         TransferChecked {
             from: ctx.accounts.user_token_b.to_account_info(),
             mint: ctx.accounts.token_b_mint.to_account_info(),
@@ -136,10 +862,21 @@ pub fn add_liquidity(
             authority: user.to_account_info(),
         },
     );
-    transfer_checked(transfer_b_ctx, amount_b, ctx.accounts.token_b_mint.decimals)?;
+    transfer_checked_with_hook(
+        transfer_b_ctx,
+        amount_b,
+        ctx.accounts.token_b_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
     
     // Mint LP tokens to user
-    let pool_seeds: &[&[u8]] = &[b"pool", &[pool_bump]];
+    let pool_seeds: &[&[u8]] = &[
+        AmmPool::SEED,
+        token_a_mint_key.as_ref(),
+        token_b_mint_key.as_ref(),
+        fee_tier_seed.as_ref(),
+        &[pool_bump],
+    ];
     let signer_seeds = &[pool_seeds];
     
     let mint_lp_ctx = CpiContext::new_with_signer(
@@ -155,28 +892,74 @@ pub fn add_liquidity(
     
     // Update pool state
     pool.add_liquidity(amount_a, amount_b, lp_tokens_to_mint)?;
+    if let Some(lp_position) = ctx.accounts.lp_position.as_mut() {
+        lp_position.settle_fees(pool)?;
+        let boost_before = lp_position.fee_boost_weight();
+        lp_position.record_deposit(lp_tokens_to_mint, Clock::get()?.unix_timestamp)?;
+        pool.reweight_fee_boost(boost_before, lp_position.fee_boost_weight())?;
+    }
+    pool.unlock();
+    
+    emit!(LiquidityAdded {
+        pool: pool.key(),
+        user: user.key(),
+        amount_a,
+        amount_b,
+        lp_tokens_minted: lp_tokens_to_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     
     msg!("Liquidity added successfully");
-    msg!("Token A: {}", amount_a);
-    msg!("Token B: {}", amount_b);
+    msg!("Token A: {} (of {} max)", amount_a, max_amount_a);
+    msg!("Token B: {} (of {} max)", amount_b, max_amount_b);
     msg!("LP Tokens: {}", lp_tokens_to_mint);
     
     Ok(())
 }
 
-pub fn remove_liquidity(
-    ctx: Context<RemoveLiquidity>,
+pub fn remove_liquidity<'info>(
+    ctx: Context<'_, '_, '_, 'info, RemoveLiquidity<'info>>,
     lp_tokens_to_burn: u64,
     min_token_a: u64,
     min_token_b: u64,
+    deadline: i64,
 ) -> Result<()> {
+    enforce_deadline(deadline)?;
+    
     let user = &ctx.accounts.user;
-    let pool_account_info = ctx.accounts.pool.to_account_info();
+    let pool_key = ctx.accounts.pool.key();
+    let vault_authority_info = ctx.accounts.vault_authority.to_account_info();
+    
+    // Missing payout accounts are created as the user's associated token accounts
+    for (user_token, mint, token_program) in [
+        (&ctx.accounts.user_token_a, &ctx.accounts.token_a_mint, &ctx.accounts.token_a_program),
+        (&ctx.accounts.user_token_b, &ctx.accounts.token_b_mint, &ctx.accounts.token_b_program),
+    ] {
+        create_ata_if_missing(
+            &user.to_account_info(),
+            &user_token.to_account_info(),
+            &user.to_account_info(),
+            &mint.to_account_info(),
+            &token_program.to_account_info(),
+            ctx.accounts.associated_token_program.as_ref().map(ToAccountInfo::to_account_info),
+            ctx.accounts.system_program.as_ref().map(ToAccountInfo::to_account_info),
+        )?;
+    }
     
     // Get pool data before mutable borrow
     let pool = &mut ctx.accounts.pool;
     let (token_a_amount, token_b_amount) = pool.calculate_tokens_for_lp_burn(lp_tokens_to_burn)?;
-    let pool_bump = pool.bump;
+    let vault_authority_bump = pool.vault_authority_bump;
+    
+    // Exit fee stays in reserves for the remaining LPs
+    let (token_a_amount, token_b_amount) = apply_exit_fee(
+        pool,
+        ctx.accounts.lp_position.as_mut(),
+        lp_tokens_to_burn,
+        token_a_amount,
+        token_b_amount,
+        Clock::get()?.unix_timestamp,
+    )?;
     
     // Check minimum amounts
     require!(
@@ -188,48 +971,94 @@ pub fn remove_liquidity(
         AmmError::InsufficientTokenB
     );
     
-    // Burn LP tokens from user
-    let burn_lp_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.user_lp_token.to_account_info(),
-            to: ctx.accounts.pool_token_a_vault.to_account_info(), // Dummy transfer to burn
-            authority: user.to_account_info(),
-        },
-    );
-    transfer(burn_lp_ctx, lp_tokens_to_burn)?;
+    // Confidential-transfer accounts may refuse the public payout outright
+    require_public_credits(pool.mint_extensions(PoolSide::A), &ctx.accounts.user_token_a.to_account_info())?;
+    require_public_credits(pool.mint_extensions(PoolSide::B), &ctx.accounts.user_token_b.to_account_info())?;
     
-    // Transfer token A from pool to user using Token-2022
-    let pool_seeds: &[&[u8]] = &[b"pool", &[pool_bump]];
-    let signer_seeds = &[pool_seeds];
+    // CPI Guard would block the user-signed LP burn
+    require_no_cpi_guard(&ctx.accounts.user_lp_token.to_account_info())?;
+    
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+    
+    // Burn LP tokens from user, or from the lock-up position's escrow
+    burn_user_lp(
+        &ctx.accounts.token_program,
+        &ctx.accounts.lp_mint,
+        &ctx.accounts.user_lp_token,
+        user,
+        ctx.accounts.lp_position.as_ref(),
+        lp_tokens_to_burn,
+    )?;
+    
+    // Transfer token A from pool to user
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
     
     let transfer_a_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_2022_program.to_account_info(),
+        ctx.accounts.token_a_program.to_account_info(),
         TransferChecked {
             from: ctx.accounts.pool_token_a_vault.to_account_info(),
             mint: ctx.accounts.token_a_mint.to_account_info(),
             to: ctx.accounts.user_token_a.to_account_info(),
-            authority: pool_account_info.clone(),
+            authority: vault_authority_info.clone(),
         },
         signer_seeds,
     );
-    transfer_checked(transfer_a_ctx, token_a_amount, ctx.accounts.token_a_mint.decimals)?;
+    memo_if_required(
+        &ctx.accounts.user_token_a.to_account_info(),
+        "remove_liquidity",
+        &pool_key,
+        ctx.remaining_accounts,
+    )?;
+    transfer_checked_with_hook(
+        transfer_a_ctx,
+        token_a_amount,
+        ctx.accounts.token_a_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
     
-    // Transfer token B from pool to user using Token-2022
+    // Transfer token B from pool to user
     let transfer_b_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_2022_program.to_account_info(),
+        ctx.accounts.token_b_program.to_account_info(),
         TransferChecked {
             from: ctx.accounts.pool_token_b_vault.to_account_info(),
             mint: ctx.accounts.token_b_mint.to_account_info(),
             to: ctx.accounts.user_token_b.to_account_info(),
-            authority: pool_account_info.clone(),
+            authority: vault_authority_info.clone(),
         },
         signer_seeds,
     );
-    transfer_checked(transfer_b_ctx, token_b_amount, ctx.accounts.token_b_mint.decimals)?;
+    memo_if_required(
+        &ctx.accounts.user_token_b.to_account_info(),
+        "remove_liquidity",
+        &pool_key,
+        ctx.remaining_accounts,
+    )?;
+    transfer_checked_with_hook(
+        transfer_b_ctx,
+        token_b_amount,
+        ctx.accounts.token_b_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
     
     // Update pool state
     pool.remove_liquidity(token_a_amount, token_b_amount, lp_tokens_to_burn)?;
+    pool.unlock();
+    
+    emit!(LiquidityRemoved {
+        pool: pool_key,
+        user: user.key(),
+        amount_a: token_a_amount,
+        amount_b: token_b_amount,
+        lp_tokens_burned: lp_tokens_to_burn,
+        iou_side: None,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     
     msg!("Liquidity removed successfully");
     msg!("LP Tokens burned: {}", lp_tokens_to_burn);
@@ -237,4 +1066,271 @@ pub fn remove_liquidity(
     msg!("Token B returned: {}", token_b_amount);
     
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Exit a position while one leg's Transfer Hook is paused: burn the LP tokens,
+/// pay out the unaffected leg and record the affected leg as a redeemable IOU
+pub fn remove_liquidity_single_leg<'info>(
+    ctx: Context<'_, '_, '_, 'info, RemoveLiquiditySingleLeg<'info>>,
+    lp_tokens_to_burn: u64,
+    affected_side: PoolSide,
+    min_amount_out: u64,
+    nonce: u64,
+) -> Result<()> {
+    let user = &ctx.accounts.user;
+    let pool_key = ctx.accounts.pool.key();
+    let vault_authority_info = ctx.accounts.vault_authority.to_account_info();
+    
+    // Only legs guarded by a Transfer Hook can be blocked
+    let affected_mint_info = match affected_side {
+        PoolSide::A => ctx.accounts.token_a_mint.to_account_info(),
+        PoolSide::B => ctx.accounts.token_b_mint.to_account_info(),
+    };
+    let hook_program_id = transfer_hook_program_id(&affected_mint_info)?.ok_or(AmmError::MintHasNoTransferHook)?;
+    
+    // The leg must actually be blocked: its hook flagged by the emergency
+    // authority, or removed from the whitelist (an entry-listed hook counts
+    // as removed only when its `WhitelistEntry` PDA is passed and empty)
+    let whitelist = &ctx.accounts.whitelist;
+    let whitelist_key = whitelist.key();
+    let delisted = !whitelist.is_hook_whitelisted(&hook_program_id)
+        && ctx
+            .remaining_accounts
+            .iter()
+            .any(|info| info.key() == WhitelistEntry::address(&whitelist_key, &hook_program_id))
+        && WhitelistEntry::find_in(ctx.remaining_accounts, &whitelist_key, &hook_program_id).is_none();
+    require_ctx!(
+        ctx.accounts.pool.is_hook_blocked(affected_side) || delisted,
+        AmmError::HookNotBlocked,
+        Some(affected_mint_info.key()),
+        Some(hook_program_id)
+    );
+    
+    // Get pool data before mutable borrow
+    let pool = &mut ctx.accounts.pool;
+    let (token_a_amount, token_b_amount) = pool.calculate_tokens_for_lp_burn(lp_tokens_to_burn)?;
+    let vault_authority_bump = pool.vault_authority_bump;
+    
+    // Exit fee stays in reserves for the remaining LPs
+    let (token_a_amount, token_b_amount) = apply_exit_fee(
+        pool,
+        ctx.accounts.lp_position.as_mut(),
+        lp_tokens_to_burn,
+        token_a_amount,
+        token_b_amount,
+        Clock::get()?.unix_timestamp,
+    )?;
+    
+    let (paid_amount, owed_amount) = match affected_side {
+        PoolSide::A => (token_b_amount, token_a_amount),
+        PoolSide::B => (token_a_amount, token_b_amount),
+    };
+    require!(
+        paid_amount >= min_amount_out,
+        AmmError::InsufficientOutputAmount
+    );
+    
+    // CPI Guard would block the user-signed LP burn
+    require_no_cpi_guard(&ctx.accounts.user_lp_token.to_account_info())?;
+    
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+    
+    // Burn LP tokens from user, or from the lock-up position's escrow
+    burn_user_lp(
+        &ctx.accounts.token_program,
+        &ctx.accounts.lp_mint,
+        &ctx.accounts.user_lp_token,
+        user,
+        ctx.accounts.lp_position.as_ref(),
+        lp_tokens_to_burn,
+    )?;
+    
+    // Transfer the unaffected leg from pool to user
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
+    
+    let (from, mint, to, decimals, token_program) = match affected_side {
+        PoolSide::A => (
+            ctx.accounts.pool_token_b_vault.to_account_info(),
+            ctx.accounts.token_b_mint.to_account_info(),
+            ctx.accounts.user_token_b.to_account_info(),
+            ctx.accounts.token_b_mint.decimals,
+            ctx.accounts.token_b_program.to_account_info(),
+        ),
+        PoolSide::B => (
+            ctx.accounts.pool_token_a_vault.to_account_info(),
+            ctx.accounts.token_a_mint.to_account_info(),
+            ctx.accounts.user_token_a.to_account_info(),
+            ctx.accounts.token_a_mint.decimals,
+            ctx.accounts.token_a_program.to_account_info(),
+        ),
+    };
+    // A missing payout account is created as the user's associated token account
+    create_ata_if_missing(
+        &user.to_account_info(),
+        &to,
+        &user.to_account_info(),
+        &mint,
+        &token_program,
+        ctx.accounts.associated_token_program.as_ref().map(ToAccountInfo::to_account_info),
+        Some(ctx.accounts.system_program.to_account_info()),
+    )?;
+    memo_if_required(&to, "remove_liquidity", &pool_key, ctx.remaining_accounts)?;
+    let transfer_ctx = CpiContext::new_with_signer(
+        token_program,
+        TransferChecked {
+            from,
+            mint,
+            to,
+            authority: vault_authority_info.clone(),
+        },
+        signer_seeds,
+    );
+    transfer_checked_with_hook(transfer_ctx, paid_amount, decimals, ctx.remaining_accounts)?;
+    
+    // Update pool state: both legs leave the reserves, the owed leg stays in the vault
+    pool.remove_liquidity(token_a_amount, token_b_amount, lp_tokens_to_burn)?;
+    pool.record_iou(affected_side, owed_amount)?;
+    pool.unlock();
+    
+    let iou = &mut ctx.accounts.iou;
+    iou.initialize(
+        pool_key,
+        user.key(),
+        affected_side,
+        owed_amount,
+        Clock::get()?.unix_timestamp,
+        nonce,
+        ctx.bumps.iou,
+    )?;
+    
+    let (amount_a, amount_b) = match affected_side {
+        PoolSide::A => (0, paid_amount),
+        PoolSide::B => (paid_amount, 0),
+    };
+    emit!(LiquidityRemoved {
+        pool: pool_key,
+        user: user.key(),
+        amount_a,
+        amount_b,
+        lp_tokens_burned: lp_tokens_to_burn,
+        iou_side: Some(affected_side),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Single-leg liquidity removal executed");
+    msg!("LP Tokens burned: {}", lp_tokens_to_burn);
+    msg!("Paid out: {}", paid_amount);
+    msg!("IOU recorded: {} ({:?} side)", owed_amount, affected_side);
+    
+    Ok(())
+}
+
+/// Redeem an IOU once the affected mint's Transfer Hook accepts transfers again
+pub fn redeem_liquidity_iou<'info>(ctx: Context<'_, '_, '_, 'info, RedeemLiquidityIou<'info>>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let vault_authority_info = ctx.accounts.vault_authority.to_account_info();
+    let pool = &mut ctx.accounts.pool;
+    let iou = &ctx.accounts.iou;
+    
+    let (expected_vault, expected_mint) = match iou.side {
+        PoolSide::A => (pool.token_a_vault, pool.token_a_mint),
+        PoolSide::B => (pool.token_b_vault, pool.token_b_mint),
+    };
+    require_ctx!(
+        ctx.accounts.pool_vault.key() == expected_vault,
+        AmmError::InvalidVault,
+        Some(expected_mint),
+        None
+    );
+    require_ctx!(
+        ctx.accounts.mint.key() == expected_mint,
+        AmmError::InvalidTokenPair,
+        Some(ctx.accounts.mint.key()),
+        None
+    );
+    
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+    
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[pool.vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
+    
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.pool_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.user_token.to_account_info(),
+            authority: vault_authority_info,
+        },
+        signer_seeds,
+    );
+    memo_if_required(
+        &ctx.accounts.user_token.to_account_info(),
+        "redeem_iou",
+        &pool_key,
+        ctx.remaining_accounts,
+    )?;
+    transfer_checked_with_hook(transfer_ctx, iou.amount, ctx.accounts.mint.decimals, ctx.remaining_accounts)?;
+    
+    pool.settle_iou(iou.side, iou.amount)?;
+    pool.unlock();
+    
+    msg!("Liquidity IOU redeemed: {} ({:?} side)", iou.amount, iou.side);
+    
+    Ok(())
+}
+
+/// Burn LP tokens from `user_lp_token`, signing as the LP position when the
+/// account is a lock-up position's escrow rather than the user's own
+fn burn_user_lp<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    lp_mint: &InterfaceAccount<'info, Mint>,
+    user_lp_token: &InterfaceAccount<'info, TokenAccount>,
+    user: &Signer<'info>,
+    lp_position: Option<&Account<'info, LpPosition>>,
+    amount: u64,
+) -> Result<()> {
+    match lp_position.filter(|position| user_lp_token.owner == position.key()) {
+        Some(position) => {
+            let position_seeds: &[&[u8]] = &[
+                LpPosition::SEED,
+                position.pool.as_ref(),
+                position.owner.as_ref(),
+                &[position.bump],
+            ];
+            let signer_seeds = &[position_seeds];
+            let burn_lp_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Burn {
+                    mint: lp_mint.to_account_info(),
+                    from: user_lp_token.to_account_info(),
+                    authority: position.to_account_info(),
+                },
+                signer_seeds,
+            );
+            burn(burn_lp_ctx, amount)
+        }
+        None => {
+            let burn_lp_ctx = CpiContext::new(
+                token_program.to_account_info(),
+                Burn {
+                    mint: lp_mint.to_account_info(),
+                    from: user_lp_token.to_account_info(),
+                    authority: user.to_account_info(),
+                },
+            );
+            burn(burn_lp_ctx, amount)
+        }
+    }
+}
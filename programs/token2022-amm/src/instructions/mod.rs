@@ -4,6 +4,27 @@ pub mod amm_pool;
 pub mod trading;
 pub mod liquidity;
 pub mod governance;
+pub mod assertions;
+pub mod emissions;
+pub mod hook_cache;
+pub mod quote;
+pub mod integration;
+pub mod views;
+pub mod pending_deposit;
+pub mod limit_order;
+pub mod global_config;
+pub mod price_feed;
+pub mod observation;
+pub mod clmm;
+pub mod hook_failure_log;
+pub mod hook_metadata;
+pub mod incentive_group;
+pub mod farm;
+pub mod settlement;
+pub mod preflight;
+pub mod protocol_fee;
+pub mod protocol_liquidity;
+pub mod test_utils;
 
 pub use initialize::*;
 pub use whitelist::*;
@@ -11,3 +32,24 @@ pub use amm_pool::*;
 pub use trading::*;
 pub use liquidity::*;
 pub use governance::*;
+pub use assertions::*;
+pub use emissions::*;
+pub use hook_cache::*;
+pub use quote::*;
+pub use integration::*;
+pub use views::*;
+pub use pending_deposit::*;
+pub use limit_order::*;
+pub use global_config::*;
+pub use price_feed::*;
+pub use observation::*;
+pub use clmm::*;
+pub use hook_failure_log::*;
+pub use hook_metadata::*;
+pub use incentive_group::*;
+pub use farm::*;
+pub use settlement::*;
+pub use preflight::*;
+pub use protocol_fee::*;
+pub use protocol_liquidity::*;
+pub use test_utils::*;
@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::constants::BPS_DENOMINATOR;
+use crate::state::{AmmPool, Observation, ObservationPoint, mul_div_floor_u64, refresh_dynamic_fee};
+use crate::error::AmmError;
+use crate::events::ObservationCranked;
+use crate::hook_interface::transfer_checked_with_hook;
+
+/// Maximum points one `observe` call reports (bounded by return data size)
+pub const MAX_OBSERVE_POINTS: usize = 16;
+
+/// Seconds that must pass since an observation's latest point before
+/// `crank_observation` may write another
+pub const OBSERVATION_CRANK_INTERVAL: i64 = 300;
+
+/// Share of the protocol fee vault balance paid to whoever cranks an
+/// observation (1 bps = 0.01%)
+pub const OBSERVATION_CRANK_REWARD_BPS: u64 = 1;
+
+#[derive(Accounts)]
+pub struct InitializeObservation<'info> {
+    /// Pool to observe. Anyone may enable the oracle; from then on every swap
+    /// must pass and update the observation.
+    #[account(
+        mut,
+        constraint = pool.observation == Pubkey::default() @ AmmError::MissingObservation
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<Observation>(),
+        seeds = [Observation::SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub observation: Account<'info, Observation>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Observe<'info> {
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        seeds = [Observation::SEED, pool.key().as_ref()],
+        bump = observation.bump,
+        has_one = pool
+    )]
+    pub observation: Account<'info, Observation>,
+}
+
+#[derive(Accounts)]
+pub struct CrankObservation<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        mut,
+        seeds = [Observation::SEED, pool.key().as_ref()],
+        bump = observation.bump,
+        has_one = pool
+    )]
+    pub observation: Account<'info, Observation>,
+
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Protocol fee vault the reward is paid from, for either pool mint
+    #[account(
+        mut,
+        seeds = [AmmPool::PROTOCOL_FEE_VAULT_SEED, pool.key().as_ref(), reward_mint.key().as_ref()],
+        bump
+    )]
+    pub protocol_fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool mint the reward is paid in
+    #[account(
+        mint::token_program = reward_token_program,
+        constraint = pool.side_of_mint(&reward_mint.key()).is_some() @ AmmError::InvalidTokenPair
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    /// Cranker's account receiving the reward
+    #[account(mut, token::mint = reward_mint)]
+    pub cranker_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub cranker: Signer<'info>,
+
+    /// Token program owning the reward mint (SPL Token or Token-2022)
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn initialize_observation(ctx: Context<InitializeObservation>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.pool;
+    let observation = &mut ctx.accounts.observation;
+    observation.initialize(pool.key(), now, ctx.bumps.observation)?;
+    pool.observation = observation.key();
+
+    msg!("Observation enabled for pool: {}", pool.key());
+    Ok(())
+}
+
+/// Report the cumulative prices as of each `seconds_agos` entry before now.
+/// The TWAP between two entries is the accumulator difference (wrapping)
+/// divided by the seconds between them, as a Q64.64 price.
+pub fn observe(ctx: Context<Observe>, seconds_agos: Vec<u32>) -> Result<Vec<ObservationPoint>> {
+    require!(
+        !seconds_agos.is_empty() && seconds_agos.len() <= MAX_OBSERVE_POINTS,
+        AmmError::InvalidObservePoints
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &ctx.accounts.pool;
+    let observation = &ctx.accounts.observation;
+    seconds_agos
+        .iter()
+        .map(|seconds_ago| observation.observe(pool, now, *seconds_ago))
+        .collect()
+}
+
+/// Write an observation point for a pool that has not traded for at least
+/// `OBSERVATION_CRANK_INTERVAL`, so its TWAP stays fresh on low volume.
+/// Permissionless; the caller earns `OBSERVATION_CRANK_REWARD_BPS` of the
+/// protocol fee vault they name.
+pub fn crank_observation<'info>(ctx: Context<'_, '_, '_, 'info, CrankObservation<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.pool;
+    let observation = &mut ctx.accounts.observation;
+    require!(
+        now >= observation.latest_timestamp().saturating_add(OBSERVATION_CRANK_INTERVAL),
+        AmmError::ObservationCrankTooSoon
+    );
+
+    observation.update(pool, now)?;
+    refresh_dynamic_fee(pool, Some(&*observation), now)?;
+
+    let reward = mul_div_floor_u64(ctx.accounts.protocol_fee_vault.amount, OBSERVATION_CRANK_REWARD_BPS, BPS_DENOMINATOR)?;
+    if reward > 0 {
+        let pool_key = pool.key();
+        let vault_authority_seeds: &[&[u8]] = &[
+            pool_key.as_ref(),
+            AmmPool::VAULT_AUTHORITY_SEED,
+            &[pool.vault_authority_bump],
+        ];
+        let signer_seeds = &[vault_authority_seeds];
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.reward_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.protocol_fee_vault.to_account_info(),
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx.accounts.cranker_token.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked_with_hook(transfer_ctx, reward, ctx.accounts.reward_mint.decimals, ctx.remaining_accounts)?;
+    }
+
+    emit!(ObservationCranked {
+        pool: pool.key(),
+        cranker: ctx.accounts.cranker.key(),
+        reward_mint: ctx.accounts.reward_mint.key(),
+        reward,
+        timestamp: now,
+    });
+
+    msg!("Observation cranked for pool: {}", pool.key());
+    msg!("Crank reward: {}", reward);
+    Ok(())
+}
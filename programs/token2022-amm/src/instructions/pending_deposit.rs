@@ -0,0 +1,569 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, close_account, CloseAccount, mint_to, MintTo};
+use crate::state::{AmmPool, lock_pool, AmmConfig, TransferHookWhitelist, PendingDeposit, LaunchGuard, enforce_launch_guard, GlobalConfig, PairOracle, enforce_oracle_admission};
+use crate::error::AmmError;
+use crate::events::LiquidityAdded;
+use crate::hook_interface::transfer_checked_with_hook;
+use crate::token_extensions::require_no_cpi_guard;
+
+#[derive(Accounts)]
+#[instruction(amount_a: u64, amount_b: u64, min_lp_tokens: u64, nonce: u64)]
+pub struct CreatePendingDeposit<'info> {
+    #[account(
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + std::mem::size_of::<PendingDeposit>(),
+        seeds = [PendingDeposit::SEED, pool.key().as_ref(), user.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub pending_deposit: Account<'info, PendingDeposit>,
+
+    /// Escrow for token A, owned by the pending deposit
+    #[account(
+        init,
+        payer = user,
+        seeds = [PendingDeposit::ESCROW_A_SEED, pending_deposit.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pending_deposit,
+        token::token_program = token_a_program,
+    )]
+    pub escrow_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow for token B, owned by the pending deposit
+    #[account(
+        init,
+        payer = user,
+        seeds = [PendingDeposit::ESCROW_B_SEED, pending_deposit.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pending_deposit,
+        token::token_program = token_b_program,
+    )]
+    pub escrow_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's token A account
+    #[account(mut)]
+    pub user_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's token B account
+    #[account(mut)]
+    pub user_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    /// AMM config naming the default whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Transfer Hook Whitelist for validation (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecutePendingDeposit<'info> {
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        has_one = lp_mint @ AmmError::InvalidLpMint,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault,
+        constraint = !pool.is_paused() @ AmmError::PoolPaused,
+        constraint = !pool.is_withdraw_only() @ AmmError::PoolWithdrawOnly
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [PendingDeposit::SEED, pool.key().as_ref(), owner.key().as_ref(), &pending_deposit.nonce.to_le_bytes()],
+        bump = pending_deposit.bump,
+        has_one = pool,
+        has_one = owner,
+        has_one = escrow_a,
+        has_one = escrow_b
+    )]
+    pub pending_deposit: Account<'info, PendingDeposit>,
+
+    #[account(mut)]
+    pub escrow_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub escrow_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// Depositor's token A account, refunded whatever the pool ratio leaves over
+    #[account(mut, token::mint = token_a_mint, token::authority = owner)]
+    pub user_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// Depositor's token B account, refunded whatever the pool ratio leaves over
+    #[account(mut, token::mint = token_b_mint, token::authority = owner)]
+    pub user_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's token A vault
+    #[account(mut)]
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's token B vault
+    #[account(mut)]
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's LP token account
+    #[account(mut)]
+    pub user_lp_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's LP token mint
+    #[account(mut)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    /// Launch guard, required while the pool has one
+    #[account(mut)]
+    pub launch_guard: Option<Account<'info, LaunchGuard>>,
+
+    /// AMM config gating deposits
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = !amm_config.paused @ AmmError::AmmPaused
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Transfer Hook Whitelist for validation (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    /// Global config holding the oracle admission settings
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// CHECK: Pair oracle PDA; may be uninitialized when the pair has no oracle
+    #[account(
+        seeds = [PairOracle::SEED, pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump
+    )]
+    pub pair_oracle: UncheckedAccount<'info>,
+
+    /// Token program owning the LP mint (SPL Token, or Token-2022 for newer pools)
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPendingDeposit<'info> {
+    #[account(
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [PendingDeposit::SEED, pool.key().as_ref(), owner.key().as_ref(), &pending_deposit.nonce.to_le_bytes()],
+        bump = pending_deposit.bump,
+        has_one = pool,
+        has_one = owner,
+        has_one = escrow_a,
+        has_one = escrow_b
+    )]
+    pub pending_deposit: Account<'info, PendingDeposit>,
+
+    #[account(mut)]
+    pub escrow_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub escrow_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's token A account (refund destination)
+    #[account(mut)]
+    pub user_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's token B account (refund destination)
+    #[account(mut)]
+    pub user_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+pub fn create_pending_deposit<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingDeposit<'info>>,
+    amount_a: u64,
+    amount_b: u64,
+    min_lp_tokens: u64,
+    nonce: u64,
+) -> Result<()> {
+    require!(amount_a > 0 && amount_b > 0, AmmError::InvalidAmount);
+
+    let user = &ctx.accounts.user;
+
+    // Each mint's Transfer Hook, if it has one, must be whitelisted
+    let whitelist = &ctx.accounts.whitelist;
+    whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.token_a_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.token_b_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    // CPI Guard would block the user-signed escrow deposits
+    require_no_cpi_guard(&ctx.accounts.user_token_a.to_account_info())?;
+    require_no_cpi_guard(&ctx.accounts.user_token_b.to_account_info())?;
+
+    // Move both legs into escrow; the user's own escrow transfer is the first
+    // leg the compliance hook sees
+    let transfer_a_ctx = CpiContext::new(
+        ctx.accounts.token_a_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.user_token_a.to_account_info(),
+            mint: ctx.accounts.token_a_mint.to_account_info(),
+            to: ctx.accounts.escrow_a.to_account_info(),
+            authority: user.to_account_info(),
+        },
+    );
+    transfer_checked_with_hook(
+        transfer_a_ctx,
+        amount_a,
+        ctx.accounts.token_a_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+
+    let transfer_b_ctx = CpiContext::new(
+        ctx.accounts.token_b_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.user_token_b.to_account_info(),
+            mint: ctx.accounts.token_b_mint.to_account_info(),
+            to: ctx.accounts.escrow_b.to_account_info(),
+            authority: user.to_account_info(),
+        },
+    );
+    transfer_checked_with_hook(
+        transfer_b_ctx,
+        amount_b,
+        ctx.accounts.token_b_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+
+    let pending_deposit = &mut ctx.accounts.pending_deposit;
+    pending_deposit.initialize(
+        ctx.accounts.pool.key(),
+        user.key(),
+        ctx.accounts.escrow_a.key(),
+        ctx.accounts.escrow_b.key(),
+        amount_a,
+        amount_b,
+        min_lp_tokens,
+        Clock::get()?.unix_timestamp,
+        nonce,
+        ctx.bumps.pending_deposit,
+    )?;
+
+    msg!("Pending deposit created: {}", pending_deposit.key());
+    msg!("Token A: {}", amount_a);
+    msg!("Token B: {}", amount_b);
+
+    Ok(())
+}
+
+pub fn execute_pending_deposit<'info>(ctx: Context<'_, '_, '_, 'info, ExecutePendingDeposit<'info>>) -> Result<()> {
+    let pool_account_info = ctx.accounts.pool.to_account_info();
+    let pool_key = ctx.accounts.pool.key();
+    let owner_key = ctx.accounts.owner.key();
+    let pending_deposit = &ctx.accounts.pending_deposit;
+    // Escrow balances may sit below the recorded amounts for transfer-fee mints
+    let escrowed_a = ctx.accounts.escrow_a.amount;
+    let escrowed_b = ctx.accounts.escrow_b.amount;
+    let nonce_bytes = pending_deposit.nonce.to_le_bytes();
+
+    // Get pool data before mutable borrow; the escrow is trimmed to the pool
+    // ratio exactly as a direct add_liquidity would be
+    let pool = &mut ctx.accounts.pool;
+    let (lp_tokens_to_mint, amount_a, amount_b) = pool.calculate_deposit(escrowed_a, escrowed_b)?;
+    let pool_bump = pool.bump;
+    let fee_tier_seed = pool.fee_tier.to_le_bytes();
+    let (token_a_mint_key, token_b_mint_key) = (pool.token_a_mint, pool.token_b_mint);
+
+    // Check minimum LP tokens
+    require!(
+        lp_tokens_to_mint >= pending_deposit.min_lp_tokens,
+        AmmError::InsufficientLPTokens
+    );
+
+    // Only allowlisted LPs may deposit while a launch guard is active
+    enforce_launch_guard(
+        &pool.launch_guard,
+        ctx.accounts.launch_guard.as_mut(),
+        &owner_key,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    // A pair with an oracle cannot be seeded far from the oracle price
+    enforce_oracle_admission(
+        &ctx.accounts.global_config,
+        &ctx.accounts.pair_oracle.to_account_info(),
+        pool,
+        amount_a,
+        amount_b,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    // Each mint's Transfer Hook, if it has one, must still be whitelisted
+    let whitelist = &ctx.accounts.whitelist;
+    whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.token_a_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.token_b_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    let deposit_seeds: &[&[u8]] = &[
+        PendingDeposit::SEED,
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        &nonce_bytes,
+        &[pending_deposit.bump],
+    ];
+    let deposit_signer = &[deposit_seeds];
+
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+
+    // Release escrow into the pool vaults (the transfer the hook approves) and
+    // hand back whatever the pool ratio did not use
+    let legs = [
+        (
+            &ctx.accounts.escrow_a,
+            &ctx.accounts.pool_token_a_vault,
+            &ctx.accounts.user_token_a,
+            &ctx.accounts.token_a_mint,
+            &ctx.accounts.token_a_program,
+            escrowed_a,
+            amount_a,
+        ),
+        (
+            &ctx.accounts.escrow_b,
+            &ctx.accounts.pool_token_b_vault,
+            &ctx.accounts.user_token_b,
+            &ctx.accounts.token_b_mint,
+            &ctx.accounts.token_b_program,
+            escrowed_b,
+            amount_b,
+        ),
+    ];
+    for (escrow, vault, user_token, mint, token_program, escrowed, amount) in legs {
+        let refund = escrowed - amount;
+        for (destination, leg_amount) in [(vault, amount), (user_token, refund)] {
+            if leg_amount == 0 {
+                continue;
+            }
+            let transfer_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: escrow.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: destination.to_account_info(),
+                    authority: pending_deposit.to_account_info(),
+                },
+                deposit_signer,
+            );
+            transfer_checked_with_hook(transfer_ctx, leg_amount, mint.decimals, ctx.remaining_accounts)?;
+        }
+    }
+
+    close_escrows(
+        &ctx.accounts.token_a_program,
+        &ctx.accounts.token_b_program,
+        &ctx.accounts.escrow_a,
+        &ctx.accounts.escrow_b,
+        &ctx.accounts.owner.to_account_info(),
+        &pending_deposit.to_account_info(),
+        deposit_signer,
+    )?;
+
+    // Mint LP tokens to user
+    let pool_seeds: &[&[u8]] = &[
+        AmmPool::SEED,
+        token_a_mint_key.as_ref(),
+        token_b_mint_key.as_ref(),
+        fee_tier_seed.as_ref(),
+        &[pool_bump],
+    ];
+    let signer_seeds = &[pool_seeds];
+
+    let mint_lp_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.user_lp_token.to_account_info(),
+            authority: pool_account_info,
+        },
+        signer_seeds,
+    );
+    mint_to(mint_lp_ctx, lp_tokens_to_mint)?;
+
+    // Update pool state
+    pool.add_liquidity(amount_a, amount_b, lp_tokens_to_mint)?;
+    pool.unlock();
+
+    emit!(LiquidityAdded {
+        pool: pool_key,
+        user: owner_key,
+        amount_a,
+        amount_b,
+        lp_tokens_minted: lp_tokens_to_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Pending deposit executed");
+    msg!("Token A: {} (of {} escrowed)", amount_a, escrowed_a);
+    msg!("Token B: {} (of {} escrowed)", amount_b, escrowed_b);
+    msg!("LP Tokens: {}", lp_tokens_to_mint);
+
+    Ok(())
+}
+
+pub fn cancel_pending_deposit<'info>(ctx: Context<'_, '_, '_, 'info, CancelPendingDeposit<'info>>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let owner_key = ctx.accounts.owner.key();
+    let pending_deposit = &ctx.accounts.pending_deposit;
+    let nonce_bytes = pending_deposit.nonce.to_le_bytes();
+
+    let deposit_seeds: &[&[u8]] = &[
+        PendingDeposit::SEED,
+        pool_key.as_ref(),
+        owner_key.as_ref(),
+        &nonce_bytes,
+        &[pending_deposit.bump],
+    ];
+    let deposit_signer = &[deposit_seeds];
+
+    // Refund both legs to the depositor
+    let refund_a_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_a_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.escrow_a.to_account_info(),
+            mint: ctx.accounts.token_a_mint.to_account_info(),
+            to: ctx.accounts.user_token_a.to_account_info(),
+            authority: pending_deposit.to_account_info(),
+        },
+        deposit_signer,
+    );
+    transfer_checked_with_hook(refund_a_ctx, ctx.accounts.escrow_a.amount, ctx.accounts.token_a_mint.decimals, ctx.remaining_accounts)?;
+
+    let refund_b_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_b_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.escrow_b.to_account_info(),
+            mint: ctx.accounts.token_b_mint.to_account_info(),
+            to: ctx.accounts.user_token_b.to_account_info(),
+            authority: pending_deposit.to_account_info(),
+        },
+        deposit_signer,
+    );
+    transfer_checked_with_hook(refund_b_ctx, ctx.accounts.escrow_b.amount, ctx.accounts.token_b_mint.decimals, ctx.remaining_accounts)?;
+
+    close_escrows(
+        &ctx.accounts.token_a_program,
+        &ctx.accounts.token_b_program,
+        &ctx.accounts.escrow_a,
+        &ctx.accounts.escrow_b,
+        &ctx.accounts.owner.to_account_info(),
+        &pending_deposit.to_account_info(),
+        deposit_signer,
+    )?;
+
+    msg!("Pending deposit cancelled and refunded: {}", pending_deposit.key());
+
+    Ok(())
+}
+
+/// Close both (now empty) escrow token accounts, returning rent to the depositor
+fn close_escrows<'info>(
+    token_a_program: &Interface<'info, TokenInterface>,
+    token_b_program: &Interface<'info, TokenInterface>,
+    escrow_a: &InterfaceAccount<'info, TokenAccount>,
+    escrow_b: &InterfaceAccount<'info, TokenAccount>,
+    owner: &AccountInfo<'info>,
+    pending_deposit: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    for (escrow, token_program) in [(escrow_a, token_a_program), (escrow_b, token_b_program)] {
+        let close_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: escrow.to_account_info(),
+                destination: owner.clone(),
+                authority: pending_deposit.clone(),
+            },
+            signer_seeds,
+        );
+        close_account(close_ctx)?;
+    }
+    Ok(())
+}
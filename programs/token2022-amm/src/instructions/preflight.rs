@@ -0,0 +1,185 @@
+use anchor_lang::prelude::*;
+use crate::state::{AmmPool, TransferHookWhitelist, TakerAllowlist, enforce_taker_allowlist};
+use crate::hook_interface::{extra_account_metas_address, HookInterfaceVersion};
+use crate::token_extensions::{memo_program_supplied, token_account_summary, TokenAccountSummary};
+
+/// Pre-flight checklist returned via return data.
+/// Each flag is `true` when that check passes; `ready` is their conjunction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct SwapPreflight {
+    /// Input and output mints are the two sides of the pool
+    pub pair_valid: bool,
+    /// Both vaults are the pool's vaults for their mints
+    pub vaults_valid: bool,
+    /// Pool is accepting swaps (not winding down or paused)
+    pub pool_active: bool,
+    /// Taker is admitted by the pool's allowlist, if it is private
+    pub taker_allowed: bool,
+    /// User's input account holds the input mint and belongs to the user
+    pub input_account_valid: bool,
+    /// User's output account holds the output mint and belongs to the user
+    pub output_account_valid: bool,
+    /// User's input balance covers `amount_in`
+    pub sufficient_balance: bool,
+    /// None of the user's accounts or the pool's vaults are frozen
+    pub accounts_not_frozen: bool,
+    /// Output account accepts transfers without a memo, or the SPL Memo
+    /// program is supplied so the swap can write one
+    pub memo_not_required: bool,
+    /// Output account accepts non-confidential credits (always true unless
+    /// it is configured for confidential transfers)
+    pub public_credits_accepted: bool,
+    /// Input account does not have CPI Guard enabled, which would block the
+    /// user-signed debit
+    pub cpi_guard_disabled: bool,
+    /// Input mint has no hook or a whitelisted one
+    pub input_hook_whitelisted: bool,
+    /// Output mint has no hook or a whitelisted one
+    pub output_hook_whitelisted: bool,
+    /// Hook programs and their validation accounts are in remaining accounts
+    pub hook_accounts_present: bool,
+    /// Pool liquidity fills the swap
+    pub liquidity_sufficient: bool,
+    /// Output the pool would send (0 when the swap cannot fill)
+    pub expected_amount_out: u64,
+    /// Every check above passes
+    pub ready: bool,
+}
+
+#[derive(Accounts)]
+pub struct PreflightSwap<'info> {
+    pub pool: Account<'info, AmmPool>,
+
+    /// CHECK: Wallet that would sign the swap; only used as a key
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: Inspected by the checklist rather than constrained
+    pub user_input_token: UncheckedAccount<'info>,
+
+    /// CHECK: Inspected by the checklist rather than constrained
+    pub user_output_token: UncheckedAccount<'info>,
+
+    /// CHECK: Inspected by the checklist rather than constrained
+    pub pool_input_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Inspected by the checklist rather than constrained
+    pub pool_output_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Inspected by the checklist rather than constrained
+    pub input_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Inspected by the checklist rather than constrained
+    pub output_mint: UncheckedAccount<'info>,
+
+    /// Transfer Hook Whitelist for validation
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    /// Taker allowlist, required when the pool is private
+    pub taker_allowlist: Option<Account<'info, TakerAllowlist>>,
+    // remaining_accounts: hook programs and validation accounts the swap would pass,
+    // plus the SPL Memo program if the output account requires memos
+}
+
+/// Run every swap check short of the transfers and report which ones would
+/// fail, so wallets can surface problems before the user signs
+pub fn preflight_swap(ctx: Context<PreflightSwap>, amount_in: u64) -> Result<SwapPreflight> {
+    let pool = &ctx.accounts.pool;
+    let user = ctx.accounts.user.key();
+    let input_mint = ctx.accounts.input_mint.key();
+    let output_mint = ctx.accounts.output_mint.key();
+    let remaining = ctx.remaining_accounts;
+
+    let input_side = pool.side_of_mint(&input_mint);
+    let pair_valid = input_side.is_some()
+        && pool.side_of_mint(&output_mint) == input_side.map(|side| side.opposite());
+    let vaults_valid = pair_valid
+        && pool.side_of_vault(&ctx.accounts.pool_input_vault.key()) == input_side
+        && pool.side_of_vault(&ctx.accounts.pool_output_vault.key()) == pool.side_of_mint(&output_mint);
+
+    let pool_active = !pool.is_winding_down() && !pool.is_paused() && !pool.is_withdraw_only();
+    let taker_allowed = enforce_taker_allowlist(
+        &pool.taker_allowlist,
+        ctx.accounts.taker_allowlist.as_ref(),
+        &user,
+    ).is_ok();
+
+    let user_input = token_account_summary(&ctx.accounts.user_input_token.to_account_info());
+    let user_output = token_account_summary(&ctx.accounts.user_output_token.to_account_info());
+    let input_vault = token_account_summary(&ctx.accounts.pool_input_vault.to_account_info());
+    let output_vault = token_account_summary(&ctx.accounts.pool_output_vault.to_account_info());
+
+    let held_by = |account: &Option<TokenAccountSummary>, mint: &Pubkey| {
+        account.is_some_and(|account| account.mint == *mint && account.owner == user)
+    };
+    let input_account_valid = held_by(&user_input, &input_mint);
+    let output_account_valid = held_by(&user_output, &output_mint);
+    let sufficient_balance = amount_in > 0 && user_input.is_some_and(|account| account.amount >= amount_in);
+    let accounts_not_frozen = [user_input, user_output, input_vault, output_vault]
+        .iter()
+        .all(|account| account.is_some_and(|account| !account.is_frozen));
+    let memo_not_required = user_output.is_some_and(|account| !account.requires_incoming_memo)
+        || (user_output.is_some() && memo_program_supplied(ctx.remaining_accounts));
+    let public_credits_accepted = user_output.is_some_and(|account| !account.rejects_public_credits);
+    let cpi_guard_disabled = user_input.is_some_and(|account| !account.cpi_guard_enabled);
+
+    let whitelist = &ctx.accounts.whitelist;
+    let input_hook = whitelist.validate_mint_hook(&ctx.accounts.input_mint.to_account_info());
+    let output_hook = whitelist.validate_mint_hook(&ctx.accounts.output_mint.to_account_info());
+    let input_hook_whitelisted = input_hook.is_ok();
+    let output_hook_whitelisted = output_hook.is_ok();
+
+    let has_account = |key: &Pubkey| remaining.iter().any(|info| info.key == key);
+    let hook_accounts_present = [(input_mint, input_hook), (output_mint, output_hook)]
+        .into_iter()
+        .all(|(mint, hook)| match hook {
+            Ok(Some(hook_program_id)) => {
+                has_account(&hook_program_id)
+                    && (whitelist.hook_interface_version(&hook_program_id) != Some(HookInterfaceVersion::SplInterface)
+                        || has_account(&extra_account_metas_address(&mint, &hook_program_id)))
+            }
+            Ok(None) => true,
+            Err(_) => false,
+        });
+
+    let expected_amount_out = input_side
+        .filter(|_| pair_valid)
+        .and_then(|side| pool.calculate_swap_output(side, amount_in).ok())
+        .unwrap_or(0);
+    let liquidity_sufficient = expected_amount_out > 0;
+
+    let ready = pair_valid
+        && vaults_valid
+        && pool_active
+        && taker_allowed
+        && input_account_valid
+        && output_account_valid
+        && sufficient_balance
+        && accounts_not_frozen
+        && memo_not_required
+        && public_credits_accepted
+        && cpi_guard_disabled
+        && input_hook_whitelisted
+        && output_hook_whitelisted
+        && hook_accounts_present
+        && liquidity_sufficient;
+
+    Ok(SwapPreflight {
+        pair_valid,
+        vaults_valid,
+        pool_active,
+        taker_allowed,
+        input_account_valid,
+        output_account_valid,
+        sufficient_balance,
+        accounts_not_frozen,
+        memo_not_required,
+        public_credits_accepted,
+        cpi_guard_disabled,
+        input_hook_whitelisted,
+        output_hook_whitelisted,
+        hook_accounts_present,
+        liquidity_sufficient,
+        expected_amount_out,
+        ready,
+    })
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::{AmmPool, PoolPriceFeed};
+use crate::error::AmmError;
+
+#[derive(Accounts)]
+pub struct EnablePriceFeed<'info> {
+    #[account(
+        has_one = authority @ AmmError::InvalidPoolAuthority,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<PoolPriceFeed>(),
+        seeds = [PoolPriceFeed::SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub price_feed: Account<'info, PoolPriceFeed>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Token A mint
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PublishPrice<'info> {
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        mut,
+        seeds = [PoolPriceFeed::SEED, pool.key().as_ref()],
+        bump = price_feed.bump,
+        has_one = pool
+    )]
+    pub price_feed: Account<'info, PoolPriceFeed>,
+}
+
+pub fn enable_price_feed(ctx: Context<EnablePriceFeed>, twap_window: i64) -> Result<()> {
+    let clock = Clock::get()?;
+    let price_feed = &mut ctx.accounts.price_feed;
+    price_feed.initialize(
+        &ctx.accounts.pool,
+        ctx.accounts.token_a_mint.decimals,
+        ctx.accounts.token_b_mint.decimals,
+        twap_window,
+        clock.unix_timestamp,
+        clock.slot,
+        ctx.bumps.price_feed,
+    )?;
+
+    msg!("Price feed enabled for pool: {}", ctx.accounts.pool.key());
+    msg!("TWAP window: {}s", twap_window);
+    Ok(())
+}
+
+/// Permissionless crank publishing the pool's current TWAP
+pub fn publish_price(ctx: Context<PublishPrice>) -> Result<()> {
+    let clock = Clock::get()?;
+    let price_feed = &mut ctx.accounts.price_feed;
+    price_feed.publish(&ctx.accounts.pool, clock.unix_timestamp, clock.slot)?;
+
+    msg!(
+        "Published price {} (conf {}) x 10^{}",
+        price_feed.price_message.price,
+        price_feed.price_message.conf,
+        price_feed.price_message.exponent
+    );
+    Ok(())
+}
@@ -0,0 +1,230 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::{AmmPool, lock_pool, AmmConfig, GlobalConfig};
+use crate::error::AmmError;
+use crate::hook_interface::transfer_checked_with_hook;
+
+#[derive(Accounts)]
+pub struct InitializeProtocolFeeVaults<'info> {
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// AMM config holding the default protocol fee share
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// CHECK: PDA that owns the pool vaults; only used as the fee vaults' authority
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Protocol fee vault for token A
+    #[account(
+        init,
+        payer = payer,
+        seeds = [AmmPool::PROTOCOL_FEE_VAULT_SEED, pool.key().as_ref(), token_a_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = vault_authority,
+        token::token_program = token_a_program,
+    )]
+    pub protocol_fee_vault_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// Protocol fee vault for token B
+    #[account(
+        init,
+        payer = payer,
+        seeds = [AmmPool::PROTOCOL_FEE_VAULT_SEED, pool.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = vault_authority,
+        token::token_program = token_b_program,
+    )]
+    pub protocol_fee_vault_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetProtocolFeeShare<'info> {
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub protocol_fee_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool: Account<'info, AmmPool>,
+
+    /// Protocol fee vault for token A; must exist before a share is taken
+    #[account(
+        seeds = [AmmPool::PROTOCOL_FEE_VAULT_SEED, pool.key().as_ref(), pool.token_a_mint.as_ref()],
+        bump
+    )]
+    pub protocol_fee_vault_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// Protocol fee vault for token B; must exist before a share is taken
+    #[account(
+        seeds = [AmmPool::PROTOCOL_FEE_VAULT_SEED, pool.key().as_ref(), pool.token_b_mint.as_ref()],
+        bump
+    )]
+    pub protocol_fee_vault_b: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub protocol_fee_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Protocol fee vault for token A
+    #[account(
+        mut,
+        seeds = [AmmPool::PROTOCOL_FEE_VAULT_SEED, pool.key().as_ref(), token_a_mint.key().as_ref()],
+        bump
+    )]
+    pub protocol_fee_vault_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// Protocol fee vault for token B
+    #[account(
+        mut,
+        seeds = [AmmPool::PROTOCOL_FEE_VAULT_SEED, pool.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub protocol_fee_vault_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury token A account receiving the collected fees
+    #[account(mut, token::mint = token_a_mint)]
+    pub treasury_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury token B account receiving the collected fees
+    #[account(mut, token::mint = token_b_mint)]
+    pub treasury_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+/// Create a pool's protocol fee vaults. Pools without a protocol fee share of
+/// their own start taking the AMM config's default once the vaults exist.
+pub fn initialize_protocol_fee_vaults(ctx: Context<InitializeProtocolFeeVaults>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    if pool.protocol_fee_share_bps() == 0 {
+        pool.set_protocol_fee_share(ctx.accounts.amm_config.protocol_fee_share_bps)?;
+    }
+
+    msg!("Protocol fee vaults initialized for pool: {}", pool.key());
+    msg!("Token A fee vault: {}", ctx.accounts.protocol_fee_vault_a.key());
+    msg!("Token B fee vault: {}", ctx.accounts.protocol_fee_vault_b.key());
+    msg!("Protocol fee share: {} bps", pool.protocol_fee_share_bps());
+    Ok(())
+}
+
+pub fn set_protocol_fee_share(ctx: Context<SetProtocolFeeShare>, share_bps: u64) -> Result<()> {
+    ctx.accounts.global_config.require_protocol_fee_authority(&ctx.accounts.protocol_fee_authority.key())?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.set_protocol_fee_share(share_bps)?;
+
+    msg!("Protocol fee share set to {} bps of the swap fee", share_bps);
+    Ok(())
+}
+
+/// Sweep both protocol fee vaults of a pool into the treasury accounts
+pub fn collect_protocol_fees<'info>(ctx: Context<'_, '_, '_, 'info, CollectProtocolFees<'info>>) -> Result<()> {
+    ctx.accounts.global_config.require_protocol_fee_authority(&ctx.accounts.protocol_fee_authority.key())?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let vault_authority_bump = ctx.accounts.pool.vault_authority_bump;
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
+
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(&mut ctx.accounts.pool)?;
+
+    for (fee_vault, mint, treasury, token_program) in [
+        (&ctx.accounts.protocol_fee_vault_a, &ctx.accounts.token_a_mint, &ctx.accounts.treasury_token_a, &ctx.accounts.token_a_program),
+        (&ctx.accounts.protocol_fee_vault_b, &ctx.accounts.token_b_mint, &ctx.accounts.treasury_token_b, &ctx.accounts.token_b_program),
+    ] {
+        let amount = fee_vault.amount;
+        if amount == 0 {
+            continue;
+        }
+        let transfer_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: fee_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: treasury.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked_with_hook(transfer_ctx, amount, mint.decimals, ctx.remaining_accounts)?;
+        msg!("Collected {} protocol fees of mint {}", amount, mint.key());
+    }
+
+    ctx.accounts.pool.unlock();
+    Ok(())
+}
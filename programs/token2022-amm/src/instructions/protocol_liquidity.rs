@@ -0,0 +1,305 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked, mint_to, MintTo,
+};
+use crate::state::{AmmPool, lock_pool, AmmConfig, TransferHookWhitelist, PoolCurve, PoolSide, GlobalConfig, PairOracle, enforce_oracle_admission, ProtocolLiquidity};
+use crate::state::math::{mul_div_floor, Q64_SHIFT};
+use crate::error::AmmError;
+use crate::events::LiquidityAdded;
+use crate::hook_interface::transfer_checked_with_hook;
+
+#[derive(Accounts)]
+pub struct BootstrapPool<'info> {
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        has_one = lp_mint @ AmmError::InvalidLpMint,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault,
+        constraint = !pool.is_paused() @ AmmError::PoolPaused,
+        constraint = !pool.is_withdraw_only() @ AmmError::PoolWithdrawOnly,
+        constraint = !pool.locked @ AmmError::PoolLocked,
+        constraint = pool.total_lp_supply == 0 @ AmmError::PoolAlreadyBootstrapped
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    /// Protocol treasury seeding the pool
+    #[account(mut, address = amm_config.treasury @ AmmError::InvalidTreasury)]
+    pub treasury: Signer<'info>,
+
+    /// AMM config naming the treasury and the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = !amm_config.paused @ AmmError::AmmPaused
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Transfer Hook Whitelist for validation (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+
+    /// Global config holding the oracle admission settings
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// CHECK: Pair oracle PDA; may be uninitialized when the pair has no oracle
+    #[account(
+        seeds = [PairOracle::SEED, pool.token_a_mint.as_ref(), pool.token_b_mint.as_ref()],
+        bump
+    )]
+    pub pair_oracle: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = treasury,
+        space = 8 + std::mem::size_of::<ProtocolLiquidity>(),
+        seeds = [ProtocolLiquidity::SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub protocol_liquidity: Account<'info, ProtocolLiquidity>,
+
+    /// Vault holding the bootstrap LP tokens, owned by `protocol_liquidity`
+    #[account(
+        init,
+        payer = treasury,
+        token::mint = lp_mint,
+        token::authority = protocol_liquidity,
+        token::token_program = token_program,
+    )]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury's token A account
+    #[account(mut, token::mint = token_a_mint, token::authority = treasury)]
+    pub treasury_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury's token B account
+    #[account(mut, token::mint = token_b_mint, token::authority = treasury)]
+    pub treasury_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's token A vault
+    #[account(mut)]
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's token B vault
+    #[account(mut)]
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's LP token mint
+    #[account(mut)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token program owning the LP mint (SPL Token, or Token-2022 for newer pools)
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: Transfer Hook accounts of either mint, if any
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProtocolLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [ProtocolLiquidity::SEED, protocol_liquidity.pool.as_ref()],
+        bump = protocol_liquidity.bump,
+        has_one = treasury @ AmmError::InvalidTreasury,
+        has_one = lp_vault
+    )]
+    pub protocol_liquidity: Account<'info, ProtocolLiquidity>,
+
+    pub treasury: Signer<'info>,
+
+    /// Vault holding the bootstrap LP tokens
+    #[account(mut)]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury's LP token account
+    #[account(mut, token::mint = lp_mint)]
+    pub treasury_lp_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's LP token mint
+    #[account(
+        address = lp_vault.mint @ AmmError::InvalidLpMint,
+        mint::token_program = token_program
+    )]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token program owning the LP mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Seed an empty pool from the protocol treasury with `amount_a` of token A
+/// and the matching token B at `price_x64` (token A priced in token B,
+/// Q64.64). The LP tokens are locked in a treasury-controlled PDA for
+/// `lock_duration` seconds.
+pub fn bootstrap_pool<'info>(
+    ctx: Context<'_, '_, '_, 'info, BootstrapPool<'info>>,
+    amount_a: u64,
+    price_x64: u128,
+    lock_duration: i64,
+) -> Result<()> {
+    require!(amount_a > 0 && price_x64 > 0, AmmError::InvalidAmount);
+    require!(lock_duration > 0, AmmError::InvalidLockDuration);
+    let now = Clock::get()?.unix_timestamp;
+
+    let pool_account_info = ctx.accounts.pool.to_account_info();
+    let pool = &mut ctx.accounts.pool;
+    // A stable pool's price has no closed form in its reserves; seed it with
+    // a regular deposit instead
+    require!(!matches!(pool.curve, PoolCurve::StableSwap { .. }), AmmError::UnsupportedPoolCurve);
+
+    // On a weighted pool the spot price also scales with the weights
+    let (weight_a, weight_b) = pool.curve.weights(PoolSide::A);
+    let amount_b = mul_div_floor(amount_a as u128, price_x64, 1u128 << Q64_SHIFT)?;
+    let amount_b = mul_div_floor(amount_b, weight_b as u128, weight_a as u128)?;
+    let amount_b = u64::try_from(amount_b).map_err(|_| AmmError::MathOverflow)?;
+    require!(amount_b > 0, AmmError::InvalidAmount);
+
+    let (lp_tokens_to_mint, amount_a, amount_b) = pool.calculate_deposit(amount_a, amount_b)?;
+    let pool_bump = pool.bump;
+    let fee_tier_seed = pool.fee_tier.to_le_bytes();
+    let (token_a_mint_key, token_b_mint_key) = (pool.token_a_mint, pool.token_b_mint);
+
+    // A pair with an oracle cannot be seeded far from the oracle price
+    enforce_oracle_admission(
+        &ctx.accounts.global_config,
+        &ctx.accounts.pair_oracle.to_account_info(),
+        pool,
+        amount_a,
+        amount_b,
+        now,
+    )?;
+
+    // Each mint's Transfer Hook, if it has one, must be whitelisted
+    let whitelist = &ctx.accounts.whitelist;
+    whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.token_a_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    whitelist.validate_mint_hook_with_entries(
+        &whitelist.key(),
+        &ctx.accounts.token_b_mint.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+
+    let treasury = &ctx.accounts.treasury;
+    for (from, mint, to, token_program, amount) in [
+        (&ctx.accounts.treasury_token_a, &ctx.accounts.token_a_mint, &ctx.accounts.pool_token_a_vault, &ctx.accounts.token_a_program, amount_a),
+        (&ctx.accounts.treasury_token_b, &ctx.accounts.token_b_mint, &ctx.accounts.pool_token_b_vault, &ctx.accounts.token_b_program, amount_b),
+    ] {
+        let transfer_ctx = CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: treasury.to_account_info(),
+            },
+        );
+        transfer_checked_with_hook(transfer_ctx, amount, mint.decimals, ctx.remaining_accounts)?;
+    }
+
+    // Mint the LP tokens into the timelocked vault
+    let pool_seeds: &[&[u8]] = &[
+        AmmPool::SEED,
+        token_a_mint_key.as_ref(),
+        token_b_mint_key.as_ref(),
+        fee_tier_seed.as_ref(),
+        &[pool_bump],
+    ];
+    let signer_seeds = &[pool_seeds];
+
+    let mint_lp_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.lp_vault.to_account_info(),
+            authority: pool_account_info,
+        },
+        signer_seeds,
+    );
+    mint_to(mint_lp_ctx, lp_tokens_to_mint)?;
+
+    pool.add_liquidity(amount_a, amount_b, lp_tokens_to_mint)?;
+    pool.unlock();
+
+    let unlock_at = now.checked_add(lock_duration).ok_or(AmmError::MathOverflow)?;
+    ctx.accounts.protocol_liquidity.initialize(
+        pool.key(),
+        treasury.key(),
+        ctx.accounts.lp_vault.key(),
+        lp_tokens_to_mint,
+        unlock_at,
+        ctx.bumps.protocol_liquidity,
+    )?;
+
+    emit!(LiquidityAdded {
+        pool: pool.key(),
+        user: treasury.key(),
+        amount_a,
+        amount_b,
+        lp_tokens_minted: lp_tokens_to_mint,
+        timestamp: now,
+    });
+
+    msg!("Pool bootstrapped by treasury {}", treasury.key());
+    msg!("Token A: {}, Token B: {}", amount_a, amount_b);
+    msg!("LP Tokens: {} locked until {}", lp_tokens_to_mint, unlock_at);
+    Ok(())
+}
+
+/// Withdraw bootstrap LP tokens to the treasury once the timelock has passed
+pub fn withdraw_protocol_liquidity(
+    ctx: Context<WithdrawProtocolLiquidity>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, AmmError::InvalidAmount);
+
+    let protocol_liquidity = &mut ctx.accounts.protocol_liquidity;
+    protocol_liquidity.record_withdrawal(amount, Clock::get()?.unix_timestamp)?;
+
+    let protocol_liquidity_seeds: &[&[u8]] = &[
+        ProtocolLiquidity::SEED,
+        protocol_liquidity.pool.as_ref(),
+        &[protocol_liquidity.bump],
+    ];
+    let signer_seeds = &[protocol_liquidity_seeds];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.lp_vault.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.treasury_lp_token.to_account_info(),
+            authority: protocol_liquidity.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer_checked(transfer_ctx, amount, ctx.accounts.lp_mint.decimals)?;
+
+    msg!("Withdrew {} protocol LP tokens from pool {}", amount, protocol_liquidity.pool);
+    msg!("{} remain in the vault", protocol_liquidity.lp_amount);
+    Ok(())
+}
@@ -0,0 +1,342 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::{AmmPool, PoolSide, LpPosition, amounts_after_exit_fee};
+use crate::error::AmmError;
+use crate::token_extensions::{amount_to_ui_string, gross_amount_for_net, interest_rate_bps, net_amount_for_gross, transfer_fee_for};
+
+/// Maximum candidate routes compared by one `quote_routes` call
+pub const MAX_QUOTE_ROUTES: usize = 8;
+
+/// Maximum hops in a single candidate route
+pub const MAX_ROUTE_HOPS: usize = 4;
+
+/// Swap quote returned via return data.
+/// Part of the CPI surface for integrators: fields are only ever appended.
+/// Raw amounts are in base units; UI amounts are decimal strings that account for
+/// mint decimals and interest-bearing scaling.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SwapQuote {
+    /// Amount the user sends
+    pub amount_in: u64,
+    /// Transfer fee withheld on the way into the pool vault
+    pub input_transfer_fee: u64,
+    /// Amount the pool sends
+    pub amount_out: u64,
+    /// Transfer fee withheld on the way to the user
+    pub output_transfer_fee: u64,
+    /// Amount the user actually receives
+    pub amount_received: u64,
+    /// UI-adjusted `amount_in`
+    pub amount_in_ui: String,
+    /// UI-adjusted `amount_received`
+    pub amount_received_ui: String,
+}
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    #[account(
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    /// Token A mint
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+}
+
+pub fn quote_swap(ctx: Context<QuoteSwap>, amount_in: u64, input_side: PoolSide) -> Result<SwapQuote> {
+    let pool = &ctx.accounts.pool;
+    let clock = Clock::get()?;
+    let (input_mint, output_mint) = match input_side {
+        PoolSide::A => (&ctx.accounts.token_a_mint, &ctx.accounts.token_b_mint),
+        PoolSide::B => (&ctx.accounts.token_b_mint, &ctx.accounts.token_a_mint),
+    };
+    let input_mint_info = input_mint.to_account_info();
+    let output_mint_info = output_mint.to_account_info();
+
+    let amount_out = pool.calculate_swap_output(input_side, amount_in)?;
+    let input_transfer_fee = transfer_fee_for(&input_mint_info, amount_in, clock.epoch)?;
+    let output_transfer_fee = transfer_fee_for(&output_mint_info, amount_out, clock.epoch)?;
+    let amount_received = amount_out
+        .checked_sub(output_transfer_fee)
+        .ok_or(AmmError::MathOverflow)?;
+
+    Ok(SwapQuote {
+        amount_in,
+        input_transfer_fee,
+        amount_out,
+        output_transfer_fee,
+        amount_received,
+        amount_in_ui: amount_to_ui_string(
+            &input_mint_info,
+            amount_in,
+            input_mint.decimals,
+            clock.unix_timestamp,
+        )?,
+        amount_received_ui: amount_to_ui_string(
+            &output_mint_info,
+            amount_received,
+            output_mint.decimals,
+            clock.unix_timestamp,
+        )?,
+    })
+}
+
+/// LP position valuation returned via return data.
+/// Raw amounts are in base units; UI amounts account for mint decimals and
+/// interest-bearing scaling, which makes them drift from the raw amounts the
+/// pool prices in.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LpValueQuote {
+    /// LP tokens valued
+    pub lp_amount: u64,
+    /// Token A redeemable for `lp_amount`
+    pub token_a_amount: u64,
+    /// Token B redeemable for `lp_amount`
+    pub token_b_amount: u64,
+    /// UI-adjusted `token_a_amount`
+    pub token_a_ui: String,
+    /// UI-adjusted `token_b_amount`
+    pub token_b_ui: String,
+    /// Current interest rate of token A (basis points per year), if it is interest-bearing
+    pub interest_rate_a_bps: Option<i16>,
+    /// Current interest rate of token B (basis points per year), if it is interest-bearing
+    pub interest_rate_b_bps: Option<i16>,
+}
+
+/// Value `lp_amount` LP tokens in raw and UI amounts of both pool tokens
+pub fn quote_lp_value(ctx: Context<QuoteSwap>, lp_amount: u64) -> Result<LpValueQuote> {
+    let pool = &ctx.accounts.pool;
+    let now = Clock::get()?.unix_timestamp;
+    let token_a_mint_info = ctx.accounts.token_a_mint.to_account_info();
+    let token_b_mint_info = ctx.accounts.token_b_mint.to_account_info();
+
+    let (token_a_amount, token_b_amount) = pool.calculate_tokens_for_lp_burn(lp_amount)?;
+
+    Ok(LpValueQuote {
+        lp_amount,
+        token_a_amount,
+        token_b_amount,
+        token_a_ui: amount_to_ui_string(
+            &token_a_mint_info,
+            token_a_amount,
+            ctx.accounts.token_a_mint.decimals,
+            now,
+        )?,
+        token_b_ui: amount_to_ui_string(
+            &token_b_mint_info,
+            token_b_amount,
+            ctx.accounts.token_b_mint.decimals,
+            now,
+        )?,
+        interest_rate_a_bps: interest_rate_bps(&token_a_mint_info)?,
+        interest_rate_b_bps: interest_rate_bps(&token_b_mint_info)?,
+    })
+}
+
+/// Liquidity add quote returned via return data, in base units
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AddLiquidityQuote {
+    /// LP tokens the deposit mints
+    pub lp_tokens: u64,
+    /// Token A the deposit takes (at most the amount offered)
+    pub amount_a: u64,
+    /// Token B the deposit takes (at most the amount offered)
+    pub amount_b: u64,
+}
+
+/// Liquidity removal quote returned via return data, in base units
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RemoveLiquidityQuote {
+    /// LP tokens burned
+    pub lp_tokens: u64,
+    /// Token A paid out, after the exit fee
+    pub amount_a: u64,
+    /// Token B paid out, after the exit fee
+    pub amount_b: u64,
+    /// Token A kept in reserves as exit fee
+    pub exit_fee_a: u64,
+    /// Token B kept in reserves as exit fee
+    pub exit_fee_b: u64,
+}
+
+#[derive(Accounts)]
+pub struct QuoteLiquidity<'info> {
+    pub pool: Account<'info, AmmPool>,
+
+    /// LP position whose holding time lowers the exit fee, if any
+    #[account(constraint = lp_position.pool == pool.key() @ AmmError::InvalidLpPosition)]
+    pub lp_position: Option<Account<'info, LpPosition>>,
+}
+
+/// Quote an `add_liquidity` of up to `max_amount_a` / `max_amount_b` without
+/// moving any tokens
+pub fn quote_add_liquidity(
+    ctx: Context<QuoteLiquidity>,
+    max_amount_a: u64,
+    max_amount_b: u64,
+) -> Result<AddLiquidityQuote> {
+    let (lp_tokens, amount_a, amount_b) = ctx.accounts.pool.calculate_deposit(max_amount_a, max_amount_b)?;
+
+    Ok(AddLiquidityQuote {
+        lp_tokens,
+        amount_a,
+        amount_b,
+    })
+}
+
+/// Quote a `remove_liquidity` of `lp_tokens` without moving any tokens,
+/// charging the exit fee the given position (or, without one, a fresh
+/// deposit) would pay
+pub fn quote_remove_liquidity(ctx: Context<QuoteLiquidity>, lp_tokens: u64) -> Result<RemoveLiquidityQuote> {
+    let pool = &ctx.accounts.pool;
+    let now = Clock::get()?.unix_timestamp;
+
+    let (gross_a, gross_b) = pool.calculate_tokens_for_lp_burn(lp_tokens)?;
+    let (tracked, entry_time) = match ctx.accounts.lp_position.as_ref() {
+        Some(position) => (lp_tokens.min(position.lp_amount), position.entry_time),
+        None => (0, now),
+    };
+    let (amount_a, amount_b) = amounts_after_exit_fee(pool, lp_tokens, tracked, entry_time, gross_a, gross_b, now)?;
+
+    Ok(RemoveLiquidityQuote {
+        lp_tokens,
+        amount_a,
+        amount_b,
+        exit_fee_a: gross_a - amount_a,
+        exit_fee_b: gross_b - amount_b,
+    })
+}
+
+/// Transfer fee round trip returned via return data, in base units
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TransferAmountQuote {
+    /// Amount the sender sends
+    pub gross_amount: u64,
+    /// Transfer fee withheld by the mint
+    pub transfer_fee: u64,
+    /// Amount the recipient is credited
+    pub net_amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct QuoteTransferAmount<'info> {
+    /// Mint whose transfer fee applies
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+/// Convert between what a sender sends and what a recipient receives through
+/// a TransferFee mint in the current epoch. With `amount_is_net`, `amount` is
+/// the target credit and the gross amount to send is computed; otherwise
+/// `amount` is what gets sent and the resulting credit is computed.
+pub fn quote_transfer_amount(
+    ctx: Context<QuoteTransferAmount>,
+    amount: u64,
+    amount_is_net: bool,
+) -> Result<TransferAmountQuote> {
+    let mint_info = ctx.accounts.mint.to_account_info();
+    let epoch = Clock::get()?.epoch;
+
+    let (gross_amount, net_amount) = if amount_is_net {
+        (gross_amount_for_net(&mint_info, amount, epoch)?, amount)
+    } else {
+        (amount, net_amount_for_gross(&mint_info, amount, epoch)?)
+    };
+
+    Ok(TransferAmountQuote {
+        gross_amount,
+        transfer_fee: gross_amount - net_amount,
+        net_amount,
+    })
+}
+
+/// Route comparison returned via return data.
+/// Amounts are pool outputs in base units of the output mint, before any
+/// Token-2022 transfer fees.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RouteQuote {
+    /// Index of the route with the highest output
+    pub best_route_index: u8,
+    /// Output of the best route
+    pub best_amount_out: u64,
+    /// Output of every candidate route, in order (0 = route cannot fill)
+    pub route_outputs: Vec<u64>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount_in: u64, input_mint: Pubkey, output_mint: Pubkey)]
+pub struct QuoteRoutes<'info> {
+    /// Mint every route must end at
+    #[account(address = output_mint @ AmmError::InvalidRoute)]
+    pub output_token_mint: InterfaceAccount<'info, Mint>,
+    // remaining_accounts: pools of every candidate route, concatenated in route order
+}
+
+/// Quote `amount_in` of `input_mint` through each candidate route and pick the
+/// one yielding the most `output_mint`. `route_lengths[i]` is the number of
+/// pools (hops) in route `i`; each hop must share its input mint with the
+/// previous hop's output, and every route must end at `output_mint`.
+pub fn quote_routes<'info>(
+    ctx: Context<'_, '_, 'info, 'info, QuoteRoutes<'info>>,
+    amount_in: u64,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    route_lengths: Vec<u8>,
+) -> Result<RouteQuote> {
+    let remaining = ctx.remaining_accounts;
+
+    require!(amount_in > 0, AmmError::InvalidAmount);
+    require!(!route_lengths.is_empty(), AmmError::InvalidRoute);
+    require!(route_lengths.len() <= MAX_QUOTE_ROUTES, AmmError::TooManyRoutes);
+    require!(
+        route_lengths.iter().all(|&len| len > 0 && (len as usize) <= MAX_ROUTE_HOPS),
+        AmmError::InvalidRoute
+    );
+    let total_hops: usize = route_lengths.iter().map(|&len| len as usize).sum();
+    require!(remaining.len() == total_hops, AmmError::InvalidRemainingAccounts);
+
+    let mut route_outputs = Vec::with_capacity(route_lengths.len());
+    let mut offset = 0;
+    for &len in route_lengths.iter() {
+        let hops = &remaining[offset..offset + len as usize];
+        offset += len as usize;
+
+        let mut current_mint = input_mint;
+        let mut amount = Some(amount_in);
+        for pool_info in hops {
+            let pool = Account::<AmmPool>::try_from(pool_info)?;
+            let (input_side, next_mint) = if pool.token_a_mint == current_mint {
+                (PoolSide::A, pool.token_b_mint)
+            } else if pool.token_b_mint == current_mint {
+                (PoolSide::B, pool.token_a_mint)
+            } else {
+                return err!(AmmError::InvalidRoute);
+            };
+
+            // A hop without enough liquidity just disqualifies this route
+            amount = amount.and_then(|amount| pool.calculate_swap_output_for_side(input_side, amount).ok());
+            current_mint = next_mint;
+        }
+        require_keys_eq!(current_mint, output_mint, AmmError::InvalidRoute);
+
+        route_outputs.push(amount.unwrap_or(0));
+    }
+
+    let (best_route_index, best_amount_out) = route_outputs
+        .iter()
+        .copied()
+        .enumerate()
+        .fold((0, 0), |best, (index, amount)| if amount > best.1 { (index, amount) } else { best });
+    require!(best_amount_out > 0, AmmError::InsufficientLiquidity);
+
+    msg!("Best route: {} ({} out)", best_route_index, best_amount_out);
+
+    Ok(RouteQuote {
+        best_route_index: best_route_index as u8,
+        best_amount_out,
+        route_outputs,
+    })
+}
@@ -0,0 +1,221 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, close_account, CloseAccount, burn, Burn};
+use crate::state::{AmmPool, lock_pool, GlobalConfig, PoolSettlement};
+use crate::error::AmmError;
+use crate::hook_interface::transfer_checked_with_hook;
+
+#[derive(Accounts)]
+pub struct StartWindDown<'info> {
+    #[account(
+        seeds = [GlobalConfig::SEED],
+        bump = global_config.bump,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<PoolSettlement>(),
+        seeds = [PoolSettlement::SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub settlement: Account<'info, PoolSettlement>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSettlement<'info> {
+    #[account(
+        mut,
+        has_one = lp_mint @ AmmError::InvalidLpMint,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(
+        mut,
+        seeds = [PoolSettlement::SEED, pool.key().as_ref()],
+        bump = settlement.bump,
+        has_one = pool
+    )]
+    pub settlement: Account<'info, PoolSettlement>,
+
+    pub user: Signer<'info>,
+
+    /// User's token A account
+    #[account(mut)]
+    pub user_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's token B account
+    #[account(mut)]
+    pub user_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's LP token account
+    #[account(
+        mut,
+        constraint = user_lp_token.mint == lp_mint.key() @ AmmError::InvalidLpMint,
+        constraint = user_lp_token.owner == user.key() @ AmmError::InvalidTokenAccountOwner
+    )]
+    pub user_lp_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Pool's token A vault
+    #[account(mut)]
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's token B vault
+    #[account(mut)]
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pool's LP token mint
+    #[account(mut)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token A mint
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token B mint
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Pool authority, receives the rent of the closed pool accounts
+    #[account(mut, address = pool.authority @ AmmError::InvalidPoolAuthority)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// Token program owning the LP mint (SPL Token, or Token-2022 for newer pools)
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+/// Freeze a pool and snapshot what its LPs are owed
+pub fn start_wind_down(ctx: Context<StartWindDown>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.pool;
+    let (token_a_amount, token_b_amount, lp_supply) = pool.start_wind_down(now)?;
+
+    let settlement = &mut ctx.accounts.settlement;
+    settlement.initialize(
+        pool.key(),
+        token_a_amount,
+        token_b_amount,
+        lp_supply,
+        now,
+        ctx.bumps.settlement,
+    )?;
+
+    msg!("Pool {} winding down", pool.key());
+    msg!("Settlement: {} token A, {} token B over {} LP tokens", token_a_amount, token_b_amount, lp_supply);
+    Ok(())
+}
+
+/// Redeem every LP token in the user's account for its pro-rata share of the
+/// settlement. The final claim closes the vaults, the settlement and the pool,
+/// unless IOUs are still outstanding against the vaults.
+pub fn claim_settlement<'info>(ctx: Context<'_, '_, '_, 'info, ClaimSettlement<'info>>) -> Result<()> {
+    require!(ctx.accounts.pool.is_winding_down(), AmmError::PoolNotWindingDown);
+
+    let lp_tokens = ctx.accounts.user_lp_token.amount;
+    let (token_a_amount, token_b_amount) = ctx.accounts.settlement.claim(lp_tokens)?;
+
+    // Burn LP tokens from user
+    let burn_lp_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            from: ctx.accounts.user_lp_token.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    burn(burn_lp_ctx, lp_tokens)?;
+
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(&mut ctx.accounts.pool)?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[ctx.accounts.pool.vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
+
+    for (vault, mint, destination, token_program, amount) in [
+        (&ctx.accounts.pool_token_a_vault, &ctx.accounts.token_a_mint, &ctx.accounts.user_token_a, &ctx.accounts.token_a_program, token_a_amount),
+        (&ctx.accounts.pool_token_b_vault, &ctx.accounts.token_b_mint, &ctx.accounts.user_token_b, &ctx.accounts.token_b_program, token_b_amount),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let transfer_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: destination.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked_with_hook(transfer_ctx, amount, mint.decimals, ctx.remaining_accounts)?;
+    }
+    ctx.accounts.pool.unlock();
+
+    msg!("Settlement claimed: {} LP tokens for {} token A and {} token B", lp_tokens, token_a_amount, token_b_amount);
+
+    let pool = &ctx.accounts.pool;
+    if !ctx.accounts.settlement.is_complete() || pool.iou_a_outstanding > 0 || pool.iou_b_outstanding > 0 {
+        return Ok(());
+    }
+
+    // Last LP out: close the vaults and the pool accounts
+    let pool_authority_info = ctx.accounts.pool_authority.to_account_info();
+    for vault in [&mut ctx.accounts.pool_token_a_vault, &mut ctx.accounts.pool_token_b_vault] {
+        vault.reload()?;
+        if vault.amount > 0 {
+            return Ok(());
+        }
+    }
+    for (vault, token_program) in [
+        (&ctx.accounts.pool_token_a_vault, &ctx.accounts.token_a_program),
+        (&ctx.accounts.pool_token_b_vault, &ctx.accounts.token_b_program),
+    ] {
+        let close_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: vault.to_account_info(),
+                destination: pool_authority_info.clone(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        close_account(close_ctx)?;
+    }
+    ctx.accounts.settlement.close(pool_authority_info.clone())?;
+    ctx.accounts.pool.close(pool_authority_info)?;
+
+    msg!("Pool {} fully settled and closed", pool_key);
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Test-only pool and governance state tooling, compiled in with the
+//! `test-utils` feature.
+//! Never enable this feature for a deployment build.
+//! The account structs stay in every build, see `feature_gated!`.
+#![cfg_attr(not(feature = "test-utils"), allow(unused_imports))]
+
+use anchor_lang::prelude::*;
+use crate::state::{AmmConfig, AmmPool, GovernanceConfig, GovernanceParams};
+use crate::error::AmmError;
+
+#[derive(Accounts)]
+pub struct DumpPoolState<'info> {
+    pub pool: Account<'info, AmmPool>,
+}
+
+#[derive(Accounts)]
+pub struct RestorePoolState<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidPoolAuthority
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGovernanceParams<'info> {
+    #[account(
+        mut,
+        seeds = [GovernanceConfig::SEED],
+        bump = governance_config.bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        has_one = authority @ AmmError::InvalidConfigAuthority
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Return the full pool state via return data
+#[cfg(feature = "test-utils")]
+pub fn dump_pool_state(ctx: Context<DumpPoolState>) -> Result<AmmPool> {
+    let pool = &ctx.accounts.pool;
+
+    msg!("Dumped pool state: {}", pool.key());
+    Ok((**pool).clone())
+}
+
+/// Overwrite the pool with a previously dumped state, field for field
+#[cfg(feature = "test-utils")]
+pub fn restore_pool_state(ctx: Context<RestorePoolState>, state: AmmPool) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.set_inner(state);
+
+    msg!("Restored pool state: {}", pool.key());
+    Ok(())
+}
+
+/// Replace the governance rules without a proposal or their validation, so a
+/// localnet suite can run a vote with a voting period of seconds
+#[cfg(feature = "test-utils")]
+pub fn set_governance_params(ctx: Context<SetGovernanceParams>, params: GovernanceParams) -> Result<()> {
+    ctx.accounts.governance_config.params = params;
+
+    msg!("Governance params overridden: voting period {}s", params.voting_period);
+    Ok(())
+}
@@ -1,49 +1,122 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Mint};
-use anchor_spl::token_2022::{Token2022, transfer_checked, TransferChecked};
-use crate::state::{AmmPool, TransferHookWhitelist};
+use anchor_lang::Ids;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::{AmmPool, lock_pool, mul_div_ceil, ratio_x64, AmmConfig, TransferHookWhitelist, HookValidationCache, PoolSide, TakerAllowlist, enforce_taker_allowlist, HookFailureLog, enforce_hook_notional_cap, Observation, record_observation, refresh_dynamic_fee};
+use crate::instructions::hook_cache::check_mint_hook_cached;
+use crate::instructions::quote::MAX_ROUTE_HOPS;
+use crate::hook_interface::{invoke_legacy_hook_if_needed, transfer_checked_with_hook, with_hook_compute_budget, HookInterfaceVersion};
 use crate::error::AmmError;
+use crate::token_extensions::{
+    create_ata_if_missing, is_native_mint, memo_if_required, require_no_cpi_guard, require_public_credits, wrap_sol,
+};
+use crate::events::{SwapExecuted, SwapPriceRealized};
 
 #[derive(Accounts)]
 pub struct Swap<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        has_one = token_a_mint @ AmmError::InvalidTokenPair,
+        has_one = token_b_mint @ AmmError::InvalidTokenPair,
+        constraint = pool.token_a_vault == pool_token_a_vault.key() @ AmmError::InvalidVault,
+        constraint = pool.token_b_vault == pool_token_b_vault.key() @ AmmError::InvalidVault,
+        constraint = !pool.is_paused() @ AmmError::PoolPaused,
+        constraint = !pool.is_withdraw_only() @ AmmError::PoolWithdrawOnly,
+        constraint = !pool.locked @ AmmError::PoolLocked
+    )]
     pub pool: Account<'info, AmmPool>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
-    /// User's token A account (input)
+    /// User's token A account
     #[account(mut)]
-    pub user_token_a: Account<'info, TokenAccount>,
+    pub user_token_a: InterfaceAccount<'info, TokenAccount>,
     
-    /// User's token B account (output)
+    /// User's token B account
     #[account(mut)]
-    pub user_token_b: Account<'info, TokenAccount>,
+    pub user_token_b: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
     
     /// Pool's token A vault
     #[account(mut)]
-    pub pool_token_a_vault: Account<'info, TokenAccount>,
+    pub pool_token_a_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's token B vault
     #[account(mut)]
-    pub pool_token_b_vault: Account<'info, TokenAccount>,
+    pub pool_token_b_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Token A mint
-    pub token_a_mint: Account<'info, Mint>,
+    #[account(mint::token_program = token_a_program)]
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
     
     /// Token B mint
-    pub token_b_mint: Account<'info, Mint>,
+    #[account(mint::token_program = token_b_program)]
+    pub token_b_mint: InterfaceAccount<'info, Mint>,
+    
+    /// AMM config gating trading and naming the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = !amm_config.paused @ AmmError::AmmPaused
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
     
-    /// Transfer Hook Whitelist for validation
+    /// Transfer Hook Whitelist for validation (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
     pub whitelist: Account<'info, TransferHookWhitelist>,
     
-    pub token_program: Program<'info, Token>,
-    pub token_2022_program: Program<'info, Token2022>,
+    /// Optional cached hook approval for (token A mint, user)
+    pub token_a_hook_cache: Option<Account<'info, HookValidationCache>>,
+    
+    /// Optional cached hook approval for (token B mint, user)
+    pub token_b_hook_cache: Option<Account<'info, HookValidationCache>>,
+    
+    /// Taker allowlist, required when the pool is private
+    pub taker_allowlist: Option<Account<'info, TakerAllowlist>>,
+    
+    /// Optional reliability log of either pair mint, credited with a
+    /// successful transfer (both legs of a swap transfer)
+    #[account(
+        mut,
+        constraint = output_failure_log.mint == token_a_mint.key()
+            || output_failure_log.mint == token_b_mint.key() @ AmmError::InvalidTokenPair
+    )]
+    pub output_failure_log: Option<Account<'info, HookFailureLog>>,
+    
+    /// TWAP observation, required when the pool has one
+    #[account(mut)]
+    pub observation: Option<Account<'info, Observation>>,
+    
+    /// Protocol fee vault of the input mint, required while the pool takes a protocol fee
+    #[account(mut)]
+    pub protocol_fee_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Token program owning token A (SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning token B (SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct SwapExactTokensForTokens<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = pool.side_of_mint(&input_mint.key()).is_some() @ AmmError::InvalidTokenPair,
+        constraint = pool.side_of_mint(&output_mint.key()) == pool.side_of_mint(&input_mint.key()).map(PoolSide::opposite) @ AmmError::InvalidTokenPair,
+        constraint = pool.side_of_vault(&pool_input_vault.key()) == pool.side_of_mint(&input_mint.key()) @ AmmError::InvalidVault,
+        constraint = pool.side_of_vault(&pool_output_vault.key()) == pool.side_of_mint(&output_mint.key()) @ AmmError::InvalidVault,
+        constraint = !pool.is_paused() @ AmmError::PoolPaused,
+        constraint = !pool.is_withdraw_only() @ AmmError::PoolWithdrawOnly,
+        constraint = !pool.locked @ AmmError::PoolLocked
+    )]
     pub pool: Account<'info, AmmPool>,
     
     #[account(mut)]
@@ -51,120 +124,1121 @@ pub struct SwapExactTokensForTokens<'info> {
     
     /// User's input token account
     #[account(mut)]
-    pub user_input_token: Account<'info, TokenAccount>,
+    pub user_input_token: InterfaceAccount<'info, TokenAccount>,
     
-    /// User's output token account
+    /// CHECK: user's output token account; created as the user's associated
+    /// token account of the output mint if it does not exist yet
     #[account(mut)]
-    pub user_output_token: Account<'info, TokenAccount>,
+    pub user_output_token: UncheckedAccount<'info>,
+    
+    /// CHECK: PDA that owns the pool vaults; only signs via seeds
+    #[account(
+        seeds = [pool.key().as_ref(), AmmPool::VAULT_AUTHORITY_SEED],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
     
     /// Pool's input token vault
     #[account(mut)]
-    pub pool_input_vault: Account<'info, TokenAccount>,
+    pub pool_input_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Pool's output token vault
     #[account(mut)]
-    pub pool_output_vault: Account<'info, TokenAccount>,
+    pub pool_output_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Input token mint
-    pub input_mint: Account<'info, Mint>,
+    #[account(mint::token_program = input_token_program)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
     
     /// Output token mint
-    pub output_mint: Account<'info, Mint>,
+    #[account(mint::token_program = output_token_program)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
     
-    /// Transfer Hook Whitelist for validation
+    /// AMM config gating trading and naming the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = !amm_config.paused @ AmmError::AmmPaused
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// Transfer Hook Whitelist for validation (the pool's own list if it has one)
+    #[account(address = pool.effective_whitelist(amm_config.whitelist) @ AmmError::WhitelistMismatch)]
     pub whitelist: Account<'info, TransferHookWhitelist>,
     
-    pub token_program: Program<'info, Token>,
-    pub token_2022_program: Program<'info, Token2022>,
+    /// Optional cached hook approval for (input mint, user)
+    pub input_hook_cache: Option<Account<'info, HookValidationCache>>,
+    
+    /// Optional cached hook approval for (output mint, user)
+    pub output_hook_cache: Option<Account<'info, HookValidationCache>>,
+    
+    /// Taker allowlist, required when the pool is private
+    pub taker_allowlist: Option<Account<'info, TakerAllowlist>>,
+    
+    /// Optional reliability log of the output mint, credited with a successful transfer
+    #[account(mut, constraint = output_failure_log.mint == output_mint.key() @ AmmError::InvalidTokenPair)]
+    pub output_failure_log: Option<Account<'info, HookFailureLog>>,
+    
+    /// TWAP observation, required when the pool has one
+    #[account(mut)]
+    pub observation: Option<Account<'info, Observation>>,
+    
+    /// Protocol fee vault of the input mint, required while the pool takes a protocol fee
+    #[account(mut)]
+    pub protocol_fee_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Token program owning the input mint (SPL Token or Token-2022)
+    pub input_token_program: Interface<'info, TokenInterface>,
+    
+    /// Token program owning the output mint (SPL Token or Token-2022)
+    pub output_token_program: Interface<'info, TokenInterface>,
+    
+    /// Associated Token program, required when `user_output_token` must be created
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    
+    /// System program, required when `user_output_token` must be created
+    pub system_program: Option<Program<'info, System>>,
 }
 
-pub fn swap(
-    ctx: Context<Swap>,
+/// Accounts each hop of a `swap_route` passes, in order: pool, vault authority,
+/// pool input vault, pool output vault, output mint, output token program,
+/// user output token account, observation and protocol fee vault (the last
+/// two are the program id when the pool has none)
+pub const ROUTE_HOP_ACCOUNTS: usize = 9;
+
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// User's account of the route's input mint
+    #[account(mut)]
+    pub user_input_token: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Input mint of the first hop
+    #[account(mint::token_program = input_token_program)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    
+    /// AMM config gating trading and naming the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = !amm_config.paused @ AmmError::AmmPaused
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// Transfer Hook Whitelist every hop's pool must validate against
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    /// Taker allowlist, required when a pool on the route is private
+    pub taker_allowlist: Option<Account<'info, TakerAllowlist>>,
+    
+    /// Token program owning the input mint (SPL Token or Token-2022)
+    pub input_token_program: Interface<'info, TokenInterface>,
+    
+    /// Associated Token program, required when a hop's user output account
+    /// must be created
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    
+    /// System program, required when a hop's user output account must be created
+    pub system_program: Option<Program<'info, System>>,
+    
+    // remaining_accounts: ROUTE_HOP_ACCOUNTS per hop in route order, then
+    // Transfer Hook accounts and the SPL Memo program if an output account requires memos
+}
+
+/// Most independent swaps one `batch_swap` may execute
+pub const MAX_BATCH_SWAP_LEGS: usize = 4;
+
+/// Accounts each leg of a `batch_swap` passes, in order: user input token
+/// account, input mint and input token program, then the leg's pool accounts
+/// laid out as for a `swap_route` hop
+pub const BATCH_LEG_ACCOUNTS: usize = 3 + ROUTE_HOP_ACCOUNTS;
+
+/// Amount and slippage bound of one `batch_swap` leg
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BatchSwapLeg {
+    pub amount_in: u64,
+    /// Minimum the user must receive from this leg, after transfer fees
+    pub min_amount_out: u64,
+}
+
+#[derive(Accounts)]
+pub struct BatchSwap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// AMM config gating trading and naming the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = !amm_config.paused @ AmmError::AmmPaused
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// Transfer Hook Whitelist every leg's pool must validate against
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    /// Taker allowlist, required when a leg's pool is private
+    pub taker_allowlist: Option<Account<'info, TakerAllowlist>>,
+    
+    /// Associated Token program, required when a leg's user output account
+    /// must be created
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    
+    /// System program, required when a leg's user output account must be created
+    pub system_program: Option<Program<'info, System>>,
+    
+    // remaining_accounts: BATCH_LEG_ACCOUNTS per leg in `legs` order, then
+    // Transfer Hook accounts and the SPL Memo program if an output account requires memos
+}
+
+#[derive(Accounts)]
+pub struct SwapSolForToken<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// CHECK: user's associated WSOL account of `native_mint`; created in the
+    /// handler if it does not exist yet
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &user.key(),
+            &native_mint.key(),
+            &native_token_program.key()
+        ) @ AmmError::InvalidWrappedSolAccount
+    )]
+    pub user_wsol: UncheckedAccount<'info>,
+    
+    /// Native mint of `native_token_program` (SPL Token or Token-2022)
+    #[account(
+        constraint = is_native_mint(&native_mint.key()) @ AmmError::NotNativeMint,
+        mint::token_program = native_token_program
+    )]
+    pub native_mint: InterfaceAccount<'info, Mint>,
+    
+    /// AMM config gating trading and naming the whitelist
+    #[account(
+        seeds = [AmmConfig::SEED],
+        bump = amm_config.bump,
+        constraint = !amm_config.paused @ AmmError::AmmPaused
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    
+    /// Transfer Hook Whitelist the pool must validate against
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    /// Taker allowlist, required when the pool is private
+    pub taker_allowlist: Option<Account<'info, TakerAllowlist>>,
+    
+    /// Token program owning the native mint (SPL Token or Token-2022)
+    pub native_token_program: Interface<'info, TokenInterface>,
+    
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    
+    // remaining_accounts: the pool's ROUTE_HOP_ACCOUNTS laid out as for a
+    // `swap_route` hop, then Transfer Hook accounts and the SPL Memo program if
+    // the output account requires memos
+}
+
+pub fn swap<'info>(
+    ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
     amount_in: u64,
     min_amount_out: u64,
+    input_side: PoolSide,
+    max_price_impact_bps: Option<u16>,
+    deadline: i64,
 ) -> Result<()> {
+    enforce_deadline(deadline)?;
+    
     let user = &ctx.accounts.user;
-    let pool_account_info = ctx.accounts.pool.to_account_info();
+    let pool_key = ctx.accounts.pool.key();
+    let vault_authority_info = ctx.accounts.vault_authority.to_account_info();
+    
+    // Orient the pair: tokens enter on `input_side` and leave on the other
+    let (user_in, user_out, vault_in, vault_out, mint_in, mint_out, cache_in, cache_out, program_in, program_out) = match input_side {
+        PoolSide::A => (
+            &ctx.accounts.user_token_a,
+            &ctx.accounts.user_token_b,
+            &ctx.accounts.pool_token_a_vault,
+            &ctx.accounts.pool_token_b_vault,
+            &ctx.accounts.token_a_mint,
+            &ctx.accounts.token_b_mint,
+            ctx.accounts.token_a_hook_cache.as_ref(),
+            ctx.accounts.token_b_hook_cache.as_ref(),
+            &ctx.accounts.token_a_program,
+            &ctx.accounts.token_b_program,
+        ),
+        PoolSide::B => (
+            &ctx.accounts.user_token_b,
+            &ctx.accounts.user_token_a,
+            &ctx.accounts.pool_token_b_vault,
+            &ctx.accounts.pool_token_a_vault,
+            &ctx.accounts.token_b_mint,
+            &ctx.accounts.token_a_mint,
+            ctx.accounts.token_b_hook_cache.as_ref(),
+            ctx.accounts.token_a_hook_cache.as_ref(),
+            &ctx.accounts.token_b_program,
+            &ctx.accounts.token_a_program,
+        ),
+    };
     
     // Get pool data before mutable borrow
     let pool = &mut ctx.accounts.pool;
-    let amount_out = pool.calculate_swap_output(amount_in)?;
-    let pool_bump = pool.bump;
+    let amount_out = pool.calculate_swap_output(input_side, amount_in)?;
+    let vault_authority_bump = pool.vault_authority_bump;
     
     // Check slippage protection
     require!(
         amount_out >= min_amount_out,
         AmmError::InsufficientOutputAmount
     );
+    pool.enforce_max_price_impact(input_side, amount_in, amount_out, max_price_impact_bps)?;
     
-    // Validate transfer hooks for Token-2022 tokens
-    let _whitelist = &ctx.accounts.whitelist;
+    // Private pools only trade with allowlisted takers
+    enforce_taker_allowlist(
+        &pool.taker_allowlist,
+        ctx.accounts.taker_allowlist.as_ref(),
+        &user.key(),
+    )?;
+    
+    // Validate transfer hooks for Token-2022 tokens, skipping the parse when a
+    // fresh cache entry already vouches for this (mint, user)
+    let whitelist = &ctx.accounts.whitelist;
+    let hook_in = check_mint_hook_cached(
+        whitelist,
+        &mint_in.to_account_info(),
+        &user.key(),
+        cache_in,
+        ctx.remaining_accounts,
+    )?;
+    let hook_out = check_mint_hook_cached(
+        whitelist,
+        &mint_out.to_account_info(),
+        &user.key(),
+        cache_out,
+        ctx.remaining_accounts,
+    )?;
+    
+    // High-risk hooks cap how much a single transfer may move
+    enforce_hook_notional_cap(whitelist, hook_in, amount_in, ctx.remaining_accounts)?;
+    enforce_hook_notional_cap(whitelist, hook_out, amount_out, ctx.remaining_accounts)?;
     
-    // For now, we'll use Token-2022 transfers for all tokens to ensure hook validation
-    // In a production system, you would check the mint's extensions to determine if it has transfer hooks
+    // Confidential-transfer accounts may refuse the public payout outright
+    require_public_credits(pool.mint_extensions(input_side.opposite()), &user_out.to_account_info())?;
+    // CPI Guard would block the user-signed debit of the input account
+    require_no_cpi_guard(&user_in.to_account_info())?;
     
-    // Transfer tokens from user to pool using Token-2022
+    // Hook-bearing transfers run under the pool's hook compute budget, if set
+    let budget_in = pool.hook_compute_budget(hook_in);
+    let budget_out = pool.hook_compute_budget(hook_out);
+    
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+    
+    // Legacy-interface hooks are not invoked by Token-2022, run them here
+    with_hook_compute_budget(budget_in, || invoke_legacy_hook_if_needed(
+        hook_in.and_then(|id| whitelist.hook_interface_version(&id)),
+        hook_in,
+        &user_in.to_account_info(),
+        &mint_in.to_account_info(),
+        &vault_in.to_account_info(),
+        &user.to_account_info(),
+        amount_in,
+        ctx.remaining_accounts,
+    ))?;
+    with_hook_compute_budget(budget_out, || invoke_legacy_hook_if_needed(
+        hook_out.and_then(|id| whitelist.hook_interface_version(&id)),
+        hook_out,
+        &vault_out.to_account_info(),
+        &mint_out.to_account_info(),
+        &user_out.to_account_info(),
+        &vault_authority_info,
+        amount_out,
+        ctx.remaining_accounts,
+    ))?;
+    
+    // Transfer tokens from user to pool through the input mint's token program
     let transfer_ctx = CpiContext::new(
-        ctx.accounts.token_2022_program.to_account_info(),
+        program_in.to_account_info(),
         TransferChecked {
-            from: ctx.accounts.user_token_a.to_account_info(),
-            mint: ctx.accounts.token_a_mint.to_account_info(),
-            to: ctx.accounts.pool_token_a_vault.to_account_info(),
+            from: user_in.to_account_info(),
+            mint: mint_in.to_account_info(),
+            to: vault_in.to_account_info(),
             authority: user.to_account_info(),
         },
     );
     
-    transfer_checked(transfer_ctx, amount_in, ctx.accounts.token_a_mint.decimals)?;
+    with_hook_compute_budget(budget_in, || transfer_checked_with_hook(
+        transfer_ctx,
+        amount_in,
+        mint_in.decimals,
+        ctx.remaining_accounts,
+    ))?;
     
-    // Transfer tokens from pool to user using Token-2022
-    let pool_seeds: &[&[u8]] = &[b"pool", &[pool_bump]];
-    let signer_seeds = &[pool_seeds];
+    // Transfer tokens from pool to user through the output mint's token program
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
     
     let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_2022_program.to_account_info(),
+        program_out.to_account_info(),
         TransferChecked {
-            from: ctx.accounts.pool_token_b_vault.to_account_info(),
-            mint: ctx.accounts.token_b_mint.to_account_info(),
-            to: ctx.accounts.user_token_b.to_account_info(),
-            authority: pool_account_info.clone(),
+            from: vault_out.to_account_info(),
+            mint: mint_out.to_account_info(),
+            to: user_out.to_account_info(),
+            authority: vault_authority_info.clone(),
         },
         signer_seeds,
     );
+    memo_if_required(&user_out.to_account_info(), "swap", &pool_key, ctx.remaining_accounts)?;
+    
+    with_hook_compute_budget(budget_out, || transfer_checked_with_hook(
+        transfer_ctx,
+        amount_out,
+        mint_out.decimals,
+        ctx.remaining_accounts,
+    ))?;
     
-    transfer_checked(transfer_ctx, amount_out, ctx.accounts.token_b_mint.decimals)?;
+    if let Some(failure_log) = ctx.accounts.output_failure_log.as_mut() {
+        failure_log.record_success(Clock::get()?.slot);
+    }
     
-    // Update pool state
-    pool.update_swap_state(amount_in, amount_out)?;
+    // Accumulate the pre-trade price, then update pool state, splitting the
+    // protocol's share off the swap fee
+    let reserves_before = (pool.token_a_reserve, pool.token_b_reserve);
+    record_observation(pool, ctx.accounts.observation.as_mut(), Clock::get()?.unix_timestamp)?;
+    pool.update_swap_state(input_side, amount_in, amount_out)?;
+    let fee_amount = pool.calculate_swap_fee(amount_in)?;
+    let protocol_fee = pool.calculate_protocol_fee(fee_amount)?;
+    pool.debit_protocol_fee(input_side, protocol_fee)?;
+    pool.accrue_swap_fee(input_side, fee_amount - protocol_fee)?;
+    let balances = vault_balances(input_side, &vault_in.to_account_info(), &vault_out.to_account_info())?;
+    pool.enforce_swap_invariant(reserves_before, balances, input_side, protocol_fee)?;
+    refresh_dynamic_fee(pool, ctx.accounts.observation.as_ref(), Clock::get()?.unix_timestamp)?;
+    
+    with_hook_compute_budget(budget_in, || transfer_protocol_fee(
+        &pool_key,
+        &program_in.to_account_info(),
+        vault_in,
+        &mint_in.to_account_info(),
+        mint_in.decimals,
+        ctx.accounts.protocol_fee_vault.as_ref(),
+        &vault_authority_info,
+        signer_seeds,
+        protocol_fee,
+        hook_in,
+        hook_in.and_then(|id| whitelist.hook_interface_version(&id)),
+        ctx.remaining_accounts,
+    ))?;
+    pool.unlock();
+    
+    emit!(SwapExecuted {
+        pool: pool_key,
+        user: user.key(),
+        input_side,
+        amount_in,
+        amount_out,
+        fee_amount,
+        protocol_fee,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    emit_realized_price(pool_key, pool, input_side, amount_in, amount_out, Clock::get()?.unix_timestamp)?;
     
     msg!("Swap executed successfully with Token-2022 hook validation");
+    msg!("Direction: {:?} -> {:?}", input_side, input_side.opposite());
     msg!("Amount in: {}", amount_in);
     msg!("Amount out: {}", amount_out);
     
     Ok(())
 }
 
-pub fn swap_exact_tokens_for_tokens(
-    ctx: Context<SwapExactTokensForTokens>,
+/// Swap with the slippage tolerance given in basis points instead of an
+/// absolute minimum. The quote the tolerance applies to is computed in this
+/// instruction, but at the reserves the client quoted against
+/// (`reference_reserve_in` / `reference_reserve_out`, input side first), so a
+/// price moved ahead of the swap still fails it.
+pub fn swap_with_slippage_bps<'info>(
+    ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
+    amount_in: u64,
+    slippage_bps: u16,
+    reference_reserve_in: u64,
+    reference_reserve_out: u64,
+    input_side: PoolSide,
+    max_price_impact_bps: Option<u16>,
+    deadline: i64,
+) -> Result<()> {
+    let min_amount_out = ctx.accounts.pool.min_output_for_slippage_bps(
+        input_side,
+        amount_in,
+        reference_reserve_in,
+        reference_reserve_out,
+        slippage_bps,
+    )?;
+    
+    msg!("Slippage tolerance: {} bps (min amount out: {})", slippage_bps, min_amount_out);
+    
+    swap(ctx, amount_in, min_amount_out, input_side, max_price_impact_bps, deadline)
+}
+
+/// Fill reported by `swap_partial` via return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PartialFill {
+    /// Part of the requested `amount_in` actually swapped
+    pub amount_in: u64,
+    /// Amount the pool sent for it
+    pub amount_out: u64,
+    /// Requested input that never left the user's account
+    pub amount_unfilled: u64,
+}
+
+/// Swap as much of `amount_in` as the pool's max swap size and
+/// `max_price_impact_bps` allow instead of failing, for keepers working large
+/// exits through thin pools. Only the filled part is transferred, so the
+/// remainder stays with the user. `min_amount_out` is the minimum for the full
+/// `amount_in` and applies pro rata to the fill, acting as a limit price.
+pub fn swap_partial<'info>(
+    ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
+    amount_in: u64,
+    min_fill_amount_in: u64,
+    min_amount_out: u64,
+    input_side: PoolSide,
+    max_price_impact_bps: u16,
+    deadline: i64,
+) -> Result<PartialFill> {
+    require!(amount_in > 0, AmmError::InvalidAmount);
+    
+    let pool = &ctx.accounts.pool;
+    let filled = pool.max_fillable_input(input_side, amount_in, max_price_impact_bps)?;
+    require!(
+        filled > 0 && filled >= min_fill_amount_in,
+        AmmError::PartialFillTooSmall
+    );
+    let min_fill_amount_out = mul_div_ceil(min_amount_out as u128, filled as u128, amount_in as u128)?;
+    let min_fill_amount_out = u64::try_from(min_fill_amount_out).map_err(|_| AmmError::MathOverflow)?;
+    let amount_out = pool.calculate_swap_output(input_side, filled)?;
+    
+    msg!("Partial fill: {} of {} requested", filled, amount_in);
+    
+    swap(ctx, filled, min_fill_amount_out, input_side, Some(max_price_impact_bps), deadline)?;
+    
+    Ok(PartialFill {
+        amount_in: filled,
+        amount_out,
+        amount_unfilled: amount_in - filled,
+    })
+}
+
+pub fn swap_exact_tokens_for_tokens<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapExactTokensForTokens<'info>>,
     amount_in: u64,
     min_amount_out: u64,
+    max_price_impact_bps: Option<u16>,
+    deadline: i64,
 ) -> Result<()> {
-    let user = &ctx.accounts.user;
-    let pool_account_info = ctx.accounts.pool.to_account_info();
+    enforce_deadline(deadline)?;
     
-    // Get pool data before mutable borrow
-    let pool = &mut ctx.accounts.pool;
-    let amount_out = pool.calculate_swap_output(amount_in)?;
-    let pool_bump = pool.bump;
+    let pool = &ctx.accounts.pool;
+    let input_side = pool.side_of_mint(&ctx.accounts.input_mint.key()).ok_or(AmmError::InvalidTokenPair)?;
+    let amount_out = pool.calculate_swap_output(input_side, amount_in)?;
     
     // Check slippage protection
     require!(
         amount_out >= min_amount_out,
         AmmError::InsufficientOutputAmount
     );
+    pool.enforce_max_price_impact(input_side, amount_in, amount_out, max_price_impact_bps)?;
+    
+    execute_exact_swap(ctx, input_side, amount_in, amount_out)?;
+    
+    msg!("Exact swap executed successfully with Token-2022 hook validation");
+    msg!("Amount in: {}", amount_in);
+    msg!("Amount out: {}", amount_out);
+    
+    Ok(())
+}
+
+/// Exact-output swap: the user names the `amount_out` they want and caps what
+/// they are willing to pay with `max_amount_in`
+pub fn swap_tokens_for_exact_tokens<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapExactTokensForTokens<'info>>,
+    amount_out: u64,
+    max_amount_in: u64,
+    max_price_impact_bps: Option<u16>,
+    deadline: i64,
+) -> Result<()> {
+    enforce_deadline(deadline)?;
+    
+    let pool = &ctx.accounts.pool;
+    let input_side = pool.side_of_mint(&ctx.accounts.input_mint.key()).ok_or(AmmError::InvalidTokenPair)?;
+    let amount_in = pool.calculate_swap_input(input_side, amount_out)?;
+    
+    // Check slippage protection
+    require!(
+        amount_in <= max_amount_in,
+        AmmError::ExcessiveInputAmount
+    );
+    pool.enforce_max_price_impact(input_side, amount_in, amount_out, max_price_impact_bps)?;
+    
+    execute_exact_swap(ctx, input_side, amount_in, amount_out)?;
+    
+    msg!("Exact-output swap executed successfully with Token-2022 hook validation");
+    msg!("Amount in: {}", amount_in);
+    msg!("Amount out: {}", amount_out);
+    
+    Ok(())
+}
+
+/// Swap `amount_in` of the input mint through `hop_count` pools in one
+/// transaction, each hop feeding what the user received from the previous one
+/// into the next. Only the final output is checked against `min_amount_out`,
+/// so pairs without a direct pool can still be traded.
+pub fn swap_route<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    amount_in: u64,
+    min_amount_out: u64,
+    hop_count: u8,
+    deadline: i64,
+) -> Result<()> {
+    enforce_deadline(deadline)?;
+    require!(amount_in > 0, AmmError::InvalidAmount);
+    require!(
+        hop_count > 0 && hop_count as usize <= MAX_ROUTE_HOPS,
+        AmmError::InvalidRoute
+    );
+    let hop_accounts_len = hop_count as usize * ROUTE_HOP_ACCOUNTS;
+    require!(
+        ctx.remaining_accounts.len() >= hop_accounts_len,
+        AmmError::InvalidRemainingAccounts
+    );
+    let (hop_infos, hook_accounts) = ctx.remaining_accounts.split_at(hop_accounts_len);
+    
+    let user = &ctx.accounts.user;
+    let whitelist = &ctx.accounts.whitelist;
+    let now = Clock::get()?.unix_timestamp;
+    
+    // The first hop spends from the named accounts, later hops from the
+    // previous hop's output
+    let mut mint_in_info = ctx.accounts.input_mint.to_account_info();
+    let mut program_in_info = ctx.accounts.input_token_program.to_account_info();
+    let mut user_in_info = ctx.accounts.user_input_token.to_account_info();
+    let mut hop_amount_in = amount_in;
+    
+    for hop in hop_infos.chunks(ROUTE_HOP_ACCOUNTS) {
+        create_hop_output_if_missing(
+            user,
+            hop,
+            ctx.accounts.associated_token_program.as_ref().map(ToAccountInfo::to_account_info),
+            ctx.accounts.system_program.as_ref().map(ToAccountInfo::to_account_info),
+        )?;
+        hop_amount_in = execute_hop_swap(
+            user,
+            &ctx.accounts.amm_config,
+            whitelist,
+            ctx.accounts.taker_allowlist.as_ref(),
+            &mint_in_info,
+            &program_in_info,
+            &user_in_info,
+            hop,
+            hook_accounts,
+            hop_amount_in,
+            now,
+        )?;
+        
+        // The next hop spends the output mint from the account it arrived in
+        mint_in_info = hop[4].clone();
+        program_in_info = hop[5].clone();
+        user_in_info = hop[6].clone();
+    }
+    
+    // Check slippage protection on what the route delivered
+    require!(
+        hop_amount_in >= min_amount_out,
+        AmmError::InsufficientOutputAmount
+    );
+    
+    msg!("Routed swap executed across {} hops", hop_count);
+    msg!("Amount in: {}", amount_in);
+    msg!("Amount out: {}", hop_amount_in);
+    
+    Ok(())
+}
+
+/// Execute independent swaps through up to `MAX_BATCH_SWAP_LEGS` pools
+/// atomically, e.g. a market maker rebalancing several pools in one
+/// transaction. Each leg is checked against its own `min_amount_out`; any
+/// failing leg reverts the whole batch.
+pub fn batch_swap<'info>(ctx: Context<'_, '_, 'info, 'info, BatchSwap<'info>>, legs: Vec<BatchSwapLeg>, deadline: i64) -> Result<()> {
+    enforce_deadline(deadline)?;
+    require!(
+        !legs.is_empty() && legs.len() <= MAX_BATCH_SWAP_LEGS,
+        AmmError::InvalidRoute
+    );
+    let leg_accounts_len = legs.len() * BATCH_LEG_ACCOUNTS;
+    require!(
+        ctx.remaining_accounts.len() >= leg_accounts_len,
+        AmmError::InvalidRemainingAccounts
+    );
+    let (leg_infos, hook_accounts) = ctx.remaining_accounts.split_at(leg_accounts_len);
     
-    // Transfer tokens from user to pool using Token-2022
+    let user = &ctx.accounts.user;
+    let whitelist = &ctx.accounts.whitelist;
+    let now = Clock::get()?.unix_timestamp;
+    
+    for (leg, infos) in legs.iter().zip(leg_infos.chunks(BATCH_LEG_ACCOUNTS)) {
+        require!(leg.amount_in > 0, AmmError::InvalidAmount);
+        let (input_infos, hop) = infos.split_at(3);
+        let [user_in_info, mint_in_info, program_in_info] = input_infos else {
+            return err!(AmmError::InvalidRemainingAccounts);
+        };
+        require_keys_eq!(*mint_in_info.owner, program_in_info.key(), AmmError::InvalidTokenPair);
+        create_hop_output_if_missing(
+            user,
+            hop,
+            ctx.accounts.associated_token_program.as_ref().map(ToAccountInfo::to_account_info),
+            ctx.accounts.system_program.as_ref().map(ToAccountInfo::to_account_info),
+        )?;
+        
+        let received = execute_hop_swap(
+            user,
+            &ctx.accounts.amm_config,
+            whitelist,
+            ctx.accounts.taker_allowlist.as_ref(),
+            mint_in_info,
+            program_in_info,
+            user_in_info,
+            hop,
+            hook_accounts,
+            leg.amount_in,
+            now,
+        )?;
+        
+        // Check slippage protection on what the leg delivered
+        require!(
+            received >= leg.min_amount_out,
+            AmmError::InsufficientOutputAmount
+        );
+    }
+    
+    msg!("Batch swap executed across {} pools", legs.len());
+    
+    Ok(())
+}
+
+/// Swap `lamports_in` of native SOL for the other token of a wrapped-SOL
+/// pool in one instruction: the SOL is wrapped into the user's associated
+/// WSOL account (created if missing), swapped through the pool named in the
+/// remaining accounts, and a WSOL account opened just for this swap is
+/// closed again, returning its rent.
+pub fn swap_sol_for_token<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapSolForToken<'info>>,
+    lamports_in: u64,
+    min_amount_out: u64,
+    deadline: i64,
+) -> Result<()> {
+    enforce_deadline(deadline)?;
+    require!(lamports_in > 0, AmmError::InvalidAmount);
+    require!(
+        ctx.remaining_accounts.len() >= ROUTE_HOP_ACCOUNTS,
+        AmmError::InvalidRemainingAccounts
+    );
+    let (hop, hook_accounts) = ctx.remaining_accounts.split_at(ROUTE_HOP_ACCOUNTS);
+    
+    let user = &ctx.accounts.user;
+    let user_wsol_info = ctx.accounts.user_wsol.to_account_info();
+    let native_mint_info = ctx.accounts.native_mint.to_account_info();
+    let native_program_info = ctx.accounts.native_token_program.to_account_info();
+    
+    let wsol_created = wrap_sol(
+        &user.to_account_info(),
+        &user_wsol_info,
+        &native_mint_info,
+        &native_program_info,
+        &ctx.accounts.associated_token_program.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        lamports_in,
+    )?;
+    create_hop_output_if_missing(
+        user,
+        hop,
+        Some(ctx.accounts.associated_token_program.to_account_info()),
+        Some(ctx.accounts.system_program.to_account_info()),
+    )?;
+    
+    let received = execute_hop_swap(
+        user,
+        &ctx.accounts.amm_config,
+        &ctx.accounts.whitelist,
+        ctx.accounts.taker_allowlist.as_ref(),
+        &native_mint_info,
+        &native_program_info,
+        &user_wsol_info,
+        hop,
+        hook_accounts,
+        lamports_in,
+        Clock::get()?.unix_timestamp,
+    )?;
+    
+    // Check slippage protection on what the swap delivered
+    require!(
+        received >= min_amount_out,
+        AmmError::InsufficientOutputAmount
+    );
+    
+    // The WSOL account was only needed for this swap; closing it unwraps
+    // anything left and refunds the rent
+    if wsol_created {
+        close_account(CpiContext::new(
+            native_program_info,
+            CloseAccount {
+                account: user_wsol_info,
+                destination: user.to_account_info(),
+                authority: user.to_account_info(),
+            },
+        ))?;
+    }
+    
+    msg!("SOL swap executed");
+    msg!("Lamports in: {}", lamports_in);
+    msg!("Amount out: {}", received);
+    
+    Ok(())
+}
+
+/// Create the user's output account of `hop` (laid out as
+/// `ROUTE_HOP_ACCOUNTS`) as their associated token account if it is missing
+fn create_hop_output_if_missing<'info>(
+    user: &Signer<'info>,
+    hop: &[AccountInfo<'info>],
+    associated_token_program: Option<AccountInfo<'info>>,
+    system_program: Option<AccountInfo<'info>>,
+) -> Result<()> {
+    let [_, _, _, _, mint_out_info, program_out_info, user_out_info, _, _] = hop else {
+        return err!(AmmError::InvalidRemainingAccounts);
+    };
+    create_ata_if_missing(
+        &user.to_account_info(),
+        user_out_info,
+        &user.to_account_info(),
+        mint_out_info,
+        program_out_info,
+        associated_token_program,
+        system_program,
+    )
+}
+
+/// Execute one swap through the pool named by `hop` (laid out as
+/// `ROUTE_HOP_ACCOUNTS`), spending `amount_in` of `mint_in_info` from
+/// `user_in_info`. Returns what arrived in the user's output account.
+fn execute_hop_swap<'info>(
+    user: &Signer<'info>,
+    amm_config: &AmmConfig,
+    whitelist: &Account<'info, TransferHookWhitelist>,
+    taker_allowlist: Option<&Account<'info, TakerAllowlist>>,
+    mint_in_info: &AccountInfo<'info>,
+    program_in_info: &AccountInfo<'info>,
+    user_in_info: &AccountInfo<'info>,
+    hop: &'info [AccountInfo<'info>],
+    hook_accounts: &[AccountInfo<'info>],
+    amount_in: u64,
+    now: i64,
+) -> Result<u64> {
+    let [pool_info, vault_authority_info, vault_in_info, vault_out_info, mint_out_info, program_out_info, user_out_info, observation_info, protocol_fee_vault_info] = hop else {
+        return err!(AmmError::InvalidRemainingAccounts);
+    };
+    
+    let mut pool = Account::<AmmPool>::try_from(pool_info)?;
+    let pool_key = pool.key();
+    require!(!pool.is_paused(), AmmError::PoolPaused);
+    require!(!pool.is_withdraw_only(), AmmError::PoolWithdrawOnly);
+    require_keys_eq!(
+        pool.effective_whitelist(amm_config.whitelist),
+        whitelist.key(),
+        AmmError::WhitelistMismatch
+    );
+    
+    // The swap must enter on the input mint's side and leave on the other;
+    // along a route this chains each hop to the previous hop's output mint
+    let input_side = pool.side_of_mint(mint_in_info.key).ok_or(AmmError::InvalidRoute)?;
+    require!(
+        pool.side_of_mint(mint_out_info.key) == Some(input_side.opposite()),
+        AmmError::InvalidRoute
+    );
+    require!(pool.side_of_vault(vault_in_info.key) == Some(input_side), AmmError::InvalidVault);
+    require!(
+        pool.side_of_vault(vault_out_info.key) == Some(input_side.opposite()),
+        AmmError::InvalidVault
+    );
+    require_keys_eq!(
+        vault_authority_info.key(),
+        pool.vault_authority_address(&pool_key)?,
+        AmmError::InvalidVault
+    );
+    
+    let mint_in = hop_mint(mint_in_info, program_in_info)?;
+    let mint_out = hop_mint(mint_out_info, program_out_info)?;
+    let vault_in = InterfaceAccount::<TokenAccount>::try_from(vault_in_info)?;
+    
+    let amount_out = pool.calculate_swap_output(input_side, amount_in)?;
+    
+    // Private pools only trade with allowlisted takers
+    enforce_taker_allowlist(
+        &pool.taker_allowlist,
+        taker_allowlist,
+        &user.key(),
+    )?;
+    
+    // Validate transfer hooks for Token-2022 tokens
+    let hook_in = whitelist.validate_mint_hook_with_entries(&whitelist.key(), mint_in_info, hook_accounts)?;
+    let hook_out = whitelist.validate_mint_hook_with_entries(&whitelist.key(), mint_out_info, hook_accounts)?;
+    
+    // High-risk hooks cap how much a single transfer may move
+    enforce_hook_notional_cap(whitelist, hook_in, amount_in, hook_accounts)?;
+    enforce_hook_notional_cap(whitelist, hook_out, amount_out, hook_accounts)?;
+    
+    // Confidential-transfer accounts may refuse the public payout outright
+    require_public_credits(pool.mint_extensions(input_side.opposite()), user_out_info)?;
+    // CPI Guard would block the user-signed debit of the input account
+    require_no_cpi_guard(user_in_info)?;
+    
+    // Hook-bearing transfers run under the pool's hook compute budget, if set
+    let budget_in = pool.hook_compute_budget(hook_in);
+    let budget_out = pool.hook_compute_budget(hook_out);
+    let version_in = hook_in.and_then(|id| whitelist.hook_interface_version(&id));
+    let version_out = hook_out.and_then(|id| whitelist.hook_interface_version(&id));
+    
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(&mut pool)?;
+    
+    // Legacy-interface hooks are not invoked by Token-2022, run them here
+    with_hook_compute_budget(budget_in, || invoke_legacy_hook_if_needed(
+        version_in,
+        hook_in,
+        user_in_info,
+        mint_in_info,
+        vault_in_info,
+        &user.to_account_info(),
+        amount_in,
+        hook_accounts,
+    ))?;
+    with_hook_compute_budget(budget_out, || invoke_legacy_hook_if_needed(
+        version_out,
+        hook_out,
+        vault_out_info,
+        mint_out_info,
+        user_out_info,
+        vault_authority_info,
+        amount_out,
+        hook_accounts,
+    ))?;
+    
+    // Transfer tokens from user to pool through the input mint's token program
     let transfer_ctx = CpiContext::new(
-        ctx.accounts.token_2022_program.to_account_info(),
+        program_in_info.clone(),
+        TransferChecked {
+            from: user_in_info.clone(),
+            mint: mint_in_info.clone(),
+            to: vault_in_info.clone(),
+            authority: user.to_account_info(),
+        },
+    );
+    
+    with_hook_compute_budget(budget_in, || transfer_checked_with_hook(
+        transfer_ctx,
+        amount_in,
+        mint_in.decimals,
+        hook_accounts,
+    ))?;
+    
+    // Transfer tokens from pool to user through the output mint's token program
+    let vault_authority_bump = pool.vault_authority_bump;
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
+    
+    let balance_before = token_amount(user_out_info)?;
+    let transfer_ctx = CpiContext::new_with_signer(
+        program_out_info.clone(),
+        TransferChecked {
+            from: vault_out_info.clone(),
+            mint: mint_out_info.clone(),
+            to: user_out_info.clone(),
+            authority: vault_authority_info.clone(),
+        },
+        signer_seeds,
+    );
+    memo_if_required(user_out_info, "swap", &pool_key, hook_accounts)?;
+    
+    with_hook_compute_budget(budget_out, || transfer_checked_with_hook(
+        transfer_ctx,
+        amount_out,
+        mint_out.decimals,
+        hook_accounts,
+    ))?;
+    
+    // Transfer fees may withhold part of the output; report only what arrived
+    let received = token_amount(user_out_info)?
+        .checked_sub(balance_before)
+        .ok_or(AmmError::MathOverflow)?;
+    
+    // Accumulate the pre-trade price, then update pool state, splitting the
+    // protocol's share off the swap fee
+    let mut observation = if observation_info.key() == crate::ID {
+        None
+    } else {
+        Some(Account::<Observation>::try_from(observation_info)?)
+    };
+    let reserves_before = (pool.token_a_reserve, pool.token_b_reserve);
+    record_observation(&pool, observation.as_mut(), now)?;
+    pool.update_swap_state(input_side, amount_in, amount_out)?;
+    let fee_amount = pool.calculate_swap_fee(amount_in)?;
+    let protocol_fee = pool.calculate_protocol_fee(fee_amount)?;
+    pool.debit_protocol_fee(input_side, protocol_fee)?;
+    pool.accrue_swap_fee(input_side, fee_amount - protocol_fee)?;
+    let balances = vault_balances(input_side, vault_in_info, vault_out_info)?;
+    pool.enforce_swap_invariant(reserves_before, balances, input_side, protocol_fee)?;
+    refresh_dynamic_fee(&mut pool, observation.as_ref(), now)?;
+    
+    let protocol_fee_vault = if protocol_fee_vault_info.key() == crate::ID {
+        None
+    } else {
+        Some(InterfaceAccount::<TokenAccount>::try_from(protocol_fee_vault_info)?)
+    };
+    with_hook_compute_budget(budget_in, || transfer_protocol_fee(
+        &pool_key,
+        program_in_info,
+        &vault_in,
+        mint_in_info,
+        mint_in.decimals,
+        protocol_fee_vault.as_ref(),
+        vault_authority_info,
+        signer_seeds,
+        protocol_fee,
+        hook_in,
+        version_in,
+        hook_accounts,
+    ))?;
+    
+    // Remaining-account state is not written back automatically
+    pool.unlock();
+    pool.exit(&crate::ID)?;
+    if let Some(observation) = observation {
+        observation.exit(&crate::ID)?;
+    }
+    
+    emit!(SwapExecuted {
+        pool: pool_key,
+        user: user.key(),
+        input_side,
+        amount_in,
+        amount_out,
+        fee_amount,
+        protocol_fee,
+        timestamp: now,
+    });
+    emit_realized_price(pool_key, &pool, input_side, amount_in, amount_out, now)?;
+    
+    Ok(received)
+}
+
+/// Move `amount_in` into the pool and `amount_out` to the user once the
+/// caller has priced the swap and checked its slippage bound
+fn execute_exact_swap<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapExactTokensForTokens<'info>>,
+    input_side: PoolSide,
+    amount_in: u64,
+    amount_out: u64,
+) -> Result<()> {
+    let user = &ctx.accounts.user;
+    let pool_key = ctx.accounts.pool.key();
+    let vault_authority_info = ctx.accounts.vault_authority.to_account_info();
+    
+    // A missing output account is created as the user's associated token account
+    create_ata_if_missing(
+        &user.to_account_info(),
+        &ctx.accounts.user_output_token.to_account_info(),
+        &user.to_account_info(),
+        &ctx.accounts.output_mint.to_account_info(),
+        &ctx.accounts.output_token_program.to_account_info(),
+        ctx.accounts.associated_token_program.as_ref().map(ToAccountInfo::to_account_info),
+        ctx.accounts.system_program.as_ref().map(ToAccountInfo::to_account_info),
+    )?;
+    
+    let pool = &mut ctx.accounts.pool;
+    let vault_authority_bump = pool.vault_authority_bump;
+    
+    // Private pools only trade with allowlisted takers
+    enforce_taker_allowlist(
+        &pool.taker_allowlist,
+        ctx.accounts.taker_allowlist.as_ref(),
+        &user.key(),
+    )?;
+    
+    // Validate transfer hooks for Token-2022 tokens
+    let whitelist = &ctx.accounts.whitelist;
+    let input_hook = check_mint_hook_cached(
+        whitelist,
+        &ctx.accounts.input_mint.to_account_info(),
+        &user.key(),
+        ctx.accounts.input_hook_cache.as_ref(),
+        ctx.remaining_accounts,
+    )?;
+    let output_hook = check_mint_hook_cached(
+        whitelist,
+        &ctx.accounts.output_mint.to_account_info(),
+        &user.key(),
+        ctx.accounts.output_hook_cache.as_ref(),
+        ctx.remaining_accounts,
+    )?;
+    
+    // High-risk hooks cap how much a single transfer may move
+    enforce_hook_notional_cap(whitelist, input_hook, amount_in, ctx.remaining_accounts)?;
+    enforce_hook_notional_cap(whitelist, output_hook, amount_out, ctx.remaining_accounts)?;
+    
+    // Confidential-transfer accounts may refuse the public payout outright
+    require_public_credits(pool.mint_extensions(input_side.opposite()), &ctx.accounts.user_output_token.to_account_info())?;
+    // CPI Guard would block the user-signed debit of the input account
+    require_no_cpi_guard(&ctx.accounts.user_input_token.to_account_info())?;
+    
+    // Hook-bearing transfers run under the pool's hook compute budget, if set
+    let budget_in = pool.hook_compute_budget(input_hook);
+    let budget_out = pool.hook_compute_budget(output_hook);
+    
+    // Hold the reentrancy lock across the hook-invoking transfers
+    lock_pool(pool)?;
+    
+    // Legacy-interface hooks are not invoked by Token-2022, run them here
+    with_hook_compute_budget(budget_in, || invoke_legacy_hook_if_needed(
+        input_hook.and_then(|id| whitelist.hook_interface_version(&id)),
+        input_hook,
+        &ctx.accounts.user_input_token.to_account_info(),
+        &ctx.accounts.input_mint.to_account_info(),
+        &ctx.accounts.pool_input_vault.to_account_info(),
+        &user.to_account_info(),
+        amount_in,
+        ctx.remaining_accounts,
+    ))?;
+    with_hook_compute_budget(budget_out, || invoke_legacy_hook_if_needed(
+        output_hook.and_then(|id| whitelist.hook_interface_version(&id)),
+        output_hook,
+        &ctx.accounts.pool_output_vault.to_account_info(),
+        &ctx.accounts.output_mint.to_account_info(),
+        &ctx.accounts.user_output_token.to_account_info(),
+        &vault_authority_info,
+        amount_out,
+        ctx.remaining_accounts,
+    ))?;
+    
+    // Transfer tokens from user to pool through the input mint's token program
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.input_token_program.to_account_info(),
         TransferChecked {
             from: ctx.accounts.user_input_token.to_account_info(),
             mint: ctx.accounts.input_mint.to_account_info(),
@@ -173,31 +1247,196 @@ pub fn swap_exact_tokens_for_tokens(
         },
     );
     
-    transfer_checked(transfer_ctx, amount_in, ctx.accounts.input_mint.decimals)?;
+    with_hook_compute_budget(budget_in, || transfer_checked_with_hook(
+        transfer_ctx,
+        amount_in,
+        ctx.accounts.input_mint.decimals,
+        ctx.remaining_accounts,
+    ))?;
     
-    // Transfer tokens from pool to user using Token-2022
-    let pool_seeds: &[&[u8]] = &[b"pool", &[pool_bump]];
-    let signer_seeds = &[pool_seeds];
+    // Transfer tokens from pool to user through the output mint's token program
+    let vault_authority_seeds: &[&[u8]] = &[
+        pool_key.as_ref(),
+        AmmPool::VAULT_AUTHORITY_SEED,
+        &[vault_authority_bump],
+    ];
+    let signer_seeds = &[vault_authority_seeds];
     
     let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_2022_program.to_account_info(),
+        ctx.accounts.output_token_program.to_account_info(),
         TransferChecked {
             from: ctx.accounts.pool_output_vault.to_account_info(),
             mint: ctx.accounts.output_mint.to_account_info(),
             to: ctx.accounts.user_output_token.to_account_info(),
-            authority: pool_account_info.clone(),
+            authority: vault_authority_info.clone(),
         },
         signer_seeds,
     );
+    memo_if_required(
+        &ctx.accounts.user_output_token.to_account_info(),
+        "swap",
+        &pool_key,
+        ctx.remaining_accounts,
+    )?;
     
-    transfer_checked(transfer_ctx, amount_out, ctx.accounts.output_mint.decimals)?;
+    with_hook_compute_budget(budget_out, || transfer_checked_with_hook(
+        transfer_ctx,
+        amount_out,
+        ctx.accounts.output_mint.decimals,
+        ctx.remaining_accounts,
+    ))?;
     
-    // Update pool state
-    pool.update_swap_state(amount_in, amount_out)?;
+    if let Some(failure_log) = ctx.accounts.output_failure_log.as_mut() {
+        failure_log.record_success(Clock::get()?.slot);
+    }
     
-    msg!("Exact swap executed successfully with Token-2022 hook validation");
-    msg!("Amount in: {}", amount_in);
-    msg!("Amount out: {}", amount_out);
+    // Accumulate the pre-trade price, then update pool state, splitting the
+    // protocol's share off the swap fee
+    let reserves_before = (pool.token_a_reserve, pool.token_b_reserve);
+    record_observation(pool, ctx.accounts.observation.as_mut(), Clock::get()?.unix_timestamp)?;
+    pool.update_swap_state(input_side, amount_in, amount_out)?;
+    let fee_amount = pool.calculate_swap_fee(amount_in)?;
+    let protocol_fee = pool.calculate_protocol_fee(fee_amount)?;
+    pool.debit_protocol_fee(input_side, protocol_fee)?;
+    pool.accrue_swap_fee(input_side, fee_amount - protocol_fee)?;
+    let balances = vault_balances(
+        input_side,
+        &ctx.accounts.pool_input_vault.to_account_info(),
+        &ctx.accounts.pool_output_vault.to_account_info(),
+    )?;
+    pool.enforce_swap_invariant(reserves_before, balances, input_side, protocol_fee)?;
+    refresh_dynamic_fee(pool, ctx.accounts.observation.as_ref(), Clock::get()?.unix_timestamp)?;
+    
+    with_hook_compute_budget(budget_in, || transfer_protocol_fee(
+        &pool_key,
+        &ctx.accounts.input_token_program.to_account_info(),
+        &ctx.accounts.pool_input_vault,
+        &ctx.accounts.input_mint.to_account_info(),
+        ctx.accounts.input_mint.decimals,
+        ctx.accounts.protocol_fee_vault.as_ref(),
+        &vault_authority_info,
+        signer_seeds,
+        protocol_fee,
+        input_hook,
+        input_hook.and_then(|id| whitelist.hook_interface_version(&id)),
+        ctx.remaining_accounts,
+    ))?;
+    pool.unlock();
     
+    emit!(SwapExecuted {
+        pool: pool_key,
+        user: user.key(),
+        input_side,
+        amount_in,
+        amount_out,
+        fee_amount,
+        protocol_fee,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    emit_realized_price(pool_key, pool, input_side, amount_in, amount_out, Clock::get()?.unix_timestamp)?;
+    
+    Ok(())
+}
+
+/// Reject a user instruction executing after its `deadline` (unix timestamp),
+/// so a transaction that sat in a retry queue cannot fill at a stale price
+pub fn enforce_deadline(deadline: i64) -> Result<()> {
+    require!(Clock::get()?.unix_timestamp <= deadline, AmmError::DeadlineExceeded);
+    Ok(())
+}
+
+/// Mint at `mint_info`, which must be owned by the token program at
+/// `program_info`
+fn hop_mint(mint_info: &AccountInfo, program_info: &AccountInfo) -> Result<Mint> {
+    require!(
+        TokenInterface::ids().contains(program_info.key),
+        AmmError::InvalidTokenPair
+    );
+    require_keys_eq!(*mint_info.owner, program_info.key(), AmmError::InvalidTokenPair);
+    Mint::try_deserialize(&mut &mint_info.try_borrow_data()?[..])
+}
+
+/// Balance of the token account at `info`
+fn token_amount(info: &AccountInfo) -> Result<u64> {
+    Ok(TokenAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?.amount)
+}
+
+/// Balances of a swap's (A, B) vaults as they stand after its transfers
+pub fn vault_balances(input_side: PoolSide, vault_in: &AccountInfo, vault_out: &AccountInfo) -> Result<(u64, u64)> {
+    let (amount_in, amount_out) = (token_amount(vault_in)?, token_amount(vault_out)?);
+    Ok(match input_side {
+        PoolSide::A => (amount_in, amount_out),
+        PoolSide::B => (amount_out, amount_in),
+    })
+}
+
+/// Emit the price a swap executed at next to the pool's spot price after it
+pub fn emit_realized_price(
+    pool_key: Pubkey,
+    pool: &AmmPool,
+    input_side: PoolSide,
+    amount_in: u64,
+    amount_out: u64,
+    timestamp: i64,
+) -> Result<()> {
+    emit!(SwapPriceRealized {
+        pool: pool_key,
+        input_side,
+        realized_price_x64: ratio_x64(amount_out, amount_in)?,
+        spot_price_x64: pool.spot_price_x64(input_side)?,
+        swap_count: pool.stats.swap_count,
+        timestamp,
+    });
     Ok(())
+}
+
+/// Move the protocol's share of a swap fee out of the input vault into the
+/// pool's protocol fee vault for the input mint
+pub fn transfer_protocol_fee<'info>(
+    pool_key: &Pubkey,
+    token_program: &AccountInfo<'info>,
+    vault_in: &InterfaceAccount<'info, TokenAccount>,
+    mint_in: &AccountInfo<'info>,
+    mint_decimals: u8,
+    protocol_fee_vault: Option<&InterfaceAccount<'info, TokenAccount>>,
+    vault_authority: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    protocol_fee: u64,
+    hook_program_id: Option<Pubkey>,
+    version: Option<HookInterfaceVersion>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if protocol_fee == 0 {
+        return Ok(());
+    }
+    
+    let protocol_fee_vault = protocol_fee_vault.ok_or(AmmError::MissingProtocolFeeVault)?;
+    require_keys_eq!(
+        protocol_fee_vault.key(),
+        AmmPool::protocol_fee_vault_address(pool_key, &mint_in.key()),
+        AmmError::MissingProtocolFeeVault
+    );
+    
+    invoke_legacy_hook_if_needed(
+        version,
+        hook_program_id,
+        &vault_in.to_account_info(),
+        mint_in,
+        &protocol_fee_vault.to_account_info(),
+        vault_authority,
+        protocol_fee,
+        remaining_accounts,
+    )?;
+    
+    let transfer_ctx = CpiContext::new_with_signer(
+        token_program.clone(),
+        TransferChecked {
+            from: vault_in.to_account_info(),
+            mint: mint_in.clone(),
+            to: protocol_fee_vault.to_account_info(),
+            authority: vault_authority.clone(),
+        },
+        signer_seeds,
+    );
+    transfer_checked_with_hook(transfer_ctx, protocol_fee, mint_decimals, remaining_accounts)
 } 
\ No newline at end of file
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::constants::BPS_DENOMINATOR;
+use crate::state::{AmmPool, PoolStats};
+use crate::error::AmmError;
+
+/// Maximum pools reported by one `position_dashboard` call (bounded by return data size)
+pub const MAX_DASHBOARD_POOLS: usize = 10;
+
+/// One wallet's position in one pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PositionView {
+    /// Pool address
+    pub pool: Pubkey,
+    /// LP tokens held by the wallet
+    pub lp_balance: u64,
+    /// Share of total LP supply in basis points
+    pub share_bps: u64,
+    /// Token A redeemable for the LP balance
+    pub token_a_amount: u64,
+    /// Token B redeemable for the LP balance
+    pub token_b_amount: u64,
+    /// Claimable token A fees (zero while fees compound into reserves)
+    pub accrued_fees_a: u64,
+    /// Claimable token B fees (zero while fees compound into reserves)
+    pub accrued_fees_b: u64,
+    /// Pending liquidity mining rewards
+    pub pending_rewards: u64,
+}
+
+#[derive(Accounts)]
+pub struct PositionDashboard<'info> {
+    /// CHECK: Wallet whose positions are reported; only used as a key
+    pub wallet: UncheckedAccount<'info>,
+    // remaining_accounts: [pool, wallet's LP token account] pairs
+}
+
+/// Report LP balances, pool share and redeemable amounts for a wallet across
+/// the pools passed as remaining accounts
+pub fn position_dashboard<'info>(ctx: Context<'_, '_, 'info, 'info, PositionDashboard<'info>>) -> Result<Vec<PositionView>> {
+    let wallet = ctx.accounts.wallet.key();
+    let remaining = ctx.remaining_accounts;
+
+    require!(remaining.len().is_multiple_of(2), AmmError::InvalidRemainingAccounts);
+    require!(remaining.len() / 2 <= MAX_DASHBOARD_POOLS, AmmError::TooManyPools);
+
+    let mut positions = Vec::with_capacity(remaining.len() / 2);
+    for pair in remaining.chunks(2) {
+        let pool = Account::<AmmPool>::try_from(&pair[0])?;
+        let lp_account = InterfaceAccount::<TokenAccount>::try_from(&pair[1])?;
+
+        require_keys_eq!(lp_account.mint, pool.lp_mint, AmmError::InvalidLpMint);
+        require_keys_eq!(lp_account.owner, wallet, AmmError::InvalidTokenAccountOwner);
+
+        let lp_balance = lp_account.amount;
+        let (share_bps, token_a_amount, token_b_amount) = if pool.total_lp_supply == 0 || lp_balance == 0 {
+            (0, 0, 0)
+        } else {
+            let redeemable = lp_balance.min(pool.total_lp_supply);
+            let (token_a_amount, token_b_amount) = pool.calculate_tokens_for_lp_burn(redeemable)?;
+            let share_bps = (redeemable as u128) * (BPS_DENOMINATOR as u128) / (pool.total_lp_supply as u128);
+            (share_bps as u64, token_a_amount, token_b_amount)
+        };
+
+        positions.push(PositionView {
+            pool: pool.key(),
+            lp_balance,
+            share_bps,
+            token_a_amount,
+            token_b_amount,
+            accrued_fees_a: 0,
+            accrued_fees_b: 0,
+            pending_rewards: 0,
+        });
+    }
+
+    Ok(positions)
+}
+
+/// Invariant-per-LP-token metric for one pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct InvariantView {
+    /// Pool address
+    pub pool: Pubkey,
+    /// sqrt(reserve_a * reserve_b), or the weighted geometric mean on a weighted pool
+    pub sqrt_k: u64,
+    /// Total LP supply
+    pub total_lp_supply: u64,
+    /// Metric recorded by the last operation (Q64.64)
+    pub recorded_invariant_per_lp_x64: u128,
+    /// Metric recomputed from the current reserves (Q64.64); differs from the
+    /// recorded value only if reserves changed outside a tracked operation
+    pub current_invariant_per_lp_x64: u128,
+    /// Metric when the pool was seeded (Q64.64)
+    pub initial_invariant_per_lp_x64: u128,
+    /// Change of the current metric since seeding, in basis points
+    pub growth_bps: i64,
+}
+
+#[derive(Accounts)]
+pub struct PoolInvariant<'info> {
+    pub pool: Account<'info, AmmPool>,
+}
+
+/// Report how the pool invariant per LP token has drifted, making
+/// LP value accrual (or anomalies from hook/fee effects) observable on-chain
+pub fn pool_invariant(ctx: Context<PoolInvariant>) -> Result<InvariantView> {
+    let pool = &ctx.accounts.pool;
+
+    let sqrt_k = pool.invariant();
+    let current = pool.calculate_invariant_per_lp_x64();
+    let initial = pool.initial_invariant_per_lp_x64;
+    let growth_bps = if initial == 0 {
+        0
+    } else {
+        let delta = (current as i128) - (initial as i128);
+        let growth = delta * (BPS_DENOMINATOR as i128) / (initial as i128);
+        i64::try_from(growth).map_err(|_| AmmError::MathOverflow)?
+    };
+
+    Ok(InvariantView {
+        pool: pool.key(),
+        sqrt_k,
+        total_lp_supply: pool.total_lp_supply,
+        recorded_invariant_per_lp_x64: pool.invariant_per_lp_x64,
+        current_invariant_per_lp_x64: current,
+        initial_invariant_per_lp_x64: initial,
+        growth_bps,
+    })
+}
+
+#[derive(Accounts)]
+pub struct ReadPoolStats<'info> {
+    pub pool: Account<'info, AmmPool>,
+}
+
+/// Report the pool's lifetime trading statistics via return data
+pub fn pool_stats(ctx: Context<ReadPoolStats>) -> Result<PoolStats> {
+    Ok(ctx.accounts.pool.stats)
+}
@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
-use crate::state::TransferHookWhitelist;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+use crate::state::{TransferHookWhitelist, WhitelistEntry, Proposal, ProposalAction, LegacyTransferHookWhitelist, TransferHookWhitelistV1, WhitelistLayoutHeader, HookChangeKind};
 use crate::error::AmmError;
+use crate::events::HookWhitelisted;
+use crate::hook_interface::HookInterfaceVersion;
 
 #[derive(Accounts)]
 pub struct InitializeWhitelist<'info> {
@@ -39,6 +43,93 @@ pub struct RemoveHookFromWhitelist<'info> {
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct SetHookInterfaceVersion<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidWhitelistAuthority
+    )]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateWhitelistTimelock<'info> {
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidWhitelistAuthority
+    )]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeHookChange<'info> {
+    /// Whitelist whose queued change is applied; anyone may finalize once the
+    /// timelock has elapsed
+    #[account(mut)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+}
+
+#[derive(Accounts)]
+#[instruction(hook_program_id: Pubkey)]
+pub struct AddWhitelistEntry<'info> {
+    #[account(has_one = authority @ AmmError::InvalidWhitelistAuthority)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<WhitelistEntry>(),
+        seeds = [WhitelistEntry::SEED, whitelist.key().as_ref(), hook_program_id.as_ref()],
+        bump
+    )]
+    pub whitelist_entry: Account<'info, WhitelistEntry>,
+    
+    /// Approved proposal for this hook, executed into the entry when given
+    #[account(mut)]
+    pub proposal: Option<Account<'info, Proposal>>,
+    
+    /// The proposal's declared dependency, required when it has one
+    pub dependency: Option<Account<'info, Proposal>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveWhitelistEntry<'info> {
+    #[account(has_one = authority @ AmmError::InvalidWhitelistAuthority)]
+    pub whitelist: Account<'info, TransferHookWhitelist>,
+    
+    #[account(
+        mut,
+        close = authority,
+        has_one = whitelist @ AmmError::InvalidWhitelistAccount
+    )]
+    pub whitelist_entry: Account<'info, WhitelistEntry>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateWhitelist<'info> {
+    /// CHECK: Whitelist still in a legacy layout, so it cannot be loaded as
+    /// `Account`; discriminator, layout version and authority are checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub whitelist: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct ValidateTransferHook<'info> {
     pub whitelist: Account<'info, TransferHookWhitelist>,
@@ -57,8 +148,17 @@ pub fn add_hook_to_whitelist(
     hook_program_id: Pubkey,
 ) -> Result<()> {
     let whitelist = &mut ctx.accounts.whitelist;
+    whitelist.require_no_timelock()?;
     whitelist.add_hook(hook_program_id)?;
     
+    emit!(HookWhitelisted {
+        whitelist: whitelist.key(),
+        hook_program_id,
+        whitelisted: true,
+        proposal: None,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
     msg!("Added hook to whitelist: {}", hook_program_id);
     Ok(())
 }
@@ -68,12 +168,203 @@ pub fn remove_hook_from_whitelist(
     hook_program_id: Pubkey,
 ) -> Result<()> {
     let whitelist = &mut ctx.accounts.whitelist;
+    whitelist.require_no_timelock()?;
     whitelist.remove_hook(&hook_program_id)?;
     
+    emit!(HookWhitelisted {
+        whitelist: whitelist.key(),
+        hook_program_id,
+        whitelisted: false,
+        proposal: None,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
     msg!("Removed hook from whitelist: {}", hook_program_id);
     Ok(())
 }
 
+pub fn set_hook_interface_version(
+    ctx: Context<SetHookInterfaceVersion>,
+    hook_program_id: Pubkey,
+    version: HookInterfaceVersion,
+) -> Result<()> {
+    let whitelist = &mut ctx.accounts.whitelist;
+    whitelist.set_hook_interface_version(&hook_program_id, version)?;
+    
+    msg!("Hook {} interface version set to {:?}", hook_program_id, version);
+    Ok(())
+}
+
+/// Enable or lengthen the whitelist timelock. From then on hooks are only
+/// added or removed through `queue_hook_change` / `finalize_hook_change`.
+pub fn set_whitelist_timelock(ctx: Context<UpdateWhitelistTimelock>, delay: i64) -> Result<()> {
+    let whitelist = &mut ctx.accounts.whitelist;
+    whitelist.set_timelock_delay(delay)?;
+    
+    msg!("Whitelist timelock set to {}s", delay);
+    Ok(())
+}
+
+pub fn queue_hook_change(
+    ctx: Context<UpdateWhitelistTimelock>,
+    kind: HookChangeKind,
+    hook_program_id: Pubkey,
+) -> Result<()> {
+    let whitelist = &mut ctx.accounts.whitelist;
+    let eta = whitelist.queue_hook_change(kind, hook_program_id, Clock::get()?.unix_timestamp)?;
+    
+    msg!("Queued {:?} of hook {}", kind, hook_program_id);
+    msg!("Executable from: {}", eta);
+    Ok(())
+}
+
+pub fn finalize_hook_change(ctx: Context<FinalizeHookChange>, hook_program_id: Pubkey) -> Result<()> {
+    let whitelist = &mut ctx.accounts.whitelist;
+    let kind = whitelist.finalize_hook_change(&hook_program_id, Clock::get()?.unix_timestamp)?;
+    
+    emit!(HookWhitelisted {
+        whitelist: whitelist.key(),
+        hook_program_id,
+        whitelisted: kind == HookChangeKind::Add,
+        proposal: None,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Finalized {:?} of hook {}", kind, hook_program_id);
+    Ok(())
+}
+
+pub fn cancel_hook_change(ctx: Context<UpdateWhitelistTimelock>, hook_program_id: Pubkey) -> Result<()> {
+    let whitelist = &mut ctx.accounts.whitelist;
+    whitelist.cancel_hook_change(&hook_program_id)?;
+    
+    msg!("Cancelled queued change of hook {}", hook_program_id);
+    Ok(())
+}
+
+/// Whitelist a hook through its own `WhitelistEntry` PDA rather than the
+/// fixed array. With an approved proposal for the hook the proposal is
+/// executed into the entry; otherwise the whitelist must not be timelocked.
+pub fn add_whitelist_entry(ctx: Context<AddWhitelistEntry>, hook_program_id: Pubkey) -> Result<()> {
+    let whitelist = &ctx.accounts.whitelist;
+    require!(
+        !whitelist.is_hook_whitelisted(&hook_program_id),
+        AmmError::HookAlreadyWhitelisted
+    );
+    
+    let proposal_key = match ctx.accounts.proposal.as_mut() {
+        Some(proposal) => {
+            require_keys_eq!(proposal.hook_program_id, hook_program_id, AmmError::InvalidHookProgram);
+            require!(proposal.action == ProposalAction::AddHook, AmmError::InvalidProposalKind);
+            require!(proposal.is_executable(), AmmError::ProposalNotApproved);
+            proposal.check_dependency(ctx.accounts.dependency.as_ref())?;
+            proposal.mark_executed()?;
+            proposal.key()
+        }
+        None => {
+            whitelist.require_no_timelock()?;
+            Pubkey::default()
+        }
+    };
+    
+    let whitelist_key = whitelist.key();
+    let entry = &mut ctx.accounts.whitelist_entry;
+    entry.initialize(
+        whitelist_key,
+        hook_program_id,
+        ctx.accounts.authority.key(),
+        proposal_key,
+        Clock::get()?.unix_timestamp,
+        ctx.bumps.whitelist_entry,
+    )?;
+    
+    emit!(HookWhitelisted {
+        whitelist: whitelist_key,
+        hook_program_id,
+        whitelisted: true,
+        proposal: (proposal_key != Pubkey::default()).then_some(proposal_key),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Hook {} whitelisted through entry {}", hook_program_id, entry.key());
+    Ok(())
+}
+
+pub fn remove_whitelist_entry(ctx: Context<RemoveWhitelistEntry>) -> Result<()> {
+    ctx.accounts.whitelist.require_no_timelock()?;
+    
+    emit!(HookWhitelisted {
+        whitelist: ctx.accounts.whitelist.key(),
+        hook_program_id: ctx.accounts.whitelist_entry.hook_program_id,
+        whitelisted: false,
+        proposal: None,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    msg!("Hook {} removed from whitelist", ctx.accounts.whitelist_entry.hook_program_id);
+    Ok(())
+}
+
+/// Rewrite a legacy-layout whitelist in the current layout, growing the account
+/// as needed. All entries are preserved; the whole rewrite happens in this one
+/// instruction so the account is never observable half-migrated.
+pub fn migrate_whitelist(ctx: Context<MigrateWhitelist>) -> Result<()> {
+    let whitelist_info = ctx.accounts.whitelist.to_account_info();
+    
+    let migrated = {
+        let data = whitelist_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == TransferHookWhitelist::DISCRIMINATOR,
+            AmmError::InvalidWhitelistAccount
+        );
+        let header = WhitelistLayoutHeader::deserialize(&mut &data[8..])
+            .map_err(|_| AmmError::InvalidWhitelistAccount)?;
+        require_keys_eq!(
+            header.authority,
+            ctx.accounts.authority.key(),
+            AmmError::InvalidWhitelistAuthority
+        );
+        match header.layout_version {
+            0 => LegacyTransferHookWhitelist::deserialize(&mut &data[8..])
+                .map_err(|_| AmmError::InvalidWhitelistAccount)?
+                .migrate(),
+            1 => TransferHookWhitelistV1::deserialize(&mut &data[8..])
+                .map_err(|_| AmmError::InvalidWhitelistAccount)?
+                .migrate(),
+            _ => return Err(AmmError::WhitelistAlreadyMigrated.into()),
+        }
+    };
+    
+    // Grow the account and top up rent for the new layout
+    let new_len = 8 + std::mem::size_of::<TransferHookWhitelist>();
+    if new_len > whitelist_info.data_len() {
+        let rent_due = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(whitelist_info.lamports());
+        if rent_due > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: whitelist_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        whitelist_info.realloc(new_len, true)?;
+    }
+    
+    let mut data = whitelist_info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data[..];
+    migrated.try_serialize(&mut writer)?;
+    
+    msg!("Whitelist migrated to layout version {}", migrated.layout_version);
+    msg!("Hooks carried over: {}", migrated.hook_count);
+    Ok(())
+}
+
 pub fn validate_transfer_hook(
     ctx: Context<ValidateTransferHook>,
     hook_program_id: Pubkey,
@@ -1,16 +1,45 @@
+// Anchor instruction handlers take their arguments positionally
+#![allow(clippy::too_many_arguments)]
+
 pub mod constants;
 pub mod error;
+pub mod events;
+pub mod hook_interface;
 pub mod instructions;
+pub mod pda;
 pub mod state;
+pub mod token_extensions;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
+pub use events::*;
+pub use hook_interface::HookInterfaceVersion;
+// Instruction and state modules share names (`amm_pool`, `whitelist`, ...);
+// their items are distinct, only the module names collide
+#[allow(ambiguous_glob_reexports)]
 pub use instructions::*;
 pub use state::*;
 
 declare_id!("5VFsZC9h31MA9gMkV8ycx8eeyHXJT4QE36SgopWKXnE7");
 
+/// Call a handler that only exists with `$feature` enabled. Anchor 0.29's
+/// `#[program]` ignores `cfg` on instructions, so every instruction stays in
+/// the program and the IDL; builds without the feature compile the handler
+/// out and fail the instruction with `FeatureDisabled`.
+macro_rules! feature_gated {
+    ($feature:literal, $($segment:ident)::+ ( $($arg:expr),* $(,)? )) => {{
+        #[cfg(feature = $feature)]
+        let result = $($segment)::+($($arg),*);
+        #[cfg(not(feature = $feature))]
+        let result = {
+            let _ = ($($arg,)*);
+            err!(crate::error::AmmError::FeatureDisabled)
+        };
+        result
+    }};
+}
+
 #[program]
 pub mod token2022_amm {
     use super::*;
@@ -19,6 +48,40 @@ pub mod token2022_amm {
         initialize::handler(ctx)
     }
 
+    pub fn update_amm_config(
+        ctx: Context<UpdateAmmConfig>,
+        fee_tiers: Vec<u64>,
+        protocol_fee_share_bps: u64,
+    ) -> Result<()> {
+        instructions::initialize::update_amm_config(ctx, fee_tiers, protocol_fee_share_bps)
+    }
+
+    pub fn set_amm_config_whitelist(ctx: Context<SetAmmConfigWhitelist>) -> Result<()> {
+        instructions::initialize::set_amm_config_whitelist(ctx)
+    }
+
+    pub fn set_governance_treasury(
+        ctx: Context<UpdateAmmConfig>,
+        treasury: Pubkey,
+        proposal_slash_bps: u64,
+    ) -> Result<()> {
+        instructions::initialize::set_governance_treasury(ctx, treasury, proposal_slash_bps)
+    }
+
+    pub fn set_mint_extension_policy(
+        ctx: Context<UpdateAmmConfig>,
+        allow_permanent_delegate: bool,
+        allow_non_transferable: bool,
+        allow_confidential_transfer: bool,
+    ) -> Result<()> {
+        instructions::initialize::set_mint_extension_policy(
+            ctx,
+            allow_permanent_delegate,
+            allow_non_transferable,
+            allow_confidential_transfer,
+        )
+    }
+
     pub fn initialize_whitelist(ctx: Context<InitializeWhitelist>) -> Result<()> {
         instructions::whitelist::initialize_whitelist(ctx)
     }
@@ -37,6 +100,46 @@ pub mod token2022_amm {
         instructions::whitelist::remove_hook_from_whitelist(ctx, hook_program_id)
     }
 
+    pub fn set_hook_interface_version(
+        ctx: Context<SetHookInterfaceVersion>,
+        hook_program_id: Pubkey,
+        version: HookInterfaceVersion,
+    ) -> Result<()> {
+        instructions::whitelist::set_hook_interface_version(ctx, hook_program_id, version)
+    }
+
+    pub fn set_whitelist_timelock(ctx: Context<UpdateWhitelistTimelock>, delay: i64) -> Result<()> {
+        instructions::whitelist::set_whitelist_timelock(ctx, delay)
+    }
+
+    pub fn queue_hook_change(
+        ctx: Context<UpdateWhitelistTimelock>,
+        kind: HookChangeKind,
+        hook_program_id: Pubkey,
+    ) -> Result<()> {
+        instructions::whitelist::queue_hook_change(ctx, kind, hook_program_id)
+    }
+
+    pub fn finalize_hook_change(ctx: Context<FinalizeHookChange>, hook_program_id: Pubkey) -> Result<()> {
+        instructions::whitelist::finalize_hook_change(ctx, hook_program_id)
+    }
+
+    pub fn cancel_hook_change(ctx: Context<UpdateWhitelistTimelock>, hook_program_id: Pubkey) -> Result<()> {
+        instructions::whitelist::cancel_hook_change(ctx, hook_program_id)
+    }
+
+    pub fn add_whitelist_entry(ctx: Context<AddWhitelistEntry>, hook_program_id: Pubkey) -> Result<()> {
+        instructions::whitelist::add_whitelist_entry(ctx, hook_program_id)
+    }
+
+    pub fn remove_whitelist_entry(ctx: Context<RemoveWhitelistEntry>) -> Result<()> {
+        instructions::whitelist::remove_whitelist_entry(ctx)
+    }
+
+    pub fn migrate_whitelist(ctx: Context<MigrateWhitelist>) -> Result<()> {
+        instructions::whitelist::migrate_whitelist(ctx)
+    }
+
     pub fn validate_transfer_hook(
         ctx: Context<ValidateTransferHook>,
         hook_program_id: Pubkey,
@@ -44,9 +147,120 @@ pub mod token2022_amm {
         instructions::whitelist::validate_transfer_hook(ctx, hook_program_id)
     }
 
+    pub fn validate_hook(ctx: Context<ValidateHook>) -> Result<HookValidation> {
+        instructions::integration::validate_hook(ctx)
+    }
+
+    // Global Config Instructions
+    pub fn initialize_global_config(
+        ctx: Context<InitializeGlobalConfig>,
+        pool_creation_cooldown: i64,
+        max_pools_per_slot: u32,
+    ) -> Result<()> {
+        instructions::global_config::initialize_global_config(ctx, pool_creation_cooldown, max_pools_per_slot)
+    }
+
+    pub fn update_pool_creation_limits(
+        ctx: Context<UpdatePoolCreationLimits>,
+        pool_creation_cooldown: i64,
+        max_pools_per_slot: u32,
+    ) -> Result<()> {
+        instructions::global_config::update_pool_creation_limits(ctx, pool_creation_cooldown, max_pools_per_slot)
+    }
+
+    pub fn set_oracle_admission(
+        ctx: Context<UpdatePoolCreationLimits>,
+        band_bps: u64,
+        max_staleness: i64,
+    ) -> Result<()> {
+        instructions::global_config::set_oracle_admission(ctx, band_bps, max_staleness)
+    }
+
+    pub fn set_protocol_fee_authority(
+        ctx: Context<UpdatePoolCreationLimits>,
+        protocol_fee_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::global_config::set_protocol_fee_authority(ctx, protocol_fee_authority)
+    }
+
+    pub fn register_pair_oracle(ctx: Context<RegisterPairOracle>, oracle: Pubkey) -> Result<()> {
+        instructions::global_config::register_pair_oracle(ctx, oracle)
+    }
+
+    pub fn publish_pair_price(ctx: Context<PublishPairPrice>, price_x64: u128) -> Result<()> {
+        instructions::global_config::publish_pair_price(ctx, price_x64)
+    }
+
+    pub fn register_pool_creator(ctx: Context<RegisterPoolCreator>) -> Result<()> {
+        instructions::global_config::register_pool_creator(ctx)
+    }
+
+    pub fn guardian_heartbeat(ctx: Context<GuardianHeartbeat>) -> Result<()> {
+        instructions::global_config::guardian_heartbeat(ctx)
+    }
+
+    pub fn set_guardian(
+        ctx: Context<SetGuardian>,
+        guardian: Pubkey,
+        heartbeat_timeout: i64,
+    ) -> Result<()> {
+        instructions::global_config::set_guardian(ctx, guardian, heartbeat_timeout)
+    }
+
+    pub fn pause_pool(ctx: Context<PausePool>) -> Result<()> {
+        instructions::global_config::pause_pool(ctx)
+    }
+
+    pub fn unpause_pool(ctx: Context<PausePool>) -> Result<()> {
+        instructions::global_config::unpause_pool(ctx)
+    }
+
+    pub fn set_pool_hook_blocked(ctx: Context<PausePool>, side: PoolSide, blocked: bool) -> Result<()> {
+        instructions::global_config::set_pool_hook_blocked(ctx, side, blocked)
+    }
+
+    pub fn pause_clmm_pool(ctx: Context<PauseClmmPool>) -> Result<()> {
+        instructions::global_config::pause_clmm_pool(ctx)
+    }
+
+    pub fn unpause_clmm_pool(ctx: Context<PauseClmmPool>) -> Result<()> {
+        instructions::global_config::unpause_clmm_pool(ctx)
+    }
+
+    pub fn set_global_pause(ctx: Context<SetGlobalPause>, paused: bool) -> Result<()> {
+        instructions::global_config::set_global_pause(ctx, paused)
+    }
+
     // AMM Pool Instructions
-    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
-        instructions::amm_pool::initialize_pool(ctx)
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        fee_tier: u64,
+        curve: PoolCurve,
+        acknowledge_permanent_delegate: bool,
+    ) -> Result<()> {
+        instructions::amm_pool::initialize_pool(ctx, fee_tier, curve, acknowledge_permanent_delegate)
+    }
+
+    pub fn initialize_pool_with_sol(
+        ctx: Context<InitializePool>,
+        fee_tier: u64,
+        curve: PoolCurve,
+        acknowledge_permanent_delegate: bool,
+        lamports: u64,
+    ) -> Result<()> {
+        instructions::amm_pool::initialize_pool_with_sol(ctx, fee_tier, curve, acknowledge_permanent_delegate, lamports)
+    }
+
+    pub fn update_lp_metadata(ctx: Context<UpdateLpMetadata>) -> Result<()> {
+        instructions::amm_pool::update_lp_metadata(ctx)
+    }
+
+    pub fn initialize_lp_group(ctx: Context<InitializeLpGroup>, max_size: u32) -> Result<()> {
+        instructions::amm_pool::initialize_lp_group(ctx, max_size)
+    }
+
+    pub fn join_lp_group(ctx: Context<JoinLpGroup>) -> Result<()> {
+        instructions::amm_pool::join_lp_group(ctx)
     }
 
     pub fn update_pool_config(
@@ -57,40 +271,312 @@ pub mod token2022_amm {
         instructions::amm_pool::update_pool_config(ctx, fee_rate, min_liquidity)
     }
 
+    pub fn set_fee_mode(ctx: Context<UpdatePoolConfig>, fee_mode: FeeMode) -> Result<()> {
+        instructions::amm_pool::set_fee_mode(ctx, fee_mode)
+    }
+
+    pub fn set_pool_authority(ctx: Context<UpdatePoolConfig>, new_authority: Pubkey) -> Result<()> {
+        instructions::amm_pool::set_pool_authority(ctx, new_authority)
+    }
+
+    pub fn schedule_fee_ramp(
+        ctx: Context<UpdatePoolConfig>,
+        target_fee_rate: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        instructions::amm_pool::schedule_fee_ramp(ctx, target_fee_rate, start_time, end_time)
+    }
+
+    pub fn set_exit_fee(
+        ctx: Context<UpdatePoolConfig>,
+        exit_fee_bps: u64,
+        decay_period: i64,
+    ) -> Result<()> {
+        instructions::amm_pool::set_exit_fee(ctx, exit_fee_bps, decay_period)
+    }
+
+    pub fn set_max_hook_cu(ctx: Context<UpdatePoolConfig>, max_hook_cu: u64) -> Result<()> {
+        instructions::amm_pool::set_max_hook_cu(ctx, max_hook_cu)
+    }
+
+    pub fn set_max_swap_size(ctx: Context<UpdatePoolConfig>, max_swap_bps: u64) -> Result<()> {
+        instructions::amm_pool::set_max_swap_size(ctx, max_swap_bps)
+    }
+
+    pub fn set_dynamic_fee(
+        ctx: Context<UpdatePoolConfig>,
+        min_fee_rate: u64,
+        max_fee_rate: u64,
+        max_volatility_bps: u64,
+        window: u32,
+    ) -> Result<()> {
+        instructions::amm_pool::set_dynamic_fee(ctx, min_fee_rate, max_fee_rate, max_volatility_bps, window)
+    }
+
+    pub fn set_withdraw_only(ctx: Context<UpdatePoolConfig>, withdraw_only: bool) -> Result<()> {
+        instructions::amm_pool::set_withdraw_only(ctx, withdraw_only)
+    }
+
+    pub fn flag_pool_hook_revoked(ctx: Context<FlagPoolHookRevoked>) -> Result<()> {
+        instructions::amm_pool::flag_pool_hook_revoked(ctx)
+    }
+
+    pub fn enable_launch_guard(
+        ctx: Context<EnableLaunchGuard>,
+        max_guarded_lps: u32,
+        guard_duration: i64,
+        allowlist: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::amm_pool::enable_launch_guard(ctx, max_guarded_lps, guard_duration, allowlist)
+    }
+
+    pub fn add_launch_allowlist_wallet(
+        ctx: Context<AddLaunchAllowlistWallet>,
+        wallet: Pubkey,
+    ) -> Result<()> {
+        instructions::amm_pool::add_launch_allowlist_wallet(ctx, wallet)
+    }
+
+    pub fn enable_private_pool(ctx: Context<EnablePrivatePool>, takers: Vec<Pubkey>) -> Result<()> {
+        instructions::amm_pool::enable_private_pool(ctx, takers)
+    }
+
+    pub fn add_pool_taker(ctx: Context<UpdateTakerAllowlist>, taker: Pubkey) -> Result<()> {
+        instructions::amm_pool::add_pool_taker(ctx, taker)
+    }
+
+    pub fn remove_pool_taker(ctx: Context<UpdateTakerAllowlist>, taker: Pubkey) -> Result<()> {
+        instructions::amm_pool::remove_pool_taker(ctx, taker)
+    }
+
+    pub fn disable_private_pool(ctx: Context<DisablePrivatePool>) -> Result<()> {
+        instructions::amm_pool::disable_private_pool(ctx)
+    }
+
+    pub fn enable_pool_whitelist(ctx: Context<EnablePoolWhitelist>, hooks: Vec<Pubkey>) -> Result<()> {
+        instructions::amm_pool::enable_pool_whitelist(ctx, hooks)
+    }
+
+    pub fn disable_pool_whitelist(ctx: Context<DisablePoolWhitelist>) -> Result<()> {
+        instructions::amm_pool::disable_pool_whitelist(ctx)
+    }
+
+    pub fn start_wind_down(ctx: Context<StartWindDown>) -> Result<()> {
+        instructions::settlement::start_wind_down(ctx)
+    }
+
+    pub fn claim_settlement<'info>(ctx: Context<'_, '_, '_, 'info, ClaimSettlement<'info>>) -> Result<()> {
+        instructions::settlement::claim_settlement(ctx)
+    }
+
+    // Protocol Fee Instructions
+    pub fn initialize_protocol_fee_vaults(ctx: Context<InitializeProtocolFeeVaults>) -> Result<()> {
+        instructions::protocol_fee::initialize_protocol_fee_vaults(ctx)
+    }
+
+    pub fn set_protocol_fee_share(ctx: Context<SetProtocolFeeShare>, share_bps: u64) -> Result<()> {
+        instructions::protocol_fee::set_protocol_fee_share(ctx, share_bps)
+    }
+
+    pub fn collect_protocol_fees<'info>(ctx: Context<'_, '_, '_, 'info, CollectProtocolFees<'info>>) -> Result<()> {
+        instructions::protocol_fee::collect_protocol_fees(ctx)
+    }
+
+    // Protocol Liquidity Instructions
+    pub fn bootstrap_pool<'info>(
+        ctx: Context<'_, '_, '_, 'info, BootstrapPool<'info>>,
+        amount_a: u64,
+        price_x64: u128,
+        lock_duration: i64,
+    ) -> Result<()> {
+        instructions::protocol_liquidity::bootstrap_pool(ctx, amount_a, price_x64, lock_duration)
+    }
+
+    pub fn withdraw_protocol_liquidity(ctx: Context<WithdrawProtocolLiquidity>, amount: u64) -> Result<()> {
+        instructions::protocol_liquidity::withdraw_protocol_liquidity(ctx, amount)
+    }
+
     // Trading Instructions
-    pub fn swap(
-        ctx: Context<Swap>,
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
         amount_in: u64,
         min_amount_out: u64,
+        input_side: PoolSide,
+        max_price_impact_bps: Option<u16>,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::trading::swap(ctx, amount_in, min_amount_out, input_side, max_price_impact_bps, deadline)
+    }
+
+    pub fn swap_with_slippage_bps<'info>(
+        ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
+        amount_in: u64,
+        slippage_bps: u16,
+        reference_reserve_in: u64,
+        reference_reserve_out: u64,
+        input_side: PoolSide,
+        max_price_impact_bps: Option<u16>,
+        deadline: i64,
     ) -> Result<()> {
-        instructions::trading::swap(ctx, amount_in, min_amount_out)
+        instructions::trading::swap_with_slippage_bps(
+            ctx,
+            amount_in,
+            slippage_bps,
+            reference_reserve_in,
+            reference_reserve_out,
+            input_side,
+            max_price_impact_bps,
+            deadline,
+        )
     }
 
-    pub fn swap_exact_tokens_for_tokens(
-        ctx: Context<SwapExactTokensForTokens>,
+    pub fn swap_partial<'info>(
+        ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
         amount_in: u64,
+        min_fill_amount_in: u64,
         min_amount_out: u64,
+        input_side: PoolSide,
+        max_price_impact_bps: u16,
+        deadline: i64,
+    ) -> Result<PartialFill> {
+        instructions::trading::swap_partial(ctx, amount_in, min_fill_amount_in, min_amount_out, input_side, max_price_impact_bps, deadline)
+    }
+
+    pub fn swap_exact_tokens_for_tokens<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapExactTokensForTokens<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        max_price_impact_bps: Option<u16>,
+        deadline: i64,
     ) -> Result<()> {
-        instructions::trading::swap_exact_tokens_for_tokens(ctx, amount_in, min_amount_out)
+        instructions::trading::swap_exact_tokens_for_tokens(ctx, amount_in, min_amount_out, max_price_impact_bps, deadline)
+    }
+
+    pub fn swap_tokens_for_exact_tokens<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapExactTokensForTokens<'info>>,
+        amount_out: u64,
+        max_amount_in: u64,
+        max_price_impact_bps: Option<u16>,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::trading::swap_tokens_for_exact_tokens(ctx, amount_out, max_amount_in, max_price_impact_bps, deadline)
+    }
+
+    pub fn swap_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        hop_count: u8,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::trading::swap_route(ctx, amount_in, min_amount_out, hop_count, deadline)
+    }
+
+    pub fn batch_swap<'info>(ctx: Context<'_, '_, 'info, 'info, BatchSwap<'info>>, legs: Vec<BatchSwapLeg>, deadline: i64) -> Result<()> {
+        instructions::trading::batch_swap(ctx, legs, deadline)
+    }
+
+    pub fn swap_sol_for_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapSolForToken<'info>>,
+        lamports_in: u64,
+        min_amount_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::trading::swap_sol_for_token(ctx, lamports_in, min_amount_out, deadline)
     }
 
     // Liquidity Instructions
-    pub fn add_liquidity(
-        ctx: Context<AddLiquidity>,
-        amount_a: u64,
-        amount_b: u64,
+    pub fn add_liquidity<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddLiquidity<'info>>,
+        max_amount_a: u64,
+        max_amount_b: u64,
         min_lp_tokens: u64,
+        deadline: i64,
     ) -> Result<()> {
-        instructions::liquidity::add_liquidity(ctx, amount_a, amount_b, min_lp_tokens)
+        instructions::liquidity::add_liquidity(ctx, max_amount_a, max_amount_b, min_lp_tokens, deadline)
     }
 
-    pub fn remove_liquidity(
-        ctx: Context<RemoveLiquidity>,
+    pub fn remove_liquidity<'info>(
+        ctx: Context<'_, '_, '_, 'info, RemoveLiquidity<'info>>,
         lp_tokens_to_burn: u64,
         min_token_a: u64,
         min_token_b: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::liquidity::remove_liquidity(ctx, lp_tokens_to_burn, min_token_a, min_token_b, deadline)
+    }
+
+    pub fn reconcile_lp_supply(ctx: Context<ReconcileLpSupply>) -> Result<()> {
+        instructions::liquidity::reconcile_lp_supply(ctx)
+    }
+
+    pub fn sync_reserves<'info>(ctx: Context<'_, '_, '_, 'info, SyncReserves<'info>>) -> Result<()> {
+        instructions::liquidity::sync_reserves(ctx)
+    }
+
+    pub fn open_lp_position(ctx: Context<OpenLpPosition>, lock_duration: i64) -> Result<()> {
+        instructions::liquidity::open_lp_position(ctx, lock_duration)
+    }
+
+    pub fn collect_fees<'info>(ctx: Context<'_, '_, '_, 'info, CollectFees<'info>>) -> Result<()> {
+        instructions::liquidity::collect_fees(ctx)
+    }
+
+    pub fn remove_liquidity_single_leg<'info>(
+        ctx: Context<'_, '_, '_, 'info, RemoveLiquiditySingleLeg<'info>>,
+        lp_tokens_to_burn: u64,
+        affected_side: PoolSide,
+        min_amount_out: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::liquidity::remove_liquidity_single_leg(
+            ctx,
+            lp_tokens_to_burn,
+            affected_side,
+            min_amount_out,
+            nonce,
+        )
+    }
+
+    pub fn redeem_liquidity_iou<'info>(ctx: Context<'_, '_, '_, 'info, RedeemLiquidityIou<'info>>) -> Result<()> {
+        instructions::liquidity::redeem_liquidity_iou(ctx)
+    }
+
+    pub fn create_pending_deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingDeposit<'info>>,
+        amount_a: u64,
+        amount_b: u64,
+        min_lp_tokens: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::pending_deposit::create_pending_deposit(ctx, amount_a, amount_b, min_lp_tokens, nonce)
+    }
+
+    pub fn execute_pending_deposit<'info>(ctx: Context<'_, '_, '_, 'info, ExecutePendingDeposit<'info>>) -> Result<()> {
+        instructions::pending_deposit::execute_pending_deposit(ctx)
+    }
+
+    pub fn cancel_pending_deposit<'info>(ctx: Context<'_, '_, '_, 'info, CancelPendingDeposit<'info>>) -> Result<()> {
+        instructions::pending_deposit::cancel_pending_deposit(ctx)
+    }
+
+    // Limit Order Instructions
+    pub fn place_limit_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceLimitOrder<'info>>,
+        nonce: u64,
+        amount_in: u64,
+        min_amount_out: u64,
     ) -> Result<()> {
-        instructions::liquidity::remove_liquidity(ctx, lp_tokens_to_burn, min_token_a, min_token_b)
+        feature_gated!("limit-orders", instructions::limit_order::place_limit_order(ctx, nonce, amount_in, min_amount_out))
+    }
+
+    pub fn cancel_limit_order<'info>(ctx: Context<'_, '_, '_, 'info, CancelLimitOrder<'info>>) -> Result<()> {
+        feature_gated!("limit-orders", instructions::limit_order::cancel_limit_order(ctx))
+    }
+
+    pub fn fill_limit_order<'info>(ctx: Context<'_, '_, '_, 'info, FillLimitOrder<'info>>) -> Result<()> {
+        feature_gated!("limit-orders", instructions::limit_order::fill_limit_order(ctx))
     }
 
     // Governance Instructions
@@ -101,13 +587,38 @@ pub mod token2022_amm {
         audit_report_url: String,
         proposer_stake: u64,
     ) -> Result<()> {
-        instructions::governance::create_hook_proposal(
+        feature_gated!("governance", instructions::governance::create_hook_proposal(
             ctx,
             hook_program_id,
             description,
             audit_report_url,
             proposer_stake,
-        )
+        ))
+    }
+
+    pub fn initialize_governance_config(
+        ctx: Context<InitializeGovernanceConfig>,
+        security_council: Vec<Pubkey>,
+        veto_threshold: u8,
+    ) -> Result<()> {
+        feature_gated!("governance", instructions::governance::initialize_governance_config(ctx, security_council, veto_threshold))
+    }
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        action: ProposalAction,
+        description: String,
+        proposer_stake: u64,
+    ) -> Result<()> {
+        feature_gated!("governance", instructions::governance::create_proposal(ctx, action, description, proposer_stake))
+    }
+
+    pub fn execute_config_proposal(ctx: Context<ExecuteConfigProposal>) -> Result<()> {
+        feature_gated!("governance", instructions::governance::execute_config_proposal(ctx))
+    }
+
+    pub fn execute_pool_proposal(ctx: Context<ExecutePoolProposal>) -> Result<()> {
+        feature_gated!("governance", instructions::governance::execute_pool_proposal(ctx))
     }
 
     pub fn vote_on_proposal(
@@ -115,14 +626,344 @@ pub mod token2022_amm {
         vote: bool,
         stake_amount: u64,
     ) -> Result<()> {
-        instructions::governance::vote_on_proposal(ctx, vote, stake_amount)
+        feature_gated!("governance", instructions::governance::vote_on_proposal(ctx, vote, stake_amount))
     }
 
     pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
-        instructions::governance::execute_proposal(ctx)
+        feature_gated!("governance", instructions::governance::execute_proposal(ctx))
+    }
+
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+        feature_gated!("governance", instructions::governance::finalize_proposal(ctx))
+    }
+
+    pub fn veto_proposal(ctx: Context<VetoProposal>) -> Result<()> {
+        feature_gated!("governance", instructions::governance::veto_proposal(ctx))
+    }
+
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        feature_gated!("governance", instructions::governance::withdraw_stake(ctx))
+    }
+
+    pub fn settle_proposer_stake(ctx: Context<SettleProposerStake>) -> Result<()> {
+        feature_gated!("governance", instructions::governance::settle_proposer_stake(ctx))
     }
 
     pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
-        instructions::governance::cancel_proposal(ctx)
+        feature_gated!("governance", instructions::governance::cancel_proposal(ctx))
+    }
+
+    // Assertion Instructions
+    pub fn snapshot_balance(ctx: Context<SnapshotBalance>) -> Result<()> {
+        instructions::assertions::snapshot_balance(ctx)
+    }
+
+    pub fn assert_balance_change(
+        ctx: Context<AssertBalanceChange>,
+        min_delta: i64,
+        max_delta: i64,
+    ) -> Result<()> {
+        instructions::assertions::assert_balance_change(ctx, min_delta, max_delta)
+    }
+
+    // Emissions Instructions
+    pub fn initialize_emissions(
+        ctx: Context<InitializeEmissions>,
+        start_time: i64,
+        epoch_duration: i64,
+        initial_epoch_emission: u64,
+        decay_bps: u64,
+    ) -> Result<()> {
+        feature_gated!("rewards", instructions::emissions::initialize_emissions(
+            ctx,
+            start_time,
+            epoch_duration,
+            initial_epoch_emission,
+            decay_bps,
+        ))
+    }
+
+    pub fn release_emissions(ctx: Context<ReleaseEmissions>) -> Result<()> {
+        feature_gated!("rewards", instructions::emissions::release_emissions(ctx))
+    }
+
+    // Incentive Group Instructions
+    pub fn create_incentive_group(
+        ctx: Context<CreateIncentiveGroup>,
+        group_id: u64,
+        reward_rate: u64,
+    ) -> Result<()> {
+        feature_gated!("rewards", instructions::incentive_group::create_incentive_group(ctx, group_id, reward_rate))
+    }
+
+    pub fn add_group_pool(ctx: Context<AddGroupPool>) -> Result<()> {
+        feature_gated!("rewards", instructions::incentive_group::add_group_pool(ctx))
+    }
+
+    pub fn set_group_reward_rate(
+        ctx: Context<SetGroupRewardRate>,
+        reward_rate: u64,
+    ) -> Result<()> {
+        feature_gated!("rewards", instructions::incentive_group::set_group_reward_rate(ctx, reward_rate))
+    }
+
+    pub fn open_incentive_stake(ctx: Context<OpenIncentiveStake>) -> Result<()> {
+        feature_gated!("rewards", instructions::incentive_group::open_incentive_stake(ctx))
+    }
+
+    pub fn stake_group_lp(
+        ctx: Context<UpdateGroupStake>,
+        amount: u64,
+    ) -> Result<()> {
+        feature_gated!("rewards", instructions::incentive_group::stake_group_lp(ctx, amount))
+    }
+
+    pub fn unstake_group_lp(
+        ctx: Context<UpdateGroupStake>,
+        amount: u64,
+    ) -> Result<()> {
+        feature_gated!("rewards", instructions::incentive_group::unstake_group_lp(ctx, amount))
+    }
+
+    pub fn claim_group_rewards<'info>(ctx: Context<'_, '_, '_, 'info, ClaimGroupRewards<'info>>) -> Result<()> {
+        feature_gated!("rewards", instructions::incentive_group::claim_group_rewards(ctx))
+    }
+
+    // Farm Instructions
+    pub fn create_farm<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateFarm<'info>>,
+        start_time: i64,
+        end_time: i64,
+        reward_amount: u64,
+    ) -> Result<()> {
+        feature_gated!("rewards", instructions::farm::create_farm(ctx, start_time, end_time, reward_amount))
+    }
+
+    pub fn open_farm_stake(ctx: Context<OpenFarmStake>) -> Result<()> {
+        feature_gated!("rewards", instructions::farm::open_farm_stake(ctx))
+    }
+
+    pub fn stake_lp(
+        ctx: Context<UpdateFarmStake>,
+        amount: u64,
+    ) -> Result<()> {
+        feature_gated!("rewards", instructions::farm::stake_lp(ctx, amount))
+    }
+
+    pub fn unstake_lp(
+        ctx: Context<UpdateFarmStake>,
+        amount: u64,
+    ) -> Result<()> {
+        feature_gated!("rewards", instructions::farm::unstake_lp(ctx, amount))
+    }
+
+    pub fn claim_rewards<'info>(ctx: Context<'_, '_, '_, 'info, ClaimFarmRewards<'info>>) -> Result<()> {
+        feature_gated!("rewards", instructions::farm::claim_rewards(ctx))
+    }
+
+    // Hook Validation Cache Instructions
+    pub fn open_hook_cache(ctx: Context<OpenHookCache>, ttl: i64) -> Result<()> {
+        instructions::hook_cache::open_hook_cache(ctx, ttl)
+    }
+
+    pub fn refresh_hook_cache(ctx: Context<RefreshHookCache>, ttl: i64) -> Result<()> {
+        instructions::hook_cache::refresh_hook_cache(ctx, ttl)
+    }
+
+    pub fn open_hook_failure_log(ctx: Context<OpenHookFailureLog>, reporter: Pubkey) -> Result<()> {
+        instructions::hook_failure_log::open_hook_failure_log(ctx, reporter)
+    }
+
+    pub fn report_hook_failure(
+        ctx: Context<ReportHookFailure>,
+        failed_slot: u64,
+        reason: u32,
+    ) -> Result<()> {
+        instructions::hook_failure_log::report_hook_failure(ctx, failed_slot, reason)
+    }
+
+    // Hook Metadata Instructions
+    pub fn register_hook_metadata(
+        ctx: Context<RegisterHookMetadata>,
+        hook_program_id: Pubkey,
+        audit_url: String,
+        auditor: String,
+        risk_tier: HookRiskTier,
+        max_notional: u64,
+    ) -> Result<()> {
+        instructions::hook_metadata::register_hook_metadata(
+            ctx,
+            hook_program_id,
+            audit_url,
+            auditor,
+            risk_tier,
+            max_notional,
+        )
+    }
+
+    pub fn update_hook_metadata(
+        ctx: Context<UpdateHookMetadata>,
+        audit_url: String,
+        auditor: String,
+        risk_tier: HookRiskTier,
+        max_notional: u64,
+    ) -> Result<()> {
+        instructions::hook_metadata::update_hook_metadata(ctx, audit_url, auditor, risk_tier, max_notional)
+    }
+
+    // Quote Instructions
+    pub fn quote_swap(ctx: Context<QuoteSwap>, amount_in: u64, input_side: PoolSide) -> Result<SwapQuote> {
+        instructions::quote::quote_swap(ctx, amount_in, input_side)
+    }
+
+    pub fn quote_lp_value(ctx: Context<QuoteSwap>, lp_amount: u64) -> Result<LpValueQuote> {
+        instructions::quote::quote_lp_value(ctx, lp_amount)
+    }
+
+    pub fn quote_add_liquidity(
+        ctx: Context<QuoteLiquidity>,
+        max_amount_a: u64,
+        max_amount_b: u64,
+    ) -> Result<AddLiquidityQuote> {
+        instructions::quote::quote_add_liquidity(ctx, max_amount_a, max_amount_b)
+    }
+
+    pub fn quote_remove_liquidity(ctx: Context<QuoteLiquidity>, lp_tokens: u64) -> Result<RemoveLiquidityQuote> {
+        instructions::quote::quote_remove_liquidity(ctx, lp_tokens)
+    }
+
+    pub fn quote_routes<'info>(
+        ctx: Context<'_, '_, 'info, 'info, QuoteRoutes<'info>>,
+        amount_in: u64,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        route_lengths: Vec<u8>,
+    ) -> Result<RouteQuote> {
+        instructions::quote::quote_routes(ctx, amount_in, input_mint, output_mint, route_lengths)
+    }
+
+    pub fn quote_transfer_amount(
+        ctx: Context<QuoteTransferAmount>,
+        amount: u64,
+        amount_is_net: bool,
+    ) -> Result<TransferAmountQuote> {
+        instructions::quote::quote_transfer_amount(ctx, amount, amount_is_net)
+    }
+
+    // Price Feed Instructions
+    pub fn enable_price_feed(ctx: Context<EnablePriceFeed>, twap_window: i64) -> Result<()> {
+        instructions::price_feed::enable_price_feed(ctx, twap_window)
+    }
+
+    pub fn publish_price(ctx: Context<PublishPrice>) -> Result<()> {
+        instructions::price_feed::publish_price(ctx)
+    }
+
+    // Observation Instructions
+    pub fn initialize_observation(ctx: Context<InitializeObservation>) -> Result<()> {
+        instructions::observation::initialize_observation(ctx)
+    }
+
+    pub fn observe(ctx: Context<Observe>, seconds_agos: Vec<u32>) -> Result<Vec<ObservationPoint>> {
+        instructions::observation::observe(ctx, seconds_agos)
+    }
+
+    pub fn crank_observation<'info>(ctx: Context<'_, '_, '_, 'info, CrankObservation<'info>>) -> Result<()> {
+        instructions::observation::crank_observation(ctx)
+    }
+
+    // Concentrated Liquidity Instructions
+    pub fn initialize_clmm_pool(
+        ctx: Context<InitializeClmmPool>,
+        fee_tier: u64,
+        initial_sqrt_price_x64: u128,
+    ) -> Result<()> {
+        instructions::clmm::initialize_clmm_pool(ctx, fee_tier, initial_sqrt_price_x64)
+    }
+
+    pub fn initialize_tick_array(ctx: Context<InitializeTickArray>, start_tick_index: i32) -> Result<()> {
+        instructions::clmm::initialize_tick_array(ctx, start_tick_index)
+    }
+
+    pub fn open_position(ctx: Context<OpenPosition>, tick_lower: i32, tick_upper: i32) -> Result<()> {
+        instructions::clmm::open_position(ctx, tick_lower, tick_upper)
+    }
+
+    pub fn increase_liquidity<'info>(
+        ctx: Context<'_, '_, '_, 'info, ModifyClmmLiquidity<'info>>,
+        liquidity_delta: u128,
+        max_amount_a: u64,
+        max_amount_b: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::clmm::increase_liquidity(ctx, liquidity_delta, max_amount_a, max_amount_b, deadline)
+    }
+
+    pub fn decrease_liquidity<'info>(
+        ctx: Context<'_, '_, '_, 'info, ModifyClmmLiquidity<'info>>,
+        liquidity_delta: u128,
+        min_amount_a: u64,
+        min_amount_b: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::clmm::decrease_liquidity(ctx, liquidity_delta, min_amount_a, min_amount_b, deadline)
+    }
+
+    pub fn collect_clmm_fees<'info>(ctx: Context<'_, '_, '_, 'info, ModifyClmmLiquidity<'info>>) -> Result<()> {
+        instructions::clmm::collect_clmm_fees(ctx)
+    }
+
+    pub fn clmm_swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClmmSwap<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        sqrt_price_limit_x64: Option<u128>,
+        input_side: PoolSide,
+        tick_array_count: u8,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::clmm::clmm_swap(ctx, amount_in, min_amount_out, sqrt_price_limit_x64, input_side, tick_array_count, deadline)
+    }
+
+    pub fn enable_clmm_pool_whitelist(ctx: Context<EnableClmmPoolWhitelist>, hooks: Vec<Pubkey>) -> Result<()> {
+        instructions::clmm::enable_clmm_pool_whitelist(ctx, hooks)
+    }
+
+    pub fn disable_clmm_pool_whitelist(ctx: Context<DisableClmmPoolWhitelist>) -> Result<()> {
+        instructions::clmm::disable_clmm_pool_whitelist(ctx)
+    }
+
+    // Test-only Instructions
+    pub fn dump_pool_state(ctx: Context<DumpPoolState>) -> Result<AmmPool> {
+        feature_gated!("test-utils", instructions::test_utils::dump_pool_state(ctx))
+    }
+
+    pub fn restore_pool_state(ctx: Context<RestorePoolState>, state: AmmPool) -> Result<()> {
+        feature_gated!("test-utils", instructions::test_utils::restore_pool_state(ctx, state))
+    }
+
+    pub fn set_governance_params(ctx: Context<SetGovernanceParams>, params: GovernanceParams) -> Result<()> {
+        feature_gated!("test-utils", instructions::test_utils::set_governance_params(ctx, params))
+    }
+
+    // View Instructions
+    pub fn position_dashboard<'info>(ctx: Context<'_, '_, 'info, 'info, PositionDashboard<'info>>) -> Result<Vec<PositionView>> {
+        instructions::views::position_dashboard(ctx)
+    }
+
+    pub fn pool_invariant(ctx: Context<PoolInvariant>) -> Result<InvariantView> {
+        instructions::views::pool_invariant(ctx)
+    }
+
+    pub fn pool_stats(ctx: Context<ReadPoolStats>) -> Result<PoolStats> {
+        instructions::views::pool_stats(ctx)
+    }
+
+    pub fn hook_failure_rate(ctx: Context<HookFailureRate>) -> Result<HookReliability> {
+        instructions::hook_failure_log::hook_failure_rate(ctx)
+    }
+
+    pub fn preflight_swap(ctx: Context<PreflightSwap>, amount_in: u64) -> Result<SwapPreflight> {
+        instructions::preflight::preflight_swap(ctx, amount_in)
     }
 }
@@ -0,0 +1,207 @@
+//! Typed PDA derivations for the program's accounts. The seed constants stay
+//! on the account types (`AmmPool::SEED`, ...) so `#[account(seeds = ...)]`
+//! constraints and these finders read from the same place; each finder
+//! documents its seed layout once.
+//!
+//! Transfer Hook whitelists and governance proposals are keypair accounts,
+//! not PDAs, so they have no finder here.
+use anchor_lang::prelude::*;
+use crate::state::{
+    AmmPool, AmmConfig, GlobalConfig, GovernanceConfig, WhitelistEntry, HookMetadata, Observation,
+    LpPosition, Proposal, PairOracle,
+};
+
+/// Pool at [`AmmPool::SEED`, token_a_mint, token_b_mint, fee_tier (LE)], mints in canonical order
+pub struct PoolPda;
+
+impl PoolPda {
+    pub fn find(token_a_mint: &Pubkey, token_b_mint: &Pubkey, fee_tier: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[AmmPool::SEED, token_a_mint.as_ref(), token_b_mint.as_ref(), &fee_tier.to_le_bytes()],
+            &crate::ID,
+        )
+    }
+}
+
+/// Authority owning a pool's vaults, at [pool, `AmmPool::VAULT_AUTHORITY_SEED`]
+pub struct VaultAuthorityPda;
+
+impl VaultAuthorityPda {
+    pub fn find(pool: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[pool.as_ref(), AmmPool::VAULT_AUTHORITY_SEED], &crate::ID)
+    }
+}
+
+/// Protocol fee vault of one pool mint, at [`AmmPool::PROTOCOL_FEE_VAULT_SEED`, pool, mint]
+pub struct ProtocolFeeVaultPda;
+
+impl ProtocolFeeVaultPda {
+    pub fn find(pool: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[AmmPool::PROTOCOL_FEE_VAULT_SEED, pool.as_ref(), mint.as_ref()],
+            &crate::ID,
+        )
+    }
+}
+
+/// A pool's own hook whitelist, at [`AmmPool::POOL_WHITELIST_SEED`, pool]
+pub struct PoolWhitelistPda;
+
+impl PoolWhitelistPda {
+    pub fn find(pool: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[AmmPool::POOL_WHITELIST_SEED, pool.as_ref()], &crate::ID)
+    }
+}
+
+/// The AMM config singleton, at [`AmmConfig::SEED`]
+pub struct AmmConfigPda;
+
+impl AmmConfigPda {
+    pub fn find() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[AmmConfig::SEED], &crate::ID)
+    }
+}
+
+/// The global config singleton, at [`GlobalConfig::SEED`]
+pub struct GlobalConfigPda;
+
+impl GlobalConfigPda {
+    pub fn find() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[GlobalConfig::SEED], &crate::ID)
+    }
+}
+
+/// Governance signer PDA, at [`GlobalConfig::GOVERNANCE_SEED`]
+pub struct GovernancePda;
+
+impl GovernancePda {
+    pub fn find() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[GlobalConfig::GOVERNANCE_SEED], &crate::ID)
+    }
+}
+
+/// The governance rules singleton, at [`GovernanceConfig::SEED`]
+pub struct GovernanceConfigPda;
+
+impl GovernanceConfigPda {
+    pub fn find() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[GovernanceConfig::SEED], &crate::ID)
+    }
+}
+
+/// Vault escrowing a proposal's stake, at [`Proposal::STAKE_VAULT_SEED`, proposal]
+pub struct ProposalStakeVaultPda;
+
+impl ProposalStakeVaultPda {
+    pub fn find(proposal: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Proposal::STAKE_VAULT_SEED, proposal.as_ref()], &crate::ID)
+    }
+}
+
+/// One voter's vote record on a proposal, at [proposal, voter]
+pub struct VoteRecordPda;
+
+impl VoteRecordPda {
+    pub fn find(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[proposal.as_ref(), voter.as_ref()], &crate::ID)
+    }
+}
+
+/// Entry-listed hook of a whitelist, at [`WhitelistEntry::SEED`, whitelist, hook_program_id]
+pub struct WhitelistEntryPda;
+
+impl WhitelistEntryPda {
+    pub fn find(whitelist: &Pubkey, hook_program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[WhitelistEntry::SEED, whitelist.as_ref(), hook_program_id.as_ref()],
+            &crate::ID,
+        )
+    }
+}
+
+/// Audit metadata of a hook, at [`HookMetadata::SEED`, whitelist, hook_program_id]
+pub struct HookMetadataPda;
+
+impl HookMetadataPda {
+    pub fn find(whitelist: &Pubkey, hook_program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[HookMetadata::SEED, whitelist.as_ref(), hook_program_id.as_ref()],
+            &crate::ID,
+        )
+    }
+}
+
+/// Price oracle of a pair, at [`PairOracle::SEED`, token_a_mint, token_b_mint]
+pub struct PairOraclePda;
+
+impl PairOraclePda {
+    pub fn find(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[PairOracle::SEED, token_a_mint.as_ref(), token_b_mint.as_ref()],
+            &crate::ID,
+        )
+    }
+}
+
+/// A pool's TWAP observation, at [`Observation::SEED`, pool]
+pub struct ObservationPda;
+
+impl ObservationPda {
+    pub fn find(pool: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Observation::SEED, pool.as_ref()], &crate::ID)
+    }
+}
+
+/// One owner's LP position in a pool, at [`LpPosition::SEED`, pool, owner]
+pub struct LpPositionPda;
+
+impl LpPositionPda {
+    pub fn find(pool: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[LpPosition::SEED, pool.as_ref(), owner.as_ref()], &crate::ID)
+    }
+}
+
+/// Off-chain helpers deriving every PDA hanging off a pool in one call
+#[cfg(feature = "client")]
+pub mod client {
+    use super::*;
+    use crate::state::{PoolSettlement, PoolPriceFeed, TakerAllowlist, LaunchGuard};
+
+    /// Addresses of a pool and the per-pool PDAs around it. Optional
+    /// accounts (observation, settlement, ...) are derived whether or not
+    /// they have been created.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct PoolAddresses {
+        pub pool: Pubkey,
+        pub vault_authority: Pubkey,
+        pub protocol_fee_vault_a: Pubkey,
+        pub protocol_fee_vault_b: Pubkey,
+        pub pool_whitelist: Pubkey,
+        pub observation: Pubkey,
+        pub settlement: Pubkey,
+        pub price_feed: Pubkey,
+        pub taker_allowlist: Pubkey,
+        pub launch_guard: Pubkey,
+        pub pair_oracle: Pubkey,
+    }
+
+    impl PoolAddresses {
+        pub fn derive(token_a_mint: &Pubkey, token_b_mint: &Pubkey, fee_tier: u64) -> Self {
+            let (pool, _) = PoolPda::find(token_a_mint, token_b_mint, fee_tier);
+            let per_pool = |seed: &[u8]| Pubkey::find_program_address(&[seed, pool.as_ref()], &crate::ID).0;
+            Self {
+                pool,
+                vault_authority: VaultAuthorityPda::find(&pool).0,
+                protocol_fee_vault_a: ProtocolFeeVaultPda::find(&pool, token_a_mint).0,
+                protocol_fee_vault_b: ProtocolFeeVaultPda::find(&pool, token_b_mint).0,
+                pool_whitelist: PoolWhitelistPda::find(&pool).0,
+                observation: ObservationPda::find(&pool).0,
+                settlement: per_pool(PoolSettlement::SEED),
+                price_feed: per_pool(PoolPriceFeed::SEED),
+                taker_allowlist: per_pool(TakerAllowlist::SEED),
+                launch_guard: per_pool(LaunchGuard::SEED),
+                pair_oracle: PairOraclePda::find(token_a_mint, token_b_mint).0,
+            }
+        }
+    }
+}
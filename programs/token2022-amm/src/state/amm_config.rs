@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::AmmError;
+use crate::state::MAX_PROTOCOL_FEE_SHARE_BPS;
+use crate::token_extensions::{
+    MINT_EXT_CONFIDENTIAL, MINT_EXT_DEFAULT_FROZEN, MINT_EXT_NON_TRANSFERABLE, MINT_EXT_PERMANENT_DELEGATE,
+};
+
+/// Maximum fee tiers a config can offer
+pub const MAX_FEE_TIERS: usize = 8;
+
+/// Fee tiers offered by a fresh config (basis points: 0.01%, 0.05%, 0.3%, 1%)
+pub const DEFAULT_FEE_TIERS: [u64; 4] = [1, 5, 30, 100];
+
+/// AMM Config
+/// Protocol-level authority and the defaults new pools are created with
+#[account]
+#[derive(Default)]
+pub struct AmmConfig {
+    /// Protocol authority that can update the config
+    pub authority: Pubkey,
+
+    /// Number of fee tiers in use
+    pub fee_tier_count: u8,
+
+    /// Fee rates a pool can be created with (basis points)
+    pub fee_tiers: [u64; MAX_FEE_TIERS],
+
+    /// Protocol fee share applied to a pool once its protocol fee vaults
+    /// exist (basis points of the swap fee)
+    pub protocol_fee_share_bps: u64,
+
+    /// Whether swaps and deposits are paused across every pool (set by the guardian)
+    pub paused: bool,
+
+    /// Transfer Hook Whitelist pools validate mints against
+    pub whitelist: Pubkey,
+
+    /// Treasury receiving slashed proposer stake (default = no slashing)
+    pub treasury: Pubkey,
+
+    /// Share of a rejected or cancelled proposal's proposer stake sent to the
+    /// treasury (basis points)
+    pub proposal_slash_bps: u64,
+
+    /// Whether pools may be created for mints with a permanent delegate
+    pub allow_permanent_delegate: bool,
+
+    /// Whether pools may be created for non-transferable mints
+    pub allow_non_transferable: bool,
+
+    /// Whether pools may be created for mints configured for confidential
+    /// transfers; the pool then only ever moves their public balances
+    pub allow_confidential_transfer: bool,
+
+    /// Token-2022 group mint every pool's LP mint joins as a member, so
+    /// wallets can cluster AMM LP tokens (default = none)
+    pub lp_group_mint: Pubkey,
+
+    /// Config bump seed
+    pub bump: u8,
+}
+
+impl AmmConfig {
+    pub const SEED: &'static [u8] = b"amm_config";
+
+    pub fn initialize(&mut self, authority: Pubkey, whitelist: Pubkey, bump: u8) -> Result<()> {
+        self.authority = authority;
+        self.set_fee_tiers(&DEFAULT_FEE_TIERS)?;
+        self.protocol_fee_share_bps = 0;
+        self.paused = false;
+        self.whitelist = whitelist;
+        self.treasury = Pubkey::default();
+        self.proposal_slash_bps = 0;
+        self.allow_permanent_delegate = false;
+        self.allow_non_transferable = false;
+        self.allow_confidential_transfer = false;
+        self.lp_group_mint = Pubkey::default();
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Replace the offered fee tiers
+    pub fn set_fee_tiers(&mut self, fee_tiers: &[u64]) -> Result<()> {
+        require!(
+            !fee_tiers.is_empty() && fee_tiers.len() <= MAX_FEE_TIERS,
+            AmmError::InvalidFeeTier
+        );
+        require!(
+            fee_tiers.iter().all(|&fee_rate| fee_rate < BPS_DENOMINATOR),
+            AmmError::InvalidFeeTier
+        );
+        self.fee_tiers = [0; MAX_FEE_TIERS];
+        self.fee_tiers[..fee_tiers.len()].copy_from_slice(fee_tiers);
+        self.fee_tier_count = fee_tiers.len() as u8;
+        Ok(())
+    }
+
+    /// Fee rate of tier `index`
+    pub fn fee_tier(&self, index: u8) -> Result<u64> {
+        require!(index < self.fee_tier_count, AmmError::InvalidFeeTier);
+        Ok(self.fee_tiers[index as usize])
+    }
+
+    /// Whether `fee_rate` is one of the offered fee tiers
+    pub fn offers_fee_tier(&self, fee_rate: u64) -> bool {
+        self.fee_tiers[..self.fee_tier_count as usize].contains(&fee_rate)
+    }
+
+    /// Set the protocol fee share new fee vaults start with
+    pub fn set_protocol_fee_share(&mut self, share_bps: u64) -> Result<()> {
+        require!(share_bps <= MAX_PROTOCOL_FEE_SHARE_BPS, AmmError::InvalidProtocolFeeShare);
+        self.protocol_fee_share_bps = share_bps;
+        Ok(())
+    }
+
+    /// Set where slashed proposer stake goes and how much of it is slashed
+    pub fn set_governance_treasury(&mut self, treasury: Pubkey, slash_bps: u64) -> Result<()> {
+        require!(slash_bps <= BPS_DENOMINATOR, AmmError::InvalidSlashRate);
+        self.treasury = treasury;
+        self.proposal_slash_bps = slash_bps;
+        Ok(())
+    }
+
+    /// Reject pool creation for a mint whose `screen_mint` bitmap carries an
+    /// extension the config does not allow. Default-frozen mints are only
+    /// accepted when their freeze authority co-signs, so the vaults that start
+    /// frozen can be thawed.
+    pub fn check_mint_extensions(&self, mint_extensions: u16, freeze_authority_signed: bool) -> Result<()> {
+        require!(
+            self.allow_permanent_delegate || mint_extensions & MINT_EXT_PERMANENT_DELEGATE == 0,
+            AmmError::PermanentDelegateNotAllowed
+        );
+        require!(
+            self.allow_non_transferable || mint_extensions & MINT_EXT_NON_TRANSFERABLE == 0,
+            AmmError::NonTransferableMintNotAllowed
+        );
+        require!(
+            self.allow_confidential_transfer || mint_extensions & MINT_EXT_CONFIDENTIAL == 0,
+            AmmError::ConfidentialTransferMintNotAllowed
+        );
+        require!(
+            freeze_authority_signed || mint_extensions & MINT_EXT_DEFAULT_FROZEN == 0,
+            AmmError::DefaultFrozenMintNotAllowed
+        );
+        Ok(())
+    }
+
+    /// Portion of `stake` slashed to the treasury (none while no treasury is set)
+    pub fn calculate_proposal_slash(&self, stake: u64) -> Result<u64> {
+        if self.treasury == Pubkey::default() {
+            return Ok(0);
+        }
+        let slash = (stake as u128)
+            .checked_mul(self.proposal_slash_bps as u128)
+            .ok_or(AmmError::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        Ok(slash as u64)
+    }
+}
@@ -1,5 +1,37 @@
 use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
 use crate::error::AmmError;
+use crate::require_ctx;
+use crate::pda::ProtocolFeeVaultPda;
+use crate::state::math::{
+    math_result, mul_div_ceil, mul_div_floor, mul_div_floor_u64, ratio_x64, sqrt_u128, weighted_geometric_mean,
+};
+use amm_math::{curve::{stable_invariant, stable_spot_price_x64, Curve}, fee};
+use crate::state::extensions::{ExtensionRegion, PoolExtensionField, PoolExtensions};
+
+/// Highest configurable LP exit fee (10%)
+pub const MAX_EXIT_FEE_BPS: u64 = 1_000;
+
+/// `FeatureFlags` bit blocking swaps and deposits while set
+pub const FEATURE_PAUSED: u64 = 1 << 0;
+
+/// `FeatureFlags` bit putting a pool in withdraw-only mode while set
+pub const FEATURE_WITHDRAW_ONLY: u64 = 1 << 1;
+
+/// `FeatureFlags` bit marking token A's hook as blocking transfers
+pub const FEATURE_HOOK_A_BLOCKED: u64 = 1 << 2;
+
+/// `FeatureFlags` bit marking token B's hook as blocking transfers
+pub const FEATURE_HOOK_B_BLOCKED: u64 = 1 << 3;
+
+/// Highest share of the swap fee the protocol can take (half)
+pub const MAX_PROTOCOL_FEE_SHARE_BPS: u64 = 5_000;
+
+/// Smallest weight either side of a weighted pool can carry (1%)
+pub const MIN_POOL_WEIGHT_BPS: u64 = 100;
+
+/// Highest amplification a stable pool can be created with
+pub const MAX_STABLE_AMP: u64 = 10_000;
 
 /// AMM Pool State
 /// Manages liquidity pools for Token-2022 trading pairs
@@ -42,11 +74,332 @@ pub struct AmmPool {
     /// Pool bump seed
     pub bump: u8,
     
-    /// Reserved for future use
-    pub reserved: [u64; 8],
+    /// Token A owed to LPs through outstanding IOUs (held in the vault, excluded from reserves)
+    pub iou_a_outstanding: u64,
+    
+    /// Token B owed to LPs through outstanding IOUs (held in the vault, excluded from reserves)
+    pub iou_b_outstanding: u64,
+    
+    /// Whether swap fees compound into reserves or accrue as claimable
+    pub fee_mode: FeeMode,
+    
+    /// Token A fees accrued in claimable mode (held in the vault, excluded from reserves)
+    pub claimable_fees_a: u64,
+    
+    /// Token B fees accrued in claimable mode (held in the vault, excluded from reserves)
+    pub claimable_fees_b: u64,
+    
+    /// Cumulative claimable token A fees per unit of fee weight, i.e. per LP
+    /// token before lock-up multipliers (Q64.64)
+    pub fee_growth_a_x64: u128,
+    
+    /// Cumulative claimable token B fees per unit of fee weight, i.e. per LP
+    /// token before lock-up multipliers (Q64.64)
+    pub fee_growth_b_x64: u128,
+    
+    /// Launch guard restricting early liquidity providers (default = none)
+    pub launch_guard: Pubkey,
+    
+    /// Bump of the vault authority PDA that owns both vaults
+    pub vault_authority_bump: u8,
+    
+    /// Scheduled linear fee rate ramp (inactive while `end_time` is 0)
+    pub fee_ramp: FeeRamp,
+    
+    /// sqrt(reserve_a * reserve_b) per LP token (Q64.64), refreshed on every operation
+    pub invariant_per_lp_x64: u128,
+    
+    /// `invariant_per_lp_x64` when the pool was first seeded
+    pub initial_invariant_per_lp_x64: u128,
+    
+    /// Taker allowlist restricting swaps on a private pool (default = public)
+    pub taker_allowlist: Pubkey,
+    
+    /// Exit fee charged on fresh liquidity (basis points, 0 = none)
+    pub exit_fee_bps: u64,
+    
+    /// Holding period over which the exit fee decays linearly to zero
+    pub exit_fee_decay_period: i64,
+    
+    /// Pool-specific Transfer Hook Whitelist (default = the AMM config's global whitelist)
+    pub hook_whitelist: Pubkey,
+    
+    /// TWAP observation updated by every swap (default = none)
+    pub observation: Pubkey,
+    
+    /// Fee tier the pool was created in (basis points). Part of the pool PDA
+    /// seeds and never changes; `fee_rate` starts at it but can be tuned.
+    pub fee_tier: u64,
+    
+    /// Versioned optional fields (deposit caps, feature flags, ...)
+    pub extensions: PoolExtensions,
+    
+    /// Invariant the pool trades along; fixed at creation
+    pub curve: PoolCurve,
+    
+    /// Risky Token-2022 extensions detected on token A at creation (`MINT_EXT_*` bits)
+    pub mint_extensions_a: u16,
+    
+    /// Risky Token-2022 extensions detected on token B at creation (`MINT_EXT_*` bits)
+    pub mint_extensions_b: u16,
+    
+    /// Set while an instruction is inside its hook-invoking transfers, so a
+    /// hook re-entering the AMM finds the pool locked
+    pub locked: bool,
+    
+    /// Extra claimable-fee weight of lock-up LP positions beyond their LP
+    /// tokens; fee growth is spread over `total_lp_supply` plus this
+    pub boosted_fee_weight: u64,
+    
+    /// Largest swap input accepted, in basis points of the input reserve
+    /// (0 = no cap); a circuit breaker against price shocks on thin pools
+    pub max_swap_bps: u64,
+    
+    /// Volatility-driven fee rate bounds (default = off)
+    pub dynamic_fee: DynamicFee,
+    
+    /// Lifetime trading statistics, kept so analytics need not replay history
+    pub stats: PoolStats,
+    
+    /// Permanent delegate of token A, who can move tokens out of the vault
+    /// (default = none)
+    pub permanent_delegate_a: Pubkey,
+    
+    /// Permanent delegate of token B, who can move tokens out of the vault
+    /// (default = none)
+    pub permanent_delegate_b: Pubkey,
+}
+
+/// Swap curve of a pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PoolCurve {
+    /// x * y = k
+    #[default]
+    ConstantProduct,
+    /// Balancer-style constant mean x^w_a * y^w_b = k, with weights in basis
+    /// points summing to `BPS_DENOMINATOR` (e.g. 8_000 / 2_000 for an 80/20 pool)
+    Weighted { weight_a_bps: u64, weight_b_bps: u64 },
+    /// Curve-style StableSwap for pegged pairs, flat around a 1:1 price and
+    /// sharper the higher `amp` (1..=`MAX_STABLE_AMP`)
+    StableSwap { amp: u64 },
+}
+
+impl PoolCurve {
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            PoolCurve::ConstantProduct => {}
+            PoolCurve::Weighted { weight_a_bps, weight_b_bps } => require!(
+                weight_a_bps >= MIN_POOL_WEIGHT_BPS
+                    && weight_b_bps >= MIN_POOL_WEIGHT_BPS
+                    && weight_a_bps.checked_add(weight_b_bps) == Some(BPS_DENOMINATOR),
+                AmmError::InvalidPoolWeights
+            ),
+            PoolCurve::StableSwap { amp } => {
+                require!((1..=MAX_STABLE_AMP).contains(&amp), AmmError::InvalidAmplification)
+            }
+        }
+        Ok(())
+    }
+    
+    /// Weights of (`side`, the other side); (1, 1) on the unweighted curves
+    pub fn weights(&self, side: PoolSide) -> (u64, u64) {
+        match (*self, side) {
+            (PoolCurve::ConstantProduct | PoolCurve::StableSwap { .. }, _) => (1, 1),
+            (PoolCurve::Weighted { weight_a_bps, weight_b_bps }, PoolSide::A) => (weight_a_bps, weight_b_bps),
+            (PoolCurve::Weighted { weight_a_bps, weight_b_bps }, PoolSide::B) => (weight_b_bps, weight_a_bps),
+        }
+    }
+    
+    /// The curve as the shared `amm-math` sees it, oriented for a swap
+    /// entering on `input_side`
+    pub fn oriented(&self, input_side: PoolSide) -> Curve {
+        match *self {
+            PoolCurve::ConstantProduct => Curve::ConstantProduct,
+            PoolCurve::Weighted { .. } => {
+                let (weight_in, weight_out) = self.weights(input_side);
+                Curve::Weighted { weight_in, weight_out }
+            }
+            PoolCurve::StableSwap { amp } => Curve::StableSwap { amp },
+        }
+    }
+}
+
+/// How swap fees are credited to LPs
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeeMode {
+    /// Fees stay in reserves, raising the value of every LP token
+    #[default]
+    Compound,
+    /// Fees are set aside per side and tracked per LP token for claiming
+    Claimable,
+}
+
+/// Linear ramp of the fee rate between two timestamps. The effective rate is
+/// interpolated at read time, so no crank is needed while the ramp runs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeRamp {
+    /// Fee rate at and before `start_time` (basis points)
+    pub start_fee_rate: u64,
+    /// Fee rate at and after `end_time` (basis points)
+    pub end_fee_rate: u64,
+    /// Unix timestamp the ramp starts at
+    pub start_time: i64,
+    /// Unix timestamp the ramp ends at (0 = no ramp)
+    pub end_time: i64,
+}
+
+impl FeeRamp {
+    pub fn is_active(&self) -> bool {
+        self.end_time != 0
+    }
+    
+    /// Interpolated fee rate at `now`
+    pub fn fee_rate_at(&self, now: i64) -> Result<u64> {
+        if now <= self.start_time {
+            return Ok(self.start_fee_rate);
+        }
+        if now >= self.end_time {
+            return Ok(self.end_fee_rate);
+        }
+        
+        let elapsed = now.checked_sub(self.start_time).ok_or(AmmError::MathOverflow)? as u128;
+        let duration = self.end_time.checked_sub(self.start_time).ok_or(AmmError::MathOverflow)? as u128;
+        let delta = self.end_fee_rate.abs_diff(self.start_fee_rate) as u128;
+        let step = u64::try_from(mul_div_floor(delta, elapsed, duration)?).map_err(|_| AmmError::MathOverflow)?;
+        let fee_rate = if self.end_fee_rate >= self.start_fee_rate {
+            self.start_fee_rate.checked_add(step)
+        } else {
+            self.start_fee_rate.checked_sub(step)
+        };
+        fee_rate.ok_or(AmmError::MathOverflow.into())
+    }
+}
+
+/// Fee rate that follows recent price volatility, measured after every swap
+/// as the deviation of the spot price from the TWAP over `window` seconds of
+/// the pool's observation. The fee scales linearly from `min_fee_rate` at no
+/// deviation to `max_fee_rate` at `max_volatility_bps` and above.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DynamicFee {
+    /// Fee rate in calm markets (basis points)
+    pub min_fee_rate: u64,
+    /// Fee rate at and above `max_volatility_bps` (basis points)
+    pub max_fee_rate: u64,
+    /// Spot / TWAP deviation at which the fee reaches `max_fee_rate` (basis points)
+    pub max_volatility_bps: u64,
+    /// TWAP window volatility is measured over, in seconds (0 = off)
+    pub window: u32,
+    /// Fee rate derived from the latest measurement (basis points)
+    pub current_fee_rate: u64,
+}
+
+impl DynamicFee {
+    pub fn is_active(&self) -> bool {
+        self.window != 0
+    }
+    
+    /// Fee rate for a measured spot / TWAP deviation
+    pub fn fee_rate_for(&self, volatility_bps: u64) -> Result<u64> {
+        let volatility = volatility_bps.min(self.max_volatility_bps);
+        let delta = self.max_fee_rate.checked_sub(self.min_fee_rate).ok_or(AmmError::MathOverflow)?;
+        let step = mul_div_floor_u64(delta, volatility, self.max_volatility_bps)?;
+        self.min_fee_rate.checked_add(step).ok_or(AmmError::MathOverflow.into())
+    }
+}
+
+/// Lifetime trading statistics of a pool, updated by every swap. Volumes and
+/// fees are in raw token units; the fee totals include the protocol's share.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Token A swapped into the pool
+    pub volume_in_a: u128,
+    /// Token B swapped into the pool
+    pub volume_in_b: u128,
+    /// Token A paid out by swaps
+    pub volume_out_a: u128,
+    /// Token B paid out by swaps
+    pub volume_out_b: u128,
+    /// Swap fees charged in token A
+    pub fees_a: u128,
+    /// Swap fees charged in token B
+    pub fees_b: u128,
+    pub swap_count: u64,
+    /// Unix timestamp of the latest swap (0 = never traded)
+    pub last_trade_at: i64,
+}
+
+impl PoolStats {
+    /// Account one swap entering the pool on `input_side`
+    pub fn record_swap(&mut self, input_side: PoolSide, amount_in: u64, amount_out: u64, fee_amount: u64, now: i64) {
+        let (volume_in, volume_out, fees) = match input_side {
+            PoolSide::A => (&mut self.volume_in_a, &mut self.volume_out_b, &mut self.fees_a),
+            PoolSide::B => (&mut self.volume_in_b, &mut self.volume_out_a, &mut self.fees_b),
+        };
+        *volume_in = volume_in.saturating_add(amount_in as u128);
+        *volume_out = volume_out.saturating_add(amount_out as u128);
+        *fees = fees.saturating_add(fee_amount as u128);
+        self.swap_count = self.swap_count.saturating_add(1);
+        self.last_trade_at = now;
+    }
+}
+
+/// One side of a pool's token pair
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PoolSide {
+    #[default]
+    A,
+    B,
+}
+
+impl PoolSide {
+    /// The other side of the pair
+    pub fn opposite(self) -> Self {
+        match self {
+            PoolSide::A => PoolSide::B,
+            PoolSide::B => PoolSide::A,
+        }
+    }
 }
 
 impl AmmPool {
+    /// Seed of a pool PDA, derived as [SEED, token_a_mint, token_b_mint, fee_tier (LE)]
+    /// with the mints in canonical (ascending) order
+    pub const SEED: &'static [u8] = b"pool";
+    
+    /// Seed of the vault authority PDA, derived as [pool, VAULT_AUTHORITY_SEED]
+    pub const VAULT_AUTHORITY_SEED: &'static [u8] = b"vault_auth";
+    
+    /// Symbol of every LP mint's TokenMetadata
+    pub const LP_TOKEN_SYMBOL: &'static str = "AMM-LP";
+    
+    /// Decimals of every LP mint
+    pub const LP_MINT_DECIMALS: u8 = 6;
+    
+    /// Name of the LP mint's TokenMetadata, e.g. "AMM LP: SOL/USDC 30bps"
+    pub fn lp_token_name(&self, symbol_a: &str, symbol_b: &str) -> String {
+        format!("AMM LP: {}/{} {}bps", symbol_a, symbol_b, self.fee_tier)
+    }
+    
+    /// Seed of a protocol fee vault, derived as [PROTOCOL_FEE_VAULT_SEED, pool, mint]
+    pub const PROTOCOL_FEE_VAULT_SEED: &'static [u8] = b"protocol_fee_vault";
+    
+    /// Address of the protocol fee vault the pool keeps for `mint`
+    pub fn protocol_fee_vault_address(pool: &Pubkey, mint: &Pubkey) -> Pubkey {
+        ProtocolFeeVaultPda::find(pool, mint).0
+    }
+    
+    /// Address of the vault authority PDA of the pool at `pool`
+    pub fn vault_authority_address(&self, pool: &Pubkey) -> Result<Pubkey> {
+        Pubkey::create_program_address(
+            &[pool.as_ref(), Self::VAULT_AUTHORITY_SEED, &[self.vault_authority_bump]],
+            &crate::ID,
+        )
+        .map_err(|_| AmmError::InvalidVault.into())
+    }
+    
+    /// Seed of a pool's own whitelist, derived as [POOL_WHITELIST_SEED, pool]
+    pub const POOL_WHITELIST_SEED: &'static [u8] = b"pool_whitelist";
+    
     /// Initialize a new AMM pool
     pub fn initialize(
         &mut self,
@@ -56,7 +409,13 @@ impl AmmPool {
         token_a_vault: Pubkey,
         token_b_vault: Pubkey,
         lp_mint: Pubkey,
+        fee_rate: u64,
+        curve: PoolCurve,
+        bump: u8,
+        vault_authority_bump: u8,
     ) -> Result<()> {
+        require!(token_a_mint < token_b_mint, AmmError::InvalidTokenPairOrder);
+        curve.validate()?;
         self.authority = authority;
         self.token_a_mint = token_a_mint;
         self.token_b_mint = token_b_mint;
@@ -66,109 +425,1139 @@ impl AmmPool {
         self.total_lp_supply = 0;
         self.token_a_reserve = 0;
         self.token_b_reserve = 0;
-        self.fee_rate = 30; // 0.3% default fee
+        self.fee_rate = fee_rate;
         self.min_liquidity = 1000; // Minimum liquidity
-        self.bump = 0; // Will be set by PDA
-        self.reserved = [0u64; 8];
+        self.bump = bump;
+        self.iou_a_outstanding = 0;
+        self.iou_b_outstanding = 0;
+        self.fee_mode = FeeMode::Compound;
+        self.claimable_fees_a = 0;
+        self.claimable_fees_b = 0;
+        self.fee_growth_a_x64 = 0;
+        self.fee_growth_b_x64 = 0;
+        self.launch_guard = Pubkey::default();
+        self.vault_authority_bump = vault_authority_bump;
+        self.fee_ramp = FeeRamp::default();
+        self.invariant_per_lp_x64 = 0;
+        self.initial_invariant_per_lp_x64 = 0;
+        self.taker_allowlist = Pubkey::default();
+        self.exit_fee_bps = 0;
+        self.exit_fee_decay_period = 0;
+        self.hook_whitelist = Pubkey::default();
+        self.observation = Pubkey::default();
+        self.fee_tier = fee_rate;
+        self.extensions = PoolExtensions::default();
+        self.curve = curve;
         Ok(())
     }
     
-    /// Update pool configuration
+    /// Update pool configuration. An explicit fee rate replaces any running ramp.
     pub fn update_config(&mut self, fee_rate: u64, min_liquidity: u64) -> Result<()> {
         self.fee_rate = fee_rate;
         self.min_liquidity = min_liquidity;
+        self.fee_ramp = FeeRamp::default();
         Ok(())
     }
     
-    /// Calculate swap output using constant product formula
-    pub fn calculate_swap_output(&self, amount_in: u64) -> Result<u64> {
-        require!(amount_in > 0, AmmError::InvalidAmount);
-        require!(self.token_a_reserve > 0, AmmError::InsufficientLiquidity);
-        require!(self.token_b_reserve > 0, AmmError::InsufficientLiquidity);
+    /// Fee rate in effect at `now`, following the scheduled ramp if any
+    pub fn fee_rate_at(&self, now: i64) -> Result<u64> {
+        if self.dynamic_fee.is_active() {
+            Ok(self.dynamic_fee.current_fee_rate)
+        } else if self.fee_ramp.is_active() {
+            self.fee_ramp.fee_rate_at(now)
+        } else {
+            Ok(self.fee_rate)
+        }
+    }
+    
+    /// Fee rate in effect at the current cluster time
+    pub fn effective_fee_rate(&self) -> Result<u64> {
+        self.fee_rate_at(Clock::get()?.unix_timestamp)
+    }
+    
+    /// Schedule a linear ramp from the current effective fee rate to
+    /// `target_fee_rate` between `start_time` and `end_time`
+    pub fn schedule_fee_ramp(
+        &mut self,
+        target_fee_rate: u64,
+        start_time: i64,
+        end_time: i64,
+        now: i64,
+    ) -> Result<()> {
+        require!(target_fee_rate < BPS_DENOMINATOR, AmmError::InvalidFeeRamp);
+        require!(start_time >= now, AmmError::InvalidFeeRamp);
+        require!(end_time > start_time, AmmError::InvalidFeeRamp);
+        
+        let start_fee_rate = self.fee_rate_at(now)?;
+        self.fee_rate = start_fee_rate;
+        self.fee_ramp = FeeRamp {
+            start_fee_rate,
+            end_fee_rate: target_fee_rate,
+            start_time,
+            end_time,
+        };
+        Ok(())
+    }
+    
+    /// Switch between compounding and claimable fees. Leaving claimable mode folds
+    /// any unclaimed fees back into reserves so nothing is stranded.
+    pub fn set_fee_mode(&mut self, fee_mode: FeeMode) -> Result<()> {
+        if self.fee_mode == FeeMode::Claimable && fee_mode == FeeMode::Compound {
+            self.token_a_reserve = self.token_a_reserve
+                .checked_add(self.claimable_fees_a)
+                .ok_or(AmmError::MathOverflow)?;
+            self.token_b_reserve = self.token_b_reserve
+                .checked_add(self.claimable_fees_b)
+                .ok_or(AmmError::MathOverflow)?;
+            self.claimable_fees_a = 0;
+            self.claimable_fees_b = 0;
+        }
+        self.fee_mode = fee_mode;
+        self.refresh_invariant_metric();
+        Ok(())
+    }
+    
+    /// Swap fee charged on an input amount
+    pub fn calculate_swap_fee(&self, amount_in: u64) -> Result<u64> {
+        math_result(fee::swap_fee(amount_in, self.effective_fee_rate()?))
+    }
+    
+    /// Credit a swap fee that was added to reserves on `side`. In compound mode this
+    /// is a no-op; in claimable mode the fee moves out of reserves into the
+    /// claimable bucket and the per-LP-token growth accumulator advances.
+    pub fn accrue_swap_fee(&mut self, side: PoolSide, fee_amount: u64) -> Result<()> {
+        if self.fee_mode == FeeMode::Compound || fee_amount == 0 || self.total_lp_supply == 0 {
+            return Ok(());
+        }
+        
+        let fee_weight = (self.total_lp_supply as u128)
+            .checked_add(self.boosted_fee_weight as u128)
+            .ok_or(AmmError::MathOverflow)?;
+        let growth_delta = ((fee_amount as u128) << 64).checked_div(fee_weight).ok_or(AmmError::MathOverflow)?;
+        let (reserve, claimable, growth) = match side {
+            PoolSide::A => (&mut self.token_a_reserve, &mut self.claimable_fees_a, &mut self.fee_growth_a_x64),
+            PoolSide::B => (&mut self.token_b_reserve, &mut self.claimable_fees_b, &mut self.fee_growth_b_x64),
+        };
+        *reserve = reserve.checked_sub(fee_amount).ok_or(AmmError::MathOverflow)?;
+        *claimable = claimable.checked_add(fee_amount).ok_or(AmmError::MathOverflow)?;
+        *growth = growth.checked_add(growth_delta).ok_or(AmmError::MathOverflow)?;
+        self.refresh_invariant_metric();
+        Ok(())
+    }
+    
+    /// Replace an LP position's multiplier boost `before` with `after` in the
+    /// pool's total fee weight
+    pub fn reweight_fee_boost(&mut self, before: u64, after: u64) -> Result<()> {
+        self.boosted_fee_weight = self.boosted_fee_weight
+            .checked_sub(before)
+            .and_then(|weight| weight.checked_add(after))
+            .ok_or(AmmError::MathOverflow)?;
+        Ok(())
+    }
+    
+    /// Pay claimable fees out of the claimable bucket. Returns the amounts
+    /// actually available, which fall short of what is asked only after the
+    /// pool left claimable mode and folded the bucket back into reserves.
+    pub fn collect_claimable_fees(&mut self, amount_a: u64, amount_b: u64) -> Result<(u64, u64)> {
+        let amount_a = amount_a.min(self.claimable_fees_a);
+        let amount_b = amount_b.min(self.claimable_fees_b);
+        self.claimable_fees_a = self.claimable_fees_a.checked_sub(amount_a).ok_or(AmmError::MathOverflow)?;
+        self.claimable_fees_b = self.claimable_fees_b.checked_sub(amount_b).ok_or(AmmError::MathOverflow)?;
+        Ok((amount_a, amount_b))
+    }
+    
+    /// Cap the compute units a hook-bearing swap transfer may consume (0 = no cap)
+    pub fn set_max_hook_cu(&mut self, max_hook_cu: u64) -> Result<()> {
+        if max_hook_cu == 0 {
+            self.extensions.clear(PoolExtensionField::MaxHookComputeUnits)
+        } else {
+            self.extensions.set(PoolExtensionField::MaxHookComputeUnits, max_hook_cu)
+        }
+    }
+    
+    /// Let the fee rate follow volatility between `min_fee_rate` and
+    /// `max_fee_rate` (`window` = 0 turns dynamic fees off). Needs a TWAP
+    /// observation to measure against; starts at `min_fee_rate`.
+    pub fn set_dynamic_fee(
+        &mut self,
+        min_fee_rate: u64,
+        max_fee_rate: u64,
+        max_volatility_bps: u64,
+        window: u32,
+    ) -> Result<()> {
+        if window == 0 {
+            self.dynamic_fee = DynamicFee::default();
+            return Ok(());
+        }
+        
+        require!(self.observation != Pubkey::default(), AmmError::MissingObservation);
+        require!(
+            min_fee_rate <= max_fee_rate && max_fee_rate < BPS_DENOMINATOR,
+            AmmError::InvalidDynamicFee
+        );
+        require!(max_volatility_bps > 0, AmmError::InvalidDynamicFee);
         
-        // Calculate fee
-        let fee_amount = (amount_in * self.fee_rate) / 10000;
-        let amount_in_after_fee = amount_in - fee_amount;
+        self.dynamic_fee = DynamicFee {
+            min_fee_rate,
+            max_fee_rate,
+            max_volatility_bps,
+            window,
+            current_fee_rate: min_fee_rate,
+        };
+        Ok(())
+    }
+    
+    /// Cap a single swap's input at `max_swap_bps` of the input reserve (0 = no cap)
+    pub fn set_max_swap_size(&mut self, max_swap_bps: u64) -> Result<()> {
+        require!(max_swap_bps <= BPS_DENOMINATOR, AmmError::InvalidMaxSwapSize);
+        self.max_swap_bps = max_swap_bps;
+        Ok(())
+    }
+    
+    /// Reject a swap whose input exceeds the pool's per-swap size cap
+    pub fn enforce_max_swap_size(&self, input_side: PoolSide, amount_in: u64) -> Result<()> {
+        if self.max_swap_bps == 0 {
+            return Ok(());
+        }
+        let (reserve_in, mint_in) = self.side_reserve(input_side);
+        let max_amount_in = mul_div_floor_u64(reserve_in, self.max_swap_bps, BPS_DENOMINATOR)?;
+        require_ctx!(amount_in <= max_amount_in, AmmError::SwapTooLarge, Some(mint_in), None);
+        Ok(())
+    }
+    
+    /// Compute budget applying to a transfer of a mint with the given hook
+    pub fn hook_compute_budget(&self, hook_program_id: Option<Pubkey>) -> Option<u64> {
+        hook_program_id.and(self.extensions.get(PoolExtensionField::MaxHookComputeUnits))
+    }
+    
+    /// Running total of token A traded through the pool in either direction.
+    /// Wraps on overflow, so only differences between two readings are meaningful.
+    pub fn cumulative_volume_a(&self) -> u64 {
+        self.extensions.get(PoolExtensionField::CumulativeVolumeA).unwrap_or(0)
+    }
+    
+    /// Feature flag bitmask (0 while unset)
+    pub fn feature_flags(&self) -> u64 {
+        self.extensions.get(PoolExtensionField::FeatureFlags).unwrap_or(0)
+    }
+    
+    /// Whether swaps and deposits are paused on this pool
+    pub fn is_paused(&self) -> bool {
+        self.feature_flags() & FEATURE_PAUSED != 0
+    }
+    
+    /// Pause or unpause swaps and deposits; withdrawals stay open either way
+    pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+        let flags = if paused {
+            self.feature_flags() | FEATURE_PAUSED
+        } else {
+            self.feature_flags() & !FEATURE_PAUSED
+        };
+        self.extensions.set(PoolExtensionField::FeatureFlags, flags)
+    }
+    
+    /// Whether the pool only accepts withdrawals
+    pub fn is_withdraw_only(&self) -> bool {
+        self.feature_flags() & FEATURE_WITHDRAW_ONLY != 0
+    }
+    
+    /// Enter or leave withdraw-only mode: swaps and deposits are rejected
+    /// while LPs can still remove liquidity. Used to wind a pool down
+    /// gracefully, e.g. when one of its mints' hooks is delisted.
+    pub fn set_withdraw_only(&mut self, withdraw_only: bool) -> Result<()> {
+        let flags = if withdraw_only {
+            self.feature_flags() | FEATURE_WITHDRAW_ONLY
+        } else {
+            self.feature_flags() & !FEATURE_WITHDRAW_ONLY
+        };
+        self.extensions.set(PoolExtensionField::FeatureFlags, flags)
+    }
+    
+    /// Whether the hook of `side`'s mint has been flagged as blocking transfers
+    pub fn is_hook_blocked(&self, side: PoolSide) -> bool {
+        self.feature_flags() & Self::hook_blocked_flag(side) != 0
+    }
+    
+    /// Flag or clear the hook of `side`'s mint as blocking transfers, which
+    /// opens single-leg exits on that side
+    pub fn set_hook_blocked(&mut self, side: PoolSide, blocked: bool) -> Result<()> {
+        let flags = if blocked {
+            self.feature_flags() | Self::hook_blocked_flag(side)
+        } else {
+            self.feature_flags() & !Self::hook_blocked_flag(side)
+        };
+        self.extensions.set(PoolExtensionField::FeatureFlags, flags)
+    }
+    
+    fn hook_blocked_flag(side: PoolSide) -> u64 {
+        match side {
+            PoolSide::A => FEATURE_HOOK_A_BLOCKED,
+            PoolSide::B => FEATURE_HOOK_B_BLOCKED,
+        }
+    }
+    
+    /// Whitelist this pool's mints are validated against: its own list when
+    /// one is set, otherwise `global_whitelist`
+    pub fn effective_whitelist(&self, global_whitelist: Pubkey) -> Pubkey {
+        if self.hook_whitelist == Pubkey::default() {
+            global_whitelist
+        } else {
+            self.hook_whitelist
+        }
+    }
+    
+    /// Share of each swap fee routed to the protocol (basis points of the fee)
+    pub fn protocol_fee_share_bps(&self) -> u64 {
+        self.extensions.get(PoolExtensionField::ProtocolFeeShareBps).unwrap_or(0)
+    }
+    
+    /// Configure the protocol's share of the swap fee (0 = LPs keep every fee)
+    pub fn set_protocol_fee_share(&mut self, share_bps: u64) -> Result<()> {
+        require!(share_bps <= MAX_PROTOCOL_FEE_SHARE_BPS, AmmError::InvalidProtocolFeeShare);
+        if share_bps == 0 {
+            self.extensions.clear(PoolExtensionField::ProtocolFeeShareBps)
+        } else {
+            self.extensions.set(PoolExtensionField::ProtocolFeeShareBps, share_bps)
+        }
+    }
+    
+    /// Protocol's portion of a swap fee
+    pub fn calculate_protocol_fee(&self, fee_amount: u64) -> Result<u64> {
+        mul_div_floor_u64(fee_amount, self.protocol_fee_share_bps(), BPS_DENOMINATOR)
+    }
+    
+    /// Take the protocol's portion of a swap fee added to reserves on `side`
+    /// out of them, once it has moved to the protocol fee vault
+    pub fn debit_protocol_fee(&mut self, side: PoolSide, protocol_fee: u64) -> Result<()> {
+        if protocol_fee == 0 {
+            return Ok(());
+        }
+        let reserve = match side {
+            PoolSide::A => &mut self.token_a_reserve,
+            PoolSide::B => &mut self.token_b_reserve,
+        };
+        *reserve = reserve.checked_sub(protocol_fee).ok_or(AmmError::MathOverflow)?;
+        self.refresh_invariant_metric();
+        Ok(())
+    }
+    
+    /// Configure the decaying LP exit fee
+    pub fn set_exit_fee(&mut self, exit_fee_bps: u64, decay_period: i64) -> Result<()> {
+        require!(exit_fee_bps <= MAX_EXIT_FEE_BPS, AmmError::InvalidExitFee);
+        require!(exit_fee_bps == 0 || decay_period > 0, AmmError::InvalidExitFee);
+        self.exit_fee_bps = exit_fee_bps;
+        self.exit_fee_decay_period = decay_period;
+        Ok(())
+    }
+    
+    /// Exit fee for liquidity deposited at `entry_time` and withdrawn at `now`
+    pub fn exit_fee_bps_at(&self, entry_time: i64, now: i64) -> Result<u64> {
+        let held = now.saturating_sub(entry_time).max(0);
+        if self.exit_fee_bps == 0 || held >= self.exit_fee_decay_period {
+            return Ok(0);
+        }
+        let remaining = self.exit_fee_decay_period.checked_sub(held).ok_or(AmmError::MathOverflow)?;
+        mul_div_floor_u64(self.exit_fee_bps, remaining as u64, self.exit_fee_decay_period as u64)
+    }
+    
+    /// Side of the pair `mint` belongs to, if any
+    pub fn side_of_mint(&self, mint: &Pubkey) -> Option<PoolSide> {
+        if *mint == self.token_a_mint {
+            Some(PoolSide::A)
+        } else if *mint == self.token_b_mint {
+            Some(PoolSide::B)
+        } else {
+            None
+        }
+    }
+    
+    /// Side of the pair `vault` holds, if it is one of the pool's vaults
+    pub fn side_of_vault(&self, vault: &Pubkey) -> Option<PoolSide> {
+        if *vault == self.token_a_vault {
+            Some(PoolSide::A)
+        } else if *vault == self.token_b_vault {
+            Some(PoolSide::B)
+        } else {
+            None
+        }
+    }
+    
+    /// `MINT_EXT_*` bitmap screened for `side`'s mint at creation
+    pub fn mint_extensions(&self, side: PoolSide) -> u16 {
+        match side {
+            PoolSide::A => self.mint_extensions_a,
+            PoolSide::B => self.mint_extensions_b,
+        }
+    }
+    
+    /// Whether either mint has a permanent delegate able to drain its vault
+    pub fn has_permanent_delegate(&self) -> bool {
+        self.permanent_delegate_a != Pubkey::default() || self.permanent_delegate_b != Pubkey::default()
+    }
+    
+    /// (reserve, mint) of `side`
+    fn side_reserve(&self, side: PoolSide) -> (u64, Pubkey) {
+        match side {
+            PoolSide::A => (self.token_a_reserve, self.token_a_mint),
+            PoolSide::B => (self.token_b_reserve, self.token_b_mint),
+        }
+    }
+    
+    /// Curve output for `amount_in` (fee already taken) entering on
+    /// `input_side`, rounded down
+    fn curve_output(&self, input_side: PoolSide, reserve_in: u64, reserve_out: u64, amount_in: u128) -> Result<u128> {
+        math_result(self.curve.oriented(input_side).output(reserve_in, reserve_out, amount_in))
+    }
+    
+    /// Curve output for `amount_in` entering on `input_side` once the swap
+    /// fee at `fee_rate` is taken off it. Swaps and route quotes both price
+    /// through this, so a quote matches the swap to the unit.
+    fn output_after_swap_fee(
+        &self,
+        input_side: PoolSide,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount_in: u64,
+        fee_rate: u64,
+    ) -> Result<u128> {
+        math_result(self.curve.oriented(input_side).output_after_fee(reserve_in, reserve_out, amount_in, fee_rate))
+    }
+    
+    /// Curve input (before the fee) paying out exactly `amount_out` on the
+    /// side opposite `input_side`, rounded up
+    fn curve_input(&self, input_side: PoolSide, reserve_in: u64, reserve_out: u64, amount_out: u64) -> Result<u128> {
+        math_result(self.curve.oriented(input_side).input(reserve_in, reserve_out, amount_out))
+    }
+    
+    /// Calculate swap output along the pool's curve for a swap entering the
+    /// pool on `input_side`
+    pub fn calculate_swap_output(&self, input_side: PoolSide, amount_in: u64) -> Result<u64> {
+        let (reserve_in, mint_in) = self.side_reserve(input_side);
+        let (reserve_out, mint_out) = self.side_reserve(input_side.opposite());
+        require!(amount_in > 0, AmmError::InvalidAmount);
+        require_ctx!(reserve_in > 0, AmmError::InsufficientLiquidity, Some(mint_in), None);
+        require_ctx!(reserve_out > 0, AmmError::InsufficientLiquidity, Some(mint_out), None);
         
-        // Constant product formula: (x + dx) * (y - dy) = x * y
-        // dy = (y * dx) / (x + dx)
-        let amount_out = (self.token_b_reserve * amount_in_after_fee) / 
-                        (self.token_a_reserve + amount_in_after_fee);
+        let amount_out = self.output_after_swap_fee(input_side, reserve_in, reserve_out, amount_in, self.effective_fee_rate()?)?;
+        let amount_out = u64::try_from(amount_out).map_err(|_| AmmError::MathOverflow)?;
         
         require!(amount_out > 0, AmmError::InsufficientOutputAmount);
-        require!(amount_out < self.token_b_reserve, AmmError::InsufficientLiquidity);
+        require_ctx!(amount_out < reserve_out, AmmError::InsufficientLiquidity, Some(mint_out), None);
         
         Ok(amount_out)
     }
     
+    /// Input needed for a swap entering the pool on `input_side` to pay out
+    /// exactly `amount_out`, inverting the pool's curve and the swap fee. Rounds up so the pool never pays out more than it is paid for.
+    pub fn calculate_swap_input(&self, input_side: PoolSide, amount_out: u64) -> Result<u64> {
+        let (reserve_in, mint_in) = self.side_reserve(input_side);
+        let (reserve_out, mint_out) = self.side_reserve(input_side.opposite());
+        require!(amount_out > 0, AmmError::InvalidAmount);
+        require_ctx!(reserve_in > 0, AmmError::InsufficientLiquidity, Some(mint_in), None);
+        require_ctx!(amount_out < reserve_out, AmmError::InsufficientLiquidity, Some(mint_out), None);
+        
+        // Curve input, then gross up by the fee
+        let amount_in_after_fee = self.curve_input(input_side, reserve_in, reserve_out, amount_out)?;
+        let amount_in = math_result(fee::amount_before_fee(amount_in_after_fee, self.effective_fee_rate()?))?;
+        
+        u64::try_from(amount_in).map_err(|_| AmmError::MathOverflow.into())
+    }
+    
+    /// Curve output for a swap entering the pool on `input_side`
+    pub fn calculate_swap_output_for_side(&self, input_side: PoolSide, amount_in: u64) -> Result<u64> {
+        let (reserve_in, reserve_out) = match input_side {
+            PoolSide::A => (self.token_a_reserve, self.token_b_reserve),
+            PoolSide::B => (self.token_b_reserve, self.token_a_reserve),
+        };
+        // Plain `require!` here: route quoting probes pools and swallows these
+        // failures, so emitting error context would only add noise
+        require!(amount_in > 0, AmmError::InvalidAmount);
+        require!(reserve_in > 0, AmmError::InsufficientLiquidity);
+        require!(reserve_out > 0, AmmError::InsufficientLiquidity);
+        
+        let amount_out = self.output_after_swap_fee(input_side, reserve_in, reserve_out, amount_in, self.effective_fee_rate()?)?;
+        
+        require!(amount_out > 0, AmmError::InsufficientOutputAmount);
+        require!(amount_out < reserve_out as u128, AmmError::InsufficientLiquidity);
+        
+        Ok(amount_out as u64)
+    }
+    
+    /// Minimum acceptable output for a slippage tolerance in basis points,
+    /// measured against the quote at the reserves the client saw
+    /// (`reference_reserve_in` / `reference_reserve_out`). Quoting against the
+    /// live reserves instead would move the bound along with a sandwich.
+    pub fn min_output_for_slippage_bps(
+        &self,
+        input_side: PoolSide,
+        amount_in: u64,
+        reference_reserve_in: u64,
+        reference_reserve_out: u64,
+        slippage_bps: u16,
+    ) -> Result<u64> {
+        require!(
+            (slippage_bps as u64) <= BPS_DENOMINATOR,
+            AmmError::InvalidSlippageTolerance
+        );
+        require!(amount_in > 0, AmmError::InvalidAmount);
+        require!(
+            reference_reserve_in > 0 && reference_reserve_out > 0,
+            AmmError::InsufficientLiquidity
+        );
+        
+        let quoted_output = self.output_after_swap_fee(
+            input_side,
+            reference_reserve_in,
+            reference_reserve_out,
+            amount_in,
+            self.effective_fee_rate()?,
+        )?;
+        let quoted_output = u64::try_from(quoted_output).map_err(|_| AmmError::MathOverflow)?;
+        
+        mul_div_floor_u64(quoted_output, BPS_DENOMINATOR - slippage_bps as u64, BPS_DENOMINATOR)
+    }
+    
+    /// How far a swap of `amount_in` for `amount_out` moves the pool price
+    /// (output per input) in basis points, rounded up. The curve weights
+    /// cancel out of the before/after ratio, so this holds for weighted pools
+    /// too; a stable pool compares its curve's slope before and after.
+    pub fn price_impact_bps(&self, input_side: PoolSide, amount_in: u64, amount_out: u64) -> Result<u64> {
+        let (reserve_in, _) = self.side_reserve(input_side);
+        let (reserve_out, _) = self.side_reserve(input_side.opposite());
+        require!(reserve_in > 0 && amount_out < reserve_out, AmmError::InsufficientLiquidity);
+        let reserve_out_after = reserve_out.checked_sub(amount_out).ok_or(AmmError::MathOverflow)? as u128;
+        let reserve_in_after = (reserve_in as u128).checked_add(amount_in as u128).ok_or(AmmError::MathOverflow)?;
+        
+        let (after, before) = if let PoolCurve::StableSwap { amp } = self.curve {
+            (
+                math_result(stable_spot_price_x64(reserve_in_after, reserve_out_after, amp))?,
+                math_result(stable_spot_price_x64(reserve_in as u128, reserve_out as u128, amp))?,
+            )
+        } else {
+            // Price after / price before = (y - dy) * x / (y * (x + dx))
+            (
+                reserve_out_after.checked_mul(reserve_in as u128).ok_or(AmmError::MathOverflow)?,
+                (reserve_out as u128).checked_mul(reserve_in_after).ok_or(AmmError::MathOverflow)?,
+            )
+        };
+        
+        // Drop low bits on very deep pools so `delta * BPS_DENOMINATOR` fits
+        let shift = 14u32.saturating_sub(before.leading_zeros());
+        let delta = before.checked_sub(after).ok_or(AmmError::MathOverflow)? >> shift;
+        let before = before >> shift;
+        let impact = mul_div_ceil(delta, BPS_DENOMINATOR as u128, before)?;
+        
+        u64::try_from(impact).map_err(|_| AmmError::MathOverflow.into())
+    }
+    
+    /// Reject a swap moving the pool price more than `max_price_impact_bps`, if given
+    pub fn enforce_max_price_impact(
+        &self,
+        input_side: PoolSide,
+        amount_in: u64,
+        amount_out: u64,
+        max_price_impact_bps: Option<u16>,
+    ) -> Result<()> {
+        let Some(max_price_impact_bps) = max_price_impact_bps else {
+            return Ok(());
+        };
+        require!(
+            (max_price_impact_bps as u64) <= BPS_DENOMINATOR,
+            AmmError::InvalidSlippageTolerance
+        );
+        
+        let impact = self.price_impact_bps(input_side, amount_in, amount_out)?;
+        require!(impact <= max_price_impact_bps as u64, AmmError::PriceImpactTooHigh);
+        Ok(())
+    }
+    
+    /// Largest part of `amount_in` a swap entering on `input_side` can fill
+    /// within the pool's max swap size and `max_price_impact_bps`. Impact grows
+    /// with the input, so this bisects on the swap's own output math; 0 means
+    /// not even one unit fits.
+    pub fn max_fillable_input(&self, input_side: PoolSide, amount_in: u64, max_price_impact_bps: u16) -> Result<u64> {
+        require!(
+            (max_price_impact_bps as u64) <= BPS_DENOMINATOR,
+            AmmError::InvalidSlippageTolerance
+        );
+        let (reserve_in, _) = self.side_reserve(input_side);
+        let (reserve_out, _) = self.side_reserve(input_side.opposite());
+        let mut high = amount_in;
+        if self.max_swap_bps > 0 {
+            high = high.min(mul_div_floor_u64(reserve_in, self.max_swap_bps, BPS_DENOMINATOR)?);
+        }
+        
+        // Same output as `calculate_swap_output`, minus the checks that would
+        // emit error context for every probe
+        let fits = |amount: u64| -> Result<bool> {
+            let amount_in_after_fee = amount.checked_sub(self.calculate_swap_fee(amount)?).ok_or(AmmError::MathOverflow)?;
+            let amount_out = self.curve_output(input_side, reserve_in, reserve_out, amount_in_after_fee as u128)?;
+            let amount_out = u64::try_from(amount_out).map_err(|_| AmmError::MathOverflow)?;
+            Ok(self.price_impact_bps(input_side, amount, amount_out)? <= max_price_impact_bps as u64)
+        };
+        if high == 0 || fits(high).unwrap_or(false) {
+            return Ok(high);
+        }
+        
+        // `low` always fits (0 trivially), `high` never does
+        let mut low = 0;
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if fits(mid).unwrap_or(false) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+    
     /// Calculate LP tokens for liquidity addition
     pub fn calculate_lp_tokens_for_liquidity(&self, amount_a: u64, amount_b: u64) -> Result<u64> {
         require!(amount_a > 0, AmmError::InvalidAmount);
         require!(amount_b > 0, AmmError::InvalidAmount);
         
         if self.total_lp_supply == 0 {
-            // First liquidity provider
-            let lp_tokens = (amount_a * amount_b).isqrt();
+            // First liquidity provider: the deposit's invariant
+            let lp_tokens = self.invariant_of(amount_a, amount_b)?;
             require!(lp_tokens >= self.min_liquidity, AmmError::InsufficientLPTokens);
             Ok(lp_tokens)
         } else {
-            // Calculate based on proportion of reserves
-            let lp_tokens_a = (amount_a * self.total_lp_supply) / self.token_a_reserve;
-            let lp_tokens_b = (amount_b * self.total_lp_supply) / self.token_b_reserve;
+            // Calculate based on proportion of reserves, rounding down so
+            // depositors never receive more than their share
+            let lp_tokens_a = mul_div_floor_u64(amount_a, self.total_lp_supply, self.token_a_reserve)?;
+            let lp_tokens_b = mul_div_floor_u64(amount_b, self.total_lp_supply, self.token_b_reserve)?;
             Ok(lp_tokens_a.min(lp_tokens_b))
         }
     }
     
+    /// LP tokens for a deposit of up to (`amount_a`, `amount_b`) and the
+    /// amounts actually taken. Past the first deposit, the side exceeding the
+    /// pool ratio is trimmed to what the minted LP tokens are worth (rounded
+    /// up), so an imbalanced add keeps its excess instead of donating it.
+    pub fn calculate_deposit(&self, amount_a: u64, amount_b: u64) -> Result<(u64, u64, u64)> {
+        let lp_tokens = self.calculate_lp_tokens_for_liquidity(amount_a, amount_b)?;
+        if self.total_lp_supply == 0 {
+            return Ok((lp_tokens, amount_a, amount_b));
+        }
+        
+        let used_a = mul_div_ceil(lp_tokens as u128, self.token_a_reserve as u128, self.total_lp_supply as u128)?;
+        let used_b = mul_div_ceil(lp_tokens as u128, self.token_b_reserve as u128, self.total_lp_supply as u128)?;
+        Ok((
+            lp_tokens,
+            (used_a as u64).min(amount_a),
+            (used_b as u64).min(amount_b),
+        ))
+    }
+    
     /// Calculate tokens for LP burn
     pub fn calculate_tokens_for_lp_burn(&self, lp_tokens_to_burn: u64) -> Result<(u64, u64)> {
         require!(lp_tokens_to_burn > 0, AmmError::InvalidAmount);
         require!(lp_tokens_to_burn <= self.total_lp_supply, AmmError::InsufficientLPTokens);
         
-        let proportion = lp_tokens_to_burn as f64 / self.total_lp_supply as f64;
-        let token_a_amount = (self.token_a_reserve as f64 * proportion) as u64;
-        let token_b_amount = (self.token_b_reserve as f64 * proportion) as u64;
+        // Round down so withdrawals never take more than their share
+        let token_a_amount = mul_div_floor_u64(self.token_a_reserve, lp_tokens_to_burn, self.total_lp_supply)?;
+        let token_b_amount = mul_div_floor_u64(self.token_b_reserve, lp_tokens_to_burn, self.total_lp_supply)?;
         
         Ok((token_a_amount, token_b_amount))
     }
     
-    /// Update pool state after swap
-    pub fn update_swap_state(&mut self, amount_in: u64, amount_out: u64) -> Result<()> {
-        self.token_a_reserve += amount_in;
-        require!(self.token_b_reserve >= amount_out, AmmError::InsufficientLiquidity);
-        self.token_b_reserve -= amount_out;
+    /// Whether the pool has been frozen for final settlement
+    pub fn is_winding_down(&self) -> bool {
+        self.extensions.has(PoolExtensionField::WindDownStartedAt)
+    }
+    
+    /// Freeze the pool for final settlement. Returns the snapshot LPs settle
+    /// against: (token A owed, token B owed, LP supply), counting claimable
+    /// fees alongside the reserves; outstanding IOUs stay with their holders.
+    pub fn start_wind_down(&mut self, now: i64) -> Result<(u64, u64, u64)> {
+        require!(!self.is_winding_down(), AmmError::PoolWindingDown);
+        self.extensions.set(PoolExtensionField::WindDownStartedAt, now as u64)?;
+        
+        let token_a_amount = self.token_a_reserve
+            .checked_add(self.claimable_fees_a)
+            .ok_or(AmmError::MathOverflow)?;
+        let token_b_amount = self.token_b_reserve
+            .checked_add(self.claimable_fees_b)
+            .ok_or(AmmError::MathOverflow)?;
+        Ok((token_a_amount, token_b_amount, self.total_lp_supply))
+    }
+    
+    /// Update pool state after a swap entering the pool on `input_side`
+    pub fn update_swap_state(&mut self, input_side: PoolSide, amount_in: u64, amount_out: u64) -> Result<()> {
+        require!(!self.is_winding_down(), AmmError::PoolWindingDown);
+        self.enforce_max_swap_size(input_side, amount_in)?;
+        let (_, mint_out) = self.side_reserve(input_side.opposite());
+        let (reserve_in, reserve_out, volume_a) = match input_side {
+            PoolSide::A => (&mut self.token_a_reserve, &mut self.token_b_reserve, amount_in),
+            PoolSide::B => (&mut self.token_b_reserve, &mut self.token_a_reserve, amount_out),
+        };
+        require_ctx!(*reserve_out >= amount_out, AmmError::InsufficientLiquidity, Some(mint_out), None);
+        *reserve_in = reserve_in.checked_add(amount_in).ok_or(AmmError::MathOverflow)?;
+        *reserve_out = reserve_out.checked_sub(amount_out).ok_or(AmmError::MathOverflow)?;
+        let volume = self.cumulative_volume_a().wrapping_add(volume_a);
+        self.extensions.set(PoolExtensionField::CumulativeVolumeA, volume)?;
+        self.refresh_invariant_metric();
+        let fee_amount = self.calculate_swap_fee(amount_in)?;
+        self.stats.record_swap(input_side, amount_in, amount_out, fee_amount, Clock::get()?.unix_timestamp);
+        Ok(())
+    }
+    
+    /// Post-swap check that the curve invariant did not shrink. The curve only
+    /// sees the input net of the swap fee, so even with the whole fee taken out
+    /// of reserves (protocol share, claimable LP fees) the reserves after the
+    /// swap must satisfy new_a * new_b >= old_a * old_b. On weighted and stable
+    /// pools the curve invariants are compared instead, allowing one unit of
+    /// rounding.
+    ///
+    /// Each new reserve is the lesser of the books and what the vault really
+    /// holds for the curve after the transfers: its balance less the
+    /// `protocol_fee` still to leave the input vault, outstanding IOUs and
+    /// claimable fees. A transfer delivering less than booked fails here.
+    pub fn enforce_swap_invariant(
+        &self,
+        reserves_before: (u64, u64),
+        vault_balances: (u64, u64),
+        input_side: PoolSide,
+        protocol_fee: u64,
+    ) -> Result<()> {
+        let (old_a, old_b) = reserves_before;
+        let (protocol_fee_a, protocol_fee_b) = match input_side {
+            PoolSide::A => (protocol_fee, 0),
+            PoolSide::B => (0, protocol_fee),
+        };
+        let backed = |balance: u64, reserve: u64, committed: [u64; 3]| -> Result<u64> {
+            let committed = committed
+                .iter()
+                .try_fold(0u64, |sum, amount| sum.checked_add(*amount))
+                .ok_or(AmmError::MathOverflow)?;
+            Ok(balance.saturating_sub(committed).min(reserve))
+        };
+        let new_a = backed(
+            vault_balances.0,
+            self.token_a_reserve,
+            [protocol_fee_a, self.iou_a_outstanding, self.claimable_fees_a],
+        )?;
+        let new_b = backed(
+            vault_balances.1,
+            self.token_b_reserve,
+            [protocol_fee_b, self.iou_b_outstanding, self.claimable_fees_b],
+        )?;
+        
+        let holds = match self.curve {
+            PoolCurve::ConstantProduct => (new_a as u128) * (new_b as u128) >= (old_a as u128) * (old_b as u128),
+            PoolCurve::Weighted { .. } | PoolCurve::StableSwap { .. } => {
+                self.invariant_of(new_a, new_b)?.saturating_add(1) >= self.invariant_of(old_a, old_b)?
+            }
+        };
+        require!(holds, AmmError::InvariantViolation);
         Ok(())
     }
     
     /// Add liquidity to pool
     pub fn add_liquidity(&mut self, amount_a: u64, amount_b: u64, lp_tokens: u64) -> Result<()> {
-        self.token_a_reserve += amount_a;
-        self.token_b_reserve += amount_b;
-        self.total_lp_supply += lp_tokens;
+        require!(!self.is_winding_down(), AmmError::PoolWindingDown);
+        self.token_a_reserve = self.token_a_reserve.checked_add(amount_a).ok_or(AmmError::MathOverflow)?;
+        self.token_b_reserve = self.token_b_reserve.checked_add(amount_b).ok_or(AmmError::MathOverflow)?;
+        self.total_lp_supply = self.total_lp_supply.checked_add(lp_tokens).ok_or(AmmError::MathOverflow)?;
+        self.refresh_invariant_metric();
         Ok(())
     }
     
     /// Remove liquidity from pool
     pub fn remove_liquidity(&mut self, amount_a: u64, amount_b: u64, lp_tokens: u64) -> Result<()> {
-        require!(self.token_a_reserve >= amount_a, AmmError::InsufficientLiquidity);
-        require!(self.token_b_reserve >= amount_b, AmmError::InsufficientLiquidity);
+        require!(!self.is_winding_down(), AmmError::PoolWindingDown);
+        require_ctx!(self.token_a_reserve >= amount_a, AmmError::InsufficientLiquidity, Some(self.token_a_mint), None);
+        require_ctx!(self.token_b_reserve >= amount_b, AmmError::InsufficientLiquidity, Some(self.token_b_mint), None);
         require!(self.total_lp_supply >= lp_tokens, AmmError::InsufficientLPTokens);
         
-        self.token_a_reserve -= amount_a;
-        self.token_b_reserve -= amount_b;
-        self.total_lp_supply -= lp_tokens;
+        self.token_a_reserve = self.token_a_reserve.checked_sub(amount_a).ok_or(AmmError::MathOverflow)?;
+        self.token_b_reserve = self.token_b_reserve.checked_sub(amount_b).ok_or(AmmError::MathOverflow)?;
+        self.total_lp_supply = self.total_lp_supply.checked_sub(lp_tokens).ok_or(AmmError::MathOverflow)?;
+        self.refresh_invariant_metric();
         Ok(())
     }
     
-    /// Get current price ratio
-    pub fn get_price_ratio(&self) -> Result<f64> {
-        require!(self.token_a_reserve > 0, AmmError::InsufficientLiquidity);
-        require!(self.token_b_reserve > 0, AmmError::InsufficientLiquidity);
+    /// Move an amount already withdrawn from reserves into the IOU bucket for `side`
+    pub fn record_iou(&mut self, side: PoolSide, amount: u64) -> Result<()> {
+        let outstanding = match side {
+            PoolSide::A => &mut self.iou_a_outstanding,
+            PoolSide::B => &mut self.iou_b_outstanding,
+        };
+        *outstanding = outstanding.checked_add(amount).ok_or(AmmError::MathOverflow)?;
+        Ok(())
+    }
+    
+    /// Settle a redeemed IOU for `side`
+    pub fn settle_iou(&mut self, side: PoolSide, amount: u64) -> Result<()> {
+        let outstanding = match side {
+            PoolSide::A => &mut self.iou_a_outstanding,
+            PoolSide::B => &mut self.iou_b_outstanding,
+        };
+        *outstanding = outstanding.checked_sub(amount).ok_or(AmmError::MathOverflow)?;
+        Ok(())
+    }
+    
+    /// Reconcile the `side` reserve with the vault's real balance. The vault
+    /// also backs outstanding IOUs and claimable fees, so the reserve becomes
+    /// whatever the vault holds beyond those. With `skim` set, a surplus over
+    /// the recorded reserve is left out of it for the caller to sweep.
+    /// Returns (previous reserve, surplus to skim).
+    pub fn sync_reserve(&mut self, side: PoolSide, vault_balance: u64, skim: bool) -> Result<(u64, u64)> {
+        require!(!self.is_winding_down(), AmmError::PoolWindingDown);
+        let (reserve, iou_outstanding, claimable_fees, mint) = match side {
+            PoolSide::A => (&mut self.token_a_reserve, self.iou_a_outstanding, self.claimable_fees_a, self.token_a_mint),
+            PoolSide::B => (&mut self.token_b_reserve, self.iou_b_outstanding, self.claimable_fees_b, self.token_b_mint),
+        };
+        let committed = iou_outstanding.checked_add(claimable_fees).ok_or(AmmError::MathOverflow)?;
+        require_ctx!(vault_balance >= committed, AmmError::VaultBelowObligations, Some(mint), None);
+        let backed = vault_balance.checked_sub(committed).ok_or(AmmError::MathOverflow)?;
+        
+        let previous_reserve = *reserve;
+        let surplus = if skim { backed.saturating_sub(previous_reserve) } else { 0 };
+        *reserve = backed.checked_sub(surplus).ok_or(AmmError::MathOverflow)?;
+        Ok((previous_reserve, surplus))
+    }
+    
+    /// Invariant of the curve at reserves (a, b), in token units: sqrt(a * b),
+    /// the weighted geometric mean a^w_a * b^w_b on a weighted pool, or the
+    /// StableSwap D (a + b at a 1:1 price) on a stable pool
+    pub fn invariant_of(&self, amount_a: u64, amount_b: u64) -> Result<u64> {
+        match self.curve {
+            PoolCurve::ConstantProduct => u64::try_from(sqrt_u128((amount_a as u128) * (amount_b as u128)))
+                .map_err(|_| AmmError::MathOverflow.into()),
+            PoolCurve::Weighted { weight_a_bps, weight_b_bps } => {
+                weighted_geometric_mean(amount_a, amount_b, weight_a_bps, weight_b_bps)
+            }
+            PoolCurve::StableSwap { amp } => {
+                u64::try_from(math_result(stable_invariant(amount_a as u128, amount_b as u128, amp))?)
+                    .map_err(|_| AmmError::MathOverflow.into())
+            }
+        }
+    }
+    
+    /// Invariant of the current reserves
+    pub fn invariant(&self) -> u64 {
+        self.invariant_of(self.token_a_reserve, self.token_b_reserve).unwrap_or(0)
+    }
+    
+    /// Pool invariant per LP token (Q64.64), computed from the current reserves
+    pub fn calculate_invariant_per_lp_x64(&self) -> u128 {
+        if self.total_lp_supply == 0 {
+            return 0;
+        }
+        ((self.invariant() as u128) << 64) / (self.total_lp_supply as u128)
+    }
+    
+    /// Record the invariant-per-LP metric after a reserve or supply change.
+    /// The baseline is (re)set whenever the pool is seeded from empty.
+    pub fn refresh_invariant_metric(&mut self) {
+        self.invariant_per_lp_x64 = self.calculate_invariant_per_lp_x64();
+        if self.total_lp_supply == 0 {
+            self.initial_invariant_per_lp_x64 = 0;
+        } else if self.initial_invariant_per_lp_x64 == 0 {
+            self.initial_invariant_per_lp_x64 = self.invariant_per_lp_x64;
+        }
+    }
+    
+    /// Get current price ratio (token B per token A, Q64.64)
+    pub fn get_price_ratio(&self) -> Result<u128> {
+        require_ctx!(self.token_a_reserve > 0, AmmError::InsufficientLiquidity, Some(self.token_a_mint), None);
+        require_ctx!(self.token_b_reserve > 0, AmmError::InsufficientLiquidity, Some(self.token_b_mint), None);
         
-        Ok(self.token_b_reserve as f64 / self.token_a_reserve as f64)
+        self.spot_price_x64(PoolSide::A)
+    }
+    
+    /// Spot price of the `base` side's token in the other token (Q64.64),
+    /// weighted on a weighted pool: (quote / w_quote) / (base / w_base), and
+    /// the slope of the StableSwap curve on a stable pool
+    pub fn spot_price_x64(&self, base: PoolSide) -> Result<u128> {
+        let (reserve_base, _) = self.side_reserve(base);
+        let (reserve_quote, _) = self.side_reserve(base.opposite());
+        require!(reserve_base > 0 && reserve_quote > 0, AmmError::InsufficientLiquidity);
+        
+        if let PoolCurve::StableSwap { amp } = self.curve {
+            return math_result(stable_spot_price_x64(reserve_base as u128, reserve_quote as u128, amp));
+        }
+        let (weight_base, weight_quote) = self.curve.weights(base);
+        mul_div_floor(ratio_x64(reserve_quote, reserve_base)?, weight_base as u128, weight_quote as u128)
     }
     
     /// Get pool information
     pub fn get_pool_info(&self) -> (u64, u64, u64) {
         (self.token_a_reserve, self.token_b_reserve, self.total_lp_supply)
     }
-} 
\ No newline at end of file
+    
+    /// Release the reentrancy lock taken by `lock_pool`
+    pub fn unlock(&mut self) {
+        self.locked = false;
+    }
+}
+
+/// Take the pool's reentrancy lock and write it to the account right away, so
+/// an instruction re-entered from a Transfer Hook CPI deserializes the pool as
+/// locked and is rejected
+pub fn lock_pool(pool: &mut Account<AmmPool>) -> Result<()> {
+    require!(!pool.locked, AmmError::PoolLocked);
+    pool.locked = true;
+    pool.exit(&crate::ID)
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    
+    fn error_code(result: Result<impl core::fmt::Debug>) -> u32 {
+        match result.unwrap_err() {
+            Error::AnchorError(error) => error.error_code_number,
+            error => panic!("unexpected error: {error:?}"),
+        }
+    }
+    
+    fn code(error: AmmError) -> u32 {
+        u32::from(error)
+    }
+    
+    fn pool_with(token_a_reserve: u64, token_b_reserve: u64, total_lp_supply: u64) -> AmmPool {
+        AmmPool { token_a_reserve, token_b_reserve, total_lp_supply, ..AmmPool::default() }
+    }
+    
+    #[test]
+    fn add_liquidity_fills_reserves_to_u64_max_and_no_further() {
+        let mut pool = pool_with(u64::MAX - 1, u64::MAX - 1, u64::MAX - 1);
+        pool.add_liquidity(1, 1, 1).unwrap();
+        assert_eq!(pool.get_pool_info(), (u64::MAX, u64::MAX, u64::MAX));
+        
+        for (a, b, lp) in [(1, 0, 0), (0, 1, 0), (0, 0, 1)] {
+            let mut pool = pool_with(u64::MAX, u64::MAX, u64::MAX);
+            assert_eq!(error_code(pool.add_liquidity(a, b, lp)), code(AmmError::MathOverflow));
+        }
+    }
+    
+    #[test]
+    fn remove_liquidity_drains_u64_max_reserves_exactly() {
+        let mut pool = pool_with(u64::MAX, u64::MAX, u64::MAX);
+        assert_eq!(pool.calculate_tokens_for_lp_burn(u64::MAX).unwrap(), (u64::MAX, u64::MAX));
+        pool.remove_liquidity(u64::MAX, u64::MAX, u64::MAX).unwrap();
+        assert_eq!(pool.get_pool_info(), (0, 0, 0));
+        
+        assert_eq!(error_code(pool.remove_liquidity(1, 0, 0)), code(AmmError::InsufficientLiquidity));
+        assert_eq!(error_code(pool.remove_liquidity(0, 0, 1)), code(AmmError::InsufficientLPTokens));
+    }
+    
+    #[test]
+    fn ious_stop_at_u64_max_and_zero() {
+        let mut pool = pool_with(0, 0, 0);
+        pool.record_iou(PoolSide::A, u64::MAX).unwrap();
+        assert_eq!(error_code(pool.record_iou(PoolSide::A, 1)), code(AmmError::MathOverflow));
+        pool.settle_iou(PoolSide::A, u64::MAX).unwrap();
+        assert_eq!(error_code(pool.settle_iou(PoolSide::A, 1)), code(AmmError::MathOverflow));
+    }
+    
+    #[test]
+    fn claimable_fees_payout_is_capped_by_the_bucket() {
+        let mut pool = pool_with(0, 0, 0);
+        pool.claimable_fees_a = u64::MAX;
+        pool.claimable_fees_b = 1;
+        assert_eq!(pool.collect_claimable_fees(u64::MAX, u64::MAX).unwrap(), (u64::MAX, 1));
+        assert_eq!(pool.collect_claimable_fees(u64::MAX, u64::MAX).unwrap(), (0, 0));
+    }
+    
+    #[test]
+    fn sync_reserve_handles_max_obligations() {
+        let mut pool = pool_with(5, 0, 0);
+        pool.iou_a_outstanding = u64::MAX;
+        assert_eq!(pool.sync_reserve(PoolSide::A, u64::MAX, false).unwrap(), (5, 0));
+        assert_eq!(pool.token_a_reserve, 0);
+        assert_eq!(error_code(pool.sync_reserve(PoolSide::A, u64::MAX - 1, false)), code(AmmError::VaultBelowObligations));
+        
+        pool.claimable_fees_a = 1;
+        assert_eq!(error_code(pool.sync_reserve(PoolSide::A, u64::MAX, false)), code(AmmError::MathOverflow));
+    }
+    
+    #[test]
+    fn accrue_swap_fee_handles_max_fee_weight() {
+        let mut pool = pool_with(u64::MAX, 0, u64::MAX);
+        pool.fee_mode = FeeMode::Claimable;
+        pool.boosted_fee_weight = u64::MAX;
+        pool.accrue_swap_fee(PoolSide::A, u64::MAX).unwrap();
+        assert_eq!(pool.token_a_reserve, 0);
+        assert_eq!(pool.claimable_fees_a, u64::MAX);
+        
+        // A second fee has no reserve left to come out of
+        assert_eq!(error_code(pool.accrue_swap_fee(PoolSide::A, 1)), code(AmmError::MathOverflow));
+    }
+    
+    #[test]
+    fn price_impact_near_u64_max_reserves() {
+        let half = 1u64 << 63;
+        let pool = pool_with(half, half, 0);
+        assert_eq!(pool.price_impact_bps(PoolSide::A, half, half / 2).unwrap(), 7_500);
+        
+        let pool = pool_with(u64::MAX, u64::MAX, 0);
+        assert_eq!(pool.price_impact_bps(PoolSide::A, 1, 0).unwrap(), 1);
+        assert_eq!(error_code(pool.price_impact_bps(PoolSide::A, 1, u64::MAX)), code(AmmError::InsufficientLiquidity));
+        // y * (x + dx) leaves u128 instead of wrapping
+        assert_eq!(error_code(pool.price_impact_bps(PoolSide::A, u64::MAX, 1)), code(AmmError::MathOverflow));
+    }
+    
+    #[test]
+    fn fee_ramp_interpolates_without_overflow() {
+        let ramp = FeeRamp { start_fee_rate: 0, end_fee_rate: 9_999, start_time: 0, end_time: i64::MAX };
+        assert_eq!(ramp.fee_rate_at(i64::MAX - 1).unwrap(), 9_998);
+        assert_eq!(ramp.fee_rate_at(i64::MAX / 2 + 1).unwrap(), 4_999);
+        
+        let down = FeeRamp { start_fee_rate: 9_999, end_fee_rate: 0, start_time: 0, end_time: i64::MAX };
+        assert_eq!(down.fee_rate_at(i64::MAX - 1).unwrap(), 1);
+        
+        // The ramp's span no longer fits an i64
+        let unbounded = FeeRamp { start_fee_rate: 0, end_fee_rate: 1, start_time: i64::MIN, end_time: i64::MAX };
+        assert_eq!(error_code(unbounded.fee_rate_at(0)), code(AmmError::MathOverflow));
+    }
+    
+    #[test]
+    fn hook_blocked_flags_are_per_side() {
+        let mut pool = pool_with(0, 0, 0);
+        pool.set_paused(true).unwrap();
+        pool.set_hook_blocked(PoolSide::B, true).unwrap();
+        assert!(pool.is_hook_blocked(PoolSide::B) && !pool.is_hook_blocked(PoolSide::A));
+        
+        pool.set_hook_blocked(PoolSide::A, true).unwrap();
+        pool.set_hook_blocked(PoolSide::B, false).unwrap();
+        assert!(pool.is_hook_blocked(PoolSide::A) && !pool.is_hook_blocked(PoolSide::B));
+        assert!(pool.is_paused() && !pool.is_withdraw_only());
+    }
+    
+    #[test]
+    fn dynamic_fee_caps_volatility() {
+        let fee = DynamicFee { min_fee_rate: 5, max_fee_rate: 100, max_volatility_bps: 1_000, window: 60, current_fee_rate: 0 };
+        assert_eq!(fee.fee_rate_for(0).unwrap(), 5);
+        assert_eq!(fee.fee_rate_for(500).unwrap(), 52);
+        assert_eq!(fee.fee_rate_for(u64::MAX).unwrap(), 100);
+        
+        let inverted = DynamicFee { min_fee_rate: 100, max_fee_rate: 5, ..fee };
+        assert_eq!(error_code(inverted.fee_rate_for(1)), code(AmmError::MathOverflow));
+    }
+    
+    #[test]
+    fn exit_fee_decays_over_extreme_hold_times() {
+        let mut pool = pool_with(0, 0, 0);
+        pool.set_exit_fee(MAX_EXIT_FEE_BPS, i64::MAX).unwrap();
+        assert_eq!(pool.exit_fee_bps_at(0, 0).unwrap(), MAX_EXIT_FEE_BPS);
+        assert_eq!(pool.exit_fee_bps_at(i64::MIN, i64::MAX).unwrap(), 0);
+        assert_eq!(pool.exit_fee_bps_at(i64::MAX, i64::MIN).unwrap(), MAX_EXIT_FEE_BPS);
+    }
+    
+    #[test]
+    fn swap_invariant_checks_the_vault_balances() {
+        let pool = pool_with(1_100, 910, 1);
+        assert!(pool.enforce_swap_invariant((1_000, 1_001), (1_105, 910), PoolSide::A, 5).is_ok());
+        
+        // The input vault received one unit less than the books credited
+        assert_eq!(
+            error_code(pool.enforce_swap_invariant((1_000, 1_001), (1_104, 910), PoolSide::A, 5)),
+            code(AmmError::InvariantViolation)
+        );
+        // A donation sitting in the output vault does not cover for the books
+        let overpaid = pool_with(1_100, 900, 1);
+        assert_eq!(
+            error_code(overpaid.enforce_swap_invariant((1_000, 1_001), (1_105, 1_000), PoolSide::A, 5)),
+            code(AmmError::InvariantViolation)
+        );
+    }
+    
+    fn pool_curve() -> impl Strategy<Value = PoolCurve> {
+        prop_oneof![
+            Just(PoolCurve::ConstantProduct),
+            (MIN_POOL_WEIGHT_BPS..=BPS_DENOMINATOR - MIN_POOL_WEIGHT_BPS).prop_map(|weight_a_bps| PoolCurve::Weighted {
+                weight_a_bps,
+                weight_b_bps: BPS_DENOMINATOR - weight_a_bps,
+            }),
+            (1..=MAX_STABLE_AMP).prop_map(|amp| PoolCurve::StableSwap { amp }),
+        ]
+    }
+    
+    fn side() -> impl Strategy<Value = PoolSide> {
+        prop_oneof![Just(PoolSide::A), Just(PoolSide::B)]
+    }
+    
+    // Every validator must land on the same integers as an exact u128
+    // reference computed off-chain, and as the amm-math quote clients use.
+    proptest! {
+        #[test]
+        fn lp_burn_matches_exact_floor(a in 1..=u64::MAX, b in 1..=u64::MAX, supply in 1..=u64::MAX, burn_bps in 1u64..=10_000) {
+            let pool = pool_with(a, b, supply);
+            let burn = ((supply as u128 * burn_bps as u128 / 10_000) as u64).max(1);
+            let (out_a, out_b) = pool.calculate_tokens_for_lp_burn(burn).unwrap();
+            prop_assert_eq!(out_a as u128, a as u128 * burn as u128 / supply as u128);
+            prop_assert_eq!(out_b as u128, b as u128 * burn as u128 / supply as u128);
+        }
+        
+        #[test]
+        fn split_lp_burn_never_pays_more(a in 1..=u64::MAX, b in 1..=u64::MAX, supply in 2..=u64::MAX, split in 1..=u64::MAX) {
+            let mut pool = pool_with(a, b, supply);
+            let first = split % (supply - 1) + 1;
+            let (whole_a, whole_b) = pool.calculate_tokens_for_lp_burn(supply).unwrap();
+            
+            let (first_a, first_b) = pool.calculate_tokens_for_lp_burn(first).unwrap();
+            pool.remove_liquidity(first_a, first_b, first).unwrap();
+            let (rest_a, rest_b) = pool.calculate_tokens_for_lp_burn(supply - first).unwrap();
+            prop_assert!(first_a as u128 + rest_a as u128 <= whole_a as u128);
+            prop_assert!(first_b as u128 + rest_b as u128 <= whole_b as u128);
+        }
+        
+        #[test]
+        fn deposit_never_dilutes(a in 1..=u64::MAX / 2, b in 1..=u64::MAX / 2, supply in 1..=u64::MAX / 2, add_a in 1..=u64::MAX / 2, add_b in 1..=u64::MAX / 2) {
+            let pool = pool_with(a, b, supply);
+            let Ok((lp, used_a, used_b)) = pool.calculate_deposit(add_a, add_b) else { return Ok(()) };
+            prop_assert!(used_a <= add_a && used_b <= add_b);
+            prop_assert!(used_a as u128 * supply as u128 >= lp as u128 * a as u128);
+            prop_assert!(used_b as u128 * supply as u128 >= lp as u128 * b as u128);
+        }
+        
+        #[test]
+        fn swap_output_matches_client_curve(curve in pool_curve(), input_side in side(), reserve_in in 1..=u64::MAX, reserve_out in 1..=u64::MAX, amount_in in 0..=u64::MAX) {
+            let pool = AmmPool { curve, ..AmmPool::default() };
+            let client = match curve {
+                PoolCurve::ConstantProduct => Curve::ConstantProduct,
+                PoolCurve::Weighted { weight_a_bps, weight_b_bps } => match input_side {
+                    PoolSide::A => Curve::Weighted { weight_in: weight_a_bps, weight_out: weight_b_bps },
+                    PoolSide::B => Curve::Weighted { weight_in: weight_b_bps, weight_out: weight_a_bps },
+                },
+                PoolCurve::StableSwap { amp } => Curve::StableSwap { amp },
+            };
+            let on_chain = pool.curve_output(input_side, reserve_in, reserve_out, amount_in as u128).ok();
+            prop_assert_eq!(on_chain, client.output(reserve_in, reserve_out, amount_in as u128).ok());
+        }
+        
+        #[test]
+        fn swap_output_passes_the_invariant_check(curve in pool_curve(), reserve_a in 1..=u64::MAX / 2, reserve_b in 1..=u64::MAX / 2, amount_in in 0..=u64::MAX / 2) {
+            let pool = AmmPool { curve, ..pool_with(reserve_a, reserve_b, 1) };
+            // The stable curve's Newton steps may overflow on wildly imbalanced
+            // reserves, which rejects the swap rather than mispricing it
+            let Ok(amount_out) = pool.curve_output(PoolSide::A, reserve_a, reserve_b, amount_in as u128) else {
+                prop_assert!(matches!(curve, PoolCurve::StableSwap { .. }), "{curve:?} failed to quote");
+                return Ok(());
+            };
+            let after = AmmPool { token_a_reserve: reserve_a + amount_in, token_b_reserve: reserve_b - amount_out as u64, ..pool };
+            let vault_balances = (after.token_a_reserve, after.token_b_reserve);
+            prop_assert!(after.enforce_swap_invariant((reserve_a, reserve_b), vault_balances, PoolSide::A, 0).is_ok());
+        }
+        
+        #[test]
+        fn swap_fee_comes_off_the_input_before_the_curve(curve in pool_curve(), input_side in side(), reserve_in in 1..=u64::MAX, reserve_out in 1..=u64::MAX, amount_in in 1..=u64::MAX, fee_rate in 0..BPS_DENOMINATOR) {
+            let pool = AmmPool { curve, ..AmmPool::default() };
+            let fee_amount = (amount_in as u128 * fee_rate as u128 / BPS_DENOMINATOR as u128) as u64;
+            prop_assert_eq!(
+                pool.output_after_swap_fee(input_side, reserve_in, reserve_out, amount_in, fee_rate).ok(),
+                pool.curve_output(input_side, reserve_in, reserve_out, (amount_in - fee_amount) as u128).ok()
+            );
+        }
+        
+        #[test]
+        fn price_ratio_matches_exact_q64(a in 1..=u64::MAX, b in 1..=u64::MAX) {
+            let pool = pool_with(a, b, 0);
+            prop_assert_eq!(pool.get_price_ratio().unwrap(), ((b as u128) << 64) / a as u128);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+
+/// Balance Snapshot
+/// Records a token account balance at the start of a client-composed
+/// transaction so a trailing `assert_balance_change` can bound the net effect
+#[account]
+#[derive(Default)]
+pub struct BalanceSnapshot {
+    /// Wallet that recorded the snapshot (receives the rent back)
+    pub owner: Pubkey,
+
+    /// Token account being tracked
+    pub token_account: Pubkey,
+
+    /// Token account balance when the snapshot was taken
+    pub amount: u64,
+
+    /// Slot the snapshot was taken in
+    pub slot: u64,
+
+    /// Snapshot bump seed
+    pub bump: u8,
+}
+
+impl BalanceSnapshot {
+    pub const SEED: &'static [u8] = b"balance_snapshot";
+
+    /// Record the starting balance of a token account
+    pub fn record(
+        &mut self,
+        owner: Pubkey,
+        token_account: Pubkey,
+        amount: u64,
+        slot: u64,
+        bump: u8,
+    ) -> Result<()> {
+        self.owner = owner;
+        self.token_account = token_account;
+        self.amount = amount;
+        self.slot = slot;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Check that the balance moved by an amount within `[min_delta, max_delta]`.
+    /// Returns the observed delta.
+    pub fn check_delta(
+        &self,
+        current_amount: u64,
+        current_slot: u64,
+        min_delta: i64,
+        max_delta: i64,
+    ) -> Result<i128> {
+        require!(min_delta <= max_delta, AmmError::InvalidBalanceDeltaRange);
+        require!(current_slot == self.slot, AmmError::StaleBalanceSnapshot);
+
+        let delta = current_amount as i128 - self.amount as i128;
+        require!(
+            delta >= min_delta as i128 && delta <= max_delta as i128,
+            AmmError::BalanceChangeOutOfRange
+        );
+
+        Ok(delta)
+    }
+}
@@ -0,0 +1,483 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+use crate::state::clmm_math::{
+    sqrt_price_at_tick, tick_at_sqrt_price, amount_a_delta, amount_b_delta, to_amount, mul_shr,
+    MIN_TICK, MAX_TICK, MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64, MAX_LIQUIDITY,
+};
+
+/// Ticks held by one tick array
+pub const TICK_ARRAY_SIZE: usize = 32;
+
+/// Widest tick spacing a pool can use
+pub const MAX_TICK_SPACING: u16 = 1_000;
+
+/// Tick spacing used by a concentrated liquidity pool in `fee_tier` (bps)
+pub fn tick_spacing_for_fee_tier(fee_tier: u64) -> u16 {
+    match fee_tier {
+        0 | 1 => 1,
+        _ => (fee_tier * 2).min(MAX_TICK_SPACING as u64) as u16,
+    }
+}
+
+/// Concentrated Liquidity Pool
+/// Pool variant where LPs provide liquidity over a chosen price range
+/// (`ClmmPosition`) instead of the whole curve. Liquidity changes at the
+/// range edges are recorded on ticks grouped into `TickArray` accounts; a
+/// swap walks those ticks, switching the active liquidity as it crosses them.
+#[account]
+#[derive(Default)]
+pub struct ClmmPool {
+    /// Pool authority
+    pub authority: Pubkey,
+
+    /// Token A mint
+    pub token_a_mint: Pubkey,
+
+    /// Token B mint
+    pub token_b_mint: Pubkey,
+
+    /// Token A vault
+    pub token_a_vault: Pubkey,
+
+    /// Token B vault
+    pub token_b_vault: Pubkey,
+
+    /// Swap fee (basis points); fixed to the pool's fee tier
+    pub fee_rate: u64,
+
+    /// Distance between usable ticks
+    pub tick_spacing: u16,
+
+    /// Current square root price of token A in token B (Q64.64)
+    pub sqrt_price_x64: u128,
+
+    /// Greatest tick at or below the current price
+    pub tick_current: i32,
+
+    /// Liquidity of the positions in range at the current price
+    pub liquidity: u128,
+
+    /// Liquidity of all positions, in range or not
+    pub total_liquidity: u128,
+
+    /// Token A fees earned per unit of liquidity over the pool's life (Q64.64)
+    pub fee_growth_global_a_x64: u128,
+
+    /// Token B fees earned per unit of liquidity over the pool's life (Q64.64)
+    pub fee_growth_global_b_x64: u128,
+
+    /// Pool bump seed
+    pub bump: u8,
+
+    /// Bump of the PDA owning the pool vaults
+    pub vault_authority_bump: u8,
+
+    /// Set while an instruction is inside its hook-invoking transfers, so a
+    /// hook re-entering the AMM finds the pool locked
+    pub locked: bool,
+
+    /// Set by the emergency authority to block swaps and deposits;
+    /// withdrawals stay open either way
+    pub paused: bool,
+
+    /// Pool-specific hook whitelist (default: use the AMM config's whitelist)
+    pub hook_whitelist: Pubkey,
+}
+
+impl ClmmPool {
+    /// Seed of a pool PDA, derived as [SEED, token_a_mint, token_b_mint, fee_tier (LE)]
+    pub const SEED: &'static [u8] = b"clmm_pool";
+
+    /// Seed of the PDA owning the pool vaults, derived as [pool, VAULT_AUTHORITY_SEED]
+    pub const VAULT_AUTHORITY_SEED: &'static [u8] = b"vault_auth";
+
+    /// Seed of a pool's own whitelist, derived as [POOL_WHITELIST_SEED, pool]
+    pub const POOL_WHITELIST_SEED: &'static [u8] = b"pool_whitelist";
+
+    pub fn initialize(
+        &mut self,
+        authority: Pubkey,
+        token_a_mint: Pubkey,
+        token_b_mint: Pubkey,
+        token_a_vault: Pubkey,
+        token_b_vault: Pubkey,
+        fee_rate: u64,
+        sqrt_price_x64: u128,
+        bump: u8,
+        vault_authority_bump: u8,
+    ) -> Result<()> {
+        require!(token_a_mint < token_b_mint, AmmError::InvalidTokenPairOrder);
+        require!(
+            (MIN_SQRT_PRICE_X64..MAX_SQRT_PRICE_X64).contains(&sqrt_price_x64),
+            AmmError::InvalidSqrtPrice
+        );
+
+        self.authority = authority;
+        self.token_a_mint = token_a_mint;
+        self.token_b_mint = token_b_mint;
+        self.token_a_vault = token_a_vault;
+        self.token_b_vault = token_b_vault;
+        self.fee_rate = fee_rate;
+        self.tick_spacing = tick_spacing_for_fee_tier(fee_rate);
+        self.sqrt_price_x64 = sqrt_price_x64;
+        self.tick_current = tick_at_sqrt_price(sqrt_price_x64)?;
+        self.liquidity = 0;
+        self.total_liquidity = 0;
+        self.fee_growth_global_a_x64 = 0;
+        self.fee_growth_global_b_x64 = 0;
+        self.bump = bump;
+        self.vault_authority_bump = vault_authority_bump;
+        self.locked = false;
+        self.paused = false;
+        self.hook_whitelist = Pubkey::default();
+        Ok(())
+    }
+
+    /// Whitelist this pool's mints are validated against: its own list when
+    /// one is set, otherwise `global_whitelist`
+    pub fn effective_whitelist(&self, global_whitelist: Pubkey) -> Pubkey {
+        if self.hook_whitelist == Pubkey::default() {
+            global_whitelist
+        } else {
+            self.hook_whitelist
+        }
+    }
+
+    /// Release the reentrancy lock taken by `lock_clmm_pool`
+    pub fn unlock(&mut self) {
+        self.locked = false;
+    }
+
+    /// Ticks covered by one tick array
+    pub fn tick_array_span(&self) -> i32 {
+        TICK_ARRAY_SIZE as i32 * self.tick_spacing as i32
+    }
+
+    /// Start tick of the array holding `tick`
+    pub fn tick_array_start(&self, tick: i32) -> i32 {
+        tick.div_euclid(self.tick_array_span()) * self.tick_array_span()
+    }
+
+    /// Check a position range: ordered, in bounds and on the tick spacing
+    pub fn validate_tick_range(&self, tick_lower: i32, tick_upper: i32) -> Result<()> {
+        let spacing = self.tick_spacing as i32;
+        require!(tick_lower < tick_upper, AmmError::InvalidTickRange);
+        require!(tick_lower >= MIN_TICK && tick_upper <= MAX_TICK, AmmError::InvalidTickRange);
+        require!(
+            tick_lower % spacing == 0 && tick_upper % spacing == 0,
+            AmmError::InvalidTickRange
+        );
+        Ok(())
+    }
+
+    /// Token amounts backing `liquidity` over a range at the current price
+    pub fn amounts_for_liquidity(
+        &self,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        round_up: bool,
+    ) -> Result<(u64, u64)> {
+        let sqrt_lower = sqrt_price_at_tick(tick_lower)?;
+        let sqrt_upper = sqrt_price_at_tick(tick_upper)?;
+
+        let (amount_a, amount_b) = if self.tick_current < tick_lower {
+            (amount_a_delta(sqrt_lower, sqrt_upper, liquidity, round_up)?, 0)
+        } else if self.tick_current < tick_upper {
+            (
+                amount_a_delta(self.sqrt_price_x64, sqrt_upper, liquidity, round_up)?,
+                amount_b_delta(sqrt_lower, self.sqrt_price_x64, liquidity, round_up)?,
+            )
+        } else {
+            (0, amount_b_delta(sqrt_lower, sqrt_upper, liquidity, round_up)?)
+        };
+        Ok((to_amount(amount_a)?, to_amount(amount_b)?))
+    }
+
+    /// Fee growth (A, B) inside a range, from the range's two edge ticks
+    pub fn fee_growth_inside(&self, tick_lower: i32, lower: &Tick, tick_upper: i32, upper: &Tick) -> (u128, u128) {
+        let (below_a, below_b) = if self.tick_current >= tick_lower {
+            (lower.fee_growth_outside_a_x64, lower.fee_growth_outside_b_x64)
+        } else {
+            (
+                self.fee_growth_global_a_x64.wrapping_sub(lower.fee_growth_outside_a_x64),
+                self.fee_growth_global_b_x64.wrapping_sub(lower.fee_growth_outside_b_x64),
+            )
+        };
+        let (above_a, above_b) = if self.tick_current < tick_upper {
+            (upper.fee_growth_outside_a_x64, upper.fee_growth_outside_b_x64)
+        } else {
+            (
+                self.fee_growth_global_a_x64.wrapping_sub(upper.fee_growth_outside_a_x64),
+                self.fee_growth_global_b_x64.wrapping_sub(upper.fee_growth_outside_b_x64),
+            )
+        };
+        (
+            self.fee_growth_global_a_x64.wrapping_sub(below_a).wrapping_sub(above_a),
+            self.fee_growth_global_b_x64.wrapping_sub(below_b).wrapping_sub(above_b),
+        )
+    }
+
+    /// Add to or remove from the total and, when the range is active, the
+    /// in-range liquidity
+    pub fn apply_liquidity_delta(&mut self, tick_lower: i32, tick_upper: i32, delta: i128) -> Result<()> {
+        self.total_liquidity = add_delta(self.total_liquidity, delta)?;
+        require!(self.total_liquidity <= MAX_LIQUIDITY, AmmError::LiquidityOverflow);
+        if self.tick_current >= tick_lower && self.tick_current < tick_upper {
+            self.liquidity = add_delta(self.liquidity, delta)?;
+        }
+        Ok(())
+    }
+
+    /// Credit a swap fee paid in the input token to in-range liquidity
+    pub fn accrue_fee(&mut self, fee_amount: u64, a_to_b: bool) {
+        if self.liquidity == 0 || fee_amount == 0 {
+            return;
+        }
+        let growth = ((fee_amount as u128) << 64) / self.liquidity;
+        if a_to_b {
+            self.fee_growth_global_a_x64 = self.fee_growth_global_a_x64.wrapping_add(growth);
+        } else {
+            self.fee_growth_global_b_x64 = self.fee_growth_global_b_x64.wrapping_add(growth);
+        }
+    }
+}
+
+/// Take the pool's reentrancy lock and write it to the account right away, so
+/// an instruction re-entered from a Transfer Hook CPI deserializes the pool as
+/// locked and is rejected
+pub fn lock_clmm_pool(pool: &mut Account<ClmmPool>) -> Result<()> {
+    require!(!pool.locked, AmmError::PoolLocked);
+    pool.locked = true;
+    pool.exit(&crate::ID)
+}
+
+/// `value + delta`, failing on underflow or overflow
+pub fn add_delta(value: u128, delta: i128) -> Result<u128> {
+    if delta >= 0 {
+        value.checked_add(delta as u128).ok_or(AmmError::LiquidityOverflow.into())
+    } else {
+        value
+            .checked_sub(delta.unsigned_abs())
+            .ok_or(AmmError::InsufficientPositionLiquidity.into())
+    }
+}
+
+/// Liquidity change and fee bookkeeping at one tick
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tick {
+    /// Whether any position uses this tick as an edge
+    pub initialized: bool,
+    /// Liquidity added to the in-range liquidity when the price crosses up
+    pub liquidity_net: i128,
+    /// Liquidity of all positions using this tick as an edge
+    pub liquidity_gross: u128,
+    /// Token A fee growth on the other side of this tick from the current price
+    pub fee_growth_outside_a_x64: u128,
+    /// Token B fee growth on the other side of this tick from the current price
+    pub fee_growth_outside_b_x64: u128,
+}
+
+/// Tick Array
+/// `TICK_ARRAY_SIZE` consecutive usable ticks of a pool, starting at
+/// `start_tick_index`. Instructions load tick arrays by hand (see `load`),
+/// so a range whose edges share an array can pass the same account twice.
+#[account]
+#[derive(Default)]
+pub struct TickArray {
+    /// Pool the ticks belong to
+    pub pool: Pubkey,
+
+    /// First tick of the array (a multiple of the pool's array span)
+    pub start_tick_index: i32,
+
+    /// Ticks, one per `tick_spacing`
+    pub ticks: [Tick; TICK_ARRAY_SIZE],
+}
+
+impl TickArray {
+    /// Seed of a tick array PDA, derived as [SEED, pool, start_tick_index (LE)]
+    pub const SEED: &'static [u8] = b"tick_array";
+
+    /// Deserialize the tick array in `info`, checking it belongs to `pool`
+    pub fn load(info: &AccountInfo, pool: &Pubkey) -> Result<Box<TickArray>> {
+        require_keys_eq!(*info.owner, crate::ID, AmmError::InvalidTickArray);
+        let data = info.try_borrow_data()?;
+        let tick_array = Box::new(TickArray::try_deserialize(&mut &data[..])?);
+        require_keys_eq!(tick_array.pool, *pool, AmmError::InvalidTickArray);
+        Ok(tick_array)
+    }
+
+    /// Write the tick array back into `info`
+    pub fn store(&self, info: &AccountInfo) -> Result<()> {
+        require!(info.is_writable, AmmError::InvalidTickArray);
+        let mut data = info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data[..];
+        self.try_serialize(&mut writer)
+    }
+
+    /// Whether `tick` falls in this array
+    pub fn contains(&self, pool: &ClmmPool, tick: i32) -> bool {
+        tick >= self.start_tick_index && tick < self.start_tick_index + pool.tick_array_span()
+    }
+
+    fn offset(&self, pool: &ClmmPool, tick: i32) -> Result<usize> {
+        require!(self.contains(pool, tick), AmmError::InvalidTickArray);
+        require!(tick % pool.tick_spacing as i32 == 0, AmmError::InvalidTickRange);
+        Ok(((tick - self.start_tick_index) / pool.tick_spacing as i32) as usize)
+    }
+
+    pub fn tick(&self, pool: &ClmmPool, tick: i32) -> Result<&Tick> {
+        let offset = self.offset(pool, tick)?;
+        Ok(&self.ticks[offset])
+    }
+
+    pub fn tick_mut(&mut self, pool: &ClmmPool, tick: i32) -> Result<&mut Tick> {
+        let offset = self.offset(pool, tick)?;
+        Ok(&mut self.ticks[offset])
+    }
+
+    /// Apply a position's liquidity change to one of its edge ticks. Returns
+    /// the updated tick and whether it flipped between used and unused.
+    pub fn update_tick(&mut self, pool: &ClmmPool, tick_index: i32, delta: i128, is_upper: bool) -> Result<(Tick, bool)> {
+        let tick = self.tick_mut(pool, tick_index)?;
+        let gross_before = tick.liquidity_gross;
+        tick.liquidity_gross = add_delta(tick.liquidity_gross, delta)?;
+        require!(tick.liquidity_gross <= MAX_LIQUIDITY, AmmError::LiquidityOverflow);
+
+        // By convention all fee growth so far happened below a fresh tick
+        if gross_before == 0 && tick.liquidity_gross > 0 {
+            tick.initialized = true;
+            if tick_index <= pool.tick_current {
+                tick.fee_growth_outside_a_x64 = pool.fee_growth_global_a_x64;
+                tick.fee_growth_outside_b_x64 = pool.fee_growth_global_b_x64;
+            }
+        }
+
+        tick.liquidity_net = if is_upper {
+            tick.liquidity_net.checked_sub(delta)
+        } else {
+            tick.liquidity_net.checked_add(delta)
+        }
+        .ok_or(AmmError::LiquidityOverflow)?;
+
+        let flipped = (gross_before == 0) != (tick.liquidity_gross == 0);
+        Ok((*tick, flipped))
+    }
+
+    /// Reset a tick no position uses any more
+    pub fn clear_tick(&mut self, pool: &ClmmPool, tick_index: i32) -> Result<()> {
+        *self.tick_mut(pool, tick_index)? = Tick::default();
+        Ok(())
+    }
+
+    /// Flip a tick's outside fee growth as the price crosses it. Returns the
+    /// tick's net liquidity.
+    pub fn cross_tick(&mut self, pool: &ClmmPool, tick_index: i32) -> Result<i128> {
+        let (global_a, global_b) = (pool.fee_growth_global_a_x64, pool.fee_growth_global_b_x64);
+        let tick = self.tick_mut(pool, tick_index)?;
+        tick.fee_growth_outside_a_x64 = global_a.wrapping_sub(tick.fee_growth_outside_a_x64);
+        tick.fee_growth_outside_b_x64 = global_b.wrapping_sub(tick.fee_growth_outside_b_x64);
+        Ok(tick.liquidity_net)
+    }
+
+    /// Nearest initialized tick in the swap direction within this array: at
+    /// or below `tick` when the price falls, above it when the price rises
+    pub fn next_initialized_tick(&self, pool: &ClmmPool, tick: i32, a_to_b: bool) -> Option<i32> {
+        let spacing = pool.tick_spacing as i32;
+        (0..TICK_ARRAY_SIZE as i32)
+            .map(|offset| self.start_tick_index + offset * spacing)
+            .filter(|index| if a_to_b { *index <= tick } else { *index > tick })
+            .filter(|index| self.ticks[((index - self.start_tick_index) / spacing) as usize].initialized)
+            .reduce(|best, index| if a_to_b { best.max(index) } else { best.min(index) })
+    }
+}
+
+/// Concentrated Liquidity Position
+/// Liquidity one owner provides to a pool over [tick_lower, tick_upper),
+/// plus the fees it has earned
+#[account]
+#[derive(Default)]
+pub struct ClmmPosition {
+    /// Pool the position is in
+    pub pool: Pubkey,
+
+    /// Position owner
+    pub owner: Pubkey,
+
+    /// Lower edge of the range
+    pub tick_lower: i32,
+
+    /// Upper edge of the range (exclusive)
+    pub tick_upper: i32,
+
+    /// Liquidity provided
+    pub liquidity: u128,
+
+    /// Token A fee growth inside the range at the last update (Q64.64)
+    pub fee_growth_inside_a_last_x64: u128,
+
+    /// Token B fee growth inside the range at the last update (Q64.64)
+    pub fee_growth_inside_b_last_x64: u128,
+
+    /// Token A fees earned and not yet paid out
+    pub fees_owed_a: u64,
+
+    /// Token B fees earned and not yet paid out
+    pub fees_owed_b: u64,
+
+    /// Position bump seed
+    pub bump: u8,
+}
+
+impl ClmmPosition {
+    /// Seed of a position PDA, derived as
+    /// [SEED, pool, owner, tick_lower (LE), tick_upper (LE)]
+    pub const SEED: &'static [u8] = b"clmm_position";
+
+    pub fn initialize(&mut self, pool: Pubkey, owner: Pubkey, tick_lower: i32, tick_upper: i32, bump: u8) -> Result<()> {
+        self.pool = pool;
+        self.owner = owner;
+        self.tick_lower = tick_lower;
+        self.tick_upper = tick_upper;
+        self.liquidity = 0;
+        self.fee_growth_inside_a_last_x64 = 0;
+        self.fee_growth_inside_b_last_x64 = 0;
+        self.fees_owed_a = 0;
+        self.fees_owed_b = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Credit fees earned since the last update, then apply `delta`
+    pub fn update(&mut self, delta: i128, fee_growth_inside_a_x64: u128, fee_growth_inside_b_x64: u128) -> Result<()> {
+        let earned_a = mul_shr(
+            self.liquidity,
+            fee_growth_inside_a_x64.wrapping_sub(self.fee_growth_inside_a_last_x64),
+            64,
+            false,
+        )?;
+        let earned_b = mul_shr(
+            self.liquidity,
+            fee_growth_inside_b_x64.wrapping_sub(self.fee_growth_inside_b_last_x64),
+            64,
+            false,
+        )?;
+        self.fees_owed_a = self.fees_owed_a.saturating_add(u64::try_from(earned_a).unwrap_or(u64::MAX));
+        self.fees_owed_b = self.fees_owed_b.saturating_add(u64::try_from(earned_b).unwrap_or(u64::MAX));
+        self.fee_growth_inside_a_last_x64 = fee_growth_inside_a_x64;
+        self.fee_growth_inside_b_last_x64 = fee_growth_inside_b_x64;
+
+        self.liquidity = add_delta(self.liquidity, delta)?;
+        require!(self.liquidity <= MAX_LIQUIDITY, AmmError::LiquidityOverflow);
+        Ok(())
+    }
+
+    /// Take out the fees owed
+    pub fn take_fees(&mut self) -> (u64, u64) {
+        let fees = (self.fees_owed_a, self.fees_owed_b);
+        self.fees_owed_a = 0;
+        self.fees_owed_b = 0;
+        fees
+    }
+}
@@ -0,0 +1,411 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::AmmError;
+use crate::state::math::mul_div_floor_u64;
+
+// Concentrated liquidity math. Prices are square roots of the token A price
+// in token B as Q64.64 fixed-point numbers; tick `i` has the price 1.0001^i.
+// Liquidity is kept at or below `MAX_LIQUIDITY` so `liquidity << 64` fits a
+// u128 and every formula stays in 128-bit integer arithmetic.
+
+/// Lowest usable tick
+pub const MIN_TICK: i32 = -443_636;
+
+/// Highest usable tick
+pub const MAX_TICK: i32 = 443_636;
+
+/// `sqrt_price_at_tick(MIN_TICK)`
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+
+/// `sqrt_price_at_tick(MAX_TICK)`
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_521_066_979_257_578_248_091;
+
+/// Largest liquidity a pool can hold across all positions
+pub const MAX_LIQUIDITY: u128 = u64::MAX as u128;
+
+/// sqrt(1.0001)^-(2^k) as Q0.128, for k = 0..18
+const TICK_RATIOS_X128: [u128; 19] = [
+    0xfffcb933bd6fad37aa2d162d1a594001,
+    0xfff97272373d413259a46990580e2139,
+    0xfff2e50f5f656932ef12357cf3c7fdcb,
+    0xffe5caca7e10e4e61c3624eaa0941ccf,
+    0xffcb9843d60f6159c9db58835c926643,
+    0xff973b41fa98c081472e6896dfb254bf,
+    0xff2ea16466c96a3843ec78b326b52860,
+    0xfe5dee046a99a2a811c461f1969c3052,
+    0xfcbe86c7900a88aedcffc83b479aa3a3,
+    0xf987a7253ac413176f2b074cf7815e53,
+    0xf3392b0822b70005940c7a398e4b70f2,
+    0xe7159475a2c29b7443b29c7fa6e889d8,
+    0xd097f3bdfd2022b8845ad8f792aa5825,
+    0xa9f746462d870fdf8a65dc1f90e061e4,
+    0x70d869a156d2a1b890bb3df62baf32f6,
+    0x31be135f97d08fd981231505542fcfa5,
+    0x09aa508b5b7a84e1c677de54f3e99bc8,
+    0x005d6af8dedb81196699c329225ee604,
+    0x00002216e584f5fa1ea926041bedfe97,
+];
+
+/// Full 256-bit product of `a * b` as (high, low) halves
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let low = (p00 & MASK) | (mid << 64);
+    let high = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (high, low)
+}
+
+/// `(a * b) >> shift` for 0 < shift <= 128, computed without overflowing
+pub fn mul_shr(a: u128, b: u128, shift: u32, round_up: bool) -> Result<u128> {
+    require!(shift > 0 && shift <= 128, AmmError::MathOverflow);
+    let (high, low) = full_mul(a, b);
+
+    let (result, has_remainder) = if shift == 128 {
+        (high, low != 0)
+    } else {
+        require!(high >> shift == 0, AmmError::MathOverflow);
+        ((high << (128 - shift)) | (low >> shift), low << (128 - shift) != 0)
+    };
+
+    if round_up && has_remainder {
+        result.checked_add(1).ok_or(AmmError::MathOverflow.into())
+    } else {
+        Ok(result)
+    }
+}
+
+fn div_ceil(numerator: u128, denominator: u128) -> u128 {
+    numerator / denominator + !numerator.is_multiple_of(denominator) as u128
+}
+
+/// Square root price of `tick` as Q64.64
+pub fn sqrt_price_at_tick(tick: i32) -> Result<u128> {
+    require!((MIN_TICK..=MAX_TICK).contains(&tick), AmmError::InvalidTickRange);
+
+    // 1.0 does not fit the Q0.128 ratio below
+    if tick == 0 {
+        return Ok(1 << 64);
+    }
+
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio = if abs_tick & 1 != 0 { TICK_RATIOS_X128[0] } else { u128::MAX };
+    for (bit, factor) in TICK_RATIOS_X128.iter().enumerate().skip(1) {
+        if abs_tick & (1 << bit) != 0 {
+            ratio = mul_shr(ratio, *factor, 128, false)?;
+        }
+    }
+
+    // `ratio` is sqrt(1.0001)^-|tick| as Q0.128; invert for positive ticks
+    if tick >= 0 {
+        Ok(u128::MAX / (ratio >> 64))
+    } else {
+        Ok(ratio >> 64)
+    }
+}
+
+/// Greatest tick whose square root price is at or below `sqrt_price_x64`
+pub fn tick_at_sqrt_price(sqrt_price_x64: u128) -> Result<i32> {
+    require!(
+        (MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&sqrt_price_x64),
+        AmmError::InvalidSqrtPrice
+    );
+
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if sqrt_price_at_tick(mid)? <= sqrt_price_x64 {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Ok(low)
+}
+
+/// Token A owed for `liquidity` between two square root prices:
+/// L * (1/sqrt_lower - 1/sqrt_upper)
+pub fn amount_a_delta(sqrt_price_0: u128, sqrt_price_1: u128, liquidity: u128, round_up: bool) -> Result<u128> {
+    let (lower, upper) = (sqrt_price_0.min(sqrt_price_1), sqrt_price_0.max(sqrt_price_1));
+    require!(lower > 0, AmmError::InvalidSqrtPrice);
+    require!(liquidity <= MAX_LIQUIDITY, AmmError::LiquidityOverflow);
+
+    let numerator = liquidity << 64;
+    Ok(if round_up {
+        div_ceil(numerator, lower) - numerator / upper
+    } else {
+        (numerator / lower).saturating_sub(div_ceil(numerator, upper))
+    })
+}
+
+/// Token B owed for `liquidity` between two square root prices:
+/// L * (sqrt_upper - sqrt_lower)
+pub fn amount_b_delta(sqrt_price_0: u128, sqrt_price_1: u128, liquidity: u128, round_up: bool) -> Result<u128> {
+    let (lower, upper) = (sqrt_price_0.min(sqrt_price_1), sqrt_price_0.max(sqrt_price_1));
+    mul_shr(liquidity, upper - lower, 64, round_up)
+}
+
+/// Narrow a token amount to u64
+pub fn to_amount(amount: u128) -> Result<u64> {
+    u64::try_from(amount).map_err(|_| AmmError::MathOverflow.into())
+}
+
+/// Square root price after adding `amount_in` of the input token. Rounded in
+/// the pool's favour: up when token A comes in, down when token B does.
+pub fn next_sqrt_price_from_input(sqrt_price_x64: u128, liquidity: u128, amount_in: u64, a_to_b: bool) -> Result<u128> {
+    require!(liquidity > 0 && liquidity <= MAX_LIQUIDITY, AmmError::InsufficientLiquidity);
+
+    if a_to_b {
+        // Virtual token A reserve L / sqrt_price grows by the input
+        let numerator = liquidity << 64;
+        let virtual_a = (numerator / sqrt_price_x64)
+            .checked_add(amount_in as u128)
+            .ok_or(AmmError::MathOverflow)?;
+        Ok(div_ceil(numerator, virtual_a))
+    } else {
+        sqrt_price_x64
+            .checked_add(((amount_in as u128) << 64) / liquidity)
+            .ok_or(AmmError::MathOverflow.into())
+    }
+}
+
+/// Square root price after removing `amount_out` of the output token.
+/// Rounded in the pool's favour: down when token B goes out, up when token A does.
+pub fn next_sqrt_price_from_output(sqrt_price_x64: u128, liquidity: u128, amount_out: u64, a_to_b: bool) -> Result<u128> {
+    require!(liquidity > 0 && liquidity <= MAX_LIQUIDITY, AmmError::InsufficientLiquidity);
+
+    if a_to_b {
+        let delta = div_ceil((amount_out as u128) << 64, liquidity);
+        require!(sqrt_price_x64 > delta, AmmError::InsufficientLiquidity);
+        Ok(sqrt_price_x64 - delta)
+    } else {
+        // Virtual token A reserve L / sqrt_price shrinks by the output
+        let numerator = liquidity << 64;
+        let virtual_a = numerator / sqrt_price_x64;
+        require!(virtual_a > amount_out as u128, AmmError::InsufficientLiquidity);
+        Ok(div_ceil(numerator, virtual_a - amount_out as u128))
+    }
+}
+
+/// Result of swapping within one stretch of constant liquidity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapStep {
+    pub sqrt_price_next_x64: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+/// Swap from `sqrt_price_x64` toward `sqrt_target_x64` at constant
+/// `liquidity`. With `exact_in`, `amount_remaining` is the input still to
+/// spend (fee included); otherwise it is the output still wanted.
+pub fn compute_swap_step(
+    sqrt_price_x64: u128,
+    sqrt_target_x64: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_rate: u64,
+    a_to_b: bool,
+    exact_in: bool,
+) -> Result<SwapStep> {
+    if liquidity == 0 {
+        return Ok(SwapStep { sqrt_price_next_x64: sqrt_target_x64, ..SwapStep::default() });
+    }
+
+    let input_delta = |sqrt_price_next_x64: u128| {
+        if a_to_b {
+            amount_a_delta(sqrt_price_next_x64, sqrt_price_x64, liquidity, true)
+        } else {
+            amount_b_delta(sqrt_price_x64, sqrt_price_next_x64, liquidity, true)
+        }
+    };
+    let output_delta = |sqrt_price_next_x64: u128| {
+        if a_to_b {
+            amount_b_delta(sqrt_price_next_x64, sqrt_price_x64, liquidity, false)
+        } else {
+            amount_a_delta(sqrt_price_x64, sqrt_price_next_x64, liquidity, false)
+        }
+    };
+    let fee_on = |amount_in: u64| {
+        div_ceil(amount_in as u128 * fee_rate as u128, (BPS_DENOMINATOR - fee_rate) as u128)
+    };
+
+    if !exact_in {
+        let max_out = output_delta(sqrt_target_x64)?;
+        let sqrt_price_next_x64 = if amount_remaining as u128 >= max_out {
+            sqrt_target_x64
+        } else {
+            next_sqrt_price_from_output(sqrt_price_x64, liquidity, amount_remaining, a_to_b)?
+        };
+        let amount_in = to_amount(input_delta(sqrt_price_next_x64)?)?;
+        return Ok(SwapStep {
+            sqrt_price_next_x64,
+            amount_in,
+            amount_out: to_amount(output_delta(sqrt_price_next_x64)?.min(amount_remaining as u128))?,
+            fee_amount: to_amount(fee_on(amount_in))?,
+        });
+    }
+
+    let amount_less_fee = mul_div_floor_u64(amount_remaining, BPS_DENOMINATOR - fee_rate, BPS_DENOMINATOR)?;
+    let max_in = input_delta(sqrt_target_x64)?;
+
+    let (sqrt_price_next_x64, amount_in, fee_amount) = if amount_less_fee as u128 >= max_in {
+        let amount_in = max_in as u64;
+        let fee_amount = fee_on(amount_in).min((amount_remaining - amount_in) as u128) as u64;
+        (sqrt_target_x64, amount_in, fee_amount)
+    } else {
+        let sqrt_price_next_x64 = next_sqrt_price_from_input(sqrt_price_x64, liquidity, amount_less_fee, a_to_b)?;
+        (sqrt_price_next_x64, amount_less_fee, amount_remaining - amount_less_fee)
+    };
+
+    Ok(SwapStep {
+        sqrt_price_next_x64,
+        amount_in,
+        amount_out: to_amount(output_delta(sqrt_price_next_x64)?)?,
+        fee_amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const Q64: u128 = 1 << 64;
+    const LIQUIDITY: u128 = 1_000_000_000_000;
+    const FEE_RATE: u64 = 30;
+
+    fn error_code(result: Result<impl core::fmt::Debug>) -> u32 {
+        match result.unwrap_err() {
+            Error::AnchorError(error) => error.error_code_number,
+            error => panic!("unexpected error: {error:?}"),
+        }
+    }
+
+    #[test]
+    fn tick_bounds_match_the_sqrt_price_bounds() {
+        assert_eq!(sqrt_price_at_tick(0).unwrap(), Q64);
+        assert_eq!(sqrt_price_at_tick(MIN_TICK).unwrap(), MIN_SQRT_PRICE_X64);
+        assert_eq!(sqrt_price_at_tick(MAX_TICK).unwrap(), MAX_SQRT_PRICE_X64);
+        assert_eq!(tick_at_sqrt_price(MIN_SQRT_PRICE_X64).unwrap(), MIN_TICK);
+        assert_eq!(tick_at_sqrt_price(MAX_SQRT_PRICE_X64).unwrap(), MAX_TICK);
+
+        for tick in [MIN_TICK - 1, MAX_TICK + 1, i32::MIN, i32::MAX] {
+            assert_eq!(error_code(sqrt_price_at_tick(tick)), u32::from(AmmError::InvalidTickRange));
+        }
+        for sqrt_price in [0, MIN_SQRT_PRICE_X64 - 1, MAX_SQRT_PRICE_X64 + 1, u128::MAX] {
+            assert_eq!(error_code(tick_at_sqrt_price(sqrt_price)), u32::from(AmmError::InvalidSqrtPrice));
+        }
+    }
+
+    #[test]
+    fn zero_liquidity_step_jumps_to_the_target() {
+        for exact_in in [true, false] {
+            let step = compute_swap_step(Q64, Q64 / 2, 0, 1_000, FEE_RATE, true, exact_in).unwrap();
+            assert_eq!(step, SwapStep { sqrt_price_next_x64: Q64 / 2, ..SwapStep::default() });
+        }
+    }
+
+    #[test]
+    fn exact_in_step_stops_short_of_a_distant_target() {
+        for a_to_b in [true, false] {
+            let target = if a_to_b { Q64 / 2 } else { Q64 * 2 };
+            let step = compute_swap_step(Q64, target, LIQUIDITY, 1_000_000, FEE_RATE, a_to_b, true).unwrap();
+
+            assert_eq!(step.amount_in + step.fee_amount, 1_000_000);
+            assert_eq!(step.fee_amount, 1_000_000 - 1_000_000 * (BPS_DENOMINATOR - FEE_RATE) / BPS_DENOMINATOR);
+            assert_eq!(step.sqrt_price_next_x64, next_sqrt_price_from_input(Q64, LIQUIDITY, step.amount_in, a_to_b).unwrap());
+            if a_to_b {
+                assert!(step.sqrt_price_next_x64 > target && step.sqrt_price_next_x64 < Q64);
+            } else {
+                assert!(step.sqrt_price_next_x64 < target && step.sqrt_price_next_x64 > Q64);
+            }
+            // Near a price of 1 the output trails the input by price impact
+            assert!(step.amount_out < step.amount_in && step.amount_out > step.amount_in - 10);
+        }
+    }
+
+    #[test]
+    fn exact_in_step_reaches_a_near_target() {
+        let target = sqrt_price_at_tick(-10).unwrap();
+        let max_in = amount_a_delta(target, Q64, LIQUIDITY, true).unwrap() as u64;
+        let step = compute_swap_step(Q64, target, LIQUIDITY, u64::MAX / 2, FEE_RATE, true, true).unwrap();
+
+        assert_eq!(step.sqrt_price_next_x64, target);
+        assert_eq!(step.amount_in, max_in);
+        assert_eq!(step.amount_out as u128, amount_b_delta(target, Q64, LIQUIDITY, false).unwrap());
+        assert!(step.fee_amount as u128 * (BPS_DENOMINATOR - FEE_RATE) as u128 >= max_in as u128 * FEE_RATE as u128);
+    }
+
+    #[test]
+    fn exact_out_step_pays_out_exactly_the_request_short_of_the_target() {
+        for a_to_b in [true, false] {
+            let target = if a_to_b { Q64 / 2 } else { Q64 * 2 };
+            let step = compute_swap_step(Q64, target, LIQUIDITY, 1_000_000, FEE_RATE, a_to_b, false).unwrap();
+
+            assert_eq!(step.amount_out, 1_000_000);
+            assert_eq!(step.sqrt_price_next_x64, next_sqrt_price_from_output(Q64, LIQUIDITY, 1_000_000, a_to_b).unwrap());
+            // Near a price of 1 the input exceeds the output by price impact
+            assert!(step.amount_in > step.amount_out && step.amount_in < step.amount_out + 10);
+            assert!(step.fee_amount as u128 * (BPS_DENOMINATOR - FEE_RATE) as u128 >= step.amount_in as u128 * FEE_RATE as u128);
+        }
+    }
+
+    #[test]
+    fn exact_out_step_caps_the_output_at_the_target() {
+        let target = sqrt_price_at_tick(10).unwrap();
+        let max_out = amount_a_delta(Q64, target, LIQUIDITY, false).unwrap() as u64;
+        let step = compute_swap_step(Q64, target, LIQUIDITY, u64::MAX, FEE_RATE, false, false).unwrap();
+
+        assert_eq!(step.sqrt_price_next_x64, target);
+        assert_eq!(step.amount_out, max_out);
+        assert_eq!(step.amount_in as u128, amount_b_delta(Q64, target, LIQUIDITY, true).unwrap());
+    }
+
+    proptest! {
+        #[test]
+        fn tick_and_sqrt_price_round_trip(tick in MIN_TICK..=MAX_TICK) {
+            let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+            prop_assert_eq!(tick_at_sqrt_price(sqrt_price).unwrap(), tick);
+            if tick < MAX_TICK {
+                let next = sqrt_price_at_tick(tick + 1).unwrap();
+                prop_assert!(next > sqrt_price);
+                prop_assert_eq!(tick_at_sqrt_price(next - 1).unwrap(), tick);
+            }
+        }
+
+        #[test]
+        fn partial_steps_never_overshoot_the_target(
+            liquidity in 1..=MAX_LIQUIDITY,
+            tick in MIN_TICK + 1_000..=MAX_TICK - 1_000,
+            distance in 1..1_000i32,
+            amount in 1..=u64::MAX / 4,
+            a_to_b in any::<bool>(),
+            exact_in in any::<bool>(),
+        ) {
+            let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+            let target = sqrt_price_at_tick(if a_to_b { tick - distance } else { tick + distance }).unwrap();
+            let Ok(step) = compute_swap_step(sqrt_price, target, liquidity, amount, FEE_RATE, a_to_b, exact_in) else {
+                return Ok(());
+            };
+
+            if a_to_b {
+                prop_assert!(step.sqrt_price_next_x64 >= target && step.sqrt_price_next_x64 <= sqrt_price);
+            } else {
+                prop_assert!(step.sqrt_price_next_x64 <= target && step.sqrt_price_next_x64 >= sqrt_price);
+            }
+            if exact_in {
+                prop_assert!(step.amount_in as u128 + step.fee_amount as u128 <= amount as u128);
+            } else {
+                prop_assert!(step.amount_out <= amount);
+            }
+        }
+    }
+}
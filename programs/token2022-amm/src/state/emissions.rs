@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::AmmError;
+
+/// Maximum number of epochs a single `release_emissions` call will catch up on
+pub const MAX_EPOCHS_PER_RELEASE: u64 = 52;
+
+/// Emissions Controller
+/// Releases reward tokens to the gauge vault once per epoch, with each
+/// epoch's emission reduced by `decay_bps` relative to the previous one
+/// (5_000 bps = a halving every epoch)
+#[account]
+#[derive(Default)]
+pub struct EmissionsController {
+    /// Authority that configured the schedule
+    pub authority: Pubkey,
+
+    /// Reward token mint
+    pub reward_mint: Pubkey,
+
+    /// Vault holding the not-yet-released reward supply
+    pub reward_vault: Pubkey,
+
+    /// Destination token account for released emissions (gauge system)
+    pub gauge_vault: Pubkey,
+
+    /// Unix timestamp at which epoch 0 starts
+    pub start_time: i64,
+
+    /// Length of one epoch in seconds
+    pub epoch_duration: i64,
+
+    /// Emission released for epoch 0
+    pub initial_epoch_emission: u64,
+
+    /// Per-epoch reduction in basis points
+    pub decay_bps: u64,
+
+    /// Next epoch whose emission has not been released yet
+    pub next_epoch: u64,
+
+    /// Emission that will be released for `next_epoch`
+    pub next_epoch_emission: u64,
+
+    /// Total reward tokens released so far
+    pub total_released: u64,
+
+    /// Controller bump seed
+    pub bump: u8,
+}
+
+impl EmissionsController {
+    pub const SEED: &'static [u8] = b"emissions";
+
+    /// Initialize a new emission schedule
+    pub fn initialize(
+        &mut self,
+        authority: Pubkey,
+        reward_mint: Pubkey,
+        reward_vault: Pubkey,
+        gauge_vault: Pubkey,
+        start_time: i64,
+        epoch_duration: i64,
+        initial_epoch_emission: u64,
+        decay_bps: u64,
+        bump: u8,
+    ) -> Result<()> {
+        require!(epoch_duration > 0, AmmError::InvalidEmissionSchedule);
+        require!(decay_bps < BPS_DENOMINATOR, AmmError::InvalidEmissionSchedule);
+        require!(initial_epoch_emission > 0, AmmError::InvalidAmount);
+
+        self.authority = authority;
+        self.reward_mint = reward_mint;
+        self.reward_vault = reward_vault;
+        self.gauge_vault = gauge_vault;
+        self.start_time = start_time;
+        self.epoch_duration = epoch_duration;
+        self.initial_epoch_emission = initial_epoch_emission;
+        self.decay_bps = decay_bps;
+        self.next_epoch = 0;
+        self.next_epoch_emission = initial_epoch_emission;
+        self.total_released = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Number of epochs that have fully elapsed at `now`
+    pub fn completed_epochs(&self, now: i64) -> u64 {
+        if now <= self.start_time {
+            return 0;
+        }
+        ((now - self.start_time) / self.epoch_duration) as u64
+    }
+
+    /// Apply one epoch of decay to an emission amount
+    pub fn decay(&self, emission: u64) -> u64 {
+        let kept = (emission as u128) * ((BPS_DENOMINATOR - self.decay_bps) as u128)
+            / (BPS_DENOMINATOR as u128);
+        kept as u64
+    }
+
+    /// Compute the amount due for all completed but unreleased epochs (bounded by
+    /// `MAX_EPOCHS_PER_RELEASE`) without mutating state.
+    /// Returns (amount, epochs_released, next_epoch_emission after release).
+    pub fn pending_release(&self, now: i64) -> Result<(u64, u64, u64)> {
+        let completed = self.completed_epochs(now);
+        let mut amount: u64 = 0;
+        let mut epochs: u64 = 0;
+        let mut emission = self.next_epoch_emission;
+
+        while self.next_epoch + epochs < completed && epochs < MAX_EPOCHS_PER_RELEASE {
+            amount = amount.checked_add(emission).ok_or(AmmError::MathOverflow)?;
+            emission = self.decay(emission);
+            epochs += 1;
+        }
+
+        Ok((amount, epochs, emission))
+    }
+
+    /// Record a release computed by `pending_release`
+    pub fn apply_release(&mut self, amount: u64, epochs: u64, next_emission: u64) -> Result<()> {
+        self.next_epoch = self.next_epoch.checked_add(epochs).ok_or(AmmError::MathOverflow)?;
+        self.next_epoch_emission = next_emission;
+        self.total_released = self.total_released
+            .checked_add(amount)
+            .ok_or(AmmError::MathOverflow)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+
+/// Extension region layout version written by this program version.
+/// Regions carved out of zeroed reserved space start at version 0 with no
+/// fields set, so existing accounts read back as "nothing configured".
+pub const CURRENT_EXTENSION_VERSION: u8 = 1;
+
+/// Optional field stored in an extension region: a presence bit in the
+/// region's flags and the u64 slot holding its value
+pub trait ExtensionField: Copy {
+    fn bit(&self) -> u8;
+    fn slot(&self) -> usize;
+}
+
+/// Versioned, bitflag-guarded optional fields layered over reserved space.
+/// A field reads as `None` until it is set, so features can be added to
+/// existing accounts without a migration.
+pub trait ExtensionRegion {
+    type Field: ExtensionField;
+
+    fn version(&self) -> u8;
+    fn flags(&self) -> u8;
+    fn slots(&self) -> &[u64];
+    fn header_mut(&mut self) -> (&mut u8, &mut u8);
+    fn slots_mut(&mut self) -> &mut [u64];
+
+    /// Whether `field` has been set
+    fn has(&self, field: Self::Field) -> bool {
+        self.flags() & (1u8 << field.bit()) != 0
+    }
+
+    /// Value of `field`, if set
+    fn get(&self, field: Self::Field) -> Option<u64> {
+        self.has(field).then(|| self.slots()[field.slot()])
+    }
+
+    /// Set `field`, stamping the region with the current layout version.
+    /// Regions written by a newer program version are left untouched.
+    fn set(&mut self, field: Self::Field, value: u64) -> Result<()> {
+        require!(
+            self.version() <= CURRENT_EXTENSION_VERSION,
+            AmmError::UnsupportedExtensionVersion
+        );
+        self.slots_mut()[field.slot()] = value;
+        let (version, flags) = self.header_mut();
+        *version = CURRENT_EXTENSION_VERSION;
+        *flags |= 1u8 << field.bit();
+        Ok(())
+    }
+
+    /// Unset `field`, zeroing its slot
+    fn clear(&mut self, field: Self::Field) -> Result<()> {
+        require!(
+            self.version() <= CURRENT_EXTENSION_VERSION,
+            AmmError::UnsupportedExtensionVersion
+        );
+        self.slots_mut()[field.slot()] = 0;
+        let (_, flags) = self.header_mut();
+        *flags &= !(1u8 << field.bit());
+        Ok(())
+    }
+}
+
+/// Optional pool fields living in the pool's extension region
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolExtensionField {
+    /// Feature flag bitmask for behaviour toggles
+    FeatureFlags,
+    /// Compute units a hook-bearing transfer may consume during a swap
+    MaxHookComputeUnits,
+    /// Running total of token A traded in either direction (wrapping; consumers take deltas)
+    CumulativeVolumeA,
+    /// Unix timestamp a wind-down started; set pools are frozen for settlement
+    WindDownStartedAt,
+    /// Share of each swap fee routed to the protocol fee vaults (basis points of the fee)
+    ProtocolFeeShareBps,
+}
+
+impl ExtensionField for PoolExtensionField {
+    fn bit(&self) -> u8 {
+        *self as u8
+    }
+
+    fn slot(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Extension region of an `AmmPool`, occupying the former reserved space
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolExtensions {
+    /// Layout version (see `CURRENT_EXTENSION_VERSION`)
+    pub version: u8,
+    /// Presence bits, one per `PoolExtensionField`
+    pub flags: u8,
+    /// Padding keeping the slots aligned
+    pub padding: [u8; 6],
+    /// Field values, indexed by `PoolExtensionField::slot`
+    pub slots: [u64; 5],
+}
+
+impl ExtensionRegion for PoolExtensions {
+    type Field = PoolExtensionField;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    fn slots(&self) -> &[u64] {
+        &self.slots
+    }
+
+    fn header_mut(&mut self) -> (&mut u8, &mut u8) {
+        (&mut self.version, &mut self.flags)
+    }
+
+    fn slots_mut(&mut self) -> &mut [u64] {
+        &mut self.slots
+    }
+}
+
+/// Optional whitelist fields living in the whitelist's extension region
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WhitelistExtensionField {
+    /// Feature flag bitmask for behaviour toggles
+    FeatureFlags,
+    /// Seconds a queued hook change waits before it can be finalized; once set,
+    /// the whitelist only changes through the queue
+    TimelockDelay,
+    /// Bitmask of inline hooks rated high risk, indexed like `whitelisted_hooks`
+    HighRiskHooks,
+}
+
+impl ExtensionField for WhitelistExtensionField {
+    fn bit(&self) -> u8 {
+        *self as u8
+    }
+
+    fn slot(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Extension region of a `TransferHookWhitelist`, occupying the former padding
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WhitelistExtensions {
+    /// Layout version (see `CURRENT_EXTENSION_VERSION`)
+    pub version: u8,
+    /// Presence bits, one per `WhitelistExtensionField`
+    pub flags: u8,
+    /// Padding keeping the slots aligned
+    pub padding: [u8; 6],
+    /// Field values, indexed by `WhitelistExtensionField::slot`
+    pub slots: [u64; 3],
+}
+
+impl ExtensionRegion for WhitelistExtensions {
+    type Field = WhitelistExtensionField;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    fn slots(&self) -> &[u64] {
+        &self.slots
+    }
+
+    fn header_mut(&mut self) -> (&mut u8, &mut u8) {
+        (&mut self.version, &mut self.flags)
+    }
+
+    fn slots_mut(&mut self) -> &mut [u64] {
+        &mut self.slots
+    }
+}
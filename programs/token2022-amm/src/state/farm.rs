@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+use crate::state::math::mul_div_floor;
+
+/// Farm
+/// Streams one reward token (SPL or Token-2022) to the LP tokens of one pool
+/// staked into it, at a constant rate between `start_time` and `end_time`.
+/// Rewards accumulate per staked LP token; each stake settles against the
+/// accumulator whenever its size changes or it claims.
+#[account]
+#[derive(Default)]
+pub struct Farm {
+    /// Pool whose LP tokens are staked
+    pub pool: Pubkey,
+
+    /// Account that created and funded the farm
+    pub authority: Pubkey,
+
+    /// Reward token mint
+    pub reward_mint: Pubkey,
+
+    /// Vault holding the reward supply, owned by the farm
+    pub reward_vault: Pubkey,
+
+    /// Vault holding staked LP tokens, owned by the farm
+    pub lp_vault: Pubkey,
+
+    /// Reward tokens streamed per second
+    pub reward_rate: u64,
+
+    /// Unix timestamp rewards start streaming at
+    pub start_time: i64,
+
+    /// Unix timestamp rewards stop streaming at
+    pub end_time: i64,
+
+    /// Unix timestamp rewards have been accrued up to
+    pub last_update_time: i64,
+
+    /// Accumulated rewards per staked LP token (Q64.64)
+    pub reward_per_lp_x64: u128,
+
+    /// LP tokens staked across all stakes
+    pub total_staked: u64,
+
+    /// Farm bump seed
+    pub bump: u8,
+}
+
+impl Farm {
+    pub const SEED: &'static [u8] = b"farm";
+
+    pub fn initialize(
+        &mut self,
+        pool: Pubkey,
+        authority: Pubkey,
+        reward_mint: Pubkey,
+        reward_vault: Pubkey,
+        lp_vault: Pubkey,
+        start_time: i64,
+        end_time: i64,
+        bump: u8,
+    ) -> Result<()> {
+        require!(end_time > start_time, AmmError::InvalidFarmSchedule);
+
+        self.pool = pool;
+        self.authority = authority;
+        self.reward_mint = reward_mint;
+        self.reward_vault = reward_vault;
+        self.lp_vault = lp_vault;
+        self.reward_rate = 0;
+        self.start_time = start_time;
+        self.end_time = end_time;
+        self.last_update_time = start_time;
+        self.reward_per_lp_x64 = 0;
+        self.total_staked = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Set the stream rate from the reward amount that actually reached the
+    /// vault, so transfer-fee reward mints never promise more than is held
+    pub fn fund(&mut self, funded_amount: u64) -> Result<()> {
+        let duration = (self.end_time - self.start_time) as u64;
+        self.reward_rate = funded_amount / duration;
+        require!(self.reward_rate > 0, AmmError::InsufficientEmissionsFunding);
+        Ok(())
+    }
+
+    /// Accrue rewards streamed since the last update. Time with nothing
+    /// staked streams nothing; those rewards stay in the vault.
+    pub fn accrue(&mut self, now: i64) -> Result<()> {
+        let until = now.min(self.end_time);
+        if until <= self.last_update_time {
+            return Ok(());
+        }
+
+        if self.total_staked > 0 {
+            let elapsed = (until - self.last_update_time) as u128;
+            let emission = (self.reward_rate as u128)
+                .checked_mul(elapsed)
+                .ok_or(AmmError::MathOverflow)?;
+            let growth = mul_div_floor(emission, 1u128 << 64, self.total_staked as u128)?;
+            self.reward_per_lp_x64 = self.reward_per_lp_x64
+                .checked_add(growth)
+                .ok_or(AmmError::MathOverflow)?;
+        }
+
+        self.last_update_time = until;
+        Ok(())
+    }
+
+    /// Record LP staked into the farm
+    pub fn record_stake(&mut self, amount: u64) -> Result<()> {
+        self.total_staked = self.total_staked
+            .checked_add(amount)
+            .ok_or(AmmError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Record LP unstaked from the farm
+    pub fn record_unstake(&mut self, amount: u64) -> Result<()> {
+        self.total_staked = self.total_staked
+            .checked_sub(amount)
+            .ok_or(AmmError::InsufficientStakedLp)?;
+        Ok(())
+    }
+}
+
+/// Farm Stake
+/// One wallet's LP staked in one farm
+#[account]
+#[derive(Default)]
+pub struct FarmStake {
+    /// Farm this stake belongs to
+    pub farm: Pubkey,
+
+    /// Staker
+    pub owner: Pubkey,
+
+    /// LP tokens staked
+    pub staked_lp: u64,
+
+    /// Farm's `reward_per_lp_x64` at the last settlement
+    pub reward_checkpoint_x64: u128,
+
+    /// Rewards earned but not yet claimed
+    pub pending_rewards: u64,
+
+    /// Stake bump seed
+    pub bump: u8,
+}
+
+impl FarmStake {
+    pub const SEED: &'static [u8] = b"farm_stake";
+
+    pub fn initialize(&mut self, farm: Pubkey, owner: Pubkey, reward_per_lp_x64: u128, bump: u8) -> Result<()> {
+        self.farm = farm;
+        self.owner = owner;
+        self.staked_lp = 0;
+        self.reward_checkpoint_x64 = reward_per_lp_x64;
+        self.pending_rewards = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Move rewards earned since the last checkpoint into `pending_rewards`
+    pub fn settle(&mut self, reward_per_lp_x64: u128) -> Result<()> {
+        let growth = reward_per_lp_x64.saturating_sub(self.reward_checkpoint_x64);
+        let earned = mul_div_floor(growth, self.staked_lp as u128, 1u128 << 64)?;
+        let earned = u64::try_from(earned).map_err(|_| AmmError::MathOverflow)?;
+        self.pending_rewards = self.pending_rewards
+            .checked_add(earned)
+            .ok_or(AmmError::MathOverflow)?;
+        self.reward_checkpoint_x64 = reward_per_lp_x64;
+        Ok(())
+    }
+}
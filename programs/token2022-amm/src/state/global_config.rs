@@ -0,0 +1,210 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::AmmError;
+use crate::pda::GovernancePda;
+
+/// Default guardian inactivity window before emergency powers pass to governance
+pub const DEFAULT_GUARDIAN_HEARTBEAT_TIMEOUT: i64 = 30 * 24 * 60 * 60; // 30 days
+
+/// Shortest guardian inactivity window that can be configured
+pub const MIN_GUARDIAN_HEARTBEAT_TIMEOUT: i64 = 24 * 60 * 60; // 1 day
+
+/// Global Config
+/// Program-wide settings shared by every pool
+#[account]
+#[derive(Default)]
+pub struct GlobalConfig {
+    /// Authority that can update the config
+    pub authority: Pubkey,
+
+    /// Seconds a payer must wait between two pool creations
+    pub pool_creation_cooldown: i64,
+
+    /// Maximum pools created across the program within one slot
+    pub max_pools_per_slot: u32,
+
+    /// Pools created in `current_slot`
+    pub pools_created_in_slot: u32,
+
+    /// Slot the per-slot counter refers to
+    pub current_slot: u64,
+
+    /// Config bump seed
+    pub bump: u8,
+
+    /// Guardian holding emergency powers while it keeps checking in
+    pub guardian: Pubkey,
+
+    /// Seconds without a heartbeat after which emergency powers pass to governance
+    pub guardian_heartbeat_timeout: i64,
+
+    /// Unix timestamp of the guardian's last heartbeat
+    pub last_guardian_heartbeat: i64,
+
+    /// Band around a pair's oracle price a pool's seeding deposit must fall
+    /// within, in basis points (0 = admission check disabled)
+    pub oracle_price_band_bps: u64,
+
+    /// Oldest oracle price accepted by the admission check, in seconds
+    pub oracle_max_staleness: i64,
+
+    /// Authority that sets pools' protocol fee share and collects protocol fees
+    pub protocol_fee_authority: Pubkey,
+}
+
+impl GlobalConfig {
+    pub const SEED: &'static [u8] = b"global_config";
+
+    /// Seed of the governance PDA that inherits emergency powers
+    pub const GOVERNANCE_SEED: &'static [u8] = b"governance";
+
+    pub fn initialize(
+        &mut self,
+        authority: Pubkey,
+        pool_creation_cooldown: i64,
+        max_pools_per_slot: u32,
+        bump: u8,
+        now: i64,
+    ) -> Result<()> {
+        self.authority = authority;
+        self.set_pool_creation_limits(pool_creation_cooldown, max_pools_per_slot)?;
+        self.pools_created_in_slot = 0;
+        self.current_slot = 0;
+        self.bump = bump;
+        self.set_guardian(authority, DEFAULT_GUARDIAN_HEARTBEAT_TIMEOUT, now)?;
+        self.oracle_price_band_bps = 0;
+        self.oracle_max_staleness = 0;
+        self.protocol_fee_authority = authority;
+        Ok(())
+    }
+
+    /// Governance PDA of this program
+    pub fn governance_authority() -> Pubkey {
+        GovernancePda::find().0
+    }
+
+    /// Install a guardian and restart its heartbeat window
+    pub fn set_guardian(&mut self, guardian: Pubkey, heartbeat_timeout: i64, now: i64) -> Result<()> {
+        require!(
+            heartbeat_timeout >= MIN_GUARDIAN_HEARTBEAT_TIMEOUT,
+            AmmError::InvalidHeartbeatTimeout
+        );
+        self.guardian = guardian;
+        self.guardian_heartbeat_timeout = heartbeat_timeout;
+        self.last_guardian_heartbeat = now;
+        Ok(())
+    }
+
+    /// Record a guardian check-in
+    pub fn record_heartbeat(&mut self, guardian: &Pubkey, now: i64) -> Result<()> {
+        require_keys_eq!(*guardian, self.guardian, AmmError::InvalidGuardian);
+        self.last_guardian_heartbeat = now;
+        Ok(())
+    }
+
+    /// Whether the guardian missed its heartbeat window at `now`
+    pub fn is_guardian_lapsed(&self, now: i64) -> bool {
+        now >= self.last_guardian_heartbeat.saturating_add(self.guardian_heartbeat_timeout)
+    }
+
+    /// Key currently holding emergency powers: the guardian while it keeps
+    /// checking in, the governance PDA once it has lapsed
+    pub fn emergency_authority(&self, now: i64) -> Pubkey {
+        if self.is_guardian_lapsed(now) {
+            Self::governance_authority()
+        } else {
+            self.guardian
+        }
+    }
+
+    /// Require `signer` to hold emergency powers at `now`
+    pub fn require_emergency_authority(&self, signer: &Pubkey, now: i64) -> Result<()> {
+        require_keys_eq!(*signer, self.emergency_authority(now), AmmError::InvalidEmergencyAuthority);
+        Ok(())
+    }
+
+    /// Update the pool creation rate limits
+    pub fn set_pool_creation_limits(
+        &mut self,
+        pool_creation_cooldown: i64,
+        max_pools_per_slot: u32,
+    ) -> Result<()> {
+        require!(pool_creation_cooldown >= 0, AmmError::InvalidRateLimit);
+        require!(max_pools_per_slot > 0, AmmError::InvalidRateLimit);
+        self.pool_creation_cooldown = pool_creation_cooldown;
+        self.max_pools_per_slot = max_pools_per_slot;
+        Ok(())
+    }
+
+    /// Configure the oracle admission check for new pools
+    pub fn set_oracle_admission(&mut self, band_bps: u64, max_staleness: i64) -> Result<()> {
+        require!(band_bps <= BPS_DENOMINATOR, AmmError::InvalidOracleAdmission);
+        require!(band_bps == 0 || max_staleness > 0, AmmError::InvalidOracleAdmission);
+        self.oracle_price_band_bps = band_bps;
+        self.oracle_max_staleness = max_staleness;
+        Ok(())
+    }
+
+    /// Require `signer` to be the protocol fee authority
+    pub fn require_protocol_fee_authority(&self, signer: &Pubkey) -> Result<()> {
+        require_keys_eq!(*signer, self.protocol_fee_authority, AmmError::InvalidProtocolFeeAuthority);
+        Ok(())
+    }
+
+    /// Count a pool creation against the per-slot global cap
+    pub fn record_pool_creation(&mut self, slot: u64) -> Result<()> {
+        if slot != self.current_slot {
+            self.current_slot = slot;
+            self.pools_created_in_slot = 0;
+        }
+        require!(
+            self.pools_created_in_slot < self.max_pools_per_slot,
+            AmmError::PoolCreationRateLimited
+        );
+        self.pools_created_in_slot += 1;
+        Ok(())
+    }
+}
+
+/// Pool Creator Record
+/// Tracks when a payer last created a pool, for the per-payer cooldown
+#[account]
+#[derive(Default)]
+pub struct PoolCreatorRecord {
+    /// Payer this record belongs to
+    pub creator: Pubkey,
+
+    /// Unix timestamp of the payer's last pool creation
+    pub last_created_at: i64,
+
+    /// Total pools created by this payer
+    pub pools_created: u64,
+
+    /// Record bump seed
+    pub bump: u8,
+}
+
+impl PoolCreatorRecord {
+    pub const SEED: &'static [u8] = b"pool_creator";
+
+    pub fn initialize(&mut self, creator: Pubkey, bump: u8) -> Result<()> {
+        self.creator = creator;
+        self.last_created_at = 0;
+        self.pools_created = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Enforce the cooldown and stamp a new creation
+    pub fn record_pool_creation(&mut self, now: i64, cooldown: i64) -> Result<()> {
+        if self.pools_created > 0 {
+            require!(
+                now >= self.last_created_at.saturating_add(cooldown),
+                AmmError::PoolCreationCooldown
+            );
+        }
+        self.last_created_at = now;
+        self.pools_created = self.pools_created.checked_add(1).ok_or(AmmError::MathOverflow)?;
+        Ok(())
+    }
+}
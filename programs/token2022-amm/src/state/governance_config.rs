@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::AmmError;
+
+/// Voting period governance starts with
+pub const DEFAULT_VOTING_PERIOD: i64 = 7 * 24 * 60 * 60; // 7 days
+
+/// Shortest voting period governance can vote itself
+pub const MIN_VOTING_PERIOD: i64 = 24 * 60 * 60; // 1 day
+
+/// Veto window governance starts with
+pub const DEFAULT_VETO_WINDOW: i64 = 2 * 24 * 60 * 60; // 2 days
+
+/// Largest security council
+pub const MAX_COUNCIL_MEMBERS: usize = 5;
+
+/// Tunable governance rules, adopted through a config proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GovernanceParams {
+    /// Seconds a proposal stays open for voting
+    pub voting_period: i64,
+    /// Total stake (approve + reject) that must take part for a vote to count
+    pub quorum_stake: u64,
+    /// Approving stake a proposal needs at minimum
+    pub min_approve_stake: u64,
+    /// Share of the participating stake that must approve (basis points)
+    pub approval_threshold_bps: u64,
+    /// Stake a proposer has to escrow to open a proposal
+    pub min_proposer_stake: u64,
+    /// Seconds after approval during which the security council can veto
+    pub veto_window: i64,
+}
+
+impl Default for GovernanceParams {
+    fn default() -> Self {
+        Self {
+            voting_period: DEFAULT_VOTING_PERIOD,
+            quorum_stake: 150 * 1_000_000_000,      // 150 SOL
+            min_approve_stake: 100 * 1_000_000_000, // 100 SOL
+            approval_threshold_bps: 6_000,          // 60%
+            min_proposer_stake: 10 * 1_000_000_000, // 10 SOL
+            veto_window: DEFAULT_VETO_WINDOW,
+        }
+    }
+}
+
+impl GovernanceParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.voting_period >= MIN_VOTING_PERIOD,
+            AmmError::InvalidGovernanceParams
+        );
+        require!(
+            self.approval_threshold_bps > 0 && self.approval_threshold_bps <= BPS_DENOMINATOR,
+            AmmError::InvalidGovernanceParams
+        );
+        require!(
+            self.quorum_stake > 0 && self.min_approve_stake > 0 && self.min_proposer_stake > 0,
+            AmmError::InvalidGovernanceParams
+        );
+        require!(self.veto_window >= 0, AmmError::InvalidGovernanceParams);
+        Ok(())
+    }
+}
+
+/// Check a council of `size` distinct `members` with a reachable veto threshold
+pub fn validate_security_council(members: &[Pubkey], size: u8, veto_threshold: u8) -> Result<()> {
+    require!(
+        (size as usize) <= MAX_COUNCIL_MEMBERS && members.len() == size as usize,
+        AmmError::InvalidSecurityCouncil
+    );
+    require!(
+        veto_threshold <= size && (size == 0 || veto_threshold > 0),
+        AmmError::InvalidSecurityCouncil
+    );
+    for (i, member) in members.iter().enumerate() {
+        require!(
+            *member != Pubkey::default() && !members[..i].contains(member),
+            AmmError::InvalidSecurityCouncil
+        );
+    }
+    Ok(())
+}
+
+/// Governance Config
+/// Voting rules every hook proposal is created and finalized under. After
+/// initialization it only changes through an executed config proposal.
+#[account]
+#[derive(Default)]
+pub struct GovernanceConfig {
+    /// Rules in force
+    pub params: GovernanceParams,
+
+    /// Security council able to veto approved proposals (first `council_size` count)
+    pub security_council: [Pubkey; MAX_COUNCIL_MEMBERS],
+
+    /// Number of council members
+    pub council_size: u8,
+
+    /// Council signatures a veto needs (0 while there is no council)
+    pub veto_threshold: u8,
+
+    /// Config bump seed
+    pub bump: u8,
+}
+
+impl GovernanceConfig {
+    pub const SEED: &'static [u8] = b"governance_config";
+
+    pub fn initialize(&mut self, council: &[Pubkey], veto_threshold: u8, bump: u8) -> Result<()> {
+        self.params = GovernanceParams::default();
+        self.set_security_council(council, veto_threshold)?;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Replace the security council
+    pub fn set_security_council(&mut self, council: &[Pubkey], veto_threshold: u8) -> Result<()> {
+        require!(council.len() <= MAX_COUNCIL_MEMBERS, AmmError::InvalidSecurityCouncil);
+        validate_security_council(council, council.len() as u8, veto_threshold)?;
+        self.security_council = [Pubkey::default(); MAX_COUNCIL_MEMBERS];
+        self.security_council[..council.len()].copy_from_slice(council);
+        self.council_size = council.len() as u8;
+        self.veto_threshold = veto_threshold;
+        Ok(())
+    }
+
+    /// Council members currently seated
+    pub fn council(&self) -> &[Pubkey] {
+        &self.security_council[..self.council_size as usize]
+    }
+
+    /// Require at least `veto_threshold` distinct council members among `signers`
+    pub fn require_council_veto(&self, signers: &[Pubkey]) -> Result<()> {
+        require!(self.veto_threshold > 0, AmmError::InvalidSecurityCouncil);
+        let council = self.council();
+        let approvals = council.iter().filter(|member| signers.contains(member)).count();
+        require!(
+            approvals >= self.veto_threshold as usize,
+            AmmError::InsufficientVetoSigners
+        );
+        Ok(())
+    }
+
+    /// Adopt the rules of an executed config proposal
+    pub fn apply(&mut self, params: GovernanceParams) -> Result<()> {
+        params.validate()?;
+        self.params = params;
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+/// Longest TTL a cached hook approval may carry (5 minutes)
+pub const MAX_HOOK_CACHE_TTL: i64 = 5 * 60;
+
+/// Hook Validation Cache
+/// Opt-in per (mint, owner) record of a recent successful hook check, letting
+/// repeat swaps by the same wallet skip the full whitelist validation while
+/// the approval is fresh. The approval only stands while the mint still
+/// points at the cached hook program and that program is still listed.
+#[account]
+#[derive(Default)]
+pub struct HookValidationCache {
+    /// Mint whose hook was validated
+    pub mint: Pubkey,
+
+    /// Wallet the approval applies to
+    pub owner: Pubkey,
+
+    /// Whitelist the hook was validated against
+    pub whitelist: Pubkey,
+
+    /// Hook program id observed on the mint (default if the mint has no hook)
+    pub hook_program_id: Pubkey,
+
+    /// Unix timestamp of the last successful validation
+    pub validated_at: i64,
+
+    /// Seconds the approval stays valid
+    pub ttl: i64,
+
+    /// Cache bump seed
+    pub bump: u8,
+}
+
+impl HookValidationCache {
+    pub const SEED: &'static [u8] = b"hook_cache";
+
+    /// Store a fresh approval
+    pub fn record(
+        &mut self,
+        mint: Pubkey,
+        owner: Pubkey,
+        whitelist: Pubkey,
+        hook_program_id: Pubkey,
+        validated_at: i64,
+        ttl: i64,
+        bump: u8,
+    ) -> Result<()> {
+        self.mint = mint;
+        self.owner = owner;
+        self.whitelist = whitelist;
+        self.hook_program_id = hook_program_id;
+        self.validated_at = validated_at;
+        self.ttl = ttl;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Whether this cache entry still vouches for `mint` traded by `owner`
+    /// against `whitelist` at time `now`, the mint currently carrying
+    /// `hook_program_id` (None if it has no hook)
+    pub fn is_fresh_for(
+        &self,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        whitelist: &Pubkey,
+        hook_program_id: Option<Pubkey>,
+        now: i64,
+    ) -> bool {
+        self.mint == *mint
+            && self.owner == *owner
+            && self.whitelist == *whitelist
+            && self.hook_program_id == hook_program_id.unwrap_or_default()
+            && now >= self.validated_at
+            && now - self.validated_at <= self.ttl
+    }
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::AmmError;
+
+/// Number of buckets in the rolling failure window
+pub const HOOK_FAILURE_BUCKETS: usize = 8;
+
+/// Slots covered by one bucket (~10 minutes)
+pub const HOOK_FAILURE_BUCKET_SLOTS: u64 = 1_500;
+
+/// Hook Failure Log
+/// Per-mint reliability record for Transfer Hook mints. Successful output-leg
+/// transfers are counted by swaps; rejections are reported by the log's
+/// reporter, because a rejected transfer aborts the whole swap transaction and
+/// cannot be recorded from inside it. Routers read the rolling failure rate to
+/// deprioritize unreliable hook mints.
+#[account]
+#[derive(Default)]
+pub struct HookFailureLog {
+    /// Mint whose Transfer Hook is tracked
+    pub mint: Pubkey,
+
+    /// Account allowed to report failures (e.g. a keeper watching failed swaps)
+    pub reporter: Pubkey,
+
+    /// Bucket id (slot / HOOK_FAILURE_BUCKET_SLOTS) each bucket currently counts
+    pub bucket_ids: [u64; HOOK_FAILURE_BUCKETS],
+
+    /// Successful transfers per bucket
+    pub successes: [u32; HOOK_FAILURE_BUCKETS],
+
+    /// Rejected transfers per bucket
+    pub failures: [u32; HOOK_FAILURE_BUCKETS],
+
+    /// Slot of the most recent reported failure
+    pub last_failure_slot: u64,
+
+    /// Hook error code of the most recent reported failure
+    pub last_failure_reason: u32,
+
+    /// Total failures ever reported
+    pub total_failures: u64,
+
+    /// Log bump seed
+    pub bump: u8,
+}
+
+impl HookFailureLog {
+    pub const SEED: &'static [u8] = b"hook_failure_log";
+
+    pub fn initialize(&mut self, mint: Pubkey, reporter: Pubkey, bump: u8) -> Result<()> {
+        self.mint = mint;
+        self.reporter = reporter;
+        self.bucket_ids = [0u64; HOOK_FAILURE_BUCKETS];
+        self.successes = [0u32; HOOK_FAILURE_BUCKETS];
+        self.failures = [0u32; HOOK_FAILURE_BUCKETS];
+        self.last_failure_slot = 0;
+        self.last_failure_reason = 0;
+        self.total_failures = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Bucket for `slot`, cleared first if it still holds an older window
+    fn bucket_for(&mut self, slot: u64) -> usize {
+        let bucket_id = slot / HOOK_FAILURE_BUCKET_SLOTS;
+        let index = (bucket_id % HOOK_FAILURE_BUCKETS as u64) as usize;
+        if self.bucket_ids[index] != bucket_id {
+            self.bucket_ids[index] = bucket_id;
+            self.successes[index] = 0;
+            self.failures[index] = 0;
+        }
+        index
+    }
+
+    /// Count a successful transfer of this mint
+    pub fn record_success(&mut self, slot: u64) {
+        let index = self.bucket_for(slot);
+        self.successes[index] = self.successes[index].saturating_add(1);
+    }
+
+    /// Count a rejected transfer of this mint at `failed_slot`
+    pub fn record_failure(&mut self, failed_slot: u64, reason: u32, current_slot: u64) -> Result<()> {
+        require!(failed_slot <= current_slot, AmmError::InvalidHookFailureReport);
+        let window_start = current_slot
+            .saturating_sub(HOOK_FAILURE_BUCKET_SLOTS * (HOOK_FAILURE_BUCKETS as u64 - 1));
+        require!(failed_slot >= window_start, AmmError::InvalidHookFailureReport);
+
+        let index = self.bucket_for(failed_slot);
+        self.failures[index] = self.failures[index].saturating_add(1);
+        if failed_slot >= self.last_failure_slot {
+            self.last_failure_slot = failed_slot;
+            self.last_failure_reason = reason;
+        }
+        self.total_failures = self.total_failures.saturating_add(1);
+        Ok(())
+    }
+
+    /// (attempts, failures) within the rolling window ending at `slot`
+    pub fn window_counts(&self, slot: u64) -> (u64, u64) {
+        let current_bucket = slot / HOOK_FAILURE_BUCKET_SLOTS;
+        let oldest_bucket = current_bucket.saturating_sub(HOOK_FAILURE_BUCKETS as u64 - 1);
+        (0..HOOK_FAILURE_BUCKETS)
+            .filter(|&i| self.bucket_ids[i] >= oldest_bucket && self.bucket_ids[i] <= current_bucket)
+            .fold((0, 0), |(attempts, failures), i| {
+                (
+                    attempts + self.successes[i] as u64 + self.failures[i] as u64,
+                    failures + self.failures[i] as u64,
+                )
+            })
+    }
+
+    /// Failure rate within the rolling window, in basis points
+    pub fn failure_rate_bps(&self, slot: u64) -> u64 {
+        let (attempts, failures) = self.window_counts(slot);
+        (failures * BPS_DENOMINATOR).checked_div(attempts).unwrap_or(0)
+    }
+}
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+use crate::pda::HookMetadataPda;
+use crate::state::{TransferHookWhitelist, WhitelistEntry};
+
+/// Longest audit report URL a metadata record can hold
+pub const MAX_AUDIT_URL_LEN: usize = 200;
+
+/// Longest auditor name a metadata record can hold
+pub const MAX_AUDITOR_LEN: usize = 64;
+
+/// Risk rating of a whitelisted hook
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HookRiskTier {
+    #[default]
+    Low = 0,
+    Medium = 1,
+    /// Transfers of mints carrying the hook are capped at `max_notional`
+    High = 2,
+}
+
+impl HookRiskTier {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => HookRiskTier::Medium,
+            2 => HookRiskTier::High,
+            _ => HookRiskTier::Low,
+        }
+    }
+}
+
+/// Hook Metadata
+/// Audit and risk information the whitelist authority keeps for a hook.
+/// The high-risk marker itself lives with the hook's whitelist listing (see
+/// `TransferHookWhitelist::is_high_risk_hook` and `WhitelistEntry::risk_tier`),
+/// so a swap cannot dodge the cap by leaving this account out.
+#[account]
+#[derive(Default)]
+pub struct HookMetadata {
+    /// Whitelist the hook is listed on
+    pub whitelist: Pubkey,
+
+    /// Hook program described
+    pub hook_program_id: Pubkey,
+
+    /// Audit report URL
+    pub audit_url: String,
+
+    /// Auditor that produced the report
+    pub auditor: String,
+
+    /// Risk rating of the hook
+    pub risk_tier: HookRiskTier,
+
+    /// Largest amount of a hooked mint a single transfer may move while the
+    /// hook is high risk (in the mint's base units)
+    pub max_notional: u64,
+
+    /// Unix timestamp the record was last updated
+    pub updated_at: i64,
+
+    /// Metadata bump seed
+    pub bump: u8,
+}
+
+impl HookMetadata {
+    /// Seed of a metadata PDA, derived as [SEED, whitelist, hook_program_id]
+    pub const SEED: &'static [u8] = b"hook_metadata";
+
+    pub const SPACE: usize = 8 // discriminator
+        + 32 + 32
+        + 4 + MAX_AUDIT_URL_LEN
+        + 4 + MAX_AUDITOR_LEN
+        + 1 + 8 + 8 + 1;
+
+    pub fn address(whitelist: &Pubkey, hook_program_id: &Pubkey) -> Pubkey {
+        HookMetadataPda::find(whitelist, hook_program_id).0
+    }
+
+    pub fn update(
+        &mut self,
+        audit_url: String,
+        auditor: String,
+        risk_tier: HookRiskTier,
+        max_notional: u64,
+        now: i64,
+    ) -> Result<()> {
+        require!(audit_url.len() <= MAX_AUDIT_URL_LEN, AmmError::InvalidHookMetadata);
+        require!(auditor.len() <= MAX_AUDITOR_LEN, AmmError::InvalidHookMetadata);
+        require!(
+            risk_tier != HookRiskTier::High || max_notional > 0,
+            AmmError::InvalidHookMetadata
+        );
+
+        self.audit_url = audit_url;
+        self.auditor = auditor;
+        self.risk_tier = risk_tier;
+        self.max_notional = max_notional;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Find the metadata record for `hook_program_id` on `whitelist` among `accounts`
+    pub fn find_in(
+        accounts: &[AccountInfo],
+        whitelist: &Pubkey,
+        hook_program_id: &Pubkey,
+    ) -> Option<HookMetadata> {
+        let address = Self::address(whitelist, hook_program_id);
+        let info = accounts.iter().find(|info| info.key() == address)?;
+        if *info.owner != crate::ID {
+            return None;
+        }
+        let data = info.try_borrow_data().ok()?;
+        HookMetadata::try_deserialize(&mut &data[..]).ok()
+    }
+}
+
+/// Enforce the per-transfer notional cap on a transfer of `amount` through a
+/// mint carrying `hook_program_id`, if that hook is rated high risk. The
+/// hook's `HookMetadata` (and its `WhitelistEntry`, for hooks not listed
+/// inline) must then be among `accounts`.
+pub fn enforce_hook_notional_cap(
+    whitelist: &Account<TransferHookWhitelist>,
+    hook_program_id: Option<Pubkey>,
+    amount: u64,
+    accounts: &[AccountInfo],
+) -> Result<()> {
+    let Some(hook_program_id) = hook_program_id else {
+        return Ok(());
+    };
+
+    let high_risk = if whitelist.is_hook_whitelisted(&hook_program_id) {
+        whitelist.is_high_risk_hook(&hook_program_id)
+    } else {
+        let entry = WhitelistEntry::find_in(accounts, &whitelist.key(), &hook_program_id)
+            .ok_or(AmmError::HookNotWhitelisted)?;
+        entry.risk_tier == HookRiskTier::High
+    };
+    if !high_risk {
+        return Ok(());
+    }
+
+    let metadata = HookMetadata::find_in(accounts, &whitelist.key(), &hook_program_id)
+        .ok_or(AmmError::MissingHookMetadata)?;
+    require!(amount <= metadata.max_notional, AmmError::HookNotionalCapExceeded);
+    Ok(())
+}
@@ -0,0 +1,231 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+use crate::state::math::mul_div_floor;
+
+/// Maximum pools linked into one incentive group
+pub const MAX_GROUP_POOLS: usize = 8;
+
+/// Fixed-point scale of the per-pool utilization weights
+const UTILIZATION_SHIFT: u32 = 32;
+
+/// Incentive Group
+/// Links related pools (e.g. the same pair across fee tiers) to one shared
+/// reward stream. Each accrual period's emission is split between member
+/// pools by utilization (token A volume over token A reserve since the last
+/// accrual), then spread over the LP staked in each pool.
+#[account]
+#[derive(Default)]
+pub struct IncentiveGroup {
+    /// Governance authority that manages the group
+    pub authority: Pubkey,
+
+    /// Group identifier (PDA seed)
+    pub group_id: u64,
+
+    /// Reward token mint
+    pub reward_mint: Pubkey,
+
+    /// Vault funding the reward stream, owned by the group
+    pub reward_vault: Pubkey,
+
+    /// Reward tokens emitted per second across the whole group
+    pub reward_rate: u64,
+
+    /// Unix timestamp rewards have been accrued up to
+    pub last_update_time: i64,
+
+    /// Number of member pools
+    pub pool_count: u8,
+
+    /// Member pools
+    pub pools: [Pubkey; MAX_GROUP_POOLS],
+
+    /// Vault holding each member pool's staked LP tokens, owned by the group
+    pub lp_vaults: [Pubkey; MAX_GROUP_POOLS],
+
+    /// LP tokens staked in each member pool
+    pub staked_lp: [u64; MAX_GROUP_POOLS],
+
+    /// Each member pool's cumulative token A volume at the last accrual
+    pub volume_snapshots: [u64; MAX_GROUP_POOLS],
+
+    /// Accumulated rewards per staked LP token for each member pool (Q64.64)
+    pub reward_per_lp_x64: [u128; MAX_GROUP_POOLS],
+
+    /// Group bump seed
+    pub bump: u8,
+}
+
+/// Live reading of a member pool taken during accrual
+pub struct GroupPoolReading {
+    pub cumulative_volume_a: u64,
+    pub token_a_reserve: u64,
+}
+
+impl IncentiveGroup {
+    pub const SEED: &'static [u8] = b"incentive_group";
+
+    pub fn initialize(
+        &mut self,
+        authority: Pubkey,
+        group_id: u64,
+        reward_mint: Pubkey,
+        reward_vault: Pubkey,
+        reward_rate: u64,
+        now: i64,
+        bump: u8,
+    ) -> Result<()> {
+        self.authority = authority;
+        self.group_id = group_id;
+        self.reward_mint = reward_mint;
+        self.reward_vault = reward_vault;
+        self.reward_rate = reward_rate;
+        self.last_update_time = now;
+        self.pool_count = 0;
+        self.pools = [Pubkey::default(); MAX_GROUP_POOLS];
+        self.lp_vaults = [Pubkey::default(); MAX_GROUP_POOLS];
+        self.staked_lp = [0u64; MAX_GROUP_POOLS];
+        self.volume_snapshots = [0u64; MAX_GROUP_POOLS];
+        self.reward_per_lp_x64 = [0u128; MAX_GROUP_POOLS];
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Position of a pool among the members
+    pub fn pool_index(&self, pool: &Pubkey) -> Option<usize> {
+        (0..(self.pool_count as usize)).find(|&i| self.pools[i] == *pool)
+    }
+
+    /// Link a pool into the group, starting its volume tracking at `cumulative_volume_a`
+    pub fn add_pool(&mut self, pool: Pubkey, lp_vault: Pubkey, cumulative_volume_a: u64) -> Result<()> {
+        require!(
+            (self.pool_count as usize) < MAX_GROUP_POOLS,
+            AmmError::IncentiveGroupFull
+        );
+        require!(self.pool_index(&pool).is_none(), AmmError::PoolAlreadyInGroup);
+
+        let index = self.pool_count as usize;
+        self.pools[index] = pool;
+        self.lp_vaults[index] = lp_vault;
+        self.staked_lp[index] = 0;
+        self.volume_snapshots[index] = cumulative_volume_a;
+        self.reward_per_lp_x64[index] = 0;
+        self.pool_count += 1;
+        Ok(())
+    }
+
+    /// Accrue the emission since the last update across member pools.
+    /// `readings` must hold one entry per member pool, in member order.
+    /// Periods without any volume on staked pools emit nothing.
+    pub fn accrue(&mut self, readings: &[GroupPoolReading], now: i64) -> Result<()> {
+        require!(
+            readings.len() == self.pool_count as usize,
+            AmmError::InvalidGroupMemberAccounts
+        );
+
+        let elapsed = now.saturating_sub(self.last_update_time);
+        if elapsed <= 0 {
+            return Ok(());
+        }
+
+        let mut weights = [0u128; MAX_GROUP_POOLS];
+        let mut total_weight: u128 = 0;
+        for (i, reading) in readings.iter().enumerate() {
+            let volume = reading.cumulative_volume_a.wrapping_sub(self.volume_snapshots[i]);
+            if self.staked_lp[i] > 0 && reading.token_a_reserve > 0 {
+                weights[i] = ((volume as u128) << UTILIZATION_SHIFT) / (reading.token_a_reserve as u128);
+                total_weight = total_weight.checked_add(weights[i]).ok_or(AmmError::MathOverflow)?;
+            }
+            self.volume_snapshots[i] = reading.cumulative_volume_a;
+        }
+
+        if total_weight > 0 {
+            let emission = (self.reward_rate as u128)
+                .checked_mul(elapsed as u128)
+                .ok_or(AmmError::MathOverflow)?;
+            for (i, &weight) in weights.iter().enumerate().take(self.pool_count as usize) {
+                if weight == 0 {
+                    continue;
+                }
+                let share = mul_div_floor(emission, weight, total_weight)?;
+                let growth = mul_div_floor(share, 1u128 << 64, self.staked_lp[i] as u128)?;
+                self.reward_per_lp_x64[i] = self.reward_per_lp_x64[i]
+                    .checked_add(growth)
+                    .ok_or(AmmError::MathOverflow)?;
+            }
+        }
+
+        self.last_update_time = now;
+        Ok(())
+    }
+
+    /// Record LP staked into member pool `index`
+    pub fn record_stake(&mut self, index: usize, amount: u64) -> Result<()> {
+        self.staked_lp[index] = self.staked_lp[index]
+            .checked_add(amount)
+            .ok_or(AmmError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Record LP unstaked from member pool `index`
+    pub fn record_unstake(&mut self, index: usize, amount: u64) -> Result<()> {
+        self.staked_lp[index] = self.staked_lp[index]
+            .checked_sub(amount)
+            .ok_or(AmmError::InsufficientStakedLp)?;
+        Ok(())
+    }
+}
+
+/// Incentive Stake
+/// One wallet's LP staked in one member pool of an incentive group
+#[account]
+#[derive(Default)]
+pub struct IncentiveStake {
+    /// Group this stake belongs to
+    pub group: Pubkey,
+
+    /// Member pool whose LP is staked
+    pub pool: Pubkey,
+
+    /// Staker
+    pub owner: Pubkey,
+
+    /// LP tokens staked
+    pub staked_lp: u64,
+
+    /// Pool's `reward_per_lp_x64` at the last settlement
+    pub reward_checkpoint_x64: u128,
+
+    /// Rewards earned but not yet claimed
+    pub pending_rewards: u64,
+
+    /// Stake bump seed
+    pub bump: u8,
+}
+
+impl IncentiveStake {
+    pub const SEED: &'static [u8] = b"incentive_stake";
+
+    pub fn initialize(&mut self, group: Pubkey, pool: Pubkey, owner: Pubkey, reward_per_lp_x64: u128, bump: u8) -> Result<()> {
+        self.group = group;
+        self.pool = pool;
+        self.owner = owner;
+        self.staked_lp = 0;
+        self.reward_checkpoint_x64 = reward_per_lp_x64;
+        self.pending_rewards = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Move rewards earned since the last checkpoint into `pending_rewards`
+    pub fn settle(&mut self, reward_per_lp_x64: u128) -> Result<()> {
+        let growth = reward_per_lp_x64.saturating_sub(self.reward_checkpoint_x64);
+        let earned = mul_div_floor(growth, self.staked_lp as u128, 1u128 << 64)?;
+        let earned = u64::try_from(earned).map_err(|_| AmmError::MathOverflow)?;
+        self.pending_rewards = self.pending_rewards
+            .checked_add(earned)
+            .ok_or(AmmError::MathOverflow)?;
+        self.reward_checkpoint_x64 = reward_per_lp_x64;
+        Ok(())
+    }
+}
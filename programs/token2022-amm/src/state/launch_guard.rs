@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+
+/// Maximum wallets on a launch allowlist
+pub const MAX_LAUNCH_ALLOWLIST: usize = 32;
+
+/// Launch Guard
+/// Creator-defined allowlist restricting liquidity provision for the first N
+/// LPs and/or the first stretch of a pool's life. Once either configured bound
+/// is reached the pool is open to everyone.
+#[account]
+#[derive(Default)]
+pub struct LaunchGuard {
+    /// Pool this guard applies to
+    pub pool: Pubkey,
+
+    /// Number of distinct allowlisted LPs admitted before the pool opens (0 = no LP bound)
+    pub max_guarded_lps: u32,
+
+    /// Number of distinct allowlisted LPs admitted so far
+    pub lps_admitted: u32,
+
+    /// Unix timestamp after which the pool opens (0 = no time bound)
+    pub guarded_until: i64,
+
+    /// Number of wallets on the allowlist
+    pub allowlist_count: u32,
+
+    /// Bitmask of allowlist entries that have already provided liquidity
+    pub admitted_mask: u32,
+
+    /// Allowlisted wallets
+    pub allowlist: [Pubkey; MAX_LAUNCH_ALLOWLIST],
+
+    /// Guard bump seed
+    pub bump: u8,
+}
+
+impl LaunchGuard {
+    pub const SEED: &'static [u8] = b"launch_guard";
+
+    pub fn initialize(
+        &mut self,
+        pool: Pubkey,
+        max_guarded_lps: u32,
+        guarded_until: i64,
+        bump: u8,
+    ) -> Result<()> {
+        require!(max_guarded_lps > 0 || guarded_until > 0, AmmError::InvalidLaunchGuard);
+
+        self.pool = pool;
+        self.max_guarded_lps = max_guarded_lps;
+        self.lps_admitted = 0;
+        self.guarded_until = guarded_until;
+        self.allowlist_count = 0;
+        self.admitted_mask = 0;
+        self.allowlist = [Pubkey::default(); MAX_LAUNCH_ALLOWLIST];
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Add a wallet to the allowlist
+    pub fn add_wallet(&mut self, wallet: Pubkey) -> Result<()> {
+        require!(
+            (self.allowlist_count as usize) < MAX_LAUNCH_ALLOWLIST,
+            AmmError::LaunchAllowlistFull
+        );
+        if self.wallet_index(&wallet).is_some() {
+            return Ok(());
+        }
+        self.allowlist[self.allowlist_count as usize] = wallet;
+        self.allowlist_count += 1;
+        Ok(())
+    }
+
+    fn wallet_index(&self, wallet: &Pubkey) -> Option<usize> {
+        (0..(self.allowlist_count as usize)).find(|&i| self.allowlist[i] == *wallet)
+    }
+
+    /// Whether the guard still restricts deposits at `now`
+    pub fn is_active(&self, now: i64) -> bool {
+        let within_lp_bound = self.max_guarded_lps == 0 || self.lps_admitted < self.max_guarded_lps;
+        let within_time_bound = self.guarded_until == 0 || now < self.guarded_until;
+        within_lp_bound && within_time_bound
+    }
+
+    /// Admit a depositor: open pools admit anyone; guarded pools only admit
+    /// allowlisted wallets, counting each one's first deposit
+    pub fn admit(&mut self, wallet: &Pubkey, now: i64) -> Result<()> {
+        if !self.is_active(now) {
+            return Ok(());
+        }
+
+        let index = self.wallet_index(wallet).ok_or(AmmError::NotOnLaunchAllowlist)?;
+        let bit = 1u32 << index;
+        if self.admitted_mask & bit == 0 {
+            self.admitted_mask |= bit;
+            self.lps_admitted += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Enforce a pool's launch guard, if it has one, for a depositor
+pub fn enforce_launch_guard(
+    pool_launch_guard: &Pubkey,
+    launch_guard: Option<&mut Account<LaunchGuard>>,
+    depositor: &Pubkey,
+    now: i64,
+) -> Result<()> {
+    if *pool_launch_guard == Pubkey::default() {
+        return Ok(());
+    }
+
+    let launch_guard = launch_guard.ok_or(AmmError::MissingLaunchGuard)?;
+    require_keys_eq!(launch_guard.key(), *pool_launch_guard, AmmError::MissingLaunchGuard);
+    launch_guard.admit(depositor, now)
+}
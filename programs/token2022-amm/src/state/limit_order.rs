@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::state::PoolSide;
+
+/// Limit Order
+/// Escrows an input amount until the pool would pay at least
+/// `min_amount_out` for all of it, at which point any keeper may fill the
+/// order through the pool. The owner can cancel and take a refund at any time
+/// before that.
+#[account]
+#[derive(Default)]
+pub struct LimitOrder {
+    /// Pool the order fills against
+    pub pool: Pubkey,
+
+    /// Account that placed the order and receives the output
+    pub owner: Pubkey,
+
+    /// Side the input tokens enter the pool on
+    pub input_side: PoolSide,
+
+    /// Escrow token account holding the input, owned by the order
+    pub escrow: Pubkey,
+
+    /// Input amount escrowed (net of any transfer fee)
+    pub amount_in: u64,
+
+    /// Output the fill must deliver for the whole input; the limit price
+    pub min_amount_out: u64,
+
+    /// Unix timestamp the order was placed
+    pub created_at: i64,
+
+    /// Caller-chosen nonce allowing several open orders per owner
+    pub nonce: u64,
+
+    /// Order bump seed
+    pub bump: u8,
+}
+
+impl LimitOrder {
+    pub const SEED: &'static [u8] = b"limit_order";
+    pub const ESCROW_SEED: &'static [u8] = b"limit_order_escrow";
+
+    pub fn initialize(
+        &mut self,
+        pool: Pubkey,
+        owner: Pubkey,
+        input_side: PoolSide,
+        escrow: Pubkey,
+        amount_in: u64,
+        min_amount_out: u64,
+        created_at: i64,
+        nonce: u64,
+        bump: u8,
+    ) -> Result<()> {
+        self.pool = pool;
+        self.owner = owner;
+        self.input_side = input_side;
+        self.escrow = escrow;
+        self.amount_in = amount_in;
+        self.min_amount_out = min_amount_out;
+        self.created_at = created_at;
+        self.nonce = nonce;
+        self.bump = bump;
+        Ok(())
+    }
+}
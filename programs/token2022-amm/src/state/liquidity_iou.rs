@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::state::PoolSide;
+
+/// Liquidity IOU
+/// Claim on one leg of a liquidity withdrawal that could not be paid out
+/// because that mint's Transfer Hook was paused or blocking transfers.
+/// Redeemable for the exact owed amount once the hook recovers.
+#[account]
+#[derive(Default)]
+pub struct LiquidityIou {
+    /// Pool the claim is against
+    pub pool: Pubkey,
+
+    /// LP who owns the claim
+    pub owner: Pubkey,
+
+    /// Side of the pool the claim is denominated in
+    pub side: PoolSide,
+
+    /// Amount owed in base units of that side's mint
+    pub amount: u64,
+
+    /// Unix timestamp the claim was created
+    pub created_at: i64,
+
+    /// Caller-chosen nonce allowing several open claims per LP
+    pub nonce: u64,
+
+    /// IOU bump seed
+    pub bump: u8,
+}
+
+impl LiquidityIou {
+    pub const SEED: &'static [u8] = b"liquidity_iou";
+
+    pub fn initialize(
+        &mut self,
+        pool: Pubkey,
+        owner: Pubkey,
+        side: PoolSide,
+        amount: u64,
+        created_at: i64,
+        nonce: u64,
+        bump: u8,
+    ) -> Result<()> {
+        self.pool = pool;
+        self.owner = owner;
+        self.side = side;
+        self.amount = amount;
+        self.created_at = created_at;
+        self.nonce = nonce;
+        self.bump = bump;
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::state::{AmmPool, mul_div_floor};
+use crate::error::AmmError;
+
+/// Longest lock-up a position can choose (one year)
+pub const MAX_LOCK_DURATION: i64 = 365 * 86_400;
+
+/// Lock-up tiers as (minimum lock duration in seconds, fee multiplier in bps),
+/// longest first; shorter lock-ups earn fees at 1x
+pub const LOCK_TIERS: [(i64, u64); 3] = [
+    (180 * 86_400, 20_000),
+    (90 * 86_400, 15_000),
+    (30 * 86_400, 12_500),
+];
+
+/// Fee multiplier (bps) earned by a position locking deposits for `lock_duration`
+pub fn lock_multiplier_bps(lock_duration: i64) -> u64 {
+    LOCK_TIERS
+        .iter()
+        .find(|(min_duration, _)| lock_duration >= *min_duration)
+        .map_or(BPS_DENOMINATOR, |(_, multiplier_bps)| *multiplier_bps)
+}
+
+/// LP Position
+/// Tracks how long an LP has held their pool tokens, for the decaying exit fee,
+/// and the claimable-mode swap fees those tokens have earned. Deposits are
+/// merged into one LP-weighted average entry time.
+///
+/// A position opened with a lock-up keeps its LP tokens in an escrow token
+/// account owned by the position PDA; each deposit relocks them for the chosen
+/// duration, and in exchange the position earns claimable fees at its tier's
+/// multiplier.
+#[account]
+#[derive(Default)]
+pub struct LpPosition {
+    /// Pool the position is in
+    pub pool: Pubkey,
+
+    /// LP who owns the position
+    pub owner: Pubkey,
+
+    /// LP tokens deposited through this position and not yet withdrawn
+    pub lp_amount: u64,
+
+    /// LP-weighted average deposit time
+    pub entry_time: i64,
+
+    /// Pool token A fee growth per LP token at the last fee settlement (Q64.64)
+    pub fee_growth_a_last_x64: u128,
+
+    /// Pool token B fee growth per LP token at the last fee settlement (Q64.64)
+    pub fee_growth_b_last_x64: u128,
+
+    /// Token A fees earned and not yet collected
+    pub fees_owed_a: u64,
+
+    /// Token B fees earned and not yet collected
+    pub fees_owed_b: u64,
+
+    /// Lock-up chosen at opening, in seconds (0 = no lock-up)
+    pub lock_duration: i64,
+
+    /// Deposits cannot be withdrawn before this timestamp
+    pub lock_until: i64,
+
+    /// Claimable fee multiplier in basis points (0 on positions opened before
+    /// lock-ups, treated as 1x)
+    pub fee_multiplier_bps: u64,
+
+    /// Position bump seed
+    pub bump: u8,
+}
+
+impl LpPosition {
+    pub const SEED: &'static [u8] = b"lp_position";
+
+    pub fn initialize(&mut self, pool: Pubkey, owner: Pubkey, lock_duration: i64, bump: u8) -> Result<()> {
+        require!(
+            (0..=MAX_LOCK_DURATION).contains(&lock_duration),
+            AmmError::InvalidLockDuration
+        );
+        self.pool = pool;
+        self.owner = owner;
+        self.lp_amount = 0;
+        self.entry_time = 0;
+        self.fee_growth_a_last_x64 = 0;
+        self.fee_growth_b_last_x64 = 0;
+        self.fees_owed_a = 0;
+        self.fees_owed_b = 0;
+        self.lock_duration = lock_duration;
+        self.lock_until = 0;
+        self.fee_multiplier_bps = lock_multiplier_bps(lock_duration);
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Whether deposits through this position are locked up
+    pub fn is_lockup(&self) -> bool {
+        self.lock_duration > 0
+    }
+
+    /// Reject a withdrawal while the lock-up is running
+    pub fn enforce_unlocked(&self, now: i64) -> Result<()> {
+        require!(now >= self.lock_until, AmmError::LpPositionLocked);
+        Ok(())
+    }
+
+    /// Fee weight the multiplier adds on top of the tracked LP tokens
+    pub fn fee_boost_weight(&self) -> u64 {
+        let multiplier_bps = self.fee_multiplier_bps.max(BPS_DENOMINATOR);
+        ((self.lp_amount as u128) * ((multiplier_bps - BPS_DENOMINATOR) as u128)
+            / (BPS_DENOMINATOR as u128)) as u64
+    }
+
+    /// Merge a deposit of `lp_tokens` at `now` into the average entry time,
+    /// restarting the lock-up if the position has one
+    pub fn record_deposit(&mut self, lp_tokens: u64, now: i64) -> Result<()> {
+        let total = self.lp_amount.checked_add(lp_tokens).ok_or(AmmError::MathOverflow)?;
+        let weighted = (self.lp_amount as i128) * (self.entry_time as i128)
+            + (lp_tokens as i128) * (now as i128);
+        self.entry_time = (weighted / (total.max(1) as i128)) as i64;
+        self.lp_amount = total;
+        if self.is_lockup() {
+            let lock_until = now.checked_add(self.lock_duration).ok_or(AmmError::MathOverflow)?;
+            self.lock_until = self.lock_until.max(lock_until);
+        }
+        Ok(())
+    }
+
+    /// Credit the fees the tracked LP tokens, scaled by the fee multiplier,
+    /// earned since the last settlement. Call before the tracked amount changes.
+    pub fn settle_fees(&mut self, pool: &AmmPool) -> Result<()> {
+        let fee_weight = (self.lp_amount as u128) + (self.fee_boost_weight() as u128);
+        let earned = |growth: u128, last: u128| -> Result<u64> {
+            let earned = fee_weight
+                .checked_mul(growth.wrapping_sub(last))
+                .ok_or(AmmError::MathOverflow)?
+                >> 64;
+            u64::try_from(earned).map_err(|_| AmmError::MathOverflow.into())
+        };
+        let earned_a = earned(pool.fee_growth_a_x64, self.fee_growth_a_last_x64)?;
+        let earned_b = earned(pool.fee_growth_b_x64, self.fee_growth_b_last_x64)?;
+
+        self.fees_owed_a = self.fees_owed_a.checked_add(earned_a).ok_or(AmmError::MathOverflow)?;
+        self.fees_owed_b = self.fees_owed_b.checked_add(earned_b).ok_or(AmmError::MathOverflow)?;
+        self.fee_growth_a_last_x64 = pool.fee_growth_a_x64;
+        self.fee_growth_b_last_x64 = pool.fee_growth_b_x64;
+        Ok(())
+    }
+
+    /// Take out the fees owed
+    pub fn take_fees(&mut self) -> (u64, u64) {
+        let fees = (self.fees_owed_a, self.fees_owed_b);
+        self.fees_owed_a = 0;
+        self.fees_owed_b = 0;
+        fees
+    }
+
+    /// Remove up to `lp_tokens` from the position, returning how many were tracked
+    pub fn record_withdrawal(&mut self, lp_tokens: u64) -> u64 {
+        let tracked = lp_tokens.min(self.lp_amount);
+        self.lp_amount -= tracked;
+        tracked
+    }
+}
+
+/// Apply the pool's exit fee to a withdrawal of `lp_tokens` worth
+/// (`amount_a`, `amount_b`), returning the amounts paid out. LP tokens covered
+/// by the position pay the fee for its age; any excess (e.g. LP tokens received
+/// by transfer) pays the full starting fee. The fee stays in reserves. A
+/// position still under lock-up cannot be withdrawn from.
+pub fn apply_exit_fee(
+    pool: &mut AmmPool,
+    position: Option<&mut Account<LpPosition>>,
+    lp_tokens: u64,
+    amount_a: u64,
+    amount_b: u64,
+    now: i64,
+) -> Result<(u64, u64)> {
+    let (tracked, entry_time) = match position {
+        Some(position) => {
+            position.settle_fees(pool)?;
+            position.enforce_unlocked(now)?;
+            let entry_time = position.entry_time;
+            let boost_before = position.fee_boost_weight();
+            let tracked = position.record_withdrawal(lp_tokens);
+            pool.reweight_fee_boost(boost_before, position.fee_boost_weight())?;
+            (tracked, entry_time)
+        }
+        None => (0, now),
+    };
+    amounts_after_exit_fee(pool, lp_tokens, tracked, entry_time, amount_a, amount_b, now)
+}
+
+/// Exit fee math of `apply_exit_fee` for a withdrawal of which `tracked` LP
+/// tokens have been held since `entry_time`, without touching the position
+pub fn amounts_after_exit_fee(
+    pool: &AmmPool,
+    lp_tokens: u64,
+    tracked: u64,
+    entry_time: i64,
+    amount_a: u64,
+    amount_b: u64,
+    now: i64,
+) -> Result<(u64, u64)> {
+    if pool.exit_fee_bps == 0 || lp_tokens == 0 {
+        return Ok((amount_a, amount_b));
+    }
+
+    let untracked = lp_tokens.checked_sub(tracked).ok_or(AmmError::MathOverflow)?;
+    let weighted_bps = (tracked as u128)
+        .checked_mul(pool.exit_fee_bps_at(entry_time, now)? as u128)
+        .and_then(|fee| fee.checked_add((untracked as u128).checked_mul(pool.exit_fee_bps as u128)?))
+        .ok_or(AmmError::MathOverflow)?;
+    let fee_bps = weighted_bps / (lp_tokens as u128);
+
+    let after_fee = |amount: u64| -> Result<u64> {
+        let fee = mul_div_floor(amount as u128, fee_bps, BPS_DENOMINATOR as u128)?;
+        amount.checked_sub(fee as u64).ok_or(AmmError::MathOverflow.into())
+    };
+    Ok((after_fee(amount_a)?, after_fee(amount_b)?))
+}
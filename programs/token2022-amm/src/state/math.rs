@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+
+pub use amm_math::Q64_SHIFT;
+
+// Fixed-point helpers shared by the pool calculations. The arithmetic lives in
+// the `amm-math` crate so off-chain quotes run the exact same code; these
+// wrappers only map its errors onto `AmmError`.
+
+impl From<amm_math::MathError> for AmmError {
+    fn from(_: amm_math::MathError) -> Self {
+        AmmError::MathOverflow
+    }
+}
+
+/// Lift an `amm-math` result into the program's error type
+pub fn math_result<T>(result: amm_math::Result<T>) -> Result<T> {
+    result.map_err(|error| AmmError::from(error).into())
+}
+
+/// `a * b / denominator`, rounded down
+pub fn mul_div_floor(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    math_result(amm_math::mul_div_floor(a, b, denominator))
+}
+
+/// `a * b / denominator`, rounded up
+pub fn mul_div_ceil(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    math_result(amm_math::mul_div_ceil(a, b, denominator))
+}
+
+/// `a * b / denominator` rounded down and narrowed back to u64
+pub fn mul_div_floor_u64(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    math_result(amm_math::mul_div_floor_u64(a, b, denominator))
+}
+
+/// `numerator / denominator` as a Q64.64 fixed-point ratio
+pub fn ratio_x64(numerator: u64, denominator: u64) -> Result<u128> {
+    math_result(amm_math::ratio_x64(numerator, denominator))
+}
+
+/// Integer square root, rounded down
+pub fn sqrt_u128(value: u128) -> u128 {
+    amm_math::sqrt_u128(value)
+}
+
+/// log2 of a positive Q64.64 value, as a signed Q64.64 value
+pub fn log2_x64(value_x64: u128) -> Result<i128> {
+    math_result(amm_math::log2_x64(value_x64))
+}
+
+/// 2 raised to a signed Q64.64 power, as a Q64.64 value. Results below the
+/// format's resolution round to zero.
+pub fn exp2_x64(exponent_x64: i128) -> Result<u128> {
+    math_result(amm_math::exp2_x64(exponent_x64))
+}
+
+/// `base ^ (numerator / denominator)` for a positive Q64.64 base, accurate
+/// to within `pow_round_up` / `pow_round_down` of the exact value
+pub fn pow_ratio_x64(base_x64: u128, numerator: u64, denominator: u64) -> Result<u128> {
+    math_result(amm_math::pow_ratio_x64(base_x64, numerator, denominator))
+}
+
+/// Upper bound of a `pow_ratio_x64` result
+pub fn pow_round_up(value_x64: u128) -> u128 {
+    amm_math::pow_round_up(value_x64)
+}
+
+/// Lower bound of a `pow_ratio_x64` result
+pub fn pow_round_down(value_x64: u128) -> u128 {
+    amm_math::pow_round_down(value_x64)
+}
+
+/// a^(weight_a / (weight_a + weight_b)) * b^(weight_b / (weight_a + weight_b)),
+/// rounded down; the weighted constant-mean analogue of sqrt(a * b)
+pub fn weighted_geometric_mean(a: u64, b: u64, weight_a: u64, weight_b: u64) -> Result<u64> {
+    math_result(amm_math::weighted_geometric_mean(a, b, weight_a, weight_b))
+}
@@ -1,7 +1,59 @@
 pub mod whitelist;
 pub mod amm_pool;
-pub mod hook_proposal;
+pub mod proposal;
+pub mod balance_snapshot;
+pub mod emissions;
+pub mod hook_cache;
+pub mod liquidity_iou;
+pub mod pending_deposit;
+pub mod global_config;
+pub mod launch_guard;
+pub mod price_feed;
+pub mod taker_allowlist;
+pub mod hook_failure_log;
+pub mod lp_position;
+pub mod math;
+pub mod extensions;
+pub mod incentive_group;
+pub mod settlement;
+pub mod pair_oracle;
+pub mod amm_config;
+pub mod whitelist_entry;
+pub mod hook_metadata;
+pub mod governance_config;
+pub mod observation;
+pub mod clmm_math;
+pub mod clmm;
+pub mod farm;
+pub mod limit_order;
+pub mod protocol_liquidity;
 
 pub use whitelist::*;
 pub use amm_pool::*;
-pub use hook_proposal::*;
\ No newline at end of file
+pub use proposal::*;
+pub use balance_snapshot::*;
+pub use emissions::*;
+pub use hook_cache::*;
+pub use liquidity_iou::*;
+pub use pending_deposit::*;
+pub use global_config::*;
+pub use launch_guard::*;
+pub use price_feed::*;
+pub use taker_allowlist::*;
+pub use hook_failure_log::*;
+pub use lp_position::*;
+pub use math::*;
+pub use extensions::*;
+pub use incentive_group::*;
+pub use settlement::*;
+pub use pair_oracle::*;
+pub use amm_config::*;
+pub use whitelist_entry::*;
+pub use hook_metadata::*;
+pub use governance_config::*;
+pub use observation::*;
+pub use clmm_math::*;
+pub use clmm::*;
+pub use farm::*;
+pub use limit_order::*;
+pub use protocol_liquidity::*;
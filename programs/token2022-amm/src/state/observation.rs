@@ -0,0 +1,204 @@
+use anchor_lang::prelude::*;
+use crate::state::{AmmPool, PoolSide};
+use crate::error::AmmError;
+use crate::constants::BPS_DENOMINATOR;
+
+/// Number of points kept in a pool's observation ring buffer
+pub const OBSERVATION_CAPACITY: usize = 64;
+
+/// One point of the ring buffer: the running price accumulators at `timestamp`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ObservationPoint {
+    /// Unix timestamp the point was written at
+    pub timestamp: i64,
+    /// Sum of (token A price in token B, Q64.64) * seconds, wrapping
+    pub price_a_cumulative_x64: u128,
+    /// Sum of (token B price in token A, Q64.64) * seconds, wrapping
+    pub price_b_cumulative_x64: u128,
+}
+
+/// Observation
+/// Per-pool TWAP oracle in the style of Uniswap's cumulative prices. Every
+/// swap folds the pre-trade spot price, weighted by the seconds since the
+/// previous point, into the accumulators and writes at most one point per
+/// second. A consumer reads the accumulators at two times and divides their
+/// difference (with wrapping subtraction) by the elapsed seconds.
+#[account]
+pub struct Observation {
+    /// Pool observed
+    pub pool: Pubkey,
+
+    /// Ring buffer of accumulator points
+    pub points: [ObservationPoint; OBSERVATION_CAPACITY],
+
+    /// Index of the most recent point
+    pub index: u16,
+
+    /// Number of points written so far (up to `OBSERVATION_CAPACITY`)
+    pub count: u16,
+
+    /// Observation bump seed
+    pub bump: u8,
+}
+
+impl Observation {
+    pub const SEED: &'static [u8] = b"observation";
+
+    pub fn initialize(&mut self, pool: Pubkey, now: i64, bump: u8) -> Result<()> {
+        self.pool = pool;
+        self.points = [ObservationPoint::default(); OBSERVATION_CAPACITY];
+        self.points[0].timestamp = now;
+        self.index = 0;
+        self.count = 1;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Spot prices of the pool as (A in B, B in A), Q64.64, weighted on a
+    /// weighted pool. Zero while the pool has no liquidity.
+    pub fn spot_prices_x64(pool: &AmmPool) -> (u128, u128) {
+        if pool.token_a_reserve == 0 || pool.token_b_reserve == 0 {
+            return (0, 0);
+        }
+        (
+            pool.spot_price_x64(PoolSide::A).unwrap_or(0),
+            pool.spot_price_x64(PoolSide::B).unwrap_or(0),
+        )
+    }
+
+    /// Unix timestamp of the most recent point
+    pub fn latest_timestamp(&self) -> i64 {
+        self.latest().timestamp
+    }
+
+    fn latest(&self) -> ObservationPoint {
+        self.points[self.index as usize]
+    }
+
+    fn oldest(&self) -> ObservationPoint {
+        let count = self.count as usize;
+        self.points[(self.index as usize + OBSERVATION_CAPACITY + 1 - count) % OBSERVATION_CAPACITY]
+    }
+
+    /// Accumulators at `now`, extending the latest point with the pool's
+    /// current spot price
+    fn extend(point: &ObservationPoint, pool: &AmmPool, now: i64) -> ObservationPoint {
+        let elapsed = now.saturating_sub(point.timestamp).max(0) as u128;
+        let (price_a, price_b) = Self::spot_prices_x64(pool);
+        ObservationPoint {
+            timestamp: now,
+            price_a_cumulative_x64: point.price_a_cumulative_x64.wrapping_add(price_a.wrapping_mul(elapsed)),
+            price_b_cumulative_x64: point.price_b_cumulative_x64.wrapping_add(price_b.wrapping_mul(elapsed)),
+        }
+    }
+
+    /// Record the price the pool traded at since the last point. Call before
+    /// a swap moves the reserves.
+    pub fn update(&mut self, pool: &AmmPool, now: i64) -> Result<()> {
+        let latest = self.latest();
+        if now <= latest.timestamp {
+            return Ok(());
+        }
+
+        let next = Self::extend(&latest, pool, now);
+        self.index = ((self.index as usize + 1) % OBSERVATION_CAPACITY) as u16;
+        self.points[self.index as usize] = next;
+        self.count = (self.count + 1).min(OBSERVATION_CAPACITY as u16);
+        Ok(())
+    }
+
+    /// Accumulators as of `seconds_ago` seconds before `now`, interpolated
+    /// between the surrounding points
+    pub fn observe(&self, pool: &AmmPool, now: i64, seconds_ago: u32) -> Result<ObservationPoint> {
+        let target = now
+            .checked_sub(seconds_ago as i64)
+            .ok_or(AmmError::MathOverflow)?;
+
+        let latest = self.latest();
+        if target >= latest.timestamp {
+            return Ok(Self::extend(&latest, pool, target));
+        }
+
+        let count = self.count as usize;
+        let oldest = self.oldest();
+        require!(target >= oldest.timestamp, AmmError::ObservationTooOld);
+
+        // Walk back from the latest point to the first one at or before `target`
+        let mut after = latest;
+        for step in 1..count {
+            let index = (self.index as usize + OBSERVATION_CAPACITY - step) % OBSERVATION_CAPACITY;
+            let before = self.points[index];
+            if before.timestamp <= target {
+                return Ok(Self::interpolate(&before, &after, target));
+            }
+            after = before;
+        }
+        Ok(oldest)
+    }
+
+    /// Deviation of token A's spot price from its TWAP over the last `window`
+    /// seconds (or as much of them as the buffer holds), in basis points.
+    /// Zero without history or liquidity.
+    pub fn volatility_bps(&self, pool: &AmmPool, now: i64, window: u32) -> Result<u64> {
+        let span = now.saturating_sub(self.oldest().timestamp).clamp(0, window as i64);
+        if span == 0 {
+            return Ok(0);
+        }
+
+        let start = self.observe(pool, now, span as u32)?;
+        let end = self.observe(pool, now, 0)?;
+        let twap = end.price_a_cumulative_x64.wrapping_sub(start.price_a_cumulative_x64) / span as u128;
+        let (spot, _) = Self::spot_prices_x64(pool);
+        if twap == 0 || spot == 0 {
+            return Ok(0);
+        }
+
+        let unit = (twap / BPS_DENOMINATOR as u128).max(1);
+        Ok(u64::try_from(spot.abs_diff(twap) / unit).unwrap_or(u64::MAX))
+    }
+
+    fn interpolate(before: &ObservationPoint, after: &ObservationPoint, target: i64) -> ObservationPoint {
+        let span = (after.timestamp - before.timestamp) as u128;
+        let elapsed = (target - before.timestamp) as u128;
+        let delta_a = after.price_a_cumulative_x64.wrapping_sub(before.price_a_cumulative_x64);
+        let delta_b = after.price_b_cumulative_x64.wrapping_sub(before.price_b_cumulative_x64);
+        ObservationPoint {
+            timestamp: target,
+            price_a_cumulative_x64: before.price_a_cumulative_x64.wrapping_add(delta_a / span * elapsed),
+            price_b_cumulative_x64: before.price_b_cumulative_x64.wrapping_add(delta_b / span * elapsed),
+        }
+    }
+}
+
+/// Update a pool's observation, if it has one, ahead of a swap
+pub fn record_observation(
+    pool: &AmmPool,
+    observation: Option<&mut Account<Observation>>,
+    now: i64,
+) -> Result<()> {
+    if pool.observation == Pubkey::default() {
+        return Ok(());
+    }
+
+    let observation = observation.ok_or(AmmError::MissingObservation)?;
+    require_keys_eq!(observation.key(), pool.observation, AmmError::MissingObservation);
+    observation.update(pool, now)
+}
+
+/// Re-measure volatility after a swap and set the fee rate the pool's next
+/// swap pays, if the pool has dynamic fees on
+pub fn refresh_dynamic_fee(
+    pool: &mut AmmPool,
+    observation: Option<&Account<Observation>>,
+    now: i64,
+) -> Result<()> {
+    if !pool.dynamic_fee.is_active() {
+        return Ok(());
+    }
+
+    let observation = observation.ok_or(AmmError::MissingObservation)?;
+    require_keys_eq!(observation.key(), pool.observation, AmmError::MissingObservation);
+    let volatility_bps = observation.volatility_bps(pool, now, pool.dynamic_fee.window)?;
+    pool.dynamic_fee.current_fee_rate = pool.dynamic_fee.fee_rate_for(volatility_bps)?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::AmmError;
+use crate::state::{AmmPool, GlobalConfig};
+use crate::state::math::{mul_div_floor, ratio_x64};
+
+/// Pair Oracle
+/// External price for a token pair, published by a designated oracle signer.
+/// While GlobalConfig enables admission checks, the first deposit into a pool
+/// for this pair must be priced within the configured band of this feed.
+#[account]
+#[derive(Default)]
+pub struct PairOracle {
+    /// Token A mint (canonical order)
+    pub token_a_mint: Pubkey,
+
+    /// Token B mint (canonical order)
+    pub token_b_mint: Pubkey,
+
+    /// Signer allowed to publish prices
+    pub oracle: Pubkey,
+
+    /// Latest price: raw token B units per raw token A unit (Q64.64)
+    pub price_x64: u128,
+
+    /// Unix timestamp of the latest price
+    pub published_at: i64,
+
+    /// Oracle bump seed
+    pub bump: u8,
+}
+
+impl PairOracle {
+    pub const SEED: &'static [u8] = b"pair_oracle";
+
+    pub fn initialize(&mut self, token_a_mint: Pubkey, token_b_mint: Pubkey, oracle: Pubkey, bump: u8) -> Result<()> {
+        self.token_a_mint = token_a_mint;
+        self.token_b_mint = token_b_mint;
+        self.oracle = oracle;
+        self.price_x64 = 0;
+        self.published_at = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Record a price signed by the oracle
+    pub fn publish(&mut self, oracle: &Pubkey, price_x64: u128, now: i64) -> Result<()> {
+        require_keys_eq!(*oracle, self.oracle, AmmError::InvalidOracleSigner);
+        require!(price_x64 > 0, AmmError::InvalidOraclePrice);
+        self.price_x64 = price_x64;
+        self.published_at = now;
+        Ok(())
+    }
+
+    /// Require a seeding deposit's implied price to sit within `band_bps` of a
+    /// price no older than `max_staleness`
+    pub fn check_admission(
+        &self,
+        amount_a: u64,
+        amount_b: u64,
+        band_bps: u64,
+        max_staleness: i64,
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            self.price_x64 > 0 && now.saturating_sub(self.published_at) <= max_staleness,
+            AmmError::StaleOraclePrice
+        );
+
+        let deposit_price_x64 = ratio_x64(amount_b, amount_a)?;
+        let deviation = deposit_price_x64.abs_diff(self.price_x64);
+        let deviation_bps = mul_div_floor(deviation, BPS_DENOMINATOR as u128, self.price_x64)?;
+        require!(
+            deviation_bps <= band_bps as u128,
+            AmmError::InitialPriceOutOfBand
+        );
+        Ok(())
+    }
+}
+
+/// Enforce the oracle admission check on a pool's seeding deposit. Only the
+/// first deposit is checked, and only when the config enables the check and
+/// the pair has a registered oracle at `pair_oracle`'s PDA.
+pub fn enforce_oracle_admission(
+    global_config: &GlobalConfig,
+    pair_oracle: &AccountInfo,
+    pool: &AmmPool,
+    amount_a: u64,
+    amount_b: u64,
+    now: i64,
+) -> Result<()> {
+    if global_config.oracle_price_band_bps == 0 || pool.total_lp_supply > 0 {
+        return Ok(());
+    }
+    if pair_oracle.data_is_empty() {
+        return Ok(());
+    }
+
+    require_keys_eq!(*pair_oracle.owner, crate::ID, AmmError::InvalidOracleAdmission);
+    let pair_oracle = PairOracle::try_deserialize(&mut &pair_oracle.try_borrow_data()?[..])?;
+    pair_oracle.check_admission(
+        amount_a,
+        amount_b,
+        global_config.oracle_price_band_bps,
+        global_config.oracle_max_staleness,
+        now,
+    )
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+/// Pending Deposit
+/// Escrowed add_liquidity for mints whose compliance hooks approve transfers
+/// asynchronously. Funds sit in escrow accounts owned by this PDA until the
+/// depositor either executes the deposit (once the hook approves) or cancels
+/// it and takes a refund.
+#[account]
+#[derive(Default)]
+pub struct PendingDeposit {
+    /// Pool the deposit targets
+    pub pool: Pubkey,
+
+    /// Depositor
+    pub owner: Pubkey,
+
+    /// Escrow token account holding token A
+    pub escrow_a: Pubkey,
+
+    /// Escrow token account holding token B
+    pub escrow_b: Pubkey,
+
+    /// Token A amount escrowed
+    pub amount_a: u64,
+
+    /// Token B amount escrowed
+    pub amount_b: u64,
+
+    /// Minimum LP tokens accepted on execution
+    pub min_lp_tokens: u64,
+
+    /// Unix timestamp the deposit was created
+    pub created_at: i64,
+
+    /// Caller-chosen nonce allowing several pending deposits per user
+    pub nonce: u64,
+
+    /// Pending deposit bump seed
+    pub bump: u8,
+}
+
+impl PendingDeposit {
+    pub const SEED: &'static [u8] = b"pending_deposit";
+    pub const ESCROW_A_SEED: &'static [u8] = b"pending_deposit_a";
+    pub const ESCROW_B_SEED: &'static [u8] = b"pending_deposit_b";
+
+    pub fn initialize(
+        &mut self,
+        pool: Pubkey,
+        owner: Pubkey,
+        escrow_a: Pubkey,
+        escrow_b: Pubkey,
+        amount_a: u64,
+        amount_b: u64,
+        min_lp_tokens: u64,
+        created_at: i64,
+        nonce: u64,
+        bump: u8,
+    ) -> Result<()> {
+        self.pool = pool;
+        self.owner = owner;
+        self.escrow_a = escrow_a;
+        self.escrow_b = escrow_b;
+        self.amount_a = amount_a;
+        self.amount_b = amount_b;
+        self.min_lp_tokens = min_lp_tokens;
+        self.created_at = created_at;
+        self.nonce = nonce;
+        self.bump = bump;
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use anchor_lang::prelude::*;
+use crate::state::{AmmPool, PoolCurve, PoolSide, mul_div_floor};
+use crate::error::AmmError;
+
+/// Decimal exponent of every price published by a pool price feed
+pub const PRICE_FEED_EXPONENT: i32 = -9;
+
+/// Shortest TWAP window a price feed can be configured with
+pub const MIN_TWAP_WINDOW: i64 = 60;
+
+/// Price message laid out like Pyth's push-oracle `PriceFeedMessage`, so
+/// consumers that already decode Pyth price updates can read pool prices.
+/// `price`/`ema_price` carry the pool TWAP; `conf`/`ema_conf` carry the
+/// distance between the spot price and the TWAP at publish time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PriceFeedMessage {
+    /// Feed identifier (the pool address)
+    pub feed_id: [u8; 32],
+    /// Price of token A in token B, as `price * 10^exponent`
+    pub price: i64,
+    /// Confidence interval around `price`
+    pub conf: u64,
+    /// Decimal exponent of `price` and `conf`
+    pub exponent: i32,
+    /// Unix timestamp of this update
+    pub publish_time: i64,
+    /// Unix timestamp of the previous update
+    pub prev_publish_time: i64,
+    /// Smoothed price (same TWAP as `price`)
+    pub ema_price: i64,
+    /// Confidence interval around `ema_price`
+    pub ema_conf: u64,
+}
+
+/// Pool Price Feed
+/// Optional per-pool account publishing the pool's TWAP in a standard price
+/// format. The TWAP is accumulated from the spot price seen by each publish
+/// over a rolling window.
+#[account]
+#[derive(Default)]
+pub struct PoolPriceFeed {
+    /// Pool this feed prices
+    pub pool: Pubkey,
+
+    /// Latest published price message
+    pub price_message: PriceFeedMessage,
+
+    /// Slot of the latest publish
+    pub posted_slot: u64,
+
+    /// Target length of the TWAP window in seconds
+    pub twap_window: i64,
+
+    /// Token A mint decimals
+    pub token_a_decimals: u8,
+
+    /// Token B mint decimals
+    pub token_b_decimals: u8,
+
+    /// Spot price seen by the latest publish
+    pub last_spot_price: i64,
+
+    /// Sum of spot price * seconds since the feed was enabled
+    pub price_cumulative: u128,
+
+    /// `price_cumulative` at the start of the current window
+    pub window_start_cumulative: u128,
+
+    /// Unix timestamp the current window started at
+    pub window_start_time: i64,
+
+    /// Feed bump seed
+    pub bump: u8,
+}
+
+impl PoolPriceFeed {
+    pub const SEED: &'static [u8] = b"price_feed";
+
+    pub fn initialize(
+        &mut self,
+        pool: &Account<AmmPool>,
+        token_a_decimals: u8,
+        token_b_decimals: u8,
+        twap_window: i64,
+        now: i64,
+        slot: u64,
+        bump: u8,
+    ) -> Result<()> {
+        require!(twap_window >= MIN_TWAP_WINDOW, AmmError::InvalidTwapWindow);
+
+        self.pool = pool.key();
+        self.twap_window = twap_window;
+        self.token_a_decimals = token_a_decimals;
+        self.token_b_decimals = token_b_decimals;
+        self.price_cumulative = 0;
+        self.window_start_cumulative = 0;
+        self.window_start_time = now;
+        self.bump = bump;
+
+        // A feed may be enabled before the pool is seeded; it starts pricing
+        // with the first publish that sees liquidity
+        let spot_price = if pool.token_a_reserve > 0 && pool.token_b_reserve > 0 {
+            self.spot_price(pool)?
+        } else {
+            0
+        };
+        self.last_spot_price = spot_price;
+        self.price_message = PriceFeedMessage {
+            feed_id: pool.key().to_bytes(),
+            price: spot_price,
+            conf: 0,
+            exponent: PRICE_FEED_EXPONENT,
+            publish_time: now,
+            prev_publish_time: now,
+            ema_price: spot_price,
+            ema_conf: 0,
+        };
+        self.posted_slot = slot;
+        Ok(())
+    }
+
+    /// Spot price of token A in token B, scaled by `10^-PRICE_FEED_EXPONENT`
+    /// and adjusted for mint decimals
+    pub fn spot_price(&self, pool: &AmmPool) -> Result<i64> {
+        require!(pool.token_a_reserve > 0, AmmError::InsufficientLiquidity);
+        require!(pool.token_b_reserve > 0, AmmError::InsufficientLiquidity);
+
+        let scale_up = 10u128
+            .checked_pow((-PRICE_FEED_EXPONENT) as u32 + self.token_a_decimals as u32)
+            .ok_or(AmmError::MathOverflow)?;
+        let scale_down = 10u128
+            .checked_pow(self.token_b_decimals as u32)
+            .ok_or(AmmError::MathOverflow)?;
+
+        // A stable pool prices off its curve's slope, which sits near 1, so
+        // half of the Q64.64 fraction bits leave room for the scaling
+        if let PoolCurve::StableSwap { .. } = pool.curve {
+            let price = mul_div_floor(pool.spot_price_x64(PoolSide::A)? >> 32, scale_up, scale_down)? >> 32;
+            return i64::try_from(price).map_err(|_| AmmError::MathOverflow.into());
+        }
+        let price = (pool.token_b_reserve as u128)
+            .checked_mul(scale_up)
+            .ok_or(AmmError::MathOverflow)?
+            / (pool.token_a_reserve as u128)
+                .checked_mul(scale_down)
+                .ok_or(AmmError::MathOverflow)?;
+
+        // Weighted pools price each side by its reserve over its weight
+        let (weight_a, weight_b) = pool.curve.weights(PoolSide::A);
+        let price = mul_div_floor(price, weight_a as u128, weight_b as u128)?;
+
+        i64::try_from(price).map_err(|_| AmmError::MathOverflow.into())
+    }
+
+    /// Fold the time since the last publish into the accumulator, publish the
+    /// TWAP over the current window and roll the window once it is full
+    pub fn publish(&mut self, pool: &AmmPool, now: i64, slot: u64) -> Result<()> {
+        let last_publish = self.price_message.publish_time;
+        require!(now > last_publish, AmmError::PriceFeedUpToDate);
+
+        // Not seeded yet: start the window at the first real spot price
+        if self.last_spot_price == 0 {
+            let spot_price = self.spot_price(pool)?;
+            self.write_message(spot_price, 0, now, slot);
+            self.last_spot_price = spot_price;
+            self.window_start_cumulative = self.price_cumulative;
+            self.window_start_time = now;
+            return Ok(());
+        }
+
+        let elapsed = (now - last_publish) as u128;
+        self.price_cumulative = self.price_cumulative
+            .checked_add((self.last_spot_price as u128) * elapsed)
+            .ok_or(AmmError::MathOverflow)?;
+
+        let window_elapsed = (now - self.window_start_time) as u128;
+        let twap = (self.price_cumulative - self.window_start_cumulative) / window_elapsed;
+        let twap = i64::try_from(twap).map_err(|_| AmmError::MathOverflow)?;
+
+        let spot_price = self.spot_price(pool)?;
+        let conf = spot_price.abs_diff(twap);
+
+        self.write_message(twap, conf, now, slot);
+        self.last_spot_price = spot_price;
+
+        if now - self.window_start_time >= self.twap_window {
+            self.window_start_cumulative = self.price_cumulative;
+            self.window_start_time = now;
+        }
+        Ok(())
+    }
+
+    fn write_message(&mut self, price: i64, conf: u64, now: i64, slot: u64) {
+        self.price_message.price = price;
+        self.price_message.conf = conf;
+        self.price_message.prev_publish_time = self.price_message.publish_time;
+        self.price_message.publish_time = now;
+        self.price_message.ema_price = price;
+        self.price_message.ema_conf = conf;
+        self.posted_slot = slot;
+    }
+}
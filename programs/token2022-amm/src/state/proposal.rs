@@ -0,0 +1,347 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+use crate::constants::BPS_DENOMINATOR;
+use crate::state::{GovernanceParams, MAX_PROTOCOL_FEE_SHARE_BPS, MAX_COUNCIL_MEMBERS, validate_security_council};
+
+/// Governance proposal: whitelisting a hook, changing the voting rules, or
+/// acting on a pool whose role is held by the governance PDA
+#[account]
+pub struct Proposal {
+    pub proposer: Pubkey,
+    /// Hook to whitelist (`AddHook` proposals; default otherwise)
+    pub hook_program_id: Pubkey,
+    pub description: String,
+    pub audit_report_url: String,
+    pub proposer_stake: u64,
+    pub created_at: i64,
+    pub voting_deadline: i64,
+    pub status: ProposalStatus,
+    pub total_approve_stake: u64,
+    pub total_reject_stake: u64,
+    /// Number of votes cast; each vote lives in its own `VoteRecord`
+    pub vote_count: u64,
+    /// Proposal that must be executed before this one can be
+    pub depends_on: Option<Pubkey>,
+    /// Whether the escrowed proposer stake has been refunded or slashed
+    pub proposer_stake_settled: bool,
+    /// What the proposal does once executed
+    pub action: ProposalAction,
+    /// Unix timestamp an approved proposal leaves its veto window and can
+    /// be executed
+    pub executable_at: i64,
+}
+
+/// Action a proposal carries out when executed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProposalAction {
+    /// Whitelist `hook_program_id`
+    AddHook,
+    /// Adopt new governance rules
+    UpdateGovernanceConfig { params: GovernanceParams },
+    /// Replace the security council (first `size` members count)
+    SetSecurityCouncil { members: [Pubkey; MAX_COUNCIL_MEMBERS], size: u8, veto_threshold: u8 },
+    /// Set a governance-owned pool's base fee rate (basis points)
+    SetFeeRate { pool: Pubkey, fee_rate: u64 },
+    /// Set a pool's protocol fee share while governance is the protocol fee authority
+    SetProtocolFeeShare { pool: Pubkey, share_bps: u64 },
+    /// Pause or unpause a governance-owned pool
+    SetPoolPaused { pool: Pubkey, paused: bool },
+    /// Put a governance-owned pool into or out of withdraw-only mode
+    SetPoolWithdrawOnly { pool: Pubkey, withdraw_only: bool },
+    /// Hand a governance-owned pool to a new authority
+    RotatePoolAuthority { pool: Pubkey, new_authority: Pubkey },
+}
+
+impl ProposalAction {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ProposalAction::AddHook => {}
+            ProposalAction::UpdateGovernanceConfig { params } => params.validate()?,
+            ProposalAction::SetSecurityCouncil { members, size, veto_threshold } => {
+                validate_security_council(&members[..(*size as usize).min(MAX_COUNCIL_MEMBERS)], *size, *veto_threshold)?
+            }
+            ProposalAction::SetFeeRate { fee_rate, .. } => {
+                require!(*fee_rate < BPS_DENOMINATOR, AmmError::InvalidFeeRate);
+            }
+            ProposalAction::SetProtocolFeeShare { share_bps, .. } => {
+                require!(*share_bps <= MAX_PROTOCOL_FEE_SHARE_BPS, AmmError::InvalidProtocolFeeShare);
+            }
+            ProposalAction::SetPoolPaused { .. } => {}
+            ProposalAction::SetPoolWithdrawOnly { .. } => {}
+            ProposalAction::RotatePoolAuthority { new_authority, .. } => {
+                require!(*new_authority != Pubkey::default(), AmmError::InvalidProposalKind);
+            }
+        }
+        Ok(())
+    }
+
+    /// Pool the action applies to, if it is a pool action
+    pub fn pool(&self) -> Option<Pubkey> {
+        match self {
+            ProposalAction::SetFeeRate { pool, .. }
+            | ProposalAction::SetProtocolFeeShare { pool, .. }
+            | ProposalAction::SetPoolPaused { pool, .. }
+            | ProposalAction::SetPoolWithdrawOnly { pool, .. }
+            | ProposalAction::RotatePoolAuthority { pool, .. } => Some(*pool),
+            ProposalAction::AddHook
+            | ProposalAction::UpdateGovernanceConfig { .. }
+            | ProposalAction::SetSecurityCouncil { .. } => None,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum ProposalStatus {
+    Active,
+    Approved,
+    Rejected,
+    Cancelled,
+    Executed,
+    /// Approved, then blocked by the security council within the veto window
+    Vetoed,
+}
+
+/// One voter's vote on a proposal, at the PDA [proposal, voter]. The PDA
+/// can only be created once, which rules out double voting.
+#[account]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub vote: bool, // true for approve, false for reject
+    pub stake_amount: u64,
+    pub timestamp: i64,
+    /// Whether the escrowed stake has been returned to the voter
+    pub withdrawn: bool,
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    pub fn initialize(
+        &mut self,
+        proposal: Pubkey,
+        voter: Pubkey,
+        vote: bool,
+        stake_amount: u64,
+        timestamp: i64,
+        bump: u8,
+    ) -> Result<()> {
+        self.proposal = proposal;
+        self.voter = voter;
+        self.vote = vote;
+        self.stake_amount = stake_amount;
+        self.timestamp = timestamp;
+        self.withdrawn = false;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Release the escrowed stake once voting on `proposal` is over
+    /// (finalized, cancelled or executed). Returns the amount to send back.
+    pub fn release_stake(&mut self, proposal: &Proposal) -> Result<u64> {
+        require!(
+            proposal.status != ProposalStatus::Active,
+            AmmError::ProposalNotFinalized
+        );
+        require!(self.stake_amount > 0, AmmError::NoStakeToWithdraw);
+        require!(!self.withdrawn, AmmError::StakeAlreadyWithdrawn);
+        self.withdrawn = true;
+        Ok(self.stake_amount)
+    }
+}
+
+impl Proposal {
+    /// Keeps any unwithdrawn escrow balance above the rent-exempt minimum
+    pub const MIN_VOTE_STAKE: u64 = 1_000_000_000; // 1 SOL minimum
+
+    /// Seed of the vault escrowing vote stake, derived as [STAKE_VAULT_SEED, proposal]
+    pub const STAKE_VAULT_SEED: &'static [u8] = b"stake_vault";
+
+    pub fn initialize(
+        &mut self,
+        proposer: Pubkey,
+        hook_program_id: Pubkey,
+        description: String,
+        audit_report_url: String,
+        proposer_stake: u64,
+        created_at: i64,
+        depends_on: Option<Pubkey>,
+        action: ProposalAction,
+        params: &GovernanceParams,
+    ) -> Result<()> {
+        require!(
+            proposer_stake >= params.min_proposer_stake,
+            AmmError::InsufficientProposerStake
+        );
+        action.validate()?;
+
+        self.proposer = proposer;
+        self.hook_program_id = hook_program_id;
+        self.description = description;
+        self.audit_report_url = audit_report_url;
+        self.proposer_stake = proposer_stake;
+        self.created_at = created_at;
+        self.voting_deadline = created_at + params.voting_period;
+        self.status = ProposalStatus::Active;
+        self.total_approve_stake = 0;
+        self.total_reject_stake = 0;
+        self.vote_count = 0;
+        self.depends_on = depends_on;
+        self.proposer_stake_settled = false;
+        self.action = action;
+        self.executable_at = 0;
+
+        Ok(())
+    }
+
+    /// Count a vote; the caller records it in the voter's `VoteRecord`
+    pub fn add_vote(&mut self, vote: bool, stake_amount: u64) -> Result<()> {
+        require!(
+            self.status == ProposalStatus::Active,
+            AmmError::ProposalNotActive
+        );
+
+        require!(
+            Clock::get()?.unix_timestamp < self.voting_deadline,
+            AmmError::VotingPeriodExpired
+        );
+
+        require!(
+            stake_amount >= Self::MIN_VOTE_STAKE,
+            AmmError::InsufficientVoteStake
+        );
+
+        self.vote_count = self.vote_count.checked_add(1)
+            .ok_or(AmmError::StakeOverflow)?;
+
+        if vote {
+            self.total_approve_stake = self.total_approve_stake.checked_add(stake_amount)
+                .ok_or(AmmError::StakeOverflow)?;
+        } else {
+            self.total_reject_stake = self.total_reject_stake.checked_add(stake_amount)
+                .ok_or(AmmError::StakeOverflow)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.status == ProposalStatus::Approved
+    }
+
+    /// Whether enough stake took part in the vote
+    pub fn quorum_reached(&self, params: &GovernanceParams) -> bool {
+        (self.total_approve_stake as u128) + (self.total_reject_stake as u128)
+            >= params.quorum_stake as u128
+    }
+
+    /// Whether the approving share of the participating stake meets the threshold
+    pub fn approval_threshold_met(&self, params: &GovernanceParams) -> bool {
+        let total = (self.total_approve_stake as u128) + (self.total_reject_stake as u128);
+        (self.total_approve_stake as u128) * (BPS_DENOMINATOR as u128)
+            >= total * (params.approval_threshold_bps as u128)
+    }
+
+    pub fn is_approved(&self, params: &GovernanceParams) -> bool {
+        self.total_approve_stake >= params.min_approve_stake
+            && self.quorum_reached(params)
+            && self.approval_threshold_met(params)
+    }
+
+    pub fn can_be_cancelled(&self) -> bool {
+        self.status == ProposalStatus::Active && 
+        Clock::get().unwrap().unix_timestamp < self.voting_deadline
+    }
+
+    pub fn cancel(&mut self) -> Result<()> {
+        require!(
+            self.status == ProposalStatus::Active,
+            AmmError::ProposalNotActive
+        );
+
+        self.status = ProposalStatus::Cancelled;
+        Ok(())
+    }
+
+    /// Close voting under the rules in `params`
+    pub fn finalize(&mut self, params: &GovernanceParams) -> Result<()> {
+        require!(
+            self.status == ProposalStatus::Active,
+            AmmError::ProposalNotActive
+        );
+
+        require!(
+            Clock::get()?.unix_timestamp >= self.voting_deadline,
+            AmmError::VotingPeriodNotExpired
+        );
+
+        if self.is_approved(params) {
+            self.status = ProposalStatus::Approved;
+            self.executable_at = Clock::get()?.unix_timestamp.saturating_add(params.veto_window);
+        } else {
+            self.status = ProposalStatus::Rejected;
+        }
+
+        Ok(())
+    }
+
+    /// Settle the escrowed proposer stake once voting is over. Returns whether
+    /// the proposal passed: passing proposals refund the stake in full,
+    /// rejected or cancelled ones have a share slashed.
+    pub fn settle_proposer_stake(&mut self) -> Result<bool> {
+        require!(
+            self.status != ProposalStatus::Active,
+            AmmError::ProposalNotFinalized
+        );
+        // An approved proposal can still be vetoed until its window closes
+        if self.status == ProposalStatus::Approved {
+            require!(
+                Clock::get()?.unix_timestamp >= self.executable_at,
+                AmmError::VetoWindowOpen
+            );
+        }
+        require!(!self.proposer_stake_settled, AmmError::ProposerStakeAlreadySettled);
+        self.proposer_stake_settled = true;
+        Ok(matches!(self.status, ProposalStatus::Approved | ProposalStatus::Executed))
+    }
+
+    /// Mark an approved proposal as executed once its veto window has passed
+    pub fn mark_executed(&mut self) -> Result<()> {
+        require!(self.is_executable(), AmmError::ProposalNotExecutable);
+        require!(
+            Clock::get()?.unix_timestamp >= self.executable_at,
+            AmmError::VetoWindowOpen
+        );
+        self.status = ProposalStatus::Executed;
+        Ok(())
+    }
+
+    /// Block an approved proposal while it is inside its veto window
+    pub fn veto(&mut self, now: i64) -> Result<()> {
+        require!(self.is_executable(), AmmError::ProposalNotExecutable);
+        require!(now < self.executable_at, AmmError::VetoWindowClosed);
+        self.status = ProposalStatus::Vetoed;
+        Ok(())
+    }
+
+    /// Check that this proposal's dependency, if any, has already executed
+    pub fn check_dependency(&self, dependency: Option<&Account<Proposal>>) -> Result<()> {
+        let Some(depends_on) = self.depends_on else {
+            return Ok(());
+        };
+        let dependency = dependency.ok_or(AmmError::MissingProposalDependency)?;
+        require_keys_eq!(dependency.key(), depends_on, AmmError::MissingProposalDependency);
+        require!(
+            dependency.status == ProposalStatus::Executed,
+            AmmError::ProposalDependencyNotExecuted
+        );
+        Ok(())
+    }
+
+    pub fn get_vote_summary(&self) -> (u64, u64, u64) {
+        (
+            self.total_approve_stake,
+            self.total_reject_stake,
+            self.vote_count,
+        )
+    }
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+
+/// Protocol Liquidity
+/// LP tokens the protocol treasury received for bootstrapping a pool, held
+/// in a vault owned by this PDA until `unlock_at`. Only the treasury that
+/// seeded the pool can withdraw them, and only once the timelock has passed.
+#[account]
+#[derive(Default)]
+pub struct ProtocolLiquidity {
+    /// Pool the liquidity was seeded into
+    pub pool: Pubkey,
+
+    /// Treasury that seeded the pool and may withdraw the LP tokens
+    pub treasury: Pubkey,
+
+    /// Vault holding the LP tokens, owned by this PDA
+    pub lp_vault: Pubkey,
+
+    /// LP tokens still held in the vault
+    pub lp_amount: u64,
+
+    /// Unix timestamp the LP tokens become withdrawable at
+    pub unlock_at: i64,
+
+    /// Protocol liquidity bump seed
+    pub bump: u8,
+}
+
+impl ProtocolLiquidity {
+    pub const SEED: &'static [u8] = b"protocol_liquidity";
+
+    pub fn initialize(
+        &mut self,
+        pool: Pubkey,
+        treasury: Pubkey,
+        lp_vault: Pubkey,
+        lp_amount: u64,
+        unlock_at: i64,
+        bump: u8,
+    ) -> Result<()> {
+        self.pool = pool;
+        self.treasury = treasury;
+        self.lp_vault = lp_vault;
+        self.lp_amount = lp_amount;
+        self.unlock_at = unlock_at;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Record LP tokens withdrawn by the treasury, once unlocked
+    pub fn record_withdrawal(&mut self, amount: u64, now: i64) -> Result<()> {
+        require!(now >= self.unlock_at, AmmError::ProtocolLiquidityLocked);
+        self.lp_amount = self.lp_amount
+            .checked_sub(amount)
+            .ok_or(AmmError::InsufficientLPTokens)?;
+        Ok(())
+    }
+}
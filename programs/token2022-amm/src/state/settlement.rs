@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+use crate::state::math::mul_div_floor_u64;
+
+/// Pool Settlement
+/// Final snapshot of a wound-down pool. Every LP token outstanding at the
+/// snapshot redeems its exact pro-rata share of the reserves plus the
+/// claimable fees, and the last claimer sweeps the rounding dust.
+#[account]
+#[derive(Default)]
+pub struct PoolSettlement {
+    /// Pool being settled
+    pub pool: Pubkey,
+
+    /// Unix timestamp the wind-down started
+    pub started_at: i64,
+
+    /// Token A owed to LPs at the snapshot (reserve + claimable fees)
+    pub token_a_amount: u64,
+
+    /// Token B owed to LPs at the snapshot (reserve + claimable fees)
+    pub token_b_amount: u64,
+
+    /// LP supply at the snapshot
+    pub lp_supply: u64,
+
+    /// Token A not yet claimed
+    pub remaining_a: u64,
+
+    /// Token B not yet claimed
+    pub remaining_b: u64,
+
+    /// LP tokens not yet redeemed
+    pub lp_outstanding: u64,
+
+    /// Settlement bump seed
+    pub bump: u8,
+}
+
+impl PoolSettlement {
+    pub const SEED: &'static [u8] = b"settlement";
+
+    pub fn initialize(
+        &mut self,
+        pool: Pubkey,
+        token_a_amount: u64,
+        token_b_amount: u64,
+        lp_supply: u64,
+        now: i64,
+        bump: u8,
+    ) -> Result<()> {
+        self.pool = pool;
+        self.started_at = now;
+        self.token_a_amount = token_a_amount;
+        self.token_b_amount = token_b_amount;
+        self.lp_supply = lp_supply;
+        self.remaining_a = token_a_amount;
+        self.remaining_b = token_b_amount;
+        self.lp_outstanding = lp_supply;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Redeem `lp_tokens` for their share of the snapshot
+    pub fn claim(&mut self, lp_tokens: u64) -> Result<(u64, u64)> {
+        require!(lp_tokens > 0, AmmError::InvalidAmount);
+        require!(lp_tokens <= self.lp_outstanding, AmmError::InsufficientLPTokens);
+
+        let (amount_a, amount_b) = if lp_tokens == self.lp_outstanding {
+            (self.remaining_a, self.remaining_b)
+        } else {
+            (
+                mul_div_floor_u64(self.token_a_amount, lp_tokens, self.lp_supply)?,
+                mul_div_floor_u64(self.token_b_amount, lp_tokens, self.lp_supply)?,
+            )
+        };
+
+        self.remaining_a = self.remaining_a.checked_sub(amount_a).ok_or(AmmError::MathOverflow)?;
+        self.remaining_b = self.remaining_b.checked_sub(amount_b).ok_or(AmmError::MathOverflow)?;
+        self.lp_outstanding -= lp_tokens;
+        Ok((amount_a, amount_b))
+    }
+
+    /// Whether every LP has been paid out
+    pub fn is_complete(&self) -> bool {
+        self.lp_outstanding == 0
+    }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use crate::error::AmmError;
+
+/// Maximum takers on a private pool's allowlist
+pub const MAX_POOL_TAKERS: usize = 32;
+
+/// Taker Allowlist
+/// Creator-managed list of wallets allowed to swap against a private pool.
+/// Liquidity provision stays open to everyone.
+#[account]
+#[derive(Default)]
+pub struct TakerAllowlist {
+    /// Pool this allowlist applies to
+    pub pool: Pubkey,
+
+    /// Number of takers on the allowlist
+    pub taker_count: u32,
+
+    /// Allowlisted takers
+    pub takers: [Pubkey; MAX_POOL_TAKERS],
+
+    /// Allowlist bump seed
+    pub bump: u8,
+}
+
+impl TakerAllowlist {
+    pub const SEED: &'static [u8] = b"taker_allowlist";
+
+    pub fn initialize(&mut self, pool: Pubkey, bump: u8) -> Result<()> {
+        self.pool = pool;
+        self.taker_count = 0;
+        self.takers = [Pubkey::default(); MAX_POOL_TAKERS];
+        self.bump = bump;
+        Ok(())
+    }
+
+    fn taker_index(&self, taker: &Pubkey) -> Option<usize> {
+        (0..(self.taker_count as usize)).find(|&i| self.takers[i] == *taker)
+    }
+
+    pub fn is_allowed(&self, taker: &Pubkey) -> bool {
+        self.taker_index(taker).is_some()
+    }
+
+    /// Add a taker to the allowlist
+    pub fn add_taker(&mut self, taker: Pubkey) -> Result<()> {
+        require!(
+            (self.taker_count as usize) < MAX_POOL_TAKERS,
+            AmmError::TakerAllowlistFull
+        );
+        require!(!self.is_allowed(&taker), AmmError::TakerAlreadyAllowed);
+        self.takers[self.taker_count as usize] = taker;
+        self.taker_count += 1;
+        Ok(())
+    }
+
+    /// Remove a taker from the allowlist
+    pub fn remove_taker(&mut self, taker: &Pubkey) -> Result<()> {
+        let index = self.taker_index(taker).ok_or(AmmError::TakerNotAllowed)?;
+        let last = self.taker_count as usize - 1;
+        self.takers[index] = self.takers[last];
+        self.takers[last] = Pubkey::default();
+        self.taker_count -= 1;
+        Ok(())
+    }
+}
+
+/// Enforce a pool's taker allowlist, if it is private, for a swapper
+pub fn enforce_taker_allowlist(
+    pool_taker_allowlist: &Pubkey,
+    taker_allowlist: Option<&Account<TakerAllowlist>>,
+    taker: &Pubkey,
+) -> Result<()> {
+    if *pool_taker_allowlist == Pubkey::default() {
+        return Ok(());
+    }
+
+    let taker_allowlist = taker_allowlist.ok_or(AmmError::MissingTakerAllowlist)?;
+    require_keys_eq!(taker_allowlist.key(), *pool_taker_allowlist, AmmError::MissingTakerAllowlist);
+    require!(taker_allowlist.is_allowed(taker), AmmError::TakerNotAllowed);
+    Ok(())
+}
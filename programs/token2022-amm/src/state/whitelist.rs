@@ -1,9 +1,29 @@
 use anchor_lang::prelude::*;
 use crate::error::AmmError;
+use crate::require_ctx;
+use crate::token_extensions::transfer_hook_program_id;
+use crate::hook_interface::HookInterfaceVersion;
+use crate::state::extensions::{ExtensionRegion, WhitelistExtensionField, WhitelistExtensions};
+use crate::state::whitelist_entry::WhitelistEntry;
+use crate::state::hook_metadata::HookRiskTier;
 
 /// Maximum number of whitelisted transfer hook programs
 pub const MAX_WHITELISTED_HOOKS: usize = 32;
 
+/// Layout version stamped on whitelists written by this program version.
+/// Whitelists created before layout versioning carry 0 and must go through
+/// `migrate_whitelist` before they can be loaded.
+pub const CURRENT_WHITELIST_LAYOUT_VERSION: u32 = 2;
+
+/// Maximum hook changes waiting out the timelock at once
+pub const MAX_PENDING_HOOK_CHANGES: usize = 8;
+
+/// Shortest timelock that can be configured on a whitelist
+pub const MIN_WHITELIST_TIMELOCK: i64 = 24 * 60 * 60; // 1 day
+
+/// Suggested timelock for whitelists guarding live pools
+pub const DEFAULT_WHITELIST_TIMELOCK: i64 = 48 * 60 * 60; // 48 hours
+
 /// Transfer Hook Whitelist Configuration
 /// This structure stores a list of trusted Transfer Hook program IDs
 /// that are allowed to be used with Token-2022 assets in this AMM
@@ -14,12 +34,104 @@ pub struct TransferHookWhitelist {
     pub authority: Pubkey,
     /// Number of currently whitelisted hook programs
     pub hook_count: u32,
-    /// Reserved for future use
-    pub reserved: u32,
+    /// Account layout version (see `CURRENT_WHITELIST_LAYOUT_VERSION`)
+    pub layout_version: u32,
     /// Array of whitelisted Transfer Hook program IDs
     pub whitelisted_hooks: [Pubkey; MAX_WHITELISTED_HOOKS],
-    /// Padding for future expansion
-    pub padding: [u64; 8],
+    /// Interface version of each whitelisted hook, parallel to `whitelisted_hooks`
+    pub hook_interface_versions: [u8; MAX_WHITELISTED_HOOKS],
+    /// Versioned optional fields, occupying the former padding
+    pub extensions: WhitelistExtensions,
+    /// Unix timestamp each hook was whitelisted at, parallel to `whitelisted_hooks`
+    /// (0 for entries carried over by a migration)
+    pub hook_added_at: [i64; MAX_WHITELISTED_HOOKS],
+    /// Hook changes waiting out the timelock (empty slots have a default hook id)
+    pub pending_hook_changes: [PendingHookChange; MAX_PENDING_HOOK_CHANGES],
+}
+
+/// Direction of a queued whitelist change
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HookChangeKind {
+    #[default]
+    Add,
+    Remove,
+}
+
+/// Whitelist change queued behind the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PendingHookChange {
+    /// Hook program the change applies to
+    pub hook_program_id: Pubkey,
+    /// Whether the hook is added or removed
+    pub kind: HookChangeKind,
+    /// Unix timestamp from which the change can be finalized
+    pub eta: i64,
+}
+
+/// Leading fields shared by every whitelist layout, enough to tell which
+/// layout an account is in
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct WhitelistLayoutHeader {
+    pub authority: Pubkey,
+    pub hook_count: u32,
+    pub layout_version: u32,
+}
+
+/// Whitelist layout prior to versioning (layout version 0), read only by
+/// `migrate_whitelist`. Serialized after the account discriminator.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyTransferHookWhitelist {
+    pub authority: Pubkey,
+    pub hook_count: u32,
+    pub layout_version: u32,
+    pub whitelisted_hooks: [Pubkey; MAX_WHITELISTED_HOOKS],
+    pub hook_interface_versions: [u8; MAX_WHITELISTED_HOOKS],
+    pub padding: [u64; 4],
+}
+
+impl LegacyTransferHookWhitelist {
+    /// Carry every entry over into the current layout
+    pub fn migrate(&self) -> TransferHookWhitelist {
+        TransferHookWhitelist {
+            authority: self.authority,
+            hook_count: self.hook_count,
+            layout_version: CURRENT_WHITELIST_LAYOUT_VERSION,
+            whitelisted_hooks: self.whitelisted_hooks,
+            hook_interface_versions: self.hook_interface_versions,
+            extensions: WhitelistExtensions::default(),
+            hook_added_at: [0i64; MAX_WHITELISTED_HOOKS],
+            pending_hook_changes: [PendingHookChange::default(); MAX_PENDING_HOOK_CHANGES],
+        }
+    }
+}
+
+/// Whitelist layout version 1, before the timelock queue, read only by
+/// `migrate_whitelist`. Serialized after the account discriminator.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TransferHookWhitelistV1 {
+    pub authority: Pubkey,
+    pub hook_count: u32,
+    pub layout_version: u32,
+    pub whitelisted_hooks: [Pubkey; MAX_WHITELISTED_HOOKS],
+    pub hook_interface_versions: [u8; MAX_WHITELISTED_HOOKS],
+    pub extensions: WhitelistExtensions,
+    pub hook_added_at: [i64; MAX_WHITELISTED_HOOKS],
+}
+
+impl TransferHookWhitelistV1 {
+    /// Carry every entry over into the current layout with an empty queue
+    pub fn migrate(&self) -> TransferHookWhitelist {
+        TransferHookWhitelist {
+            authority: self.authority,
+            hook_count: self.hook_count,
+            layout_version: CURRENT_WHITELIST_LAYOUT_VERSION,
+            whitelisted_hooks: self.whitelisted_hooks,
+            hook_interface_versions: self.hook_interface_versions,
+            extensions: self.extensions,
+            hook_added_at: self.hook_added_at,
+            pending_hook_changes: [PendingHookChange::default(); MAX_PENDING_HOOK_CHANGES],
+        }
+    }
 }
 
 impl TransferHookWhitelist {
@@ -27,20 +139,131 @@ impl TransferHookWhitelist {
     pub fn initialize(&mut self, authority: Pubkey) -> Result<()> {
         self.authority = authority;
         self.hook_count = 0;
-        self.reserved = 0;
+        self.layout_version = CURRENT_WHITELIST_LAYOUT_VERSION;
         self.whitelisted_hooks = [Pubkey::default(); MAX_WHITELISTED_HOOKS];
-        self.padding = [0u64; 8];
+        self.hook_interface_versions = [0u8; MAX_WHITELISTED_HOOKS];
+        self.extensions = WhitelistExtensions::default();
+        self.hook_added_at = [0i64; MAX_WHITELISTED_HOOKS];
+        self.pending_hook_changes = [PendingHookChange::default(); MAX_PENDING_HOOK_CHANGES];
+        Ok(())
+    }
+
+    /// Timelock on hook changes, if the authority has enabled one
+    pub fn timelock_delay(&self) -> Option<i64> {
+        self.extensions.get(WhitelistExtensionField::TimelockDelay).map(|delay| delay as i64)
+    }
+
+    /// Enable or lengthen the timelock. It can never be shortened or removed,
+    /// so a compromised authority cannot skip the waiting period.
+    pub fn set_timelock_delay(&mut self, delay: i64) -> Result<()> {
+        require!(delay >= MIN_WHITELIST_TIMELOCK, AmmError::InvalidWhitelistTimelock);
+        require!(
+            self.timelock_delay().is_none_or(|current| delay >= current),
+            AmmError::InvalidWhitelistTimelock
+        );
+        self.extensions.set(WhitelistExtensionField::TimelockDelay, delay as u64)
+    }
+
+    /// Reject instant hook changes once a timelock is in place
+    pub fn require_no_timelock(&self) -> Result<()> {
+        require!(self.timelock_delay().is_none(), AmmError::WhitelistTimelocked);
+        Ok(())
+    }
+
+    fn pending_change_index(&self, hook_program_id: &Pubkey) -> Option<usize> {
+        self.pending_hook_changes
+            .iter()
+            .position(|change| change.hook_program_id == *hook_program_id && *hook_program_id != Pubkey::default())
+    }
+
+    /// Queue a hook change behind the timelock. Returns the time from which
+    /// it can be finalized.
+    pub fn queue_hook_change(&mut self, kind: HookChangeKind, hook_program_id: Pubkey, now: i64) -> Result<i64> {
+        require!(hook_program_id != Pubkey::default(), AmmError::InvalidHookProgram);
+        require!(self.pending_change_index(&hook_program_id).is_none(), AmmError::HookChangeAlreadyQueued);
+        match kind {
+            HookChangeKind::Add => require!(!self.is_hook_whitelisted(&hook_program_id), AmmError::HookAlreadyWhitelisted),
+            HookChangeKind::Remove => require!(self.is_hook_whitelisted(&hook_program_id), AmmError::HookNotWhitelisted),
+        }
+
+        let slot = self.pending_hook_changes
+            .iter()
+            .position(|change| change.hook_program_id == Pubkey::default())
+            .ok_or(AmmError::HookChangeQueueFull)?;
+        let eta = now.checked_add(self.timelock_delay().unwrap_or(0)).ok_or(AmmError::MathOverflow)?;
+        self.pending_hook_changes[slot] = PendingHookChange { hook_program_id, kind, eta };
+        Ok(eta)
+    }
+
+    /// Apply a queued hook change whose timelock has elapsed
+    pub fn finalize_hook_change(&mut self, hook_program_id: &Pubkey, now: i64) -> Result<HookChangeKind> {
+        let index = self.pending_change_index(hook_program_id).ok_or(AmmError::HookChangeNotQueued)?;
+        let change = self.pending_hook_changes[index];
+        require!(now >= change.eta, AmmError::HookChangeNotReady);
+
+        self.pending_hook_changes[index] = PendingHookChange::default();
+        match change.kind {
+            HookChangeKind::Add => self.add_hook(change.hook_program_id)?,
+            HookChangeKind::Remove => self.remove_hook(&change.hook_program_id)?,
+        }
+        Ok(change.kind)
+    }
+
+    /// Drop a queued hook change
+    pub fn cancel_hook_change(&mut self, hook_program_id: &Pubkey) -> Result<()> {
+        let index = self.pending_change_index(hook_program_id).ok_or(AmmError::HookChangeNotQueued)?;
+        self.pending_hook_changes[index] = PendingHookChange::default();
         Ok(())
     }
 
     /// Check if a Transfer Hook program ID is whitelisted
     pub fn is_hook_whitelisted(&self, hook_program_id: &Pubkey) -> bool {
-        for i in 0..(self.hook_count as usize) {
-            if self.whitelisted_hooks[i] == *hook_program_id {
-                return true;
-            }
-        }
-        false
+        self.hook_index(hook_program_id).is_some()
+    }
+
+    /// Position of a hook in the whitelist array
+    pub fn hook_index(&self, hook_program_id: &Pubkey) -> Option<usize> {
+        (0..(self.hook_count as usize)).find(|&i| self.whitelisted_hooks[i] == *hook_program_id)
+    }
+
+    /// Interface version recorded for a whitelisted hook
+    pub fn hook_interface_version(&self, hook_program_id: &Pubkey) -> Option<HookInterfaceVersion> {
+        self.hook_index(hook_program_id)
+            .map(|i| HookInterfaceVersion::from_u8(self.hook_interface_versions[i]))
+    }
+
+    /// Record the interface version a whitelisted hook speaks
+    pub fn set_hook_interface_version(
+        &mut self,
+        hook_program_id: &Pubkey,
+        version: HookInterfaceVersion,
+    ) -> Result<()> {
+        let index = self.hook_index(hook_program_id).ok_or(AmmError::HookNotWhitelisted)?;
+        self.hook_interface_versions[index] = version as u8;
+        Ok(())
+    }
+
+    fn high_risk_mask(&self) -> u64 {
+        self.extensions.get(WhitelistExtensionField::HighRiskHooks).unwrap_or(0)
+    }
+
+    /// Whether an inline hook is rated high risk
+    pub fn is_high_risk_hook(&self, hook_program_id: &Pubkey) -> bool {
+        self.hook_index(hook_program_id)
+            .is_some_and(|index| self.high_risk_mask() & (1u64 << index) != 0)
+    }
+
+    /// Record an inline hook's risk tier
+    pub fn set_hook_risk_tier(&mut self, hook_program_id: &Pubkey, risk_tier: HookRiskTier) -> Result<()> {
+        let index = self
+            .hook_index(hook_program_id)
+            .ok_or(AmmError::HookNotWhitelisted)?;
+        let mask = if risk_tier == HookRiskTier::High {
+            self.high_risk_mask() | (1u64 << index)
+        } else {
+            self.high_risk_mask() & !(1u64 << index)
+        };
+        self.extensions.set(WhitelistExtensionField::HighRiskHooks, mask)
     }
 
     /// Add a Transfer Hook program ID to the whitelist
@@ -55,6 +278,8 @@ impl TransferHookWhitelist {
         }
 
         self.whitelisted_hooks[self.hook_count as usize] = hook_program_id;
+        self.hook_interface_versions[self.hook_count as usize] = HookInterfaceVersion::default() as u8;
+        self.hook_added_at[self.hook_count as usize] = Clock::get()?.unix_timestamp;
         self.hook_count += 1;
         Ok(())
     }
@@ -63,16 +288,144 @@ impl TransferHookWhitelist {
     pub fn remove_hook(&mut self, hook_program_id: &Pubkey) -> Result<()> {
         for i in 0..(self.hook_count as usize) {
             if self.whitelisted_hooks[i] == *hook_program_id {
+                // Keep the high-risk bits aligned with the shifted entries
+                let mask = self.high_risk_mask();
+                if mask != 0 {
+                    let below = mask & ((1u64 << i) - 1);
+                    let above = (mask >> (i + 1)) << i;
+                    self.extensions.set(WhitelistExtensionField::HighRiskHooks, below | above)?;
+                }
                 // Shift remaining elements left
                 for j in i..(self.hook_count as usize - 1) {
                     self.whitelisted_hooks[j] = self.whitelisted_hooks[j + 1];
+                    self.hook_interface_versions[j] = self.hook_interface_versions[j + 1];
+                    self.hook_added_at[j] = self.hook_added_at[j + 1];
                 }
                 // Clear the last element
                 self.whitelisted_hooks[self.hook_count as usize - 1] = Pubkey::default();
+                self.hook_interface_versions[self.hook_count as usize - 1] = 0;
+                self.hook_added_at[self.hook_count as usize - 1] = 0;
                 self.hook_count -= 1;
                 return Ok(());
             }
         }
         Err(AmmError::HookNotWhitelisted.into())
     }
-}
\ No newline at end of file
+
+    /// Validate a mint's Transfer Hook (if it has one) against this whitelist.
+    /// Returns the hook program id that was checked.
+    pub fn validate_mint_hook(&self, mint_info: &AccountInfo) -> Result<Option<Pubkey>> {
+        match transfer_hook_program_id(mint_info)? {
+            Some(hook_program_id) => {
+                require_ctx!(
+                    self.is_hook_whitelisted(&hook_program_id),
+                    AmmError::HookNotWhitelisted,
+                    Some(mint_info.key()),
+                    Some(hook_program_id)
+                );
+                Ok(Some(hook_program_id))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// `validate_mint_hook` that also accepts a hook listed through a
+    /// `WhitelistEntry` PDA of this whitelist (`whitelist_key`) found in `entries`
+    pub fn validate_mint_hook_with_entries(
+        &self,
+        whitelist_key: &Pubkey,
+        mint_info: &AccountInfo,
+        entries: &[AccountInfo],
+    ) -> Result<Option<Pubkey>> {
+        match transfer_hook_program_id(mint_info)? {
+            Some(hook_program_id) => {
+                require_ctx!(
+                    self.is_hook_whitelisted(&hook_program_id)
+                        || WhitelistEntry::find_in(entries, whitelist_key, &hook_program_id).is_some(),
+                    AmmError::HookNotWhitelisted,
+                    Some(mint_info.key()),
+                    Some(hook_program_id)
+                );
+                Ok(Some(hook_program_id))
+            }
+            None => Ok(None),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::{
+        self,
+        extension::{transfer_hook::TransferHook, ExtensionType, StateWithExtensionsMut},
+        state::Mint,
+    };
+    use crate::token_extensions::transfer_hook_program_id;
+
+    /// Packed Token-2022 mint carrying a Transfer Hook extension when `hook` is set
+    fn mint_data(hook: Option<Pubkey>) -> Vec<u8> {
+        let extensions: &[ExtensionType] = if hook.is_some() { &[ExtensionType::TransferHook] } else { &[] };
+        let mut data = vec![0u8; ExtensionType::try_calculate_account_len::<Mint>(extensions).unwrap()];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        if let Some(hook) = hook {
+            state.init_extension::<TransferHook>(true).unwrap().program_id = Some(hook).try_into().unwrap();
+        }
+        state.base = Mint { decimals: 6, is_initialized: true, ..Mint::default() };
+        state.pack_base();
+        if hook.is_some() {
+            state.init_account_type().unwrap();
+        }
+        data
+    }
+
+    fn whitelist_with(hook: Pubkey) -> TransferHookWhitelist {
+        let mut whitelist = TransferHookWhitelist::default();
+        whitelist.initialize(Pubkey::new_unique()).unwrap();
+        whitelist.whitelisted_hooks[0] = hook;
+        whitelist.hook_count = 1;
+        whitelist
+    }
+
+    fn check(whitelist: &TransferHookWhitelist, data: &mut [u8]) -> (Result<Option<Pubkey>>, Option<Pubkey>) {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, data, &spl_token_2022::ID, false, 0);
+        let deposit = whitelist.validate_mint_hook_with_entries(&Pubkey::new_unique(), &info, &[]);
+        (deposit, transfer_hook_program_id(&info).unwrap())
+    }
+
+    #[test]
+    fn mint_without_hook_passes_every_path() {
+        let whitelist = whitelist_with(Pubkey::new_unique());
+        let (deposit, withdrawal) = check(&whitelist, &mut mint_data(None));
+        assert_eq!(deposit.unwrap(), None);
+        assert_eq!(withdrawal, None);
+    }
+
+    #[test]
+    fn whitelisted_hook_passes_deposits() {
+        let hook = Pubkey::new_unique();
+        let whitelist = whitelist_with(hook);
+        let (deposit, withdrawal) = check(&whitelist, &mut mint_data(Some(hook)));
+        assert_eq!(deposit.unwrap(), Some(hook));
+        assert_eq!(withdrawal, Some(hook));
+    }
+
+    #[test]
+    fn unlisted_hook_is_rejected_on_deposits() {
+        let whitelist = whitelist_with(Pubkey::new_unique());
+        let (deposit, _) = check(&whitelist, &mut mint_data(Some(Pubkey::new_unique())));
+        assert!(deposit.is_err());
+    }
+
+    // Withdrawals only read the hook, so delisting it cannot trap LP funds
+    #[test]
+    fn delisted_hook_still_resolves_for_withdrawals() {
+        let hook = Pubkey::new_unique();
+        let mut whitelist = whitelist_with(hook);
+        whitelist.remove_hook(&hook).unwrap();
+        let (deposit, withdrawal) = check(&whitelist, &mut mint_data(Some(hook)));
+        assert!(deposit.is_err());
+        assert_eq!(withdrawal, Some(hook));
+    }
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use crate::state::HookRiskTier;
+use crate::pda::WhitelistEntryPda;
+
+/// Whitelist Entry
+/// One whitelisted hook stored in its own PDA next to a whitelist, so a list
+/// is not capped by `MAX_WHITELISTED_HOOKS` and each lookup is a single
+/// account fetch. Entry-listed hooks speak the spl-transfer-hook-interface;
+/// legacy hooks stay in the whitelist's own array.
+#[account]
+#[derive(Default)]
+pub struct WhitelistEntry {
+    /// Whitelist this entry extends
+    pub whitelist: Pubkey,
+
+    /// Whitelisted Transfer Hook program
+    pub hook_program_id: Pubkey,
+
+    /// Unix timestamp the hook was whitelisted at
+    pub added_at: i64,
+
+    /// Signer that added the entry
+    pub added_by: Pubkey,
+
+    /// Governance proposal the entry was executed from (default = none)
+    pub proposal: Pubkey,
+
+    /// Risk rating of the hook (see `HookMetadata`)
+    pub risk_tier: HookRiskTier,
+
+    /// Entry bump seed
+    pub bump: u8,
+}
+
+impl WhitelistEntry {
+    /// Seed of an entry PDA, derived as [SEED, whitelist, hook_program_id]
+    pub const SEED: &'static [u8] = b"hook";
+
+    pub fn address(whitelist: &Pubkey, hook_program_id: &Pubkey) -> Pubkey {
+        WhitelistEntryPda::find(whitelist, hook_program_id).0
+    }
+
+    pub fn initialize(
+        &mut self,
+        whitelist: Pubkey,
+        hook_program_id: Pubkey,
+        added_by: Pubkey,
+        proposal: Pubkey,
+        now: i64,
+        bump: u8,
+    ) -> Result<()> {
+        self.whitelist = whitelist;
+        self.hook_program_id = hook_program_id;
+        self.added_at = now;
+        self.added_by = added_by;
+        self.proposal = proposal;
+        self.risk_tier = HookRiskTier::Low;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Find the entry vouching for `hook_program_id` on `whitelist` among
+    /// `accounts`. Only a program-owned account at the entry PDA counts.
+    pub fn find_in(
+        accounts: &[AccountInfo],
+        whitelist: &Pubkey,
+        hook_program_id: &Pubkey,
+    ) -> Option<WhitelistEntry> {
+        let address = Self::address(whitelist, hook_program_id);
+        let info = accounts.iter().find(|info| info.key() == address)?;
+        if *info.owner != crate::ID {
+            return None;
+        }
+        let data = info.try_borrow_data().ok()?;
+        let entry = WhitelistEntry::try_deserialize(&mut &data[..]).ok()?;
+        (entry.whitelist == *whitelist && entry.hook_program_id == *hook_program_id).then_some(entry)
+    }
+}
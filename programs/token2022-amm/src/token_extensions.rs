@@ -0,0 +1,419 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::associated_token::{create_idempotent, Create};
+use anchor_spl::memo::{build_memo, BuildMemo, Memo};
+use anchor_spl::token_interface::{sync_native, SyncNative};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        confidential_transfer::{ConfidentialTransferAccount, ConfidentialTransferMint},
+        cpi_guard::CpiGuard,
+        default_account_state::DefaultAccountState,
+        interest_bearing_mint::InterestBearingConfig,
+        memo_transfer::MemoTransfer,
+        mint_close_authority::MintCloseAuthority,
+        non_transferable::NonTransferable,
+        permanent_delegate::PermanentDelegate,
+        transfer_fee::TransferFeeConfig,
+        transfer_hook,
+        BaseStateWithExtensions,
+        StateWithExtensions,
+    },
+    state::{Account as TokenAccountState, AccountState, Mint as MintState},
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+use crate::error::AmmError;
+
+/// Run `f` against the unpacked extension state of a Token-2022 mint.
+/// Returns `None` for legacy SPL mints, which carry no extensions.
+fn with_mint_extensions<T>(
+    mint_info: &AccountInfo,
+    f: impl FnOnce(&StateWithExtensions<MintState>) -> Result<T>,
+) -> Result<Option<T>> {
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<MintState>::unpack(&data)?;
+    f(&mint).map(Some)
+}
+
+/// Fields of a token account that decide whether a transfer through it can succeed
+#[derive(Clone, Copy, Debug)]
+pub struct TokenAccountSummary {
+    /// Mint the account holds
+    pub mint: Pubkey,
+    /// Wallet that owns the account
+    pub owner: Pubkey,
+    /// Current balance
+    pub amount: u64,
+    /// Whether the mint's freeze authority has frozen the account
+    pub is_frozen: bool,
+    /// Incoming transfers must be preceded by a memo (MemoTransfer extension)
+    pub requires_incoming_memo: bool,
+    /// Account is configured for confidential transfers with non-confidential
+    /// credits disabled, so plain `transfer_checked` deposits into it fail
+    pub rejects_public_credits: bool,
+    /// CPI Guard is on, so the owner cannot sign transfers or burns out of
+    /// the account through a program
+    pub cpi_guard_enabled: bool,
+}
+
+/// Summarize an SPL Token or Token-2022 account.
+/// Returns `None` when the account is not owned by a token program or does not
+/// unpack as a token account.
+pub fn token_account_summary(account_info: &AccountInfo) -> Option<TokenAccountSummary> {
+    if *account_info.owner != spl_token_2022::ID && *account_info.owner != anchor_spl::token::ID {
+        return None;
+    }
+
+    let data = account_info.try_borrow_data().ok()?;
+    let account = StateWithExtensions::<TokenAccountState>::unpack(&data).ok()?;
+    let requires_incoming_memo = account
+        .get_extension::<MemoTransfer>()
+        .map(|memo| bool::from(memo.require_incoming_transfer_memos))
+        .unwrap_or(false);
+    let rejects_public_credits = account
+        .get_extension::<ConfidentialTransferAccount>()
+        .map(|confidential| !bool::from(confidential.allow_non_confidential_credits))
+        .unwrap_or(false);
+    let cpi_guard_enabled = account
+        .get_extension::<CpiGuard>()
+        .map(|guard| bool::from(guard.lock_cpi))
+        .unwrap_or(false);
+
+    Some(TokenAccountSummary {
+        mint: account.base.mint,
+        owner: account.base.owner,
+        amount: account.base.amount,
+        is_frozen: account.base.state == AccountState::Frozen,
+        requires_incoming_memo,
+        rejects_public_credits,
+        cpi_guard_enabled,
+    })
+}
+
+/// Reject an owner-signed debit of `account_info` up front when the account
+/// has CPI Guard enabled; Token-2022 would otherwise fail the transfer or burn
+/// deep inside the CPI with an opaque error.
+pub fn require_no_cpi_guard(account_info: &AccountInfo) -> Result<()> {
+    if *account_info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let cpi_guard_enabled = token_account_summary(account_info)
+        .is_some_and(|account| account.cpi_guard_enabled);
+    require!(!cpi_guard_enabled, AmmError::CpiGuardEnabled);
+    Ok(())
+}
+
+/// Reject a pool payout into `account_info` that Token-2022 would refuse
+/// because the account only takes confidential credits. Only mints screened
+/// with `MINT_EXT_CONFIDENTIAL` can have such accounts, so others skip the parse.
+pub fn require_public_credits(mint_extensions: u16, account_info: &AccountInfo) -> Result<()> {
+    if mint_extensions & MINT_EXT_CONFIDENTIAL == 0 {
+        return Ok(());
+    }
+    let rejects_public_credits = token_account_summary(account_info)
+        .is_some_and(|account| account.rejects_public_credits);
+    require!(!rejects_public_credits, AmmError::PublicCreditsDisabled);
+    Ok(())
+}
+
+/// Prefix of the memos the AMM writes ahead of its own payouts
+pub const AMM_MEMO_PREFIX: &str = "token2022-amm";
+
+/// Write a structured memo (`token2022-amm:<action>:<pool>`) immediately before
+/// a pool payout into `destination` when that account has MemoTransfer enabled.
+/// Token-2022 reads the memo as the transfer's preceding sibling instruction, so
+/// this must be the last CPI before the transfer. The SPL Memo program is only
+/// needed in `remaining_accounts` for memo-required destinations.
+pub fn memo_if_required<'info>(
+    destination: &AccountInfo<'info>,
+    action: &str,
+    pool: &Pubkey,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if *destination.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let requires_incoming_memo = token_account_summary(destination)
+        .is_some_and(|account| account.requires_incoming_memo);
+    if !requires_incoming_memo {
+        return Ok(());
+    }
+
+    let memo_program = remaining_accounts
+        .iter()
+        .find(|account| account.key() == Memo::id())
+        .ok_or(AmmError::MemoProgramRequired)?;
+    let memo = format!("{}:{}:{}", AMM_MEMO_PREFIX, action, pool);
+    build_memo(CpiContext::new(memo_program.clone(), BuildMemo {}), memo.as_bytes())
+}
+
+/// Whether the SPL Memo program is among `remaining_accounts`
+pub fn memo_program_supplied(remaining_accounts: &[AccountInfo]) -> bool {
+    remaining_accounts.iter().any(|account| account.key() == Memo::id())
+}
+
+/// Read the permanent delegate configured on a Token-2022 mint.
+/// Returns `None` for legacy SPL mints and Token-2022 mints without one.
+pub fn permanent_delegate(mint_info: &AccountInfo) -> Result<Option<Pubkey>> {
+    Ok(with_mint_extensions(mint_info, |mint| {
+        Ok(mint
+            .get_extension::<PermanentDelegate>()
+            .ok()
+            .and_then(|extension| Option::<Pubkey>::from(extension.delegate)))
+    })?
+    .flatten())
+}
+
+/// Whether `mint` is the native mint of SPL Token or Token-2022, whose
+/// accounts hold wrapped SOL
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == anchor_spl::token::spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID
+}
+
+/// Create `owner`'s associated token account of `mint` at `account_info`
+/// when it does not exist yet, with `payer` paying the rent. Existing accounts
+/// are left alone, so any account the owner already holds still works; the
+/// Associated Token and System programs are only needed when one is missing.
+pub fn create_ata_if_missing<'info>(
+    payer: &AccountInfo<'info>,
+    account_info: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    associated_token_program: Option<AccountInfo<'info>>,
+    system_program: Option<AccountInfo<'info>>,
+) -> Result<()> {
+    if !account_info.data_is_empty() {
+        return Ok(());
+    }
+    let (Some(associated_token_program), Some(system_program)) = (associated_token_program, system_program) else {
+        return err!(AmmError::TokenAccountMissing);
+    };
+    create_idempotent(CpiContext::new(
+        associated_token_program,
+        Create {
+            payer: payer.clone(),
+            associated_token: account_info.clone(),
+            authority: owner.clone(),
+            mint: mint.clone(),
+            system_program,
+            token_program: token_program.clone(),
+        },
+    ))
+}
+
+/// Wrap `lamports` of `owner`'s SOL into their associated WSOL account of
+/// `native_mint`, creating the account first if it does not exist. Returns
+/// whether it was created, so a caller can close it again afterwards.
+pub fn wrap_sol<'info>(
+    owner: &AccountInfo<'info>,
+    wsol_account: &AccountInfo<'info>,
+    native_mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    associated_token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    lamports: u64,
+) -> Result<bool> {
+    let created = wsol_account.data_is_empty();
+    create_ata_if_missing(
+        owner,
+        wsol_account,
+        owner,
+        native_mint,
+        token_program,
+        Some(associated_token_program.clone()),
+        Some(system_program.clone()),
+    )?;
+
+    system_program::transfer(
+        CpiContext::new(
+            system_program.clone(),
+            Transfer {
+                from: owner.clone(),
+                to: wsol_account.clone(),
+            },
+        ),
+        lamports,
+    )?;
+    sync_native(CpiContext::new(
+        token_program.clone(),
+        SyncNative { account: wsol_account.clone() },
+    ))?;
+    Ok(created)
+}
+
+/// Read the Transfer Hook program id configured on a Token-2022 mint.
+/// Returns `None` for legacy SPL mints and Token-2022 mints without a hook.
+pub fn transfer_hook_program_id(mint_info: &AccountInfo) -> Result<Option<Pubkey>> {
+    Ok(with_mint_extensions(mint_info, |mint| Ok(transfer_hook::get_program_id(mint)))?.flatten())
+}
+
+/// `screen_mint` bit: a permanent delegate can move or burn tokens out of any
+/// account, pool vaults included
+pub const MINT_EXT_PERMANENT_DELEGATE: u16 = 1 << 0;
+
+/// `screen_mint` bit: the mint is non-transferable (soulbound)
+pub const MINT_EXT_NON_TRANSFERABLE: u16 = 1 << 1;
+
+/// `screen_mint` bit: new token accounts, pool vaults included, start frozen
+pub const MINT_EXT_DEFAULT_FROZEN: u16 = 1 << 2;
+
+/// `screen_mint` bit: a pause authority can halt every transfer of the mint
+pub const MINT_EXT_PAUSABLE: u16 = 1 << 3;
+
+/// `screen_mint` bit: the mint supports confidential transfers, so balances
+/// can move where the pool cannot see them
+pub const MINT_EXT_CONFIDENTIAL: u16 = 1 << 4;
+
+/// `screen_mint` bit: a close authority can close the mint once supply is zero
+pub const MINT_EXT_CLOSE_AUTHORITY: u16 = 1 << 5;
+
+/// `screen_mint` bit: the mint accrues interest, so UI amounts drift away
+/// from the raw amounts the pool prices in
+pub const MINT_EXT_INTEREST_BEARING: u16 = 1 << 6;
+
+/// TLV type of the Pausable mint extension, which the spl-token-2022 version
+/// this program builds against does not know yet
+const PAUSABLE_CONFIG_EXTENSION_TYPE: u16 = 26;
+
+/// Bitmap of the risky Token-2022 extensions a mint carries (`MINT_EXT_*`).
+/// Legacy SPL mints screen as 0.
+pub fn screen_mint(mint_info: &AccountInfo) -> Result<u16> {
+    let flags = with_mint_extensions(mint_info, |mint| {
+        let mut flags = 0;
+        if let Ok(extension) = mint.get_extension::<PermanentDelegate>() {
+            if Option::<Pubkey>::from(extension.delegate).is_some() {
+                flags |= MINT_EXT_PERMANENT_DELEGATE;
+            }
+        }
+        if mint.get_extension::<NonTransferable>().is_ok() {
+            flags |= MINT_EXT_NON_TRANSFERABLE;
+        }
+        if let Ok(extension) = mint.get_extension::<DefaultAccountState>() {
+            if extension.state == AccountState::Frozen as u8 {
+                flags |= MINT_EXT_DEFAULT_FROZEN;
+            }
+        }
+        if mint.get_extension::<ConfidentialTransferMint>().is_ok() {
+            flags |= MINT_EXT_CONFIDENTIAL;
+        }
+        if let Ok(extension) = mint.get_extension::<MintCloseAuthority>() {
+            if Option::<Pubkey>::from(extension.close_authority).is_some() {
+                flags |= MINT_EXT_CLOSE_AUTHORITY;
+            }
+        }
+        if mint.get_extension::<InterestBearingConfig>().is_ok() {
+            flags |= MINT_EXT_INTEREST_BEARING;
+        }
+        Ok(flags)
+    })?;
+    let Some(mut flags) = flags else {
+        return Ok(0);
+    };
+
+    if has_raw_mint_extension(&mint_info.try_borrow_data()?, PAUSABLE_CONFIG_EXTENSION_TYPE) {
+        flags |= MINT_EXT_PAUSABLE;
+    }
+    Ok(flags)
+}
+
+/// Walk a Token-2022 mint's TLV entries looking for `extension_type`
+fn has_raw_mint_extension(data: &[u8], extension_type: u16) -> bool {
+    // Extensions follow the base state padded to an account's length and
+    // the account type byte
+    let mut offset = TokenAccountState::LEN + 1;
+    while offset + 4 <= data.len() {
+        let tlv_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        if tlv_type == extension_type {
+            return true;
+        }
+        if tlv_type == 0 {
+            return false;
+        }
+        offset += 4 + length;
+    }
+    false
+}
+
+/// Transfer fee withheld by the mint when `amount` is sent during `epoch`
+pub fn transfer_fee_for(mint_info: &AccountInfo, amount: u64, epoch: u64) -> Result<u64> {
+    let fee = with_mint_extensions(mint_info, |mint| {
+        match mint.get_extension::<TransferFeeConfig>() {
+            Ok(config) => Ok(config
+                .calculate_epoch_fee(epoch, amount)
+                .ok_or(AmmError::MathOverflow)?),
+            Err(_) => Ok(0),
+        }
+    })?;
+    Ok(fee.unwrap_or(0))
+}
+
+/// Gross amount to send during `epoch` so the recipient is credited exactly
+/// `net_amount` after the mint's transfer fee, honouring the fee cap
+pub fn gross_amount_for_net(mint_info: &AccountInfo, net_amount: u64, epoch: u64) -> Result<u64> {
+    let fee = with_mint_extensions(mint_info, |mint| {
+        match mint.get_extension::<TransferFeeConfig>() {
+            Ok(config) => Ok(config
+                .calculate_inverse_epoch_fee(epoch, net_amount)
+                .ok_or(AmmError::MathOverflow)?),
+            Err(_) => Ok(0),
+        }
+    })?;
+    Ok(net_amount.checked_add(fee.unwrap_or(0)).ok_or(AmmError::MathOverflow)?)
+}
+
+/// Amount credited to the recipient when `gross_amount` is sent during `epoch`
+pub fn net_amount_for_gross(mint_info: &AccountInfo, gross_amount: u64, epoch: u64) -> Result<u64> {
+    let fee = transfer_fee_for(mint_info, gross_amount, epoch)?;
+    Ok(gross_amount.checked_sub(fee).ok_or(AmmError::MathOverflow)?)
+}
+
+/// Short label for a mint in display strings: the symbol from its in-mint
+/// TokenMetadata, or the first characters of its address when it has none
+pub fn mint_symbol(mint_info: &AccountInfo) -> Result<String> {
+    let symbol = with_mint_extensions(mint_info, |mint| {
+        Ok(mint
+            .get_variable_len_extension::<TokenMetadata>()
+            .ok()
+            .map(|metadata| metadata.symbol)
+            .filter(|symbol| !symbol.is_empty()))
+    })?.flatten();
+    Ok(symbol.unwrap_or_else(|| mint_info.key.to_string()[..4].to_string()))
+}
+
+/// Current interest rate of an interest-bearing mint (basis points per year).
+/// Returns `None` for mints without an InterestBearingConfig.
+pub fn interest_rate_bps(mint_info: &AccountInfo) -> Result<Option<i16>> {
+    Ok(with_mint_extensions(mint_info, |mint| {
+        Ok(mint
+            .get_extension::<InterestBearingConfig>()
+            .ok()
+            .map(|config| i16::from(config.current_rate)))
+    })?.flatten())
+}
+
+/// UI representation of a raw amount, applying interest-bearing scaling when the
+/// mint has an InterestBearingConfig
+pub fn amount_to_ui_string(
+    mint_info: &AccountInfo,
+    amount: u64,
+    decimals: u8,
+    unix_timestamp: i64,
+) -> Result<String> {
+    let scaled = with_mint_extensions(mint_info, |mint| {
+        match mint.get_extension::<InterestBearingConfig>() {
+            Ok(config) => Ok(Some(config
+                .amount_to_ui_amount(amount, decimals, unix_timestamp)
+                .ok_or(AmmError::MathOverflow)?)),
+            Err(_) => Ok(None),
+        }
+    })?.flatten();
+
+    Ok(scaled.unwrap_or_else(|| spl_token_2022::amount_to_ui_amount_string_trimmed(amount, decimals)))
+}
@@ -1,6 +1,15 @@
 import { Connection, PublicKey, Transaction, sendAndConfirmTransaction } from '@solana/web3.js';
 import { Program, AnchorProvider, web3, BN } from '@coral-xyz/anchor';
 import { TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID } from '@solana/spl-token';
+import { SwapCurve } from '../crates/amm-math-wasm/pkg';
+
+/** Seconds a transaction built with the default deadline stays executable */
+export const DEFAULT_DEADLINE_SECONDS = 60;
+
+/** Deadline (unix seconds) `DEFAULT_DEADLINE_SECONDS` from now */
+export function defaultDeadline(): number {
+  return Math.floor(Date.now() / 1000) + DEFAULT_DEADLINE_SECONDS;
+}
 
 /**
  * Token-2022 AMM SDK
@@ -24,6 +33,177 @@ export class Token2022AmmSDK {
     this.provider = provider;
   }
 
+  /**
+   * Derive the pool PDA for a token pair in a fee tier (basis points). The
+   * mints are sorted into the canonical order the program expects, so either
+   * order can be passed.
+   */
+  findPoolAddress(mintA: PublicKey, mintB: PublicKey, feeTier: number = 30): PublicKey {
+    const [tokenAMint, tokenBMint] =
+      Buffer.compare(mintA.toBuffer(), mintB.toBuffer()) < 0 ? [mintA, mintB] : [mintB, mintA];
+    const [pool] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('pool'),
+        tokenAMint.toBuffer(),
+        tokenBMint.toBuffer(),
+        new BN(feeTier).toArrayLike(Buffer, 'le', 8),
+      ],
+      this.program.programId
+    );
+
+    return pool;
+  }
+
+  /**
+   * Derive the PDA that owns a pool's token vaults
+   */
+  findVaultAuthorityAddress(poolAddress: PublicKey): PublicKey {
+    const [vaultAuthority] = PublicKey.findProgramAddressSync(
+      [poolAddress.toBuffer(), Buffer.from('vault_auth')],
+      this.program.programId
+    );
+
+    return vaultAuthority;
+  }
+
+  /**
+   * Derive the AMM config PDA
+   */
+  findAmmConfigAddress(): PublicKey {
+    const [ammConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from('amm_config')],
+      this.program.programId
+    );
+
+    return ammConfig;
+  }
+
+  /**
+   * Derive a pool's TWAP observation PDA
+   */
+  findObservationAddress(poolAddress: PublicKey): PublicKey {
+    const [observation] = PublicKey.findProgramAddressSync(
+      [Buffer.from('observation'), poolAddress.toBuffer()],
+      this.program.programId
+    );
+
+    return observation;
+  }
+
+  /**
+   * Derive a concentrated liquidity pool PDA for a pair in one fee tier
+   */
+  findClmmPoolAddress(mintA: PublicKey, mintB: PublicKey, feeTier: number = 30): PublicKey {
+    const [pool] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('clmm_pool'),
+        mintA.toBuffer(),
+        mintB.toBuffer(),
+        new BN(feeTier).toArrayLike(Buffer, 'le', 8),
+      ],
+      this.program.programId
+    );
+
+    return pool;
+  }
+
+  /**
+   * Derive the tick array PDA of a concentrated liquidity pool starting at `startTickIndex`
+   */
+  findTickArrayAddress(poolAddress: PublicKey, startTickIndex: number): PublicKey {
+    const start = Buffer.alloc(4);
+    start.writeInt32LE(startTickIndex);
+    const [tickArray] = PublicKey.findProgramAddressSync(
+      [Buffer.from('tick_array'), poolAddress.toBuffer(), start],
+      this.program.programId
+    );
+
+    return tickArray;
+  }
+
+  /**
+   * Derive an owner's concentrated liquidity position PDA over [tickLower, tickUpper)
+   */
+  findClmmPositionAddress(
+    poolAddress: PublicKey,
+    owner: PublicKey,
+    tickLower: number,
+    tickUpper: number
+  ): PublicKey {
+    const lower = Buffer.alloc(4);
+    lower.writeInt32LE(tickLower);
+    const upper = Buffer.alloc(4);
+    upper.writeInt32LE(tickUpper);
+    const [position] = PublicKey.findProgramAddressSync(
+      [Buffer.from('clmm_position'), poolAddress.toBuffer(), owner.toBuffer(), lower, upper],
+      this.program.programId
+    );
+
+    return position;
+  }
+
+  /**
+   * Read a pool's cumulative prices as of each `secondsAgos` entry. The TWAP
+   * between two entries is the accumulator difference divided by the seconds
+   * between them, as a Q64.64 price.
+   */
+  async observe(poolAddress: PublicKey, secondsAgos: number[]): Promise<any[]> {
+    return await this.program.methods
+      .observe(secondsAgos)
+      .accounts({
+        pool: poolAddress,
+        observation: this.findObservationAddress(poolAddress),
+      })
+      .view();
+  }
+
+  /**
+   * Quote an exact-input swap by simulating the program's `quote_swap`, which
+   * runs the same `amm-math` curve and fee code as `swap`, so the quote
+   * matches execution against the current pool state exactly.
+   */
+  async quoteSwap(
+    poolAddress: PublicKey,
+    tokenAMint: PublicKey,
+    tokenBMint: PublicKey,
+    amountIn: number,
+    inputSide: "a" | "b"
+  ): Promise<any> {
+    return await this.program.methods
+      .quoteSwap(new BN(amountIn), inputSide === "a" ? { a: {} } : { b: {} })
+      .accounts({
+        pool: poolAddress,
+        tokenAMint,
+        tokenBMint,
+      })
+      .view();
+  }
+
+  /**
+   * Quote an exact-input swap offline against a fetched `AmmPool` account,
+   * through `amm-math` compiled to WebAssembly (`npm run build:math`), so a
+   * router can price many amounts without an RPC round trip each. The caller
+   * passes the fee rate in effect; `quoteSwap` stays the quote to use on a
+   * pool with a fee ramp or dynamic fee.
+   */
+  quoteSwapFromState(pool: any, amountIn: bigint, inputSide: "a" | "b", feeRateBps: bigint): bigint {
+    const [reserveIn, reserveOut] = inputSide === "a"
+      ? [pool.tokenAReserve, pool.tokenBReserve]
+      : [pool.tokenBReserve, pool.tokenAReserve];
+    let curve: SwapCurve;
+    if (pool.curve.weighted) {
+      const { weightABps, weightBBps } = pool.curve.weighted;
+      curve = inputSide === "a"
+        ? SwapCurve.weighted(BigInt(weightABps.toString()), BigInt(weightBBps.toString()))
+        : SwapCurve.weighted(BigInt(weightBBps.toString()), BigInt(weightABps.toString()));
+    } else if (pool.curve.stableSwap) {
+      curve = SwapCurve.stableSwap(BigInt(pool.curve.stableSwap.amp.toString()));
+    } else {
+      curve = SwapCurve.constantProduct();
+    }
+    return curve.outputAfterFee(BigInt(reserveIn.toString()), BigInt(reserveOut.toString()), amountIn, feeRateBps);
+  }
+
   /**
    * Add a transfer hook program to the whitelist
    */
@@ -65,12 +245,55 @@ export class Token2022AmmSDK {
   }
 
   /**
-   * Execute a swap
+   * Queue a hook addition or removal on a timelocked whitelist
+   */
+  async queueHookChange(
+    whitelistAddress: PublicKey,
+    hookProgramId: PublicKey,
+    kind: "add" | "remove",
+    authority: web3.Keypair
+  ): Promise<string> {
+    const tx = await this.program.methods
+      .queueHookChange(kind === "add" ? { add: {} } : { remove: {} }, hookProgramId)
+      .accounts({
+        whitelist: whitelistAddress,
+        authority: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+
+    return tx;
+  }
+
+  /**
+   * Apply a queued hook change once its timelock has elapsed (permissionless)
+   */
+  async finalizeHookChange(
+    whitelistAddress: PublicKey,
+    hookProgramId: PublicKey
+  ): Promise<string> {
+    const tx = await this.program.methods
+      .finalizeHookChange(hookProgramId)
+      .accounts({
+        whitelist: whitelistAddress,
+      })
+      .rpc();
+
+    return tx;
+  }
+
+  /**
+   * Execute a swap. `inputSide` selects the direction: tokens enter the
+   * pool on that side and leave on the other. `tokenAProgram` and
+   * `tokenBProgram` are the programs owning each mint (SPL Token or Token-2022).
+   * `maxPriceImpactBps`, if given, rejects swaps moving the pool price further.
+   * The swap is rejected once `deadline` (unix seconds) has passed.
    */
   async swap(
     poolAddress: PublicKey,
     amountIn: number,
     minAmountOut: number,
+    inputSide: "a" | "b",
     userTokenAAccount: PublicKey,
     userTokenBAccount: PublicKey,
     poolTokenAVault: PublicKey,
@@ -78,22 +301,34 @@ export class Token2022AmmSDK {
     tokenAMint: PublicKey,
     tokenBMint: PublicKey,
     whitelistAddress: PublicKey,
-    user: web3.Keypair
+    user: web3.Keypair,
+    tokenAProgram: PublicKey = TOKEN_2022_PROGRAM_ID,
+    tokenBProgram: PublicKey = TOKEN_2022_PROGRAM_ID,
+    maxPriceImpactBps: number | null = null,
+    deadline: number = defaultDeadline()
   ): Promise<string> {
     const tx = await this.program.methods
-      .swap(new BN(amountIn), new BN(minAmountOut))
+      .swap(
+        new BN(amountIn),
+        new BN(minAmountOut),
+        inputSide === "a" ? { a: {} } : { b: {} },
+        maxPriceImpactBps,
+        new BN(deadline)
+      )
       .accounts({
         pool: poolAddress,
         user: user.publicKey,
         userTokenA: userTokenAAccount,
         userTokenB: userTokenBAccount,
+        vaultAuthority: this.findVaultAuthorityAddress(poolAddress),
         poolTokenAVault: poolTokenAVault,
         poolTokenBVault: poolTokenBVault,
         tokenAMint: tokenAMint,
         tokenBMint: tokenBMint,
+        ammConfig: this.findAmmConfigAddress(),
         whitelist: whitelistAddress,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        token2022Program: TOKEN_2022_PROGRAM_ID,
+        tokenAProgram: tokenAProgram,
+        tokenBProgram: tokenBProgram,
       })
       .signers([user])
       .rpc();
@@ -102,12 +337,14 @@ export class Token2022AmmSDK {
   }
 
   /**
-   * Add liquidity to a pool
+   * Add liquidity to a pool. Only the amounts matching the pool ratio are
+   * taken; the excess of an imbalanced add stays with the user. Pass
+   * TOKEN_PROGRAM_ID as `lpTokenProgram` for pools with a legacy SPL LP mint.
    */
   async addLiquidity(
     poolAddress: PublicKey,
-    amountA: number,
-    amountB: number,
+    maxAmountA: number,
+    maxAmountB: number,
     minLpTokens: number,
     userTokenAAccount: PublicKey,
     userTokenBAccount: PublicKey,
@@ -118,13 +355,18 @@ export class Token2022AmmSDK {
     tokenAMint: PublicKey,
     tokenBMint: PublicKey,
     whitelistAddress: PublicKey,
-    user: web3.Keypair
+    user: web3.Keypair,
+    tokenAProgram: PublicKey = TOKEN_2022_PROGRAM_ID,
+    tokenBProgram: PublicKey = TOKEN_2022_PROGRAM_ID,
+    lpTokenProgram: PublicKey = TOKEN_2022_PROGRAM_ID,
+    deadline: number = defaultDeadline()
   ): Promise<string> {
     const tx = await this.program.methods
       .addLiquidity(
-        new BN(amountA),
-        new BN(amountB),
-        new BN(minLpTokens)
+        new BN(maxAmountA),
+        new BN(maxAmountB),
+        new BN(minLpTokens),
+        new BN(deadline)
       )
       .accounts({
         pool: poolAddress,
@@ -138,8 +380,9 @@ export class Token2022AmmSDK {
         tokenAMint: tokenAMint,
         tokenBMint: tokenBMint,
         whitelist: whitelistAddress,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        token2022Program: TOKEN_2022_PROGRAM_ID,
+        tokenProgram: lpTokenProgram,
+        tokenAProgram: tokenAProgram,
+        tokenBProgram: tokenBProgram,
       })
       .signers([user])
       .rpc();
@@ -164,13 +407,18 @@ export class Token2022AmmSDK {
     tokenAMint: PublicKey,
     tokenBMint: PublicKey,
     whitelistAddress: PublicKey,
-    user: web3.Keypair
+    user: web3.Keypair,
+    tokenAProgram: PublicKey = TOKEN_2022_PROGRAM_ID,
+    tokenBProgram: PublicKey = TOKEN_2022_PROGRAM_ID,
+    lpTokenProgram: PublicKey = TOKEN_2022_PROGRAM_ID,
+    deadline: number = defaultDeadline()
   ): Promise<string> {
     const tx = await this.program.methods
       .removeLiquidity(
         new BN(lpTokensToBurn),
         new BN(minTokenA),
-        new BN(minTokenB)
+        new BN(minTokenB),
+        new BN(deadline)
       )
       .accounts({
         pool: poolAddress,
@@ -178,14 +426,17 @@ export class Token2022AmmSDK {
         userTokenA: userTokenAAccount,
         userTokenB: userTokenBAccount,
         userLpToken: userLpTokenAccount,
+        vaultAuthority: this.findVaultAuthorityAddress(poolAddress),
         poolTokenAVault: poolTokenAVault,
         poolTokenBVault: poolTokenBVault,
         lpMint: lpMint,
         tokenAMint: tokenAMint,
         tokenBMint: tokenBMint,
+        ammConfig: this.findAmmConfigAddress(),
         whitelist: whitelistAddress,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        token2022Program: TOKEN_2022_PROGRAM_ID,
+        tokenProgram: lpTokenProgram,
+        tokenAProgram: tokenAProgram,
+        tokenBProgram: tokenBProgram,
       })
       .signers([user])
       .rpc();
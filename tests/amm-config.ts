@@ -0,0 +1,69 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { AmmEnv, createTestPool, expectRejected, setupAmm } from "./helpers";
+
+// The config authority picks the fee tiers pools may use, the default
+// protocol fee share and the whitelist; this suite restores the defaults
+
+describe("AMM config", () => {
+  let env: AmmEnv;
+
+  const updateConfig = (feeTiers: number[], protocolFeeShareBps: number, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .updateAmmConfig(feeTiers.map((tier) => new BN(tier)), new BN(protocolFeeShareBps))
+      .accounts({ ammConfig: env.ammConfig, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const setWhitelist = (whitelist: PublicKey, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .setAmmConfigWhitelist()
+      .accounts({ ammConfig: env.ammConfig, authority: authority.publicKey, whitelist })
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+  });
+
+  after(async () => {
+    await updateConfig([1, 5, 30, 100], 0);
+    await setWhitelist(env.whitelist);
+  });
+
+  it("refuses empty or out-of-range fee tiers and oversized protocol shares", async () => {
+    await expectRejected(updateConfig([], 0), "InvalidFeeTier");
+    await expectRejected(updateConfig([30, 10_000], 0), "InvalidFeeTier");
+    await expectRejected(updateConfig([1, 5, 30, 100], 5_001), "InvalidProtocolFeeShare");
+    await expectRejected(updateConfig([1, 5, 30, 100], 0, Keypair.generate()), "InvalidConfigAuthority");
+  });
+
+  it("only lets pools use the tiers the config offers", async () => {
+    await expectRejected(createTestPool(env, { feeTier: 42 }), "InvalidFeeTier");
+    await updateConfig([1, 5, 30, 42, 100], 1_000);
+
+    const config = await env.program.account.ammConfig.fetch(env.ammConfig);
+    expect(config.feeTierCount).to.equal(5);
+    expect(config.protocolFeeShareBps.toNumber()).to.equal(1_000);
+    await createTestPool(env, { feeTier: 42 });
+  });
+
+  it("points the config at a replacement whitelist", async () => {
+    const replacement = Keypair.generate();
+    await env.program.methods
+      .initializeWhitelist()
+      .accounts({
+        whitelist: replacement.publicKey,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([replacement])
+      .rpc();
+
+    await expectRejected(setWhitelist(replacement.publicKey, Keypair.generate()), "InvalidConfigAuthority");
+    await setWhitelist(replacement.publicKey);
+    const config = await env.program.account.ammConfig.fetch(env.ammConfig);
+    expect(config.whitelist.equals(replacement.publicKey)).to.be.true;
+  });
+});
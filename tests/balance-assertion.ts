@@ -0,0 +1,71 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// A client-composed transaction snapshots a token account, runs its swaps and
+// asserts the balance moved within bounds, all in the same slot
+
+describe("balance change assertions", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let snapshot: PublicKey;
+
+  const snapshotIx = () =>
+    env.program.methods
+      .snapshotBalance()
+      .accounts({
+        snapshot,
+        owner: user.keypair.publicKey,
+        tokenAccount: user.tokenB,
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+
+  const assertIx = (minDelta: number, maxDelta: number) =>
+    env.program.methods
+      .assertBalanceChange(new BN(minDelta), new BN(maxDelta))
+      .accounts({ snapshot, owner: user.keypair.publicKey, tokenAccount: user.tokenB })
+      .instruction();
+
+  const guardedSwap = async (minDelta: number, maxDelta: number) =>
+    env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .preInstructions([await snapshotIx()])
+      .postInstructions([await assertIx(minDelta, maxDelta)])
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+    [snapshot] = PublicKey.findProgramAddressSync(
+      [Buffer.from("balance_snapshot"), user.tokenB.toBuffer(), user.keypair.publicKey.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  it("passes a swap whose output lands within the bounds and closes the snapshot", async () => {
+    await guardedSwap(900_000, 1_000_000);
+    expect(await env.provider.connection.getAccountInfo(snapshot)).to.be.null;
+  });
+
+  it("reverts the whole transaction when the output falls short", async () => {
+    await expectRejected(guardedSwap(2_000_000, 3_000_000), "BalanceChangeOutOfRange");
+  });
+});
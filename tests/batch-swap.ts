@@ -0,0 +1,138 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// A batch swap runs unrelated swaps on A/B and C/D in one transaction; each
+// leg has its own minimum and any leg falling short undoes them all
+
+describe("batch swaps", () => {
+  let env: AmmEnv;
+  let first: TestPool;
+  let second: TestPool;
+  let trader: TestUser;
+
+  const tokenAccount = (mint: PublicKey) =>
+    getAssociatedTokenAddressSync(mint, trader.keypair.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  // One leg's accounts, laid out as `BATCH_LEG_ACCOUNTS`: the input account,
+  // mint and program, then a route hop
+  const leg = (pool: TestPool) => {
+    const account = (pubkey: PublicKey, isWritable: boolean) => ({ pubkey, isSigner: false, isWritable });
+    return [
+      account(tokenAccount(pool.tokenAMint), true),
+      account(pool.tokenAMint, false),
+      account(TOKEN_2022_PROGRAM_ID, false),
+      account(pool.pool, true),
+      account(pool.vaultAuthority, false),
+      account(pool.poolTokenAVault, true),
+      account(pool.poolTokenBVault, true),
+      account(pool.tokenBMint, false),
+      account(TOKEN_2022_PROGRAM_ID, false),
+      account(tokenAccount(pool.tokenBMint), true),
+      // No observation or protocol fee vault on either pool
+      account(env.program.programId, false),
+      account(env.program.programId, false),
+    ];
+  };
+
+  const batchSwap = (legs: { amountIn: BN; minAmountOut: BN }[], pools: TestPool[]) =>
+    env.program.methods
+      .batchSwap(legs, deadline())
+      .accounts({
+        user: trader.keypair.publicKey,
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        takerAllowlist: null,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(pools.flatMap(leg))
+      .signers([trader.keypair])
+      .rpc();
+
+  const quote = async (pool: TestPool, amountIn: number) =>
+    (
+      await env.program.methods
+        .quoteRoutes(new BN(amountIn), pool.tokenAMint, pool.tokenBMint, Buffer.from([1]))
+        .accounts({ outputTokenMint: pool.tokenBMint })
+        .remainingAccounts([{ pubkey: pool.pool, isSigner: false, isWritable: false }])
+        .view()
+    ).bestAmountOut;
+
+  before(async () => {
+    env = await setupAmm();
+    const mints: PublicKey[] = [];
+    for (let i = 0; i < 4; i++) {
+      mints.push(await createTestMint(env));
+    }
+    first = await createTestPool(env, { mints: [mints[0], mints[1]] });
+    second = await createTestPool(env, { mints: [mints[2], mints[3]] });
+    await addLiquidity(env, first, await createTestUser(env, first), 100_000_000);
+    await addLiquidity(env, second, await createTestUser(env, second), 100_000_000);
+
+    // Holds A, B and C; the batch creates the D account on the way
+    trader = await createTestUser(env, first);
+    const connection = env.provider.connection;
+    const accountC = await getOrCreateAssociatedTokenAccount(
+      connection, env.payer.payer, second.tokenAMint, trader.keypair.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(connection, env.payer.payer, second.tokenAMint, accountC.address, env.payer.publicKey, 1_000_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+  });
+
+  it("needs at least one leg", async () => {
+    await expectRejected(batchSwap([], []), "InvalidRoute");
+  });
+
+  it("undoes every leg when one misses its minimum", async () => {
+    const quoteD = await quote(second, 2_000_000);
+    const beforeA = await tokenBalance(env.provider.connection, tokenAccount(first.tokenAMint));
+    await expectRejected(
+      batchSwap(
+        [
+          { amountIn: new BN(1_000_000), minAmountOut: new BN(1) },
+          { amountIn: new BN(2_000_000), minAmountOut: quoteD.addn(1) },
+        ],
+        [first, second]
+      ),
+      "InsufficientOutputAmount"
+    );
+    expect(await tokenBalance(env.provider.connection, tokenAccount(first.tokenAMint))).to.equal(beforeA);
+  });
+
+  it("pays each leg what a swap on its own pool would", async () => {
+    const [quoteB, quoteD] = [await quote(first, 1_000_000), await quote(second, 2_000_000)];
+    const connection = env.provider.connection;
+    const beforeB = await tokenBalance(connection, tokenAccount(first.tokenBMint));
+    await batchSwap(
+      [
+        { amountIn: new BN(1_000_000), minAmountOut: quoteB },
+        { amountIn: new BN(2_000_000), minAmountOut: quoteD },
+      ],
+      [first, second]
+    );
+
+    const receivedB = (await tokenBalance(connection, tokenAccount(first.tokenBMint))) - beforeB;
+    expect(receivedB.toString()).to.equal(quoteB.toString());
+    expect((await tokenBalance(connection, tokenAccount(second.tokenBMint))).toString()).to.equal(quoteD.toString());
+  });
+});
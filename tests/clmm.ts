@@ -0,0 +1,258 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import { AmmEnv, createTestMint, deadline, expectRejected, setupAmm, tokenBalance } from "./helpers";
+
+// A concentrated liquidity pool starts at parity with one position over
+// [-600, 600); swaps walk its ticks across tick arrays and the position
+// withdraws what it backs plus the fees it earned
+
+const FEE_TIER = 30;
+// Tick spacing 60 for the 30 bps tier, 32 ticks to an array
+const TICK_ARRAY_SPAN = 1_920;
+const TICK_LOWER = -600;
+const TICK_UPPER = 600;
+
+describe("concentrated liquidity pools", () => {
+  let env: AmmEnv;
+  let pool: PublicKey;
+  let vaultAuthority: PublicKey;
+  let position: PublicKey;
+  let tokenAMint: PublicKey;
+  let tokenBMint: PublicKey;
+  const vaultA = Keypair.generate();
+  const vaultB = Keypair.generate();
+  const owner = Keypair.generate();
+  let ownerTokenA: PublicKey;
+  let ownerTokenB: PublicKey;
+  const liquidity = new BN(1_000_000_000);
+
+  const tickBytes = (tick: number) => new BN(tick).toTwos(32).toArrayLike(Buffer, "le", 4);
+
+  const tickArrayAddress = (startTickIndex: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("tick_array"), pool.toBuffer(), tickBytes(startTickIndex)],
+      env.program.programId
+    )[0];
+
+  const tokenAccounts = () => ({
+    userTokenA: ownerTokenA,
+    userTokenB: ownerTokenB,
+    vaultAuthority,
+    poolTokenAVault: vaultA.publicKey,
+    poolTokenBVault: vaultB.publicKey,
+    tokenAMint,
+    tokenBMint,
+    ammConfig: env.ammConfig,
+    whitelist: env.whitelist,
+    tokenAProgram: TOKEN_2022_PROGRAM_ID,
+    tokenBProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  const positionAccounts = () => ({
+    pool,
+    position,
+    owner: owner.publicKey,
+    tickArrayLower: tickArrayAddress(-TICK_ARRAY_SPAN),
+    tickArrayUpper: tickArrayAddress(0),
+    ...tokenAccounts(),
+  });
+
+  const swap = (amountIn: number, tickArrays: PublicKey[], sqrtPriceLimit: BN | null = null, swapDeadline = deadline()) =>
+    env.program.methods
+      .clmmSwap(new BN(amountIn), new BN(1), sqrtPriceLimit, { a: {} }, tickArrays.length, swapDeadline)
+      .accounts({ pool, user: owner.publicKey, ...tokenAccounts() })
+      .remainingAccounts(tickArrays.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .signers([owner])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    [tokenAMint, tokenBMint] = [await createTestMint(env), await createTestMint(env)].sort((a, b) =>
+      Buffer.compare(a.toBuffer(), b.toBuffer())
+    );
+    [pool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("clmm_pool"), tokenAMint.toBuffer(), tokenBMint.toBuffer(), new BN(FEE_TIER).toArrayLike(Buffer, "le", 8)],
+      env.program.programId
+    );
+    [vaultAuthority] = PublicKey.findProgramAddressSync(
+      [pool.toBuffer(), Buffer.from("vault_auth")],
+      env.program.programId
+    );
+    [position] = PublicKey.findProgramAddressSync(
+      [Buffer.from("clmm_position"), pool.toBuffer(), owner.publicKey.toBuffer(), tickBytes(TICK_LOWER), tickBytes(TICK_UPPER)],
+      env.program.programId
+    );
+
+    const connection = env.provider.connection;
+    const airdrop = await connection.requestAirdrop(owner.publicKey, 2_000_000_000);
+    await connection.confirmTransaction(airdrop, "confirmed");
+    [ownerTokenA, ownerTokenB] = await Promise.all(
+      [tokenAMint, tokenBMint].map(async (mint) => {
+        const account = await getOrCreateAssociatedTokenAccount(
+          connection, env.payer.payer, mint, owner.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID
+        );
+        await mintTo(connection, env.payer.payer, mint, account.address, env.payer.publicKey, 1_000_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+        return account.address;
+      })
+    );
+  });
+
+  it("opens at the initial price with tick arrays either side of it", async () => {
+    await env.program.methods
+      .initializeClmmPool(new BN(FEE_TIER), new BN(1).shln(64))
+      .accounts({
+        pool,
+        authority: env.payer.publicKey,
+        ammConfig: env.ammConfig,
+        tokenAMint,
+        tokenBMint,
+        vaultAuthority,
+        tokenAVault: vaultA.publicKey,
+        tokenBVault: vaultB.publicKey,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([vaultA, vaultB])
+      .rpc();
+
+    const state = await env.program.account.clmmPool.fetch(pool);
+    expect(state.tickCurrent).to.equal(0);
+    expect(state.tickSpacing).to.equal(60);
+
+    for (const startTickIndex of [-TICK_ARRAY_SPAN, 0]) {
+      await env.program.methods
+        .initializeTickArray(startTickIndex)
+        .accounts({
+          pool,
+          tickArray: tickArrayAddress(startTickIndex),
+          payer: env.payer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+    await expectRejected(
+      env.program.methods
+        .initializeTickArray(100)
+        .accounts({ pool, tickArray: tickArrayAddress(100), payer: env.payer.publicKey, systemProgram: SystemProgram.programId })
+        .rpc(),
+      "InvalidTickArray"
+    );
+  });
+
+  it("funds a position with both tokens while the price is in range", async () => {
+    await env.program.methods
+      .openPosition(TICK_LOWER, TICK_UPPER)
+      .accounts({ pool, position, owner: owner.publicKey, systemProgram: SystemProgram.programId })
+      .signers([owner])
+      .rpc();
+
+    const increase = (delta: BN, maxAmount: number) =>
+      env.program.methods
+        .increaseLiquidity(delta, new BN(maxAmount), new BN(maxAmount), deadline())
+        .accounts(positionAccounts())
+        .signers([owner])
+        .rpc();
+    await expectRejected(increase(new BN(0), 100_000_000), "InvalidAmount");
+    await expectRejected(increase(liquidity, 1_000_000), "ExcessiveInputAmount");
+    await increase(liquidity, 100_000_000);
+
+    // sqrt(1.0001^600) puts about 2.96% of the liquidity on each side
+    const connection = env.provider.connection;
+    const vaultABalance = await tokenBalance(connection, vaultA.publicKey);
+    const vaultBBalance = await tokenBalance(connection, vaultB.publicKey);
+    expect(vaultABalance > BigInt(29_000_000) && vaultABalance < BigInt(30_000_000)).to.be.true;
+    expect(vaultBBalance > BigInt(29_000_000) && vaultBBalance < BigInt(30_000_000)).to.be.true;
+    expect((await env.program.account.clmmPool.fetch(pool)).liquidity.eq(liquidity)).to.be.true;
+  });
+
+  it("walks down into the next tick array on a token A swap", async () => {
+    const parity = new BN(1).shln(64);
+    await expectRejected(swap(1_000_000, [tickArrayAddress(0)], parity.addn(1)), "InvalidSqrtPriceLimit");
+    // The price leaves the array at tick 0 as soon as it moves
+    await expectRejected(swap(1_000_000, [tickArrayAddress(0)]), "MissingTickArray");
+
+    const connection = env.provider.connection;
+    const before = await tokenBalance(connection, ownerTokenB);
+    await swap(1_000_000, [tickArrayAddress(0), tickArrayAddress(-TICK_ARRAY_SPAN)]);
+    const amountOut = (await tokenBalance(connection, ownerTokenB)) - before;
+
+    // 30 bps fee plus a small move along the curve
+    expect(amountOut > BigInt(990_000) && amountOut < BigInt(997_000)).to.be.true;
+    const state = await env.program.account.clmmPool.fetch(pool);
+    expect(state.tickCurrent).to.be.lessThan(0);
+    expect(state.sqrtPriceX64.lt(parity)).to.be.true;
+  });
+
+  it("refuses expired swaps and any trading while the pool is paused", async () => {
+    const tickArrays = [tickArrayAddress(0), tickArrayAddress(-TICK_ARRAY_SPAN)];
+    await expectRejected(swap(1_000, tickArrays, null, new BN(Math.floor(Date.now() / 1000) - 60)), "DeadlineExceeded");
+
+    const setPaused = (paused: boolean) =>
+      (paused ? env.program.methods.pauseClmmPool() : env.program.methods.unpauseClmmPool())
+        .accounts({ globalConfig: env.globalConfig, pool, emergencyAuthority: env.payer.publicKey })
+        .rpc();
+    await setPaused(true);
+    await expectRejected(swap(1_000, tickArrays), "PoolPaused");
+    await expectRejected(
+      env.program.methods
+        .increaseLiquidity(new BN(1_000), new BN(1_000_000), new BN(1_000_000), deadline())
+        .accounts(positionAccounts())
+        .signers([owner])
+        .rpc(),
+      "PoolPaused"
+    );
+    await setPaused(false);
+  });
+
+  it("validates hooks against its own whitelist while it has one", async () => {
+    const [poolWhitelist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_whitelist"), pool.toBuffer()],
+      env.program.programId
+    );
+    await env.program.methods
+      .enableClmmPoolWhitelist([])
+      .accounts({ pool, poolWhitelist, authority: env.payer.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+    expect((await env.program.account.clmmPool.fetch(pool)).hookWhitelist.equals(poolWhitelist)).to.be.true;
+    await expectRejected(swap(1_000, [tickArrayAddress(0), tickArrayAddress(-TICK_ARRAY_SPAN)]), "WhitelistMismatch");
+
+    await env.program.methods
+      .disableClmmPoolWhitelist()
+      .accounts({ pool, poolWhitelist, authority: env.payer.publicKey })
+      .rpc();
+    expect((await env.program.account.clmmPool.fetch(pool)).hookWhitelist.equals(PublicKey.default)).to.be.true;
+  });
+
+  it("collects the fees earned in range without touching the liquidity", async () => {
+    const connection = env.provider.connection;
+    const [beforeA, beforeB] = [await tokenBalance(connection, ownerTokenA), await tokenBalance(connection, ownerTokenB)];
+    await env.program.methods.collectClmmFees().accounts(positionAccounts()).signers([owner]).rpc();
+
+    // The only position in range earns the whole 30 bps fee, up to rounding
+    const feesA = (await tokenBalance(connection, ownerTokenA)) - beforeA;
+    expect(feesA >= BigInt(2_990) && feesA <= BigInt(3_010)).to.be.true;
+    expect(await tokenBalance(connection, ownerTokenB)).to.equal(beforeB);
+    const state = await env.program.account.clmmPosition.fetch(position);
+    expect(state.feesOwedA.isZero()).to.be.true;
+    expect(state.liquidity.eq(liquidity)).to.be.true;
+  });
+
+  it("returns the position's tokens when its liquidity is removed", async () => {
+    const connection = env.provider.connection;
+    const beforeA = await tokenBalance(connection, ownerTokenA);
+    await env.program.methods
+      .decreaseLiquidity(liquidity, new BN(0), new BN(0), deadline())
+      .accounts(positionAccounts())
+      .signers([owner])
+      .rpc();
+
+    // The sole position owns everything left in the vaults but rounding dust
+    expect((await tokenBalance(connection, ownerTokenA)) - beforeA > BigInt(30_000_000)).to.be.true;
+    expect((await env.program.account.clmmPosition.fetch(position)).liquidity.isZero()).to.be.true;
+    expect((await env.program.account.clmmPool.fetch(pool)).liquidity.isZero()).to.be.true;
+  });
+});
@@ -23,6 +23,11 @@ import {
 } from "@solana/spl-token";
 import { expect } from "chai";
 import { createSimulationService, SimulationResult } from "../scripts/simulation-service";
+import { AmmEnv, setupAmm } from "./helpers";
+
+// Votes close after this many seconds; the suite's governance rules are set
+// through the `test-utils` build's `set_governance_params`
+const VOTING_PERIOD_SECONDS = 3;
 
 describe("Comprehensive Token-2022 AMM Integration Tests", () => {
   const provider = anchor.AnchorProvider.env();
@@ -43,6 +48,8 @@ describe("Comprehensive Token-2022 AMM Integration Tests", () => {
   let ammPool: Keypair;
   let proposalAccount: Keypair;
   let proxyAccount: Keypair;
+  let proposedHook: Keypair;
+  let env: AmmEnv;
 
   // Token accounts
   let userATokenA: PublicKey;
@@ -73,6 +80,36 @@ describe("Comprehensive Token-2022 AMM Integration Tests", () => {
 
     // Initialize simulation service
     simulationService = createSimulationService(connection, ammProgram, provider);
+
+    // Governance with a voting period of seconds and stakes a test wallet can
+    // afford, so proposals can be finalized within the suite
+    env = await setupAmm();
+    const [governanceConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("governance_config")],
+      ammProgram.programId
+    );
+    if (!(await connection.getAccountInfo(governanceConfig))) {
+      await ammProgram.methods
+        .initializeGovernanceConfig([], 0)
+        .accounts({
+          governanceConfig,
+          ammConfig: env.ammConfig,
+          authority: payer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+    await ammProgram.methods
+      .setGovernanceParams({
+        votingPeriod: new anchor.BN(VOTING_PERIOD_SECONDS),
+        quorumStake: new anchor.BN(LAMPORTS_PER_SOL),
+        minApproveStake: new anchor.BN(LAMPORTS_PER_SOL),
+        approvalThresholdBps: new anchor.BN(6_000),
+        minProposerStake: new anchor.BN(LAMPORTS_PER_SOL),
+        vetoWindow: new anchor.BN(0),
+      })
+      .accounts({ governanceConfig, ammConfig: env.ammConfig, authority: payer.publicKey })
+      .rpc();
   });
 
   describe("1. Core AMM Functionality", () => {
@@ -187,11 +224,11 @@ describe("Comprehensive Token-2022 AMM Integration Tests", () => {
 
   describe("2. Governance System", () => {
     it("Should create a hook proposal", async () => {
-      const newHookProgram = Keypair.generate();
+      proposedHook = Keypair.generate();
       
       const tx = await ammProgram.methods
         .createHookProposal(
-          newHookProgram.publicKey,
+          proposedHook.publicKey,
           "Test Hook Proposal",
           "https://audit.example.com/report.pdf",
           new anchor.BN(10 * LAMPORTS_PER_SOL) // 10 SOL stake
@@ -200,6 +237,15 @@ describe("Comprehensive Token-2022 AMM Integration Tests", () => {
           proposal: proposalAccount.publicKey,
           proposer: payer.publicKey,
           whitelist: whitelistAccount.publicKey,
+          dependency: null,
+          stakeVault: PublicKey.findProgramAddressSync(
+            [Buffer.from("stake_vault"), proposalAccount.publicKey.toBuffer()],
+            ammProgram.programId
+          )[0],
+          governanceConfig: PublicKey.findProgramAddressSync(
+            [Buffer.from("governance_config")],
+            ammProgram.programId
+          )[0],
           systemProgram: SystemProgram.programId,
         })
         .signers([proposalAccount])
@@ -209,12 +255,23 @@ describe("Comprehensive Token-2022 AMM Integration Tests", () => {
     });
 
     it("Should vote on proposal", async () => {
+      const [stakeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake_vault"), proposalAccount.publicKey.toBuffer()],
+        ammProgram.programId
+      );
+
       const tx = await ammProgram.methods
-        .voteOnProposal(true, new anchor.BN(5 * LAMPORTS_PER_SOL)) // Approve with 5 SOL stake
+        .voteOnProposal(true, new anchor.BN(LAMPORTS_PER_SOL)) // Approve with 1 SOL stake
         .accounts({
           proposal: proposalAccount.publicKey,
           voter: userA.publicKey,
           whitelist: whitelistAccount.publicKey,
+          voteRecord: PublicKey.findProgramAddressSync(
+            [proposalAccount.publicKey.toBuffer(), userA.publicKey.toBuffer()],
+            ammProgram.programId
+          )[0],
+          stakeVault,
+          systemProgram: SystemProgram.programId,
         })
         .signers([userA])
         .rpc();
@@ -223,18 +280,34 @@ describe("Comprehensive Token-2022 AMM Integration Tests", () => {
     });
 
     it("Should execute approved proposal", async () => {
-      // First finalize the proposal
-      // Note: In a real scenario, you'd wait for the voting period to end
+      // Finalizing is only allowed once the voting period has ended
+      await new Promise(resolve => setTimeout(resolve, (VOTING_PERIOD_SECONDS + 1) * 1000));
+      await ammProgram.methods
+        .finalizeProposal()
+        .accounts({
+          proposal: proposalAccount.publicKey,
+          governanceConfig: PublicKey.findProgramAddressSync(
+            [Buffer.from("governance_config")],
+            ammProgram.programId
+          )[0],
+        })
+        .rpc();
       
       const tx = await ammProgram.methods
         .executeProposal()
         .accounts({
           proposal: proposalAccount.publicKey,
-          whitelist: whitelistAccount.publicKey,
-          authority: payer.publicKey,
+          ammConfig: PublicKey.findProgramAddressSync(
+            [Buffer.from("amm_config")],
+            ammProgram.programId
+          )[0],
+          whitelist: env.whitelist,
+          dependency: null,
         })
         .rpc();
 
+      const whitelist = await ammProgram.account.transferHookWhitelist.fetch(env.whitelist);
+      expect(whitelist.whitelistedHooks.some((hook) => hook.equals(proposedHook.publicKey))).to.be.true;
       console.log("✅ Proposal executed:", tx);
     });
   });
@@ -408,10 +481,15 @@ describe("Comprehensive Token-2022 AMM Integration Tests", () => {
               await ammProgram.methods
                 .executeProposal()
                 .accounts({
-                  proposal: PublicKey.default,
+                  proposal: PublicKey.default, // Not an approved proposal
+                  ammConfig: PublicKey.findProgramAddressSync(
+                    [Buffer.from("amm_config")],
+                    ammProgram.programId
+                  )[0],
                   whitelist: whitelistAccount.publicKey,
-                  authority: userA.publicKey, // Not the authority
+                  dependency: null,
                 })
+                .signers([userA])
                 .rpc();
               return false; // Should fail
             } catch {
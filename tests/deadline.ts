@@ -0,0 +1,60 @@
+import { BN } from "@coral-xyz/anchor";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// Swaps and liquidity changes carry a deadline so a transaction that sits in
+// a retry queue cannot land later at a stale price
+
+describe("deadlines", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  const expired = () => new BN(Math.floor(Date.now() / 1000) - 60);
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("refuses an expired swap", async () => {
+    await expectRejected(
+      env.program.methods
+        .swap(new BN(1_000_000), new BN(1), { a: {} }, null, expired())
+        .accounts(swapAccounts(env, pool, user))
+        .signers([user.keypair])
+        .rpc(),
+      "DeadlineExceeded"
+    );
+  });
+
+  it("refuses expired deposits and withdrawals", async () => {
+    await expectRejected(
+      env.program.methods
+        .addLiquidity(new BN(1_000_000), new BN(1_000_000), new BN(0), expired())
+        .accounts(liquidityAccounts(env, pool, user))
+        .signers([user.keypair])
+        .rpc(),
+      "DeadlineExceeded"
+    );
+    await expectRejected(
+      env.program.methods
+        .removeLiquidity(new BN(1_000_000), new BN(0), new BN(0), expired())
+        .accounts(liquidityAccounts(env, pool, user))
+        .signers([user.keypair])
+        .rpc(),
+      "DeadlineExceeded"
+    );
+  });
+});
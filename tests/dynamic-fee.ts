@@ -0,0 +1,85 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// A dynamic fee moves between its bounds with how far the spot price has
+// strayed from the TWAP, so a sudden price shock makes the next trade pay
+// the most
+
+describe("dynamic fees", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let observation: PublicKey;
+
+  const setDynamicFee = (minFeeRate: number, maxFeeRate: number, maxVolatilityBps: number, window: number, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .setDynamicFee(new BN(minFeeRate), new BN(maxFeeRate), new BN(maxVolatilityBps), window)
+      .accounts({ pool: pool.pool, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const swap = (amountIn: number) =>
+    env.program.methods
+      .swap(new BN(amountIn), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user, { observation }))
+      .signers([user.keypair])
+      .rpc();
+
+  const currentFeeRate = async () =>
+    (await env.program.account.ammPool.fetch(pool.pool)).dynamicFee.currentFeeRate.toNumber();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+    [observation] = PublicKey.findProgramAddressSync(
+      [Buffer.from("observation"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  it("needs an observation and ordered bounds", async () => {
+    await expectRejected(setDynamicFee(5, 100, 1_000, 60), "MissingObservation");
+    await env.program.methods
+      .initializeObservation()
+      .accounts({ pool: pool.pool, observation, payer: env.payer.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+
+    await expectRejected(setDynamicFee(100, 5, 1_000, 60), "InvalidDynamicFee");
+    await expectRejected(setDynamicFee(5, 100, 0, 60), "InvalidDynamicFee");
+    await expectRejected(setDynamicFee(5, 100, 1_000, 60, Keypair.generate()), "InvalidPoolAuthority");
+    await setDynamicFee(5, 100, 1_000, 60);
+    expect(await currentFeeRate()).to.equal(5);
+  });
+
+  it("charges the most right after a price shock", async () => {
+    await swap(100_000);
+    expect(await currentFeeRate()).to.equal(5);
+
+    // Build a few seconds of history at the old price, then knock the price
+    // down by about a third; the next swap sees spot far below the TWAP
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+    await swap(20_000_000);
+    await swap(100_000);
+    expect(await currentFeeRate()).to.equal(100);
+  });
+
+  it("switches off with a zero window", async () => {
+    await setDynamicFee(0, 0, 0, 0);
+    expect((await env.program.account.ammPool.fetch(pool.pool)).dynamicFee.window).to.equal(0);
+  });
+});
@@ -0,0 +1,91 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import { AmmEnv, createTestMint, expectRejected, setupAmm, tokenBalance } from "./helpers";
+
+// The emissions controller releases each completed epoch's emission to an
+// incentive group's reward vault, decaying it epoch over epoch
+
+describe("emissions", () => {
+  let env: AmmEnv;
+  let rewardMint: PublicKey;
+  let controller: PublicKey;
+  let rewardVault: Keypair;
+  let gaugeVault: Keypair;
+
+  const release = () =>
+    env.program.methods
+      .releaseEmissions()
+      .accounts({
+        controller,
+        rewardMint,
+        rewardVault: rewardVault.publicKey,
+        gaugeVault: gaugeVault.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    rewardMint = await createTestMint(env);
+
+    const groupId = new BN(Date.now());
+    const [gauge] = PublicKey.findProgramAddressSync(
+      [Buffer.from("incentive_group"), groupId.toArrayLike(Buffer, "le", 8)],
+      env.program.programId
+    );
+    gaugeVault = Keypair.generate();
+    await env.program.methods
+      .createIncentiveGroup(groupId, new BN(0))
+      .accounts({
+        globalConfig: env.globalConfig,
+        group: gauge,
+        authority: env.payer.publicKey,
+        rewardMint,
+        rewardVault: gaugeVault.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([gaugeVault])
+      .rpc();
+
+    // Started 15s ago with 10s epochs: one epoch has completed
+    [controller] = PublicKey.findProgramAddressSync(
+      [Buffer.from("emissions"), rewardMint.toBuffer()],
+      env.program.programId
+    );
+    rewardVault = Keypair.generate();
+    await env.program.methods
+      .initializeEmissions(new BN(Math.floor(Date.now() / 1000) - 15), new BN(10), new BN(1_000), new BN(5_000))
+      .accounts({
+        controller,
+        ammConfig: env.ammConfig,
+        authority: env.payer.publicKey,
+        rewardMint,
+        gauge,
+        rewardVault: rewardVault.publicKey,
+        gaugeVault: gaugeVault.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([rewardVault])
+      .rpc();
+    await mintTo(
+      env.provider.connection, env.payer.payer, rewardMint, rewardVault.publicKey, env.payer.publicKey, 1_000_000, [], undefined, TOKEN_2022_PROGRAM_ID
+    );
+  });
+
+  it("releases the completed epoch to the gauge and decays the next one", async () => {
+    await release();
+
+    expect((await tokenBalance(env.provider.connection, gaugeVault.publicKey)).toString()).to.equal("1000");
+    const state = await env.program.account.emissionsController.fetch(controller);
+    expect(state.nextEpoch.toNumber()).to.equal(1);
+    expect(state.nextEpochEmission.toNumber()).to.equal(500);
+  });
+
+  it("has nothing to release until the next epoch completes", async () => {
+    await expectRejected(release(), "NoEmissionsDue");
+  });
+});
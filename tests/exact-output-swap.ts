@@ -0,0 +1,79 @@
+import { BN } from "@coral-xyz/anchor";
+import { SystemProgram } from "@solana/web3.js";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// `swap_tokens_for_exact_tokens` delivers an exact output and charges the
+// input it costs, failing when that exceeds the caller's maximum
+
+describe("exact-output swaps", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const buyB = (amountOut: number, maxAmountIn: number) =>
+    env.program.methods
+      .swapTokensForExactTokens(new BN(amountOut), new BN(maxAmountIn), null, deadline())
+      .accounts({
+        pool: pool.pool,
+        user: user.keypair.publicKey,
+        userInputToken: user.tokenA,
+        userOutputToken: user.tokenB,
+        vaultAuthority: pool.vaultAuthority,
+        poolInputVault: pool.poolTokenAVault,
+        poolOutputVault: pool.poolTokenBVault,
+        inputMint: pool.tokenAMint,
+        outputMint: pool.tokenBMint,
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        inputHookCache: null,
+        outputHookCache: null,
+        takerAllowlist: null,
+        outputFailureLog: null,
+        observation: null,
+        protocolFeeVault: null,
+        inputTokenProgram: TOKEN_2022_PROGRAM_ID,
+        outputTokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("delivers exactly the requested output within the input cap", async () => {
+    const beforeA = await tokenBalance(env.provider.connection, user.tokenA);
+    const beforeB = await tokenBalance(env.provider.connection, user.tokenB);
+    await buyB(1_000_000, 2_000_000);
+
+    const spent = beforeA - (await tokenBalance(env.provider.connection, user.tokenA));
+    const received = (await tokenBalance(env.provider.connection, user.tokenB)) - beforeB;
+    expect(received.toString()).to.equal("1000000");
+    expect(spent > BigInt(1_000_000) && spent <= BigInt(2_000_000)).to.be.true;
+
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    expect(state.tokenBReserve.toString()).to.equal("99000000");
+  });
+
+  it("refuses an output that costs more than the maximum input", async () => {
+    await expectRejected(buyB(1_000_000, 1_000_000), "ExcessiveInputAmount");
+  });
+});
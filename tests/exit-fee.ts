@@ -0,0 +1,94 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// The LP exit fee decays with how long an LP position has held its
+// liquidity; withdrawals that no position tracks pay the full fee
+
+describe("LP exit fee", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let seeder: TestUser;
+  let holder: TestUser;
+  let lpPosition: PublicKey;
+  const decayPeriod = 3;
+
+  const setExitFee = (exitFeeBps: number, decay: number) =>
+    env.program.methods
+      .setExitFee(new BN(exitFeeBps), new BN(decay))
+      .accounts({ pool: pool.pool, authority: env.payer.publicKey })
+      .rpc();
+
+  // Token A the pool owes for `lpTokens` before any exit fee
+  const proRataA = async (lpTokens: number) => {
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    return state.tokenAReserve.muln(lpTokens).div(state.totalLpSupply);
+  };
+
+  const withdraw = async (user: TestUser, lpTokens: number, position: PublicKey | null) => {
+    const before = await tokenBalance(env.provider.connection, user.tokenA);
+    await env.program.methods
+      .removeLiquidity(new BN(lpTokens), new BN(0), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user, { lpPosition: position }))
+      .signers([user.keypair])
+      .rpc();
+    return new BN(((await tokenBalance(env.provider.connection, user.tokenA)) - before).toString());
+  };
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    seeder = await createTestUser(env, pool);
+    holder = await createTestUser(env, pool);
+    await addLiquidity(env, pool, seeder, 100_000_000);
+  });
+
+  it("refuses a fee above the cap or one that never decays", async () => {
+    await expectRejected(setExitFee(1_001, decayPeriod), "InvalidExitFee");
+    await expectRejected(setExitFee(100, 0), "InvalidExitFee");
+  });
+
+  it("charges untracked withdrawals the full fee and leaves it with the LPs", async () => {
+    await setExitFee(100, decayPeriod);
+    const owed = await proRataA(1_000_000);
+    const received = await withdraw(seeder, 1_000_000, null);
+    expect(received.eq(owed.sub(owed.muln(100).divn(10_000)))).to.be.true;
+  });
+
+  it("waives the fee once a position has held for the decay period", async () => {
+    [lpPosition] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_position"), pool.pool.toBuffer(), holder.keypair.publicKey.toBuffer()],
+      env.program.programId
+    );
+    await env.program.methods
+      .openLpPosition(new BN(0))
+      .accounts({ pool: pool.pool, lpPosition, owner: holder.keypair.publicKey, systemProgram: SystemProgram.programId })
+      .signers([holder.keypair])
+      .rpc();
+    await env.program.methods
+      .addLiquidity(new BN(10_000_000), new BN(10_000_000), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, holder, { lpPosition }))
+      .signers([holder.keypair])
+      .rpc();
+    const position = await env.program.account.lpPosition.fetch(lpPosition);
+    expect(position.lpAmount.toNumber()).to.be.greaterThan(0);
+    expect(position.entryTime.toNumber()).to.be.greaterThan(0);
+
+    await new Promise((resolve) => setTimeout(resolve, (decayPeriod + 2) * 1000));
+    const owed = await proRataA(1_000_000);
+    expect((await withdraw(holder, 1_000_000, lpPosition)).eq(owed)).to.be.true;
+  });
+});
@@ -0,0 +1,167 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  expectRejected,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// A pool has one farm per reward mint, so only the pool authority may
+// create it; anyone else would squat the PDA. Staked LP tokens earn the
+// farm's reward stream pro rata until they are unstaked
+
+describe("farms", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let rewardMint: PublicKey;
+
+  const farmAddress = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("farm"), pool.pool.toBuffer(), rewardMint.toBuffer()],
+      env.program.programId
+    )[0];
+
+  const createFarm = async (authority: Keypair, funderRewardToken: PublicKey, rewardAmount: number) => {
+    const now = Math.floor(Date.now() / 1000);
+    const rewardVault = Keypair.generate();
+    const lpVault = Keypair.generate();
+    return env.program.methods
+      .createFarm(new BN(now + 5), new BN(now + 3_600), new BN(rewardAmount))
+      .accounts({
+        pool: pool.pool,
+        farm: farmAddress(),
+        authority: authority.publicKey,
+        rewardMint,
+        rewardVault: rewardVault.publicKey,
+        funderRewardToken,
+        lpMint: pool.lpMint,
+        lpVault: lpVault.publicKey,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+        lpTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority, rewardVault, lpVault])
+      .rpc();
+  };
+
+  const stakeAddress = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("farm_stake"), farmAddress().toBuffer(), user.keypair.publicKey.toBuffer()],
+      env.program.programId
+    )[0];
+
+  const updateStake = async (stake: boolean, amount: number) => {
+    const farm = await env.program.account.farm.fetch(farmAddress());
+    return (stake ? env.program.methods.stakeLp(new BN(amount)) : env.program.methods.unstakeLp(new BN(amount)))
+      .accounts({
+        farm: farmAddress(),
+        pool: pool.pool,
+        stake: stakeAddress(),
+        owner: user.keypair.publicKey,
+        lpMint: pool.lpMint,
+        userLpToken: user.lpToken,
+        lpVault: farm.lpVault,
+        lpTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([user.keypair])
+      .rpc();
+  };
+
+  const claim = async () => {
+    const farm = await env.program.account.farm.fetch(farmAddress());
+    return env.program.methods
+      .claimRewards()
+      .accounts({
+        farm: farmAddress(),
+        stake: stakeAddress(),
+        owner: user.keypair.publicKey,
+        rewardMint,
+        rewardVault: farm.rewardVault,
+        userRewardToken: getAssociatedTokenAddressSync(rewardMint, user.keypair.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([user.keypair])
+      .rpc();
+  };
+
+  const fundedRewardAccount = async (owner: PublicKey, amount: number) => {
+    const connection = env.provider.connection;
+    const account = await getOrCreateAssociatedTokenAccount(
+      connection, env.payer.payer, rewardMint, owner, false, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(connection, env.payer.payer, rewardMint, account.address, env.payer.publicKey, amount, [], undefined, TOKEN_2022_PROGRAM_ID);
+    return account.address;
+  };
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+    rewardMint = await createTestMint(env);
+  });
+
+  it("refuses a farm from anyone but the pool authority", async () => {
+    const funder = await fundedRewardAccount(user.keypair.publicKey, 1_000_000);
+    await expectRejected(createFarm(user.keypair, funder, 1_000_000), "InvalidPoolAuthority");
+    expect(await env.provider.connection.getAccountInfo(farmAddress())).to.be.null;
+  });
+
+  it("lets the pool authority create the farm", async () => {
+    const funder = await fundedRewardAccount(env.payer.publicKey, 1_000_000);
+    await createFarm(env.payer.payer, funder, 1_000_000);
+
+    const farm = await env.program.account.farm.fetch(farmAddress());
+    expect(farm.authority.equals(env.payer.publicKey)).to.be.true;
+    expect((await tokenBalance(env.provider.connection, farm.rewardVault)).toString()).to.equal("1000000");
+  });
+
+  it("streams rewards to staked LP once the farm starts", async () => {
+    await env.program.methods
+      .openFarmStake()
+      .accounts({
+        farm: farmAddress(),
+        stake: stakeAddress(),
+        owner: user.keypair.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user.keypair])
+      .rpc();
+    await expectRejected(claim(), "NoRewardsToClaim");
+
+    await updateStake(true, 1_000_000);
+    expect((await env.program.account.farm.fetch(farmAddress())).totalStaked.toNumber()).to.equal(1_000_000);
+
+    // Past the start, where the sole staker earns the whole stream
+    await new Promise((resolve) => setTimeout(resolve, 8_000));
+    const rewardAccount = getAssociatedTokenAddressSync(rewardMint, user.keypair.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    const before = await tokenBalance(env.provider.connection, rewardAccount);
+    await claim();
+    const rewards = (await tokenBalance(env.provider.connection, rewardAccount)) - before;
+    expect(rewards > BigInt(0) && rewards < BigInt(1_000_000)).to.be.true;
+  });
+
+  it("hands back staked LP, but no more than was staked", async () => {
+    await expectRejected(updateStake(false, 2_000_000), "InsufficientStakedLp");
+
+    const before = await tokenBalance(env.provider.connection, user.lpToken);
+    await updateStake(false, 1_000_000);
+    expect((await tokenBalance(env.provider.connection, user.lpToken)) - before).to.equal(BigInt(1_000_000));
+    expect((await env.program.account.farmStake.fetch(stakeAddress())).stakedLp.toNumber()).to.equal(0);
+  });
+});
@@ -0,0 +1,108 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// In claimable mode an LP position earns its share of the per-LP fee growth
+// and `collect_fees` pays out exactly what it has accrued
+
+describe("LP fee collection", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let seeder: TestUser;
+  let holder: TestUser;
+  let lpPosition: PublicKey;
+
+  const collect = (owner: TestUser) =>
+    env.program.methods
+      .collectFees()
+      .accounts({
+        pool: pool.pool,
+        lpPosition,
+        owner: owner.keypair.publicKey,
+        userTokenA: owner.tokenA,
+        userTokenB: owner.tokenB,
+        vaultAuthority: pool.vaultAuthority,
+        poolTokenAVault: pool.poolTokenAVault,
+        poolTokenBVault: pool.poolTokenBVault,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([owner.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    seeder = await createTestUser(env, pool);
+    holder = await createTestUser(env, pool);
+    await addLiquidity(env, pool, seeder, 100_000_000);
+    await env.program.methods
+      .setFeeMode({ claimable: {} })
+      .accounts({ pool: pool.pool, authority: env.payer.publicKey })
+      .rpc();
+
+    [lpPosition] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_position"), pool.pool.toBuffer(), holder.keypair.publicKey.toBuffer()],
+      env.program.programId
+    );
+    await env.program.methods
+      .openLpPosition(new BN(0))
+      .accounts({ pool: pool.pool, lpPosition, owner: holder.keypair.publicKey, systemProgram: SystemProgram.programId })
+      .signers([holder.keypair])
+      .rpc();
+    await env.program.methods
+      .addLiquidity(new BN(100_000_000), new BN(100_000_000), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, holder, { lpPosition }))
+      .signers([holder.keypair])
+      .rpc();
+  });
+
+  it("pays a position its share of the fee growth since it deposited", async () => {
+    await env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, seeder))
+      .signers([seeder.keypair])
+      .rpc();
+
+    const { feeGrowthAX64 } = await env.program.account.ammPool.fetch(pool.pool);
+    const position = await env.program.account.lpPosition.fetch(lpPosition);
+    const owed = position.lpAmount.mul(feeGrowthAX64.sub(position.feeGrowthALastX64)).shrn(64);
+    // Roughly half of the 3,000 fee, the holder owning about half the supply
+    expect(owed.toNumber()).to.be.within(1_400, 1_500);
+
+    const connection = env.provider.connection;
+    const before = await tokenBalance(connection, holder.tokenA);
+    await collect(holder);
+    expect((await tokenBalance(connection, holder.tokenA)) - before).to.equal(BigInt(owed.toString()));
+  });
+
+  it("has nothing more to pay until the pool earns again", async () => {
+    const connection = env.provider.connection;
+    const before = await tokenBalance(connection, holder.tokenA);
+    await collect(holder);
+    expect(await tokenBalance(connection, holder.tokenA)).to.equal(before);
+  });
+
+  it("only pays the position's owner", async () => {
+    await expectRejected(collect(seeder), "InvalidLpPosition");
+  });
+});
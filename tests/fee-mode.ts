@@ -0,0 +1,84 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// In claimable mode swap fees leave the reserves for a per-side bucket;
+// switching back to compounding folds the bucket into the reserves
+
+describe("fee mode", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const setFeeMode = (feeMode: object) =>
+    env.program.methods
+      .setFeeMode(feeMode as any)
+      .accounts({ pool: pool.pool, authority: env.payer.publicKey })
+      .rpc();
+
+  const swapA = () =>
+    env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("compounds fees into reserves by default", async () => {
+    const before = await env.program.account.ammPool.fetch(pool.pool);
+    await swapA();
+    const after = await env.program.account.ammPool.fetch(pool.pool);
+    expect(after.claimableFeesA.toNumber()).to.equal(0);
+    expect(after.tokenAReserve.sub(before.tokenAReserve).toNumber()).to.equal(1_000_000);
+  });
+
+  it("sets swap fees aside in claimable mode", async () => {
+    await setFeeMode({ claimable: {} });
+    const before = await env.program.account.ammPool.fetch(pool.pool);
+    await swapA();
+    const after = await env.program.account.ammPool.fetch(pool.pool);
+
+    const fee = after.claimableFeesA.sub(before.claimableFeesA);
+    expect(fee.toNumber()).to.equal(3_000);
+    expect(after.tokenAReserve.sub(before.tokenAReserve).toNumber()).to.equal(1_000_000 - 3_000);
+    expect(after.feeGrowthAX64.gt(before.feeGrowthAX64)).to.be.true;
+  });
+
+  it("folds unclaimed fees back into reserves when compounding again", async () => {
+    const before = await env.program.account.ammPool.fetch(pool.pool);
+    await setFeeMode({ compound: {} });
+    const after = await env.program.account.ammPool.fetch(pool.pool);
+    expect(after.claimableFeesA.toNumber()).to.equal(0);
+    expect(after.tokenAReserve.eq(before.tokenAReserve.add(before.claimableFeesA))).to.be.true;
+  });
+
+  it("only lets the pool authority change the fee mode", async () => {
+    const stranger = Keypair.generate();
+    await expectRejected(
+      env.program.methods
+        .setFeeMode({ claimable: {} })
+        .accounts({ pool: pool.pool, authority: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+      "InvalidPoolAuthority"
+    );
+  });
+});
@@ -0,0 +1,72 @@
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// A scheduled fee ramp moves the fee rate linearly between two timestamps;
+// the pool runs in claimable mode here so each swap's fee can be read back
+
+describe("fee ramps", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const now = () => Math.floor(Date.now() / 1000);
+
+  const scheduleRamp = (targetFeeRate: number, startTime: number, endTime: number) =>
+    env.program.methods
+      .scheduleFeeRamp(new BN(targetFeeRate), new BN(startTime), new BN(endTime))
+      .accounts({ pool: pool.pool, authority: env.payer.publicKey })
+      .rpc();
+
+  const swapFee = async () => {
+    const before = await env.program.account.ammPool.fetch(pool.pool);
+    await env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+    const after = await env.program.account.ammPool.fetch(pool.pool);
+    return after.claimableFeesA.sub(before.claimableFeesA).toNumber();
+  };
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+    await env.program.methods
+      .setFeeMode({ claimable: {} })
+      .accounts({ pool: pool.pool, authority: env.payer.publicKey })
+      .rpc();
+  });
+
+  it("refuses a ramp that starts in the past or ends before it starts", async () => {
+    await expectRejected(scheduleRamp(100, now() - 60, now() + 60), "InvalidFeeRamp");
+    await expectRejected(scheduleRamp(100, now() + 60, now() + 60), "InvalidFeeRamp");
+  });
+
+  it("charges the starting rate until the ramp begins", async () => {
+    await scheduleRamp(100, now() + 30, now() + 32);
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    expect(state.feeRamp.startFeeRate.toNumber()).to.equal(30);
+    expect(state.feeRamp.endFeeRate.toNumber()).to.equal(100);
+    expect(await swapFee()).to.equal(3_000);
+  });
+
+  it("charges the target rate once the ramp has ended", async () => {
+    await scheduleRamp(100, now() + 5, now() + 7);
+    await new Promise((resolve) => setTimeout(resolve, 9_000));
+    expect(await swapFee()).to.equal(10_000);
+  });
+});
@@ -0,0 +1,62 @@
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { AmmEnv, expectRejected, finalizeProposals, proposeAndApprove, setupAmm, setupGovernance } from "./helpers";
+
+// A proposal that declares a dependency only executes once that proposal
+// has, and only with it passed alongside
+
+const MAX_COUNCIL_MEMBERS = 5;
+
+describe("proposal dependencies", () => {
+  let env: AmmEnv;
+  let governanceConfig: PublicKey;
+  let first: Keypair;
+  let second: Keypair;
+
+  // Re-installs the empty council governance starts with
+  const clearCouncil = {
+    setSecurityCouncil: {
+      members: Array(MAX_COUNCIL_MEMBERS).fill(PublicKey.default),
+      size: 0,
+      vetoThreshold: 0,
+    },
+  };
+
+  const execute = (proposal: Keypair, dependency: PublicKey | null) =>
+    env.program.methods
+      .executeConfigProposal()
+      .accounts({ proposal: proposal.publicKey, governanceConfig, dependency })
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    governanceConfig = await setupGovernance(env);
+
+    const connection = env.provider.connection;
+    const voter = Keypair.generate();
+    const airdrop = await connection.requestAirdrop(voter.publicKey, 5 * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdrop, "confirmed");
+
+    first = Keypair.generate();
+    second = Keypair.generate();
+    await proposeAndApprove(env, governanceConfig, first, voter, clearCouncil);
+    await proposeAndApprove(env, governanceConfig, second, voter, clearCouncil, first.publicKey);
+    await finalizeProposals(env, governanceConfig, [first, second]);
+  });
+
+  it("records the declared dependency", async () => {
+    const proposal = await env.program.account.proposal.fetch(second.publicKey);
+    expect(proposal.dependsOn.equals(first.publicKey)).to.be.true;
+  });
+
+  it("refuses to execute before the dependency has", async () => {
+    await expectRejected(execute(second, null), "MissingProposalDependency");
+    await expectRejected(execute(second, first.publicKey), "ProposalDependencyNotExecuted");
+  });
+
+  it("executes once the dependency has", async () => {
+    await execute(first, null);
+    await execute(second, first.publicKey);
+    expect((await env.program.account.proposal.fetch(second.publicKey)).status).to.deep.equal({ executed: {} });
+  });
+});
@@ -0,0 +1,52 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+import { AmmEnv, expectRejected, setupAmm } from "./helpers";
+
+// The guardian holds emergency powers while it keeps checking in; the suites
+// start with the payer as guardian, so this one hands the role back when done
+
+describe("guardian heartbeat", () => {
+  let env: AmmEnv;
+  const thirtyDays = 30 * 24 * 60 * 60;
+
+  const heartbeat = (guardian: Keypair) =>
+    env.program.methods
+      .guardianHeartbeat()
+      .accounts({ globalConfig: env.globalConfig, guardian: guardian.publicKey })
+      .signers([guardian])
+      .rpc();
+
+  const setGuardian = (emergencyAuthority: Keypair, guardian: Keypair, timeout: number) =>
+    env.program.methods
+      .setGuardian(guardian.publicKey, new BN(timeout))
+      .accounts({ globalConfig: env.globalConfig, emergencyAuthority: emergencyAuthority.publicKey })
+      .signers([emergencyAuthority])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+  });
+
+  it("records the guardian's check-in and refuses anyone else's", async () => {
+    const before = await env.program.account.globalConfig.fetch(env.globalConfig);
+    await heartbeat(env.payer.payer);
+    const after = await env.program.account.globalConfig.fetch(env.globalConfig);
+    expect(after.lastGuardianHeartbeat.gte(before.lastGuardianHeartbeat)).to.be.true;
+
+    await expectRejected(heartbeat(Keypair.generate()), "InvalidGuardian");
+  });
+
+  it("hands emergency powers to a new guardian", async () => {
+    const successor = Keypair.generate();
+
+    await expectRejected(setGuardian(env.payer.payer, successor, 60), "InvalidHeartbeatTimeout");
+    await setGuardian(env.payer.payer, successor, thirtyDays);
+    const config = await env.program.account.globalConfig.fetch(env.globalConfig);
+    expect(config.guardian.equals(successor.publicKey)).to.be.true;
+    expect(config.guardianHeartbeatTimeout.toNumber()).to.equal(thirtyDays);
+
+    await expectRejected(setGuardian(env.payer.payer, env.payer.payer, thirtyDays), "InvalidEmergencyAuthority");
+    await setGuardian(successor, env.payer.payer, thirtyDays);
+  });
+});
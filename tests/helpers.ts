@@ -0,0 +1,462 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Token2022Amm } from "../target/types/token2022_amm";
+import { SafeTransferHook } from "../target/types/safe_transfer_hook";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  LAMPORTS_PER_SOL,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  createInitializeMintInstruction,
+  createInitializeTransferHookInstruction,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+// Shared setup for the behaviour suites: the global PDAs, a funded user and
+// a seeded constant-product pool over two fresh Token-2022 mints
+
+export const deadline = () => new BN(Math.floor(Date.now() / 1000) + 60);
+
+export const expectRejected = async (call: Promise<unknown>, error: string) => {
+  try {
+    await call;
+    expect.fail(`Should have been rejected with ${error}`);
+  } catch (err) {
+    expect(err.toString()).to.include(error);
+  }
+};
+
+export const tokenBalance = async (connection: anchor.web3.Connection, account: PublicKey) =>
+  BigInt((await getAccount(connection, account, "confirmed", TOKEN_2022_PROGRAM_ID)).amount.toString());
+
+export interface AmmEnv {
+  program: Program<Token2022Amm>;
+  provider: anchor.AnchorProvider;
+  payer: anchor.Wallet;
+  ammConfig: PublicKey;
+  globalConfig: PublicKey;
+  creatorRecord: PublicKey;
+  whitelist: PublicKey;
+}
+
+export interface TestPool {
+  pool: PublicKey;
+  feeTier: number;
+  vaultAuthority: PublicKey;
+  tokenAMint: PublicKey;
+  tokenBMint: PublicKey;
+  poolTokenAVault: PublicKey;
+  poolTokenBVault: PublicKey;
+  lpMint: PublicKey;
+}
+
+export interface TestUser {
+  keypair: Keypair;
+  tokenA: PublicKey;
+  tokenB: PublicKey;
+  lpToken: PublicKey;
+}
+
+/// Create the global config, AMM config, whitelist and the payer's pool
+/// creator record unless an earlier suite already did
+export const setupAmm = async (): Promise<AmmEnv> => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Token2022Amm as Program<Token2022Amm>;
+  const payer = provider.wallet as anchor.Wallet;
+  const connection = provider.connection;
+
+  const [ammConfig] = PublicKey.findProgramAddressSync([Buffer.from("amm_config")], program.programId);
+  const [globalConfig] = PublicKey.findProgramAddressSync([Buffer.from("global_config")], program.programId);
+  const [creatorRecord] = PublicKey.findProgramAddressSync(
+    [Buffer.from("pool_creator"), payer.publicKey.toBuffer()],
+    program.programId
+  );
+
+  if (!(await connection.getAccountInfo(globalConfig))) {
+    await program.methods
+      .initializeGlobalConfig(new BN(0), 1_000)
+      .accounts({ globalConfig, authority: payer.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+  }
+  if (!(await connection.getAccountInfo(ammConfig))) {
+    const whitelistAccount = Keypair.generate();
+    await program.methods
+      .initializeWhitelist()
+      .accounts({
+        whitelist: whitelistAccount.publicKey,
+        authority: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([whitelistAccount])
+      .rpc();
+    await program.methods
+      .initialize()
+      .accounts({
+        ammConfig,
+        whitelist: whitelistAccount.publicKey,
+        authority: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+  if (!(await connection.getAccountInfo(creatorRecord))) {
+    await program.methods
+      .registerPoolCreator()
+      .accounts({ creatorRecord, creator: payer.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+  }
+  const whitelist = (await program.account.ammConfig.fetch(ammConfig)).whitelist;
+
+  return { program, provider, payer, ammConfig, globalConfig, creatorRecord, whitelist };
+};
+
+/// Fresh Token-2022 mint with the payer as mint authority
+export const createTestMint = (env: AmmEnv, decimals = 9) =>
+  createMint(
+    env.provider.connection,
+    env.payer.payer,
+    env.payer.publicKey,
+    null,
+    decimals,
+    Keypair.generate(),
+    undefined,
+    TOKEN_2022_PROGRAM_ID
+  );
+
+export interface HookedMint {
+  mint: PublicKey;
+  /// Remaining accounts a transfer of the mint forwards to Token-2022
+  hookAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+}
+
+/// Fresh Token-2022 mint guarded by the safe transfer hook, whitelisting the
+/// hook first unless an earlier suite already did
+export const createHookedMint = async (env: AmmEnv): Promise<HookedMint> => {
+  const hookProgram = anchor.workspace.SafeTransferHook as Program<SafeTransferHook>;
+  const connection = env.provider.connection;
+
+  const whitelist = await env.program.account.transferHookWhitelist.fetch(env.whitelist);
+  if (!whitelist.whitelistedHooks.some((hook) => hook.equals(hookProgram.programId))) {
+    await env.program.methods
+      .addHookToWhitelist(hookProgram.programId)
+      .accounts({ whitelist: env.whitelist, authority: env.payer.publicKey })
+      .rpc();
+  }
+
+  const mintKeypair = Keypair.generate();
+  const mintLen = getMintLen([ExtensionType.TransferHook]);
+  await sendAndConfirmTransaction(
+    connection,
+    new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: env.payer.publicKey,
+        newAccountPubkey: mintKeypair.publicKey,
+        space: mintLen,
+        lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      createInitializeTransferHookInstruction(
+        mintKeypair.publicKey,
+        env.payer.publicKey,
+        hookProgram.programId,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createInitializeMintInstruction(mintKeypair.publicKey, 9, env.payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    ),
+    [env.payer.payer, mintKeypair],
+    { commitment: "confirmed" }
+  );
+  const mint = mintKeypair.publicKey;
+
+  const [extraAccountMetas] = PublicKey.findProgramAddressSync(
+    [Buffer.from("extra-account-metas"), mint.toBuffer()],
+    hookProgram.programId
+  );
+  const [hookConfig] = PublicKey.findProgramAddressSync(
+    [Buffer.from("hook_config"), mint.toBuffer()],
+    hookProgram.programId
+  );
+  await hookProgram.methods
+    .initializeExtraAccountMetas()
+    .accounts({
+      extraAccountMetas,
+      hookConfig,
+      mint,
+      authority: env.payer.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return {
+    mint,
+    hookAccounts: [
+      { pubkey: hookProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: extraAccountMetas, isSigner: false, isWritable: false },
+    ],
+  };
+};
+
+/// Rate-limit record of a pool creator, made by `registerPoolCreator`
+export const creatorRecordAddress = (env: AmmEnv, creator: PublicKey) =>
+  PublicKey.findProgramAddressSync([Buffer.from("pool_creator"), creator.toBuffer()], env.program.programId)[0];
+
+/// The AMM's LP group mint, which every new pool's LP mint must join, or
+/// null while the config has no LP group
+export const lpGroupMint = async (env: AmmEnv) => {
+  const config = await env.program.account.ammConfig.fetch(env.ammConfig);
+  return config.lpGroupMint.equals(PublicKey.default) ? null : config.lpGroupMint;
+};
+
+/// Pool PDA of a canonically ordered pair at `feeTier`
+export const poolAddress = (env: AmmEnv, tokenAMint: PublicKey, tokenBMint: PublicKey, feeTier: number) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("pool"), tokenAMint.toBuffer(), tokenBMint.toBuffer(), new BN(feeTier).toArrayLike(Buffer, "le", 8)],
+    env.program.programId
+  )[0];
+
+/// Create a constant-product pool over `mints` (two fresh mints by default),
+/// as `authority` (the payer by default, otherwise a registered pool creator)
+export const createTestPool = async (
+  env: AmmEnv,
+  {
+    feeTier = 30,
+    mints,
+    authority = env.payer.payer,
+    acknowledgePermanentDelegate = false,
+  }: { feeTier?: number; mints?: PublicKey[]; authority?: Keypair; acknowledgePermanentDelegate?: boolean } = {}
+): Promise<TestPool> => {
+  const pair = mints ?? [await createTestMint(env), await createTestMint(env)];
+  const [tokenAMint, tokenBMint] = [...pair].sort((a, b) => Buffer.compare(a.toBuffer(), b.toBuffer()));
+  const pool = poolAddress(env, tokenAMint, tokenBMint, feeTier);
+  const [vaultAuthority] = PublicKey.findProgramAddressSync(
+    [pool.toBuffer(), Buffer.from("vault_auth")],
+    env.program.programId
+  );
+
+  const vaultA = Keypair.generate();
+  const vaultB = Keypair.generate();
+  const lpMint = Keypair.generate();
+  await env.program.methods
+    .initializePool(new BN(feeTier), { constantProduct: {} }, acknowledgePermanentDelegate)
+    .accounts({
+      pool,
+      authority: authority.publicKey,
+      globalConfig: env.globalConfig,
+      ammConfig: env.ammConfig,
+      whitelist: env.whitelist,
+      creatorRecord: creatorRecordAddress(env, authority.publicKey),
+      tokenAMint,
+      tokenBMint,
+      vaultAuthority,
+      tokenAVault: vaultA.publicKey,
+      tokenBVault: vaultB.publicKey,
+      lpMint: lpMint.publicKey,
+      lpGroupMint: await lpGroupMint(env),
+      freezeAuthorityA: null,
+      freezeAuthorityB: null,
+      creatorWsol: null,
+      tokenAProgram: TOKEN_2022_PROGRAM_ID,
+      tokenBProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: SYSVAR_RENT_PUBKEY,
+      associatedTokenProgram: null,
+    })
+    .signers([authority, vaultA, vaultB, lpMint])
+    .rpc();
+
+  return {
+    pool,
+    feeTier,
+    vaultAuthority,
+    tokenAMint,
+    tokenBMint,
+    poolTokenAVault: vaultA.publicKey,
+    poolTokenBVault: vaultB.publicKey,
+    lpMint: lpMint.publicKey,
+  };
+};
+
+/// Airdropped wallet holding `amount` of each of the pool's tokens
+export const createTestUser = async (env: AmmEnv, pool: TestPool, amount = 1_000_000_000): Promise<TestUser> => {
+  const connection = env.provider.connection;
+  const keypair = Keypair.generate();
+  const airdrop = await connection.requestAirdrop(keypair.publicKey, 2 * LAMPORTS_PER_SOL);
+  await connection.confirmTransaction(airdrop, "confirmed");
+
+  for (const mint of [pool.tokenAMint, pool.tokenBMint]) {
+    const account = await getOrCreateAssociatedTokenAccount(
+      connection, env.payer.payer, mint, keypair.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(connection, env.payer.payer, mint, account.address, env.payer.publicKey, amount, [], undefined, TOKEN_2022_PROGRAM_ID);
+  }
+
+  return {
+    keypair,
+    tokenA: getAssociatedTokenAddressSync(pool.tokenAMint, keypair.publicKey, false, TOKEN_2022_PROGRAM_ID),
+    tokenB: getAssociatedTokenAddressSync(pool.tokenBMint, keypair.publicKey, false, TOKEN_2022_PROGRAM_ID),
+    lpToken: getAssociatedTokenAddressSync(pool.lpMint, keypair.publicKey, false, TOKEN_2022_PROGRAM_ID),
+  };
+};
+
+export const swapAccounts = (env: AmmEnv, pool: TestPool, user: TestUser, overrides: Record<string, PublicKey> = {}) => ({
+  pool: pool.pool,
+  user: user.keypair.publicKey,
+  userTokenA: user.tokenA,
+  userTokenB: user.tokenB,
+  vaultAuthority: pool.vaultAuthority,
+  poolTokenAVault: pool.poolTokenAVault,
+  poolTokenBVault: pool.poolTokenBVault,
+  tokenAMint: pool.tokenAMint,
+  tokenBMint: pool.tokenBMint,
+  ammConfig: env.ammConfig,
+  whitelist: env.whitelist,
+  tokenAHookCache: null,
+  tokenBHookCache: null,
+  takerAllowlist: null,
+  outputFailureLog: null,
+  observation: null,
+  protocolFeeVault: null,
+  tokenAProgram: TOKEN_2022_PROGRAM_ID,
+  tokenBProgram: TOKEN_2022_PROGRAM_ID,
+  ...overrides,
+});
+
+export const liquidityAccounts = (env: AmmEnv, pool: TestPool, user: TestUser, overrides: Record<string, PublicKey> = {}) => ({
+  pool: pool.pool,
+  user: user.keypair.publicKey,
+  userTokenA: user.tokenA,
+  userTokenB: user.tokenB,
+  userLpToken: user.lpToken,
+  vaultAuthority: pool.vaultAuthority,
+  poolTokenAVault: pool.poolTokenAVault,
+  poolTokenBVault: pool.poolTokenBVault,
+  lpMint: pool.lpMint,
+  tokenAMint: pool.tokenAMint,
+  tokenBMint: pool.tokenBMint,
+  ammConfig: env.ammConfig,
+  whitelist: env.whitelist,
+  launchGuard: null,
+  globalConfig: env.globalConfig,
+  pairOracle: PublicKey.findProgramAddressSync(
+    [Buffer.from("pair_oracle"), pool.tokenAMint.toBuffer(), pool.tokenBMint.toBuffer()],
+    env.program.programId
+  )[0],
+  lpPosition: null,
+  tokenProgram: TOKEN_2022_PROGRAM_ID,
+  tokenAProgram: TOKEN_2022_PROGRAM_ID,
+  tokenBProgram: TOKEN_2022_PROGRAM_ID,
+  associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+  systemProgram: SystemProgram.programId,
+  ...overrides,
+});
+
+/// Deposit `amount` of both tokens from `user`
+export const addLiquidity = (env: AmmEnv, pool: TestPool, user: TestUser, amount: number) =>
+  env.program.methods
+    .addLiquidity(new BN(amount), new BN(amount), new BN(0), deadline())
+    .accounts(liquidityAccounts(env, pool, user))
+    .signers([user.keypair])
+    .rpc();
+
+/// Seconds `setupGovernance` leaves proposals open for voting
+export const VOTING_PERIOD_SECONDS = 3;
+
+/// Governance with a voting period of seconds and stakes a test wallet can
+/// afford, through the `test-utils` build's `set_governance_params`
+export const setupGovernance = async (env: AmmEnv) => {
+  const connection = env.provider.connection;
+  const [governanceConfig] = PublicKey.findProgramAddressSync([Buffer.from("governance_config")], env.program.programId);
+  if (!(await connection.getAccountInfo(governanceConfig))) {
+    await env.program.methods
+      .initializeGovernanceConfig([], 0)
+      .accounts({
+        governanceConfig,
+        ammConfig: env.ammConfig,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+  await env.program.methods
+    .setGovernanceParams({
+      votingPeriod: new BN(VOTING_PERIOD_SECONDS),
+      quorumStake: new BN(LAMPORTS_PER_SOL),
+      minApproveStake: new BN(LAMPORTS_PER_SOL),
+      approvalThresholdBps: new BN(6_000),
+      minProposerStake: new BN(LAMPORTS_PER_SOL),
+      vetoWindow: new BN(0),
+    })
+    .accounts({ governanceConfig, ammConfig: env.ammConfig, authority: env.payer.publicKey })
+    .rpc();
+  return governanceConfig;
+};
+
+export const stakeVaultAddress = (env: AmmEnv, proposal: PublicKey) =>
+  PublicKey.findProgramAddressSync([Buffer.from("stake_vault"), proposal.toBuffer()], env.program.programId)[0];
+
+/// Open a proposal for `action` with the payer's 1 SOL stake and approve it
+/// with `voter`'s 1 SOL; it can be finalized once the voting period is over
+export const proposeAndApprove = async (
+  env: AmmEnv,
+  governanceConfig: PublicKey,
+  proposal: Keypair,
+  voter: Keypair,
+  action: object,
+  dependency: PublicKey | null = null
+) => {
+  const stakeVault = stakeVaultAddress(env, proposal.publicKey);
+  await env.program.methods
+    .createProposal(action as any, "Behaviour suite proposal", new BN(LAMPORTS_PER_SOL))
+    .accounts({
+      proposal: proposal.publicKey,
+      proposer: env.payer.publicKey,
+      dependency,
+      governanceConfig,
+      stakeVault,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([proposal])
+    .rpc();
+  await env.program.methods
+    .voteOnProposal(true, new BN(LAMPORTS_PER_SOL))
+    .accounts({
+      proposal: proposal.publicKey,
+      voter: voter.publicKey,
+      whitelist: env.whitelist,
+      voteRecord: PublicKey.findProgramAddressSync(
+        [proposal.publicKey.toBuffer(), voter.publicKey.toBuffer()],
+        env.program.programId
+      )[0],
+      stakeVault,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([voter])
+    .rpc();
+};
+
+/// Wait out the voting period, then finalize `proposals`
+export const finalizeProposals = async (env: AmmEnv, governanceConfig: PublicKey, proposals: Keypair[]) => {
+  await new Promise((resolve) => setTimeout(resolve, (VOTING_PERIOD_SECONDS + 1) * 1000));
+  for (const proposal of proposals) {
+    await env.program.methods
+      .finalizeProposal()
+      .accounts({ proposal: proposal.publicKey, governanceConfig })
+      .rpc();
+  }
+};
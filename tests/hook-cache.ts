@@ -0,0 +1,86 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// A wallet can cache a mint's whitelist validation for a short TTL and pass
+// the cache to its swaps instead of having the hook re-validated each time
+
+describe("hook validation cache", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let cache: PublicKey;
+
+  const openCache = (ttl: number) =>
+    env.program.methods
+      .openHookCache(new BN(ttl))
+      .accounts({
+        cache,
+        owner: user.keypair.publicKey,
+        mint: pool.tokenAMint,
+        whitelist: env.whitelist,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user.keypair])
+      .rpc();
+
+  const refreshCache = (ttl: number) =>
+    env.program.methods
+      .refreshHookCache(new BN(ttl))
+      .accounts({ cache, owner: user.keypair.publicKey, mint: pool.tokenAMint, whitelist: env.whitelist })
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+    [cache] = PublicKey.findProgramAddressSync(
+      [Buffer.from("hook_cache"), pool.tokenAMint.toBuffer(), user.keypair.publicKey.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  it("refuses a TTL above the five-minute cap", async () => {
+    await expectRejected(openCache(301), "InvalidCacheTtl");
+  });
+
+  it("records the validation and serves it to the owner's swaps", async () => {
+    await openCache(60);
+    const entry = await env.program.account.hookValidationCache.fetch(cache);
+    expect(entry.whitelist.equals(env.whitelist)).to.be.true;
+    expect(entry.hookProgramId.equals(PublicKey.default)).to.be.true;
+    expect(entry.ttl.toNumber()).to.equal(60);
+
+    const before = await tokenBalance(env.provider.connection, user.tokenB);
+    await env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user, { tokenAHookCache: cache }))
+      .signers([user.keypair])
+      .rpc();
+    expect((await tokenBalance(env.provider.connection, user.tokenB)) > before).to.be.true;
+  });
+
+  it("re-validates on refresh with the new TTL", async () => {
+    const before = (await env.program.account.hookValidationCache.fetch(cache)).validatedAt;
+    await refreshCache(120);
+    const entry = await env.program.account.hookValidationCache.fetch(cache);
+    expect(entry.ttl.toNumber()).to.equal(120);
+    expect(entry.validatedAt.gte(before)).to.be.true;
+    await expectRejected(refreshCache(0), "InvalidCacheTtl");
+  });
+});
@@ -0,0 +1,71 @@
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  HookedMint,
+  TestPool,
+  TestUser,
+  createHookedMint,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// A pool can cap the compute its hook-bearing transfers consume, so a
+// bloated hook fails the swap with a dedicated error
+
+describe("hook compute budget", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let hooked: HookedMint;
+
+  const setMaxHookCu = (maxHookCu: number) =>
+    env.program.methods
+      .setMaxHookCu(new BN(maxHookCu))
+      .accounts({ pool: pool.pool, authority: env.payer.publicKey })
+      .rpc();
+
+  const swapHooked = () =>
+    env.program.methods
+      .swap(new BN(1_000_000), new BN(1), pool.tokenAMint.equals(hooked.mint) ? { a: {} } : { b: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .remainingAccounts(hooked.hookAccounts)
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    hooked = await createHookedMint(env);
+    pool = await createTestPool(env, { mints: [hooked.mint, await createTestMint(env)] });
+    user = await createTestUser(env, pool);
+    await env.program.methods
+      .addLiquidity(new BN(100_000_000), new BN(100_000_000), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .remainingAccounts(hooked.hookAccounts)
+      .signers([user.keypair])
+      .rpc();
+  });
+
+  it("aborts a swap whose hook transfer exceeds the budget", async () => {
+    await setMaxHookCu(1);
+    await expectRejected(swapHooked(), "HookComputeBudgetExceeded");
+  });
+
+  it("swaps within a budget the hook fits in, and without one", async () => {
+    const userOut = pool.tokenAMint.equals(hooked.mint) ? user.tokenB : user.tokenA;
+    await setMaxHookCu(150_000);
+    const before = await tokenBalance(env.provider.connection, userOut);
+    await swapHooked();
+    expect((await tokenBalance(env.provider.connection, userOut)) > before).to.be.true;
+
+    await setMaxHookCu(0);
+    await swapHooked();
+  });
+});
@@ -0,0 +1,99 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  HookedMint,
+  TestPool,
+  TestUser,
+  createHookedMint,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// A hook mint's failure log counts the swaps that delivered it and the
+// rejections its reporter observed, and serves a rolling failure rate
+
+describe("hook failure log", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let hooked: HookedMint;
+  let failureLog: PublicKey;
+
+  const report = (failedSlot: number, reporter: Keypair = env.payer.payer) =>
+    env.program.methods
+      .reportHookFailure(new BN(failedSlot), 6_001)
+      .accounts({ failureLog, reporter: reporter.publicKey })
+      .signers([reporter])
+      .rpc();
+
+  const reliability = () => env.program.methods.hookFailureRate().accounts({ failureLog }).view();
+
+  before(async () => {
+    env = await setupAmm();
+    hooked = await createHookedMint(env);
+    pool = await createTestPool(env, { mints: [hooked.mint, await createTestMint(env)] });
+    user = await createTestUser(env, pool);
+    await env.program.methods
+      .addLiquidity(new BN(100_000_000), new BN(100_000_000), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .remainingAccounts(hooked.hookAccounts)
+      .signers([user.keypair])
+      .rpc();
+
+    [failureLog] = PublicKey.findProgramAddressSync(
+      [Buffer.from("hook_failure_log"), hooked.mint.toBuffer()],
+      env.program.programId
+    );
+    await env.program.methods
+      .openHookFailureLog(env.payer.publicKey)
+      .accounts({
+        failureLog,
+        mint: hooked.mint,
+        whitelist: env.whitelist,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("counts a swap that delivered the hooked mint as an attempt", async () => {
+    const inputSide = pool.tokenAMint.equals(hooked.mint) ? { b: {} } : { a: {} };
+    await env.program.methods
+      .swap(new BN(1_000_000), new BN(1), inputSide, null, deadline())
+      .accounts(swapAccounts(env, pool, user, { outputFailureLog: failureLog }))
+      .remainingAccounts(hooked.hookAccounts)
+      .signers([user.keypair])
+      .rpc();
+
+    const view = await reliability();
+    expect(view.mint.equals(hooked.mint)).to.be.true;
+    expect(view.attempts.toNumber()).to.equal(1);
+    expect(view.failures.toNumber()).to.equal(0);
+  });
+
+  it("folds reported rejections into the failure rate", async () => {
+    const slot = await env.provider.connection.getSlot("confirmed");
+    await report(slot);
+
+    const view = await reliability();
+    expect(view.attempts.toNumber()).to.equal(2);
+    expect(view.failures.toNumber()).to.equal(1);
+    expect(view.failureRateBps.toNumber()).to.equal(5_000);
+    expect(view.lastFailureSlot.toNumber()).to.equal(slot);
+    expect(view.lastFailureReason).to.equal(6_001);
+  });
+
+  it("only takes reports from the log's reporter, for slots that happened", async () => {
+    const slot = await env.provider.connection.getSlot("confirmed");
+    await expectRejected(report(slot, Keypair.generate()), "InvalidHookFailureReporter");
+    await expectRejected(report(slot + 1_000_000), "InvalidHookFailureReport");
+  });
+});
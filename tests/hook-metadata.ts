@@ -0,0 +1,115 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  HookedMint,
+  TestPool,
+  TestUser,
+  createHookedMint,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// Hook metadata records a hook's audit and risk tier; swaps through a hook
+// rated high risk are capped at the notional its metadata allows
+
+describe("hook metadata", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let hooked: HookedMint;
+  let poolWhitelist: PublicKey;
+  let hookMetadata: PublicKey;
+
+  const register = (riskTier: object, maxNotional: number, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .registerHookMetadata(hooked.hookAccounts[0].pubkey, "https://audits.example/hook", "Example Audits", riskTier as any, new BN(maxNotional))
+      .accounts({
+        whitelist: poolWhitelist,
+        hookMetadata,
+        whitelistEntry: null,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+  const swapHooked = (amountIn: number, withMetadata = true) =>
+    env.program.methods
+      .swap(new BN(amountIn), new BN(1), pool.tokenAMint.equals(hooked.mint) ? { a: {} } : { b: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user, { whitelist: poolWhitelist }))
+      .remainingAccounts([
+        ...hooked.hookAccounts,
+        ...(withMetadata ? [{ pubkey: hookMetadata, isSigner: false, isWritable: false }] : []),
+      ])
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    hooked = await createHookedMint(env);
+    pool = await createTestPool(env, { mints: [hooked.mint, await createTestMint(env)] });
+    user = await createTestUser(env, pool);
+    await env.program.methods
+      .addLiquidity(new BN(100_000_000), new BN(100_000_000), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .remainingAccounts(hooked.hookAccounts)
+      .signers([user.keypair])
+      .rpc();
+
+    // Rate the hook on a pool whitelist, leaving the global one untouched
+    [poolWhitelist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_whitelist"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+    await env.program.methods
+      .enablePoolWhitelist([hooked.hookAccounts[0].pubkey])
+      .accounts({
+        pool: pool.pool,
+        poolWhitelist,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    [hookMetadata] = PublicKey.findProgramAddressSync(
+      [Buffer.from("hook_metadata"), poolWhitelist.toBuffer(), hooked.hookAccounts[0].pubkey.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  it("requires a notional cap on a high-risk rating", async () => {
+    await expectRejected(register({ high: {} }, 0), "InvalidHookMetadata");
+    await expectRejected(register({ high: {} }, 2_000_000, Keypair.generate()), "InvalidWhitelistAuthority");
+    await register({ high: {} }, 2_000_000);
+
+    const metadata = await env.program.account.hookMetadata.fetch(hookMetadata);
+    expect(metadata.auditor).to.equal("Example Audits");
+    expect(metadata.maxNotional.toNumber()).to.equal(2_000_000);
+    const validation = await env.program.methods
+      .validateHook()
+      .accounts({ pool: pool.pool, ammConfig: env.ammConfig, whitelist: poolWhitelist, mint: hooked.mint })
+      .view();
+    expect(validation.highRisk).to.be.true;
+  });
+
+  it("caps swaps through a high-risk hook at its notional", async () => {
+    await expectRejected(swapHooked(1_000_000, false), "MissingHookMetadata");
+    await expectRejected(swapHooked(5_000_000), "HookNotionalCapExceeded");
+    await swapHooked(1_000_000);
+  });
+
+  it("lifts the cap once the hook is re-rated", async () => {
+    await env.program.methods
+      .updateHookMetadata("https://audits.example/hook-v2", "Example Audits", { low: {} }, new BN(0))
+      .accounts({ whitelist: poolWhitelist, hookMetadata, whitelistEntry: null, authority: env.payer.publicKey })
+      .rpc();
+    await swapHooked(5_000_000, false);
+  });
+});
@@ -0,0 +1,79 @@
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  AmmEnv,
+  TestPool,
+  createHookedMint,
+  createTestMint,
+  createTestPool,
+  expectRejected,
+  setupAmm,
+} from "./helpers";
+
+// Once a pool's hook drops off its whitelist anyone can flag the pool, which
+// sends it withdraw-only. The pool gets its own whitelist here so the global
+// one stays as it is
+
+describe("hook revocation", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let hookProgram: PublicKey;
+  let poolWhitelist: PublicKey;
+  let entry: PublicKey;
+
+  const flag = (entries: PublicKey[], caller: Keypair = Keypair.generate()) =>
+    env.program.methods
+      .flagPoolHookRevoked()
+      .accounts({
+        pool: pool.pool,
+        ammConfig: env.ammConfig,
+        whitelist: poolWhitelist,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+      })
+      .remainingAccounts(entries.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+      .signers([caller])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    const hooked = await createHookedMint(env);
+    hookProgram = hooked.hookAccounts[0].pubkey;
+    pool = await createTestPool(env, { mints: [hooked.mint, await createTestMint(env)] });
+
+    [poolWhitelist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_whitelist"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+    await env.program.methods
+      .enablePoolWhitelist([hookProgram])
+      .accounts({
+        pool: pool.pool,
+        poolWhitelist,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    [entry] = PublicKey.findProgramAddressSync(
+      [Buffer.from("hook"), poolWhitelist.toBuffer(), hookProgram.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  it("leaves a pool alone while its hook is whitelisted", async () => {
+    await expectRejected(flag([]), "HookStillWhitelisted");
+  });
+
+  it("sends the pool withdraw-only once the hook is removed", async () => {
+    await env.program.methods
+      .removeHookFromWhitelist(hookProgram)
+      .accounts({ whitelist: poolWhitelist, authority: env.payer.publicKey })
+      .rpc();
+
+    // The hook's entry has to be passed so its absence is proven
+    await expectRejected(flag([]), "MissingWhitelistEntry");
+    await flag([entry]);
+
+    // A withdraw-only pool refuses the flag, as it does swaps and deposits
+    await expectRejected(flag([entry]), "PoolWithdrawOnly");
+  });
+});
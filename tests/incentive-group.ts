@@ -0,0 +1,239 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { SafeTransferHook } from "../target/types/safe_transfer_hook";
+import { PublicKey, Keypair, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  createInitializeMintInstruction,
+  createInitializeTransferHookInstruction,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// Incentive groups pay one reward stream across member pools; a claim in a
+// Transfer Hook reward mint carries the hook accounts after the members
+
+describe("incentive groups", () => {
+  const hookProgram = anchor.workspace.SafeTransferHook as Program<SafeTransferHook>;
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let rewardMint: PublicKey;
+  let hookAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+  const groupId = new BN(Date.now());
+  const rewardVault = Keypair.generate();
+  const lpVault = Keypair.generate();
+
+  const groupAddress = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("incentive_group"), groupId.toArrayLike(Buffer, "le", 8)],
+      env.program.programId
+    )[0];
+
+  const stakeAddress = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("incentive_stake"), groupAddress().toBuffer(), pool.pool.toBuffer(), user.keypair.publicKey.toBuffer()],
+      env.program.programId
+    )[0];
+
+  const stakeAccounts = () => ({
+    group: groupAddress(),
+    pool: pool.pool,
+    stake: stakeAddress(),
+    owner: user.keypair.publicKey,
+    lpMint: pool.lpMint,
+    userLpToken: user.lpToken,
+    lpVault: lpVault.publicKey,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  const memberPools = () => [{ pubkey: pool.pool, isSigner: false, isWritable: false }];
+
+  before(async () => {
+    env = await setupAmm();
+    const connection = env.provider.connection;
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+
+    const mintKeypair = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferHook]);
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: env.payer.publicKey,
+          newAccountPubkey: mintKeypair.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(
+          mintKeypair.publicKey,
+          env.payer.publicKey,
+          hookProgram.programId,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mintKeypair.publicKey, 9, env.payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [env.payer.payer, mintKeypair],
+      { commitment: "confirmed" }
+    );
+    rewardMint = mintKeypair.publicKey;
+
+    const [extraAccountMetas] = PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), rewardMint.toBuffer()],
+      hookProgram.programId
+    );
+    const [hookConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("hook_config"), rewardMint.toBuffer()],
+      hookProgram.programId
+    );
+    await hookProgram.methods
+      .initializeExtraAccountMetas()
+      .accounts({
+        extraAccountMetas,
+        hookConfig,
+        mint: rewardMint,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    hookAccounts = [
+      { pubkey: hookProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: extraAccountMetas, isSigner: false, isWritable: false },
+    ];
+  });
+
+  it("claims a hooked reward mint with the hook accounts after the member pools", async () => {
+    const connection = env.provider.connection;
+    const group = groupAddress();
+
+    await env.program.methods
+      .createIncentiveGroup(groupId, new BN(1_000))
+      .accounts({
+        globalConfig: env.globalConfig,
+        group,
+        authority: env.payer.publicKey,
+        rewardMint,
+        rewardVault: rewardVault.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([rewardVault])
+      .rpc();
+    await mintTo(connection, env.payer.payer, rewardMint, rewardVault.publicKey, env.payer.publicKey, 1_000_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    await env.program.methods
+      .addGroupPool()
+      .accounts({
+        group,
+        authority: env.payer.publicKey,
+        pool: pool.pool,
+        lpMint: pool.lpMint,
+        lpVault: lpVault.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([lpVault])
+      .rpc();
+
+    await env.program.methods
+      .openIncentiveStake()
+      .accounts({
+        group,
+        pool: pool.pool,
+        stake: stakeAddress(),
+        owner: user.keypair.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user.keypair])
+      .rpc();
+
+    await env.program.methods
+      .stakeGroupLp(new BN(1_000_000))
+      .accounts(stakeAccounts())
+      .remainingAccounts(memberPools())
+      .signers([user.keypair])
+      .rpc();
+
+    // Emission follows swap volume, so trade through the pool and let time pass
+    await env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+
+    const userReward = (
+      await getOrCreateAssociatedTokenAccount(
+        connection, env.payer.payer, rewardMint, user.keypair.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    await env.program.methods
+      .claimGroupRewards()
+      .accounts({
+        group,
+        pool: pool.pool,
+        stake: stakeAddress(),
+        owner: user.keypair.publicKey,
+        rewardMint,
+        rewardVault: rewardVault.publicKey,
+        userRewardToken: userReward,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts([...memberPools(), ...hookAccounts])
+      .signers([user.keypair])
+      .rpc();
+
+    expect((await tokenBalance(connection, userReward)) > BigInt(0)).to.be.true;
+  });
+
+  it("lets only the group authority change the reward rate", async () => {
+    const setRate = (authority: Keypair) =>
+      env.program.methods
+        .setGroupRewardRate(new BN(2_000))
+        .accounts({ group: groupAddress(), authority: authority.publicKey })
+        .remainingAccounts(memberPools())
+        .signers([authority])
+        .rpc();
+
+    await expectRejected(setRate(Keypair.generate()), "InvalidConfigAuthority");
+    await setRate(env.payer.payer);
+    expect((await env.program.account.incentiveGroup.fetch(groupAddress())).rewardRate.toNumber()).to.equal(2_000);
+  });
+
+  it("returns staked LP tokens on unstake", async () => {
+    const unstake = (amount: number) =>
+      env.program.methods
+        .unstakeGroupLp(new BN(amount))
+        .accounts(stakeAccounts())
+        .remainingAccounts(memberPools())
+        .signers([user.keypair])
+        .rpc();
+
+    await expectRejected(unstake(2_000_000), "InsufficientStakedLp");
+    const before = await tokenBalance(env.provider.connection, user.lpToken);
+    await unstake(1_000_000);
+
+    expect(((await tokenBalance(env.provider.connection, user.lpToken)) - before).toString()).to.equal("1000000");
+    const stake = await env.program.account.incentiveStake.fetch(stakeAddress());
+    expect(stake.stakedLp.toNumber()).to.equal(0);
+  });
+});
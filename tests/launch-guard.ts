@@ -0,0 +1,77 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+} from "./helpers";
+
+// A launch guard admits only allowlisted LPs until the configured number of
+// them has deposited, after which the pool is open to everyone
+
+describe("launch guard", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let launchGuard: PublicKey;
+  let insider: TestUser;
+  let latecomer: TestUser;
+  let outsider: TestUser;
+
+  const deposit = (user: TestUser, guard: PublicKey | null = launchGuard) =>
+    env.program.methods
+      .addLiquidity(new BN(10_000_000), new BN(10_000_000), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user, { launchGuard: guard }))
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    insider = await createTestUser(env, pool);
+    latecomer = await createTestUser(env, pool);
+    outsider = await createTestUser(env, pool);
+    [launchGuard] = PublicKey.findProgramAddressSync(
+      [Buffer.from("launch_guard"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+
+    await env.program.methods
+      .enableLaunchGuard(2, new BN(0), [insider.keypair.publicKey])
+      .accounts({
+        pool: pool.pool,
+        launchGuard,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("turns away wallets that are not on the allowlist", async () => {
+    await expectRejected(deposit(outsider), "NotOnLaunchAllowlist");
+    await expectRejected(deposit(insider, null), "MissingLaunchGuard");
+  });
+
+  it("admits wallets the creator adds later", async () => {
+    await env.program.methods
+      .addLaunchAllowlistWallet(latecomer.keypair.publicKey)
+      .accounts({ pool: pool.pool, launchGuard, authority: env.payer.publicKey })
+      .rpc();
+
+    await deposit(insider);
+    await deposit(latecomer);
+    const guard = await env.program.account.launchGuard.fetch(launchGuard);
+    expect(guard.allowlistCount).to.equal(2);
+    expect(guard.lpsAdmitted).to.equal(2);
+  });
+
+  it("opens the pool once the guarded LPs have deposited", async () => {
+    await deposit(outsider);
+  });
+});
@@ -0,0 +1,147 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { SafeTransferHook } from "../target/types/safe_transfer_hook";
+import { PublicKey, Keypair, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  createInitializeMintInstruction,
+  createInitializeTransferHookInstruction,
+  getMintLen,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  deadline,
+  liquidityAccounts,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// A hook whitelisted as LegacyExecute is still a TransferHook mint to
+// Token-2022: its transfers must carry the hook accounts, and the AMM runs
+// the legacy execute on top of the one Token-2022 makes
+
+describe("legacy-interface transfer hooks", () => {
+  const hookProgram = anchor.workspace.SafeTransferHook as Program<SafeTransferHook>;
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let hookedMint: PublicKey;
+  let hookAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+
+  const setHookVersion = (version: object) =>
+    env.program.methods
+      .setHookInterfaceVersion(hookProgram.programId, version as any)
+      .accounts({ whitelist: env.whitelist, authority: env.payer.publicKey })
+      .rpc();
+
+  const hookApprovals = async (signature: string) => {
+    const tx = await env.provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    return tx.meta.logMessages.filter((log) => log.includes("Transfer approved by Safe Transfer Hook")).length;
+  };
+
+  before(async () => {
+    env = await setupAmm();
+    const connection = env.provider.connection;
+
+    const whitelist = await env.program.account.transferHookWhitelist.fetch(env.whitelist);
+    if (!whitelist.whitelistedHooks.some((hook) => hook.equals(hookProgram.programId))) {
+      await env.program.methods
+        .addHookToWhitelist(hookProgram.programId)
+        .accounts({ whitelist: env.whitelist, authority: env.payer.publicKey })
+        .rpc();
+    }
+    await setHookVersion({ legacyExecute: {} });
+
+    const mintKeypair = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferHook]);
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: env.payer.publicKey,
+          newAccountPubkey: mintKeypair.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(
+          mintKeypair.publicKey,
+          env.payer.publicKey,
+          hookProgram.programId,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mintKeypair.publicKey, 9, env.payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [env.payer.payer, mintKeypair],
+      { commitment: "confirmed" }
+    );
+    hookedMint = mintKeypair.publicKey;
+
+    const [extraAccountMetas] = PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), hookedMint.toBuffer()],
+      hookProgram.programId
+    );
+    const [hookConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("hook_config"), hookedMint.toBuffer()],
+      hookProgram.programId
+    );
+    await hookProgram.methods
+      .initializeExtraAccountMetas()
+      .accounts({
+        extraAccountMetas,
+        hookConfig,
+        mint: hookedMint,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    hookAccounts = [
+      { pubkey: hookProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: extraAccountMetas, isSigner: false, isWritable: false },
+    ];
+
+    pool = await createTestPool(env, { mints: [hookedMint, await createTestMint(env)] });
+    user = await createTestUser(env, pool);
+    await env.program.methods
+      .addLiquidity(new BN(100_000_000), new BN(100_000_000), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .remainingAccounts(hookAccounts)
+      .signers([user.keypair])
+      .rpc();
+  });
+
+  after(async () => {
+    await setHookVersion({ splInterface: {} });
+  });
+
+  it("swaps the hooked mint with the hook accounts forwarded to Token-2022", async () => {
+    const connection = env.provider.connection;
+    const hookedSide = pool.tokenAMint.equals(hookedMint) ? { a: {} } : { b: {} };
+    const [userIn, userOut] = pool.tokenAMint.equals(hookedMint) ? [user.tokenA, user.tokenB] : [user.tokenB, user.tokenA];
+    const inBefore = await tokenBalance(connection, userIn);
+    const outBefore = await tokenBalance(connection, userOut);
+
+    const signature = await env.program.methods
+      .swap(new BN(1_000_000), new BN(1), hookedSide, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .remainingAccounts(hookAccounts)
+      .signers([user.keypair])
+      .rpc({ commitment: "confirmed" });
+
+    expect((inBefore - (await tokenBalance(connection, userIn))).toString()).to.equal("1000000");
+    expect((await tokenBalance(connection, userOut)) > outBefore).to.be.true;
+    // Once from Token-2022 during the transfer, once as the AMM's legacy execute
+    expect(await hookApprovals(signature)).to.equal(2);
+  });
+});
@@ -0,0 +1,143 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, transferChecked } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  expectRejected,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// Limit orders escrow their input until the pool pays the limit; a keeper's
+// fill swaps the whole escrow and closes it, cancelling refunds it
+
+describe("limit orders", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const orderAccounts = (nonce: number) => {
+    const [order] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("limit_order"),
+        pool.pool.toBuffer(),
+        user.keypair.publicKey.toBuffer(),
+        new BN(nonce).toArrayLike(Buffer, "le", 8),
+      ],
+      env.program.programId
+    );
+    const [escrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from("limit_order_escrow"), order.toBuffer()],
+      env.program.programId
+    );
+    return { order, escrow };
+  };
+
+  const placeOrder = (nonce: number, amountIn: number, minAmountOut: number) =>
+    env.program.methods
+      .placeLimitOrder(new BN(nonce), new BN(amountIn), new BN(minAmountOut))
+      .accounts({
+        pool: pool.pool,
+        ...orderAccounts(nonce),
+        owner: user.keypair.publicKey,
+        ownerInputToken: user.tokenA,
+        inputMint: pool.tokenAMint,
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        takerAllowlist: null,
+        inputTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user.keypair])
+      .rpc();
+
+  const fillOrder = (nonce: number) =>
+    env.program.methods
+      .fillLimitOrder()
+      .accounts({
+        pool: pool.pool,
+        ...orderAccounts(nonce),
+        owner: user.keypair.publicKey,
+        ownerOutputToken: user.tokenB,
+        keeper: env.payer.publicKey,
+        vaultAuthority: pool.vaultAuthority,
+        poolInputVault: pool.poolTokenAVault,
+        poolOutputVault: pool.poolTokenBVault,
+        inputMint: pool.tokenAMint,
+        outputMint: pool.tokenBMint,
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        observation: null,
+        protocolFeeVault: null,
+        inputTokenProgram: TOKEN_2022_PROGRAM_ID,
+        outputTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("fills the whole escrow, donation included, and closes it", async () => {
+    const connection = env.provider.connection;
+    await placeOrder(1, 1_000_000, 1);
+    const { order, escrow } = orderAccounts(1);
+
+    // Anyone can send tokens to the escrow; the fill must still close it
+    await transferChecked(
+      connection,
+      env.payer.payer,
+      user.tokenA,
+      pool.tokenAMint,
+      escrow,
+      user.keypair,
+      5_000,
+      9,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const vaultBefore = await tokenBalance(connection, pool.poolTokenAVault);
+    const outBefore = await tokenBalance(connection, user.tokenB);
+    await fillOrder(1);
+
+    expect(((await tokenBalance(connection, pool.poolTokenAVault)) - vaultBefore).toString()).to.equal("1005000");
+    expect((await tokenBalance(connection, user.tokenB)) > outBefore).to.be.true;
+    expect(await connection.getAccountInfo(escrow)).to.be.null;
+    expect(await connection.getAccountInfo(order)).to.be.null;
+  });
+
+  it("refuses a fill below the limit and refunds on cancel", async () => {
+    const connection = env.provider.connection;
+    const before = await tokenBalance(connection, user.tokenA);
+    await placeOrder(2, 1_000_000, 10_000_000);
+    await expectRejected(fillOrder(2), "LimitPriceNotReached");
+
+    const { order, escrow } = orderAccounts(2);
+    await env.program.methods
+      .cancelLimitOrder()
+      .accounts({
+        order,
+        escrow,
+        owner: user.keypair.publicKey,
+        ownerInputToken: user.tokenA,
+        inputMint: pool.tokenAMint,
+        inputTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([user.keypair])
+      .rpc();
+
+    expect((await tokenBalance(connection, user.tokenA)).toString()).to.equal(before.toString());
+    expect(await connection.getAccountInfo(order)).to.be.null;
+  });
+});
@@ -0,0 +1,74 @@
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  liquidityAccounts,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// Liquidity quotes run the deposit and withdrawal math without moving tokens,
+// so what they return is exactly what the real instruction does
+
+describe("liquidity quotes", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const balances = () =>
+    Promise.all([user.tokenA, user.tokenB, user.lpToken].map((account) => tokenBalance(env.provider.connection, account)));
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("quotes the deposit an unbalanced offer actually makes", async () => {
+    const quote = await env.program.methods
+      .quoteAddLiquidity(new BN(10_000_000), new BN(5_000_000))
+      .accounts({ pool: pool.pool, lpPosition: null })
+      .view();
+    // A 1:1 pool only takes as much A as the B offered matches
+    expect(quote.amountA.toNumber()).to.equal(5_000_000);
+    expect(quote.amountB.toNumber()).to.equal(5_000_000);
+
+    const [beforeA, beforeB, beforeLp] = await balances();
+    await env.program.methods
+      .addLiquidity(new BN(10_000_000), new BN(5_000_000), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+    const [afterA, afterB, afterLp] = await balances();
+
+    expect((beforeA - afterA).toString()).to.equal(quote.amountA.toString());
+    expect((beforeB - afterB).toString()).to.equal(quote.amountB.toString());
+    expect((afterLp - beforeLp).toString()).to.equal(quote.lpTokens.toString());
+  });
+
+  it("quotes what a withdrawal pays out", async () => {
+    const quote = await env.program.methods
+      .quoteRemoveLiquidity(new BN(3_000_000))
+      .accounts({ pool: pool.pool, lpPosition: null })
+      .view();
+    expect(quote.exitFeeA.toNumber()).to.equal(0);
+
+    const [beforeA, beforeB] = await balances();
+    await env.program.methods
+      .removeLiquidity(new BN(3_000_000), new BN(0), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+    const [afterA, afterB] = await balances();
+
+    expect((afterA - beforeA).toString()).to.equal(quote.amountA.toString());
+    expect((afterB - beforeB).toString()).to.equal(quote.amountB.toString());
+  });
+});
@@ -0,0 +1,81 @@
+import { Keypair, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getMint, getTokenGroupMemberState, getTokenGroupState } from "@solana/spl-token";
+import { expect } from "chai";
+import { AmmEnv, TestPool, createTestPool, expectRejected, setupAmm } from "./helpers";
+
+// The AMM can gather every LP mint under one Token-2022 token group: pools
+// created once the group exists join it straight away, and older pools can
+// join later. The group stays on the config for the suites running after
+// this one
+
+describe("LP token group", () => {
+  let env: AmmEnv;
+  let olderPool: TestPool;
+  const groupMint = Keypair.generate();
+
+  const initializeGroup = (authority: Keypair = env.payer.payer, mint: Keypair = groupMint) =>
+    env.program.methods
+      .initializeLpGroup(10_000)
+      .accounts({
+        ammConfig: env.ammConfig,
+        authority: authority.publicKey,
+        lpGroupMint: mint.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority, mint])
+      .rpc();
+
+  const joinGroup = (pool: TestPool) =>
+    env.program.methods
+      .joinLpGroup()
+      .accounts({
+        pool: pool.pool,
+        ammConfig: env.ammConfig,
+        lpGroupMint: groupMint.publicKey,
+        lpMint: pool.lpMint,
+        payer: env.payer.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const member = async (pool: TestPool) =>
+    getTokenGroupMemberState(await getMint(env.provider.connection, pool.lpMint, "confirmed", TOKEN_2022_PROGRAM_ID));
+
+  const groupSize = async () =>
+    getTokenGroupState(await getMint(env.provider.connection, groupMint.publicKey, "confirmed", TOKEN_2022_PROGRAM_ID)).size;
+
+  before(async () => {
+    env = await setupAmm();
+    olderPool = await createTestPool(env);
+  });
+
+  it("is created once, by the config authority", async () => {
+    await expectRejected(joinGroup(olderPool), "LpGroupMismatch");
+
+    const stranger = Keypair.generate();
+    const airdrop = await env.provider.connection.requestAirdrop(stranger.publicKey, 1_000_000_000);
+    await env.provider.connection.confirmTransaction(airdrop, "confirmed");
+    await expectRejected(initializeGroup(stranger), "InvalidConfigAuthority");
+
+    await initializeGroup();
+    const config = await env.program.account.ammConfig.fetch(env.ammConfig);
+    expect(config.lpGroupMint.equals(groupMint.publicKey)).to.be.true;
+    expect((await groupSize()).toString()).to.equal("0");
+    await expectRejected(initializeGroup(env.payer.payer, Keypair.generate()), "LpGroupAlreadyInitialized");
+  });
+
+  it("lets a pool created before the group join it", async () => {
+    await joinGroup(olderPool);
+    expect((await member(olderPool)).group.equals(groupMint.publicKey)).to.be.true;
+    expect((await groupSize()).toString()).to.equal("1");
+  });
+
+  it("adds new pools' LP mints as they are created", async () => {
+    const pool = await createTestPool(env);
+    const state = await member(pool);
+    expect(state.group.equals(groupMint.publicKey)).to.be.true;
+    expect(state.memberNumber.toString()).to.equal("2");
+  });
+});
@@ -0,0 +1,111 @@
+import { Keypair, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  createInitializeMetadataPointerInstruction,
+  createInitializeMintInstruction,
+  getMintLen,
+  getTokenMetadata,
+  tokenMetadataInitializeWithRentTransfer,
+  tokenMetadataUpdateFieldWithRentTransfer,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { AmmEnv, TestPool, createTestMint, createTestPool, setupAmm } from "./helpers";
+
+// LP mints carry Token-2022 metadata naming the pair and fee tier, from each
+// token's metadata symbol or the start of its address when it has none;
+// `update_lp_metadata` renames the LP token after a symbol changes
+
+describe("LP token metadata", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let symbolMint: PublicKey;
+
+  // Name the pool's LP mint should carry with `symbolMint` shown as `symbol`
+  const expectedName = (symbol: string) => {
+    const [a, b] = [pool.tokenAMint, pool.tokenBMint].map((mint) =>
+      mint.equals(symbolMint) ? symbol : mint.toBase58().slice(0, 4)
+    );
+    return `AMM LP: ${a}/${b} 30bps`;
+  };
+
+  before(async () => {
+    env = await setupAmm();
+    const connection = env.provider.connection;
+
+    const mintKeypair = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.MetadataPointer]);
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: env.payer.publicKey,
+          newAccountPubkey: mintKeypair.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeMetadataPointerInstruction(
+          mintKeypair.publicKey,
+          env.payer.publicKey,
+          mintKeypair.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mintKeypair.publicKey, 9, env.payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [env.payer.payer, mintKeypair],
+      { commitment: "confirmed" }
+    );
+    symbolMint = mintKeypair.publicKey;
+    await tokenMetadataInitializeWithRentTransfer(
+      connection,
+      env.payer.payer,
+      symbolMint,
+      env.payer.publicKey,
+      env.payer.payer,
+      "Old Dollar",
+      "OLD",
+      "https://tokens.example/old",
+      [],
+      { commitment: "confirmed" },
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    pool = await createTestPool(env, { mints: [symbolMint, await createTestMint(env)] });
+  });
+
+  it("names the LP token after the pair and fee tier", async () => {
+    const metadata = await getTokenMetadata(env.provider.connection, pool.lpMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(metadata.name).to.equal(expectedName("OLD"));
+    expect(metadata.symbol).to.equal("AMM-LP");
+  });
+
+  it("renames the LP token once a pool token's symbol changes", async () => {
+    await tokenMetadataUpdateFieldWithRentTransfer(
+      env.provider.connection,
+      env.payer.payer,
+      symbolMint,
+      env.payer.payer,
+      "symbol",
+      "NEWDOLLAR",
+      [],
+      { commitment: "confirmed" },
+      TOKEN_2022_PROGRAM_ID
+    );
+    await env.program.methods
+      .updateLpMetadata()
+      .accounts({
+        pool: pool.pool,
+        lpMint: pool.lpMint,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        payer: env.payer.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const metadata = await getTokenMetadata(env.provider.connection, pool.lpMint, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(metadata.name).to.equal(expectedName("NEWDOLLAR"));
+  });
+});
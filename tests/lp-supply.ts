@@ -0,0 +1,87 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  getMint,
+  getOrCreateAssociatedTokenAccount,
+  transferChecked,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// Withdrawals burn their LP tokens, and `reconcile_lp_supply` burns LP
+// tokens stranded in pool-owned accounts and resyncs the recorded supply
+
+describe("LP supply", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const lpMintSupply = async () =>
+    (await getMint(env.provider.connection, pool.lpMint, "confirmed", TOKEN_2022_PROGRAM_ID)).supply;
+
+  const reconcile = (strayLpToken: PublicKey | null, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .reconcileLpSupply()
+      .accounts({
+        pool: pool.pool,
+        authority: authority.publicKey,
+        vaultAuthority: pool.vaultAuthority,
+        lpMint: pool.lpMint,
+        strayLpToken,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("burns the LP tokens a withdrawal redeems", async () => {
+    const supplyBefore = await lpMintSupply();
+    await env.program.methods
+      .removeLiquidity(new BN(1_000_000), new BN(0), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+    expect((supplyBefore - (await lpMintSupply())).toString()).to.equal("1000000");
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    expect(state.totalLpSupply.toString()).to.equal((await lpMintSupply()).toString());
+  });
+
+  it("burns stray LP tokens held by the pool and resyncs the supply", async () => {
+    const connection = env.provider.connection;
+    const stray = await getOrCreateAssociatedTokenAccount(
+      connection, env.payer.payer, pool.lpMint, pool.vaultAuthority, true, "confirmed", undefined, TOKEN_2022_PROGRAM_ID
+    );
+    await transferChecked(
+      connection, env.payer.payer, user.lpToken, pool.lpMint, stray.address, user.keypair, 500_000, 6, [], undefined, TOKEN_2022_PROGRAM_ID
+    );
+
+    await expectRejected(reconcile(stray.address, Keypair.generate()), "InvalidPoolAuthority");
+    const supplyBefore = await lpMintSupply();
+    await reconcile(stray.address);
+
+    expect((await tokenBalance(connection, stray.address)).toString()).to.equal("0");
+    expect((supplyBefore - (await lpMintSupply())).toString()).to.equal("500000");
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    expect(state.totalLpSupply.toString()).to.equal((await lpMintSupply()).toString());
+  });
+});
@@ -0,0 +1,97 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  createInitializeInterestBearingMintInstruction,
+  createInitializeMintInstruction,
+  getMintLen,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  expectRejected,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// The LP value quote prices a holding in both raw and UI amounts; for an
+// interest-bearing mint the UI amount carries the accrued interest and the
+// quote reports the current rate
+
+describe("LP value quotes", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let interestMint: PublicKey;
+
+  const quote = (lpAmount: BN) =>
+    env.program.methods
+      .quoteLpValue(lpAmount)
+      .accounts({ pool: pool.pool, tokenAMint: pool.tokenAMint, tokenBMint: pool.tokenBMint })
+      .view();
+
+  before(async () => {
+    env = await setupAmm();
+    const connection = env.provider.connection;
+
+    // 5% a year, accruing from creation
+    const mintKeypair = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.InterestBearingConfig]);
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: env.payer.publicKey,
+          newAccountPubkey: mintKeypair.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeInterestBearingMintInstruction(mintKeypair.publicKey, env.payer.publicKey, 500, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mintKeypair.publicKey, 9, env.payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [env.payer.payer, mintKeypair],
+      { commitment: "confirmed" }
+    );
+    interestMint = mintKeypair.publicKey;
+
+    pool = await createTestPool(env, { mints: [interestMint, await createTestMint(env)] });
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("values a holding at its pro-rata share of the reserves", async () => {
+    const lpAmount = new BN((await tokenBalance(env.provider.connection, user.lpToken)).toString());
+    const value = await quote(lpAmount);
+
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    expect(value.tokenAAmount.eq(state.tokenAReserve.mul(lpAmount).div(state.totalLpSupply))).to.be.true;
+    expect(value.tokenBAmount.eq(state.tokenBReserve.mul(lpAmount).div(state.totalLpSupply))).to.be.true;
+  });
+
+  it("scales the interest-bearing side's UI amount and reports its rate", async () => {
+    const value = await quote(new BN(10_000_000));
+    const sides = [
+      { amount: value.tokenAAmount, ui: value.tokenAUi, rate: value.interestRateABps },
+      { amount: value.tokenBAmount, ui: value.tokenBUi, rate: value.interestRateBBps },
+    ];
+    const [interest, plain] = pool.tokenAMint.equals(interestMint) ? sides : sides.reverse();
+
+    expect(interest.rate).to.equal(500);
+    expect(parseFloat(interest.ui)).to.be.at.least(interest.amount.toNumber() / 1e9);
+    expect(plain.rate).to.be.null;
+    expect(parseFloat(plain.ui)).to.equal(plain.amount.toNumber() / 1e9);
+  });
+
+  it("refuses more LP tokens than exist", async () => {
+    const { totalLpSupply } = await env.program.account.ammPool.fetch(pool.pool);
+    await expectRejected(quote(totalLpSupply.addn(1)), "InsufficientLPTokens");
+  });
+});
@@ -0,0 +1,61 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// The pool authority can cap a single swap's input at a share of the input
+// reserve; anything larger is refused outright
+
+describe("max swap size", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const setMaxSwapSize = (maxSwapBps: number, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .setMaxSwapSize(new BN(maxSwapBps))
+      .accounts({ pool: pool.pool, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const swap = (amountIn: number) =>
+    env.program.methods
+      .swap(new BN(amountIn), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("only takes a cap of at most 100% from the pool authority", async () => {
+    await expectRejected(setMaxSwapSize(10_001), "InvalidMaxSwapSize");
+    await expectRejected(setMaxSwapSize(100, Keypair.generate()), "InvalidPoolAuthority");
+  });
+
+  it("refuses swaps above the cap", async () => {
+    // 1% of the 100M input reserve
+    await setMaxSwapSize(100);
+    await expectRejected(swap(1_000_001), "SwapTooLarge");
+    await swap(1_000_000);
+  });
+
+  it("lifts the cap when set back to zero", async () => {
+    await setMaxSwapSize(0);
+    await swap(5_000_000);
+  });
+});
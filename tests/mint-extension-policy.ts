@@ -0,0 +1,88 @@
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  createInitializeMintInstruction,
+  createInitializePermanentDelegateInstruction,
+  getMintLen,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { AmmEnv, createTestMint, createTestPool, creatorRecordAddress, expectRejected, setupAmm } from "./helpers";
+
+// Anyone registered as a pool creator can open a pool, but only once per
+// pair and fee tier, and mints with dangerous extensions are refused unless
+// the AMM config allows them. The suite resets the policy when done
+
+describe("permissionless pool creation", () => {
+  let env: AmmEnv;
+  let delegatedMint: PublicKey;
+  const creator = Keypair.generate();
+
+  const setPolicy = (allowPermanentDelegate: boolean, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .setMintExtensionPolicy(allowPermanentDelegate, false, false)
+      .accounts({ ammConfig: env.ammConfig, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    const connection = env.provider.connection;
+    const airdrop = await connection.requestAirdrop(creator.publicKey, 5 * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdrop, "confirmed");
+    await env.program.methods
+      .registerPoolCreator()
+      .accounts({
+        creatorRecord: creatorRecordAddress(env, creator.publicKey),
+        creator: creator.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+
+    // A mint whose permanent delegate could move tokens out of any vault
+    const mintKeypair = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.PermanentDelegate]);
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: env.payer.publicKey,
+          newAccountPubkey: mintKeypair.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializePermanentDelegateInstruction(mintKeypair.publicKey, env.payer.publicKey, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mintKeypair.publicKey, 9, env.payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [env.payer.payer, mintKeypair],
+      { commitment: "confirmed" }
+    );
+    delegatedMint = mintKeypair.publicKey;
+  });
+
+  after(async () => {
+    await setPolicy(false);
+  });
+
+  it("lets any registered creator open a pool, once per pair and tier", async () => {
+    const mints = [await createTestMint(env), await createTestMint(env)];
+    const pool = await createTestPool(env, { mints, authority: creator });
+    expect((await env.program.account.ammPool.fetch(pool.pool)).authority.equals(creator.publicKey)).to.be.true;
+
+    await expectRejected(createTestPool(env, { mints }), "already in use");
+    await createTestPool(env, { mints, feeTier: 100 });
+  });
+
+  it("refuses a permanent delegate mint until the config allows it", async () => {
+    const mints = [delegatedMint, await createTestMint(env)];
+    await expectRejected(createTestPool(env, { mints, acknowledgePermanentDelegate: true }), "PermanentDelegateNotAllowed");
+
+    await expectRejected(setPolicy(true, creator), "InvalidConfigAuthority");
+    await setPolicy(true);
+    // Allowed by policy, the creator must still acknowledge the delegate
+    await expectRejected(createTestPool(env, { mints }), "PermanentDelegateNotAcknowledged");
+    await createTestPool(env, { mints, acknowledgePermanentDelegate: true });
+  });
+});
@@ -0,0 +1,74 @@
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { AmmEnv, TestPool, createTestPool, expectRejected, setupAmm } from "./helpers";
+
+// Anyone can write an observation into a quiet pool for a small cut of its
+// protocol fees, but only once the crank interval has passed since the last
+// one. The interval is five minutes, so only the early crank is run here
+
+describe("observation crank", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let observation: PublicKey;
+  let feeVaultA: PublicKey;
+  let feeVaultB: PublicKey;
+  const cranker = Keypair.generate();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    [observation] = PublicKey.findProgramAddressSync(
+      [Buffer.from("observation"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+    await env.program.methods
+      .initializeObservation()
+      .accounts({ pool: pool.pool, observation, payer: env.payer.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+
+    [feeVaultA, feeVaultB] = [pool.tokenAMint, pool.tokenBMint].map(
+      (mint) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("protocol_fee_vault"), pool.pool.toBuffer(), mint.toBuffer()],
+          env.program.programId
+        )[0]
+    );
+    await env.program.methods
+      .initializeProtocolFeeVaults()
+      .accounts({
+        pool: pool.pool,
+        payer: env.payer.publicKey,
+        ammConfig: env.ammConfig,
+        vaultAuthority: pool.vaultAuthority,
+        protocolFeeVaultA: feeVaultA,
+        protocolFeeVaultB: feeVaultB,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("refuses a crank right after the last observation", async () => {
+    const crankerToken = await getOrCreateAssociatedTokenAccount(
+      env.provider.connection, env.payer.payer, pool.tokenAMint, cranker.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    const crank = env.program.methods
+      .crankObservation()
+      .accounts({
+        pool: pool.pool,
+        observation,
+        vaultAuthority: pool.vaultAuthority,
+        protocolFeeVault: feeVaultA,
+        rewardMint: pool.tokenAMint,
+        crankerToken: crankerToken.address,
+        cranker: cranker.publicKey,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([cranker])
+      .rpc();
+    await expectRejected(crank, "ObservationCrankTooSoon");
+  });
+});
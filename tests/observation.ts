@@ -0,0 +1,73 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// Once a pool has an observation every swap must update it, and `observe`
+// serves cumulative prices that difference into a TWAP
+
+describe("TWAP observations", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let observation: PublicKey;
+  const parity = new BN(1).shln(64);
+
+  const swap = (withObservation: boolean) =>
+    env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user, { observation: withObservation ? observation : null }))
+      .signers([user.keypair])
+      .rpc();
+
+  const observe = (secondsAgos: number[]) =>
+    env.program.methods.observe(secondsAgos).accounts({ pool: pool.pool, observation }).view();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+    [observation] = PublicKey.findProgramAddressSync(
+      [Buffer.from("observation"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  it("makes every swap carry the observation once enabled", async () => {
+    await env.program.methods
+      .initializeObservation()
+      .accounts({ pool: pool.pool, observation, payer: env.payer.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+    expect((await env.program.account.ammPool.fetch(pool.pool)).observation.equals(observation)).to.be.true;
+
+    await expectRejected(swap(false), "MissingObservation");
+    await swap(true);
+  });
+
+  it("serves a TWAP near the pool's price", async () => {
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+    const [start, end] = await observe([2, 0]);
+    expect(end.timestamp.toNumber() - start.timestamp.toNumber()).to.equal(2);
+
+    // A 1% trade barely moves a 1:1 pool off parity
+    const twap = end.priceACumulativeX64.sub(start.priceACumulativeX64).divn(2);
+    expect(twap.gt(parity.muln(95).divn(100)) && twap.lt(parity.muln(105).divn(100))).to.be.true;
+  });
+
+  it("refuses points before its history or an empty request", async () => {
+    await expectRejected(observe([3_600]), "ObservationTooOld");
+    await expectRejected(observe([]), "InvalidObservePoints");
+  });
+});
@@ -0,0 +1,102 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+} from "./helpers";
+
+// A pair with a registered oracle can only be seeded near the oracle's
+// fresh price; the suites run with the check off, so this one restores it
+
+describe("oracle admission", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let oracle: Keypair;
+  let pairOracle: PublicKey;
+  const parity = new BN(1).shln(64);
+
+  const setAdmission = (bandBps: number, maxStaleness: number, authority?: Keypair) =>
+    env.program.methods
+      .setOracleAdmission(new BN(bandBps), new BN(maxStaleness))
+      .accounts({ globalConfig: env.globalConfig, authority: (authority ?? env.payer).publicKey })
+      .signers(authority ? [authority] : [])
+      .rpc();
+
+  const publish = (priceX64: BN, signer: Keypair = oracle) =>
+    env.program.methods
+      .publishPairPrice(priceX64)
+      .accounts({ pairOracle, oracle: signer.publicKey })
+      .signers([signer])
+      .rpc();
+
+  const seed = (amountA: number, amountB: number) =>
+    env.program.methods
+      .addLiquidity(new BN(amountA), new BN(amountB), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    oracle = Keypair.generate();
+    [pairOracle] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pair_oracle"), pool.tokenAMint.toBuffer(), pool.tokenBMint.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  after(async () => {
+    await setAdmission(0, 0);
+  });
+
+  it("refuses a band above 100% or one without a staleness bound", async () => {
+    await expectRejected(setAdmission(10_001, 60), "InvalidOracleAdmission");
+    await expectRejected(setAdmission(100, 0), "InvalidOracleAdmission");
+    await expectRejected(setAdmission(100, 60, Keypair.generate()), "InvalidConfigAuthority");
+    await setAdmission(100, 60);
+  });
+
+  it("only takes prices from the pair's oracle", async () => {
+    await env.program.methods
+      .registerPairOracle(oracle.publicKey)
+      .accounts({
+        globalConfig: env.globalConfig,
+        pairOracle,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await expectRejected(seed(10_000_000, 10_000_000), "StaleOraclePrice");
+    await expectRejected(publish(parity, Keypair.generate()), "InvalidOracleSigner");
+    await expectRejected(publish(new BN(0)), "InvalidOraclePrice");
+    await publish(parity);
+
+    const state = await env.program.account.pairOracle.fetch(pairOracle);
+    expect(state.priceX64.eq(parity)).to.be.true;
+    expect(state.publishedAt.toNumber()).to.be.greaterThan(0);
+  });
+
+  it("only admits a seeding deposit priced within the band", async () => {
+    await expectRejected(seed(10_000_000, 20_000_000), "InitialPriceOutOfBand");
+    await seed(10_000_000, 10_050_000);
+
+    // Only the seeding deposit is checked
+    await publish(parity.muln(3));
+    await addLiquidity(env, pool, user, 1_000_000);
+  });
+});
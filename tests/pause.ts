@@ -0,0 +1,81 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// The emergency authority can pause one pool or every pool; a paused pool
+// still lets LPs withdraw. This suite leaves the AMM unpaused
+
+describe("emergency pauses", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const setPoolPaused = (paused: boolean, authority: Keypair = env.payer.payer) =>
+    env.program.methods[paused ? "pausePool" : "unpausePool"]()
+      .accounts({ globalConfig: env.globalConfig, pool: pool.pool, emergencyAuthority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const setGlobalPause = (paused: boolean, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .setGlobalPause(paused)
+      .accounts({ globalConfig: env.globalConfig, ammConfig: env.ammConfig, emergencyAuthority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const swap = () =>
+    env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  after(async () => {
+    await setGlobalPause(false);
+  });
+
+  it("only takes pauses from the emergency authority", async () => {
+    await expectRejected(setPoolPaused(true, Keypair.generate()), "InvalidEmergencyAuthority");
+    await expectRejected(setGlobalPause(true, Keypair.generate()), "InvalidEmergencyAuthority");
+  });
+
+  it("halts swaps and deposits on a paused pool but lets LPs withdraw", async () => {
+    await setPoolPaused(true);
+    await expectRejected(swap(), "PoolPaused");
+    await expectRejected(addLiquidity(env, pool, user, 1_000_000), "PoolPaused");
+    await env.program.methods
+      .removeLiquidity(new BN(1_000_000), new BN(0), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+    await setPoolPaused(false);
+    await swap();
+  });
+
+  it("halts every pool under a global pause", async () => {
+    await setGlobalPause(true);
+    await expectRejected(swap(), "AmmPaused");
+    await setGlobalPause(false);
+    await swap();
+  });
+});
@@ -0,0 +1,161 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  liquidityAccounts,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// Escrowed deposits: executing one takes only what the pool ratio allows
+// and refunds the rest; cancelling one refunds everything
+
+describe("pending deposits", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const pendingDepositAccounts = (nonce: number) => {
+    const [pendingDeposit] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pending_deposit"),
+        pool.pool.toBuffer(),
+        user.keypair.publicKey.toBuffer(),
+        new BN(nonce).toArrayLike(Buffer, "le", 8),
+      ],
+      env.program.programId
+    );
+    const [escrowA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pending_deposit_a"), pendingDeposit.toBuffer()],
+      env.program.programId
+    );
+    const [escrowB] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pending_deposit_b"), pendingDeposit.toBuffer()],
+      env.program.programId
+    );
+    return { pendingDeposit, escrowA, escrowB };
+  };
+
+  const createPendingDeposit = (nonce: number, amountA: number, amountB: number) =>
+    env.program.methods
+      .createPendingDeposit(new BN(amountA), new BN(amountB), new BN(0), new BN(nonce))
+      .accounts({
+        pool: pool.pool,
+        user: user.keypair.publicKey,
+        ...pendingDepositAccounts(nonce),
+        userTokenA: user.tokenA,
+        userTokenB: user.tokenB,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("executes at the pool ratio and refunds the excess", async () => {
+    const connection = env.provider.connection;
+    const userABefore = await tokenBalance(connection, user.tokenA);
+    const userBBefore = await tokenBalance(connection, user.tokenB);
+    const vaultABefore = await tokenBalance(connection, pool.poolTokenAVault);
+    const vaultBBefore = await tokenBalance(connection, pool.poolTokenBVault);
+    const lpBefore = await tokenBalance(connection, user.lpToken);
+
+    // Three times as much B as the 1:1 pool can take
+    await createPendingDeposit(1, 1_000_000, 3_000_000);
+    const { pendingDeposit, escrowA, escrowB } = pendingDepositAccounts(1);
+    const { launchGuard, globalConfig, pairOracle, tokenProgram } = liquidityAccounts(env, pool, user);
+
+    await env.program.methods
+      .executePendingDeposit()
+      .accounts({
+        pool: pool.pool,
+        owner: user.keypair.publicKey,
+        pendingDeposit,
+        escrowA,
+        escrowB,
+        userTokenA: user.tokenA,
+        userTokenB: user.tokenB,
+        poolTokenAVault: pool.poolTokenAVault,
+        poolTokenBVault: pool.poolTokenBVault,
+        userLpToken: user.lpToken,
+        lpMint: pool.lpMint,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        launchGuard,
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        globalConfig,
+        pairOracle,
+        tokenProgram,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([user.keypair])
+      .rpc();
+
+    const usedA = (await tokenBalance(connection, pool.poolTokenAVault)) - vaultABefore;
+    const usedB = (await tokenBalance(connection, pool.poolTokenBVault)) - vaultBBefore;
+    expect(usedA.toString()).to.equal("1000000");
+    expect(Number(usedB)).to.be.closeTo(1_000_000, 1);
+
+    // The user is only out what went into the vaults
+    expect((userABefore - (await tokenBalance(connection, user.tokenA))).toString()).to.equal(usedA.toString());
+    expect((userBBefore - (await tokenBalance(connection, user.tokenB))).toString()).to.equal(usedB.toString());
+    expect((await tokenBalance(connection, user.lpToken)) > lpBefore).to.be.true;
+
+    // Escrows and the pending deposit are closed
+    expect(await connection.getAccountInfo(pendingDeposit)).to.be.null;
+    expect(await connection.getAccountInfo(escrowA)).to.be.null;
+    expect(await connection.getAccountInfo(escrowB)).to.be.null;
+  });
+
+  it("refunds both legs in full on cancel", async () => {
+    const connection = env.provider.connection;
+    const userABefore = await tokenBalance(connection, user.tokenA);
+    const userBBefore = await tokenBalance(connection, user.tokenB);
+
+    await createPendingDeposit(2, 500_000, 700_000);
+    expect((userABefore - (await tokenBalance(connection, user.tokenA))).toString()).to.equal("500000");
+
+    const { pendingDeposit, escrowA, escrowB } = pendingDepositAccounts(2);
+    await env.program.methods
+      .cancelPendingDeposit()
+      .accounts({
+        pool: pool.pool,
+        owner: user.keypair.publicKey,
+        pendingDeposit,
+        escrowA,
+        escrowB,
+        userTokenA: user.tokenA,
+        userTokenB: user.tokenB,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([user.keypair])
+      .rpc();
+
+    expect((await tokenBalance(connection, user.tokenA)).toString()).to.equal(userABefore.toString());
+    expect((await tokenBalance(connection, user.tokenB)).toString()).to.equal(userBBefore.toString());
+    expect(await connection.getAccountInfo(pendingDeposit)).to.be.null;
+  });
+});
@@ -0,0 +1,388 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Token2022Amm } from "../target/types/token2022_amm";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  LAMPORTS_PER_SOL,
+} from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("Pool vault and mint validation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const ammProgram = anchor.workspace.Token2022Amm as Program<Token2022Amm>;
+  const payer = provider.wallet as anchor.Wallet;
+  const connection = provider.connection;
+
+  const FEE_TIER = 30;
+
+  const [ammConfig] = PublicKey.findProgramAddressSync(
+    [Buffer.from("amm_config")],
+    ammProgram.programId
+  );
+  const [globalConfig] = PublicKey.findProgramAddressSync(
+    [Buffer.from("global_config")],
+    ammProgram.programId
+  );
+  const [creatorRecord] = PublicKey.findProgramAddressSync(
+    [Buffer.from("pool_creator"), payer.publicKey.toBuffer()],
+    ammProgram.programId
+  );
+
+  let whitelist: PublicKey;
+  let lpGroupMint: PublicKey | null;
+  let tokenAMint: PublicKey;
+  let tokenBMint: PublicKey;
+  let otherMint: PublicKey;
+  let pool: PublicKey;
+  let vaultAuthority: PublicKey;
+  let poolTokenAVault: PublicKey;
+  let poolTokenBVault: PublicKey;
+  let lpMint: PublicKey;
+
+  let user: Keypair;
+  let userTokenA: PublicKey;
+  let userTokenB: PublicKey;
+  let userLpToken: PublicKey;
+
+  // Token accounts of the right mints that the attacker owns
+  let spoofedVaultA: PublicKey;
+  let spoofedVaultB: PublicKey;
+
+  const deadline = () => new BN(Math.floor(Date.now() / 1000) + 60);
+
+  const expectRejected = async (call: Promise<unknown>, error: string) => {
+    try {
+      await call;
+      expect.fail(`Should have been rejected with ${error}`);
+    } catch (err) {
+      expect(err.toString()).to.include(error);
+    }
+  };
+
+  const swapAccounts = (overrides: Record<string, PublicKey> = {}) => ({
+    pool,
+    user: user.publicKey,
+    userTokenA,
+    userTokenB,
+    vaultAuthority,
+    poolTokenAVault,
+    poolTokenBVault,
+    tokenAMint,
+    tokenBMint,
+    ammConfig,
+    whitelist,
+    tokenAHookCache: null,
+    tokenBHookCache: null,
+    takerAllowlist: null,
+    outputFailureLog: null,
+    observation: null,
+    protocolFeeVault: null,
+    tokenAProgram: TOKEN_2022_PROGRAM_ID,
+    tokenBProgram: TOKEN_2022_PROGRAM_ID,
+    ...overrides,
+  });
+
+  const liquidityAccounts = (overrides: Record<string, PublicKey> = {}) => ({
+    pool,
+    user: user.publicKey,
+    userTokenA,
+    userTokenB,
+    userLpToken,
+    vaultAuthority,
+    poolTokenAVault,
+    poolTokenBVault,
+    lpMint,
+    tokenAMint,
+    tokenBMint,
+    ammConfig,
+    whitelist,
+    launchGuard: null,
+    globalConfig,
+    pairOracle: PublicKey.findProgramAddressSync(
+      [Buffer.from("pair_oracle"), tokenAMint.toBuffer(), tokenBMint.toBuffer()],
+      ammProgram.programId
+    )[0],
+    lpPosition: null,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+    tokenAProgram: TOKEN_2022_PROGRAM_ID,
+    tokenBProgram: TOKEN_2022_PROGRAM_ID,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+    ...overrides,
+  });
+
+  const swap = (overrides: Record<string, PublicKey> = {}) =>
+    ammProgram.methods
+      .swap(new BN(1_000), new BN(0), { a: {} }, null, deadline())
+      .accounts(swapAccounts(overrides))
+      .signers([user])
+      .rpc();
+
+  const addLiquidity = (overrides: Record<string, PublicKey> = {}, amount = 1_000_000) =>
+    ammProgram.methods
+      .addLiquidity(new BN(amount), new BN(amount), new BN(0), deadline())
+      .accounts(liquidityAccounts(overrides))
+      .signers([user])
+      .rpc();
+
+  const removeLiquidity = (overrides: Record<string, PublicKey> = {}) =>
+    ammProgram.methods
+      .removeLiquidity(new BN(1_000), new BN(0), new BN(0), deadline())
+      .accounts(liquidityAccounts(overrides))
+      .signers([user])
+      .rpc();
+
+  before(async () => {
+    user = Keypair.generate();
+    const airdrop = await connection.requestAirdrop(user.publicKey, 2 * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdrop, "confirmed");
+
+    // The global PDAs may already exist when other suites ran first
+    if (!(await connection.getAccountInfo(globalConfig))) {
+      await ammProgram.methods
+        .initializeGlobalConfig(new BN(0), 1_000)
+        .accounts({ globalConfig, authority: payer.publicKey, systemProgram: SystemProgram.programId })
+        .rpc();
+    }
+    if (!(await connection.getAccountInfo(ammConfig))) {
+      const whitelistAccount = Keypair.generate();
+      await ammProgram.methods
+        .initializeWhitelist()
+        .accounts({
+          whitelist: whitelistAccount.publicKey,
+          authority: payer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([whitelistAccount])
+        .rpc();
+      await ammProgram.methods
+        .initialize()
+        .accounts({
+          ammConfig,
+          whitelist: whitelistAccount.publicKey,
+          authority: payer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+    if (!(await connection.getAccountInfo(creatorRecord))) {
+      await ammProgram.methods
+        .registerPoolCreator()
+        .accounts({ creatorRecord, creator: payer.publicKey, systemProgram: SystemProgram.programId })
+        .rpc();
+    }
+    const config = await ammProgram.account.ammConfig.fetch(ammConfig);
+    whitelist = config.whitelist;
+    // New LP mints must join the LP group once another suite has created it
+    lpGroupMint = config.lpGroupMint.equals(PublicKey.default) ? null : config.lpGroupMint;
+
+    const mints = [];
+    for (let i = 0; i < 3; i++) {
+      mints.push(
+        await createMint(connection, payer.payer, payer.publicKey, null, 9, Keypair.generate(), undefined, TOKEN_2022_PROGRAM_ID)
+      );
+    }
+    [tokenAMint, tokenBMint] = mints.slice(0, 2).sort((a, b) => Buffer.compare(a.toBuffer(), b.toBuffer()));
+    otherMint = mints[2];
+
+    [pool] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pool"),
+        tokenAMint.toBuffer(),
+        tokenBMint.toBuffer(),
+        new BN(FEE_TIER).toArrayLike(Buffer, "le", 8),
+      ],
+      ammProgram.programId
+    );
+    [vaultAuthority] = PublicKey.findProgramAddressSync(
+      [pool.toBuffer(), Buffer.from("vault_auth")],
+      ammProgram.programId
+    );
+
+    const vaultA = Keypair.generate();
+    const vaultB = Keypair.generate();
+    const lpMintKeypair = Keypair.generate();
+    await ammProgram.methods
+      .initializePool(new BN(FEE_TIER), { constantProduct: {} }, false)
+      .accounts({
+        pool,
+        authority: payer.publicKey,
+        globalConfig,
+        ammConfig,
+        whitelist,
+        creatorRecord,
+        tokenAMint,
+        tokenBMint,
+        vaultAuthority,
+        tokenAVault: vaultA.publicKey,
+        tokenBVault: vaultB.publicKey,
+        lpMint: lpMintKeypair.publicKey,
+        lpGroupMint,
+        freezeAuthorityA: null,
+        freezeAuthorityB: null,
+        creatorWsol: null,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+        associatedTokenProgram: null,
+      })
+      .signers([vaultA, vaultB, lpMintKeypair])
+      .rpc();
+    poolTokenAVault = vaultA.publicKey;
+    poolTokenBVault = vaultB.publicKey;
+    lpMint = lpMintKeypair.publicKey;
+
+    for (const mint of [tokenAMint, tokenBMint]) {
+      const account = await getOrCreateAssociatedTokenAccount(
+        connection, payer.payer, mint, user.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(connection, payer.payer, mint, account.address, payer.publicKey, 1_000_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+    }
+    userTokenA = getAssociatedTokenAddressSync(tokenAMint, user.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    userTokenB = getAssociatedTokenAddressSync(tokenBMint, user.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    userLpToken = getAssociatedTokenAddressSync(lpMint, user.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+    spoofedVaultA = await createAccount(connection, payer.payer, tokenAMint, user.publicKey, Keypair.generate(), undefined, TOKEN_2022_PROGRAM_ID);
+    spoofedVaultB = await createAccount(connection, payer.payer, tokenBMint, user.publicKey, Keypair.generate(), undefined, TOKEN_2022_PROGRAM_ID);
+
+    // Seed the pool so the spoofing attempts below fail on their accounts
+    // rather than on an empty pool
+    await addLiquidity({}, 100_000_000);
+  });
+
+  describe("Swap", () => {
+    it("Rejects a vault the pool does not own", async () => {
+      await expectRejected(swap({ poolTokenAVault: spoofedVaultA }), "InvalidVault");
+      await expectRejected(swap({ poolTokenBVault: spoofedVaultB }), "InvalidVault");
+    });
+
+    it("Rejects the pool's vaults passed as B and A", async () => {
+      await expectRejected(
+        swap({ poolTokenAVault: poolTokenBVault, poolTokenBVault: poolTokenAVault }),
+        "InvalidVault"
+      );
+    });
+
+    it("Rejects a mint outside the pair", async () => {
+      await expectRejected(swap({ tokenBMint: otherMint }), "InvalidTokenPair");
+    });
+
+    it("Rejects the pair's mints passed as B and A", async () => {
+      await expectRejected(swap({ tokenAMint: tokenBMint, tokenBMint: tokenAMint }), "InvalidTokenPair");
+    });
+
+    it("Rejects swapped vaults on a directional exact-in swap", async () => {
+      await expectRejected(
+        ammProgram.methods
+          .swapExactTokensForTokens(new BN(1_000), new BN(0), null, deadline())
+          .accounts({
+            pool,
+            user: user.publicKey,
+            userInputToken: userTokenA,
+            userOutputToken: userTokenB,
+            vaultAuthority,
+            poolInputVault: poolTokenBVault,
+            poolOutputVault: poolTokenAVault,
+            inputMint: tokenAMint,
+            outputMint: tokenBMint,
+            ammConfig,
+            whitelist,
+            inputHookCache: null,
+            outputHookCache: null,
+            takerAllowlist: null,
+            outputFailureLog: null,
+            observation: null,
+            protocolFeeVault: null,
+            inputTokenProgram: TOKEN_2022_PROGRAM_ID,
+            outputTokenProgram: TOKEN_2022_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc(),
+        "InvalidVault"
+      );
+    });
+  });
+
+  describe("Add liquidity", () => {
+    it("Rejects a vault the pool does not own", async () => {
+      await expectRejected(addLiquidity({ poolTokenAVault: spoofedVaultA }), "InvalidVault");
+      await expectRejected(addLiquidity({ poolTokenBVault: spoofedVaultB }), "InvalidVault");
+    });
+
+    it("Rejects the pool's vaults passed as B and A", async () => {
+      await expectRejected(
+        addLiquidity({ poolTokenAVault: poolTokenBVault, poolTokenBVault: poolTokenAVault }),
+        "InvalidVault"
+      );
+    });
+
+    it("Rejects a mint outside the pair", async () => {
+      await expectRejected(addLiquidity({ tokenAMint: otherMint }), "InvalidTokenPair");
+    });
+
+    it("Rejects an LP mint the pool did not create", async () => {
+      await expectRejected(addLiquidity({ lpMint: otherMint }), "InvalidLpMint");
+    });
+  });
+
+  describe("Remove liquidity", () => {
+    it("Rejects a vault the pool does not own", async () => {
+      await expectRejected(removeLiquidity({ poolTokenAVault: spoofedVaultA }), "InvalidVault");
+      await expectRejected(removeLiquidity({ poolTokenBVault: spoofedVaultB }), "InvalidVault");
+    });
+
+    it("Rejects the pool's vaults passed as B and A", async () => {
+      await expectRejected(
+        removeLiquidity({ poolTokenAVault: poolTokenBVault, poolTokenBVault: poolTokenAVault }),
+        "InvalidVault"
+      );
+    });
+
+    it("Rejects a mint outside the pair", async () => {
+      await expectRejected(removeLiquidity({ tokenBMint: otherMint }), "InvalidTokenPair");
+    });
+
+    it("Rejects an LP mint the pool did not create", async () => {
+      await expectRejected(removeLiquidity({ lpMint: otherMint }), "InvalidLpMint");
+    });
+
+    it("Rejects a whitelist other than the pool's", async () => {
+      const otherWhitelist = Keypair.generate();
+      await ammProgram.methods
+        .initializeWhitelist()
+        .accounts({
+          whitelist: otherWhitelist.publicKey,
+          authority: payer.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([otherWhitelist])
+        .rpc();
+      await expectRejected(removeLiquidity({ whitelist: otherWhitelist.publicKey }), "WhitelistMismatch");
+    });
+
+    it("Still withdraws with the pool's own accounts", async () => {
+      const before = await ammProgram.account.ammPool.fetch(pool);
+      await removeLiquidity();
+      const after = await ammProgram.account.ammPool.fetch(pool);
+      expect(after.totalLpSupply.lt(before.totalLpSupply)).to.be.true;
+    });
+  });
+});
@@ -0,0 +1,45 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+import { AmmEnv, createTestPool, expectRejected, setupAmm } from "./helpers";
+
+// The global config rate-limits pool creation per creator; the suites run
+// with the cooldown off, so this one restores it when done
+
+describe("pool creation limits", () => {
+  let env: AmmEnv;
+
+  const setLimits = (cooldown: number, maxPoolsPerSlot: number, authority?: Keypair) =>
+    env.program.methods
+      .updatePoolCreationLimits(new BN(cooldown), maxPoolsPerSlot)
+      .accounts({ globalConfig: env.globalConfig, authority: (authority ?? env.payer).publicKey })
+      .signers(authority ? [authority] : [])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    await createTestPool(env);
+  });
+
+  after(async () => {
+    await setLimits(0, 1_000);
+  });
+
+  it("holds a creator to the cooldown since their last pool", async () => {
+    await setLimits(3_600, 1_000);
+    const config = await env.program.account.globalConfig.fetch(env.globalConfig);
+    expect(config.poolCreationCooldown.toNumber()).to.equal(3_600);
+
+    await expectRejected(createTestPool(env), "PoolCreationCooldown");
+    await setLimits(0, 1_000);
+    await createTestPool(env);
+  });
+
+  it("refuses an empty per-slot cap", async () => {
+    await expectRejected(setLimits(0, 0), "InvalidRateLimit");
+  });
+
+  it("only lets the config authority change the limits", async () => {
+    await expectRejected(setLimits(0, 1_000, Keypair.generate()), "InvalidConfigAuthority");
+  });
+});
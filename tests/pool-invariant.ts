@@ -0,0 +1,53 @@
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// The invariant per LP token only grows as swap fees compound into the
+// reserves, and the view recomputes it to match what each operation recorded
+
+describe("pool invariant metric", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const invariant = () => env.program.methods.poolInvariant().accounts({ pool: pool.pool }).view();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("starts at the seeding value", async () => {
+    const view = await invariant();
+    expect(view.sqrtK.toNumber()).to.equal(100_000_000);
+    expect(view.currentInvariantPerLpX64.eq(view.initialInvariantPerLpX64)).to.be.true;
+    expect(view.growthBps.toNumber()).to.equal(0);
+  });
+
+  it("grows with the fees of each swap and stays in step with the recorded value", async () => {
+    for (const inputSide of [{ a: {} }, { b: {} }, { a: {} }]) {
+      await env.program.methods
+        .swap(new BN(10_000_000), new BN(1), inputSide, null, deadline())
+        .accounts(swapAccounts(env, pool, user))
+        .signers([user.keypair])
+        .rpc();
+    }
+
+    const view = await invariant();
+    expect(view.currentInvariantPerLpX64.gt(view.initialInvariantPerLpX64)).to.be.true;
+    expect(view.currentInvariantPerLpX64.eq(view.recordedInvariantPerLpX64)).to.be.true;
+    expect(view.growthBps.toNumber()).to.be.greaterThan(0);
+  });
+});
@@ -0,0 +1,86 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  createTestPool,
+  expectRejected,
+  finalizeProposals,
+  proposeAndApprove,
+  setupAmm,
+  setupGovernance,
+} from "./helpers";
+
+// Generic proposals change pool parameters through governance, but only on
+// pools where the governance PDA holds the role
+
+describe("pool proposals", () => {
+  let env: AmmEnv;
+  let governanceConfig: PublicKey;
+  let governance: PublicKey;
+  let governedPool: TestPool;
+  let ownPool: TestPool;
+  let setFee: Keypair;
+  let rotate: Keypair;
+  let withdrawOnlyOwn: Keypair;
+
+  const execute = (proposal: Keypair, pool: TestPool) =>
+    env.program.methods
+      .executePoolProposal()
+      .accounts({
+        proposal: proposal.publicKey,
+        pool: pool.pool,
+        globalConfig: env.globalConfig,
+        protocolFeeVaultA: null,
+        protocolFeeVaultB: null,
+        dependency: null,
+      })
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    governanceConfig = await setupGovernance(env);
+    [governance] = PublicKey.findProgramAddressSync([Buffer.from("governance")], env.program.programId);
+
+    governedPool = await createTestPool(env);
+    ownPool = await createTestPool(env);
+    await env.program.methods
+      .setPoolAuthority(governance)
+      .accounts({ pool: governedPool.pool, authority: env.payer.publicKey })
+      .rpc();
+
+    const connection = env.provider.connection;
+    const voter = Keypair.generate();
+    const airdrop = await connection.requestAirdrop(voter.publicKey, 5 * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdrop, "confirmed");
+
+    setFee = Keypair.generate();
+    rotate = Keypair.generate();
+    withdrawOnlyOwn = Keypair.generate();
+    await proposeAndApprove(env, governanceConfig, setFee, voter, {
+      setFeeRate: { pool: governedPool.pool, feeRate: new BN(50) },
+    });
+    await proposeAndApprove(env, governanceConfig, rotate, voter, {
+      rotatePoolAuthority: { pool: governedPool.pool, newAuthority: env.payer.publicKey },
+    });
+    await proposeAndApprove(env, governanceConfig, withdrawOnlyOwn, voter, {
+      setPoolWithdrawOnly: { pool: ownPool.pool, withdrawOnly: true },
+    });
+    await finalizeProposals(env, governanceConfig, [setFee, rotate, withdrawOnlyOwn]);
+  });
+
+  it("sets the fee rate of a governance-owned pool", async () => {
+    await execute(setFee, governedPool);
+    expect((await env.program.account.ammPool.fetch(governedPool.pool)).feeRate.toNumber()).to.equal(50);
+  });
+
+  it("hands a governance-owned pool back to a new authority", async () => {
+    await execute(rotate, governedPool);
+    expect((await env.program.account.ammPool.fetch(governedPool.pool)).authority.equals(env.payer.publicKey)).to.be.true;
+  });
+
+  it("leaves pools governance does not own alone", async () => {
+    await expectRejected(execute(withdrawOnlyOwn, ownPool), "InvalidGovernanceRole");
+  });
+});
@@ -0,0 +1,64 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// The `test-utils` build can dump a pool's full state and write it back,
+// so a suite can rewind a pool to a captured snapshot
+
+describe("pool state snapshots", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const dump = () => env.program.methods.dumpPoolState().accounts({ pool: pool.pool }).view();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("rewinds the pool to a dumped state", async () => {
+    const snapshot = await dump();
+    expect(snapshot.tokenAMint.equals(pool.tokenAMint)).to.be.true;
+
+    await env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+    const swapped = await env.program.account.ammPool.fetch(pool.pool);
+    expect(swapped.tokenAReserve.eq(snapshot.tokenAReserve)).to.be.false;
+
+    await env.program.methods
+      .restorePoolState(snapshot)
+      .accounts({ pool: pool.pool, authority: env.payer.publicKey })
+      .rpc();
+    expect(JSON.stringify(await dump())).to.equal(JSON.stringify(snapshot));
+  });
+
+  it("only lets the pool authority restore a state", async () => {
+    const stranger = Keypair.generate();
+    await expectRejected(
+      env.program.methods
+        .restorePoolState(await dump())
+        .accounts({ pool: pool.pool, authority: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+      "InvalidPoolAuthority"
+    );
+  });
+});
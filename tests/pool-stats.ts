@@ -0,0 +1,66 @@
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// Every swap adds to the pool's lifetime statistics, which `pool_stats`
+// hands back without replaying any history
+
+describe("pool statistics", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const stats = () => env.program.methods.poolStats().accounts({ pool: pool.pool }).view();
+
+  const swap = (amountIn: number, inputSide: object) =>
+    env.program.methods
+      .swap(new BN(amountIn), new BN(1), inputSide, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("starts out empty", async () => {
+    const initial = await stats();
+    expect(initial.swapCount.toNumber()).to.equal(0);
+    expect(initial.lastTradeAt.toNumber()).to.equal(0);
+  });
+
+  it("records each swap's volume and fee on its own side", async () => {
+    const beforeB = await tokenBalance(env.provider.connection, user.tokenB);
+    await swap(1_000_000, { a: {} });
+    const receivedB = (await tokenBalance(env.provider.connection, user.tokenB)) - beforeB;
+
+    let current = await stats();
+    expect(current.volumeInA.toNumber()).to.equal(1_000_000);
+    expect(current.volumeOutB.toString()).to.equal(receivedB.toString());
+    // 30 bps of the input
+    expect(current.feesA.toNumber()).to.equal(3_000);
+    expect(current.swapCount.toNumber()).to.equal(1);
+    expect(current.lastTradeAt.toNumber()).to.be.greaterThan(0);
+
+    await swap(500_000, { b: {} });
+    current = await stats();
+    expect(current.volumeInA.toNumber()).to.equal(1_000_000);
+    expect(current.volumeInB.toNumber()).to.equal(500_000);
+    expect(current.feesB.toNumber()).to.equal(1_500);
+    expect(current.swapCount.toNumber()).to.equal(2);
+  });
+});
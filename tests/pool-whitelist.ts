@@ -0,0 +1,81 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// A pool that opts into its own hook whitelist is validated against it
+// instead of the global one, on swaps and withdrawals alike
+
+describe("per-pool hook whitelists", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let poolWhitelist: PublicKey;
+
+  const swap = (whitelist: PublicKey) =>
+    env.program.methods
+      .swap(new BN(1_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user, { whitelist }))
+      .signers([user.keypair])
+      .rpc();
+
+  const removeLiquidity = (whitelist: PublicKey) =>
+    env.program.methods
+      .removeLiquidity(new BN(1_000), new BN(0), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user, { whitelist }))
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+    [poolWhitelist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_whitelist"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  it("validates against the pool's own list once enabled", async () => {
+    await env.program.methods
+      .enablePoolWhitelist([])
+      .accounts({
+        pool: pool.pool,
+        poolWhitelist,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    expect((await env.program.account.ammPool.fetch(pool.pool)).hookWhitelist.equals(poolWhitelist)).to.be.true;
+
+    await expectRejected(swap(env.whitelist), "WhitelistMismatch");
+    await expectRejected(removeLiquidity(env.whitelist), "WhitelistMismatch");
+    await swap(poolWhitelist);
+    await removeLiquidity(poolWhitelist);
+  });
+
+  it("falls back to the global list once disabled", async () => {
+    await env.program.methods
+      .disablePoolWhitelist()
+      .accounts({ pool: pool.pool, poolWhitelist, authority: env.payer.publicKey })
+      .rpc();
+    expect(await env.provider.connection.getAccountInfo(poolWhitelist)).to.be.null;
+
+    await expectRejected(removeLiquidity(poolWhitelist), "AccountNotInitialized");
+    await swap(env.whitelist);
+    await removeLiquidity(env.whitelist);
+  });
+});
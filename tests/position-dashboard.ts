@@ -0,0 +1,83 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  expectRejected,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// One view call reports a wallet's LP balance, pool share and redeemable
+// amounts across every pool passed as a [pool, LP token account] pair
+
+describe("position dashboard", () => {
+  let env: AmmEnv;
+  let pools: TestPool[];
+  let user: TestUser;
+  let partner: TestUser;
+
+  const lpAccount = (pool: TestPool, owner: PublicKey) =>
+    getAssociatedTokenAddressSync(pool.lpMint, owner, false, TOKEN_2022_PROGRAM_ID);
+
+  const dashboard = (wallet: PublicKey, pairs: [TestPool, PublicKey][]) =>
+    env.program.methods
+      .positionDashboard()
+      .accounts({ wallet })
+      .remainingAccounts(
+        pairs.flatMap(([pool, lp]) => [
+          { pubkey: pool.pool, isSigner: false, isWritable: false },
+          { pubkey: lp, isSigner: false, isWritable: false },
+        ])
+      )
+      .view();
+
+  before(async () => {
+    env = await setupAmm();
+    const first = await createTestPool(env);
+    // Same pair at another fee tier, so one wallet can hold both positions
+    const second = await createTestPool(env, { feeTier: 100, mints: [first.tokenAMint, first.tokenBMint] });
+    pools = [first, second];
+
+    user = await createTestUser(env, first);
+    partner = await createTestUser(env, first);
+    for (const pool of pools) {
+      await addLiquidity(env, pool, { ...user, lpToken: lpAccount(pool, user.keypair.publicKey) }, 100_000_000);
+    }
+    await addLiquidity(env, first, partner, 100_000_000);
+  });
+
+  it("reports the wallet's position in each pool", async () => {
+    const connection = env.provider.connection;
+    const wallet = user.keypair.publicKey;
+    const views = await dashboard(wallet, pools.map((pool) => [pool, lpAccount(pool, wallet)] as [TestPool, PublicKey]));
+    expect(views).to.have.length(2);
+
+    for (const [index, pool] of pools.entries()) {
+      const view = views[index];
+      const state = await env.program.account.ammPool.fetch(pool.pool);
+      const lpBalance = new BN((await tokenBalance(connection, lpAccount(pool, wallet))).toString());
+      expect(view.pool.equals(pool.pool)).to.be.true;
+      expect(view.lpBalance.eq(lpBalance)).to.be.true;
+      expect(view.shareBps.eq(lpBalance.muln(10_000).div(state.totalLpSupply))).to.be.true;
+      expect(view.tokenAAmount.eq(state.tokenAReserve.mul(lpBalance).div(state.totalLpSupply))).to.be.true;
+    }
+    // Sharing the first pool with the partner roughly halves the wallet's share
+    expect(views[0].shareBps.toNumber()).to.be.within(4_900, 5_000);
+    expect(views[1].shareBps.toNumber()).to.be.greaterThan(9_900);
+  });
+
+  it("rejects an LP account the wallet does not own", async () => {
+    const [pool] = pools;
+    await expectRejected(
+      dashboard(user.keypair.publicKey, [[pool, lpAccount(pool, partner.keypair.publicKey)]]),
+      "InvalidTokenAccountOwner"
+    );
+  });
+});
@@ -0,0 +1,107 @@
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  HookedMint,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createHookedMint,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  deadline,
+  liquidityAccounts,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// `preflight_swap` runs every swap check short of the transfers and
+// reports each one, so a wallet can explain a swap that would fail
+
+describe("swap preflight", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let other: TestUser;
+
+  // Checklist for `user` swapping token A into `pool`
+  const preflight = (amountIn: number, overrides: Record<string, unknown> = {}, testPool: TestPool = pool, remaining: HookedMint["hookAccounts"] = []) =>
+    env.program.methods
+      .preflightSwap(new BN(amountIn))
+      .accounts({
+        pool: testPool.pool,
+        user: user.keypair.publicKey,
+        userInputToken: user.tokenA,
+        userOutputToken: user.tokenB,
+        poolInputVault: testPool.poolTokenAVault,
+        poolOutputVault: testPool.poolTokenBVault,
+        inputMint: testPool.tokenAMint,
+        outputMint: testPool.tokenBMint,
+        whitelist: env.whitelist,
+        takerAllowlist: null,
+        ...overrides,
+      })
+      .remainingAccounts(remaining)
+      .view();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    other = await createTestUser(env, pool);
+    await addLiquidity(env, pool, other, 100_000_000);
+  });
+
+  it("clears a swap that would go through and quotes its output", async () => {
+    const report = await preflight(1_000_000);
+    expect(report.ready).to.be.true;
+
+    const before = await tokenBalance(env.provider.connection, user.tokenB);
+    await env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+    const received = (await tokenBalance(env.provider.connection, user.tokenB)) - before;
+    expect(received.toString()).to.equal(report.expectedAmountOut.toString());
+  });
+
+  it("flags the individual checks a swap would fail", async () => {
+    const overdrawn = await preflight(1_000_000_000_000);
+    expect(overdrawn.sufficientBalance).to.be.false;
+    expect(overdrawn.ready).to.be.false;
+
+    const foreign = await preflight(1_000_000, { userInputToken: other.tokenA });
+    expect(foreign.inputAccountValid).to.be.false;
+    expect(foreign.sufficientBalance).to.be.true;
+
+    const swapped = await preflight(1_000_000, { poolInputVault: pool.poolTokenBVault, poolOutputVault: pool.poolTokenAVault });
+    expect(swapped.pairValid).to.be.true;
+    expect(swapped.vaultsValid).to.be.false;
+    expect(swapped.ready).to.be.false;
+  });
+
+  it("checks that the hook accounts a hooked mint needs are supplied", async () => {
+    const hooked = await createHookedMint(env);
+    const hookedPool = await createTestPool(env, { mints: [hooked.mint, await createTestMint(env)] });
+    const seeder = await createTestUser(env, hookedPool);
+    await env.program.methods
+      .addLiquidity(new BN(100_000_000), new BN(100_000_000), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, hookedPool, seeder))
+      .remainingAccounts(hooked.hookAccounts)
+      .signers([seeder.keypair])
+      .rpc();
+    user = await createTestUser(env, hookedPool);
+
+    const bare = await preflight(1_000_000, {}, hookedPool);
+    expect(bare.inputHookWhitelisted && bare.outputHookWhitelisted).to.be.true;
+    expect(bare.hookAccountsPresent).to.be.false;
+    expect(bare.ready).to.be.false;
+
+    const supplied = await preflight(1_000_000, {}, hookedPool, hooked.hookAccounts);
+    expect(supplied.hookAccountsPresent).to.be.true;
+    expect(supplied.ready).to.be.true;
+  });
+});
@@ -0,0 +1,90 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// A pool can publish its TWAP in a Pyth-style price message; the spot
+// price's distance from the TWAP is reported as the confidence
+
+describe("pool price feed", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let priceFeed: PublicKey;
+
+  const enablePriceFeed = (twapWindow: number) =>
+    env.program.methods
+      .enablePriceFeed(new BN(twapWindow))
+      .accounts({
+        pool: pool.pool,
+        priceFeed,
+        authority: env.payer.publicKey,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const publishIx = () => env.program.methods.publishPrice().accounts({ pool: pool.pool, priceFeed });
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+    [priceFeed] = PublicKey.findProgramAddressSync(
+      [Buffer.from("price_feed"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  it("refuses a TWAP window under a minute", async () => {
+    await expectRejected(enablePriceFeed(30), "InvalidTwapWindow");
+  });
+
+  it("starts the feed at the pool's spot price", async () => {
+    await enablePriceFeed(60);
+    const feed = await env.program.account.poolPriceFeed.fetch(priceFeed);
+    expect(Buffer.from(feed.priceMessage.feedId).equals(pool.pool.toBuffer())).to.be.true;
+    expect(feed.priceMessage.exponent).to.equal(-9);
+    expect(feed.priceMessage.price.toNumber()).to.equal(1_000_000_000);
+  });
+
+  it("publishes the TWAP with the spot price's distance as confidence", async () => {
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+    await env.program.methods
+      .swap(new BN(10_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+    const before = await env.program.account.poolPriceFeed.fetch(priceFeed);
+
+    await publishIx().rpc();
+    const feed = await env.program.account.poolPriceFeed.fetch(priceFeed);
+    // The spot price held at 1.0 since the feed was enabled; the swap only
+    // moved it for the time after this publish
+    expect(feed.priceMessage.price.toNumber()).to.equal(1_000_000_000);
+    expect(feed.priceMessage.conf.toNumber()).to.be.greaterThan(0);
+    expect(feed.priceMessage.prevPublishTime.eq(before.priceMessage.publishTime)).to.be.true;
+    expect(feed.lastSpotPrice.toNumber()).to.be.lessThan(1_000_000_000);
+  });
+
+  it("publishes at most once per second", async () => {
+    await new Promise((resolve) => setTimeout(resolve, 1_000));
+    await expectRejected(
+      publishIx().postInstructions([await publishIx().instruction()]).rpc(),
+      "PriceFeedUpToDate"
+    );
+  });
+});
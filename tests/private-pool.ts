@@ -0,0 +1,89 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// A private pool only lets takers on its allowlist swap, while anyone can
+// still provide liquidity
+
+describe("private pools", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let desk: TestUser;
+  let stranger: TestUser;
+  let takerAllowlist: PublicKey;
+
+  const swap = (taker: TestUser, allowlist: PublicKey | null = takerAllowlist) =>
+    env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, taker, { takerAllowlist: allowlist }))
+      .signers([taker.keypair])
+      .rpc();
+
+  const updateTaker = (method: "addPoolTaker" | "removePoolTaker", taker: TestUser) =>
+    env.program.methods[method](taker.keypair.publicKey)
+      .accounts({ pool: pool.pool, takerAllowlist, authority: env.payer.publicKey })
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    desk = await createTestUser(env, pool);
+    stranger = await createTestUser(env, pool);
+    [takerAllowlist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("taker_allowlist"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+
+    await env.program.methods
+      .enablePrivatePool([desk.keypair.publicKey])
+      .accounts({
+        pool: pool.pool,
+        takerAllowlist,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("keeps liquidity provision open to everyone", async () => {
+    await addLiquidity(env, pool, stranger, 100_000_000);
+  });
+
+  it("only lets allowlisted takers swap", async () => {
+    await swap(desk);
+    await expectRejected(swap(stranger), "TakerNotAllowed");
+    await expectRejected(swap(desk, null), "MissingTakerAllowlist");
+  });
+
+  it("follows the creator's additions and removals", async () => {
+    await updateTaker("addPoolTaker", stranger);
+    await swap(stranger);
+    await expectRejected(updateTaker("addPoolTaker", stranger), "TakerAlreadyAllowed");
+
+    await updateTaker("removePoolTaker", desk);
+    await expectRejected(swap(desk), "TakerNotAllowed");
+    const allowlist = await env.program.account.takerAllowlist.fetch(takerAllowlist);
+    expect(allowlist.takerCount).to.equal(1);
+  });
+
+  it("opens swaps to everyone once the pool is no longer private", async () => {
+    await env.program.methods
+      .disablePrivatePool()
+      .accounts({ pool: pool.pool, takerAllowlist, authority: env.payer.publicKey })
+      .rpc();
+    expect(await env.provider.connection.getAccountInfo(takerAllowlist)).to.be.null;
+    await swap(desk, null);
+  });
+});
@@ -0,0 +1,87 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { AmmEnv, expectRejected, finalizeProposals, proposeAndApprove, setupAmm, setupGovernance } from "./helpers";
+
+// During the veto window an approved proposal can be struck down by enough
+// security council members; the suite installs a council and removes it
+// again when done
+
+const MAX_COUNCIL_MEMBERS = 5;
+
+describe("proposal vetoes", () => {
+  let env: AmmEnv;
+  let governanceConfig: PublicKey;
+  let voter: Keypair;
+  let target: Keypair;
+  const members = [Keypair.generate(), Keypair.generate()];
+
+  const councilAction = (council: PublicKey[], vetoThreshold: number) => ({
+    setSecurityCouncil: {
+      members: [...council, ...Array(MAX_COUNCIL_MEMBERS - council.length).fill(PublicKey.default)],
+      size: council.length,
+      vetoThreshold,
+    },
+  });
+
+  // Pass `action` through a full vote and execute it
+  const enact = async (action: object) => {
+    const proposal = Keypair.generate();
+    await proposeAndApprove(env, governanceConfig, proposal, voter, action);
+    await finalizeProposals(env, governanceConfig, [proposal]);
+    await execute(proposal);
+  };
+
+  const execute = (proposal: Keypair) =>
+    env.program.methods
+      .executeConfigProposal()
+      .accounts({ proposal: proposal.publicKey, governanceConfig, dependency: null })
+      .rpc();
+
+  const veto = (signers: Keypair[]) =>
+    env.program.methods
+      .vetoProposal()
+      .accounts({ proposal: target.publicKey, governanceConfig })
+      .remainingAccounts(signers.map((member) => ({ pubkey: member.publicKey, isSigner: true, isWritable: false })))
+      .signers(signers)
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    governanceConfig = await setupGovernance(env);
+
+    const connection = env.provider.connection;
+    voter = Keypair.generate();
+    const airdrop = await connection.requestAirdrop(voter.publicKey, 5 * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdrop, "confirmed");
+
+    await enact(councilAction(members.map((member) => member.publicKey), 2));
+
+    // Open a veto window for the next proposal
+    const { params } = await env.program.account.governanceConfig.fetch(governanceConfig);
+    await env.program.methods
+      .setGovernanceParams({ ...params, vetoWindow: new BN(600) })
+      .accounts({ governanceConfig, ammConfig: env.ammConfig, authority: env.payer.publicKey })
+      .rpc();
+    target = Keypair.generate();
+    await proposeAndApprove(env, governanceConfig, target, voter, councilAction([], 0));
+    await finalizeProposals(env, governanceConfig, [target]);
+  });
+
+  after(async () => {
+    await setupGovernance(env);
+    await enact(councilAction([], 0));
+  });
+
+  it("holds an approved proposal for the veto window", async () => {
+    await expectRejected(execute(target), "VetoWindowOpen");
+  });
+
+  it("takes the council's threshold of members to veto", async () => {
+    await expectRejected(veto([members[0]]), "InsufficientVetoSigners");
+    await veto(members);
+
+    expect((await env.program.account.proposal.fetch(target.publicKey)).status).to.deep.equal({ vetoed: {} });
+    await expectRejected(execute(target), "ProposalNotExecutable");
+  });
+});
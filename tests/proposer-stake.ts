@@ -0,0 +1,111 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  expectRejected,
+  finalizeProposals,
+  proposeAndApprove,
+  setupAmm,
+  setupGovernance,
+  stakeVaultAddress,
+} from "./helpers";
+
+// Proposers get their stake back when a proposal passes; a rejected one
+// has the configured share slashed to the governance treasury. This suite
+// clears the treasury when done
+
+const MAX_COUNCIL_MEMBERS = 5;
+
+describe("proposer stake", () => {
+  let env: AmmEnv;
+  let governanceConfig: PublicKey;
+  let passed: Keypair;
+  let rejected: Keypair;
+  const treasury = Keypair.generate().publicKey;
+  const clearCouncil = {
+    setSecurityCouncil: { members: Array(MAX_COUNCIL_MEMBERS).fill(PublicKey.default), size: 0, vetoThreshold: 0 },
+  };
+
+  const setTreasury = (account: PublicKey, slashBps: number, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .setGovernanceTreasury(account, new BN(slashBps))
+      .accounts({ ammConfig: env.ammConfig, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const settle = (proposal: Keypair) =>
+    env.program.methods
+      .settleProposerStake()
+      .accounts({
+        proposal: proposal.publicKey,
+        stakeVault: stakeVaultAddress(env, proposal.publicKey),
+        proposer: env.payer.publicKey,
+        ammConfig: env.ammConfig,
+        treasury,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const vaultBalance = (proposal: Keypair) =>
+    env.provider.connection.getBalance(stakeVaultAddress(env, proposal.publicKey), "confirmed");
+
+  before(async () => {
+    env = await setupAmm();
+    governanceConfig = await setupGovernance(env);
+
+    const connection = env.provider.connection;
+    const voter = Keypair.generate();
+    const airdrop = await connection.requestAirdrop(voter.publicKey, 5 * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdrop, "confirmed");
+
+    passed = Keypair.generate();
+    await proposeAndApprove(env, governanceConfig, passed, voter, clearCouncil);
+
+    // Nobody votes on this one, so it misses quorum
+    rejected = Keypair.generate();
+    await env.program.methods
+      .createProposal(clearCouncil as any, "Behaviour suite proposal", new BN(LAMPORTS_PER_SOL))
+      .accounts({
+        proposal: rejected.publicKey,
+        proposer: env.payer.publicKey,
+        dependency: null,
+        governanceConfig,
+        stakeVault: stakeVaultAddress(env, rejected.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([rejected])
+      .rpc();
+  });
+
+  after(async () => {
+    await setTreasury(PublicKey.default, 0);
+  });
+
+  it("refuses a slash above 100% or a treasury set by a stranger", async () => {
+    await expectRejected(setTreasury(treasury, 10_001), "InvalidSlashRate");
+    await expectRejected(setTreasury(treasury, 5_000, Keypair.generate()), "InvalidConfigAuthority");
+    await setTreasury(treasury, 5_000);
+  });
+
+  it("holds the stake until voting is over", async () => {
+    await expectRejected(settle(rejected), "ProposalNotFinalized");
+    await finalizeProposals(env, governanceConfig, [passed, rejected]);
+  });
+
+  it("refunds a passed proposal's stake in full", async () => {
+    const before = await vaultBalance(passed);
+    await settle(passed);
+    expect(before - (await vaultBalance(passed))).to.equal(LAMPORTS_PER_SOL);
+    expect(await env.provider.connection.getBalance(treasury, "confirmed")).to.equal(0);
+    await expectRejected(settle(passed), "ProposerStakeAlreadySettled");
+  });
+
+  it("slashes the configured share of a rejected proposal's stake", async () => {
+    expect((await env.program.account.proposal.fetch(rejected.publicKey)).status).to.deep.equal({ rejected: {} });
+    const before = await vaultBalance(rejected);
+    await settle(rejected);
+    expect(before - (await vaultBalance(rejected))).to.equal(LAMPORTS_PER_SOL);
+    expect(await env.provider.connection.getBalance(treasury, "confirmed")).to.equal(LAMPORTS_PER_SOL / 2);
+  });
+});
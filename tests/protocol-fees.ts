@@ -0,0 +1,150 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// A pool can route a share of every swap fee to protocol fee vaults, which
+// only the protocol fee authority can drain to its treasury
+
+describe("protocol fees", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let feeAuthority: Keypair;
+  let feeVaultA: PublicKey;
+  let feeVaultB: PublicKey;
+
+  const setFeeAuthority = (protocolFeeAuthority: PublicKey, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .setProtocolFeeAuthority(protocolFeeAuthority)
+      .accounts({ globalConfig: env.globalConfig, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const setShare = (shareBps: number, signer: Keypair = feeAuthority) =>
+    env.program.methods
+      .setProtocolFeeShare(new BN(shareBps))
+      .accounts({
+        globalConfig: env.globalConfig,
+        protocolFeeAuthority: signer.publicKey,
+        pool: pool.pool,
+        protocolFeeVaultA: feeVaultA,
+        protocolFeeVaultB: feeVaultB,
+      })
+      .signers([signer])
+      .rpc();
+
+  const swapA = (protocolFeeVault: PublicKey | null) =>
+    env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user, { protocolFeeVault }))
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+    feeAuthority = Keypair.generate();
+    [feeVaultA, feeVaultB] = [pool.tokenAMint, pool.tokenBMint].map(
+      (mint) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("protocol_fee_vault"), pool.pool.toBuffer(), mint.toBuffer()],
+          env.program.programId
+        )[0]
+    );
+  });
+
+  after(async () => {
+    await setFeeAuthority(env.payer.publicKey);
+  });
+
+  it("only lets the config authority hand over the protocol fee role", async () => {
+    await expectRejected(setFeeAuthority(feeAuthority.publicKey, Keypair.generate()), "InvalidConfigAuthority");
+    await setFeeAuthority(feeAuthority.publicKey);
+    const config = await env.program.account.globalConfig.fetch(env.globalConfig);
+    expect(config.protocolFeeAuthority.equals(feeAuthority.publicKey)).to.be.true;
+  });
+
+  it("lets the protocol fee authority take a capped share once the vaults exist", async () => {
+    await env.program.methods
+      .initializeProtocolFeeVaults()
+      .accounts({
+        pool: pool.pool,
+        payer: env.payer.publicKey,
+        ammConfig: env.ammConfig,
+        vaultAuthority: pool.vaultAuthority,
+        protocolFeeVaultA: feeVaultA,
+        protocolFeeVaultB: feeVaultB,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await expectRejected(setShare(5_000, env.payer.payer), "InvalidProtocolFeeAuthority");
+    await expectRejected(setShare(5_001), "InvalidProtocolFeeShare");
+    await setShare(5_000);
+  });
+
+  it("moves the protocol's half of the swap fee into its vault", async () => {
+    await expectRejected(swapA(null), "MissingProtocolFeeVault");
+    await swapA(feeVaultA);
+
+    // 30 bps of 1,000,000 is 3,000, half of which goes to the protocol
+    expect((await tokenBalance(env.provider.connection, feeVaultA)).toString()).to.equal("1500");
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    expect(state.tokenAReserve.toString()).to.equal("100998500");
+  });
+
+  it("collects the vaults into the authority's treasury", async () => {
+    const connection = env.provider.connection;
+    const [treasuryA, treasuryB] = await Promise.all(
+      [pool.tokenAMint, pool.tokenBMint].map((mint) =>
+        getOrCreateAssociatedTokenAccount(
+          connection, env.payer.payer, mint, feeAuthority.publicKey, false, "confirmed", undefined, TOKEN_2022_PROGRAM_ID
+        )
+      )
+    );
+    const collect = (signer: Keypair) =>
+      env.program.methods
+        .collectProtocolFees()
+        .accounts({
+          globalConfig: env.globalConfig,
+          protocolFeeAuthority: signer.publicKey,
+          pool: pool.pool,
+          vaultAuthority: pool.vaultAuthority,
+          protocolFeeVaultA: feeVaultA,
+          protocolFeeVaultB: feeVaultB,
+          treasuryTokenA: treasuryA.address,
+          treasuryTokenB: treasuryB.address,
+          tokenAMint: pool.tokenAMint,
+          tokenBMint: pool.tokenBMint,
+          tokenAProgram: TOKEN_2022_PROGRAM_ID,
+          tokenBProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc();
+
+    await expectRejected(collect(Keypair.generate()), "InvalidProtocolFeeAuthority");
+    await collect(feeAuthority);
+    expect((await tokenBalance(connection, treasuryA.address)).toString()).to.equal("1500");
+    expect((await tokenBalance(connection, feeVaultA)).toString()).to.equal("0");
+  });
+});
@@ -0,0 +1,123 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import { AmmEnv, TestPool, createTestPool, expectRejected, setupAmm, tokenBalance } from "./helpers";
+
+// The treasury can seed a new pool at a chosen price; the LP tokens it gets
+// stay in a protocol-owned vault until the lock-up ends. The payer acts as
+// treasury here and the suite clears the treasury when done
+
+const LOCK_SECONDS = 3;
+
+describe("protocol-owned liquidity", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let protocolLiquidity: PublicKey;
+  const lpVault = Keypair.generate();
+  let treasuryTokenA: PublicKey;
+  let treasuryTokenB: PublicKey;
+  let treasuryLpToken: PublicKey;
+
+  const setTreasury = (treasury: PublicKey) =>
+    env.program.methods
+      .setGovernanceTreasury(treasury, new BN(0))
+      .accounts({ ammConfig: env.ammConfig, authority: env.payer.publicKey })
+      .rpc();
+
+  const bootstrap = (treasury: Keypair = env.payer.payer) =>
+    env.program.methods
+      .bootstrapPool(new BN(10_000_000), new BN(2).shln(64), new BN(LOCK_SECONDS))
+      .accounts({
+        pool: pool.pool,
+        treasury: treasury.publicKey,
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        globalConfig: env.globalConfig,
+        pairOracle: PublicKey.findProgramAddressSync(
+          [Buffer.from("pair_oracle"), pool.tokenAMint.toBuffer(), pool.tokenBMint.toBuffer()],
+          env.program.programId
+        )[0],
+        protocolLiquidity,
+        lpVault: lpVault.publicKey,
+        treasuryTokenA,
+        treasuryTokenB,
+        poolTokenAVault: pool.poolTokenAVault,
+        poolTokenBVault: pool.poolTokenBVault,
+        lpMint: pool.lpMint,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([treasury, lpVault])
+      .rpc();
+
+  const withdraw = (amount: BN) =>
+    env.program.methods
+      .withdrawProtocolLiquidity(amount)
+      .accounts({
+        protocolLiquidity,
+        treasury: env.payer.publicKey,
+        lpVault: lpVault.publicKey,
+        treasuryLpToken,
+        lpMint: pool.lpMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    [protocolLiquidity] = PublicKey.findProgramAddressSync(
+      [Buffer.from("protocol_liquidity"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+
+    const connection = env.provider.connection;
+    const treasuryAccount = async (mint: PublicKey, amount: number) => {
+      const account = await getOrCreateAssociatedTokenAccount(
+        connection, env.payer.payer, mint, env.payer.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      if (amount > 0) {
+        await mintTo(connection, env.payer.payer, mint, account.address, env.payer.publicKey, amount, [], undefined, TOKEN_2022_PROGRAM_ID);
+      }
+      return account.address;
+    };
+    treasuryTokenA = await treasuryAccount(pool.tokenAMint, 100_000_000);
+    treasuryTokenB = await treasuryAccount(pool.tokenBMint, 100_000_000);
+    treasuryLpToken = await treasuryAccount(pool.lpMint, 0);
+    await setTreasury(env.payer.publicKey);
+  });
+
+  after(async () => {
+    await setTreasury(PublicKey.default);
+  });
+
+  it("seeds the pool at the treasury's price and escrows the LP tokens", async () => {
+    await expectRejected(bootstrap(Keypair.generate()), "InvalidTreasury");
+    await bootstrap();
+
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    expect(state.tokenAReserve.toNumber()).to.equal(10_000_000);
+    expect(state.tokenBReserve.toNumber()).to.equal(20_000_000);
+    const { lpAmount } = await env.program.account.protocolLiquidity.fetch(protocolLiquidity);
+    expect((await tokenBalance(env.provider.connection, lpVault.publicKey)).toString()).to.equal(lpAmount.toString());
+  });
+
+  it("only bootstraps a pool without liquidity", async () => {
+    await expectRejected(bootstrap(), "PoolAlreadyBootstrapped");
+  });
+
+  it("holds the LP tokens until the lock-up ends", async () => {
+    const { lpAmount } = await env.program.account.protocolLiquidity.fetch(protocolLiquidity);
+    await expectRejected(withdraw(lpAmount), "ProtocolLiquidityLocked");
+
+    await new Promise((resolve) => setTimeout(resolve, (LOCK_SECONDS + 1) * 1000));
+    await expectRejected(withdraw(lpAmount.addn(1)), "InsufficientLPTokens");
+    await withdraw(lpAmount);
+    expect((await tokenBalance(env.provider.connection, treasuryLpToken)).toString()).to.equal(lpAmount.toString());
+  });
+});
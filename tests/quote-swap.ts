@@ -0,0 +1,112 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  getMintLen,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  deadline,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// The swap quote nets out Token-2022 transfer fees and renders UI amounts,
+// so the amount it says the user receives is what lands in their account
+
+describe("swap quotes", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let feeMint: PublicKey;
+
+  before(async () => {
+    env = await setupAmm();
+    const connection = env.provider.connection;
+
+    // 1% transfer fee on the output side
+    const mintKeypair = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: env.payer.publicKey,
+          newAccountPubkey: mintKeypair.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          mintKeypair.publicKey,
+          env.payer.publicKey,
+          env.payer.publicKey,
+          100,
+          BigInt(1_000_000_000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mintKeypair.publicKey, 9, env.payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [env.payer.payer, mintKeypair],
+      { commitment: "confirmed" }
+    );
+    feeMint = mintKeypair.publicKey;
+
+    pool = await createTestPool(env, { mints: [feeMint, await createTestMint(env)] });
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("quotes the amount received net of the output transfer fee", async () => {
+    const connection = env.provider.connection;
+    const feeIsB = pool.tokenBMint.equals(feeMint);
+    const inputSide = feeIsB ? { a: {} } : { b: {} };
+    const userOut = feeIsB ? user.tokenB : user.tokenA;
+
+    const quote = await env.program.methods
+      .quoteSwap(new BN(1_000_000), inputSide)
+      .accounts({ pool: pool.pool, tokenAMint: pool.tokenAMint, tokenBMint: pool.tokenBMint })
+      .view();
+    expect(quote.inputTransferFee.toNumber()).to.equal(0);
+    expect(quote.outputTransferFee.toNumber()).to.be.greaterThan(0);
+    expect(quote.amountReceived.toNumber()).to.equal(quote.amountOut.toNumber() - quote.outputTransferFee.toNumber());
+    expect(quote.amountInUi).to.equal("0.001");
+
+    const before = await tokenBalance(connection, userOut);
+    await env.program.methods
+      .swap(new BN(1_000_000), new BN(1), inputSide, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+    expect(((await tokenBalance(connection, userOut)) - before).toString()).to.equal(quote.amountReceived.toString());
+  });
+
+  it("converts between gross and net transfer amounts of a fee mint", async () => {
+    const quoteTransfer = (mint: PublicKey, amount: number, amountIsNet: boolean) =>
+      env.program.methods.quoteTransferAmount(new BN(amount), amountIsNet).accounts({ mint }).view();
+
+    const fromGross = await quoteTransfer(feeMint, 1_000_000, false);
+    expect(fromGross.transferFee.toNumber()).to.equal(10_000);
+    expect(fromGross.netAmount.toNumber()).to.equal(990_000);
+
+    const fromNet = await quoteTransfer(feeMint, 990_000, true);
+    expect(fromNet.grossAmount.toNumber()).to.equal(1_000_000);
+    expect(fromNet.netAmount.toNumber()).to.equal(990_000);
+
+    const plainMint = pool.tokenAMint.equals(feeMint) ? pool.tokenBMint : pool.tokenAMint;
+    const plain = await quoteTransfer(plainMint, 1_000_000, true);
+    expect(plain.grossAmount.toNumber()).to.equal(1_000_000);
+    expect(plain.transferFee.toNumber()).to.equal(0);
+  });
+});
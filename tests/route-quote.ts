@@ -0,0 +1,52 @@
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// The route quote must price a hop through the same fee rounding as the
+// swap itself, so what the router promises is what the user receives
+
+describe("route quotes", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env, { feeTier: 30 });
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("quotes exactly what the swap pays out", async () => {
+    // 1001 at 30 bps is where rounding the fee up and the net input down diverge
+    for (const amountIn of [1_001, 12_345, 1_000_000]) {
+      const quote = await env.program.methods
+        .quoteRoutes(new BN(amountIn), pool.tokenAMint, pool.tokenBMint, Buffer.from([1]))
+        .accounts({ outputTokenMint: pool.tokenBMint })
+        .remainingAccounts([{ pubkey: pool.pool, isSigner: false, isWritable: false }])
+        .view();
+
+      const before = await tokenBalance(env.provider.connection, user.tokenB);
+      await env.program.methods
+        .swap(new BN(amountIn), new BN(0), { a: {} }, null, deadline())
+        .accounts(swapAccounts(env, pool, user))
+        .signers([user.keypair])
+        .rpc();
+      const received = (await tokenBalance(env.provider.connection, user.tokenB)) - before;
+
+      expect(quote.bestRouteIndex).to.equal(0);
+      expect(received.toString()).to.equal(quote.bestAmountOut.toString());
+    }
+  });
+});
@@ -0,0 +1,120 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  HookedMint,
+  TestPool,
+  TestUser,
+  createHookedMint,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// While the emergency authority blocks one leg's Transfer Hook, an LP can
+// still exit: the other leg is paid out and the blocked leg is owed as an
+// IOU, redeemable once the hook is unblocked
+
+describe("single-leg exits", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let hooked: HookedMint;
+  let hookedSide: object;
+  let otherSide: object;
+  const nonce = new BN(1);
+
+  const setHookBlocked = (side: object, blocked: boolean) =>
+    env.program.methods
+      .setPoolHookBlocked(side as any, blocked)
+      .accounts({ globalConfig: env.globalConfig, pool: pool.pool, emergencyAuthority: env.payer.publicKey })
+      .rpc();
+
+  const iouAddress = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("liquidity_iou"), pool.pool.toBuffer(), user.keypair.publicKey.toBuffer(), nonce.toArrayLike(Buffer, "le", 8)],
+      env.program.programId
+    )[0];
+
+  const exitSingleLeg = (side: object) =>
+    env.program.methods
+      .removeLiquiditySingleLeg(new BN(10_000_000), side as any, new BN(1), nonce)
+      .accounts({ ...liquidityAccounts(env, pool, user), iou: iouAddress() })
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    hooked = await createHookedMint(env);
+    pool = await createTestPool(env, { mints: [hooked.mint, await createTestMint(env)] });
+    [hookedSide, otherSide] = pool.tokenAMint.equals(hooked.mint) ? [{ a: {} }, { b: {} }] : [{ b: {} }, { a: {} }];
+    user = await createTestUser(env, pool);
+    await env.program.methods
+      .addLiquidity(new BN(100_000_000), new BN(100_000_000), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .remainingAccounts(hooked.hookAccounts)
+      .signers([user.keypair])
+      .rpc();
+  });
+
+  it("refuses a single-leg exit while the leg's hook is not blocked", async () => {
+    await expectRejected(exitSingleLeg(hookedSide), "HookNotBlocked");
+  });
+
+  it("refuses to owe a leg that has no Transfer Hook", async () => {
+    await expectRejected(exitSingleLeg(otherSide), "MintHasNoTransferHook");
+  });
+
+  it("pays the unblocked leg and records the blocked leg as an IOU", async () => {
+    const connection = env.provider.connection;
+    const hookedIsA = pool.tokenAMint.equals(hooked.mint);
+    const userOther = hookedIsA ? user.tokenB : user.tokenA;
+    await setHookBlocked(hookedSide, true);
+
+    const otherBefore = await tokenBalance(connection, userOther);
+    const lpBefore = await tokenBalance(connection, user.lpToken);
+    await exitSingleLeg(hookedSide);
+
+    expect((lpBefore - (await tokenBalance(connection, user.lpToken))).toString()).to.equal("10000000");
+    expect((await tokenBalance(connection, userOther)) > otherBefore).to.be.true;
+    const iou = await env.program.account.liquidityIou.fetch(iouAddress());
+    expect(iou.owner.equals(user.keypair.publicKey)).to.be.true;
+    expect(iou.amount.toNumber()).to.be.greaterThan(0);
+    expect(Object.keys(iou.side)[0]).to.equal(Object.keys(hookedSide)[0]);
+  });
+
+  it("redeems the IOU through the hook once it is unblocked", async () => {
+    const connection = env.provider.connection;
+    const hookedIsA = pool.tokenAMint.equals(hooked.mint);
+    const iou = await env.program.account.liquidityIou.fetch(iouAddress());
+    await setHookBlocked(hookedSide, false);
+
+    const userHooked = hookedIsA ? user.tokenA : user.tokenB;
+    const before = await tokenBalance(connection, userHooked);
+    await env.program.methods
+      .redeemLiquidityIou()
+      .accounts({
+        pool: pool.pool,
+        owner: user.keypair.publicKey,
+        iou: iouAddress(),
+        userToken: userHooked,
+        vaultAuthority: pool.vaultAuthority,
+        poolVault: hookedIsA ? pool.poolTokenAVault : pool.poolTokenBVault,
+        mint: hooked.mint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hooked.hookAccounts)
+      .signers([user.keypair])
+      .rpc();
+
+    expect(((await tokenBalance(connection, userHooked)) - before).toString()).to.equal(iou.amount.toString());
+    expect(await connection.getAccountInfo(iouAddress())).to.be.null;
+  });
+});
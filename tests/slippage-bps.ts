@@ -0,0 +1,64 @@
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// `swap_with_slippage_bps` derives the minimum output from the reserves the
+// client quoted against, so a pool that moved beyond the tolerance since the
+// quote rejects the swap
+
+describe("slippage in basis points", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+  let frontrunner: TestUser;
+
+  const reserves = async () => {
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    return { reserveIn: state.tokenAReserve, reserveOut: state.tokenBReserve };
+  };
+
+  const swapWithSlippage = (trader: TestUser, amountIn: number, slippageBps: number, reference: { reserveIn: BN; reserveOut: BN }) =>
+    env.program.methods
+      .swapWithSlippageBps(new BN(amountIn), slippageBps, reference.reserveIn, reference.reserveOut, { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, trader))
+      .signers([trader.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    frontrunner = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("swaps when the pool still matches the quoted reserves", async () => {
+    const before = await tokenBalance(env.provider.connection, user.tokenB);
+    await swapWithSlippage(user, 1_000_000, 0, await reserves());
+    expect((await tokenBalance(env.provider.connection, user.tokenB)) > before).to.be.true;
+  });
+
+  it("rejects a swap once the pool moved beyond the tolerance", async () => {
+    const quoted = await reserves();
+    await swapWithSlippage(frontrunner, 10_000_000, 10_000, await reserves());
+
+    await expectRejected(swapWithSlippage(user, 1_000_000, 50, quoted), "InsufficientOutputAmount");
+    await swapWithSlippage(user, 1_000_000, 3_000, quoted);
+  });
+
+  it("refuses a tolerance above 100%", async () => {
+    await expectRejected(swapWithSlippage(user, 1_000_000, 10_001, await reserves()), "InvalidSlippageTolerance");
+  });
+});
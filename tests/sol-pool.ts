@@ -0,0 +1,183 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SYSVAR_RENT_PUBKEY, SystemProgram } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  NATIVE_MINT,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  createTestMint,
+  creatorRecordAddress,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  lpGroupMint,
+  poolAddress,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// A SOL pool pairs the legacy wrapped-SOL mint with a Token-2022 token. Its
+// creator's SOL is wrapped during pool creation, and a trader can swap native
+// SOL without holding a WSOL account; the one opened for the swap is closed
+// again straight after
+
+const FEE_TIER = 30;
+
+describe("wrapped SOL pools", () => {
+  let env: AmmEnv;
+  let token: PublicKey;
+  let pool: TestPool;
+  let trader: Keypair;
+
+  const programOf = (mint: PublicKey) => (mint.equals(NATIVE_MINT) ? TOKEN_PROGRAM_ID : TOKEN_2022_PROGRAM_ID);
+  const ata = (mint: PublicKey, owner: PublicKey) => getAssociatedTokenAddressSync(mint, owner, false, programOf(mint));
+
+  const initializePoolWithSol = async (mints: PublicKey[], lamports: number): Promise<TestPool> => {
+    const [tokenAMint, tokenBMint] = [...mints].sort((a, b) => Buffer.compare(a.toBuffer(), b.toBuffer()));
+    const address = poolAddress(env, tokenAMint, tokenBMint, FEE_TIER);
+    const [vaultAuthority] = PublicKey.findProgramAddressSync(
+      [address.toBuffer(), Buffer.from("vault_auth")],
+      env.program.programId
+    );
+    const [vaultA, vaultB, lpMint] = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    await env.program.methods
+      .initializePoolWithSol(new BN(FEE_TIER), { constantProduct: {} }, false, new BN(lamports))
+      .accounts({
+        pool: address,
+        authority: env.payer.publicKey,
+        globalConfig: env.globalConfig,
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        creatorRecord: creatorRecordAddress(env, env.payer.publicKey),
+        tokenAMint,
+        tokenBMint,
+        vaultAuthority,
+        tokenAVault: vaultA.publicKey,
+        tokenBVault: vaultB.publicKey,
+        lpMint: lpMint.publicKey,
+        lpGroupMint: await lpGroupMint(env),
+        freezeAuthorityA: null,
+        freezeAuthorityB: null,
+        creatorWsol: ata(NATIVE_MINT, env.payer.publicKey),
+        tokenAProgram: programOf(tokenAMint),
+        tokenBProgram: programOf(tokenBMint),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([vaultA, vaultB, lpMint])
+      .rpc();
+
+    return {
+      pool: address,
+      feeTier: FEE_TIER,
+      vaultAuthority,
+      tokenAMint,
+      tokenBMint,
+      poolTokenAVault: vaultA.publicKey,
+      poolTokenBVault: vaultB.publicKey,
+      lpMint: lpMint.publicKey,
+    };
+  };
+
+  const wsolBalance = async (owner: PublicKey) => {
+    const wsol = ata(NATIVE_MINT, owner);
+    if (!(await env.provider.connection.getAccountInfo(wsol))) {
+      return BigInt(0);
+    }
+    return (await getAccount(env.provider.connection, wsol, "confirmed", TOKEN_PROGRAM_ID)).amount;
+  };
+
+  const swapSolForToken = (lamportsIn: number, minAmountOut: BN) => {
+    const solIsA = pool.tokenAMint.equals(NATIVE_MINT);
+    const account = (pubkey: PublicKey, isWritable: boolean) => ({ pubkey, isSigner: false, isWritable });
+    return env.program.methods
+      .swapSolForToken(new BN(lamportsIn), minAmountOut, deadline())
+      .accounts({
+        user: trader.publicKey,
+        userWsol: ata(NATIVE_MINT, trader.publicKey),
+        nativeMint: NATIVE_MINT,
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        takerAllowlist: null,
+        nativeTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      // The pool's hop, laid out as `ROUTE_HOP_ACCOUNTS`
+      .remainingAccounts([
+        account(pool.pool, true),
+        account(pool.vaultAuthority, false),
+        account(solIsA ? pool.poolTokenAVault : pool.poolTokenBVault, true),
+        account(solIsA ? pool.poolTokenBVault : pool.poolTokenAVault, true),
+        account(token, false),
+        account(TOKEN_2022_PROGRAM_ID, false),
+        account(ata(token, trader.publicKey), true),
+        account(env.program.programId, false),
+        account(env.program.programId, false),
+      ])
+      .signers([trader])
+      .rpc();
+  };
+
+  before(async () => {
+    env = await setupAmm();
+    token = await createTestMint(env);
+    trader = Keypair.generate();
+    const airdrop = await env.provider.connection.requestAirdrop(trader.publicKey, 2 * LAMPORTS_PER_SOL);
+    await env.provider.connection.confirmTransaction(airdrop, "confirmed");
+  });
+
+  it("wraps the creator's SOL while creating a SOL pool", async () => {
+    await expectRejected(initializePoolWithSol([await createTestMint(env), token], 1_000), "NotNativeMint");
+
+    const before = await wsolBalance(env.payer.publicKey);
+    pool = await initializePoolWithSol([NATIVE_MINT, token], LAMPORTS_PER_SOL);
+    expect((await wsolBalance(env.payer.publicKey)) - before).to.equal(BigInt(LAMPORTS_PER_SOL));
+
+    // Seed the pool from the wrapped SOL
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(
+      env.provider.connection, env.payer.payer, token, env.payer.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(env.provider.connection, env.payer.payer, token, tokenAccount.address, env.payer.publicKey, 500_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+    const creator = {
+      keypair: env.payer.payer,
+      tokenA: ata(pool.tokenAMint, env.payer.publicKey),
+      tokenB: ata(pool.tokenBMint, env.payer.publicKey),
+      lpToken: ata(pool.lpMint, env.payer.publicKey),
+    };
+    await env.program.methods
+      .addLiquidity(new BN(500_000_000), new BN(500_000_000), new BN(0), deadline())
+      .accounts(
+        liquidityAccounts(env, pool, creator, {
+          tokenAProgram: programOf(pool.tokenAMint),
+          tokenBProgram: programOf(pool.tokenBMint),
+        })
+      )
+      .rpc();
+  });
+
+  it("swaps native SOL and closes the WSOL account it opened", async () => {
+    const quote = await env.program.methods
+      .quoteRoutes(new BN(10_000_000), NATIVE_MINT, token, Buffer.from([1]))
+      .accounts({ outputTokenMint: token })
+      .remainingAccounts([{ pubkey: pool.pool, isSigner: false, isWritable: false }])
+      .view();
+    await expectRejected(swapSolForToken(10_000_000, quote.bestAmountOut.addn(1)), "InsufficientOutputAmount");
+
+    await swapSolForToken(10_000_000, quote.bestAmountOut);
+    const received = await tokenBalance(env.provider.connection, ata(token, trader.publicKey));
+    expect(received.toString()).to.equal(quote.bestAmountOut.toString());
+    expect(await env.provider.connection.getAccountInfo(ata(NATIVE_MINT, trader.publicKey))).to.be.null;
+  });
+});
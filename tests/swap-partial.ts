@@ -0,0 +1,62 @@
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// A partial swap fills as much of the input as the price-impact cap allows
+// and leaves the rest in the user's account
+
+describe("partial-fill swaps", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const swapPartial = (amountIn: number, minFillAmountIn: number, maxPriceImpactBps: number) =>
+    env.program.methods
+      .swapPartial(new BN(amountIn), new BN(minFillAmountIn), new BN(1), { a: {} }, maxPriceImpactBps, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+  const spent = async (call: () => Promise<string>) => {
+    const before = await tokenBalance(env.provider.connection, user.tokenA);
+    await call();
+    return before - (await tokenBalance(env.provider.connection, user.tokenA));
+  };
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("fills the whole input when it stays under the cap", async () => {
+    expect((await spent(() => swapPartial(100_000, 100_000, 100))).toString()).to.equal("100000");
+  });
+
+  it("fills only what the cap allows and keeps the rest", async () => {
+    const reserveBefore = (await env.program.account.ammPool.fetch(pool.pool)).tokenAReserve;
+    // 1% impact on a 100M pool is far short of a 10M trade
+    const filled = await spent(() => swapPartial(10_000_000, 1, 100));
+    expect(filled > BigInt(0) && filled < BigInt(10_000_000)).to.be.true;
+
+    const reserveAfter = (await env.program.account.ammPool.fetch(pool.pool)).tokenAReserve;
+    expect(reserveAfter.sub(reserveBefore).toString()).to.equal(filled.toString());
+  });
+
+  it("refuses a fill below the caller's minimum", async () => {
+    await expectRejected(swapPartial(10_000_000, 10_000_000, 100), "PartialFillTooSmall");
+  });
+});
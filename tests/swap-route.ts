@@ -0,0 +1,102 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestMint,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// With pools X/Y and Y/Z but none for X/Z, a routed swap trades X for Z in
+// one transaction, checking only the final output
+
+describe("routed swaps", () => {
+  let env: AmmEnv;
+  let mints: PublicKey[];
+  let first: TestPool;
+  let second: TestPool;
+  let trader: TestUser;
+
+  const tokenAccount = (mint: PublicKey) =>
+    getAssociatedTokenAddressSync(mint, trader.keypair.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  // One hop's accounts, laid out as `ROUTE_HOP_ACCOUNTS`
+  const hop = (pool: TestPool, mintIn: PublicKey) => {
+    const aToB = pool.tokenAMint.equals(mintIn);
+    const mintOut = aToB ? pool.tokenBMint : pool.tokenAMint;
+    const account = (pubkey: PublicKey, isWritable: boolean) => ({ pubkey, isSigner: false, isWritable });
+    return [
+      account(pool.pool, true),
+      account(pool.vaultAuthority, false),
+      account(aToB ? pool.poolTokenAVault : pool.poolTokenBVault, true),
+      account(aToB ? pool.poolTokenBVault : pool.poolTokenAVault, true),
+      account(mintOut, false),
+      account(TOKEN_2022_PROGRAM_ID, false),
+      account(tokenAccount(mintOut), true),
+      // No observation or protocol fee vault on either pool
+      account(env.program.programId, false),
+      account(env.program.programId, false),
+    ];
+  };
+
+  const route = (amountIn: number, minAmountOut: BN, hops: ReturnType<typeof hop>[]) =>
+    env.program.methods
+      .swapRoute(new BN(amountIn), minAmountOut, hops.length, deadline())
+      .accounts({
+        user: trader.keypair.publicKey,
+        userInputToken: tokenAccount(mints[0]),
+        inputMint: mints[0],
+        ammConfig: env.ammConfig,
+        whitelist: env.whitelist,
+        takerAllowlist: null,
+        inputTokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(hops.flat())
+      .signers([trader.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    mints = [await createTestMint(env), await createTestMint(env), await createTestMint(env)];
+    first = await createTestPool(env, { mints: [mints[0], mints[1]] });
+    second = await createTestPool(env, { mints: [mints[1], mints[2]] });
+    await addLiquidity(env, first, await createTestUser(env, first), 100_000_000);
+    await addLiquidity(env, second, await createTestUser(env, second), 100_000_000);
+    // Holds X and Y; the route creates the Z account on the way
+    trader = await createTestUser(env, first);
+  });
+
+  it("takes X to Z through Y, paying what the route quote promises", async () => {
+    const quote = await env.program.methods
+      .quoteRoutes(new BN(1_000_000), mints[0], mints[2], Buffer.from([2]))
+      .accounts({ outputTokenMint: mints[2] })
+      .remainingAccounts([first.pool, second.pool].map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+      .view();
+
+    const hops = [hop(first, mints[0]), hop(second, mints[1])];
+    await expectRejected(route(1_000_000, quote.bestAmountOut.addn(1), hops), "InsufficientOutputAmount");
+
+    const connection = env.provider.connection;
+    const beforeY = await tokenBalance(connection, tokenAccount(mints[1]));
+    await route(1_000_000, quote.bestAmountOut, hops);
+    const received = await tokenBalance(connection, tokenAccount(mints[2]));
+    expect(received.toString()).to.equal(quote.bestAmountOut.toString());
+    // The intermediate Y passes straight through
+    expect(await tokenBalance(connection, tokenAccount(mints[1]))).to.equal(beforeY);
+  });
+
+  it("refuses hops that do not chain", async () => {
+    await expectRejected(route(1_000_000, new BN(1), [hop(second, mints[1])]), "InvalidRoute");
+  });
+});
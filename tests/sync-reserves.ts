@@ -0,0 +1,73 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID, getOrCreateAssociatedTokenAccount, transferChecked } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  expectRejected,
+  setupAmm,
+  tokenBalance,
+} from "./helpers";
+
+// `sync_reserves` resyncs the recorded reserves with tokens sent straight
+// to the vaults, skimming the surplus when a fee account is supplied
+
+describe("reserve sync", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const donate = (from: PublicKey, mint: PublicKey, vault: PublicKey, amount: number) =>
+    transferChecked(
+      env.provider.connection, env.payer.payer, from, mint, vault, user.keypair, amount, 9, [], undefined, TOKEN_2022_PROGRAM_ID
+    );
+
+  const sync = (feeA: PublicKey | null, feeB: PublicKey | null, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .syncReserves()
+      .accounts({
+        pool: pool.pool,
+        authority: authority.publicKey,
+        vaultAuthority: pool.vaultAuthority,
+        poolTokenAVault: pool.poolTokenAVault,
+        poolTokenBVault: pool.poolTokenBVault,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        protocolFeeTokenA: feeA,
+        protocolFeeTokenB: feeB,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("only lets the pool authority sync", async () => {
+    await expectRejected(sync(null, null, Keypair.generate()), "InvalidPoolAuthority");
+  });
+
+  it("skims a donation to the fee account, or folds it into the reserve", async () => {
+    const feeA = await getOrCreateAssociatedTokenAccount(
+      env.provider.connection, env.payer.payer, pool.tokenAMint, env.payer.publicKey, false, "confirmed", undefined, TOKEN_2022_PROGRAM_ID
+    );
+    await donate(user.tokenA, pool.tokenAMint, pool.poolTokenAVault, 1_000_000);
+    await donate(user.tokenB, pool.tokenBMint, pool.poolTokenBVault, 2_000_000);
+    await sync(feeA.address, null);
+
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    expect(state.tokenAReserve.toString()).to.equal("100000000");
+    expect((await tokenBalance(env.provider.connection, feeA.address)).toString()).to.equal("1000000");
+    expect(state.tokenBReserve.toString()).to.equal("102000000");
+    expect((await tokenBalance(env.provider.connection, pool.poolTokenBVault)).toString()).to.equal("102000000");
+  });
+});
@@ -392,6 +392,27 @@ describe("Token-2022 AMM Integration Tests", () => {
         expect(error.toString()).to.include("HookNotWhitelisted");
       }
     });
+
+    it("Reports a pool mint's hook through the CPI validation surface", async () => {
+      const [ammConfig] = PublicKey.findProgramAddressSync(
+        [Buffer.from("amm_config")],
+        ammProgram.programId
+      );
+
+      const validation = await ammProgram.methods
+        .validateHook()
+        .accounts({
+          pool: ammPool.publicKey,
+          ammConfig,
+          whitelist: whitelistAccount.publicKey,
+          mint: mintA.publicKey,
+        })
+        .view();
+
+      expect(validation.mint.toString()).to.equal(mintA.publicKey.toString());
+      expect(validation.hookProgramId.toString()).to.equal(hookProgram.programId.toString());
+      expect(validation.whitelisted).to.be.true;
+    });
   });
 
   describe("Security Tests", () => {
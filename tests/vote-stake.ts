@@ -0,0 +1,68 @@
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  expectRejected,
+  finalizeProposals,
+  proposeAndApprove,
+  setupAmm,
+  setupGovernance,
+  stakeVaultAddress,
+} from "./helpers";
+
+// Vote stake stays escrowed until the proposal is finalized, then each
+// voter can take theirs back exactly once
+
+const MAX_COUNCIL_MEMBERS = 5;
+
+describe("vote stake withdrawal", () => {
+  let env: AmmEnv;
+  let governanceConfig: PublicKey;
+  let proposal: Keypair;
+  let voter: Keypair;
+
+  const withdraw = () =>
+    env.program.methods
+      .withdrawStake()
+      .accounts({
+        proposal: proposal.publicKey,
+        voteRecord: PublicKey.findProgramAddressSync(
+          [proposal.publicKey.toBuffer(), voter.publicKey.toBuffer()],
+          env.program.programId
+        )[0],
+        stakeVault: stakeVaultAddress(env, proposal.publicKey),
+        voter: voter.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    governanceConfig = await setupGovernance(env);
+
+    const connection = env.provider.connection;
+    voter = Keypair.generate();
+    const airdrop = await connection.requestAirdrop(voter.publicKey, 5 * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(airdrop, "confirmed");
+
+    proposal = Keypair.generate();
+    await proposeAndApprove(env, governanceConfig, proposal, voter, {
+      setSecurityCouncil: { members: Array(MAX_COUNCIL_MEMBERS).fill(PublicKey.default), size: 0, vetoThreshold: 0 },
+    });
+  });
+
+  it("keeps the stake escrowed while voting is open", async () => {
+    await expectRejected(withdraw(), "ProposalNotFinalized");
+  });
+
+  it("returns the stake once, after the proposal is finalized", async () => {
+    await finalizeProposals(env, governanceConfig, [proposal]);
+    const connection = env.provider.connection;
+    const before = await connection.getBalance(voter.publicKey, "confirmed");
+    await withdraw();
+
+    expect((await connection.getBalance(voter.publicKey, "confirmed")) - before).to.equal(LAMPORTS_PER_SOL);
+    await expectRejected(withdraw(), "StakeAlreadyWithdrawn");
+  });
+});
@@ -0,0 +1,89 @@
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  HookedMint,
+  TestPool,
+  createHookedMint,
+  createTestMint,
+  createTestPool,
+  expectRejected,
+  setupAmm,
+} from "./helpers";
+
+// A whitelist can list a hook through a `WhitelistEntry` PDA instead of its
+// own array; a pool accepts the hook whenever the entry is passed along
+
+describe("whitelist entries", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let hooked: HookedMint;
+  let poolWhitelist: PublicKey;
+  let entry: PublicKey;
+
+  const validate = () =>
+    env.program.methods
+      .validateHook()
+      .accounts({ pool: pool.pool, ammConfig: env.ammConfig, whitelist: poolWhitelist, mint: hooked.mint })
+      .remainingAccounts([{ pubkey: entry, isSigner: false, isWritable: false }])
+      .view();
+
+  const addEntry = (authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .addWhitelistEntry(hooked.hookAccounts[0].pubkey)
+      .accounts({
+        whitelist: poolWhitelist,
+        whitelistEntry: entry,
+        proposal: null,
+        dependency: null,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    hooked = await createHookedMint(env);
+    pool = await createTestPool(env, { mints: [hooked.mint, await createTestMint(env)] });
+
+    // An empty pool whitelist, so the hook is only listed through the entry
+    [poolWhitelist] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_whitelist"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+    await env.program.methods
+      .enablePoolWhitelist([])
+      .accounts({
+        pool: pool.pool,
+        poolWhitelist,
+        authority: env.payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    [entry] = PublicKey.findProgramAddressSync(
+      [Buffer.from("hook"), poolWhitelist.toBuffer(), hooked.hookAccounts[0].pubkey.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  it("accepts the hook once the whitelist authority adds its entry", async () => {
+    expect((await validate()).whitelisted).to.be.false;
+    await expectRejected(addEntry(Keypair.generate()), "InvalidWhitelistAuthority");
+    await addEntry();
+
+    const record = await env.program.account.whitelistEntry.fetch(entry);
+    expect(record.hookProgramId.equals(hooked.hookAccounts[0].pubkey)).to.be.true;
+    expect(record.addedBy.equals(env.payer.publicKey)).to.be.true;
+    expect((await validate()).whitelisted).to.be.true;
+  });
+
+  it("stops accepting the hook once the entry is removed", async () => {
+    await env.program.methods
+      .removeWhitelistEntry()
+      .accounts({ whitelist: poolWhitelist, whitelistEntry: entry, authority: env.payer.publicKey })
+      .rpc();
+    expect(await env.provider.connection.getAccountInfo(entry)).to.be.null;
+    expect((await validate()).whitelisted).to.be.false;
+  });
+});
@@ -0,0 +1,50 @@
+import * as fs from "fs";
+import * as path from "path";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { AmmEnv, expectRejected, setupAmm } from "./helpers";
+
+// `migrate_whitelist` rewrites a whitelist from an older layout into the
+// current one. The validator starts with a layout-0 whitelist listing the
+// safe transfer hook (tests/fixtures/legacy-whitelist.json, see Anchor.toml).
+
+describe("whitelist migration", () => {
+  const legacyWhitelist = new PublicKey("7qTko1Rc2Hvx4jVSCNNf8y7QzBw54cU2GaqoRZLoDBTX");
+  const safeTransferHook = new PublicKey("BroadwayHooK11111111111111111111111111111111");
+  const authority = Keypair.fromSecretKey(
+    Uint8Array.from(JSON.parse(fs.readFileSync(path.join(__dirname, "fixtures/legacy-whitelist-authority.json"), "utf8")))
+  );
+  let env: AmmEnv;
+
+  const migrate = (whitelist: PublicKey, signer: Keypair) =>
+    env.program.methods
+      .migrateWhitelist()
+      .accounts({ whitelist, authority: signer.publicKey, systemProgram: SystemProgram.programId })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    const airdrop = await env.provider.connection.requestAirdrop(authority.publicKey, 1_000_000_000);
+    await env.provider.connection.confirmTransaction(airdrop, "confirmed");
+  });
+
+  it("only lets the whitelist authority migrate it", async () => {
+    await expectRejected(migrate(legacyWhitelist, Keypair.generate()), "InvalidWhitelistAuthority");
+  });
+
+  it("carries every entry over into the current layout", async () => {
+    await migrate(legacyWhitelist, authority);
+
+    const whitelist = await env.program.account.transferHookWhitelist.fetch(legacyWhitelist);
+    expect(whitelist.layoutVersion).to.equal(2);
+    expect(whitelist.authority.equals(authority.publicKey)).to.be.true;
+    expect(whitelist.hookCount).to.equal(1);
+    expect(whitelist.whitelistedHooks[0].equals(safeTransferHook)).to.be.true;
+  });
+
+  it("refuses a whitelist already in the current layout", async () => {
+    await expectRejected(migrate(legacyWhitelist, authority), "WhitelistAlreadyMigrated");
+    await expectRejected(migrate(env.whitelist, env.payer.payer), "WhitelistAlreadyMigrated");
+  });
+});
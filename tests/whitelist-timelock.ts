@@ -0,0 +1,78 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { AmmEnv, expectRejected, setupAmm } from "./helpers";
+
+// A whitelist timelock routes hook changes through a queue that can only be
+// finalized once the delay has passed. Timelocks never come off, so this
+// suite uses a whitelist of its own
+
+describe("whitelist timelock", () => {
+  let env: AmmEnv;
+  let whitelist: PublicKey;
+  const earlyHook = Keypair.generate().publicKey;
+  const lateHook = Keypair.generate().publicKey;
+  const day = 24 * 60 * 60;
+
+  const queue = (hook: PublicKey, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .queueHookChange({ add: {} }, hook)
+      .accounts({ whitelist, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const finalize = (hook: PublicKey) =>
+    env.program.methods.finalizeHookChange(hook).accounts({ whitelist }).rpc();
+
+  const setTimelock = (delay: number) =>
+    env.program.methods
+      .setWhitelistTimelock(new BN(delay))
+      .accounts({ whitelist, authority: env.payer.publicKey })
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    const whitelistAccount = Keypair.generate();
+    whitelist = whitelistAccount.publicKey;
+    await env.program.methods
+      .initializeWhitelist()
+      .accounts({ whitelist, authority: env.payer.publicKey, systemProgram: SystemProgram.programId })
+      .signers([whitelistAccount])
+      .rpc();
+  });
+
+  it("applies a queued change straight away while no timelock is set", async () => {
+    await expectRejected(queue(earlyHook, Keypair.generate()), "InvalidWhitelistAuthority");
+    await queue(earlyHook);
+    await finalize(earlyHook);
+
+    const state = await env.program.account.transferHookWhitelist.fetch(whitelist);
+    expect(state.whitelistedHooks.some((hook) => hook.equals(earlyHook))).to.be.true;
+  });
+
+  it("only lengthens a timelock of at least a day", async () => {
+    await expectRejected(setTimelock(3_600), "InvalidWhitelistTimelock");
+    await setTimelock(2 * day);
+    await expectRejected(setTimelock(day), "InvalidWhitelistTimelock");
+  });
+
+  it("holds queued changes until the delay has passed", async () => {
+    await expectRejected(
+      env.program.methods.addHookToWhitelist(lateHook).accounts({ whitelist, authority: env.payer.publicKey }).rpc(),
+      "WhitelistTimelocked"
+    );
+    await queue(lateHook);
+    await expectRejected(queue(lateHook), "HookChangeAlreadyQueued");
+    await expectRejected(finalize(lateHook), "HookChangeNotReady");
+
+    const state = await env.program.account.transferHookWhitelist.fetch(whitelist);
+    const pending = state.pendingHookChanges.find((change) => change.hookProgramId.equals(lateHook));
+    const now = Math.floor(Date.now() / 1000);
+    expect(pending.eta.toNumber()).to.be.greaterThan(now + 2 * day - 60);
+  });
+
+  it("lets the authority cancel a queued change", async () => {
+    await env.program.methods.cancelHookChange(lateHook).accounts({ whitelist, authority: env.payer.publicKey }).rpc();
+    await expectRejected(finalize(lateHook), "HookChangeNotQueued");
+  });
+});
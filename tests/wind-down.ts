@@ -0,0 +1,126 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  setupAmm,
+  swapAccounts,
+  tokenBalance,
+} from "./helpers";
+
+// Winding a pool down freezes trading and snapshots its balances; every LP
+// then claims a pro-rata share and the last claim closes the pool
+
+describe("pool wind-down", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let first: TestUser;
+  let last: TestUser;
+  let settlement: PublicKey;
+
+  const startWindDown = (authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .startWindDown()
+      .accounts({
+        globalConfig: env.globalConfig,
+        authority: authority.publicKey,
+        pool: pool.pool,
+        settlement,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+  const claim = (user: TestUser) =>
+    env.program.methods
+      .claimSettlement()
+      .accounts({
+        pool: pool.pool,
+        settlement,
+        user: user.keypair.publicKey,
+        userTokenA: user.tokenA,
+        userTokenB: user.tokenB,
+        userLpToken: user.lpToken,
+        vaultAuthority: pool.vaultAuthority,
+        poolTokenAVault: pool.poolTokenAVault,
+        poolTokenBVault: pool.poolTokenBVault,
+        lpMint: pool.lpMint,
+        tokenAMint: pool.tokenAMint,
+        tokenBMint: pool.tokenBMint,
+        poolAuthority: env.payer.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenAProgram: TOKEN_2022_PROGRAM_ID,
+        tokenBProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    first = await createTestUser(env, pool);
+    last = await createTestUser(env, pool);
+    await addLiquidity(env, pool, first, 100_000_000);
+    await addLiquidity(env, pool, last, 50_000_000);
+    [settlement] = PublicKey.findProgramAddressSync(
+      [Buffer.from("settlement"), pool.pool.toBuffer()],
+      env.program.programId
+    );
+  });
+
+  it("only settles pools the config authority has wound down", async () => {
+    await expectRejected(claim(first), "AccountNotInitialized");
+    await expectRejected(startWindDown(Keypair.generate()), "InvalidConfigAuthority");
+    await startWindDown();
+
+    const state = await env.program.account.ammPool.fetch(pool.pool);
+    const snapshot = await env.program.account.poolSettlement.fetch(settlement);
+    expect(snapshot.tokenAAmount.eq(state.tokenAReserve)).to.be.true;
+    expect(snapshot.lpSupply.eq(state.totalLpSupply)).to.be.true;
+    expect(snapshot.lpOutstanding.eq(state.totalLpSupply)).to.be.true;
+  });
+
+  it("freezes trading once the pool is winding down", async () => {
+    await expectRejected(
+      env.program.methods
+        .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+        .accounts(swapAccounts(env, pool, first))
+        .signers([first.keypair])
+        .rpc(),
+      "PoolWindingDown"
+    );
+  });
+
+  it("pays each claim its pro-rata share of the snapshot", async () => {
+    const snapshot = await env.program.account.poolSettlement.fetch(settlement);
+    const lpTokens = new BN((await tokenBalance(env.provider.connection, first.lpToken)).toString());
+    const beforeA = await tokenBalance(env.provider.connection, first.tokenA);
+    const beforeB = await tokenBalance(env.provider.connection, first.tokenB);
+    await claim(first);
+
+    const receivedA = (await tokenBalance(env.provider.connection, first.tokenA)) - beforeA;
+    const receivedB = (await tokenBalance(env.provider.connection, first.tokenB)) - beforeB;
+    expect(receivedA.toString()).to.equal(snapshot.tokenAAmount.mul(lpTokens).div(snapshot.lpSupply).toString());
+    expect(receivedB.toString()).to.equal(snapshot.tokenBAmount.mul(lpTokens).div(snapshot.lpSupply).toString());
+    expect((await tokenBalance(env.provider.connection, first.lpToken)).toString()).to.equal("0");
+  });
+
+  it("closes the pool once the last LP has claimed", async () => {
+    const remaining = (await env.program.account.poolSettlement.fetch(settlement)).remainingA;
+    const beforeA = await tokenBalance(env.provider.connection, last.tokenA);
+    await claim(last);
+
+    expect(((await tokenBalance(env.provider.connection, last.tokenA)) - beforeA).toString()).to.equal(remaining.toString());
+    for (const account of [pool.pool, settlement, pool.poolTokenAVault, pool.poolTokenBVault]) {
+      expect(await env.provider.connection.getAccountInfo(account)).to.be.null;
+    }
+  });
+});
@@ -0,0 +1,70 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AmmEnv,
+  TestPool,
+  TestUser,
+  addLiquidity,
+  createTestPool,
+  createTestUser,
+  deadline,
+  expectRejected,
+  liquidityAccounts,
+  setupAmm,
+  swapAccounts,
+} from "./helpers";
+
+// A pool being delisted goes withdraw-only: swaps and deposits stop while
+// LPs can still take their liquidity out
+
+describe("withdraw-only pools", () => {
+  let env: AmmEnv;
+  let pool: TestPool;
+  let user: TestUser;
+
+  const setWithdrawOnly = (withdrawOnly: boolean, authority: Keypair = env.payer.payer) =>
+    env.program.methods
+      .setWithdrawOnly(withdrawOnly)
+      .accounts({ pool: pool.pool, authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const swap = () =>
+    env.program.methods
+      .swap(new BN(1_000_000), new BN(1), { a: {} }, null, deadline())
+      .accounts(swapAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+
+  before(async () => {
+    env = await setupAmm();
+    pool = await createTestPool(env);
+    user = await createTestUser(env, pool);
+    await addLiquidity(env, pool, user, 100_000_000);
+  });
+
+  it("only takes the switch from the pool authority", async () => {
+    await expectRejected(setWithdrawOnly(true, Keypair.generate()), "InvalidPoolAuthority");
+  });
+
+  it("stops swaps and deposits but lets LPs withdraw", async () => {
+    await setWithdrawOnly(true);
+    await expectRejected(swap(), "PoolWithdrawOnly");
+    await expectRejected(addLiquidity(env, pool, user, 1_000_000), "PoolWithdrawOnly");
+
+    const before = await env.program.account.ammPool.fetch(pool.pool);
+    await env.program.methods
+      .removeLiquidity(new BN(1_000_000), new BN(0), new BN(0), deadline())
+      .accounts(liquidityAccounts(env, pool, user))
+      .signers([user.keypair])
+      .rpc();
+    const after = await env.program.account.ammPool.fetch(pool.pool);
+    expect(before.totalLpSupply.sub(after.totalLpSupply).toNumber()).to.equal(1_000_000);
+  });
+
+  it("trades again once switched back", async () => {
+    await setWithdrawOnly(false);
+    await swap();
+  });
+});